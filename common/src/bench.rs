@@ -0,0 +1,105 @@
+use crate::messages::{Msg, t};
+use ethers::types::Address;
+use ethers::utils::keccak256;
+use std::error::Error;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// 单个策略的多轮耗时记录
+pub struct BenchResult {
+    pub name: &'static str,
+    pub runs: Vec<Duration>,
+}
+
+impl BenchResult {
+    /// 最快一轮的耗时
+    pub fn best(&self) -> Duration {
+        self.runs.iter().min().copied().unwrap_or_default()
+    }
+
+    /// 所有轮次的平均耗时
+    pub fn mean(&self) -> Duration {
+        if self.runs.is_empty() {
+            return Duration::ZERO;
+        }
+        self.runs.iter().sum::<Duration>() / self.runs.len() as u32
+    }
+}
+
+/// 生成确定性的测试地址，保证每次基准测试查询的是同一批地址
+///
+/// # 参数
+/// * `count` - 地址数量
+///
+/// # 返回
+/// * `Vec<Address>` - 由 keccak256("arbi-bench-{i}") 截取的地址
+pub fn bench_addresses(count: usize) -> Vec<Address> {
+    (0..count)
+        .map(|i| Address::from_slice(&keccak256(format!("arbi-bench-{}", i))[12..]))
+        .collect()
+}
+
+/// 多次运行同一个异步操作并记录每轮耗时
+///
+/// # 参数
+/// * `name` - 策略名称（用于打印表格）
+/// * `rounds` - 运行轮数
+/// * `f` - 每轮调用一次的异步操作
+///
+/// # 返回
+/// * `Result<(BenchResult, T), Box<dyn Error>>` - 耗时记录和最后一轮的结果（用于校验各策略结果一致）
+pub async fn measure<F, Fut, T>(
+    name: &'static str,
+    rounds: usize,
+    mut f: F,
+) -> Result<(BenchResult, T), Box<dyn Error>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Box<dyn Error>>>,
+{
+    let mut runs = Vec::with_capacity(rounds);
+    let mut last = None;
+
+    for _ in 0..rounds.max(1) {
+        let start = Instant::now();
+        let output = f().await?;
+        runs.push(start.elapsed());
+        last = Some(output);
+    }
+
+    let last = last.expect("至少运行一轮");
+    Ok((BenchResult { name, runs }, last))
+}
+
+/// 打印各策略的耗时对比表，第一行作为基准计算加速比
+///
+/// # 参数
+/// * `title` - 表格标题
+/// * `results` - 各策略的测量结果
+pub fn print_table(title: &str, results: &[BenchResult]) {
+    println!("\n=== {} ===", title);
+    println!(
+        "{:<14} {:>12} {:>12} {:>10}",
+        t(Msg::BenchStrategy),
+        t(Msg::BenchBest),
+        t(Msg::BenchMean),
+        t(Msg::BenchSpeedup)
+    );
+
+    let baseline = results.first().map(BenchResult::mean).unwrap_or_default();
+    for result in results {
+        let mean = result.mean();
+        let speedup = if mean.is_zero() {
+            0.0
+        } else {
+            baseline.as_secs_f64() / mean.as_secs_f64()
+        };
+        println!(
+            "{:<14} {:>12.1} {:>12.1} {:>9.2}x",
+            result.name,
+            result.best().as_secs_f64() * 1000.0,
+            mean.as_secs_f64() * 1000.0,
+            speedup
+        );
+    }
+}
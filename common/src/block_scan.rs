@@ -0,0 +1,195 @@
+//! 分段并发逐块扫描，找出区块里符合条件的交易
+//!
+//! 没有区块浏览器 API 时，只能读取每个区块的完整交易列表来找和地址有关的交易。
+//! 区块范围切成若干段，段内按顺序读取，段之间通过 [`run_bounded`](crate::concurrency::run_bounded) 并发。
+
+use ethers::providers::Middleware;
+use ethers::types::{Block, Transaction};
+use std::cell::Cell;
+use std::error::Error;
+
+use crate::concurrency;
+use crate::messages::{Msg, t, tf};
+use crate::rpc::RpcProvider;
+
+/// 把区块范围切成若干段，每段最多 `size` 个区块
+///
+/// # 参数
+/// * `from` - 起始区块（含）
+/// * `to` - 结束区块（含）
+/// * `size` - 每段的区块数
+///
+/// # 返回
+/// * `Result<Vec<(u64, u64)>, Box<dyn Error>>` - 按顺序排列的闭区间，`from > to` 时为空；`size` 为 0 时返回错误
+pub fn chunk_range(from: u64, to: u64, size: u64) -> Result<Vec<(u64, u64)>, Box<dyn Error>> {
+    if size == 0 {
+        return Err(t(Msg::ScanChunkSizeZero).into());
+    }
+    let mut chunks = Vec::new();
+    let mut start = from;
+    while start <= to {
+        let end = start.saturating_add(size - 1).min(to);
+        chunks.push((start, end));
+        if end == u64::MAX {
+            break;
+        }
+        start = end + 1;
+    }
+    Ok(chunks)
+}
+
+/// 顺序读取一段区块，对每个区块调用 `pick` 取出需要的交易
+async fn scan_chunk<T>(
+    provider: &RpcProvider,
+    (from, to): (u64, u64),
+    pick: &impl Fn(&Block<Transaction>) -> Vec<T>,
+) -> Result<Vec<T>, Box<dyn Error>> {
+    let mut found = Vec::new();
+    for number in from..=to {
+        let Some(block) = provider.get_block_with_txs(number).await? else {
+            return Err(tf(Msg::ScanBlockMissing, &[&number]).into());
+        };
+        found.extend(pick(&block));
+    }
+    Ok(found)
+}
+
+/// 分段并发扫描 `[from, to]`
+///
+/// 段不按顺序完成，每完成一段调用一次 `on_progress(已扫描区块数, 总区块数, 已找到的数量)`；
+/// 结果仍按区块号排列。请求速率受 `--max-rps` 限制。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `(from, to)` - 区块范围（含两端）
+/// * `chunk_size` - 每段的区块数，必须大于 0
+/// * `concurrency` - 同时扫描的段数
+/// * `pick` - 从一个区块里取出需要的内容
+/// * `on_progress` - 进度回调
+///
+/// # 返回
+/// * `Result<Vec<T>, Box<dyn Error>>` - 按区块号排列的结果；节点缺少某个区块时返回错误
+pub async fn scan_blocks<T>(
+    provider: &RpcProvider,
+    (from, to): (u64, u64),
+    chunk_size: u64,
+    concurrency: usize,
+    pick: impl Fn(&Block<Transaction>) -> Vec<T>,
+    mut on_progress: impl FnMut(u64, u64, usize),
+) -> Result<Vec<T>, Box<dyn Error>> {
+    let chunks = chunk_range(from, to, chunk_size)?;
+    let total = to.saturating_sub(from).saturating_add(1);
+    let (scanned, found) = (Cell::new(0u64), Cell::new(0usize));
+    let pick = &pick;
+    let results = concurrency::run_bounded_with_progress(
+        chunks,
+        concurrency,
+        |(start, end)| {
+            let (scanned, found) = (&scanned, &found);
+            async move {
+                let items = scan_chunk(provider, (start, end), pick).await?;
+                scanned.set(scanned.get() + end - start + 1);
+                found.set(found.get() + items.len());
+                Ok::<_, Box<dyn Error>>(items)
+            }
+        },
+        |_, _| on_progress(scanned.get(), total, found.get()),
+    )
+    .await;
+
+    let mut items = Vec::new();
+    for result in results {
+        items.extend(result?);
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_rpc::{MockRpc, Reply};
+    use ethers::types::H256;
+    use serde_json::{Value, json};
+
+    #[test]
+    fn chunk_range_splits_inclusive_ranges() {
+        assert_eq!(chunk_range(0, 9, 5).unwrap(), [(0, 4), (5, 9)]);
+        assert_eq!(chunk_range(10, 22, 5).unwrap(), [(10, 14), (15, 19), (20, 22)]);
+        assert_eq!(chunk_range(7, 7, 100).unwrap(), [(7, 7)]);
+        assert!(chunk_range(8, 7, 100).unwrap().is_empty());
+        let top = chunk_range(u64::MAX - 1, u64::MAX, 1).unwrap();
+        assert_eq!(top, [(u64::MAX - 1, u64::MAX - 1), (u64::MAX, u64::MAX)]);
+    }
+
+    #[test]
+    fn chunk_range_rejects_zero_size() {
+        let error = chunk_range(0, 9, 0).unwrap_err();
+        assert_eq!(error.to_string(), t(Msg::ScanChunkSizeZero));
+    }
+
+    // 区块 n 里有一笔哈希为 n 的交易；`missing` 之后的区块节点还没有
+    fn node(missing: u64) -> MockRpc {
+        MockRpc::start(move |_, params| {
+            let hex = params[0].as_str().unwrap().trim_start_matches("0x");
+            let number = u64::from_str_radix(hex, 16).unwrap();
+            if number >= missing {
+                return Reply::Result(Value::Null);
+            }
+            Reply::Result(json!({
+                "number": format!("{number:#x}"),
+                "hash": format!("{:?}", H256::from_low_u64_be(number + 1_000)),
+                "transactions": [{
+                    "hash": format!("{:?}", H256::from_low_u64_be(number)),
+                    "nonce": "0x0",
+                    "from": "0x1111111111111111111111111111111111111111",
+                    "value": "0x0",
+                    "gas": "0x5208",
+                    "input": "0x",
+                    "v": "0x0",
+                    "r": "0x0",
+                    "s": "0x0",
+                }],
+            }))
+        })
+    }
+
+    #[tokio::test]
+    async fn scans_in_block_order_and_reports_progress() {
+        let node = node(u64::MAX);
+        let provider = node.provider();
+        let mut progress = Vec::new();
+        let hashes = scan_blocks(
+            &provider,
+            (100, 124),
+            10,
+            3,
+            |block| block.transactions.iter().map(|tx| tx.hash.to_low_u64_be()).collect(),
+            |scanned, total, found| progress.push((scanned, total, found)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(hashes, (100..=124).collect::<Vec<_>>());
+        assert_eq!(node.count("eth_getBlockByNumber"), 25);
+        assert_eq!(progress.len(), 3);
+        assert_eq!(progress.last(), Some(&(25, 25, 25)));
+    }
+
+    #[tokio::test]
+    async fn missing_block_is_an_error() {
+        let node = node(105);
+        let provider = node.provider();
+        let result = scan_blocks(&provider, (100, 109), 5, 2, |_| vec![()], |_, _, _| {}).await;
+        let error = result.unwrap_err();
+        assert_eq!(error.to_string(), tf(Msg::ScanBlockMissing, &[&105]));
+    }
+
+    #[tokio::test]
+    async fn zero_chunk_size_sends_no_requests() {
+        let node = node(u64::MAX);
+        let provider = node.provider();
+        let result = scan_blocks(&provider, (0, 9), 0, 2, |_| vec![()], |_, _, _| {}).await;
+        assert!(result.is_err());
+        assert!(node.requests().is_empty());
+    }
+}
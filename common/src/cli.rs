@@ -0,0 +1,153 @@
+use clap::Args;
+use ethers::types::Address;
+use std::error::Error;
+use std::path::PathBuf;
+
+use crate::i18n::{self, Lang};
+use crate::messages::{Msg, tf};
+use crate::logging::{self, LogFormat, Verbosity};
+use crate::network::{self, Network};
+use crate::rate_limit;
+use crate::style::{self, ColorChoice};
+use crate::table::Table;
+use crate::{narrate, payment, qr, units};
+
+/// 所有命令共用的全局选项，用 `#[command(flatten)]` 嵌入各自的 Cli
+#[derive(Args, Debug)]
+pub struct GlobalArgs {
+    /// 输出语言（默认读取 ARBI_LANG，未设置时为中文）
+    #[arg(long, global = true, value_enum)]
+    pub lang: Option<Lang>,
+
+    /// 日志格式（默认读取 ARBI_LOG_FORMAT，未设置时为 human）
+    #[arg(long, global = true, value_enum)]
+    pub log_format: Option<LogFormat>,
+
+    /// 彩色输出（auto 时遵守 NO_COLOR，并在输出不是终端时关闭）
+    #[arg(long, global = true, value_enum)]
+    pub color: Option<ColorChoice>,
+
+    /// 输出更多细节：-v 显示 RPC 请求摘要，-vv 显示完整请求和响应（敏感内容脱敏）
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// 只输出结果和错误
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// 网络（默认读取 ARBI_NETWORK，未设置时为 arbitrum-sepolia）
+    #[arg(long, global = true, value_enum)]
+    pub network: Option<Network>,
+
+    /// 允许在主网上签名交易（仍需在终端里再次确认）
+    #[arg(long, global = true)]
+    pub mainnet_i_accept_risk: bool,
+
+    /// 节点的链 ID 与 --network 不一致时仍然发送交易（默认拒绝）
+    #[arg(long, global = true)]
+    pub allow_chain_mismatch: bool,
+
+    /// 每个 RPC 节点每秒最多请求数，所有并发任务共用（默认读取 ARBI_MAX_RPS，未设置时不限速）
+    #[arg(long, global = true, value_name = "N", value_parser = rate_limit::parse_rps)]
+    pub max_rps: Option<f64>,
+}
+
+impl GlobalArgs {
+    /// 按选项初始化语言、日志、彩色输出、网络和限速
+    pub fn init(&self) {
+        i18n::init(self.lang);
+        network::init(self.network, self.mainnet_i_accept_risk, self.allow_chain_mismatch);
+        logging::init(self.log_format, Verbosity::from_flags(self.verbose, self.quiet));
+        style::init(self.color);
+        rate_limit::init(self.max_rps);
+    }
+}
+
+/// 显示地址二维码的选项，用 `#[command(flatten)]` 嵌入会显示地址的命令
+#[derive(Args, Debug)]
+pub struct QrArgs {
+    /// 在终端里显示地址二维码，方便手机钱包扫码（终端不支持 UTF-8 时用 ASCII 字符）
+    #[arg(long)]
+    pub qr: bool,
+
+    /// 二维码改为请求这么多 ETH 的 EIP-681 收款链接（带当前网络的链 ID）
+    #[arg(long, requires = "qr")]
+    pub amount: Option<String>,
+}
+
+impl QrArgs {
+    /// 传了 `--qr` 时打印地址二维码
+    ///
+    /// # 参数
+    /// * `address` - 要显示的地址
+    ///
+    /// # 返回
+    /// * `Result<(), Box<dyn Error>>` - 执行结果；金额无效时返回错误
+    pub fn print(&self, address: &Address) -> Result<(), Box<dyn Error>> {
+        if !self.qr {
+            return Ok(());
+        }
+        let value = self.amount.as_deref().map(units::parse_ether_amount).transpose()?;
+        qr::print(&payment::qr_payload(address, network::current().chain_id(), value))
+    }
+}
+
+/// 导出 CSV 的选项，用 `#[command(flatten)]` 嵌入输出表格的命令
+#[derive(Args, Debug)]
+pub struct CsvArgs {
+    /// 同时把表格写入 CSV 文件（先写临时文件再改名，中途失败不会留下不完整的文件）
+    #[arg(long, value_name = "PATH")]
+    pub csv: Option<PathBuf>,
+
+    /// CSV 开头加 UTF-8 BOM，让 Excel 正确显示中文
+    #[arg(long, requires = "csv")]
+    pub bom: bool,
+}
+
+impl CsvArgs {
+    /// 传了 `--csv` 时写入表格
+    ///
+    /// # 参数
+    /// * `table` - 要导出的表格
+    ///
+    /// # 返回
+    /// * `Result<(), Box<dyn Error>>` - 执行结果；写入失败时返回错误
+    pub fn write(&self, table: &Table) -> Result<(), Box<dyn Error>> {
+        let Some(path) = &self.csv else {
+            return Ok(());
+        };
+        table
+            .write_csv(path, self.bom)
+            .map_err(|e| tf(Msg::CsvWriteFailed, &[&path.display(), &e]))?;
+        narrate!("{}", tf(Msg::CsvWritten, &[&table.len(), &path.display()]));
+        Ok(())
+    }
+}
+
+/// 导出 JSON 的选项，用 `#[command(flatten)]` 嵌入输出表格的命令
+#[derive(Args, Debug)]
+pub struct JsonArgs {
+    /// 同时把表格写入 JSON 文件（每行一个对象，写入方式与 --csv 相同）
+    #[arg(long, value_name = "PATH")]
+    pub json: Option<PathBuf>,
+}
+
+impl JsonArgs {
+    /// 传了 `--json` 时写入表格
+    ///
+    /// # 参数
+    /// * `table` - 要导出的表格
+    ///
+    /// # 返回
+    /// * `Result<(), Box<dyn Error>>` - 执行结果；写入失败时返回错误
+    pub fn write(&self, table: &Table) -> Result<(), Box<dyn Error>> {
+        let Some(path) = &self.json else {
+            return Ok(());
+        };
+        table
+            .write_json(path)
+            .map_err(|e| tf(Msg::JsonWriteFailed, &[&path.display(), &e]))?;
+        narrate!("{}", tf(Msg::JsonWritten, &[&table.len(), &path.display()]));
+        Ok(())
+    }
+}
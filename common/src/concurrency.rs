@@ -0,0 +1,132 @@
+//! 限制并发数地批量执行异步任务
+//!
+//! 给 500 个地址各开一个 future 再 `join_all`，会同时发出 500 个请求，本地连接池和远端节点都吃不消。
+//! 批量查余额、扫描区块这类功能都通过 [`run_bounded`] 执行：同时最多运行 N 个，结果仍按输入顺序返回。
+
+use futures::stream::{self, StreamExt};
+use std::future::Future;
+
+/// 默认的并发数
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// 同时最多运行 `concurrency` 个任务，按输入顺序返回每个任务的结果
+///
+/// 某个任务失败不会取消其他任务，每个结果单独返回；需要"任一失败即失败"时
+/// 对结果 `collect::<Result<Vec<_>, _>>()`。
+///
+/// # 参数
+/// * `items` - 输入
+/// * `concurrency` - 同时运行的任务数上限，0 按 1 处理
+/// * `f` - 为每个输入创建任务
+///
+/// # 返回
+/// * `Vec<Result<T, E>>` - 与输入顺序一致的结果
+pub async fn run_bounded<I, F, Fut, T, E>(items: I, concurrency: usize, f: F) -> Vec<Result<T, E>>
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    run_bounded_with_progress(items, concurrency, f, |_, _| {}).await
+}
+
+/// 同 [`run_bounded`]，每完成一个任务调用一次 `on_progress(已完成数, 总数)`，用于显示进度
+///
+/// 任务按完成的先后调用回调，不一定是输入顺序。
+///
+/// # 参数
+/// * `items` - 输入
+/// * `concurrency` - 同时运行的任务数上限，0 按 1 处理
+/// * `f` - 为每个输入创建任务
+/// * `on_progress` - 进度回调
+///
+/// # 返回
+/// * `Vec<Result<T, E>>` - 与输入顺序一致的结果
+pub async fn run_bounded_with_progress<I, F, Fut, T, E>(
+    items: I,
+    concurrency: usize,
+    f: F,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Vec<Result<T, E>>
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let tasks: Vec<_> = items.into_iter().map(f).collect();
+    let total = tasks.len();
+    let mut slots: Vec<Option<Result<T, E>>> = (0..total).map(|_| None).collect();
+    // buffer_unordered 先完成的先返回，带上下标再放回原来的位置
+    let mut results = stream::iter(tasks.into_iter().enumerate())
+        .map(|(index, task)| async move { (index, task.await) })
+        .buffer_unordered(concurrency.max(1));
+    let mut done = 0;
+    while let Some((index, result)) = results.next().await {
+        slots[index] = Some(result);
+        done += 1;
+        on_progress(done, total);
+    }
+    slots.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    // 记录同时运行的任务数的峰值；后面的任务先完成，打乱完成顺序
+    async fn run(count: u64, concurrency: usize) -> (Vec<Result<u64, String>>, usize) {
+        let (running, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let results = run_bounded(0..count, concurrency, |i| {
+            let (running, peak) = (&running, &peak);
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(100 - i)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                if i % 7 == 3 { Err(format!("task {i} failed")) } else { Ok(i * 10) }
+            }
+        })
+        .await;
+        (results, peak.load(Ordering::SeqCst))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn respects_cap_and_input_order() {
+        let (results, peak) = run(20, 4).await;
+        assert_eq!(peak, 4);
+        let expected: Vec<_> = (0..20)
+            .map(|i| if i % 7 == 3 { Err(format!("task {i} failed")) } else { Ok(i * 10) })
+            .collect();
+        assert_eq!(results, expected);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn zero_concurrency_runs_one_at_a_time() {
+        let (results, peak) = run(5, 0).await;
+        assert_eq!(peak, 1);
+        assert_eq!(results.len(), 5);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cap_larger_than_input_runs_everything_at_once() {
+        let (results, peak) = run(3, 100).await;
+        assert_eq!(peak, 3);
+        assert_eq!(results, [Ok(0), Ok(10), Ok(20)]);
+    }
+
+    #[tokio::test]
+    async fn reports_progress_for_every_task() {
+        let mut progress = Vec::new();
+        let results = run_bounded_with_progress(
+            ["a", "b", "c"],
+            2,
+            |item| async move { Ok::<_, ()>(item.len()) },
+            |done, total| progress.push((done, total)),
+        )
+        .await;
+        assert_eq!(results, [Ok(1), Ok(1), Ok(1)]);
+        assert_eq!(progress, [(1, 3), (2, 3), (3, 3)]);
+    }
+}
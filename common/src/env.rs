@@ -0,0 +1,48 @@
+//! 读取 `.env` / 环境变量里的配置
+
+/// 读取环境变量，去掉首尾空白；未设置、为空或只有空白时当作没有设置
+///
+/// `.env` 里写成 `PRIVATE_KEY=""` 或 `TO_ADDRESS=" "` 是常见的笔误，按存在处理的话
+/// 后面解析时才报出难懂的错误；当作没有设置则会走默认值或提示配置的分支。
+///
+/// # 参数
+/// * `name` - 环境变量名
+///
+/// # 返回
+/// * `Option<String>` - 去掉首尾空白后的值
+pub fn var(name: &str) -> Option<String> {
+    non_blank(std::env::var(name).ok())
+}
+
+// 去掉首尾空白，剩下空字符串时为 `None`
+fn non_blank(value: Option<String>) -> Option<String> {
+    let value = value?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT_TO: &str = "0x741CD80d41eDE318feD4010E296704a061f4115a";
+
+    // 和 level4 读取 TO_ADDRESS 的写法一致
+    fn to_address(value: Option<&str>) -> String {
+        non_blank(value.map(str::to_string)).unwrap_or_else(|| DEFAULT_TO.to_string())
+    }
+
+    #[test]
+    fn empty_to_address_falls_back_to_the_default() {
+        for value in [None, Some(""), Some(" "), Some("\t\r\n")] {
+            assert_eq!(to_address(value), DEFAULT_TO, "{value:?}");
+        }
+        let set = "0x0000000000000000000000000000000000000001";
+        assert_eq!(to_address(Some(&format!("  {set}\r"))), set);
+    }
+
+    #[test]
+    fn unset_variable_reads_as_none() {
+        assert_eq!(var("ARBI_TEST_VARIABLE_THAT_IS_NEVER_SET"), None);
+    }
+}
@@ -0,0 +1,28 @@
+//! 监控命令的 `--exec`：提醒时在 shell 中执行用户提供的命令
+
+use crate::messages::{Msg, tf};
+use crate::style;
+
+/// 在 shell 中执行用户命令（Windows 上用 `cmd /C`，其他系统用 `sh -c`）
+///
+/// 失败或退出码非 0 时只打印警告，不影响调用方继续监控。
+///
+/// # 参数
+/// * `command` - 命令行
+/// * `env` - 额外的环境变量（变量名和值）
+pub async fn run_exec(command: &str, env: Vec<(String, String)>) {
+    let mut process = if cfg!(windows) {
+        let mut process = tokio::process::Command::new("cmd");
+        process.arg("/C");
+        process
+    } else {
+        let mut process = tokio::process::Command::new("sh");
+        process.arg("-c");
+        process
+    };
+    match process.arg(command).envs(env).status().await {
+        Ok(status) if status.success() => {}
+        Ok(status) => eprintln!("{}", style::warn(tf(Msg::WatchExecFailed, &[&status]))),
+        Err(e) => eprintln!("{}", style::warn(tf(Msg::WatchExecFailed, &[&e]))),
+    }
+}
@@ -0,0 +1,74 @@
+use crate::messages::{Msg, tf};
+use crate::network;
+use crate::rate_limit;
+use crate::retry::Backoff;
+use crate::rpc;
+use serde_json::Value;
+use std::error::Error;
+use std::time::Duration;
+use tracing::debug;
+
+// 读取 API key 的环境变量
+pub const API_KEY_ENV: &str = "ARBISCAN_API_KEY";
+
+// 免费 API key 每秒只能调用几次，被限流时按指数退避重试
+const RATE_LIMIT_BACKOFF: Backoff = Backoff {
+    retries: 5,
+    base_delay: Duration::from_secs(1),
+    max_delay: Duration::from_secs(16),
+};
+
+/// 读取区块浏览器 API key，没有配置或为空时返回 `None`
+pub fn api_key() -> Option<String> {
+    std::env::var(API_KEY_ENV)
+        .ok()
+        .filter(|key| !key.trim().is_empty())
+}
+
+/// 调用当前网络的 Etherscan 风格区块浏览器 API
+///
+/// 这类 API 出错时 HTTP 状态仍是 200，要看返回的 `status` 字段（"1" 为成功），
+/// 失败原因在 `result` 或 `message` 里。被限流时等待后重试；列表查询没有结果时
+/// `status` 也是 "0"，这种情况按空列表返回。
+///
+/// # 参数
+/// * `api_key` - API key
+/// * `params` - 查询参数，如 `[("module", "contract"), ("action", "getcontractcreation")]`
+///
+/// # 返回
+/// * `Result<Value, Box<dyn Error>>` - 成功时的 `result` 字段
+pub async fn get(api_key: &str, params: &[(&str, &str)]) -> Result<Value, Box<dyn Error>> {
+    let client = rpc::http_client()?;
+    let url = network::current().explorer_api_url();
+    let endpoint = rate_limit::endpoint(url);
+    let mut attempt = 0;
+    loop {
+        rate_limit::acquire(&endpoint).await;
+        let response: Value = client
+            .get(url)
+            .query(params)
+            .query(&[("apikey", api_key)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if response["status"].as_str() == Some("1")
+            || response["result"].as_array().is_some_and(|list| list.is_empty())
+        {
+            return Ok(response["result"].clone());
+        }
+
+        let reason = match &response["result"] {
+            Value::String(reason) => reason.clone(),
+            _ => response["message"].as_str().unwrap_or_default().to_string(),
+        };
+        attempt += 1;
+        if !reason.to_lowercase().contains("rate limit") || attempt > RATE_LIMIT_BACKOFF.retries {
+            return Err(tf(Msg::ExplorerFailed, &[&reason]).into());
+        }
+        debug!(attempt, reason, "explorer rate limited, retrying");
+        tokio::time::sleep(RATE_LIMIT_BACKOFF.delay(attempt)).await;
+    }
+}
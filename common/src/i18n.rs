@@ -0,0 +1,207 @@
+use clap::ValueEnum;
+use std::cell::Cell;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+// 选择输出语言的环境变量
+pub const LANG_ENV: &str = "ARBI_LANG";
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+thread_local! {
+    // with_lang 设置的临时语言，优先于全局设置
+    static LANG_OVERRIDE: Cell<Option<Lang>> = const { Cell::new(None) };
+}
+
+/// 输出语言
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Lang {
+    /// 中文（默认）
+    Zh,
+    /// English
+    En,
+}
+
+impl Lang {
+    /// 解析语言代码，大小写不敏感，兼容 `zh-CN` / `en_US` 这类写法
+    pub fn parse(code: &str) -> Option<Self> {
+        let code = code.trim().to_ascii_lowercase();
+        if code.starts_with("zh") {
+            Some(Lang::Zh)
+        } else if code.starts_with("en") {
+            Some(Lang::En)
+        } else {
+            None
+        }
+    }
+}
+
+/// 初始化输出语言：命令行 `--lang` 优先，其次 `ARBI_LANG` 环境变量，默认中文
+///
+/// # 参数
+/// * `cli` - 命令行传入的语言
+///
+/// # 返回
+/// * `Lang` - 最终生效的语言
+pub fn init(cli: Option<Lang>) -> Lang {
+    let lang = cli
+        .or_else(|| std::env::var(LANG_ENV).ok().as_deref().and_then(Lang::parse))
+        .unwrap_or(Lang::Zh);
+    *LANG.get_or_init(|| lang)
+}
+
+/// 当前输出语言（未初始化时为中文）
+pub fn lang() -> Lang {
+    LANG_OVERRIDE
+        .with(Cell::get)
+        .or_else(|| LANG.get().copied())
+        .unwrap_or(Lang::Zh)
+}
+
+/// 在当前线程临时改用 `lang` 执行 `f`，用于按指定语言生成消息（如测试中的输出快照）
+///
+/// # 参数
+/// * `lang` - 临时使用的语言
+/// * `f` - 要执行的函数
+///
+/// # 返回
+/// * `T` - `f` 的返回值
+pub fn with_lang<T>(lang: Lang, f: impl FnOnce() -> T) -> T {
+    let previous = LANG_OVERRIDE.with(|cell| cell.replace(Some(lang)));
+    let result = f();
+    LANG_OVERRIDE.with(|cell| cell.set(previous));
+    result
+}
+
+/// 按当前语言从 (中文, English) 中选择一个
+pub fn pick(texts: (&'static str, &'static str)) -> &'static str {
+    match lang() {
+        Lang::Zh => texts.0,
+        Lang::En => texts.1,
+    }
+}
+
+/// 依次用参数替换模板中的 `{}` 占位符
+///
+/// # 参数
+/// * `template` - 消息模板
+/// * `args` - 参数列表
+///
+/// # 返回
+/// * `String` - 替换后的文本
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+
+    while let Some(pos) = rest.find("{}") {
+        output.push_str(&rest[..pos]);
+        match args.next() {
+            Some(arg) => output.push_str(&arg.to_string()),
+            None => output.push_str("{}"),
+        }
+        rest = &rest[pos + 2..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// 定义消息目录：每个消息标识必须同时给出中文和英文模板，缺一种无法编译
+///
+/// 模板中的 `{}` 会按顺序替换成 `tf` 的参数，两种语言的占位符数量必须一致。
+#[macro_export]
+macro_rules! define_messages {
+    ($($id:ident => ($zh:expr, $en:expr),)*) => {
+        /// 消息标识
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum Msg {
+            $($id,)*
+        }
+
+        impl Msg {
+            /// 全部消息标识
+            #[allow(dead_code)]
+            pub const ALL: &'static [Msg] = &[$(Msg::$id,)*];
+
+            /// (中文, English) 模板
+            pub fn templates(self) -> (&'static str, &'static str) {
+                match self {
+                    $(Msg::$id => ($zh, $en),)*
+                }
+            }
+        }
+
+        /// 当前语言的消息
+        #[allow(dead_code)]
+        pub fn t(msg: Msg) -> &'static str {
+            $crate::i18n::pick(msg.templates())
+        }
+
+        /// 当前语言的消息，按顺序替换模板中的 `{}`
+        #[allow(dead_code)]
+        pub fn tf(msg: Msg, args: &[&dyn std::fmt::Display]) -> String {
+            let (zh, en) = msg.templates();
+            debug_assert_eq!(
+                zh.matches("{}").count(),
+                en.matches("{}").count(),
+                "消息 {:?} 的中英文占位符数量不一致",
+                msg
+            );
+            $crate::i18n::fill(t(msg), args)
+        }
+
+        #[cfg(test)]
+        mod message_catalog_tests {
+            use super::Msg;
+
+            #[test]
+            fn every_message_has_both_translations_with_matching_placeholders() {
+                for msg in Msg::ALL {
+                    let (zh, en) = msg.templates();
+                    assert!(!zh.trim().is_empty(), "{:?} 缺少中文", msg);
+                    assert!(!en.trim().is_empty(), "{:?} 缺少英文", msg);
+                    let counts = (zh.matches("{}").count(), en.matches("{}").count());
+                    assert_eq!(counts.0, counts.1, "{:?} 的中英文占位符数量不一致", msg);
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_language_codes_and_regions() {
+        assert_eq!(Lang::parse("zh"), Some(Lang::Zh));
+        assert_eq!(Lang::parse(" ZH-cn "), Some(Lang::Zh));
+        assert_eq!(Lang::parse("zh_TW"), Some(Lang::Zh));
+        assert_eq!(Lang::parse("en"), Some(Lang::En));
+        assert_eq!(Lang::parse("en_US.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::parse("EN"), Some(Lang::En));
+        assert_eq!(Lang::parse("fr"), None);
+        assert_eq!(Lang::parse(""), None);
+    }
+
+    #[test]
+    fn with_lang_switches_only_inside_the_closure() {
+        assert_eq!(lang(), Lang::Zh);
+        assert_eq!(with_lang(Lang::En, || pick(("中", "en"))), "en");
+        assert_eq!(with_lang(Lang::En, || with_lang(Lang::Zh, lang)), Lang::Zh);
+        assert_eq!(lang(), Lang::Zh);
+    }
+
+    #[test]
+    fn fill_replaces_placeholders_in_order() {
+        assert_eq!(fill("{} → {}", &[&1, &"0xabc"]), "1 → 0xabc");
+        assert_eq!(fill("no args", &[]), "no args");
+        assert_eq!(fill("{}{}", &[&"a", &"b"]), "ab");
+    }
+
+    #[test]
+    fn fill_keeps_unmatched_placeholders_and_ignores_extra_args() {
+        assert_eq!(fill("{} and {}", &[&"one"]), "one and {}");
+        assert_eq!(fill("{}", &[&"one", &"two"]), "one");
+    }
+}
@@ -0,0 +1,118 @@
+//! 私钥和助记词的解析
+
+use ethers::core::k256::elliptic_curve::zeroize::Zeroizing;
+use ethers::signers::coins_bip39::English;
+use ethers::signers::{LocalWallet, MnemonicBuilder};
+use std::error::Error;
+
+use crate::messages::{Msg, t, tf};
+
+// 私钥的十六进制字符数（32 字节）
+const PRIVATE_KEY_HEX_LEN: usize = 64;
+
+// 去掉首尾空白和一对包裹的引号（从 .env 或剪贴板复制时常带上）
+fn unquote(raw: &str) -> &str {
+    let trimmed = raw.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = trimmed.strip_prefix(quote).and_then(|s| s.strip_suffix(quote)) {
+            return inner.trim();
+        }
+    }
+    trimmed
+}
+
+/// 宽松地解析私钥：允许首尾空白、一对引号和 0x / 0X 前缀
+///
+/// 出错时只报告长度或字符问题，不会把输入回显到错误信息里。
+///
+/// # 参数
+/// * `raw` - 私钥字符串
+///
+/// # 返回
+/// * `Result<LocalWallet, Box<dyn Error>>` - 钱包；不是 64 个十六进制字符时返回错误
+pub fn parse_private_key(raw: &str) -> Result<LocalWallet, Box<dyn Error>> {
+    let key = unquote(raw);
+    let key = key
+        .strip_prefix("0x")
+        .or_else(|| key.strip_prefix("0X"))
+        .unwrap_or(key);
+    if key.len() != PRIVATE_KEY_HEX_LEN {
+        return Err(tf(Msg::InvalidPrivateKeyLength, &[&key.len(), &PRIVATE_KEY_HEX_LEN]).into());
+    }
+    if !key.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(t(Msg::InvalidPrivateKeyChars).into());
+    }
+    key.parse::<LocalWallet>()
+        .map_err(|_| t(Msg::InvalidPrivateKeyValue).into())
+}
+
+/// 从 BIP-39 助记词按 BIP-44 路径 m/44'/60'/0'/0/{index} 派生钱包
+///
+/// 单词之间多余的空白和大小写会先统一成单个空格、小写。
+///
+/// # 参数
+/// * `phrase` - 助记词
+/// * `index` - 地址序号
+///
+/// # 返回
+/// * `Result<LocalWallet, Box<dyn Error>>` - 钱包；助记词无效（单词不在词表或校验和不对）时返回错误
+pub fn mnemonic_wallet(phrase: &str, index: u32) -> Result<LocalWallet, Box<dyn Error>> {
+    let words: Vec<&str> = unquote(phrase).split_whitespace().collect();
+    let normalized = Zeroizing::new(words.join(" ").to_lowercase());
+    MnemonicBuilder::<English>::default()
+        .phrase(normalized.as_str())
+        .index(index)?
+        .build()
+        .map_err(|e| tf(Msg::InvalidMnemonic, &[&e]).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::signers::Signer;
+    use ethers::types::Address;
+    use std::str::FromStr;
+
+    // Hardhat / anvil 默认的测试助记词，只用于测试
+    const TEST_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+    fn address(s: &str) -> Address {
+        Address::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn derives_the_standard_bip44_accounts() {
+        let first = mnemonic_wallet(TEST_MNEMONIC, 0).unwrap();
+        assert_eq!(first.address(), address("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"));
+        let key = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+        assert_eq!(first.signer().to_bytes()[..], ethers::utils::hex::decode(key).unwrap());
+
+        let second = mnemonic_wallet(TEST_MNEMONIC, 1).unwrap();
+        assert_eq!(second.address(), address("0x70997970C51812dc3A010C7d01b50e0d17dc79C8"));
+    }
+
+    #[test]
+    fn normalizes_spacing_case_and_quotes() {
+        let messy = "  \"Test test  TEST test test test\ttest test test test test Junk\"\n";
+        let wallet = mnemonic_wallet(messy, 0).unwrap();
+        assert_eq!(wallet.address(), address("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"));
+    }
+
+    #[test]
+    fn rejects_bad_mnemonics() {
+        // 最后一个单词决定校验和
+        assert!(mnemonic_wallet("test test test test test test test test test test test test", 0)
+            .is_err());
+        assert!(mnemonic_wallet("test test test notaword", 0).is_err());
+    }
+
+    #[test]
+    fn private_key_matches_the_mnemonic_account() {
+        let key = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+        let expected = address("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+        assert_eq!(parse_private_key(key).unwrap().address(), expected);
+        assert_eq!(parse_private_key(&format!(" '{}' ", key)).unwrap().address(), expected);
+        let error = parse_private_key("0x1234").unwrap_err();
+        assert_eq!(error.to_string(), tf(Msg::InvalidPrivateKeyLength, &[&4, &64]));
+    }
+}
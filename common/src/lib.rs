@@ -0,0 +1,33 @@
+//! 各个 level 共用的工具代码
+
+pub mod bench;
+pub mod block_scan;
+pub mod cli;
+pub mod concurrency;
+pub mod env;
+pub mod exec;
+pub mod explorer;
+pub mod i18n;
+pub mod keys;
+pub mod logging;
+pub mod logs;
+pub mod messages;
+pub mod metrics;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock_rpc;
+pub mod network;
+pub mod notify;
+pub mod payment;
+pub mod price_feed;
+pub mod qr;
+pub mod rate_limit;
+pub mod rawtx;
+pub mod retry;
+pub mod revert;
+pub mod rpc;
+pub mod rpc_batch;
+pub mod shutdown;
+pub mod style;
+pub mod table;
+pub mod txhash;
+pub mod units;
@@ -0,0 +1,269 @@
+use clap::ValueEnum;
+use std::sync::OnceLock;
+use tracing::Subscriber;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+
+// 选择日志格式的环境变量
+pub const LOG_FORMAT_ENV: &str = "ARBI_LOG_FORMAT";
+
+// RPC 请求日志使用的 target，-v / -vv 只放开这个 target 的级别
+pub const RPC_TARGET: &str = "arbi::rpc";
+
+static VERBOSITY: OnceLock<Verbosity> = OnceLock::new();
+
+/// 输出详细程度
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// -q：只输出结果和错误
+    Quiet,
+    /// 默认：输出每一步的说明
+    Normal,
+    /// -v：额外输出每个 RPC 请求的方法、参数大小和耗时
+    Verbose,
+    /// -vv：额外输出完整的 JSON-RPC 请求和响应（敏感内容已脱敏）
+    Trace,
+}
+
+impl Verbosity {
+    /// 由 `-v` 的次数和 `-q` 计算详细程度
+    pub fn from_flags(verbose: u8, quiet: bool) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::Trace,
+        }
+    }
+}
+
+/// 当前详细程度（未初始化时为默认）
+pub fn verbosity() -> Verbosity {
+    VERBOSITY.get().copied().unwrap_or(Verbosity::Normal)
+}
+
+/// 是否处于 -q 模式
+pub fn is_quiet() -> bool {
+    verbosity() == Verbosity::Quiet
+}
+
+/// 是否处于 -v / -vv 模式
+pub fn is_verbose() -> bool {
+    verbosity() >= Verbosity::Verbose
+}
+
+/// 输出步骤说明，-q 时不输出；结果和错误仍然直接用 println! / eprintln!
+#[macro_export]
+macro_rules! narrate {
+    ($($arg:tt)*) => {
+        if !$crate::logging::is_quiet() {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// 在 stderr 的同一行刷新进度，-q 时不输出；进度结束后调用 [`end_progress`] 换行
+#[macro_export]
+macro_rules! progress {
+    ($($arg:tt)*) => {
+        if !$crate::logging::is_quiet() {
+            eprint!("\r{}", format_args!($($arg)*));
+        }
+    };
+}
+
+/// 结束 [`progress!`] 输出的进度行，-q 时不输出
+pub fn end_progress() {
+    if !is_quiet() {
+        eprintln!();
+    }
+}
+
+/// 日志格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// 适合人看的控制台输出（默认，只显示警告和错误）
+    Human,
+    /// 每行一个 JSON 对象，便于日志系统采集
+    Json,
+}
+
+impl LogFormat {
+    /// 解析格式名称，大小写不敏感
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "human" | "text" => Some(LogFormat::Human),
+            "json" => Some(LogFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// 初始化日志：命令行 `--log-format` 优先，其次 `ARBI_LOG_FORMAT` 环境变量，默认 human
+///
+/// 日志统一写到 stderr，不会和 stdout 上的结果输出混在一起。`RUST_LOG` 可以覆盖默认级别
+/// （human 默认 warn，json 默认 info，-q 时只有 error；-v / -vv 打开 RPC 请求日志）。JSON 模式下 chain_id、rpc_url、tx_hash 等字段
+/// 记录在 span 上，每行日志的 `spans` 数组里都带着这些字段，而不是拼接在消息文本里；
+/// 每个阶段（connect / estimate / broadcast / confirm）结束时还会输出一行带耗时的记录。
+///
+/// # 参数
+/// * `cli` - 命令行传入的日志格式
+/// * `verbosity` - 输出详细程度
+///
+/// # 返回
+/// * `LogFormat` - 最终生效的日志格式
+pub fn init(cli: Option<LogFormat>, verbosity: Verbosity) -> LogFormat {
+    let format = cli
+        .or_else(|| std::env::var(LOG_FORMAT_ENV).ok().as_deref().and_then(LogFormat::parse))
+        .unwrap_or(LogFormat::Human);
+    let verbosity = *VERBOSITY.get_or_init(|| verbosity);
+    let default_filter = default_filter(verbosity, format);
+
+    // 重复初始化（例如测试里）时保留第一次的设置
+    let filter = env_filter(&default_filter);
+    match format {
+        LogFormat::Human => {
+            let _ = tracing_subscriber::fmt()
+                .with_writer(std::io::stderr)
+                .with_env_filter(filter)
+                .with_target(false)
+                .try_init();
+        }
+        LogFormat::Json => {
+            let _ = json_subscriber(std::io::stderr, filter).try_init();
+        }
+    }
+
+    format
+}
+
+// JSON 格式的订阅者：事件字段展开到顶层，span 字段放在 `span` 和 `spans` 里，span 结束时输出耗时
+fn json_subscriber<W>(writer: W, filter: EnvFilter) -> impl Subscriber + Send + Sync
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt()
+        .with_writer(writer)
+        .json()
+        .with_env_filter(filter)
+        .with_current_span(true)
+        .with_span_list(true)
+        .flatten_event(true)
+        .with_span_events(FmtSpan::CLOSE)
+        .finish()
+}
+
+/// 没有设置 `RUST_LOG` 时的过滤规则
+///
+/// # 参数
+/// * `verbosity` - 输出详细程度
+/// * `format` - 日志格式
+///
+/// # 返回
+/// * `String` - `EnvFilter` 格式的规则
+pub(crate) fn default_filter(verbosity: Verbosity, format: LogFormat) -> String {
+    let base = match (verbosity, format) {
+        (Verbosity::Quiet, _) => "error",
+        (_, LogFormat::Human) => "warn",
+        (_, LogFormat::Json) => "info",
+    };
+    match verbosity {
+        Verbosity::Verbose => format!("{},{}=debug", base, RPC_TARGET),
+        Verbosity::Trace => format!("{},{}=trace", base, RPC_TARGET),
+        _ => base.to_string(),
+    }
+}
+
+/// 读取 `RUST_LOG`，未设置或无效时使用默认级别
+fn env_filter(default: &str) -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+    use std::io;
+    use std::sync::{Arc, Mutex};
+    use tracing::{field, info, info_span};
+
+    // 把日志写进共享的缓冲区
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Capture {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(bytes);
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn json_lines(capture: &Capture) -> Vec<Value> {
+        let bytes = capture.0.lock().unwrap().clone();
+        let text = String::from_utf8(bytes).unwrap();
+        text.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+    }
+
+    #[test]
+    fn json_lines_carry_event_and_span_fields() {
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber = json_subscriber(move || writer.clone(), EnvFilter::new("info"));
+        tracing::subscriber::with_default(subscriber, || {
+            let span = info_span!(
+                "transfer",
+                chain_id = 421614u64,
+                rpc_url = "https://rpc.example",
+                tx_hash = field::Empty
+            );
+            let _entered = span.enter();
+            span.record("tx_hash", "0xabc");
+            info!(gas = 21_000u64, "broadcast");
+            tracing::debug!("filtered out");
+        });
+
+        let lines = json_lines(&capture);
+        assert_eq!(lines.len(), 2, "{lines:?}");
+        let event = &lines[0];
+        assert_eq!(event["level"], "INFO");
+        assert_eq!(event["message"], "broadcast");
+        // flatten_event：事件字段在顶层，不在 `fields` 里
+        assert_eq!(event["gas"], 21_000);
+        assert!(event.get("fields").is_none());
+        assert!(event["timestamp"].is_string());
+        assert!(event["target"].as_str().unwrap().starts_with("arbi_common"));
+        let span = json!({
+            "name": "transfer",
+            "chain_id": 421614,
+            "rpc_url": "https://rpc.example",
+            "tx_hash": "0xabc",
+        });
+        assert_eq!(event["span"], span);
+        assert_eq!(event["spans"], json!([span]));
+
+        // span 结束时的一行带耗时
+        let close = &lines[1];
+        assert_eq!(close["message"], "close");
+        assert_eq!(close["span"]["name"], "transfer");
+        assert!(close["time.busy"].is_string() && close["time.idle"].is_string());
+    }
+
+    #[test]
+    fn default_filter_follows_verbosity_and_format() {
+        use LogFormat::{Human, Json};
+        assert_eq!(default_filter(Verbosity::Quiet, Json), "error");
+        assert_eq!(default_filter(Verbosity::Normal, Human), "warn");
+        assert_eq!(default_filter(Verbosity::Normal, Json), "info");
+        assert_eq!(default_filter(Verbosity::Verbose, Human), "warn,arbi::rpc=debug");
+        assert_eq!(default_filter(Verbosity::Trace, Json), "info,arbi::rpc=trace");
+        assert_eq!(LogFormat::parse(" JSON "), Some(Json));
+        assert_eq!(LogFormat::parse("text"), Some(Human));
+        assert_eq!(LogFormat::parse("yaml"), None);
+    }
+}
@@ -0,0 +1,241 @@
+//! 分段查询事件日志
+
+use ethers::providers::Middleware;
+use ethers::types::{Filter, H256, Log};
+use ethers::utils::keccak256;
+use std::error::Error;
+use tracing::warn;
+
+use crate::rpc::RpcProvider;
+
+/// 每次 `eth_getLogs` 默认查询的区块数，多数公共节点都接受
+pub const DEFAULT_CHUNK_SIZE: u64 = 2_000;
+
+// 节点因为结果太多或区块范围太大而拒绝查询时，错误信息里常见的片段（小写）
+const RANGE_LIMIT_ERRORS: [&str; 7] = [
+    "query returned more than",
+    "block range",
+    "range is too large",
+    "range too large",
+    "response size exceeded",
+    "too many results",
+    "max results",
+];
+
+/// ERC20 / ERC721 `Transfer(address,address,uint256)` 事件的 topic0
+pub fn transfer_topic() -> H256 {
+    H256::from(keccak256("Transfer(address,address,uint256)"))
+}
+
+/// 错误是否表示这次查询的范围太大，缩小范围后可以重试
+///
+/// # 参数
+/// * `message` - 错误信息
+///
+/// # 返回
+/// * `bool` - 是范围限制错误时为 `true`
+pub fn is_range_limit_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    RANGE_LIMIT_ERRORS.iter().any(|pattern| message.contains(pattern))
+}
+
+/// 一段查询的结果
+pub struct LogChunk {
+    pub from: u64,
+    pub to: u64,
+    pub logs: Vec<Log>,
+}
+
+/// 把 `[from, to]` 拆成若干段依次查询日志
+///
+/// 每次调用 `next` 查询一段。节点返回范围限制错误时把分段大小减半后重试同一位置，
+/// 减到 1 个区块仍然失败才返回错误；减小后的分段大小会沿用到后面的查询。
+/// 调用方可以在每段之后报告进度，找到需要的日志后随时停止。
+pub struct LogChunks<'a> {
+    provider: &'a RpcProvider,
+    filter: Filter,
+    /// 还没查询的范围，查完后为 `None`
+    remaining: Option<(u64, u64)>,
+    chunk_size: u64,
+    newest_first: bool,
+}
+
+impl<'a> LogChunks<'a> {
+    /// 创建分段查询，默认从旧到新
+    ///
+    /// # 参数
+    /// * `provider` - Provider 引用
+    /// * `filter` - 地址、topic 等过滤条件，区块范围会被每段的范围覆盖
+    /// * `from` - 起始区块（含）
+    /// * `to` - 结束区块（含）
+    /// * `chunk_size` - 每段的区块数，至少为 1
+    pub fn new(
+        provider: &'a RpcProvider,
+        filter: Filter,
+        from: u64,
+        to: u64,
+        chunk_size: u64,
+    ) -> Self {
+        Self {
+            provider,
+            filter,
+            remaining: (from <= to).then_some((from, to)),
+            chunk_size: chunk_size.max(1),
+            newest_first: false,
+        }
+    }
+
+    /// 改为从新到旧查询，适合查找最近一次事件
+    pub fn newest_first(mut self) -> Self {
+        self.newest_first = true;
+        self
+    }
+
+    /// 当前的分段大小（遇到范围限制后会变小）
+    pub fn chunk_size(&self) -> u64 {
+        self.chunk_size
+    }
+
+    /// 查询下一段
+    ///
+    /// # 返回
+    /// * `Result<Option<LogChunk>, Box<dyn Error>>` - 这一段的范围和日志，全部查完时为 `None`
+    pub async fn next(&mut self) -> Result<Option<LogChunk>, Box<dyn Error>> {
+        let Some((start, end)) = self.remaining else {
+            return Ok(None);
+        };
+        loop {
+            let span = self.chunk_size - 1;
+            let (from, to) = if self.newest_first {
+                (end.saturating_sub(span).max(start), end)
+            } else {
+                (start, start.saturating_add(span).min(end))
+            };
+            let filter = self.filter.clone().from_block(from).to_block(to);
+            match self.provider.get_logs(&filter).await {
+                Ok(logs) => {
+                    self.remaining = match self.newest_first {
+                        true => (from > start).then(|| (start, from - 1)),
+                        false => (to < end).then(|| (to + 1, end)),
+                    };
+                    return Ok(Some(LogChunk { from, to, logs }));
+                }
+                Err(e) if self.chunk_size > 1 && is_range_limit_error(&e.to_string()) => {
+                    self.chunk_size /= 2;
+                    let chunk_size = self.chunk_size;
+                    warn!(from, to, chunk_size, error = %e, "log range rejected, halving");
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_rpc::{MockRpc, Reply};
+    use serde_json::{Value, json};
+
+    // eth_getLogs 参数里的区块范围
+    fn range(params: &Value) -> (u64, u64) {
+        let block = |key: &str| {
+            let hex = params[0][key].as_str().unwrap().trim_start_matches("0x");
+            u64::from_str_radix(hex, 16).unwrap()
+        };
+        (block("fromBlock"), block("toBlock"))
+    }
+
+    // 拒绝超过 `max_blocks` 个区块的查询，错误信息和 Infura 的一样
+    fn limited_node(max_blocks: u64) -> MockRpc {
+        MockRpc::start(move |_, params| {
+            let (from, to) = range(params);
+            match to - from < max_blocks {
+                true => Reply::Result(json!([])),
+                false => Reply::Error("query returned more than 10000 results".to_string()),
+            }
+        })
+    }
+
+    async fn collect(chunks: &mut LogChunks<'_>) -> Vec<(u64, u64)> {
+        let mut ranges = Vec::new();
+        while let Some(chunk) = chunks.next().await.unwrap() {
+            ranges.push((chunk.from, chunk.to));
+        }
+        ranges
+    }
+
+    #[test]
+    fn recognizes_range_limit_errors() {
+        for message in [
+            "query returned more than 10000 results",
+            "exceed maximum block range: 5000",
+            "Log response size exceeded.",
+            "eth_getLogs block range is too large, max is 1k blocks",
+            "block range too large",
+            "Query timeout exceeded. Consider reducing your block range",
+            "Too Many Results",
+            "max results reached",
+        ] {
+            assert!(is_range_limit_error(message), "{message}");
+        }
+        for message in ["execution reverted", "connection refused", "header not found", ""] {
+            assert!(!is_range_limit_error(message), "{message}");
+        }
+    }
+
+    #[tokio::test]
+    async fn splits_range_into_chunks() {
+        let node = limited_node(u64::MAX);
+        let provider = node.provider();
+        let mut chunks = LogChunks::new(&provider, Filter::new(), 100, 349, 100);
+        assert_eq!(collect(&mut chunks).await, [(100, 199), (200, 299), (300, 349)]);
+
+        let mut chunks = LogChunks::new(&provider, Filter::new(), 100, 349, 100).newest_first();
+        assert_eq!(collect(&mut chunks).await, [(250, 349), (150, 249), (100, 149)]);
+
+        // from 大于 to 时不发请求；分段大小为 0 时按 1 处理
+        let mut empty = LogChunks::new(&provider, Filter::new(), 10, 9, 100);
+        assert!(empty.next().await.unwrap().is_none());
+        assert_eq!(LogChunks::new(&provider, Filter::new(), 0, 1, 0).chunk_size(), 1);
+        assert_eq!(node.count("eth_getLogs"), 6);
+    }
+
+    #[tokio::test]
+    async fn halves_chunk_size_on_range_limit() {
+        let node = limited_node(300);
+        let provider = node.provider();
+        let mut chunks = LogChunks::new(&provider, Filter::new(), 0, 999, 2_000);
+        let ranges = collect(&mut chunks).await;
+
+        // 2000 → 1000 → 500 → 250，之后沿用 250
+        assert_eq!(chunks.chunk_size(), 250);
+        assert_eq!(ranges, [(0, 249), (250, 499), (500, 749), (750, 999)]);
+        assert_eq!(node.count("eth_getLogs"), 3 + 4);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_halving_to_one_block() {
+        let node = limited_node(0);
+        let provider = node.provider();
+        let mut chunks = LogChunks::new(&provider, Filter::new(), 0, 10_000, 2_000);
+        let error = chunks.next().await.err().unwrap();
+
+        assert!(is_range_limit_error(&error.to_string()), "{error}");
+        assert_eq!(chunks.chunk_size(), 1);
+        // 2000、1000、500、250、125、62、31、15、7、3、1
+        assert_eq!(node.count("eth_getLogs"), 11);
+        let last = node.requests().last().map(|(_, params)| range(params));
+        assert_eq!(last, Some((0, 0)));
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_not_retried() {
+        let node = MockRpc::start(|_, _| Reply::Error("execution reverted".to_string()));
+        let provider = node.provider();
+        let mut chunks = LogChunks::new(&provider, Filter::new(), 0, 10_000, 2_000);
+        assert!(chunks.next().await.is_err());
+        assert_eq!(chunks.chunk_size(), 2_000);
+        assert_eq!(node.count("eth_getLogs"), 1);
+    }
+}
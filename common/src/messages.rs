@@ -0,0 +1,100 @@
+//! 公共模块输出的消息目录
+
+crate::define_messages! {
+    BenchStrategy => ("策略", "Strategy"),
+    BenchBest => ("最快(ms)", "Best(ms)"),
+    BenchMean => ("平均(ms)", "Mean(ms)"),
+    BenchSpeedup => ("加速比", "Speedup"),
+    CtrlCFirst => (
+        "⚠ 收到 Ctrl-C，完成当前操作后退出（再按一次强制退出）",
+        "⚠ Ctrl-C received, exiting after the current operation (press again to force quit)"
+    ),
+    CtrlCForce => ("⚠ 再次收到 Ctrl-C，强制退出", "⚠ Ctrl-C received again, forcing exit"),
+    Interrupted => ("操作被 Ctrl-C 中断", "operation interrupted by Ctrl-C"),
+    RevertNoReason => ("（没有返回 revert 原因）", "(no revert reason returned)"),
+    RevertCustomError => ("自定义错误 0x{}", "custom error 0x{}"),
+    InvalidAmount => (
+        "无效的金额: {}（可以用 `_` 或每 3 位一组的 `,` 分隔，如 1_000 或 1,000.5）",
+        "Invalid amount: {} (`_` or `,` thousands separators are allowed, e.g. 1_000 or 1,000.5)"
+    ),
+    InvalidHashLength => (
+        "{} 应为 {} 个十六进制字符（0x 前缀可有可无），实际为 {} 个",
+        "{} should be {} hex characters (the 0x prefix is optional), got {}"
+    ),
+    InvalidHashChars => ("{} 含有非十六进制字符", "{} contains non-hex characters"),
+    NegativeAmount => ("金额不能为负数: {}", "The amount cannot be negative: {}"),
+    ExcessPrecision => (
+        "金额 {} 的小数位太多：这个单位最多 {} 位小数",
+        "Amount {} has too many decimals: this unit allows at most {}"
+    ),
+    InvalidAmountReason => ("无效的金额 {}: {}", "Invalid amount {}: {}"),
+    InvalidEthUsd => ("无效的 ETH 美元价格: {}（应为正数，如 3150.5）", "Invalid ETH/USD rate: {} (expected a positive number such as 3150.5)"),
+    InvalidPrivateKeyLength => (
+        "私钥长度不对：去掉 0x 后有 {} 个字符，应为 {} 个十六进制字符",
+        "Wrong private key length: {} characters after stripping 0x, expected {} hex characters"
+    ),
+    InvalidPrivateKeyChars => ("私钥含有非十六进制字符", "The private key contains non-hex characters"),
+    InvalidPrivateKeyValue => ("私钥超出 secp256k1 的有效范围", "The private key is outside the valid secp256k1 range"),
+    InvalidMnemonic => ("无效的助记词: {}", "Invalid mnemonic: {}"),
+    ExplorerFailed => ("区块浏览器 API 返回错误: {}", "Block explorer API error: {}"),
+    MainnetRefused => (
+        "拒绝在主网（链 ID {}）上签名：这是学习用的工具，默认只在测试网上发送交易。确实要使用真实资金时请加上 --mainnet-i-accept-risk",
+        "Refusing to sign on mainnet (chain id {}): this is a learning tool and only sends testnet transactions by default. Pass --mainnet-i-accept-risk if you really mean to spend real funds"
+    ),
+    MainnetConfirmPrompt => (
+        "⚠ 即将在主网上签名交易，会花费真实资金。输入链 ID {} 确认: ",
+        "⚠ About to sign a mainnet transaction with real funds. Type the chain id {} to confirm: "
+    ),
+    MainnetNotConfirmed => ("未确认，已取消主网签名", "Not confirmed; mainnet signing cancelled"),
+    MainnetNotInteractive => (
+        "主网签名需要在终端里确认，当前输入不是终端",
+        "Mainnet signing must be confirmed in a terminal, but stdin is not a terminal"
+    ),
+    ChainMismatch => (
+        "链 ID 不一致：网络 {} 的链 ID 应为 {}，但 RPC 节点返回 {}。请检查 RPC 地址和 --network，确实要继续时加上 --allow-chain-mismatch",
+        "Chain id mismatch: network {} expects chain id {}, but the RPC node returned {}. Check the RPC URL and --network, or pass --allow-chain-mismatch to proceed anyway"
+    ),
+    ChainMismatchAllowed => ("⚠ {}（已通过 --allow-chain-mismatch 放行）", "⚠ {} (allowed by --allow-chain-mismatch)"),
+    BatchMissingId => ("批量响应缺少 id 字段", "batch response is missing the id field"),
+    BatchItemFailed => ("批量请求第 {} 项失败: {}", "batch request item {} failed: {}"),
+    BatchIdOutOfRange => ("批量响应 id 越界: {}", "batch response id out of range: {}"),
+    WebhookFailed => ("⚠ webhook 通知发送失败（不影响命令结果）: {}", "⚠ Webhook notification failed (the command result is unaffected): {}"),
+    DecodeInvalidHex => ("无效的十六进制: {}", "Invalid hex: {}"),
+    DecodeType => ("类型:         {}", "Type:            {}"),
+    DecodeFrom => ("发送方:       {}（由签名恢复）", "From:            {} (recovered from the signature)"),
+    DecodeTo => ("接收方:       {}", "To:              {}"),
+    DecodeValue => ("金额:         {} ETH（{} wei）", "Value:           {} ETH ({} wei)"),
+    DecodeContractCreation => ("（合约创建）", "(contract creation)"),
+    DecodeNonce => ("Nonce:        {}", "Nonce:           {}"),
+    DecodeGas => ("Gas 限额:     {}", "Gas limit:       {}"),
+    DecodeGasPrice => ("Gas 价格:     {}", "Gas price:       {}"),
+    DecodeMaxFee => ("最高 Gas 价格: {}", "Max fee per gas: {}"),
+    DecodePriorityFee => ("最高小费:     {}", "Max priority fee: {}"),
+    DecodeChainId => ("链 ID:        {}", "Chain ID:        {}"),
+    DecodeNoChainId => ("（无，未使用 EIP-155，可在任意链上重放）", "(none: no EIP-155, replayable on any chain)"),
+    DecodeData => ("数据:         {} 字节 {}", "Data:            {} bytes {}"),
+    DecodeHash => ("交易哈希:     {}", "Tx hash:         {}"),
+    DecodeEmpty => ("交易为空", "The transaction is empty"),
+    DecodeReadFailed => ("无法读取文件 {}: {}", "Cannot read file {}: {}"),
+    DecodeUnknownType => (
+        "不支持的交易类型 {}：只能解码 legacy、EIP-2930（0x01）和 EIP-1559（0x02）交易",
+        "Unsupported transaction type {}: only legacy, EIP-2930 (0x01) and EIP-1559 (0x02) can be decoded"
+    ),
+    DecodeInvalidRlp => ("RLP 解码失败: {}", "RLP decoding failed: {}"),
+    DecodeRecoverFailed => ("无法从签名恢复发送方: {}", "Cannot recover the sender from the signature: {}"),
+    DecodeAccessList => ("访问列表:     {} 项", "Access list:     {} entries"),
+    DecodeSelector => ("选择器:       {} {}", "Selector:        {} {}"),
+    DecodeUnknownSelector => ("（未知函数）", "(unknown function)"),
+    DecodeSignature => ("签名:         v={} r={} s={}", "Signature:       v={} r={} s={}"),
+    CsvWritten => ("已写入 {} 行到 {}", "Wrote {} rows to {}"),
+    CsvWriteFailed => ("无法写入 CSV 文件 {}: {}", "Cannot write the CSV file {}: {}"),
+    JsonWriteFailed => ("无法写入 JSON 文件 {}: {}", "Cannot write the JSON file {}: {}"),
+    JsonWritten => ("已写入 {} 个 JSON 对象到 {}", "Wrote {} JSON objects to {}"),
+    MetricsListening => ("Prometheus 指标: http://{}/metrics", "Prometheus metrics: http://{}/metrics"),
+    InvalidFeedAddress => ("{} 不是合法的合约地址: {}", "{} is not a valid contract address: {}"),
+    InvalidFeedAnswer => ("喂价合约 {} 返回了无效的值: {}", "Price feed {} returned an invalid value: {}"),
+    MetricsBindFailed => ("无法在 {} 上提供指标: {}", "Cannot serve metrics on {}: {}"),
+    ScanChunkSizeZero => ("扫描区块的分段大小必须大于 0", "The block scan chunk size must be greater than 0"),
+    ScanBlockMissing => ("节点还没有区块 #{}", "The node does not have block #{} yet"),
+    WatchExecFailed => ("⚠ --exec 命令执行失败: {}", "⚠ The --exec command failed: {}"),
+}
@@ -0,0 +1,347 @@
+//! Prometheus 指标：进程内共享的注册表，以及可选的 `/metrics` HTTP 端点
+//!
+//! 轮询类命令在每轮读取后更新仪表（余额、Gas 价格、finalized 落后的区块数），
+//! RPC 客户端和重试逻辑累加计数器；传了 `--metrics-listen` 时由 [`serve_while`] 对外提供。
+
+use ethers::types::{Address, U256};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode, header};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt::Write;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+use crate::messages::{Msg, tf};
+
+/// Prometheus 文本格式的 Content-Type
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+// 停止时等待正在处理的请求完成的最长时间
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 指标类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetricKind {
+    /// 只增不减的计数器
+    Counter,
+    /// 可以任意变化的仪表
+    Gauge,
+}
+
+impl MetricKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+        }
+    }
+}
+
+// 同名指标的说明、类型和按标签区分的取值
+struct Family {
+    help: &'static str,
+    kind: MetricKind,
+    samples: BTreeMap<String, f64>,
+}
+
+/// 指标注册表：第一次更新时注册，按名称排序输出
+pub struct Registry {
+    families: Mutex<BTreeMap<&'static str, Family>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Registry {
+    /// 创建空的注册表
+    pub const fn new() -> Self {
+        Self {
+            families: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn update(
+        &self,
+        (name, help, kind): (&'static str, &'static str, MetricKind),
+        labels: &[(&str, &str)],
+        update: impl FnOnce(&mut f64),
+    ) {
+        let mut families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let family = families.entry(name).or_insert_with(|| Family {
+            help,
+            kind,
+            samples: BTreeMap::new(),
+        });
+        update(family.samples.entry(render_labels(labels)).or_insert(0.0));
+    }
+
+    /// 设置仪表的值
+    ///
+    /// # 参数
+    /// * `name` - 指标名称
+    /// * `help` - 说明（`# HELP` 行）
+    /// * `labels` - 标签
+    /// * `value` - 新的值
+    pub fn set_gauge(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        self.update((name, help, MetricKind::Gauge), labels, |sample| *sample = value);
+    }
+
+    /// 计数器加 1
+    ///
+    /// # 参数
+    /// * `name` - 指标名称
+    /// * `help` - 说明（`# HELP` 行）
+    /// * `labels` - 标签
+    pub fn inc_counter(&self, name: &'static str, help: &'static str, labels: &[(&str, &str)]) {
+        self.add_counter(name, help, labels, 1.0);
+    }
+
+    /// 计数器加上 `amount`（如累计的秒数）
+    ///
+    /// # 参数
+    /// * `name` - 指标名称
+    /// * `help` - 说明（`# HELP` 行）
+    /// * `labels` - 标签
+    /// * `amount` - 增加的值，不能为负
+    pub fn add_counter(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        amount: f64,
+    ) {
+        self.update((name, help, MetricKind::Counter), labels, |sample| *sample += amount);
+    }
+
+    /// 按 Prometheus 文本格式输出全部指标
+    ///
+    /// # 返回
+    /// * `String` - 每个指标一组 `# HELP`、`# TYPE` 和取值行
+    pub fn render(&self) -> String {
+        let families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let mut text = String::new();
+        for (name, family) in families.iter() {
+            let _ = writeln!(text, "# HELP {} {}", name, family.help);
+            let _ = writeln!(text, "# TYPE {} {}", name, family.kind.as_str());
+            for (labels, value) in &family.samples {
+                let _ = writeln!(text, "{}{} {}", name, labels, value);
+            }
+        }
+        text
+    }
+}
+
+// 标签写成 `{a="1",b="2"}`，值里的反斜杠、双引号和换行按文本格式转义
+fn render_labels(labels: &[(&str, &str)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+            format!("{}=\"{}\"", key, value)
+        })
+        .collect();
+    format!("{{{}}}", pairs.join(","))
+}
+
+/// 进程内共享的注册表
+pub static REGISTRY: Registry = Registry::new();
+
+// 超过 2^53 的 wei 转成 f64 会丢精度，Prometheus 的取值本来就是 f64
+fn wei_value(value: U256) -> f64 {
+    value.to_string().parse().unwrap_or(f64::NAN)
+}
+
+/// 记录一次 JSON-RPC 请求
+pub fn record_rpc_request(method: &str) {
+    let help = "JSON-RPC requests sent, by method";
+    REGISTRY.inc_counter("arbi_rpc_requests_total", help, &[("method", method)]);
+}
+
+/// 记录一次失败的 JSON-RPC 请求（网络错误或节点返回错误）
+pub fn record_rpc_error(method: &str) {
+    let help = "JSON-RPC requests that failed, by method";
+    REGISTRY.inc_counter("arbi_rpc_errors_total", help, &[("method", method)]);
+}
+
+/// 记录一次退避重试（RPC 轮询和区块浏览器 API 共用）
+pub fn record_retry() {
+    REGISTRY.inc_counter("arbi_retries_total", "Retries after a failed attempt", &[]);
+}
+
+/// 记录一次经过限速器的请求和为等额度而等待的时间
+pub fn record_rate_limit(endpoint: &str, waited: Duration) {
+    let labels = [("endpoint", endpoint)];
+    let help = "Requests that passed the rate limiter, by endpoint";
+    REGISTRY.inc_counter("arbi_rate_limit_requests_total", help, &labels);
+    if !waited.is_zero() {
+        let help = "Requests delayed by the rate limiter, by endpoint";
+        REGISTRY.inc_counter("arbi_rate_limit_delayed_total", help, &labels);
+    }
+    let help = "Seconds spent waiting for the rate limiter, by endpoint";
+    let seconds = waited.as_secs_f64();
+    REGISTRY.add_counter("arbi_rate_limit_wait_seconds_total", help, &labels, seconds);
+}
+
+/// 更新监控地址的余额
+pub fn set_balance(address: Address, wei: U256) {
+    let address = format!("{:?}", address);
+    let help = "Balance of the watched address in wei";
+    REGISTRY.set_gauge("arbi_balance_wei", help, &[("address", &address)], wei_value(wei));
+}
+
+/// 更新当前 Gas 价格
+pub fn set_gas_price(wei: U256) {
+    REGISTRY.set_gauge("arbi_gas_price_wei", "Current gas price in wei", &[], wei_value(wei));
+}
+
+/// 更新 safe / finalized 落后 latest 的区块数和 finalized 落后的秒数
+pub fn set_finality_lag(safe_blocks: u64, finalized_blocks: u64, finalized_seconds: u64) {
+    let help = "Blocks the safe block trails latest";
+    REGISTRY.set_gauge("arbi_safe_lag_blocks", help, &[], safe_blocks as f64);
+    let help = "Blocks the finalized block trails latest";
+    REGISTRY.set_gauge("arbi_finalized_lag_blocks", help, &[], finalized_blocks as f64);
+    let help = "Seconds the finalized block trails latest";
+    REGISTRY.set_gauge("arbi_finalized_lag_seconds", help, &[], finalized_seconds as f64);
+}
+
+async fn handle(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let response = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/metrics") => Response::builder()
+            .header(header::CONTENT_TYPE, CONTENT_TYPE)
+            .body(Body::from(REGISTRY.render())),
+        _ => Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty()),
+    };
+    Ok(response.unwrap_or_else(|_| Response::new(Body::empty())))
+}
+
+/// 运行 `task`；传了 `listen` 时同时在该地址提供 `/metrics`，`task` 结束（包括 Ctrl-C）后停止服务
+///
+/// 地址在 `task` 开始前绑定，端口被占用等错误会立即返回，不会等到第一次抓取才发现。
+///
+/// # 参数
+/// * `listen` - 监听地址，如 `0.0.0.0:9898`；`None` 时只运行 `task`
+/// * `task` - 轮询主循环
+///
+/// # 返回
+/// * `Result<T, Box<dyn Error>>` - `task` 的结果；绑定失败时返回错误
+pub async fn serve_while<T>(
+    listen: Option<SocketAddr>,
+    task: impl Future<Output = Result<T, Box<dyn Error>>>,
+) -> Result<T, Box<dyn Error>> {
+    let Some(addr) = listen else {
+        return task.await;
+    };
+    let server = Server::try_bind(&addr).map_err(|e| tf(Msg::MetricsBindFailed, &[&addr, &e]))?;
+    let service = make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(handle)) });
+    let server = server.serve(service);
+    eprintln!("{}", tf(Msg::MetricsListening, &[&server.local_addr()]));
+
+    let (stop, stopped) = oneshot::channel::<()>();
+    let server = tokio::spawn(server.with_graceful_shutdown(async {
+        stopped.await.ok();
+    }));
+    let result = task.await;
+    let _ = stop.send(());
+    let _ = tokio::time::timeout(SHUTDOWN_TIMEOUT, server).await;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write as _};
+    use std::net::{TcpListener, TcpStream};
+
+    // 找一个空闲端口；释放后马上由 serve_while 绑定
+    fn free_addr() -> SocketAddr {
+        TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap()
+    }
+
+    // 用最简单的 HTTP/1.0 请求抓取，读到连接关闭为止
+    fn get(addr: SocketAddr, path: &str) -> std::io::Result<String> {
+        let mut stream = TcpStream::connect(addr)?;
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        write!(stream, "GET {} HTTP/1.0\r\nHost: {}\r\n\r\n", path, addr)?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        Ok(response)
+    }
+
+    #[test]
+    fn renders_families_in_text_format() {
+        let registry = Registry::new();
+        registry.inc_counter("b_total", "Requests", &[("method", "eth_call")]);
+        registry.add_counter("b_total", "Requests", &[("method", "eth_call")], 2.5);
+        registry.set_gauge("a_gauge", "Value", &[("path", "C:\\x \"y\"\nz")], 7.0);
+        registry.set_gauge("a_gauge", "Value", &[], 1e21);
+        assert_eq!(
+            registry.render(),
+            "# HELP a_gauge Value\n\
+             # TYPE a_gauge gauge\n\
+             a_gauge 1000000000000000000000\n\
+             a_gauge{path=\"C:\\\\x \\\"y\\\"\\nz\"} 7\n\
+             # HELP b_total Requests\n\
+             # TYPE b_total counter\n\
+             b_total{method=\"eth_call\"} 3.5\n"
+        );
+        assert_eq!(wei_value(U256::exp10(18)), 1e18);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn serves_the_registry_while_the_task_runs() {
+        let address = Address::from_low_u64_be(0x5c);
+        set_balance(address, U256::from(1_500));
+        let addr = free_addr();
+        let scrape = async move {
+            let blocking = tokio::task::spawn_blocking(move || {
+                (get(addr, "/metrics").unwrap(), get(addr, "/other").unwrap())
+            });
+            Ok(blocking.await?)
+        };
+        let (metrics, other) = serve_while(Some(addr), scrape).await.unwrap();
+
+        assert!(metrics.starts_with("HTTP/1.0 200") || metrics.starts_with("HTTP/1.1 200"));
+        assert!(metrics.contains(&format!("content-type: {}", CONTENT_TYPE)), "{metrics}");
+        assert!(metrics.contains("# TYPE arbi_balance_wei gauge"), "{metrics}");
+        let sample = format!("arbi_balance_wei{{address=\"{:?}\"}} 1500\n", address);
+        assert!(metrics.contains(&sample), "{metrics}");
+        assert!(other.contains(" 404 "), "{other}");
+
+        // task 结束后端口已经释放
+        assert!(get(addr, "/metrics").is_err());
+    }
+
+    #[tokio::test]
+    async fn occupied_port_fails_before_the_task_starts() {
+        let taken = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = taken.local_addr().unwrap();
+        let mut started = false;
+        let result = serve_while(Some(addr), async {
+            started = true;
+            Ok(())
+        })
+        .await;
+        let error = result.unwrap_err().to_string();
+        assert!(error.starts_with(&tf(Msg::MetricsBindFailed, &[&addr, &""])), "{error}");
+        assert!(!started);
+        // 不传地址时只运行 task
+        assert_eq!(serve_while(None, async { Ok(7) }).await.unwrap(), 7);
+    }
+}
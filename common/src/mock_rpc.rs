@@ -0,0 +1,148 @@
+//! 测试用的本地 JSON-RPC 节点
+//!
+//! 在后台线程里监听 127.0.0.1 的随机端口，每个请求交给测试提供的处理函数，
+//! 可以返回结果、JSON-RPC 错误，或者不回应直接断开连接来模拟网络错误。
+//! 请求经过真正的 [`RpcClient`](crate::rpc::RpcClient)，日志、脱敏和限速都和连接真实节点时一样。
+//! 只在本 crate 的测试和 `test-util` feature 下编译，其他 crate 在 dev-dependencies 里打开它。
+
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+use crate::rpc::{self, RpcProvider};
+
+/// 处理函数对一个请求的回应
+pub enum Reply {
+    /// 正常返回结果
+    Result(Value),
+    /// 返回 JSON-RPC 错误（code -32000）
+    Error(String),
+    /// 不回应直接断开连接，客户端看到的是网络错误
+    Disconnect,
+}
+
+type Handler = dyn Fn(&str, &Value) -> Reply + Send + Sync;
+type RequestLog = Mutex<Vec<(String, Value)>>;
+
+/// 本地 JSON-RPC 节点，进程结束前一直在后台运行
+pub struct MockRpc {
+    url: String,
+    requests: Arc<RequestLog>,
+}
+
+impl MockRpc {
+    /// 启动节点
+    ///
+    /// # 参数
+    /// * `handler` - 由方法名和参数决定回应；批量请求里的每个调用分别调用一次
+    pub fn start(handler: impl Fn(&str, &Value) -> Reply + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock rpc");
+        let url = format!("http://{}", listener.local_addr().expect("mock rpc address"));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+        let log = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let (handler, log) = (Arc::clone(&handler), Arc::clone(&log));
+                std::thread::spawn(move || serve(stream, &*handler, &log));
+            }
+        });
+        Self { url, requests }
+    }
+
+    /// 节点地址
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// 连接这个节点的 Provider
+    pub fn provider(&self) -> RpcProvider {
+        rpc::connect(&self.url).expect("connect mock rpc")
+    }
+
+    /// 收到的全部请求（方法名和参数），按到达顺序
+    pub fn requests(&self) -> Vec<(String, Value)> {
+        self.requests.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// 某个方法被调用的次数
+    pub fn count(&self, method: &str) -> usize {
+        self.requests().iter().filter(|(name, _)| name == method).count()
+    }
+}
+
+// 在一个连接上依次处理请求，直到客户端关闭连接或处理函数要求断开
+fn serve(stream: TcpStream, handler: &Handler, log: &RequestLog) {
+    let Ok(read_half) = stream.try_clone() else {
+        return;
+    };
+    let (mut reader, mut writer) = (BufReader::new(read_half), stream);
+    loop {
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':')
+                && name.eq_ignore_ascii_case("content-length")
+            {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+        let mut body = vec![0; length];
+        if reader.read_exact(&mut body).is_err() {
+            return;
+        }
+        let Ok(request) = serde_json::from_slice::<Value>(&body) else {
+            return;
+        };
+        let response = match &request {
+            Value::Array(calls) => {
+                let replies: Option<Vec<Value>> =
+                    calls.iter().map(|call| respond(call, handler, log)).collect();
+                match replies {
+                    Some(replies) => Value::Array(replies),
+                    None => return,
+                }
+            }
+            call => match respond(call, handler, log) {
+                Some(reply) => reply,
+                None => return,
+            },
+        };
+        let body = response.to_string();
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+            body.len()
+        );
+        let written = writer.write_all(head.as_bytes());
+        if written.and_then(|_| writer.write_all(body.as_bytes())).is_err() {
+            return;
+        }
+    }
+}
+
+// 记录请求并生成回应；要求断开时为 `None`
+fn respond(call: &Value, handler: &Handler, log: &RequestLog) -> Option<Value> {
+    let method = call["method"].as_str().unwrap_or_default();
+    let params = call.get("params").cloned().unwrap_or(Value::Null);
+    let id = call["id"].clone();
+    log.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push((method.to_string(), params.clone()));
+    match handler(method, &params) {
+        Reply::Result(result) => Some(json!({"jsonrpc": "2.0", "id": id, "result": result})),
+        Reply::Error(message) => Some(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {"code": -32000, "message": message},
+        })),
+        Reply::Disconnect => None,
+    }
+}
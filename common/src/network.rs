@@ -0,0 +1,380 @@
+use crate::messages::{Msg, t, tf};
+use crate::style;
+use clap::ValueEnum;
+use ethers::signers::{LocalWallet, Signer};
+use std::error::Error;
+use std::fmt;
+use std::io::{BufRead, IsTerminal, Write};
+use std::sync::OnceLock;
+
+// 选择网络的环境变量
+pub const NETWORK_ENV: &str = "ARBI_NETWORK";
+
+static NETWORK: OnceLock<Network> = OnceLock::new();
+static ACCEPT_MAINNET_RISK: OnceLock<bool> = OnceLock::new();
+static ALLOW_CHAIN_MISMATCH: OnceLock<bool> = OnceLock::new();
+
+// 主网链 ID：Ethereum、Arbitrum One、Arbitrum Nova。在这些链上签名会花费真实资金
+const MAINNET_CHAIN_IDS: [u64; 3] = [1, 42161, 42170];
+
+/// 支持的网络
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Network {
+    /// Arbitrum Sepolia 测试网（默认）
+    ArbitrumSepolia,
+    /// Arbitrum One 主网（签名交易需要 --mainnet-i-accept-risk）
+    ArbitrumOne,
+}
+
+impl Network {
+    /// 命令行和提示中使用的名称
+    pub fn name(self) -> &'static str {
+        match self {
+            Network::ArbitrumSepolia => "arbitrum-sepolia",
+            Network::ArbitrumOne => "arbitrum-one",
+        }
+    }
+
+    pub fn chain_id(self) -> u64 {
+        match self {
+            Network::ArbitrumSepolia => 421614,
+            Network::ArbitrumOne => 42161,
+        }
+    }
+
+    /// 对应 L1 的链 ID
+    pub fn l1_chain_id(self) -> u64 {
+        match self {
+            Network::ArbitrumSepolia => 11155111,
+            Network::ArbitrumOne => 1,
+        }
+    }
+
+    /// 公共 RPC 节点，第一个为默认节点，其余用于查询时的备用
+    pub fn rpc_urls(self) -> &'static [&'static str] {
+        match self {
+            Network::ArbitrumSepolia => &[
+                "https://sepolia-rollup.arbitrum.io/rpc",
+                "https://arbitrum-sepolia-rpc.publicnode.com",
+                "https://arbitrum-sepolia.drpc.org",
+            ],
+            Network::ArbitrumOne => &[
+                "https://arb1.arbitrum.io/rpc",
+                "https://arbitrum-one-rpc.publicnode.com",
+                "https://arbitrum.drpc.org",
+            ],
+        }
+    }
+
+    /// 默认 RPC 节点
+    pub fn rpc_url(self) -> &'static str {
+        self.rpc_urls()[0]
+    }
+
+    /// 默认 WebSocket 节点，用于订阅（公共 HTTP 入口不提供 WebSocket）
+    pub fn ws_url(self) -> &'static str {
+        match self {
+            Network::ArbitrumSepolia => "wss://arbitrum-sepolia-rpc.publicnode.com",
+            Network::ArbitrumOne => "wss://arbitrum-one-rpc.publicnode.com",
+        }
+    }
+
+    /// 对应 L1 的默认 RPC 节点
+    pub fn l1_rpc_url(self) -> &'static str {
+        match self {
+            Network::ArbitrumSepolia => "https://ethereum-sepolia-rpc.publicnode.com",
+            Network::ArbitrumOne => "https://ethereum-rpc.publicnode.com",
+        }
+    }
+
+    /// L1 上的 Inbox 合约地址
+    pub fn inbox_address(self) -> &'static str {
+        match self {
+            Network::ArbitrumSepolia => "0xaAe29B0366299461418F5324a79Afc425BE5ae21",
+            Network::ArbitrumOne => "0x4Dbd4fc535Ac27206064B68FfCf827b0A60BAB3f",
+        }
+    }
+
+    /// L1 上的 Outbox 合约地址，挑战期结束后在这里执行 L2 → L1 消息
+    pub fn outbox_address(self) -> &'static str {
+        match self {
+            Network::ArbitrumSepolia => "0x65f07C7D521164a4d5DaC6eB8Fac8DA067A3B78F",
+            Network::ArbitrumOne => "0x0B9857ae2D4A3DBe74ffE1d7DF045bb7F96E4840",
+        }
+    }
+
+    /// L1 上的 L1GatewayRouter 合约地址，标准 ERC20 跨链桥的入口
+    pub fn l1_gateway_router_address(self) -> &'static str {
+        match self {
+            Network::ArbitrumSepolia => "0xcE18836b233C83325Cc8848CA4487e94C6288264",
+            Network::ArbitrumOne => "0x72Ce9c846789fdB6fC1f34aC4AD25Dd9ef7031ef",
+        }
+    }
+
+    /// L2 上的 USDC 合约地址（测试网为 Circle 的测试代币）
+    pub fn usdc_address(self) -> &'static str {
+        match self {
+            Network::ArbitrumSepolia => "0x75faf114eafb1BDbe2F0316DF893fd58CE46AA4d",
+            Network::ArbitrumOne => "0xaf88d065e77c8cC2239327C5EDb3A432268e5831",
+        }
+    }
+
+    /// L2 上 Chainlink ETH/USD 喂价合约（聚合器代理）地址
+    ///
+    /// 测试网的喂价不保证持续更新，默认不配置；需要时用 `ARBI_ETH_USD_FEED` 指定。
+    pub fn eth_usd_feed(self) -> Option<&'static str> {
+        match self {
+            Network::ArbitrumSepolia => None,
+            Network::ArbitrumOne => Some("0x639Fe6ab55C921f74e7fac1ee960C0B6293ba612"),
+        }
+    }
+
+    /// 区块浏览器地址
+    pub fn explorer_url(self) -> &'static str {
+        match self {
+            Network::ArbitrumSepolia => "https://sepolia.arbiscan.io",
+            Network::ArbitrumOne => "https://arbiscan.io",
+        }
+    }
+
+    /// 区块浏览器 API 地址
+    pub fn explorer_api_url(self) -> &'static str {
+        match self {
+            Network::ArbitrumSepolia => "https://api-sepolia.arbiscan.io/api",
+            Network::ArbitrumOne => "https://api.arbiscan.io/api",
+        }
+    }
+
+    /// 解析网络名称，大小写不敏感
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::from_str(name.trim(), true).ok()
+    }
+}
+
+/// 初始化网络：命令行 `--network` 优先，其次 `ARBI_NETWORK` 环境变量，默认 Arbitrum Sepolia
+///
+/// # 参数
+/// * `cli` - 命令行传入的网络
+/// * `accept_mainnet_risk` - 是否传了 `--mainnet-i-accept-risk`
+/// * `allow_chain_mismatch` - 是否传了 `--allow-chain-mismatch`
+///
+/// # 返回
+/// * `Network` - 最终生效的网络
+pub fn init(
+    cli: Option<Network>,
+    accept_mainnet_risk: bool,
+    allow_chain_mismatch: bool,
+) -> Network {
+    let network = cli
+        .or_else(|| std::env::var(NETWORK_ENV).ok().as_deref().and_then(Network::parse))
+        .unwrap_or(Network::ArbitrumSepolia);
+    ACCEPT_MAINNET_RISK.get_or_init(|| accept_mainnet_risk);
+    ALLOW_CHAIN_MISMATCH.get_or_init(|| allow_chain_mismatch);
+    *NETWORK.get_or_init(|| network)
+}
+
+/// 当前网络（未初始化时为 Arbitrum Sepolia）
+pub fn current() -> Network {
+    NETWORK.get().copied().unwrap_or(Network::ArbitrumSepolia)
+}
+
+/// 节点的链 ID 与所选网络不一致时返回的错误
+#[derive(Debug)]
+pub struct ChainMismatch {
+    pub network: Network,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+impl fmt::Display for ChainMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = tf(Msg::ChainMismatch, &[&self.network.name(), &self.expected, &self.actual]);
+        write!(f, "{}", msg)
+    }
+}
+
+impl Error for ChainMismatch {}
+
+/// 检查节点返回的链 ID 是否属于当前网络，防止 RPC 地址配错时把交易发到别的链上
+///
+/// 传了 `--allow-chain-mismatch` 时只打印警告。
+///
+/// # 参数
+/// * `actual` - 节点返回的链 ID
+///
+/// # 返回
+/// * `Result<(), ChainMismatch>` - 不一致且没有放行时返回错误
+pub fn check_chain_id(actual: u64) -> Result<(), ChainMismatch> {
+    let network = current();
+    warn_allowed(compare_chain_id(network, network.chain_id(), actual, allow_mismatch()))
+}
+
+/// 检查 L1 节点返回的链 ID 是否属于当前网络对应的 L1，在 L1 上签名前调用
+///
+/// # 参数
+/// * `actual` - L1 节点返回的链 ID
+///
+/// # 返回
+/// * `Result<(), ChainMismatch>` - 不一致且没有放行时返回错误
+pub fn check_l1_chain_id(actual: u64) -> Result<(), ChainMismatch> {
+    let network = current();
+    warn_allowed(compare_chain_id(network, network.l1_chain_id(), actual, allow_mismatch()))
+}
+
+fn allow_mismatch() -> bool {
+    ALLOW_CHAIN_MISMATCH.get().copied().unwrap_or(false)
+}
+
+// 放行的不一致只打印警告
+fn warn_allowed(
+    outcome: Result<Option<ChainMismatch>, ChainMismatch>,
+) -> Result<(), ChainMismatch> {
+    if let Some(mismatch) = outcome? {
+        eprintln!("{}", style::warn(tf(Msg::ChainMismatchAllowed, &[&mismatch])));
+    }
+    Ok(())
+}
+
+// 比较链 ID：一致时为 Ok(None)，不一致但放行时为 Ok(Some)，否则为 Err
+fn compare_chain_id(
+    network: Network,
+    expected: u64,
+    actual: u64,
+    allow_mismatch: bool,
+) -> Result<Option<ChainMismatch>, ChainMismatch> {
+    if actual == expected {
+        return Ok(None);
+    }
+    let mismatch = ChainMismatch {
+        network,
+        expected,
+        actual,
+    };
+    if allow_mismatch {
+        Ok(Some(mismatch))
+    } else {
+        Err(mismatch)
+    }
+}
+
+/// 签名检查的结果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SigningGate {
+    /// 测试网，直接签名
+    Allowed,
+    /// 主网且传了 `--mainnet-i-accept-risk`，还要在终端里再确认一次
+    NeedsConfirmation,
+    /// 主网但没有传 `--mainnet-i-accept-risk`
+    Refused,
+}
+
+/// 判断在指定链上签名是否需要额外确认
+///
+/// # 参数
+/// * `chain_id` - 节点返回的链 ID（以节点为准，不信任 `--network`）
+/// * `accept_mainnet_risk` - 是否传了 `--mainnet-i-accept-risk`
+///
+/// # 返回
+/// * `SigningGate` - 允许、需要确认或拒绝
+pub fn signing_gate(chain_id: u64, accept_mainnet_risk: bool) -> SigningGate {
+    if !MAINNET_CHAIN_IDS.contains(&chain_id) {
+        SigningGate::Allowed
+    } else if accept_mainnet_risk {
+        SigningGate::NeedsConfirmation
+    } else {
+        SigningGate::Refused
+    }
+}
+
+/// 在终端里要求用户输入链 ID 确认主网签名；输入不是终端时直接拒绝
+fn confirm_mainnet(chain_id: u64) -> Result<(), Box<dyn Error>> {
+    if !std::io::stdin().is_terminal() {
+        return Err(t(Msg::MainnetNotInteractive).into());
+    }
+
+    eprint!("{}", tf(Msg::MainnetConfirmPrompt, &[&chain_id]));
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+
+    if answer.trim() == chain_id.to_string() {
+        Ok(())
+    } else {
+        Err(t(Msg::MainnetNotConfirmed).into())
+    }
+}
+
+/// 所有签名都要经过这里：主网必须传 `--mainnet-i-accept-risk` 并在终端里再次确认
+///
+/// 检查在创建签名客户端之前完成，被拒绝时不会产生任何签名。
+///
+/// # 参数
+/// * `wallet` - 从私钥创建的钱包
+/// * `chain_id` - 节点返回的链 ID
+///
+/// # 返回
+/// * `Result<LocalWallet, Box<dyn Error>>` - 绑定了链 ID 的钱包；主网未确认时返回错误
+pub fn signing_wallet(wallet: LocalWallet, chain_id: u64) -> Result<LocalWallet, Box<dyn Error>> {
+    let accept = ACCEPT_MAINNET_RISK.get().copied().unwrap_or(false);
+    match signing_gate(chain_id, accept) {
+        SigningGate::Allowed => {}
+        SigningGate::NeedsConfirmation => confirm_mainnet(chain_id)?,
+        SigningGate::Refused => return Err(tf(Msg::MainnetRefused, &[&chain_id]).into()),
+    }
+    Ok(wallet.with_chain_id(chain_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signing_gate_outcomes() {
+        for chain_id in [421614, 11155111, 31337] {
+            assert_eq!(signing_gate(chain_id, false), SigningGate::Allowed, "{chain_id}");
+            assert_eq!(signing_gate(chain_id, true), SigningGate::Allowed, "{chain_id}");
+        }
+        for chain_id in MAINNET_CHAIN_IDS {
+            assert_eq!(signing_gate(chain_id, true), SigningGate::NeedsConfirmation, "{chain_id}");
+            assert_eq!(signing_gate(chain_id, false), SigningGate::Refused, "{chain_id}");
+        }
+    }
+
+    // 测试里不会调用 init，相当于没有传 --mainnet-i-accept-risk
+    #[test]
+    fn signing_wallet_refuses_arbitrum_one_without_flag() {
+        let wallet = LocalWallet::new(&mut ethers::core::rand::thread_rng());
+        let refused = signing_wallet(wallet.clone(), 42161).unwrap_err();
+        assert_eq!(refused.to_string(), tf(Msg::MainnetRefused, &[&42161]));
+
+        let signer = signing_wallet(wallet, 421614).unwrap();
+        assert_eq!(signer.chain_id(), 421614);
+    }
+
+    #[test]
+    fn chain_id_mismatch_is_refused_unless_allowed() {
+        let network = Network::ArbitrumSepolia;
+        for allow in [false, true] {
+            assert!(compare_chain_id(network, 421614, 421614, allow).unwrap().is_none());
+        }
+
+        let refused = compare_chain_id(network, 421614, 42161, false).unwrap_err();
+        assert_eq!((refused.expected, refused.actual), (421614, 42161));
+        assert_eq!(
+            refused.to_string(),
+            tf(Msg::ChainMismatch, &[&"arbitrum-sepolia", &421614, &42161])
+        );
+
+        let allowed = compare_chain_id(network, 421614, 42161, true).unwrap().unwrap();
+        assert_eq!((allowed.expected, allowed.actual), (421614, 42161));
+    }
+
+    // 测试里不会调用 init：网络是 Arbitrum Sepolia，没有传 --allow-chain-mismatch
+    #[test]
+    fn checks_use_the_current_network() {
+        assert!(check_chain_id(421614).is_ok());
+        assert_eq!(check_chain_id(11155111).unwrap_err().expected, 421614);
+        assert!(check_l1_chain_id(11155111).is_ok());
+        assert_eq!(check_l1_chain_id(421614).unwrap_err().expected, 11155111);
+    }
+}
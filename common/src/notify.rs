@@ -0,0 +1,141 @@
+//! 命令结束时向 webhook 发送结果通知
+
+use ethers::types::TxHash;
+use serde_json::{Value, json};
+use tracing::{info, warn};
+
+use crate::messages::{Msg, tf};
+use crate::network;
+use crate::rpc;
+use crate::style;
+
+/// webhook 地址的环境变量（也可以写在 .env 里），没有设置时不发送通知
+pub const WEBHOOK_ENV: &str = "ARBI_WEBHOOK_URL";
+
+/// 命令的结束状态
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    /// 交易已广播，但在超时内没有确认
+    Pending,
+    /// 被 Ctrl-C 中断
+    Interrupted,
+    Failure,
+}
+
+impl Status {
+    fn as_str(self) -> &'static str {
+        match self {
+            Status::Success => "success",
+            Status::Pending => "pending",
+            Status::Interrupted => "interrupted",
+            Status::Failure => "failure",
+        }
+    }
+}
+
+/// 通知的内容
+pub struct Summary {
+    /// 命令名，如 `transfer`、`batch`
+    pub command: &'static str,
+    pub status: Status,
+    /// 已广播交易的哈希
+    pub tx_hashes: Vec<TxHash>,
+    /// 失败原因
+    pub error: Option<String>,
+}
+
+impl Summary {
+    /// 成功或未出错结束的命令
+    pub fn new(command: &'static str, status: Status, tx_hashes: Vec<TxHash>) -> Self {
+        Self {
+            command,
+            status,
+            tx_hashes,
+            error: None,
+        }
+    }
+
+    /// 出错结束的命令
+    pub fn failure(command: &'static str, error: impl ToString) -> Self {
+        Self {
+            command,
+            status: Status::Failure,
+            tx_hashes: Vec::new(),
+            error: Some(error.to_string()),
+        }
+    }
+
+    /// 构造 JSON 负载：带上网络和每笔交易的浏览器链接
+    ///
+    /// `text` 和 `content` 是同一段一行摘要，分别对应 Slack 和 Discord 的 incoming webhook，
+    /// 这样 webhook 地址可以直接填这两个服务的，不需要中间转换。
+    ///
+    /// # 返回
+    /// * `Value` - JSON 负载
+    pub fn payload(&self) -> Value {
+        let network = network::current();
+        let links: Vec<String> = self
+            .tx_hashes
+            .iter()
+            .map(|hash| format!("{}/tx/{:?}", network.explorer_url(), hash))
+            .collect();
+        let mut text = format!("[{}] {}: {}", network.name(), self.command, self.status.as_str());
+        if let Some(error) = &self.error {
+            text.push_str(&format!(" - {}", error));
+        }
+        for link in &links {
+            text.push_str(&format!("\n{}", link));
+        }
+        json!({
+            "command": self.command,
+            "status": self.status.as_str(),
+            "network": network.name(),
+            "chain_id": network.chain_id(),
+            "tx_hashes": self.tx_hashes,
+            "explorer_links": links,
+            "error": self.error,
+            "text": text,
+            "content": text,
+        })
+    }
+}
+
+/// 把结果 POST 到 webhook（尽力而为）
+///
+/// 发送失败只打印警告，不影响命令本身的结果和退出码。
+///
+/// # 参数
+/// * `webhook_url` - webhook 地址
+/// * `summary` - 通知内容
+pub async fn notify(webhook_url: &str, summary: &Summary) {
+    let result = async {
+        rpc::http_client()?
+            .post(webhook_url)
+            .json(&summary.payload())
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok::<_, Box<dyn std::error::Error>>(())
+    }
+    .await;
+    match result {
+        Ok(()) => info!(command = summary.command, "webhook notified"),
+        Err(e) => {
+            warn!(command = summary.command, error = %e, "webhook notification failed");
+            eprintln!("{}", style::warn(tf(Msg::WebhookFailed, &[&e])));
+        }
+    }
+}
+
+/// 设置了 `ARBI_WEBHOOK_URL` 时发送通知，否则什么都不做
+///
+/// # 参数
+/// * `summary` - 通知内容
+pub async fn notify_configured(summary: &Summary) {
+    if let Ok(url) = std::env::var(WEBHOOK_ENV)
+        && !url.trim().is_empty()
+    {
+        notify(url.trim(), summary).await;
+    }
+}
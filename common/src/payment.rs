@@ -0,0 +1,73 @@
+//! EIP-681 支付链接
+
+use ethers::types::{Address, U256};
+use ethers::utils::to_checksum;
+
+/// 构造 EIP-681 支付链接 `ethereum:<地址>@<链 ID>?value=<wei>`
+///
+/// 金额按 EIP-681 的要求以 wei 为单位写成整数；带上链 ID，钱包扫码后会切换到对应网络，
+/// 不会把测试网的收款请求当成主网转账。
+///
+/// # 参数
+/// * `address` - 收款地址
+/// * `chain_id` - 链 ID
+/// * `value` - 金额（wei）
+///
+/// # 返回
+/// * `String` - 支付链接
+pub fn payment_uri(address: &Address, chain_id: u64, value: U256) -> String {
+    format!("ethereum:{}@{}?value={}", to_checksum(address, None), chain_id, value)
+}
+
+/// 二维码里放的内容：没有金额时是 EIP-55 校验格式的地址，有金额时是支付链接
+///
+/// # 参数
+/// * `address` - 地址
+/// * `chain_id` - 链 ID
+/// * `value` - 请求的金额（wei）
+///
+/// # 返回
+/// * `String` - 要编码的文本
+pub fn qr_payload(address: &Address, chain_id: u64, value: Option<U256>) -> String {
+    match value {
+        Some(value) => payment_uri(address, chain_id, value),
+        None => to_checksum(address, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const SAMPLE: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    fn sample() -> Address {
+        Address::from_str(&SAMPLE.to_lowercase()).unwrap()
+    }
+
+    #[test]
+    fn uri_carries_checksum_address_chain_id_and_wei() {
+        // 0.01 ETH 写成 wei 整数，不用小数或科学计数法
+        let value = U256::from(10_000_000_000_000_000u64);
+        assert_eq!(
+            payment_uri(&sample(), 421614, value),
+            format!("ethereum:{}@421614?value=10000000000000000", SAMPLE)
+        );
+        assert_eq!(
+            payment_uri(&sample(), 42161, U256::one()),
+            format!("ethereum:{}@42161?value=1", SAMPLE)
+        );
+        let max = payment_uri(&sample(), 42161, U256::MAX);
+        assert!(max.ends_with(&format!("?value={}", U256::MAX)), "{max}");
+    }
+
+    #[test]
+    fn payload_without_amount_is_the_bare_address() {
+        assert_eq!(qr_payload(&sample(), 421614, None), SAMPLE);
+        assert_eq!(
+            qr_payload(&sample(), 421614, Some(U256::zero())),
+            format!("ethereum:{}@421614?value=0", SAMPLE)
+        );
+    }
+}
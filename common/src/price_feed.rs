@@ -0,0 +1,281 @@
+//! 读取 Chainlink 喂价合约，把 wei 换算成美元参考值
+
+use crate::env;
+use crate::messages::{Msg, tf};
+use crate::network;
+use crate::rpc::RpcProvider;
+use ethers::abi::Token;
+use ethers::providers::Middleware;
+use ethers::types::{Address, I256, TransactionRequest, U256, U512};
+use std::error::Error;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 指定 ETH/USD 喂价合约地址的环境变量，优先于网络的默认配置
+pub const ETH_USD_FEED_ENV: &str = "ARBI_ETH_USD_FEED";
+
+// Chainlink 聚合器代理合约的只读接口
+const AGGREGATOR_ABI: [&str; 2] = [
+    "function decimals() view returns (uint8)",
+    "function latestRoundData() view returns (uint80, int256, uint256, uint256, uint80)",
+];
+
+// 1 ETH = 10^18 wei
+const WEI_DECIMALS: usize = 18;
+
+/// 喂价合约返回的最新价格
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeedPrice {
+    pub feed: Address,
+    /// 价格，`decimals` 位小数（ETH/USD 为 8 位）
+    pub answer: U256,
+    pub decimals: u8,
+    /// 价格最后更新的时间（Unix 秒）
+    pub updated_at: u64,
+}
+
+impl FeedPrice {
+    /// 把 wei 换算成美分，见 [`wei_to_usd_cents`]
+    pub fn usd_cents(&self, wei: U256) -> U512 {
+        wei_to_usd_cents(wei, self.answer, self.decimals)
+    }
+
+    /// 把 `decimals` 位小数的代币数量换算成美分，见 [`to_usd_cents`]
+    pub fn value_cents(&self, amount: U256, decimals: u8) -> U512 {
+        to_usd_cents(amount, decimals, self.answer, self.decimals)
+    }
+
+    /// 1 ETH 的美分价格
+    pub fn eth_cents(&self) -> U512 {
+        self.usd_cents(U256::exp10(WEI_DECIMALS))
+    }
+
+    /// 距离价格最后更新过了多久；更新时间在 `now` 之后（本地时钟偏慢）时为 0
+    pub fn age(&self, now: SystemTime) -> Duration {
+        let now = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        Duration::from_secs(now.saturating_sub(self.updated_at))
+    }
+}
+
+/// 当前网络使用的 ETH/USD 喂价合约
+///
+/// `ARBI_ETH_USD_FEED` 优先；没有设置时使用网络的默认配置（测试网没有配置）。
+///
+/// # 返回
+/// * `Result<Option<Address>, Box<dyn Error>>` - 喂价合约地址，没有配置时为 `None`；
+///   环境变量不是合法地址时返回错误
+pub fn eth_usd_feed() -> Result<Option<Address>, Box<dyn Error>> {
+    let feed = match env::var(ETH_USD_FEED_ENV) {
+        Some(feed) => feed,
+        None => match network::current().eth_usd_feed() {
+            Some(feed) => feed.to_string(),
+            None => return Ok(None),
+        },
+    };
+    let address = feed
+        .parse()
+        .map_err(|_| tf(Msg::InvalidFeedAddress, &[&ETH_USD_FEED_ENV, &feed]))?;
+    Ok(Some(address))
+}
+
+/// 读取喂价合约的最新价格和小数位数
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `feed` - 喂价合约（聚合器代理）地址
+///
+/// # 返回
+/// * `Result<FeedPrice, Box<dyn Error>>` - 最新价格；价格不是正数时返回错误
+pub async fn latest_price(
+    provider: &RpcProvider,
+    feed: Address,
+) -> Result<FeedPrice, Box<dyn Error>> {
+    let abi = ethers::abi::parse_abi(&AGGREGATOR_ABI)?;
+    let call = |name: &str| {
+        let function = abi.function(name).cloned();
+        async move {
+            let function = function?;
+            let tx = TransactionRequest::new().to(feed).data(function.encode_input(&[])?);
+            let output = provider.call(&tx.into(), None).await?;
+            Ok::<_, Box<dyn Error>>(function.decode_output(&output)?)
+        }
+    };
+    let decimals = call("decimals").await?;
+    let round = call("latestRoundData").await?;
+
+    let decimals = match decimals.first() {
+        Some(Token::Uint(decimals)) if *decimals <= U256::from(u8::MAX) => decimals.as_u32() as u8,
+        _ => return Err(tf(Msg::InvalidFeedAnswer, &[&format!("{:?}", feed), &"decimals"]).into()),
+    };
+    // int256 按补码返回，最高位为 1 时是负数
+    let (answer, updated_at) = match (round.get(1), round.get(3)) {
+        (Some(Token::Int(answer)), Some(Token::Uint(updated_at)))
+            if !answer.is_zero() && !answer.bit(255) =>
+        {
+            (*answer, updated_at.low_u64())
+        }
+        (answer, _) => {
+            let answer = match answer {
+                Some(Token::Int(answer)) => I256::from_raw(*answer).to_string(),
+                answer => format!("{:?}", answer),
+            };
+            return Err(tf(Msg::InvalidFeedAnswer, &[&format!("{:?}", feed), &answer]).into());
+        }
+    };
+    Ok(FeedPrice {
+        feed,
+        answer,
+        decimals,
+        updated_at,
+    })
+}
+
+/// 按喂价把 wei 换算成美分，四舍五入
+///
+/// `wei × answer` 最多 256 + 255 位（answer 是正的 int256），用 512 位整数计算，
+/// 不会溢出也不经过浮点数。换算成美分的 ×100 合并进除数，避免乘积超出 512 位。
+///
+/// # 参数
+/// * `wei` - 金额（wei）
+/// * `answer` - 1 ETH 的美元价格，`decimals` 位小数
+/// * `decimals` - 价格的小数位数
+///
+/// # 返回
+/// * `U512` - 美分
+pub fn wei_to_usd_cents(wei: U256, answer: U256, decimals: u8) -> U512 {
+    to_usd_cents(wei, WEI_DECIMALS as u8, answer, decimals)
+}
+
+/// 按喂价把任意代币的数量换算成美分，四舍五入
+///
+/// 与 [`wei_to_usd_cents`] 相同，只是代币的小数位数不固定为 18（如 USDC 为 6）。
+///
+/// # 参数
+/// * `amount` - 数量（最小单位）
+/// * `amount_decimals` - 代币的小数位数
+/// * `answer` - 1 个代币的美元价格，`decimals` 位小数
+/// * `decimals` - 价格的小数位数
+///
+/// # 返回
+/// * `U512` - 美分
+pub fn to_usd_cents(amount: U256, amount_decimals: u8, answer: U256, decimals: u8) -> U512 {
+    let numerator = U512::from(amount) * U512::from(answer);
+    // 最小单位 → 代币除以 10^amount_decimals，价格除以 10^decimals，美元 → 美分乘以 10^2
+    let scale = amount_decimals as usize + decimals as usize;
+    if scale < 2 {
+        return numerator.saturating_mul(U512::exp10(2 - scale));
+    }
+    let denominator = U512::exp10(scale - 2);
+    (numerator + denominator / 2) / denominator
+}
+
+/// 把美分格式化为 `$1234.56`
+pub fn format_cents(cents: U512) -> String {
+    let hundred = U512::from(100);
+    format!("${}.{:02}", cents / hundred, (cents % hundred).as_u32())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_rpc::{MockRpc, Reply};
+    use ethers::utils::{hex, keccak256};
+    use serde_json::json;
+
+    const FEED: Address = Address::repeat_byte(0x5f);
+    const UPDATED_AT: u64 = 1_760_000_000;
+
+    fn word(value: U256) -> String {
+        format!("{:064x}", value)
+    }
+
+    // decimals 返回 `decimals`，latestRoundData 返回补码形式的 `answer`
+    fn feed(decimals: u64, answer: I256) -> MockRpc {
+        MockRpc::start(move |_, params| {
+            let call = &params[0];
+            let input = call["input"].as_str().or(call["data"].as_str()).unwrap();
+            let selector = |signature: &str| hex::encode(&keccak256(signature)[..4]);
+            if input[2..] == selector("decimals()") {
+                Reply::Result(json!(format!("0x{}", word(U256::from(decimals)))))
+            } else if input[2..] == selector("latestRoundData()") {
+                let round = [
+                    word(U256::from(0x1234)),
+                    word(answer.into_raw()),
+                    word(U256::from(UPDATED_AT - 10)),
+                    word(U256::from(UPDATED_AT)),
+                    word(U256::from(0x1234)),
+                ];
+                Reply::Result(json!(format!("0x{}", round.concat())))
+            } else {
+                Reply::Error("execution reverted".to_string())
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn reads_answer_decimals_and_update_time() {
+        let rpc = feed(8, I256::from(300_012_345_678i64));
+        let price = latest_price(&rpc.provider(), FEED).await.unwrap();
+        assert_eq!(
+            price,
+            FeedPrice {
+                feed: FEED,
+                answer: U256::from(300_012_345_678u64),
+                decimals: 8,
+                updated_at: UPDATED_AT,
+            }
+        );
+        assert_eq!(format_cents(price.eth_cents()), "$3000.12");
+    }
+
+    #[tokio::test]
+    async fn negative_or_zero_answers_are_rejected() {
+        for (answer, shown) in [(I256::from(-5), "-5"), (I256::zero(), "0")] {
+            let rpc = feed(8, answer);
+            let error = latest_price(&rpc.provider(), FEED).await.unwrap_err();
+            let expected = tf(Msg::InvalidFeedAnswer, &[&format!("{:?}", FEED), &shown]);
+            assert_eq!(error.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn converts_with_0_8_and_18_price_decimals() {
+        let eth = U256::exp10(18);
+        let half = U256::exp10(17) * 5;
+        // 价格 2500 美元，分别用 0、8、18 位小数表示
+        assert_eq!(wei_to_usd_cents(eth, U256::from(2_500), 0), U512::from(250_000));
+        assert_eq!(wei_to_usd_cents(half, U256::from(2_500) * U256::exp10(8), 8), 125_000.into());
+        assert_eq!(wei_to_usd_cents(eth, U256::from(2_500) * U256::exp10(18), 18), 250_000.into());
+        // 1 wei 不到 1 美分，四舍五入为 0；0.005 美元进位为 1 美分
+        assert_eq!(wei_to_usd_cents(U256::one(), U256::from(2_500), 0), U512::zero());
+        let usdc = to_usd_cents(U256::from(5_000), 6, U256::exp10(8), 8);
+        assert_eq!(usdc, U512::one());
+        // 小数位数加起来不到 2 时直接乘
+        assert_eq!(to_usd_cents(U256::from(3), 0, U256::from(7), 0), U512::from(2_100));
+        assert_eq!(to_usd_cents(U256::from(3), 1, U256::from(7), 0), U512::from(210));
+        // 最大值也不会溢出
+        let max = wei_to_usd_cents(U256::MAX, U256::MAX >> 1, 0);
+        let product = U512::from(U256::MAX) * U512::from(U256::MAX >> 1);
+        assert_eq!(max, (product + U512::exp10(16) / 2) / U512::exp10(16));
+    }
+
+    #[test]
+    fn age_reports_stale_prices_and_ignores_clock_skew() {
+        let price = FeedPrice {
+            feed: FEED,
+            answer: U256::one(),
+            decimals: 8,
+            updated_at: UPDATED_AT,
+        };
+        let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+        assert_eq!(price.age(at(UPDATED_AT + 86_400)), Duration::from_secs(86_400));
+        assert_eq!(price.age(at(UPDATED_AT)), Duration::ZERO);
+        assert_eq!(price.age(at(UPDATED_AT - 30)), Duration::ZERO);
+    }
+
+    #[test]
+    fn formats_cents_with_two_digits() {
+        assert_eq!(format_cents(U512::zero()), "$0.00");
+        assert_eq!(format_cents(U512::from(7)), "$0.07");
+        assert_eq!(format_cents(U512::from(123_456)), "$1234.56");
+    }
+}
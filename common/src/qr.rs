@@ -0,0 +1,111 @@
+//! 在终端里显示二维码
+
+use qrcode::QrCode;
+use qrcode::render::unicode::Dense1x2;
+use std::error::Error;
+
+// 按 POSIX 的优先级查看这几个变量，第一个非空的决定字符集
+const LOCALE_VARS: [&str; 3] = ["LC_ALL", "LC_CTYPE", "LANG"];
+
+/// 终端是否能显示 Unicode 半角方块
+///
+/// Windows 终端默认支持；其他系统看 locale 是否为 UTF-8，没有设置时按不支持处理。
+pub fn unicode_supported() -> bool {
+    cfg!(windows) || utf8_locale(|name| std::env::var(name).ok())
+}
+
+// 按 `LOCALE_VARS` 的顺序找第一个非空的变量，看它是不是 UTF-8
+fn utf8_locale(var: impl Fn(&str) -> Option<String>) -> bool {
+    LOCALE_VARS
+        .iter()
+        .find_map(|name| var(name).filter(|value| !value.is_empty()))
+        .map(|locale| {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+        .unwrap_or(false)
+}
+
+/// 把文本编码成二维码并渲染成终端字符
+///
+/// Unicode 模式用上下半块（▀ ▄ █）把两行模块合进一行字符；ASCII 模式每个模块占两个字符宽，
+/// 让图形接近正方形。终端多是深色背景，这里把浅色模块画成实心，手机扫描时黑白才不会颠倒。
+///
+/// # 参数
+/// * `text` - 要编码的内容
+/// * `unicode` - 是否使用 Unicode 半角方块
+///
+/// # 返回
+/// * `Result<String, Box<dyn Error>>` - 多行字符串；内容太长放不进二维码时返回错误
+pub fn render(text: &str, unicode: bool) -> Result<String, Box<dyn Error>> {
+    let code = QrCode::new(text.as_bytes())?;
+    let rendered = if unicode {
+        code.render::<Dense1x2>()
+            .dark_color(Dense1x2::Light)
+            .light_color(Dense1x2::Dark)
+            .build()
+    } else {
+        code.render::<char>()
+            .module_dimensions(2, 1)
+            .dark_color(' ')
+            .light_color('#')
+            .build()
+    };
+    Ok(rendered)
+}
+
+/// 按终端能力渲染二维码并打印，下面附上原文方便核对
+///
+/// # 参数
+/// * `text` - 要编码的内容
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+pub fn print(text: &str) -> Result<(), Box<dyn Error>> {
+    println!("{}", render(text, unicode_supported())?);
+    println!("{}", text);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locale(vars: &[(&str, &str)]) -> bool {
+        utf8_locale(|name| {
+            let value = vars.iter().find(|(key, _)| *key == name);
+            value.map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn first_non_empty_locale_variable_decides() {
+        assert!(locale(&[("LANG", "zh_CN.UTF-8")]));
+        assert!(locale(&[("LC_CTYPE", "en_US.utf8"), ("LANG", "C")]));
+        assert!(!locale(&[("LC_ALL", "C"), ("LANG", "en_US.UTF-8")]));
+        // 空值跳过，继续看下一个
+        assert!(locale(&[("LC_ALL", ""), ("LANG", "en_US.UTF-8")]));
+        // 没有设置时退回 ASCII
+        assert!(!locale(&[]));
+    }
+
+    #[test]
+    fn ascii_fallback_uses_only_plain_characters() {
+        let text = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let ascii = render(text, false).unwrap();
+        assert!(ascii.chars().all(|c| matches!(c, ' ' | '#' | '\n')), "{ascii}");
+        // 每个模块两个字符宽，每行宽度一致
+        let widths: Vec<_> = ascii.lines().map(str::len).collect();
+        assert!(widths.iter().all(|width| *width == widths[0] && width % 2 == 0));
+        assert_eq!(widths[0] / 2, ascii.lines().count());
+
+        let unicode = render(text, true).unwrap();
+        assert!(unicode.contains('\u{2588}'));
+        assert!(!unicode.contains('#'));
+    }
+
+    #[test]
+    fn text_too_long_for_a_code_is_an_error() {
+        assert!(render(&"a".repeat(8_000), false).is_err());
+    }
+}
@@ -0,0 +1,232 @@
+//! 进程内共享的请求限速：每个 RPC 端点一个令牌桶
+//!
+//! 批量查询、分段扫描日志这些并发功能各自都很克制，同时运行时加起来仍可能超过公共节点的限额。
+//! 所有请求（[`crate::rpc::RpcClient`]、批量请求和区块浏览器 API）发出前都先经过这里；
+//! 每个端点单独计算，换到备用节点时不受主节点额度的影响。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use crate::logging::RPC_TARGET;
+use crate::metrics;
+
+/// 设置每秒最多请求数的环境变量，`--max-rps` 优先
+pub const MAX_RPS_ENV: &str = "ARBI_MAX_RPS";
+
+static LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+
+// 一个端点的令牌桶：令牌按速率匀速补充，最多攒到突发上限
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// 按端点划分令牌桶的限速器
+///
+/// 用 tokio 的时钟计时，测试里可以用 `tokio::time::pause()` 控制时间。
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    // 每个桶放在 tokio 的 Mutex 里：它按先来先得的顺序唤醒等待者，
+    // 持锁等待令牌，后来的请求只能排在后面，不会插队
+    buckets: Mutex<HashMap<String, Arc<tokio::sync::Mutex<Bucket>>>>,
+}
+
+impl RateLimiter {
+    /// 创建限速器
+    ///
+    /// # 参数
+    /// * `rate` - 每个端点每秒最多请求数，必须大于 0
+    /// * `burst` - 空闲后允许连续发出的请求数（桶的容量），至少为 1
+    pub fn new(rate: f64, burst: u32) -> Self {
+        Self {
+            rate,
+            burst: f64::from(burst.max(1)),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn bucket(&self, endpoint: &str) -> Arc<tokio::sync::Mutex<Bucket>> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets.entry(endpoint.to_string()).or_insert_with(|| {
+            Arc::new(tokio::sync::Mutex::new(Bucket {
+                tokens: self.burst,
+                updated: Instant::now(),
+            }))
+        });
+        Arc::clone(bucket)
+    }
+
+    /// 等到该端点有可用的令牌后取走一个
+    ///
+    /// # 参数
+    /// * `endpoint` - 端点标识（见 [`endpoint`]）
+    ///
+    /// # 返回
+    /// * `Duration` - 为等令牌而等待的时间，不需要等待时为 0
+    pub async fn acquire(&self, endpoint: &str) -> Duration {
+        let bucket = self.bucket(endpoint);
+        let mut bucket = bucket.lock().await;
+        let refill = |bucket: &mut Bucket| {
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+            bucket.updated = now;
+        };
+        refill(&mut bucket);
+        let mut waited = Duration::ZERO;
+        if bucket.tokens < 1.0 {
+            waited = Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate);
+            tokio::time::sleep(waited).await;
+            refill(&mut bucket);
+        }
+        // 浮点误差可能让余量略小于 0，下次补充时会自然抵消
+        bucket.tokens -= 1.0;
+        waited
+    }
+}
+
+/// 端点标识：URL 的 scheme、主机和端口
+///
+/// 不含路径，同一节点的不同路径共用额度，路径里的 API key 也不会出现在日志和指标里。
+///
+/// # 参数
+/// * `url` - 节点地址
+///
+/// # 返回
+/// * `String` - 端点标识；无法解析时为原字符串
+pub fn endpoint(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(url) => url.origin().ascii_serialization(),
+        Err(_) => url.to_string(),
+    }
+}
+
+/// 解析 `--max-rps`：大于 0 的数，可以是小数（如 0.5 表示每 2 秒一个请求）
+///
+/// 在 clap 解析参数时调用，这时还没有初始化输出语言，错误信息和 clap 自己的一样用英文。
+pub fn parse_rps(value: &str) -> Result<f64, String> {
+    match value.trim().parse::<f64>() {
+        Ok(rps) if rps.is_finite() && rps > 0.0 => Ok(rps),
+        _ => Err("expected a number of requests per second above 0".to_string()),
+    }
+}
+
+/// 按选项初始化全局限速器；`--max-rps` 优先，其次 `ARBI_MAX_RPS`，都没有时不限速
+///
+/// 突发上限为 1 秒的请求数（至少 1 个）。
+///
+/// # 参数
+/// * `cli` - 命令行传入的每秒最多请求数
+///
+/// # 返回
+/// * `Option<f64>` - 生效的每秒最多请求数
+pub fn init(cli: Option<f64>) -> Option<f64> {
+    let rate = cli.or_else(|| {
+        let value = std::env::var(MAX_RPS_ENV).ok()?;
+        parse_rps(&value)
+            .inspect_err(|_| warn!(value, env = MAX_RPS_ENV, "invalid rate limit, ignoring"))
+            .ok()
+    })?;
+    let limiter = LIMITER.get_or_init(|| RateLimiter::new(rate, rate.ceil() as u32));
+    Some(limiter.rate)
+}
+
+/// 发出请求前调用：配置了限速时等到该端点有额度，并记录到指标
+///
+/// # 参数
+/// * `endpoint` - 端点标识（见 [`endpoint`]）
+pub async fn acquire(endpoint: &str) {
+    let Some(limiter) = LIMITER.get() else {
+        return;
+    };
+    let waited = limiter.acquire(endpoint).await;
+    if !waited.is_zero() {
+        let waited_ms = waited.as_millis() as u64;
+        debug!(target: RPC_TARGET, endpoint, waited_ms, "rate limited");
+    }
+    metrics::record_rate_limit(endpoint, waited);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NODE: &str = "https://node.example";
+
+    #[tokio::test(start_paused = true)]
+    async fn burst_then_paced_at_rate() {
+        let limiter = RateLimiter::new(2.0, 2);
+        let start = Instant::now();
+        assert_eq!(limiter.acquire(NODE).await, Duration::ZERO);
+        assert_eq!(limiter.acquire(NODE).await, Duration::ZERO);
+        // 桶空了，之后每 0.5 秒一个
+        assert_eq!(limiter.acquire(NODE).await, Duration::from_millis(500));
+        assert_eq!(limiter.acquire(NODE).await, Duration::from_millis(500));
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_time_refills_up_to_burst() {
+        let limiter = RateLimiter::new(1.0, 3);
+        for _ in 0..3 {
+            limiter.acquire(NODE).await;
+        }
+        // 空闲 10 秒也只攒回 3 个令牌
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        for _ in 0..3 {
+            assert_eq!(limiter.acquire(NODE).await, Duration::ZERO);
+        }
+        assert_eq!(limiter.acquire(NODE).await, Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn endpoints_have_separate_buckets() {
+        let limiter = RateLimiter::new(1.0, 1);
+        assert_eq!(limiter.acquire(NODE).await, Duration::ZERO);
+        assert_eq!(limiter.acquire("https://backup.example").await, Duration::ZERO);
+        assert_eq!(limiter.acquire(NODE).await, Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_callers_are_spaced_out() {
+        let limiter = Arc::new(RateLimiter::new(10.0, 1));
+        let start = Instant::now();
+        let tasks: Vec<_> = (0..5)
+            .map(|_| {
+                let limiter = Arc::clone(&limiter);
+                tokio::spawn(async move {
+                    limiter.acquire(NODE).await;
+                    start.elapsed()
+                })
+            })
+            .collect();
+        let mut finished = Vec::new();
+        for task in tasks {
+            finished.push(task.await.unwrap());
+        }
+        finished.sort();
+        let expected: Vec<_> = (0..5).map(|i| Duration::from_millis(100 * i)).collect();
+        assert_eq!(finished, expected);
+    }
+
+    #[test]
+    fn endpoint_drops_path_and_query() {
+        let with_key = "https://arb-mainnet.example/v2/secret-key?x=1";
+        assert_eq!(endpoint(with_key), "https://arb-mainnet.example");
+        assert_eq!(endpoint("http://127.0.0.1:8545/"), "http://127.0.0.1:8545");
+        assert_eq!(endpoint("not a url"), "not a url");
+    }
+
+    #[test]
+    fn parse_rps_requires_positive_number() {
+        assert_eq!(parse_rps("5"), Ok(5.0));
+        assert_eq!(parse_rps(" 0.5 "), Ok(0.5));
+        for value in ["0", "-1", "abc", "inf", "NaN", ""] {
+            assert!(parse_rps(value).is_err(), "{value}");
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! 离线解码已签名的原始交易
+
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Bytes, NameOrAddress, Signature, U256};
+use ethers::utils::rlp::Rlp;
+use ethers::utils::{format_units, hex, id};
+use std::error::Error;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::messages::{Msg, t, tf};
+use crate::style;
+use crate::units;
+
+// 常见函数的签名，解码时按选择器给 calldata 加上名称
+const KNOWN_FUNCTIONS: [&str; 12] = [
+    "transfer(address,uint256)",
+    "approve(address,uint256)",
+    "transferFrom(address,address,uint256)",
+    "increaseAllowance(address,uint256)",
+    "decreaseAllowance(address,uint256)",
+    "deposit()",
+    "withdraw(uint256)",
+    "safeTransferFrom(address,address,uint256)",
+    "setApprovalForAll(address,bool)",
+    "withdrawEth(address)",
+    "sendTxToL1(address,bytes)",
+    "depositEth()",
+];
+
+// EIP-2718 类型交易的类型字节；RLP 列表的首字节不小于 0xc0，据此区分 legacy 交易
+const EIP2930_TYPE: u8 = 0x01;
+const EIP1559_TYPE: u8 = 0x02;
+const RLP_LIST_PREFIX: u8 = 0xc0;
+
+/// 解码后的已签名交易
+pub struct DecodedTx {
+    pub tx: TypedTransaction,
+    pub signature: Signature,
+    /// 从签名恢复出的发送方
+    pub from: Address,
+}
+
+/// 读取要解码的交易：参数是已存在的文件时读文件内容，否则当作十六进制本身
+///
+/// # 参数
+/// * `arg` - 十六进制或文件路径
+///
+/// # 返回
+/// * `Result<String, Box<dyn Error>>` - 去掉首尾空白的十六进制
+pub fn load(arg: &str) -> Result<String, Box<dyn Error>> {
+    let path = Path::new(arg);
+    if path.is_file() {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| tf(Msg::DecodeReadFailed, &[&path.display(), &e]))?;
+        return Ok(content.trim().to_string());
+    }
+    Ok(arg.trim().to_string())
+}
+
+/// 离线解码已签名交易的十六进制（`eth_sendRawTransaction` 的参数），不连接节点
+///
+/// 以 `0x01` / `0x02` 开头的是 EIP-2718 类型交易（EIP-2930 / EIP-1559），后面跟 RLP 列表；
+/// 直接是 RLP 列表的是 legacy 交易，链 ID 编码在签名的 `v` 里（EIP-155）。
+/// 发送方不在交易里，要用签名对交易哈希做 ecrecover 才能得到。
+/// 出错时分别报告是十六进制、交易类型、RLP 还是签名恢复这一步失败。
+///
+/// # 参数
+/// * `raw` - 十六进制，0x 前缀可省略
+///
+/// # 返回
+/// * `Result<DecodedTx, Box<dyn Error>>` - 交易、签名和发送方
+pub fn decode_raw(raw: &str) -> Result<DecodedTx, Box<dyn Error>> {
+    let raw = raw.trim();
+    let bytes = Bytes::from_str(raw).map_err(|_| tf(Msg::DecodeInvalidHex, &[&raw]))?;
+    match bytes.first() {
+        None => return Err(t(Msg::DecodeEmpty).into()),
+        Some(&EIP2930_TYPE | &EIP1559_TYPE) => {}
+        Some(&first) if first >= RLP_LIST_PREFIX => {}
+        Some(first) => {
+            return Err(tf(Msg::DecodeUnknownType, &[&format!("0x{:02x}", first)]).into());
+        }
+    }
+    let (tx, signature) = TypedTransaction::decode_signed(&Rlp::new(&bytes))
+        .map_err(|e| tf(Msg::DecodeInvalidRlp, &[&e]))?;
+    let from = signature
+        .recover(tx.sighash())
+        .map_err(|e| tf(Msg::DecodeRecoverFailed, &[&e]))?;
+    Ok(DecodedTx {
+        tx,
+        signature,
+        from,
+    })
+}
+
+/// 按 calldata 的前 4 字节查找常见函数的签名
+///
+/// # 参数
+/// * `data` - calldata
+///
+/// # 返回
+/// * `Option<&'static str>` - 函数签名；不足 4 字节或不认识时为 `None`
+pub fn selector_label(data: &[u8]) -> Option<&'static str> {
+    let selector = data.get(..4)?;
+    KNOWN_FUNCTIONS
+        .iter()
+        .find(|signature| &id(signature)[..] == selector)
+        .copied()
+}
+
+fn gwei(value: Option<&U256>) -> Result<String, Box<dyn Error>> {
+    Ok(match value {
+        Some(value) => format!("{} Gwei", format_units(*value, "gwei")?),
+        None => "-".to_string(),
+    })
+}
+
+impl DecodedTx {
+    /// 逐项打印交易字段
+    pub fn print(&self) -> Result<(), Box<dyn Error>> {
+        let tx = &self.tx;
+        let kind = match tx {
+            TypedTransaction::Legacy(_) => "legacy (0x00)",
+            TypedTransaction::Eip2930(_) => "EIP-2930 (0x01)",
+            TypedTransaction::Eip1559(_) => "EIP-1559 (0x02)",
+        };
+        let to = match tx.to() {
+            Some(NameOrAddress::Address(to)) => format!("{:?}", to),
+            Some(NameOrAddress::Name(name)) => name.clone(),
+            None => t(Msg::DecodeContractCreation).to_string(),
+        };
+        let chain_id = tx
+            .chain_id()
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| t(Msg::DecodeNoChainId).to_string());
+        let value = tx.value().copied().unwrap_or_default();
+        let data = tx.data().cloned().unwrap_or_default();
+
+        println!("{}", tf(Msg::DecodeType, &[&kind]));
+        println!("{}", tf(Msg::DecodeFrom, &[&style::addr(format!("{:?}", self.from))]));
+        println!("{}", tf(Msg::DecodeTo, &[&to]));
+        let wei = units::group_digits(value);
+        println!("{}", tf(Msg::DecodeValue, &[&format_units(value, "ether")?, &wei]));
+        println!("{}", tf(Msg::DecodeNonce, &[&tx.nonce().copied().unwrap_or_default()]));
+        println!("{}", tf(Msg::DecodeGas, &[&tx.gas().copied().unwrap_or_default()]));
+        match tx {
+            TypedTransaction::Eip1559(inner) => {
+                let max_fee = gwei(inner.max_fee_per_gas.as_ref())?;
+                let priority = gwei(inner.max_priority_fee_per_gas.as_ref())?;
+                println!("{}", tf(Msg::DecodeMaxFee, &[&max_fee]));
+                println!("{}", tf(Msg::DecodePriorityFee, &[&priority]));
+            }
+            _ => println!("{}", tf(Msg::DecodeGasPrice, &[&gwei(tx.gas_price().as_ref())?])),
+        }
+        if let Some(access_list) = tx.access_list() {
+            println!("{}", tf(Msg::DecodeAccessList, &[&access_list.0.len()]));
+        }
+        println!("{}", tf(Msg::DecodeChainId, &[&chain_id]));
+        println!("{}", tf(Msg::DecodeData, &[&data.len(), &data]));
+        if data.len() >= 4 {
+            let label = selector_label(&data)
+                .map(str::to_string)
+                .unwrap_or_else(|| t(Msg::DecodeUnknownSelector).to_string());
+            let selector = hex::encode_prefixed(&data[..4]);
+            println!("{}", tf(Msg::DecodeSelector, &[&selector, &label]));
+        }
+        let signature = &self.signature;
+        let (r, s) = (format!("{:#x}", signature.r), format!("{:#x}", signature.s));
+        println!("{}", tf(Msg::DecodeSignature, &[&signature.v, &r, &s]));
+        println!("{}", tf(Msg::DecodeHash, &[&format!("{:?}", tx.hash(signature))]));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::utils::keccak256;
+
+    // EIP-155 规范里的示例交易：私钥 0x4646…46，链 ID 1
+    const LEGACY_TX: &str = concat!(
+        "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000",
+        "8025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f76",
+        "1aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+    );
+
+    // 同一私钥在 Arbitrum Sepolia 上签名的 EIP-1559 交易：调用 transfer(0x3535…35, 100)
+    const EIP1559_TX: &str = concat!(
+        "02f8ae83066eee07808405f5e10082ea6094757575757575757575757575757575757575757580b844a9",
+        "059cbb000000000000000000000000353535353535353535353535353535353535353500000000000000",
+        "00000000000000000000000000000000000000000000000064c080a0095d987d77939cce2452c671798f",
+        "9bd5a8d2a03ededd0c1aed3c42e94b042194a048d54552c4710fe199e21b767f2b52e92ac8eea06f9727",
+        "657320a7744e8046bb",
+    );
+
+    const SIGNER: &str = "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f";
+
+    fn hash_of(raw: &str) -> ethers::types::H256 {
+        keccak256(Bytes::from_str(raw).unwrap()).into()
+    }
+
+    #[test]
+    fn decodes_legacy_tx() {
+        let decoded = decode_raw(LEGACY_TX).unwrap();
+        assert!(matches!(decoded.tx, TypedTransaction::Legacy(_)));
+        assert_eq!(decoded.from, Address::from_str(SIGNER).unwrap());
+        assert_eq!(decoded.tx.to_addr(), Some(&Address::repeat_byte(0x35)));
+        assert_eq!(decoded.tx.value(), Some(&U256::exp10(18)));
+        assert_eq!(decoded.tx.nonce(), Some(&U256::from(9)));
+        assert_eq!(decoded.tx.gas(), Some(&U256::from(21_000)));
+        assert_eq!(decoded.tx.gas_price(), Some(U256::from(20_000_000_000u64)));
+        assert_eq!(decoded.tx.chain_id(), Some(1.into()));
+        assert_eq!(decoded.signature.v, 37);
+        assert_eq!(decoded.tx.hash(&decoded.signature), hash_of(LEGACY_TX));
+    }
+
+    #[test]
+    fn decodes_eip1559_tx() {
+        // 不带 0x 前缀，首尾有空白
+        let decoded = decode_raw(&format!(" {EIP1559_TX}\n")).unwrap();
+        let TypedTransaction::Eip1559(inner) = &decoded.tx else {
+            panic!("不是 EIP-1559 交易");
+        };
+        assert_eq!(decoded.from, Address::from_str(SIGNER).unwrap());
+        assert_eq!(inner.chain_id, Some(421_614.into()));
+        assert_eq!(inner.nonce, Some(7.into()));
+        assert_eq!(inner.gas, Some(60_000.into()));
+        assert_eq!(inner.max_fee_per_gas, Some(100_000_000.into()));
+        assert_eq!(inner.max_priority_fee_per_gas, Some(U256::zero()));
+        assert_eq!(decoded.tx.to_addr(), Some(&Address::repeat_byte(0x75)));
+        let data = decoded.tx.data().unwrap();
+        assert_eq!(selector_label(data), Some("transfer(address,uint256)"));
+        assert_eq!(decoded.tx.hash(&decoded.signature), hash_of(EIP1559_TX));
+    }
+
+    #[test]
+    fn reports_which_step_failed() {
+        let error = |raw: &str| decode_raw(raw).err().unwrap().to_string();
+        assert_eq!(error(""), t(Msg::DecodeEmpty));
+        assert_eq!(error("0xzz"), tf(Msg::DecodeInvalidHex, &[&"0xzz"]));
+        assert_eq!(error("0x05c0"), tf(Msg::DecodeUnknownType, &[&"0x05"]));
+
+        let truncated = &EIP1559_TX[..EIP1559_TX.len() - 2];
+        let rlp_prefix = tf(Msg::DecodeInvalidRlp, &[&""]);
+        assert!(error(truncated).starts_with(rlp_prefix.trim_end()), "{}", error(truncated));
+    }
+
+    #[test]
+    fn altered_tx_recovers_a_different_sender() {
+        // 改动 nonce 后签名不再对应原交易，恢复出的是别的地址
+        let altered = LEGACY_TX.replacen("f86c09", "f86c0a", 1);
+        let decoded = decode_raw(&altered).unwrap();
+        assert_ne!(decoded.from, Address::from_str(SIGNER).unwrap());
+    }
+
+    #[test]
+    fn selector_label_needs_four_known_bytes() {
+        assert_eq!(selector_label(&[0xa9, 0x05, 0x9c]), None);
+        assert_eq!(selector_label(&[0xde, 0xad, 0xbe, 0xef]), None);
+        let approve = [0x09, 0x5e, 0xa7, 0xb3, 0x00];
+        assert_eq!(selector_label(&approve), Some("approve(address,uint256)"));
+    }
+}
@@ -0,0 +1,201 @@
+//! 失败后按指数退避重试，供轮询类命令、区块浏览器 API 和批量转账共用
+
+use std::error::Error;
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+use tracing::debug;
+
+use crate::metrics;
+
+/// 重试策略：第 n 次重试前等待 `base_delay × 2^(n-1)`，不超过 `max_delay`
+#[derive(Clone, Copy, Debug)]
+pub struct Backoff {
+    /// 最多重试的次数（不含第一次尝试）
+    pub retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Backoff {
+    /// 第 `attempt` 次重试（从 1 开始）前等待的时间
+    ///
+    /// # 参数
+    /// * `attempt` - 重试序号
+    ///
+    /// # 返回
+    /// * `Duration` - 等待时间
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// 一批操作共用的重试次数上限
+///
+/// 每个操作各自重试 3 次，批量发送 100 笔时遇到节点抖动，重试加退避可能拖上几分钟。
+/// 整批共用一个预算后，重试总数不超过上限；用完后剩下的失败直接返回，不再重试。
+#[derive(Debug)]
+pub struct RetryBudget {
+    limit: u32,
+    used: AtomicU32,
+}
+
+impl RetryBudget {
+    /// 最多允许 `limit` 次重试
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            used: AtomicU32::new(0),
+        }
+    }
+
+    /// 不限制总数，只受每个操作自己的 `Backoff::retries` 限制
+    pub fn unlimited() -> Self {
+        Self::new(u32::MAX)
+    }
+
+    /// 占用一次重试
+    ///
+    /// # 返回
+    /// * `bool` - 预算还有剩余时为 `true`；已用完时为 `false`，不占用
+    pub fn try_acquire(&self) -> bool {
+        self.used
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| {
+                (used < self.limit).then_some(used + 1)
+            })
+            .is_ok()
+    }
+
+    /// 已经使用的重试次数
+    pub fn used(&self) -> u32 {
+        self.used.load(Ordering::SeqCst)
+    }
+
+    /// 重试次数上限
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    /// 预算是否已经用完
+    pub fn is_exhausted(&self) -> bool {
+        self.used() >= self.limit
+    }
+}
+
+/// 执行 `op`，失败时按 `backoff` 等待后重试，重试用完后返回最后一次的错误
+///
+/// # 参数
+/// * `backoff` - 重试策略
+/// * `op` - 每次调用都会重新创建的操作
+///
+/// # 返回
+/// * `Result<T, Box<dyn Error>>` - 第一次成功的结果，或最后一次的错误
+pub async fn with_backoff<T, F, Fut>(backoff: Backoff, op: F) -> Result<T, Box<dyn Error>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Box<dyn Error>>>,
+{
+    with_budget(backoff, &RetryBudget::unlimited(), op).await
+}
+
+/// 同 [`with_backoff`]，每次重试还要从共用的 `budget` 中占用一次，预算用完后不再重试
+///
+/// # 参数
+/// * `backoff` - 单个操作的重试策略
+/// * `budget` - 整批操作共用的重试预算
+/// * `op` - 每次调用都会重新创建的操作
+///
+/// # 返回
+/// * `Result<T, Box<dyn Error>>` - 第一次成功的结果，或最后一次的错误
+pub async fn with_budget<T, F, Fut>(
+    backoff: Backoff,
+    budget: &RetryBudget,
+    mut op: F,
+) -> Result<T, Box<dyn Error>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Box<dyn Error>>>,
+{
+    let mut attempt = 0;
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < backoff.retries && budget.try_acquire() => {
+                attempt += 1;
+                let delay = backoff.delay(attempt);
+                debug!(attempt, delay_ms = delay.as_millis() as u64, error = %e, "retrying");
+                metrics::record_retry();
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => {
+                if attempt < backoff.retries {
+                    debug!(used = budget.used(), error = %e, "retry budget exhausted");
+                }
+                return Err(e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn backoff(retries: u32) -> Backoff {
+        Backoff {
+            retries,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(4),
+        }
+    }
+
+    #[test]
+    fn delay_doubles_up_to_max() {
+        let delays: Vec<_> = (1..=5).map(|attempt| backoff(5).delay(attempt).as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 4, 4]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn budget_caps_total_retry_attempts() {
+        let budget = RetryBudget::new(4);
+        let attempts = Cell::new(0);
+        // 每个操作最多重试 3 次，5 个一直失败的操作共用 4 次重试
+        for _ in 0..5 {
+            let result: Result<(), _> = with_budget(backoff(3), &budget, || {
+                attempts.set(attempts.get() + 1);
+                async { Err("offline".into()) }
+            })
+            .await;
+            assert!(result.is_err());
+        }
+        assert_eq!(attempts.get(), 5 + 4);
+        assert_eq!(budget.used(), 4);
+        assert!(budget.is_exhausted());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stops_retrying_after_success() {
+        let budget = RetryBudget::unlimited();
+        let attempts = Cell::new(0);
+        let result = with_budget(backoff(3), &budget, || {
+            attempts.set(attempts.get() + 1);
+            let attempt = attempts.get();
+            async move { if attempt < 3 { Err("offline".into()) } else { Ok(attempt) } }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(budget.used(), 2);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::messages::{Msg, t, tf};
+use ethers::abi::{ParamType, Token, decode};
+use ethers::providers::{ProviderError, RpcError};
+use ethers::utils::hex;
+
+// `Error(string)` 的函数选择器
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+// `Panic(uint256)` 的函数选择器
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// 把 revert 返回数据解码成可读的原因
+///
+/// # 参数
+/// * `data` - 节点返回的 revert 数据
+///
+/// # 返回
+/// * `String` - `Error(string)` 的文本、`Panic(uint256)` 的错误码，或自定义错误的原始十六进制
+pub fn decode_revert_data(data: &[u8]) -> String {
+    if data.is_empty() {
+        return t(Msg::RevertNoReason).to_string();
+    }
+
+    if data.len() >= 4
+        && data[..4] == ERROR_STRING_SELECTOR
+        && let Ok(tokens) = decode(&[ParamType::String], &data[4..])
+        && let Some(Token::String(reason)) = tokens.into_iter().next()
+    {
+        return reason;
+    }
+
+    if data.len() >= 4
+        && data[..4] == PANIC_SELECTOR
+        && let Ok(tokens) = decode(&[ParamType::Uint(256)], &data[4..])
+        && let Some(Token::Uint(code)) = tokens.into_iter().next()
+    {
+        return format!("Panic(0x{:x})", code);
+    }
+
+    tf(Msg::RevertCustomError, &[&hex::encode(data)])
+}
+
+/// 从调用失败的错误中提取节点返回的原因
+///
+/// # 参数
+/// * `err` - `eth_call` / `eth_estimateGas` 返回的错误
+///
+/// # 返回
+/// * `Option<String>` - 节点拒绝执行时的原因；网络错误等非执行失败返回 `None`
+pub fn call_error_reason(err: &ProviderError) -> Option<String> {
+    let response = err.as_error_response()?;
+
+    match response.as_revert_data() {
+        Some(data) if !data.is_empty() => Some(decode_revert_data(&data)),
+        _ => Some(response.message.clone()),
+    }
+}
@@ -0,0 +1,356 @@
+use async_trait::async_trait;
+use ethers::providers::{Http, HttpClientError, JsonRpcClient, Provider};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::error::Error;
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+use tracing::{Level, debug, enabled, trace, warn};
+
+use crate::logging::RPC_TARGET;
+use crate::metrics;
+use crate::rate_limit;
+
+/// 覆盖 HTTP 请求超时（秒）的环境变量
+pub const TIMEOUT_ENV: &str = "ARBI_RPC_TIMEOUT";
+
+/// 默认的 HTTP 请求超时
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+// 替换敏感内容时使用的占位符
+pub const REDACTED: &str = "<redacted>";
+
+// 参数或结果中包含签名数据的方法，-vv 时整体脱敏
+const SENSITIVE_METHODS: [&str; 3] = ["eth_sendRawTransaction", "eth_signTransaction", "eth_sign"];
+
+// personal_* 方法的参数里常有不带字段名的密码（如 personal_sendTransaction 的第二个参数），整体脱敏
+const SENSITIVE_METHOD_PREFIX: &str = "personal_";
+
+// 对象里名字包含这些词的字段一律脱敏
+const SENSITIVE_KEYS: [&str; 5] = ["key", "secret", "mnemonic", "password", "seed"];
+
+// 节点已裁剪历史状态时常见的报错片段（geth / Nitro / Erigon 和各家 RPC 服务商）
+const PRUNED_STATE_ERRORS: [&str; 5] = [
+    "missing trie node",
+    "header not found",
+    "state is not available",
+    "historical state",
+    "pruned",
+];
+
+/// 所有 level 共用的 Provider 类型
+pub type RpcProvider = Provider<RpcClient>;
+
+/// 在 HTTP 传输外面加一层请求日志和限速的 JSON-RPC 客户端
+///
+/// -v 时每个请求记录方法、参数 / 响应大小和耗时；-vv 时再记录完整的请求和响应，
+/// 其中签名交易、私钥之类的敏感内容会先用 `redact` 脱敏。配置了 `--max-rps` 时，
+/// 每个请求发出前先等该节点的限速额度（见 [`rate_limit`]）。
+#[derive(Clone, Debug)]
+pub struct RpcClient {
+    inner: Http,
+    endpoint: String,
+}
+
+impl RpcClient {
+    /// 包装一个 HTTP 传输
+    pub fn new(inner: Http) -> Self {
+        let endpoint = rate_limit::endpoint(inner.url().as_str());
+        Self { inner, endpoint }
+    }
+}
+
+/// HTTP 请求超时：`ARBI_RPC_TIMEOUT`（秒，大于 0）优先，未设置或无效时为 30 秒
+pub fn request_timeout() -> Duration {
+    let Ok(value) = std::env::var(TIMEOUT_ENV) else {
+        return DEFAULT_TIMEOUT;
+    };
+    match value.trim().parse::<f64>() {
+        Ok(secs) if secs.is_finite() && secs > 0.0 => Duration::from_secs_f64(secs),
+        _ => {
+            warn!(value, env = TIMEOUT_ENV, "invalid timeout, using the default");
+            DEFAULT_TIMEOUT
+        }
+    }
+}
+
+/// 创建带请求超时的 HTTP 客户端
+///
+/// reqwest 默认没有超时，公共节点卡住时请求会一直挂着；设置超时后卡住的节点会很快报错，
+/// 交给上层的重试 / 换节点逻辑处理。连接 RPC 节点、批量请求和浏览器 API 都用它。
+///
+/// # 返回
+/// * `Result<reqwest::Client, Box<dyn Error>>` - 客户端
+pub fn http_client() -> Result<reqwest::Client, Box<dyn Error>> {
+    client_with_timeout(request_timeout())
+}
+
+fn client_with_timeout(timeout: Duration) -> Result<reqwest::Client, Box<dyn Error>> {
+    Ok(reqwest::Client::builder().timeout(timeout).build()?)
+}
+
+/// 连接 RPC 节点，请求超时见 `request_timeout`
+///
+/// # 参数
+/// * `rpc_url` - RPC 节点地址
+///
+/// # 返回
+/// * `Result<RpcProvider, Box<dyn Error>>` - Provider
+pub fn connect(rpc_url: &str) -> Result<RpcProvider, Box<dyn Error>> {
+    connect_with_timeout(rpc_url, request_timeout())
+}
+
+fn connect_with_timeout(rpc_url: &str, timeout: Duration) -> Result<RpcProvider, Box<dyn Error>> {
+    let url = reqwest::Url::parse(rpc_url)?;
+    let http = Http::new_with_client(url, client_with_timeout(timeout)?);
+    Ok(Provider::new(RpcClient::new(http)))
+}
+
+/// 对 JSON-RPC 参数或结果脱敏
+///
+/// 敏感方法（签名相关的 eth_* 和所有 personal_*）的整个值替换成占位符；
+/// 其他方法只替换名字像私钥 / 助记词 / 密码的字段，嵌套在对象和数组里的也会替换。
+///
+/// # 参数
+/// * `method` - JSON-RPC 方法名
+/// * `value` - 参数或结果
+///
+/// # 返回
+/// * `Value` - 脱敏后的值
+pub fn redact(method: &str, value: &Value) -> Value {
+    if SENSITIVE_METHODS.contains(&method) || method.starts_with(SENSITIVE_METHOD_PREFIX) {
+        return Value::String(REDACTED.to_string());
+    }
+    redact_keys(value)
+}
+
+/// 判断读取历史区块失败是否因为节点不保存那个区块的状态（非归档节点）
+///
+/// # 参数
+/// * `error` - 错误信息
+///
+/// # 返回
+/// * `bool` - 看起来是状态被裁剪时为 `true`
+pub fn is_pruned_state_error(error: &str) -> bool {
+    let error = error.to_lowercase();
+    PRUNED_STATE_ERRORS.iter().any(|pattern| error.contains(pattern))
+}
+
+/// 递归替换名字敏感的字段
+fn redact_keys(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    let lower = k.to_ascii_lowercase();
+                    if SENSITIVE_KEYS.iter().any(|s| lower.contains(s)) {
+                        (k.clone(), Value::String(REDACTED.to_string()))
+                    } else {
+                        (k.clone(), redact_keys(v))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact_keys).collect()),
+        other => other.clone(),
+    }
+}
+
+#[async_trait]
+impl JsonRpcClient for RpcClient {
+    type Error = HttpClientError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, HttpClientError>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let params = serde_json::to_value(&params).map_err(|err| HttpClientError::SerdeJson {
+            err,
+            text: format!("{:?}", params),
+        })?;
+        let params_bytes = params.to_string().len();
+        if enabled!(target: RPC_TARGET, Level::TRACE) {
+            trace!(target: RPC_TARGET, method, params = %redact(method, &params), "rpc request");
+        }
+
+        rate_limit::acquire(&self.endpoint).await;
+        metrics::record_rpc_request(method);
+        let started = Instant::now();
+        let result: Result<Value, HttpClientError> = self.inner.request(method, params).await;
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+
+        let value = match result {
+            Ok(value) => value,
+            Err(e) => {
+                metrics::record_rpc_error(method);
+                debug!(target: RPC_TARGET, method, params_bytes, elapsed_ms, error = %e, "rpc error");
+                return Err(e);
+            }
+        };
+
+        debug!(
+            target: RPC_TARGET,
+            method,
+            params_bytes,
+            response_bytes = value.to_string().len(),
+            elapsed_ms,
+            "rpc call"
+        );
+        if enabled!(target: RPC_TARGET, Level::TRACE) {
+            trace!(target: RPC_TARGET, method, result = %redact(method, &value), "rpc response");
+        }
+
+        serde_json::from_value(value.clone()).map_err(|err| HttpClientError::SerdeJson {
+            err,
+            text: value.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging::{LogFormat, Verbosity, default_filter};
+    use crate::mock_rpc::{MockRpc, Reply};
+    use ethers::providers::Middleware;
+    use ethers::types::Bytes;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::EnvFilter;
+
+    // anvil 的第一个测试账户私钥
+    const KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    // EIP-155 规范里的示例签名交易
+    const RAW_TX: &str = concat!(
+        "0xf86c808504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000",
+        "8025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f76",
+        "1aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+    );
+    const PASSWORD: &str = "correct horse battery staple";
+    const MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+    #[test]
+    fn signing_methods_are_redacted_entirely() {
+        for method in ["eth_sendRawTransaction", "eth_signTransaction", "eth_sign"] {
+            assert_eq!(redact(method, &json!([RAW_TX])), json!(REDACTED));
+        }
+    }
+
+    #[test]
+    fn every_personal_method_is_redacted_entirely() {
+        let params = json!([{"from": "0x01", "value": "0x1"}, PASSWORD]);
+        for method in [
+            "personal_sign",
+            "personal_unlockAccount",
+            "personal_importRawKey",
+            "personal_sendTransaction",
+            "personal_signTransaction",
+        ] {
+            assert_eq!(redact(method, &params), json!(REDACTED), "{}", method);
+        }
+    }
+
+    #[test]
+    fn nested_secret_fields_are_redacted() {
+        let params = json!([{
+            "account": {"privateKey": KEY, "address": "0xf39f"},
+            "wallets": [{"mnemonic": MNEMONIC}, {"PASSWORD": PASSWORD}],
+            "seedPhrase": MNEMONIC,
+            "apiSecret": "s3cr3t",
+        }]);
+        let redacted = redact("wallet_import", &params);
+        assert_eq!(
+            redacted,
+            json!([{
+                "account": {"privateKey": REDACTED, "address": "0xf39f"},
+                "wallets": [{"mnemonic": REDACTED}, {"PASSWORD": REDACTED}],
+                "seedPhrase": REDACTED,
+                "apiSecret": REDACTED,
+            }])
+        );
+    }
+
+    #[test]
+    fn ordinary_calls_are_left_alone() {
+        let to = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let params = json!([{"to": to, "data": "0x70a0"}]);
+        assert_eq!(redact("eth_call", &params), params);
+        assert_eq!(redact("eth_blockNumber", &json!("0x10")), json!("0x10"));
+    }
+
+    // 把日志写进内存，供测试检查
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn trace_output_never_contains_key_material() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_env_filter(EnvFilter::new(default_filter(Verbosity::Trace, LogFormat::Human)))
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        // 节点把收到的参数原样带回结果里，请求和响应两个方向都要脱敏
+        let rpc = MockRpc::start(|method, params| match method {
+            "eth_sendRawTransaction" => Reply::Result(json!(format!("0x{}", "ab".repeat(32)))),
+            _ => Reply::Result(params.clone()),
+        });
+        let provider = rpc.provider();
+        let raw: Bytes = RAW_TX.parse().unwrap();
+        provider.send_raw_transaction(raw).await.unwrap();
+        let _: Value = provider.request("personal_importRawKey", [KEY, PASSWORD]).await.unwrap();
+        let _: Value = provider
+            .request("wallet_import", [json!({"privateKey": KEY, "mnemonic": MNEMONIC})])
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("rpc request"), "没有捕获到 -vv 日志：{}", output);
+        assert!(output.contains("eth_sendRawTransaction"));
+        assert!(output.contains(REDACTED));
+        for secret in [KEY, &RAW_TX[2..], PASSWORD, MNEMONIC] {
+            assert!(!output.contains(secret), "日志中出现了敏感内容：{}", output);
+        }
+        // 签名交易里 r / s 的片段也不能出现
+        assert!(!output.contains("28ef61340bd939bc"));
+    }
+
+    #[tokio::test]
+    async fn stalled_node_fails_within_the_timeout() {
+        // 接受连接但从不响应的节点
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let mut held = Vec::new();
+            for socket in listener.incoming() {
+                held.push(socket);
+            }
+        });
+
+        let timeout = Duration::from_millis(300);
+        let provider = connect_with_timeout(&format!("http://{}", address), timeout).unwrap();
+        let started = Instant::now();
+        let result = provider.get_block_number().await;
+        let elapsed = started.elapsed();
+
+        assert!(result.is_err(), "卡住的节点应该超时报错");
+        assert!(elapsed >= timeout, "没等到超时就返回了：{:?}", elapsed);
+        assert!(elapsed < timeout * 5, "超时没有生效：{:?}", elapsed);
+    }
+}
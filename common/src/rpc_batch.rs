@@ -0,0 +1,60 @@
+use crate::messages::{Msg, t, tf};
+use crate::rate_limit;
+use crate::rpc;
+use serde_json::{Value, json};
+use std::error::Error;
+
+/// 通过一次 HTTP 请求发送 JSON-RPC 批量调用
+///
+/// ethers 的 `Http` 传输层不支持 JSON-RPC batch，这里直接用 reqwest 发送请求数组，
+/// 再按 `id` 把响应还原成与输入一致的顺序。
+///
+/// # 参数
+/// * `rpc_url` - RPC 节点地址
+/// * `calls` - `(方法名, 参数数组)` 列表
+///
+/// # 返回
+/// * `Result<Vec<Value>, Box<dyn Error>>` - 与输入顺序一致的 `result` 字段
+pub async fn batch_call(
+    rpc_url: &str,
+    calls: &[(&str, Value)],
+) -> Result<Vec<Value>, Box<dyn Error>> {
+    if calls.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let body: Vec<Value> = calls
+        .iter()
+        .enumerate()
+        .map(|(id, (method, params))| {
+            json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+        })
+        .collect();
+
+    // 整个批量请求是一次 HTTP 请求，占一个限速额度
+    rate_limit::acquire(&rate_limit::endpoint(rpc_url)).await;
+    let responses: Vec<Value> = rpc::http_client()?
+        .post(rpc_url)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let mut results = vec![Value::Null; calls.len()];
+    for response in responses {
+        let id = response["id"]
+            .as_u64()
+            .ok_or(t(Msg::BatchMissingId))? as usize;
+        if let Some(error) = response.get("error") {
+            return Err(tf(Msg::BatchItemFailed, &[&id, error]).into());
+        }
+        let slot = results
+            .get_mut(id)
+            .ok_or_else(|| tf(Msg::BatchIdOutOfRange, &[&id]))?;
+        *slot = response["result"].clone();
+    }
+
+    Ok(results)
+}
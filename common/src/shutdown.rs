@@ -0,0 +1,150 @@
+use crate::messages::{Msg, t};
+use crate::style;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Notify;
+
+// 被 Ctrl-C 中断时使用的退出码（与 shell 中 128 + SIGINT 的约定一致）
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// 进程级的关闭信号，长时间运行的循环在每一步之间检查它
+#[derive(Clone, Default)]
+pub struct Shutdown {
+    requested: Arc<AtomicBool>,
+    notify: Arc<Notify>,
+}
+
+impl Shutdown {
+    /// 创建一个不监听信号的关闭句柄，可以手动调用 `trigger`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建关闭句柄并安装 Ctrl-C 处理
+    ///
+    /// 第一次 Ctrl-C 只设置关闭标记，让当前操作完成后再退出；
+    /// 第二次 Ctrl-C 立即强制退出进程。
+    ///
+    /// # 返回
+    /// * `Shutdown` - 可在各个任务之间克隆共享的句柄
+    pub fn install() -> Self {
+        let shutdown = Self::new();
+        let handle = shutdown.clone();
+
+        tokio::spawn(async move {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                if handle.is_requested() {
+                    eprintln!("\n{}", style::warn(t(Msg::CtrlCForce)));
+                    std::process::exit(INTERRUPTED_EXIT_CODE);
+                }
+                eprintln!("\n{}", style::warn(t(Msg::CtrlCFirst)));
+                handle.trigger();
+            }
+        });
+
+        shutdown
+    }
+
+    /// 请求关闭
+    pub fn trigger(&self) {
+        self.requested.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    /// 是否已经请求关闭
+    pub fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+
+    /// 等待关闭请求，可与其他 future 一起放进 `tokio::select!`
+    pub async fn wait(&self) {
+        let notified = self.notify.notified();
+        if self.is_requested() {
+            return;
+        }
+        notified.await;
+    }
+}
+
+/// 操作因 Ctrl-C 中断时返回的错误，`main` 据此使用 `INTERRUPTED_EXIT_CODE` 退出
+#[derive(Debug)]
+pub struct Interrupted;
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", t(Msg::Interrupted))
+    }
+}
+
+impl Error for Interrupted {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::time::{Instant, sleep};
+
+    // 模拟一个分步执行的长操作：每步 10 秒，步与步之间检查关闭标记，
+    // 每一步也可以被关闭信号立即打断
+    async fn long_operation(shutdown: Shutdown, steps: u32) -> Result<u32, Box<dyn Error>> {
+        for _ in 0..steps {
+            if shutdown.is_requested() {
+                return Err(Interrupted.into());
+            }
+            tokio::select! {
+                _ = sleep(Duration::from_secs(10)) => {}
+                _ = shutdown.wait() => return Err(Interrupted.into()),
+            }
+        }
+        Ok(steps)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn trigger_cancels_a_long_operation_midway() {
+        let shutdown = Shutdown::new();
+        let started = Instant::now();
+        let interrupt = async {
+            sleep(Duration::from_secs(25)).await;
+            shutdown.trigger();
+        };
+        let (result, ()) = tokio::join!(long_operation(shutdown.clone(), 100), interrupt);
+        let error = result.unwrap_err();
+
+        assert!(error.downcast_ref::<Interrupted>().is_some());
+        assert_eq!(error.to_string(), t(Msg::Interrupted));
+        // 在第三步中途停下，而不是跑完 1000 秒
+        assert_eq!(started.elapsed(), Duration::from_secs(25));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn untouched_operation_runs_to_completion() {
+        let shutdown = Shutdown::new();
+        let started = Instant::now();
+        assert_eq!(long_operation(shutdown.clone(), 3).await.unwrap(), 3);
+        assert_eq!(started.elapsed(), Duration::from_secs(30));
+        assert!(!shutdown.is_requested());
+    }
+
+    #[tokio::test]
+    async fn wait_returns_at_once_after_trigger_and_wakes_every_clone() {
+        let shutdown = Shutdown::new();
+        let waiters: Vec<_> = (0..3)
+            .map(|_| {
+                let shutdown = shutdown.clone();
+                tokio::spawn(async move { shutdown.wait().await })
+            })
+            .collect();
+        tokio::task::yield_now().await;
+        shutdown.trigger();
+        for waiter in waiters {
+            tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+        }
+        // 已经请求过关闭时，新的等待不会挂住
+        let late = shutdown.clone();
+        tokio::time::timeout(Duration::from_secs(1), late.wait()).await.unwrap();
+        assert!(late.is_requested());
+        assert!(long_operation(late, 1).await.is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use clap::ValueEnum;
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// 何时使用彩色输出
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// stdout 是终端且没有设置 NO_COLOR 时启用（默认）
+    Auto,
+    /// 总是启用
+    Always,
+    /// 总是关闭
+    Never,
+}
+
+/// 初始化彩色输出：`--color` 优先；`auto` 时遵守 NO_COLOR 环境变量并检测 stdout 是否为终端
+///
+/// # 参数
+/// * `cli` - 命令行传入的选项
+///
+/// # 返回
+/// * `bool` - 是否启用彩色输出
+pub fn init(cli: Option<ColorChoice>) -> bool {
+    let enabled = match cli.unwrap_or(ColorChoice::Auto) {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            // 按 https://no-color.org 的约定，NO_COLOR 为空字符串时不生效
+            let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+            !no_color && std::io::stdout().is_terminal()
+        }
+    };
+    *ENABLED.get_or_init(|| enabled)
+}
+
+/// 是否启用彩色输出（未初始化时关闭）
+pub fn enabled() -> bool {
+    ENABLED.get().copied().unwrap_or(false)
+}
+
+/// 给文字套上 ANSI 颜色；关闭彩色输出时原样返回
+fn paint(code: &str, text: impl Display) -> String {
+    styled(enabled(), code, text)
+}
+
+// `color` 为 `false` 时原样返回，方便测试两种输出
+fn styled(color: bool, code: &str, text: impl Display) -> String {
+    if color {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// 成功信息（绿色）
+pub fn ok(text: impl Display) -> String {
+    paint("32", text)
+}
+
+/// 警告信息（黄色）
+pub fn warn(text: impl Display) -> String {
+    paint("33", text)
+}
+
+/// 错误信息（红色）
+pub fn err(text: impl Display) -> String {
+    paint("31", text)
+}
+
+/// 地址和交易哈希（暗青色）
+pub fn addr(text: impl Display) -> String {
+    paint("2;36", text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "✓ 0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    #[test]
+    fn plain_fallback_has_no_escape_codes() {
+        // 测试里没有调用 init，彩色输出保持关闭
+        assert!(!enabled());
+        for styled in [ok(TEXT), warn(TEXT), err(TEXT), addr(TEXT)] {
+            assert_eq!(styled, TEXT);
+            assert!(!styled.contains('\x1b'));
+        }
+    }
+
+    #[test]
+    fn colored_output_wraps_text_and_resets() {
+        let colored = styled(true, "32", TEXT);
+        assert_eq!(colored, format!("\x1b[32m{}\x1b[0m", TEXT));
+        assert_eq!(styled(false, "32", TEXT), TEXT);
+    }
+}
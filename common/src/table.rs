@@ -0,0 +1,267 @@
+//! 表格输出的 CSV / JSON 导出：所有输出表格的命令共用同一套转义、表头和写入方式
+
+use std::borrow::Cow;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// UTF-8 BOM：Excel 靠它识别 UTF-8，否则中文会显示成乱码
+const UTF8_BOM: &str = "\u{feff}";
+
+// 需要加引号的字符：分隔符、引号和换行
+const SPECIAL_CHARS: [char; 4] = [',', '"', '\n', '\r'];
+
+/// 按 RFC 4180 转义一个字段
+///
+/// 含逗号、双引号或换行时整个字段加双引号，字段里的双引号写成两个；其他字段原样输出。
+///
+/// # 参数
+/// * `field` - 字段内容
+///
+/// # 返回
+/// * `Cow<str>` - 转义后的字段
+pub fn escape(field: &str) -> Cow<'_, str> {
+    match field.contains(SPECIAL_CHARS) {
+        true => Cow::Owned(format!("\"{}\"", field.replace('"', "\"\""))),
+        false => Cow::Borrowed(field),
+    }
+}
+
+/// 一张表：表头加若干行，所有值都是字符串
+///
+/// 金额类的列同时放原始值（如 wei 整数）和格式化后的值，方便表格软件计算。
+pub struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// 用表头创建空表
+    ///
+    /// # 参数
+    /// * `header` - 列名
+    pub fn new<I, S>(header: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            header: header.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// 追加一行；列数应与表头一致
+    ///
+    /// # 参数
+    /// * `row` - 各列的值
+    pub fn push<I, S>(&mut self, row: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        let row: Vec<String> = row.into_iter().map(|value| value.to_string()).collect();
+        debug_assert_eq!(row.len(), self.header.len(), "CSV 行的列数与表头不一致");
+        self.rows.push(row);
+    }
+
+    /// 数据行数（不含表头）
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// 是否没有数据行
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// 生成 CSV 文本：第一行是表头，每行以 `\n` 结尾
+    ///
+    /// # 参数
+    /// * `bom` - 是否在开头加 UTF-8 BOM
+    ///
+    /// # 返回
+    /// * `String` - CSV 文本
+    pub fn to_csv(&self, bom: bool) -> String {
+        let mut csv = String::new();
+        if bom {
+            csv.push_str(UTF8_BOM);
+        }
+        for row in std::iter::once(&self.header).chain(&self.rows) {
+            let fields: Vec<Cow<str>> = row.iter().map(|field| escape(field)).collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// 原子地写入 CSV 文件（见 [`write_atomic`]）
+    ///
+    /// # 参数
+    /// * `path` - 输出路径
+    /// * `bom` - 是否在开头加 UTF-8 BOM
+    ///
+    /// # 返回
+    /// * `io::Result<()>` - 写入结果
+    pub fn write_csv(&self, path: &Path, bom: bool) -> io::Result<()> {
+        write_atomic(path, self.to_csv(bom).as_bytes())
+    }
+
+    /// 生成 JSON 文本：每行一个对象，键为表头，按表头顺序排列，值都是字符串
+    ///
+    /// # 返回
+    /// * `String` - JSON 数组，每个对象占一行
+    pub fn to_json(&self) -> String {
+        if self.rows.is_empty() {
+            return "[]\n".to_string();
+        }
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let fields: Vec<String> = self
+                    .header
+                    .iter()
+                    .zip(row)
+                    .map(|(key, value)| {
+                        format!("{}: {}", Value::from(key.as_str()), Value::from(value.as_str()))
+                    })
+                    .collect();
+                format!("  {{{}}}", fields.join(", "))
+            })
+            .collect();
+        format!("[\n{}\n]\n", rows.join(",\n"))
+    }
+
+    /// 原子地写入 JSON 文件（见 [`write_atomic`]）
+    ///
+    /// # 参数
+    /// * `path` - 输出路径
+    ///
+    /// # 返回
+    /// * `io::Result<()>` - 写入结果
+    pub fn write_json(&self, path: &Path) -> io::Result<()> {
+        write_atomic(path, self.to_json().as_bytes())
+    }
+}
+
+// 与目标文件同目录的临时文件，保证 rename 不跨文件系统
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()))
+}
+
+/// 原子地写入文件：先写同目录下的临时文件并刷到磁盘，再改名为目标文件
+///
+/// 中途崩溃或出错时目标文件保持原样（不存在或旧内容），不会留下写了一半的文件；
+/// 出错时删除临时文件。
+///
+/// # 参数
+/// * `path` - 目标路径
+/// * `contents` - 文件内容
+///
+/// # 返回
+/// * `io::Result<()>` - 写入结果
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp = temp_path(path);
+    let result = File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 每个测试一个独立目录
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("arbi-table-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn escapes_special_characters() {
+        for (field, expected) in [
+            ("plain", "plain"),
+            ("", ""),
+            ("a,b", "\"a,b\""),
+            ("say \"hi\"", "\"say \"\"hi\"\"\""),
+            ("line\nbreak", "\"line\nbreak\""),
+            ("crlf\r\n", "\"crlf\r\n\""),
+            ("中文", "中文"),
+        ] {
+            assert_eq!(escape(field), expected, "{:?}", field);
+        }
+        assert!(matches!(escape("plain"), Cow::Borrowed(_)));
+    }
+
+    fn sample() -> Table {
+        let mut table = Table::new(["address", "note"]);
+        table.push(["0x01", "a,b"]);
+        table.push(["0x02", "备注"]);
+        table
+    }
+
+    #[test]
+    fn csv_with_and_without_bom() {
+        let body = "address,note\n0x01,\"a,b\"\n0x02,备注\n";
+        assert_eq!(sample().to_csv(false), body);
+        assert_eq!(sample().to_csv(true), format!("\u{feff}{}", body));
+        assert_eq!(Table::new(["a"]).to_csv(false), "a\n");
+    }
+
+    #[test]
+    fn json_rows_follow_header_order() {
+        let expected = "[\n  {\"address\": \"0x01\", \"note\": \"a,b\"},\n  \
+                        {\"address\": \"0x02\", \"note\": \"备注\"}\n]\n";
+        assert_eq!(sample().to_json(), expected);
+        assert_eq!(Table::new(["a"]).to_json(), "[]\n");
+    }
+
+    #[test]
+    fn write_atomic_replaces_the_target() {
+        let dir = scratch("replace");
+        let path = dir.join("out.csv");
+        fs::write(&path, "old").unwrap();
+        write_atomic(&path, b"new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        // 只剩目标文件，没有遗留的临时文件
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failed_write_leaves_the_target_untouched() {
+        let dir = scratch("create-fails");
+        let path = dir.join("out.csv");
+        fs::write(&path, "old").unwrap();
+        // 临时文件的位置被目录占住，创建临时文件失败
+        fs::create_dir(temp_path(&path)).unwrap();
+        assert!(write_atomic(&path, b"new").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failed_rename_removes_the_temp_file() {
+        let dir = scratch("rename-fails");
+        // 目标是非空目录，临时文件写好后改名失败
+        let path = dir.join("out.csv");
+        fs::create_dir(&path).unwrap();
+        fs::write(path.join("keep"), "old").unwrap();
+        assert!(write_atomic(&path, b"new").is_err());
+        assert_eq!(fs::read_to_string(path.join("keep")).unwrap(), "old");
+        assert!(!temp_path(&path).exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
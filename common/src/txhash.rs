@@ -0,0 +1,122 @@
+//! 交易哈希的解析
+
+use ethers::types::TxHash;
+use ethers::utils::hex;
+use std::error::Error;
+use std::fmt;
+
+use crate::messages::{Msg, tf};
+
+// 交易哈希的十六进制字符数（32 字节）
+const TX_HASH_HEX_LEN: usize = 64;
+
+/// 交易哈希格式不对
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvalidHash {
+    /// 去掉 0x 前缀后不是 64 个字符
+    Length { input: String, len: usize },
+    /// 含有非十六进制字符
+    NotHex { input: String },
+}
+
+impl fmt::Display for InvalidHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            InvalidHash::Length { input, len } => {
+                tf(Msg::InvalidHashLength, &[input, &TX_HASH_HEX_LEN, len])
+            }
+            InvalidHash::NotHex { input } => tf(Msg::InvalidHashChars, &[input]),
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+impl Error for InvalidHash {}
+
+/// 解析交易哈希：允许首尾空白，`0x` / `0X` 前缀可有可无
+///
+/// 也用于 retryable ticket ID 等同样是 32 字节哈希的输入。
+///
+/// # 参数
+/// * `s` - 哈希字符串
+///
+/// # 返回
+/// * `Result<TxHash, InvalidHash>` - 交易哈希；去掉前缀后不是 64 个十六进制字符时返回错误
+pub fn parse_tx_hash(s: &str) -> Result<TxHash, InvalidHash> {
+    let input = s.trim();
+    let hex = input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix("0X"))
+        .unwrap_or(input);
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(InvalidHash::NotHex {
+            input: input.to_string(),
+        });
+    }
+    if hex.len() != TX_HASH_HEX_LEN {
+        return Err(InvalidHash::Length {
+            input: input.to_string(),
+            len: hex.len(),
+        });
+    }
+    let bytes = hex::decode(hex).map_err(|_| InvalidHash::NotHex {
+        input: input.to_string(),
+    })?;
+    Ok(TxHash::from_slice(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060";
+
+    fn expected() -> TxHash {
+        TxHash::from_slice(&hex::decode(HASH).unwrap())
+    }
+
+    #[test]
+    fn accepts_hash_with_or_without_prefix() {
+        assert_eq!(parse_tx_hash(HASH), Ok(expected()));
+        assert_eq!(parse_tx_hash(&format!("0x{HASH}")), Ok(expected()));
+        assert_eq!(parse_tx_hash(&format!("0X{HASH}")), Ok(expected()));
+        assert_eq!(parse_tx_hash(&format!("  0x{}\n", HASH.to_uppercase())), Ok(expected()));
+    }
+
+    #[test]
+    fn rejects_wrong_length() {
+        let short = format!("0x{}", &HASH[..63]);
+        assert_eq!(
+            parse_tx_hash(&short),
+            Err(InvalidHash::Length { input: short.clone(), len: 63 })
+        );
+        let long = format!("{HASH}00");
+        assert_eq!(parse_tx_hash(&long), Err(InvalidHash::Length { input: long, len: 66 }));
+        assert_eq!(
+            parse_tx_hash("0x"),
+            Err(InvalidHash::Length { input: "0x".to_string(), len: 0 })
+        );
+        // 只剥掉一个前缀
+        let doubled = format!("0x0x{}", &HASH[..62]);
+        assert_eq!(parse_tx_hash(&doubled), Err(InvalidHash::NotHex { input: doubled }));
+    }
+
+    #[test]
+    fn rejects_non_hex_characters() {
+        let with_g = format!("0x{}g", &HASH[..63]);
+        assert_eq!(parse_tx_hash(&with_g), Err(InvalidHash::NotHex { input: with_g }));
+        let with_space = format!("{} {}", &HASH[..32], &HASH[32..]);
+        assert_eq!(parse_tx_hash(&with_space), Err(InvalidHash::NotHex { input: with_space }));
+        // 非 ASCII 字符不会被当成长度问题
+        let wide = format!("{}é", &HASH[..63]);
+        assert_eq!(parse_tx_hash(&wide), Err(InvalidHash::NotHex { input: wide }));
+    }
+
+    #[test]
+    fn error_messages_name_the_input() {
+        let error = parse_tx_hash("0x1234").unwrap_err();
+        assert_eq!(error.to_string(), tf(Msg::InvalidHashLength, &[&"0x1234", &64, &4]));
+        let error = parse_tx_hash("xyz").unwrap_err();
+        assert_eq!(error.to_string(), tf(Msg::InvalidHashChars, &[&"xyz"]));
+    }
+}
@@ -0,0 +1,440 @@
+use crate::messages::{Msg, tf};
+use ethers::types::U256;
+use ethers::utils::{ConversionError, Units, format_units};
+use std::error::Error;
+use std::sync::OnceLock;
+
+/// 余额默认显示的小数位数
+pub const DEFAULT_PRECISION: usize = 6;
+
+static PRECISION: OnceLock<usize> = OnceLock::new();
+
+/// ETH 美元价格的环境变量，没有设置时不显示美元金额
+pub const ETH_USD_ENV: &str = "ARBI_ETH_USD";
+
+// 不足 1 美元的金额保留的有效数字位数
+const USD_SIGNIFICANT_DIGITS: i32 = 4;
+
+/// 去掉金额中的分组分隔符：`1_000` → `1000`，`1,000.5` → `1000.5`
+///
+/// `_` 只能出现在两个数字之间；`,` 只能用在整数部分，并且必须是每 3 位一组的千分位。
+/// 位置不对的分隔符（`1,00`、`_1`、`1.0,5`）视为无效输入，避免把抄错的金额悄悄解析成别的数。
+/// 去掉分隔符后只能剩下数字和最多一个小数点（负数、`1.2.3` 都会被拒绝）。
+///
+/// # 参数
+/// * `amount` - 用户输入的金额
+///
+/// # 返回
+/// * `Result<String, String>` - 去掉分隔符后的金额；分隔符位置不对时返回错误信息
+pub fn strip_grouping(amount: &str) -> Result<String, String> {
+    let amount = amount.trim();
+    if amount.starts_with('-') {
+        return Err(tf(Msg::NegativeAmount, &[&amount]));
+    }
+    let invalid = || tf(Msg::InvalidAmount, &[&amount]);
+
+    let chars: Vec<char> = amount.chars().collect();
+    for (i, c) in chars.iter().enumerate() {
+        if *c == '_' {
+            let between_digits = i > 0
+                && chars[i - 1].is_ascii_digit()
+                && chars.get(i + 1).is_some_and(|next| next.is_ascii_digit());
+            if !between_digits {
+                return Err(invalid());
+            }
+        }
+    }
+    let amount = amount.replace('_', "");
+
+    let (integer, fraction) = match amount.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (amount.as_str(), None),
+    };
+    if fraction.is_some_and(|fraction| fraction.contains(',')) {
+        return Err(invalid());
+    }
+
+    let integer = if integer.contains(',') {
+        let mut groups = integer.split(',');
+        let first = groups.next().unwrap_or_default();
+        let first_ok = (1..=3).contains(&first.len());
+        if !first_ok || !groups.all(|group| group.len() == 3) {
+            return Err(invalid());
+        }
+        integer.replace(',', "")
+    } else {
+        integer.to_string()
+    };
+
+    let digits_only = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    let well_formed = match fraction {
+        Some(fraction) => {
+            digits_only(&integer)
+                && digits_only(fraction)
+                && !(integer.is_empty() && fraction.is_empty())
+        }
+        None => digits_only(&integer) && !integer.is_empty(),
+    };
+    if !well_formed {
+        return Err(invalid());
+    }
+
+    Ok(match fraction {
+        Some(fraction) => format!("{}.{}", integer, fraction),
+        None => integer,
+    })
+}
+
+/// 解析带单位的金额，允许 `_` 和 `,` 分组分隔符
+///
+/// 不用 `parse_units`：它会悄悄截掉超出单位精度的小数，结果超出 U256 时还会 panic。
+/// 这里超出精度的部分不全是 0 时返回错误（例如 `1.5` wei、`0.0000000001` gwei），
+/// 再把整数和小数部分拼成最小单位的数字串解析，超出 U256 时同样返回错误。
+///
+/// # 参数
+/// * `amount` - 用户输入的金额，如 `1,000.5`
+/// * `units` - 单位名（`"ether"`、`"gwei"`）或小数位数
+///
+/// # 返回
+/// * `Result<U256, Box<dyn Error>>` - 最小单位的数值；格式无效、为负数或精度超出单位时返回错误
+pub fn parse_amount<K>(amount: &str, units: K) -> Result<U256, Box<dyn Error>>
+where
+    K: TryInto<Units, Error = ConversionError> + Copy,
+{
+    let stripped = strip_grouping(amount)?;
+    let decimals = units.try_into()?.as_num() as usize;
+    let (integer, fraction) = stripped.split_once('.').unwrap_or((&stripped, ""));
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals {
+        return Err(tf(Msg::ExcessPrecision, &[&amount.trim(), &decimals]).into());
+    }
+    let digits = format!("{}{}{}", integer, fraction, "0".repeat(decimals - fraction.len()));
+    U256::from_dec_str(&digits)
+        .map_err(|e| tf(Msg::InvalidAmountReason, &[&amount.trim(), &e]).into())
+}
+
+/// 解析 ETH 金额，允许 `_` 和 `,` 分组分隔符
+///
+/// # 参数
+/// * `amount` - 用户输入的金额（ETH）
+///
+/// # 返回
+/// * `Result<U256, Box<dyn Error>>` - 金额（wei）
+pub fn parse_ether_amount(amount: &str) -> Result<U256, Box<dyn Error>> {
+    parse_amount(amount, "ether")
+}
+
+/// 把最小单位的数值精确格式化成十进制，去掉小数末尾的 0（`1500000`、6 位 → `1.5`）
+///
+/// # 参数
+/// * `value` - 最小单位的数值
+/// * `decimals` - 小数位数（0 到 77）
+///
+/// # 返回
+/// * `Result<String, Box<dyn Error>>` - 十进制字符串；小数位数超出范围时返回错误
+pub fn format_exact(value: U256, decimals: u32) -> Result<String, Box<dyn Error>> {
+    let formatted = format_units(value, decimals)?;
+    Ok(match formatted.split_once('.') {
+        Some(_) => formatted.trim_end_matches('0').trim_end_matches('.').to_string(),
+        None => formatted,
+    })
+}
+
+/// 在两种小数位数的单位之间换算，全程用 U256 整数运算，结果是精确值
+///
+/// 先按来源单位解析成最小单位，再按目标单位格式化：`convert("0.005", 18, 0)` 为
+/// `"5000000000000000"`，`convert("2500000", 9, 18)` 为 `"0.0025"`。
+///
+/// # 参数
+/// * `amount` - 金额，允许 `_` 和 `,` 分组分隔符
+/// * `from_decimals` - 来源单位的小数位数
+/// * `to_decimals` - 目标单位的小数位数
+///
+/// # 返回
+/// * `Result<String, Box<dyn Error>>` - 换算结果；负数或精度超出来源单位时返回错误
+pub fn convert(
+    amount: &str,
+    from_decimals: u32,
+    to_decimals: u32,
+) -> Result<String, Box<dyn Error>> {
+    format_exact(parse_amount(amount, from_decimals)?, to_decimals)
+}
+
+/// 设置余额显示的小数位数（命令行 `--precision`）
+pub fn init_precision(precision: usize) {
+    PRECISION.get_or_init(|| precision);
+}
+
+/// 把 wei 四舍五入到指定的 ETH 小数位数，并去掉末尾的 0
+///
+/// 先在 wei 上按 `10^(18 - precision)` 取整再格式化，不会出现浮点误差：
+/// `0.0012345678` 保留 6 位为 `0.001235`，`1.5000001` 为 `1.5`，`2.0000001` 为 `2`。
+///
+/// # 参数
+/// * `wei` - 金额（wei）
+/// * `precision` - 保留的小数位数，不小于 18 时不取整
+///
+/// # 返回
+/// * `String` - 取整后的 ETH 金额
+pub fn round_ether(wei: U256, precision: usize) -> String {
+    let rounded = match 18usize.checked_sub(precision) {
+        Some(shift) if shift > 0 => {
+            let unit = U256::exp10(shift);
+            wei.saturating_add(unit / 2) / unit * unit
+        }
+        _ => wei,
+    };
+    match format_units(rounded, "ether") {
+        Ok(formatted) => formatted.trim_end_matches('0').trim_end_matches('.').to_string(),
+        Err(_) => rounded.to_string(),
+    }
+}
+
+/// 给整数加上千位分隔符，如 `1,000,000,000`，方便阅读很长的 wei 数值
+///
+/// # 参数
+/// * `n` - 整数
+///
+/// # 返回
+/// * `String` - 每 3 位一组、用 `,` 分隔的十进制数
+pub fn group_digits(n: U256) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// 按 `--precision` 格式化余额（默认保留 6 位小数），日志里另外记录精确的 wei
+///
+/// # 参数
+/// * `wei` - 余额（wei）
+///
+/// # 返回
+/// * `String` - 取整后的 ETH 金额
+pub fn format_balance(wei: U256) -> String {
+    round_ether(wei, PRECISION.get().copied().unwrap_or(DEFAULT_PRECISION))
+}
+
+/// ETH 的美元价格：命令行优先，其次 `ARBI_ETH_USD`
+///
+/// 价格只用来换算显示，不参与任何金额计算，所以用 f64 足够。
+///
+/// # 参数
+/// * `cli` - 命令行传入的价格
+///
+/// # 返回
+/// * `Result<Option<f64>, Box<dyn Error>>` - 都没有配置时为 `None`；不是正数时返回错误
+pub fn eth_usd_rate(cli: Option<&str>) -> Result<Option<f64>, Box<dyn Error>> {
+    let configured = match cli {
+        Some(rate) => rate.to_string(),
+        None => match std::env::var(ETH_USD_ENV) {
+            Ok(rate) if !rate.trim().is_empty() => rate,
+            _ => return Ok(None),
+        },
+    };
+    match strip_grouping(configured.trim()).map(|rate| rate.parse::<f64>()) {
+        Ok(Ok(rate)) if rate.is_finite() && rate > 0.0 => Ok(Some(rate)),
+        _ => Err(tf(Msg::InvalidEthUsd, &[&configured.trim()]).into()),
+    }
+}
+
+/// 按 ETH 的美元价格把 wei 换算成美元
+///
+/// 1 美元以上保留 2 位小数；不足 1 美元时保留 4 位有效数字，因为单笔 Gas 费常常不到 1 美分：
+/// `0.00000021 ETH` 按 3000 美元为 `$0.00063`。
+///
+/// # 参数
+/// * `wei` - 金额（wei）
+/// * `rate` - ETH 的美元价格
+///
+/// # 返回
+/// * `String` - 带 `$` 的金额
+pub fn format_usd(wei: U256, rate: f64) -> String {
+    let eth: f64 = format_units(wei, "ether")
+        .ok()
+        .and_then(|eth| eth.parse().ok())
+        .unwrap_or_default();
+    let usd = eth * rate;
+    if usd == 0.0 {
+        return "$0".to_string();
+    }
+    if usd >= 1.0 {
+        return format!("${:.2}", usd);
+    }
+    let decimals = (USD_SIGNIFICANT_DIGITS - 1 - usd.log10().floor() as i32).max(0) as usize;
+    let formatted = format!("{:.*}", decimals, usd);
+    format!("${}", formatted.trim_end_matches('0').trim_end_matches('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::utils::parse_units;
+
+    #[test]
+    fn strip_grouping_removes_separators() {
+        for (input, expected) in [
+            ("1_000", "1000"),
+            ("1,000.5", "1000.5"),
+            ("1,234,567", "1234567"),
+            ("12,345", "12345"),
+            ("0.000_001", "0.000001"),
+            ("1_0,000", "10000"),
+            (" 42 ", "42"),
+            (".5", ".5"),
+            ("5.", "5."),
+        ] {
+            assert_eq!(strip_grouping(input).as_deref(), Ok(expected), "{input}");
+        }
+    }
+
+    #[test]
+    fn strip_grouping_rejects_misplaced_separators() {
+        for input in [
+            "1,00",
+            "1000,000",
+            ",100",
+            "1,,000",
+            "_1",
+            "1_",
+            "1__000",
+            "1_.5",
+            "1._5",
+            "0.5_",
+            "1.0,5",
+            "1.000,000",
+        ] {
+            assert!(strip_grouping(input).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn strip_grouping_rejects_non_numbers() {
+        for input in ["", ".", "-1", "1.2.3", "1e18", "abc", "0x10"] {
+            assert!(strip_grouping(input).is_err(), "{input}");
+        }
+    }
+
+    fn ether(amount: &str) -> U256 {
+        parse_units(amount, "ether").unwrap().into()
+    }
+
+    #[test]
+    fn round_ether_rounds_half_up() {
+        assert_eq!(round_ether(ether("0.0012345678"), 6), "0.001235");
+        assert_eq!(round_ether(ether("0.0012344999"), 6), "0.001234");
+        assert_eq!(round_ether(ether("1.5000001"), 6), "1.5");
+        assert_eq!(round_ether(ether("2.0000001"), 6), "2");
+        assert_eq!(round_ether(U256::zero(), 6), "0");
+    }
+
+    #[test]
+    fn round_ether_carries_into_integer() {
+        assert_eq!(round_ether(ether("0.9999"), 2), "1");
+        assert_eq!(round_ether(ether("9.995"), 2), "10");
+        assert_eq!(round_ether(ether("0.994"), 2), "0.99");
+    }
+
+    #[test]
+    fn round_ether_precision_zero_and_full() {
+        assert_eq!(round_ether(ether("1.5"), 0), "2");
+        assert_eq!(round_ether(ether("1.4999"), 0), "1");
+        assert_eq!(round_ether(ether("0.4"), 0), "0");
+        // 不小于 18 位时不取整
+        assert_eq!(round_ether(U256::one(), 18), "0.000000000000000001");
+        assert_eq!(round_ether(U256::one(), 30), "0.000000000000000001");
+    }
+
+    #[test]
+    fn round_ether_saturates_at_max() {
+        // 加上半个单位会溢出时不再进位，按向下取整处理
+        let whole_ether = U256::MAX / U256::exp10(18);
+        assert_eq!(round_ether(U256::MAX, 0), whole_ether.to_string());
+    }
+
+    #[test]
+    fn group_digits_inserts_thousands_separators() {
+        assert_eq!(group_digits(U256::zero()), "0");
+        assert_eq!(group_digits(U256::from(999)), "999");
+        assert_eq!(group_digits(U256::from(1_000)), "1,000");
+        assert_eq!(group_digits(U256::from(100_000)), "100,000");
+        assert_eq!(group_digits(U256::from(1_000_000_000u64)), "1,000,000,000");
+        assert_eq!(
+            group_digits(U256::MAX),
+            concat!(
+                "115,792,089,237,316,195,423,570,985,008,687,907,853,269,",
+                "984,665,640,564,039,457,584,007,913,129,639,935",
+            )
+        );
+    }
+
+    #[test]
+    fn convert_table() {
+        for (amount, from, to, expected) in [
+            ("0.005", 18, 0, "5000000000000000"),
+            ("2500000", 9, 18, "0.0025"),
+            ("1", 18, 9, "1000000000"),
+            ("1", 0, 18, "0.000000000000000001"),
+            ("1,000.5", 18, 9, "1000500000000"),
+            ("1_500_000", 0, 6, "1.5"),
+            ("1.0", 0, 18, "0.000000000000000001"),
+            (".5", 18, 18, "0.5"),
+            ("0", 18, 0, "0"),
+            ("0.000000001", 9, 0, "1"),
+            ("000123", 0, 0, "123"),
+        ] {
+            let result = convert(amount, from, to).unwrap();
+            assert_eq!(result, expected, "{} ({} → {})", amount, from, to);
+        }
+    }
+
+    // wei → gwei / ether → wei 换算回来与原值一致
+    #[test]
+    fn wei_gwei_ether_round_trip() {
+        for wei in ["0", "1", "999999999", "1000000000", "123456789012345678901234567890"] {
+            for decimals in [9, 18] {
+                let there = convert(wei, 0, decimals).unwrap();
+                assert_eq!(convert(&there, decimals, 0).unwrap(), wei, "{} via {}", wei, there);
+            }
+        }
+        let max = U256::MAX.to_string();
+        let ether = convert(&max, 0, 18).unwrap();
+        assert_eq!(convert(&ether, 18, 0).unwrap(), max);
+    }
+
+    #[test]
+    fn too_many_fraction_digits_are_rejected() {
+        for (amount, decimals) in [("1.5", 0), ("0.0000000001", 9), ("0.0000000000000000001", 18)] {
+            let error = convert(amount, decimals, 0).unwrap_err();
+            assert_eq!(error.to_string(), tf(Msg::ExcessPrecision, &[&amount, &decimals]));
+        }
+    }
+
+    #[test]
+    fn values_beyond_u256_are_rejected() {
+        // U256::MAX + 1 wei
+        let over = "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+        // 1e60 ETH 换成 wei 超出 U256
+        let ether = format!("1{}", "0".repeat(60));
+        for (amount, decimals) in [(over, 0), (&ether, 18), ("1.5", 77)] {
+            let error = convert(amount, decimals, 0).unwrap_err().to_string();
+            assert!(error.contains(amount), "{}", error);
+        }
+        assert_eq!(convert(&U256::MAX.to_string(), 0, 0).unwrap(), U256::MAX.to_string());
+    }
+
+    #[test]
+    fn hex_and_malformed_inputs_are_rejected() {
+        for amount in ["0x10", "0X10", "1e18", "0b1", "", "."] {
+            let error = convert(amount, 18, 0).unwrap_err();
+            assert_eq!(error.to_string(), tf(Msg::InvalidAmount, &[&amount]), "{:?}", amount);
+        }
+        let error = convert("-1", 18, 0).unwrap_err();
+        assert_eq!(error.to_string(), tf(Msg::NegativeAmount, &[&"-1"]));
+    }
+}
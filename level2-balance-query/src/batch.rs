@@ -0,0 +1,249 @@
+use arbi_common::concurrency::{self, DEFAULT_CONCURRENCY};
+use arbi_common::narrate;
+use arbi_common::rpc::RpcProvider;
+use crate::messages::{Msg, tf};
+use arbi_common::rpc_batch;
+use arbi_common::style;
+use clap::ValueEnum;
+use ethers::prelude::Multicall;
+use ethers::providers::Middleware;
+use ethers::types::{Address, U256};
+use serde_json::json;
+use std::error::Error;
+
+use crate::portfolio;
+
+/// 批量查询结果的排序方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SortKey {
+    /// 按余额（wei 原始值）
+    Balance,
+    /// 按地址
+    Address,
+}
+
+/// 前 N 个地址的余额占全部地址的比例
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TopShare {
+    /// 前 N 个地址的余额之和（wei）
+    pub top: U256,
+    /// 全部地址的余额之和（wei）
+    pub total: U256,
+    /// 以万分之一为单位；合计为 0 时为 `None`
+    pub bps: Option<u32>,
+}
+
+/// 逐个顺序查询余额（每个地址一次往返）
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `addrs` - 要查询的地址列表
+///
+/// # 返回
+/// * `Result<Vec<U256>, Box<dyn Error>>` - 与输入顺序一致的余额（wei）
+pub async fn get_balances_sequential(
+    provider: &RpcProvider,
+    addrs: &[Address],
+) -> Result<Vec<U256>, Box<dyn Error>> {
+    let mut balances = Vec::with_capacity(addrs.len());
+    for addr in addrs {
+        balances.push(provider.get_balance(*addr, None).await?);
+    }
+    Ok(balances)
+}
+
+/// 并发查询余额，同时最多 `DEFAULT_CONCURRENCY` 个请求
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `addrs` - 要查询的地址列表
+///
+/// # 返回
+/// * `Result<Vec<U256>, Box<dyn Error>>` - 与输入顺序一致的余额（wei）
+pub async fn get_balances_concurrent(
+    provider: &RpcProvider,
+    addrs: &[Address],
+) -> Result<Vec<U256>, Box<dyn Error>> {
+    let balances = concurrency::run_bounded(addrs, DEFAULT_CONCURRENCY, |addr| {
+        provider.get_balance(*addr, None)
+    })
+    .await;
+    Ok(balances.into_iter().collect::<Result<_, _>>()?)
+}
+
+/// 通过 Multicall3 的 `getEthBalance` 在一次 `eth_call` 中查询所有余额
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `addrs` - 要查询的地址列表
+///
+/// # 返回
+/// * `Result<Vec<U256>, Box<dyn Error>>` - 与输入顺序一致的余额（wei）
+pub async fn get_balances_multicall(
+    provider: &RpcProvider,
+    addrs: &[Address],
+) -> Result<Vec<U256>, Box<dyn Error>> {
+    let mut multicall = Multicall::new(provider.clone(), None).await?;
+    for addr in addrs {
+        multicall.add_get_eth_balance(*addr, false);
+    }
+    let balances: Vec<U256> = multicall.call_array().await?;
+    Ok(balances)
+}
+
+/// 把所有 `eth_getBalance` 打包成一个 JSON-RPC 批量请求
+///
+/// # 参数
+/// * `rpc_url` - RPC 节点地址
+/// * `addrs` - 要查询的地址列表
+///
+/// # 返回
+/// * `Result<Vec<U256>, Box<dyn Error>>` - 与输入顺序一致的余额（wei）
+pub async fn get_balances_batched(
+    rpc_url: &str,
+    addrs: &[Address],
+) -> Result<Vec<U256>, Box<dyn Error>> {
+    let calls: Vec<_> = addrs
+        .iter()
+        .map(|addr| ("eth_getBalance", json!([addr, "latest"])))
+        .collect();
+
+    let results = rpc_batch::batch_call(rpc_url, &calls).await?;
+    let balances = results
+        .into_iter()
+        .map(serde_json::from_value::<U256>)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(balances)
+}
+
+/// 排序地址和余额
+///
+/// 按余额排序时比较 U256 原始值，余额相同时按地址升序，输出总是确定的；
+/// `desc` 只反转主键，相同余额之间仍按地址升序。
+///
+/// # 参数
+/// * `entries` - 地址和余额（wei）
+/// * `key` - 排序方式
+/// * `desc` - 是否降序
+pub fn sort_balances(entries: &mut [(Address, U256)], key: SortKey, desc: bool) {
+    entries.sort_by(|a, b| {
+        let primary = match key {
+            SortKey::Balance => a.1.cmp(&b.1),
+            SortKey::Address => a.0.cmp(&b.0),
+        };
+        let primary = if desc { primary.reverse() } else { primary };
+        primary.then_with(|| a.0.cmp(&b.0))
+    });
+}
+
+/// 取余额最大的 `n` 个地址，并计算它们占全部余额的比例
+///
+/// # 参数
+/// * `entries` - 地址和余额（wei）
+/// * `n` - 保留的地址数，超过地址总数时保留全部
+///
+/// # 返回
+/// * `(Vec<(Address, U256)>, TopShare)` - 按余额从大到小排列的前 `n` 个地址，以及占比
+pub fn top_holders(entries: &[(Address, U256)], n: usize) -> (Vec<(Address, U256)>, TopShare) {
+    // ETH 的总量远小于 U256 的上限，真实余额相加不会饱和
+    let sum = |entries: &[(Address, U256)]| {
+        entries.iter().fold(U256::zero(), |sum, (_, balance)| sum.saturating_add(*balance))
+    };
+    let mut ranked = entries.to_vec();
+    sort_balances(&mut ranked, SortKey::Balance, true);
+    ranked.truncate(n);
+    let (top, total) = (sum(&ranked), sum(entries));
+    let bps = portfolio::share_bps(top.into(), total.into());
+    (ranked, TopShare { top, total, bps })
+}
+
+/// 批量查询余额：优先使用 Multicall3，节点上没有部署时（例如本地 anvil）退回并发查询
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `addrs` - 要查询的地址列表
+///
+/// # 返回
+/// * `Result<Vec<U256>, Box<dyn Error>>` - 与输入顺序一致的余额（wei）
+pub async fn get_balances(
+    provider: &RpcProvider,
+    addrs: &[Address],
+) -> Result<Vec<U256>, Box<dyn Error>> {
+    match get_balances_multicall(provider, addrs).await {
+        Ok(balances) => Ok(balances),
+        Err(e) => {
+            narrate!("{}", style::warn(tf(Msg::MulticallFallback, &[&e])));
+            get_balances_concurrent(provider, addrs).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(n: u64) -> Address {
+        Address::from_low_u64_be(n)
+    }
+
+    fn entry(n: u64, balance: u64) -> (Address, U256) {
+        (addr(n), U256::from(balance))
+    }
+
+    #[test]
+    fn equal_balances_keep_address_order_in_both_directions() {
+        let mut entries = vec![entry(3, 5), entry(1, 5), entry(4, 9), entry(2, 5)];
+        sort_balances(&mut entries, SortKey::Balance, false);
+        assert_eq!(entries, [entry(1, 5), entry(2, 5), entry(3, 5), entry(4, 9)]);
+
+        sort_balances(&mut entries, SortKey::Balance, true);
+        assert_eq!(entries, [entry(4, 9), entry(1, 5), entry(2, 5), entry(3, 5)]);
+    }
+
+    #[test]
+    fn sorts_by_address() {
+        let mut entries = vec![entry(2, 1), entry(3, 0), entry(1, 7)];
+        sort_balances(&mut entries, SortKey::Address, false);
+        assert_eq!(entries, [entry(1, 7), entry(2, 1), entry(3, 0)]);
+        sort_balances(&mut entries, SortKey::Address, true);
+        assert_eq!(entries, [entry(3, 0), entry(2, 1), entry(1, 7)]);
+    }
+
+    #[test]
+    fn ties_at_the_cut_are_broken_by_address() {
+        let entries = [entry(9, 10), entry(7, 10), entry(8, 10), entry(1, 30)];
+        let (top, share) = top_holders(&entries, 2);
+        assert_eq!(top, [entry(1, 30), entry(7, 10)]);
+        assert_eq!((share.top, share.total), (U256::from(40), U256::from(60)));
+        assert_eq!(share.bps, Some(6_667));
+    }
+
+    #[test]
+    fn n_larger_than_the_list_keeps_everything() {
+        let entries = [entry(1, 1), entry(2, 3)];
+        let (top, share) = top_holders(&entries, 10);
+        assert_eq!(top, [entry(2, 3), entry(1, 1)]);
+        assert_eq!(share.top, share.total);
+        assert_eq!(share.bps, Some(10_000));
+    }
+
+    #[test]
+    fn all_zero_balances_have_no_share() {
+        let entries = [entry(2, 0), entry(1, 0), entry(3, 0)];
+        let (top, share) = top_holders(&entries, 2);
+        assert_eq!(top, [entry(1, 0), entry(2, 0)]);
+        assert!(share.total.is_zero());
+        assert_eq!(share.bps, None);
+    }
+
+    #[test]
+    fn empty_list_and_zero_n() {
+        let (top, share) = top_holders(&[], 3);
+        assert!(top.is_empty());
+        assert_eq!(share.bps, None);
+
+        let (top, share) = top_holders(&[entry(1, 5)], 0);
+        assert!(top.is_empty());
+        assert_eq!(share.bps, Some(0));
+    }
+}
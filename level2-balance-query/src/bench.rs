@@ -0,0 +1,73 @@
+use crate::batch;
+use crate::messages::{Msg, tf};
+use arbi_common::bench::{self, BenchResult};
+use arbi_common::narrate;
+use arbi_common::rpc;
+use arbi_common::style;
+use ethers::types::U256;
+use std::error::Error;
+
+/// 对比四种批量查询余额方式的耗时
+///
+/// 基准测试调用的就是 `batch` 命令使用的函数。用本地 anvil（`--rpc-url http://127.0.0.1:8545`）
+/// 运行可以排除公共 RPC 网络抖动的影响；在 fork 出来的 anvil 上 Multicall3 也可用。
+///
+/// # 参数
+/// * `rpc_url` - RPC 节点地址
+/// * `count` - 查询的地址数量
+/// * `rounds` - 每种方式运行的轮数
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+pub async fn run_balance_bench(rpc_url: &str, count: usize, rounds: usize) -> Result<(), Box<dyn Error>> {
+    let provider = rpc::connect(rpc_url)?;
+    let addrs = bench::bench_addresses(count);
+
+    narrate!("{}", tf(Msg::BenchRpc, &[&rpc_url]));
+    narrate!("{}", tf(Msg::BenchStart, &[&count, &rounds]));
+
+    let mut results: Vec<BenchResult> = Vec::new();
+    let mut outputs: Vec<(&'static str, Vec<U256>)> = Vec::new();
+
+    let (result, output) = bench::measure("sequential", rounds, || {
+        batch::get_balances_sequential(&provider, &addrs)
+    })
+    .await?;
+    results.push(result);
+    outputs.push(("sequential", output));
+
+    let (result, output) = bench::measure("concurrent", rounds, || {
+        batch::get_balances_concurrent(&provider, &addrs)
+    })
+    .await?;
+    results.push(result);
+    outputs.push(("concurrent", output));
+
+    match bench::measure("multicall", rounds, || batch::get_balances_multicall(&provider, &addrs)).await {
+        Ok((result, output)) => {
+            results.push(result);
+            outputs.push(("multicall", output));
+        }
+        Err(e) => println!("{}", style::warn(tf(Msg::BenchSkipped, &[&"multicall", &e]))),
+    }
+
+    match bench::measure("batched-http", rounds, || batch::get_balances_batched(rpc_url, &addrs)).await {
+        Ok((result, output)) => {
+            results.push(result);
+            outputs.push(("batched-http", output));
+        }
+        Err(e) => println!("{}", style::warn(tf(Msg::BenchSkipped, &[&"batched-http", &e]))),
+    }
+
+    bench::print_table(&tf(Msg::BenchTitle, &[&count]), &results);
+
+    // 各方式查询的是同一个状态，结果应当一致
+    let (_, expected) = &outputs[0];
+    for (name, output) in &outputs[1..] {
+        if output != expected {
+            println!("{}", style::warn(tf(Msg::BenchMismatch, &[name])));
+        }
+    }
+
+    Ok(())
+}
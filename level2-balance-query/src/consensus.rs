@@ -0,0 +1,279 @@
+//! 在多个 RPC 节点上查询同一区块的余额，找出状态落后或不一致的节点
+
+use arbi_common::rpc;
+use arbi_common::style;
+use arbi_common::units;
+use ethers::providers::Middleware;
+use ethers::types::{Address, BlockId, U256};
+use futures::future::join_all;
+use std::error::Error;
+use tracing::{info, warn};
+
+use crate::messages::{Msg, t, tf};
+
+/// 一个节点的查询结果
+#[derive(Clone, Debug)]
+pub struct Reading {
+    pub rpc_url: String,
+    /// 节点当前的最新区块；查询失败时为 `None`
+    pub head: Option<u64>,
+    /// 目标区块上的余额（wei），失败时为错误信息
+    pub balance: Result<U256, String>,
+}
+
+/// 单个节点的判定
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// 与多数节点一致
+    Agree,
+    /// 节点还没同步到目标区块，结果不同或查不到是因为落后
+    Lagging,
+    /// 节点已经有目标区块，余额却与多数节点不同
+    Inconsistent,
+    /// 查询失败，且不是因为落后
+    Failed,
+}
+
+/// 所有节点的对比结果
+#[derive(Clone, Debug)]
+pub struct Consensus {
+    /// 查询的区块号
+    pub block: u64,
+    /// 多数节点给出的余额；没有任何节点有效返回时为 `None`
+    pub balance: Option<U256>,
+    /// 与输入顺序一致的每个节点的判定
+    pub verdicts: Vec<Verdict>,
+}
+
+impl Consensus {
+    /// 是否所有节点都一致
+    pub fn is_unanimous(&self) -> bool {
+        self.verdicts.iter().all(|verdict| *verdict == Verdict::Agree)
+    }
+}
+
+// 节点是否已经有目标区块；最新区块没查到时不能断定落后，按已同步处理
+fn has_block(reading: &Reading, block: u64) -> bool {
+    reading.head.is_none_or(|head| head >= block)
+}
+
+/// 对比各节点在同一区块上的余额
+///
+/// 以已同步到目标区块的节点中出现次数最多的余额为准（次数相同时取排在前面的节点的值）。
+/// 余额不同或查询失败的节点，最新区块低于目标区块时判为落后，否则判为不一致 / 失败，
+/// 这样可以区分"节点慢了几个区块"和"节点返回了错误的状态"。
+///
+/// # 参数
+/// * `block` - 查询的区块号
+/// * `readings` - 各节点的查询结果
+///
+/// # 返回
+/// * `Consensus` - 多数余额和每个节点的判定
+pub fn classify(block: u64, readings: &[Reading]) -> Consensus {
+    let synced: Vec<U256> = readings
+        .iter()
+        .filter(|reading| has_block(reading, block))
+        .filter_map(|reading| reading.balance.clone().ok())
+        .collect();
+    // max_by_key 在次数相同时取最后一个，这里倒序遍历，相同时取最前面的
+    let balance = synced
+        .iter()
+        .rev()
+        .max_by_key(|value| synced.iter().filter(|other| other == value).count())
+        .copied();
+
+    let verdicts = readings
+        .iter()
+        .map(|reading| match (&reading.balance, has_block(reading, block)) {
+            (Ok(value), _) if Some(*value) == balance => Verdict::Agree,
+            (_, false) => Verdict::Lagging,
+            (Ok(_), true) => Verdict::Inconsistent,
+            (Err(_), true) => Verdict::Failed,
+        })
+        .collect();
+
+    Consensus {
+        block,
+        balance,
+        verdicts,
+    }
+}
+
+// 在一个节点上同时查询最新区块和目标区块上的余额
+async fn read(rpc_url: &str, address: Address, block: u64) -> Reading {
+    let (head, balance) = match rpc::connect(rpc_url) {
+        Ok(provider) => {
+            let at = Some(BlockId::Number(block.into()));
+            let (head, balance) =
+                tokio::join!(provider.get_block_number(), provider.get_balance(address, at));
+            (head.ok().map(|head| head.as_u64()), balance.map_err(|e| e.to_string()))
+        }
+        Err(e) => (None, Err(e.to_string())),
+    };
+    Reading {
+        rpc_url: rpc_url.to_string(),
+        head,
+        balance,
+    }
+}
+
+/// 在所有节点上查询同一区块的余额并对比
+///
+/// 目标区块取第一个节点的最新区块，再把这个区块号显式传给所有节点并发查询，
+/// 避免各节点按各自的 `latest` 回答导致结果不可比。
+///
+/// # 参数
+/// * `rpc_urls` - 参与对比的节点（至少 2 个）
+/// * `address` - 要查询的地址
+///
+/// # 返回
+/// * `Result<(Vec<Reading>, Consensus), Box<dyn Error>>` - 各节点的结果和对比结论；
+///   节点少于 2 个或第一个节点查不到最新区块时返回错误
+pub async fn check(
+    rpc_urls: &[String],
+    address: Address,
+) -> Result<(Vec<Reading>, Consensus), Box<dyn Error>> {
+    if rpc_urls.len() < 2 {
+        return Err(t(Msg::ConsensusTooFew).into());
+    }
+    let block = rpc::connect(&rpc_urls[0])?.get_block_number().await?.as_u64();
+    info!(block, endpoints = rpc_urls.len(), "consensus block pinned");
+
+    let readings = join_all(rpc_urls.iter().map(|url| read(url, address, block))).await;
+    let consensus = classify(block, &readings);
+    for (reading, verdict) in readings.iter().zip(&consensus.verdicts) {
+        if *verdict != Verdict::Agree {
+            warn!(rpc_url = %reading.rpc_url, ?verdict, head = ?reading.head, "endpoint disagrees");
+        }
+    }
+    Ok((readings, consensus))
+}
+
+/// 打印每个节点的结果和结论
+///
+/// # 参数
+/// * `readings` - 各节点的查询结果
+/// * `consensus` - `classify` 的结果
+pub fn print(readings: &[Reading], consensus: &Consensus) {
+    println!("{}", tf(Msg::ConsensusBlock, &[&consensus.block, &readings.len()]));
+    for (reading, verdict) in readings.iter().zip(&consensus.verdicts) {
+        let head = match reading.head {
+            Some(head) => head.to_string(),
+            None => "?".to_string(),
+        };
+        let value = match &reading.balance {
+            Ok(balance) => format!("{} ETH", units::format_balance(*balance)),
+            Err(e) => e.clone(),
+        };
+        let line = tf(Msg::ConsensusEndpoint, &[&reading.rpc_url, &value, &head]);
+        match verdict {
+            Verdict::Agree => println!("  {}", style::ok(format!("✓ {}", line))),
+            Verdict::Lagging => println!("  {}", style::warn(tf(Msg::ConsensusLagging, &[&line]))),
+            Verdict::Inconsistent => {
+                println!("  {}", style::err(tf(Msg::ConsensusInconsistent, &[&line])))
+            }
+            Verdict::Failed => println!("  {}", style::err(tf(Msg::ConsensusFailed, &[&line]))),
+        }
+    }
+
+    let agreed = consensus.verdicts.iter().filter(|v| **v == Verdict::Agree).count();
+    match consensus.balance {
+        Some(balance) if consensus.is_unanimous() => {
+            let balance = units::format_balance(balance);
+            println!("{}", style::ok(tf(Msg::ConsensusUnanimous, &[&balance])));
+        }
+        Some(balance) => {
+            let balance = units::format_balance(balance);
+            let summary = tf(Msg::ConsensusMajority, &[&agreed, &readings.len(), &balance]);
+            println!("{}", style::warn(summary));
+        }
+        None => println!("{}", style::err(t(Msg::ConsensusNone))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOCK: u64 = 1_000;
+
+    fn ok(head: u64, balance: u64) -> Reading {
+        Reading {
+            rpc_url: format!("https://node-{}.example", balance),
+            head: Some(head),
+            balance: Ok(U256::from(balance)),
+        }
+    }
+
+    fn failed(head: Option<u64>) -> Reading {
+        Reading {
+            rpc_url: "https://down.example".to_string(),
+            head,
+            balance: Err("missing trie node".to_string()),
+        }
+    }
+
+    #[test]
+    fn all_nodes_agree() {
+        let consensus = classify(BLOCK, &[ok(1_000, 5), ok(1_003, 5), ok(1_001, 5)]);
+        assert_eq!(consensus.balance, Some(U256::from(5)));
+        assert_eq!(consensus.verdicts, [Verdict::Agree; 3]);
+        assert!(consensus.is_unanimous());
+    }
+
+    #[test]
+    fn behind_the_block_is_lagging_not_inconsistent() {
+        // 落后的节点不管返回什么都判为落后，也不参与多数
+        let readings = [ok(1_002, 5), ok(998, 9), ok(999, 9), failed(Some(990)), ok(1_000, 5)];
+        let consensus = classify(BLOCK, &readings);
+        assert_eq!(consensus.balance, Some(U256::from(5)));
+        assert_eq!(
+            consensus.verdicts,
+            [
+                Verdict::Agree,
+                Verdict::Lagging,
+                Verdict::Lagging,
+                Verdict::Lagging,
+                Verdict::Agree,
+            ]
+        );
+        assert!(!consensus.is_unanimous());
+    }
+
+    #[test]
+    fn synced_node_with_another_balance_is_divergent() {
+        let readings = [ok(1_000, 5), ok(1_001, 7), ok(1_005, 5), failed(Some(1_001))];
+        let consensus = classify(BLOCK, &readings);
+        assert_eq!(consensus.balance, Some(U256::from(5)));
+        assert_eq!(
+            consensus.verdicts,
+            [Verdict::Agree, Verdict::Inconsistent, Verdict::Agree, Verdict::Failed]
+        );
+        // 最新区块查不到的节点按已同步处理
+        let consensus = classify(BLOCK, &[ok(1_000, 5), failed(None)]);
+        assert_eq!(consensus.verdicts, [Verdict::Agree, Verdict::Failed]);
+    }
+
+    #[test]
+    fn ties_go_to_the_earlier_node() {
+        let consensus = classify(BLOCK, &[ok(1_000, 7), ok(1_000, 5), ok(1_000, 5), ok(1_000, 7)]);
+        assert_eq!(consensus.balance, Some(U256::from(7)));
+        let consensus = classify(BLOCK, &[ok(1_000, 5), ok(1_000, 7)]);
+        assert_eq!(consensus.balance, Some(U256::from(5)));
+        assert_eq!(consensus.verdicts, [Verdict::Agree, Verdict::Inconsistent]);
+    }
+
+    #[test]
+    fn no_valid_reading_means_no_majority() {
+        let consensus = classify(BLOCK, &[failed(Some(1_000)), ok(900, 5)]);
+        assert_eq!(consensus.balance, None);
+        assert_eq!(consensus.verdicts, [Verdict::Failed, Verdict::Lagging]);
+    }
+
+    #[tokio::test]
+    async fn needs_at_least_two_endpoints() {
+        let urls = ["http://127.0.0.1:1".to_string()];
+        let error = check(&urls, Address::zero()).await.unwrap_err();
+        assert_eq!(error.to_string(), t(Msg::ConsensusTooFew));
+    }
+}
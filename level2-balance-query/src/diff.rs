@@ -0,0 +1,308 @@
+//! 对比地址在两个区块高度的余额，给出变化量和按区块时间戳算出的平均每天变化
+//!
+//! 读取历史区块的余额需要节点保存那个区块的状态，普通节点只保留最近一段时间，
+//! 更早的区块要用归档节点。
+
+use arbi_common::rpc::{RpcProvider, is_pruned_state_error};
+use arbi_common::style;
+use arbi_common::units;
+use ethers::contract::Contract;
+use ethers::providers::Middleware;
+use ethers::types::{Address, U256, U512};
+use std::error::Error;
+use std::sync::Arc;
+
+use crate::messages::{Msg, t, tf};
+use crate::pending::{PendingDelta, pending_delta};
+use crate::portfolio::ERC20_ABI;
+use crate::watch::WatchedToken;
+
+// 一天的秒数
+const SECS_PER_DAY: u64 = 86_400;
+
+/// 某个区块高度的余额
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockBalance {
+    pub block: u64,
+    /// 区块头里的时间戳（Unix 秒）
+    pub timestamp: u64,
+    /// 余额（ETH 为 wei，代币为最小单位）
+    pub balance: U256,
+}
+
+/// 两个区块之间的余额变化
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BalanceChange {
+    /// 变化方向和差额：`Incoming` 为增加，`Outgoing` 为减少
+    pub delta: PendingDelta,
+    /// 两个区块的时间间隔（秒）
+    pub interval_secs: u64,
+    /// 平均每天的变化量（方向同 `delta`）；两个区块时间戳相同时为 `None`
+    pub per_day: Option<U256>,
+}
+
+// 差额的绝对值
+fn magnitude(delta: PendingDelta) -> U256 {
+    match delta {
+        PendingDelta::Same => U256::zero(),
+        PendingDelta::Incoming(amount) | PendingDelta::Outgoing(amount) => amount,
+    }
+}
+
+/// 计算两个区块之间的余额变化和平均每天的变化量
+///
+/// 每天的变化量为 `差额 × 86400 / 间隔`，四舍五入；用 512 位整数计算不会溢出，
+/// 结果超出 256 位时取最大值。`to` 的时间戳早于 `from` 时按间隔为 0 处理。
+///
+/// # 参数
+/// * `from` - 较早区块的余额
+/// * `to` - 较晚区块的余额
+///
+/// # 返回
+/// * `BalanceChange` - 变化量、时间间隔和平均每天的变化量
+pub fn balance_change(from: &BlockBalance, to: &BlockBalance) -> BalanceChange {
+    let delta = pending_delta(from.balance, to.balance);
+    let interval_secs = to.timestamp.saturating_sub(from.timestamp);
+    let amount = magnitude(delta);
+    let per_day = (interval_secs > 0).then(|| {
+        let interval = U512::from(interval_secs);
+        let rate = (U512::from(amount) * U512::from(SECS_PER_DAY) + interval / 2) / interval;
+        U256::try_from(rate).unwrap_or(U256::MAX)
+    });
+    BalanceChange {
+        delta,
+        interval_secs,
+        per_day,
+    }
+}
+
+/// 读取区块头的时间戳和地址在那个区块的余额
+///
+/// 节点没有那个区块的状态（已裁剪）时返回说明需要归档节点的错误。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `owner` - 要查询的地址
+/// * `token` - 代币；为 `None` 时查询 ETH 余额
+/// * `block` - 区块号
+///
+/// # 返回
+/// * `Result<BlockBalance, Box<dyn Error>>` - 区块的时间戳和余额
+pub async fn balance_at(
+    provider: &RpcProvider,
+    owner: Address,
+    token: Option<&WatchedToken>,
+    block: u64,
+) -> Result<BlockBalance, Box<dyn Error>> {
+    let Some(header) = provider.get_block(block).await? else {
+        return Err(tf(Msg::WatchBlockMissing, &[&block]).into());
+    };
+    let balance: Result<U256, Box<dyn Error>> = match token {
+        Some(token) => {
+            let abi = ethers::abi::parse_abi(&ERC20_ABI)?;
+            let contract = Contract::new(token.address, abi, Arc::new(provider.clone()));
+            let call = contract.method::<_, U256>("balanceOf", owner)?.block(block);
+            call.call().await.map_err(Into::into)
+        }
+        None => provider.get_balance(owner, Some(block.into())).await.map_err(Into::into),
+    };
+    let balance = balance.map_err(|e| -> Box<dyn Error> {
+        match is_pruned_state_error(&e.to_string()) {
+            true => tf(Msg::DiffStateUnavailable, &[&block, &e]).into(),
+            false => e,
+        }
+    })?;
+    Ok(BlockBalance {
+        block,
+        timestamp: header.timestamp.low_u64(),
+        balance,
+    })
+}
+
+// 两个区块的间隔按大小选单位
+fn format_interval(secs: u64) -> String {
+    match secs {
+        secs if secs < 120 => format!("{}s", secs),
+        secs if secs < 7200 => format!("{}min", secs / 60),
+        secs if secs < 2 * SECS_PER_DAY => format!("{:.1}h", secs as f64 / 3600.0),
+        secs => format!("{:.1}d", secs as f64 / SECS_PER_DAY as f64),
+    }
+}
+
+// ETH 按 --precision 取整，代币显示精确值
+fn format_amount(amount: U256, token: Option<&WatchedToken>) -> String {
+    match token {
+        Some(token) => {
+            let exact = units::format_exact(amount, token.decimals.into());
+            format!("{} {}", exact.unwrap_or_else(|_| amount.to_string()), token.symbol)
+        }
+        None => format!("{} ETH", units::format_balance(amount)),
+    }
+}
+
+// 带符号的金额，如 `+0.5 ETH` / `-12 USDC`
+fn format_signed(delta: PendingDelta, amount: U256, token: Option<&WatchedToken>) -> String {
+    match delta {
+        PendingDelta::Same => format_amount(U256::zero(), token),
+        PendingDelta::Incoming(_) => format!("+{}", format_amount(amount, token)),
+        PendingDelta::Outgoing(_) => format!("-{}", format_amount(amount, token)),
+    }
+}
+
+/// 打印两个区块的余额、变化量和平均每天的变化量
+///
+/// # 参数
+/// * `from` - 较早区块的余额
+/// * `to` - 较晚区块的余额
+/// * `token` - 代币；为 `None` 时为 ETH
+pub fn print(from: &BlockBalance, to: &BlockBalance, token: Option<&WatchedToken>) {
+    for balance in [from, to] {
+        let amount = format_amount(balance.balance, token);
+        println!("{}", tf(Msg::DiffAtBlock, &[&balance.block, &balance.timestamp, &amount]));
+    }
+    let change = balance_change(from, to);
+    let delta = format_signed(change.delta, magnitude(change.delta), token);
+    let line = tf(Msg::DiffChange, &[&delta, &format_interval(change.interval_secs)]);
+    match change.delta {
+        PendingDelta::Same => println!("{}", line),
+        PendingDelta::Incoming(_) => println!("{}", style::ok(line)),
+        PendingDelta::Outgoing(_) => println!("{}", style::warn(line)),
+    }
+    match change.per_day {
+        Some(per_day) => {
+            let per_day = format_signed(change.delta, per_day, token);
+            println!("{}", tf(Msg::DiffPerDay, &[&per_day]));
+        }
+        None => println!("{}", t(Msg::DiffSameTimestamp)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use serde_json::json;
+
+    const OWNER: Address = Address::repeat_byte(0xaa);
+
+    fn at(block: u64, timestamp: u64, balance: u64) -> BlockBalance {
+        BlockBalance {
+            block,
+            timestamp,
+            balance: U256::from(balance),
+        }
+    }
+
+    #[test]
+    fn delta_and_rate_per_day() {
+        // 两天增加 1000，每天 500
+        let change = balance_change(&at(100, 0, 1_000), &at(200, 2 * SECS_PER_DAY, 2_000));
+        assert_eq!(change.delta, PendingDelta::Incoming(U256::from(1_000)));
+        assert_eq!(change.interval_secs, 2 * SECS_PER_DAY);
+        assert_eq!(change.per_day, Some(U256::from(500)));
+
+        // 6 小时减少 10，每天 40
+        let change = balance_change(&at(100, 0, 30), &at(200, 6 * 3_600, 20));
+        assert_eq!(change.delta, PendingDelta::Outgoing(U256::from(10)));
+        assert_eq!(change.per_day, Some(U256::from(40)));
+
+        let same = balance_change(&at(100, 0, 7), &at(200, 60, 7));
+        assert_eq!(same.delta, PendingDelta::Same);
+        assert_eq!(same.per_day, Some(U256::zero()));
+    }
+
+    #[test]
+    fn rate_rounds_half_up() {
+        // 3 天 10：每天 3.33 → 3；3 天 11：每天 3.67 → 4；2 天 3：每天 1.5 → 2
+        let rate = |secs, amount| balance_change(&at(1, 0, 0), &at(2, secs, amount)).per_day;
+        assert_eq!(rate(3 * SECS_PER_DAY, 10), Some(U256::from(3)));
+        assert_eq!(rate(3 * SECS_PER_DAY, 11), Some(U256::from(4)));
+        assert_eq!(rate(2 * SECS_PER_DAY, 3), Some(U256::from(2)));
+    }
+
+    #[test]
+    fn same_or_backwards_timestamps_have_no_rate() {
+        assert_eq!(balance_change(&at(1, 500, 0), &at(2, 500, 9)).per_day, None);
+        let backwards = balance_change(&at(1, 500, 0), &at(2, 400, 9));
+        assert_eq!(backwards.interval_secs, 0);
+        assert_eq!(backwards.per_day, None);
+    }
+
+    #[test]
+    fn huge_rate_is_clamped() {
+        let from = BlockBalance {
+            block: 1,
+            timestamp: 0,
+            balance: U256::zero(),
+        };
+        let to = BlockBalance {
+            block: 2,
+            timestamp: 1,
+            balance: U256::MAX,
+        };
+        assert_eq!(balance_change(&from, &to).per_day, Some(U256::MAX));
+    }
+
+    #[test]
+    fn formats_intervals_and_signed_token_amounts() {
+        assert_eq!(format_interval(90), "90s");
+        assert_eq!(format_interval(3_600), "60min");
+        assert_eq!(format_interval(36 * 3_600), "36.0h");
+        assert_eq!(format_interval(3 * SECS_PER_DAY), "3.0d");
+        let usdc = WatchedToken {
+            address: Address::repeat_byte(0x0c),
+            symbol: "USDC".to_string(),
+            decimals: 6,
+        };
+        let amount = U256::from(12_500_000);
+        let delta = PendingDelta::Outgoing(amount);
+        assert_eq!(format_signed(delta, amount, Some(&usdc)), "-12.5 USDC");
+        let delta = PendingDelta::Incoming(amount);
+        assert_eq!(format_signed(delta, amount, Some(&usdc)), "+12.5 USDC");
+        assert_eq!(format_signed(PendingDelta::Same, amount, Some(&usdc)), "0 USDC");
+    }
+
+    // 区块 0x64 的时间戳为 0x6700_0000；余额查询返回 `balance`，为 Err 时返回这个错误
+    fn node(balance: Result<&'static str, &'static str>) -> MockRpc {
+        MockRpc::start(move |method, params| match method {
+            "eth_getBlockByNumber" => Reply::Result(json!({
+                "number": params[0],
+                "hash": format!("{:?}", ethers::types::H256::repeat_byte(0x64)),
+                "timestamp": "0x67000000",
+                "transactions": [],
+            })),
+            "eth_getBalance" => {
+                assert_eq!(params[1], json!("0x64"));
+                match balance {
+                    Ok(balance) => Reply::Result(json!(balance)),
+                    Err(message) => Reply::Error(message.to_string()),
+                }
+            }
+            _ => Reply::Error(format!("unexpected {}", method)),
+        })
+    }
+
+    #[tokio::test]
+    async fn reads_balance_and_timestamp_at_the_block() {
+        let rpc = node(Ok("0x3e8"));
+        let balance = balance_at(&rpc.provider(), OWNER, None, 100).await.unwrap();
+        assert_eq!(balance, at(100, 0x6700_0000, 1_000));
+    }
+
+    #[tokio::test]
+    async fn pruned_state_asks_for_an_archive_node() {
+        // 节点的错误信息嵌在提示中间
+        let template = tf(Msg::DiffStateUnavailable, &[&100, &"\0"]);
+        let (head, tail) = template.split_once('\0').unwrap();
+        let hinted = |error: &str| error.starts_with(head) && error.ends_with(tail);
+
+        let rpc = node(Err("missing trie node abc123 (path )"));
+        let error = balance_at(&rpc.provider(), OWNER, None, 100).await.unwrap_err();
+        assert!(hinted(&error.to_string()), "{error}");
+        assert!(error.to_string().contains("missing trie node abc123"), "{error}");
+
+        let rpc = node(Err("internal error"));
+        let error = balance_at(&rpc.provider(), OWNER, None, 100).await.unwrap_err();
+        assert!(!hinted(&error.to_string()), "{error}");
+    }
+}
@@ -1,51 +1,677 @@
-use ethers::providers::{Http, Middleware, Provider};
-use ethers::types::Address;
-use ethers::utils::format_units;
+mod batch;
+mod bench;
+mod consensus;
+mod diff;
+mod messages;
+mod pending;
+mod portfolio;
+mod recent;
+mod watch;
+
+use arbi_common::cli::{CsvArgs, GlobalArgs, JsonArgs, QrArgs};
+use arbi_common::logging;
+use arbi_common::narrate;
+use arbi_common::network;
+use arbi_common::price_feed;
+use arbi_common::rpc::{self, RpcProvider};
+use arbi_common::shutdown::{INTERRUPTED_EXIT_CODE, Interrupted};
+use arbi_common::style;
+use arbi_common::table::Table;
+use arbi_common::units;
+use clap::{Parser, Subcommand};
+use ethers::providers::Middleware;
+use ethers::types::{Address, U256};
+use messages::{Msg, tf};
 use std::error::Error;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{Instrument, info, info_span};
+
+// 以太坊主网（L1）RPC URL，ENS 注册表部署在 L1 上
+const ENS_RPC_URL: &str = "https://ethereum-rpc.publicnode.com";
+
+// 默认查询的测试地址
+const TEST_ADDRESS: &str = "0x51F14ab69C8f748F72b6DB1Aa66875faf7c24Bd2";
+
+/// Arbitrum 测试网余额查询工具
+#[derive(Parser)]
+#[command(about = "查询 Arbitrum 测试网 ETH 余额")]
+#[command(args_conflicts_with_subcommands = true)]
+struct Cli {
+    /// 要查询的地址或 ENS 名称（如 vitalik.eth，默认使用测试地址）
+    address: Option<String>,
+
+    /// 解析 ENS 名称使用的 L1 RPC 节点地址
+    #[arg(long, default_value = ENS_RPC_URL)]
+    ens_rpc_url: String,
+
+    /// 余额显示的小数位数（四舍五入，JSON 日志中保留精确的 wei）
+    #[arg(long, global = true, default_value_t = units::DEFAULT_PRECISION)]
+    precision: usize,
+
+    /// 同时显示按 Chainlink ETH/USD 喂价换算的美元参考值（测试网需用 ARBI_ETH_USD_FEED 指定喂价）
+    #[arg(long)]
+    usd: bool,
+
+    /// 同时查询 pending 标签的余额，显示还没确认的转入 / 转出
+    #[arg(long)]
+    show_pending: bool,
+
+    /// 轮询直到 latest 余额追上 pending 或超时（秒，默认 120），隐含 --show-pending
+    #[arg(long, value_name = "SECS", num_args = 0..=1, default_missing_value = "120")]
+    wait_for_pending: Option<u64>,
+
+    /// 在多个 RPC 节点上查询同一区块的余额并对比，列出落后或不一致的节点
+    #[arg(long, conflicts_with_all = ["show_pending", "wait_for_pending"])]
+    consensus: bool,
+
+    /// 参与 --consensus 对比的节点，可重复指定（默认为当前网络的全部公共节点）
+    #[arg(long = "consensus-rpc", value_name = "URL", requires = "consensus")]
+    consensus_rpcs: Vec<String>,
+
+    /// 持续监控余额，每隔 SECS 秒（默认 10）轮询一次，变化时打印差额，Ctrl-C 退出
+    #[arg(
+        long,
+        value_name = "SECS",
+        num_args = 0..=1,
+        default_missing_value = "10",
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with_all = ["consensus", "show_pending", "wait_for_pending"]
+    )]
+    watch: Option<u64>,
+
+    /// 监控时找出余额变化对应的转入交易（扫描两次轮询之间的所有区块），打印发送方、金额和交易哈希
+    #[arg(long, requires = "watch")]
+    notify_incoming: bool,
+
+    /// 同时找出这个 ERC20 代币的转入（Transfer 事件）
+    #[arg(long, value_name = "ADDRESS", requires = "notify_incoming")]
+    token: Option<String>,
+
+    /// 每笔转入执行的 shell 命令，转入信息通过 ARBI_INCOMING_* 环境变量传入
+    #[arg(long, value_name = "COMMAND", requires = "notify_incoming")]
+    exec: Option<String>,
+
+    #[command(flatten)]
+    qr: QrArgs,
+
+    #[command(flatten)]
+    global: GlobalArgs,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 批量查询多个地址的余额
+    Batch {
+        /// 要查询的地址列表
+        #[arg(required = true)]
+        addresses: Vec<String>,
+        /// 按余额或地址排序（余额相同时按地址）
+        #[arg(long, value_enum)]
+        sort: Option<batch::SortKey>,
+        /// 降序排列
+        #[arg(long, requires = "sort")]
+        desc: bool,
+        /// 只显示余额最大的 N 个地址，并给出它们占全部余额的比例（未指定 --sort 时按余额降序显示）
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+        top: Option<u64>,
+        #[command(flatten)]
+        csv: CsvArgs,
+    },
+    /// 资产组合：ETH 加上代币清单中每个代币的余额，配置了喂价时显示美元价值和占比
+    Portfolio {
+        /// 要查询的地址
+        address: String,
+        /// 代币清单文件，每行 `代币地址[,USD 喂价地址]`，`#` 开头为注释
+        #[arg(long, value_name = "PATH")]
+        tokens: std::path::PathBuf,
+        #[command(flatten)]
+        csv: CsvArgs,
+        #[command(flatten)]
+        json: JsonArgs,
+    },
+    /// 列出地址最近 N 个区块中发出或收到的交易（从新到旧）和 ETH 的净变化，Ctrl-C 中断扫描
+    Recent {
+        /// 要查询的地址
+        address: String,
+        /// 扫描最新的多少个区块
+        #[arg(
+            long,
+            default_value_t = recent::DEFAULT_BLOCKS,
+            value_parser = clap::value_parser!(u64).range(1..=recent::MAX_BLOCKS)
+        )]
+        blocks: u64,
+    },
+    /// 对比地址在两个区块的余额，显示变化量、两个区块的时间戳和平均每天的变化（需要节点保存两个区块的状态）
+    Diff {
+        /// 要查询的地址
+        address: String,
+        /// 较早的区块号
+        #[arg(long)]
+        from_block: u64,
+        /// 较晚的区块号
+        #[arg(long)]
+        to_block: u64,
+        /// 改为对比这个 ERC20 代币的余额（balanceOf）
+        #[arg(long, value_name = "ADDRESS")]
+        token: Option<String>,
+    },
+    /// 对比顺序 / 并发 / Multicall / 批量 HTTP 四种方式查询余额的耗时
+    Bench {
+        /// 查询的地址数量
+        #[arg(long, default_value_t = 50)]
+        count: usize,
+        /// 每种方式运行的轮数
+        #[arg(long, default_value_t = 3)]
+        rounds: usize,
+        /// RPC 节点地址（默认为当前网络的节点，本地 anvil 可传 http://127.0.0.1:8545）
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+}
 
 /// 查询指定地址在 Arbitrum 测试网的 ETH 余额
 ///
 /// # 参数
+/// * `provider` - Provider 引用
 /// * `address` - 要查询的以太坊地址（字符串格式，如 "0x..."）
 ///
 /// # 返回
-/// * `Result<String, Box<dyn Error>>` - 格式化后的余额（ETH 单位）
-async fn get_balance(address: &str) -> Result<String, Box<dyn Error>> {
-    // Arbitrum Sepolia 测试网 RPC URL
-    let rpc_url = "https://Arbitrum-sepolia-rpc.publicnode.com";
-
-    // 创建 HTTP Provider
-    let provider = Provider::<Http>::try_from(rpc_url)?;
-
+/// * `Result<U256, Box<dyn Error>>` - 余额（wei）
+async fn get_balance(provider: &RpcProvider, address: &str) -> Result<U256, Box<dyn Error>> {
     // 解析地址
     let address: Address = address.parse()?;
 
     // 查询余额（返回 U256，单位为 wei）
     let balance = provider.get_balance(address, None).await?;
+    info!(balance_wei = %balance, "balance fetched");
+    Ok(balance)
+}
 
-    // 将 wei 转换为 ETH（18 位小数）
-    let balance_in_eth = format_units(balance, "ether")?;
+/// 在 L1 上通过 ENS 解析名称
+///
+/// ENS 注册表部署在以太坊主网，所以这里单独连接 L1 节点，余额仍然在 Arbitrum 上查询。
+///
+/// # 参数
+/// * `ens_rpc_url` - L1 RPC 节点地址
+/// * `name` - ENS 名称
+///
+/// # 返回
+/// * `Result<Address, Box<dyn Error>>` - 解析出的地址
+async fn resolve_ens(ens_rpc_url: &str, name: &str) -> Result<Address, Box<dyn Error>> {
+    let l1_provider = rpc::connect(ens_rpc_url)?;
+    let address = l1_provider.resolve_name(name).await?;
+    Ok(address)
+}
 
-    Ok(balance_in_eth)
+/// 批量查询结果的排序和筛选
+struct BatchRanking {
+    /// 排序方式和是否降序，`None` 时保持输入顺序
+    sort: Option<(batch::SortKey, bool)>,
+    /// 只保留余额最大的 N 个地址
+    top: Option<usize>,
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-   
-    let test_address = "0x51F14ab69C8f748F72b6DB1Aa66875faf7c24Bd2";
+/// 批量查询并打印多个地址的余额
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `addresses` - 地址字符串列表
+/// * `ranking` - 排序和筛选
+/// * `csv` - CSV 导出选项（与屏幕上显示的行相同）
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_batch(
+    provider: &RpcProvider,
+    addresses: &[String],
+    ranking: &BatchRanking,
+    csv: &CsvArgs,
+) -> Result<(), Box<dyn Error>> {
+    let addrs = addresses
+        .iter()
+        .map(|addr| addr.parse::<Address>())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    narrate!("{}\n", tf(Msg::BatchQuerying, &[&addrs.len()]));
+    let balances = batch::get_balances(provider, &addrs).await?;
+    let mut entries: Vec<(Address, U256)> = addrs.iter().copied().zip(balances).collect();
+    for (addr, balance) in &entries {
+        info!(address = ?addr, balance_wei = %balance, "balance fetched");
+    }
+
+    let mut share = None;
+    if let Some(n) = ranking.top {
+        let (top, top_share) = batch::top_holders(&entries, n);
+        entries = top;
+        share = Some(top_share);
+    }
+    if let Some((key, desc)) = ranking.sort {
+        batch::sort_balances(&mut entries, key, desc);
+    }
+
+    let mut table = Table::new(["address", "balance_wei", "balance_eth"]);
+    for (addr, balance) in &entries {
+        let formatted = units::format_balance(*balance);
+        println!("{:?}  {} ETH", addr, formatted);
+        table.push([format!("{:?}", addr), balance.to_string(), formatted]);
+    }
+    if let Some(share) = share {
+        let percent = share.bps.map(portfolio::format_bps).unwrap_or_else(|| "-".to_string());
+        let summary = tf(
+            Msg::BatchTopShare,
+            &[
+                &entries.len(),
+                &units::format_balance(share.top),
+                &addrs.len(),
+                &units::format_balance(share.total),
+                &percent,
+            ],
+        );
+        println!("\n{}", summary);
+    }
+
+    csv.write(&table)
+}
+
+/// 查询并打印资产组合
+///
+/// ETH 的喂价与 `--usd` 相同（网络默认配置或 `ARBI_ETH_USD_FEED`），代币的喂价来自代币清单。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 要查询的地址
+/// * `tokens` - 代币清单文件
+/// * `csv` - CSV 导出选项
+/// * `json` - JSON 导出选项
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_portfolio(
+    provider: &RpcProvider,
+    address: &str,
+    tokens: &Path,
+    csv: &CsvArgs,
+    json: &JsonArgs,
+) -> Result<(), Box<dyn Error>> {
+    let owner: Address = address.parse()?;
+    let tokens = portfolio::load_token_list(tokens)?;
+    let eth_feed = price_feed::eth_usd_feed()?;
+    narrate!("{}\n", tf(Msg::PortfolioQuerying, &[&style::addr(address), &tokens.len()]));
+
+    let holdings = portfolio::read_holdings(provider, owner, &tokens, eth_feed).await?;
+    let values = portfolio::usd_values(provider, &holdings).await;
+    let summary = portfolio::summarize(&values);
+    portfolio::print(&holdings, &values, &summary);
+
+    let table = portfolio::table(&holdings, &values, &summary);
+    csv.write(&table)?;
+    json.write(&table)
+}
+
+/// 扫描最近的区块，打印地址发出或收到的交易和净变化
+///
+/// 扫描可以用 Ctrl-C 中断，此时返回 `Interrupted`，不打印不完整的结果。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 要查询的地址
+/// * `blocks` - 扫描最新的多少个区块
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_recent(
+    provider: &RpcProvider,
+    address: &str,
+    blocks: u64,
+) -> Result<(), Box<dyn Error>> {
+    let owner: Address = address.parse()?;
+    let to = provider.get_block_number().await?.as_u64();
+    let from = to.saturating_sub(blocks - 1);
+    narrate!("{}\n", tf(Msg::RecentScanning, &[&style::addr(address), &from, &to]));
+
+    let transfers = tokio::select! {
+        transfers = recent::scan(provider, owner, from, to) => transfers?,
+        _ = tokio::signal::ctrl_c() => return Err(Interrupted.into()),
+    };
+    recent::print(&transfers, from, to);
+    Ok(())
+}
+
+/// 对比地址在两个区块的余额
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 要查询的地址
+/// * `(from, to)` - 较早和较晚的区块号
+/// * `token` - 代币合约地址，为 `None` 时对比 ETH 余额
+async fn run_diff(
+    provider: &RpcProvider,
+    address: &str,
+    (from, to): (u64, u64),
+    token: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    if from > to {
+        return Err(tf(Msg::DiffRangeReversed, &[&from, &to]).into());
+    }
+    let owner: Address = address.parse()?;
+    let token = match token {
+        Some(token) => Some(watch::watched_token(provider, token.parse()?).await?),
+        None => None,
+    };
+    let symbol = token.as_ref().map_or("ETH", |token| token.symbol.as_str());
+    narrate!("{}\n", tf(Msg::DiffQuerying, &[&symbol, &style::addr(address), &from, &to]));
+
+    let (before, after) = tokio::try_join!(
+        diff::balance_at(provider, owner, token.as_ref(), from),
+        diff::balance_at(provider, owner, token.as_ref(), to),
+    )?;
+    diff::print(&before, &after, token.as_ref());
+    Ok(())
+}
+
+/// 按 ETH/USD 喂价打印余额的美元参考值
+///
+/// 没有配置喂价或读取失败时只打印提示，不影响余额查询的结果。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `balance` - 余额（wei）
+async fn print_usd(provider: &RpcProvider, balance: U256) {
+    let price = match price_feed::eth_usd_feed() {
+        Ok(Some(feed)) => price_feed::latest_price(provider, feed).await,
+        Ok(None) => {
+            let network = network::current().name();
+            let hint = tf(Msg::UsdNoFeed, &[&network, &price_feed::ETH_USD_FEED_ENV]);
+            println!("{}", style::warn(hint));
+            return;
+        }
+        Err(e) => Err(e),
+    };
+    match price {
+        Ok(price) => {
+            let cents = price.usd_cents(balance);
+            info!(
+                usd_cents = %cents,
+                feed = ?price.feed,
+                updated_at = price.updated_at,
+                "usd value computed"
+            );
+            let age = format_age(price.age(SystemTime::now()));
+            let rate = price_feed::format_cents(price.eth_cents());
+            println!("{}", style::ok(tf(Msg::UsdValue, &[&price_feed::format_cents(cents)])));
+            println!("{}", tf(Msg::UsdSource, &[&rate, &age]));
+        }
+        Err(e) => println!("{}", style::warn(tf(Msg::UsdFailed, &[&e]))),
+    }
+}
 
-    println!("正在查询地址 {} 的余额...", test_address);
+// 喂价的更新时间间隔从几秒到一天不等，按大小选单位
+fn format_age(age: Duration) -> String {
+    match age.as_secs() {
+        secs if secs < 120 => format!("{}s", secs),
+        secs if secs < 7200 => format!("{}min", secs / 60),
+        secs => format!("{:.1}h", secs as f64 / 3600.0),
+    }
+}
 
-    match get_balance(test_address).await {
-        Ok(balance) => {
-            println!("余额: {} ETH", balance);
+/// 在多个节点上对比余额，有节点不一致、落后或查询失败时以退出码 1 退出
+///
+/// # 参数
+/// * `rpc_urls` - 参与对比的节点
+/// * `address` - 要查询的地址
+async fn run_consensus(rpc_urls: &[String], address: &str) {
+    narrate!("{}", tf(Msg::ConsensusQuerying, &[&style::addr(address), &rpc_urls.len()]));
+    let span = info_span!("consensus", address, endpoints = rpc_urls.len());
+    let result = async { consensus::check(rpc_urls, address.parse()?).await }
+        .instrument(span)
+        .await;
+    match result {
+        Ok((readings, consensus)) => {
+            consensus::print(&readings, &consensus);
+            if !consensus.is_unanimous() {
+                std::process::exit(1);
+            }
         }
         Err(e) => {
-            eprintln!("查询余额失败: {}", e);
+            eprintln!("{}", style::err(tf(Msg::ConsensusCheckFailed, &[&e])));
+            std::process::exit(1);
         }
     }
+}
 
+/// 持续监控地址的余额和转入
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 要监控的地址
+/// * `interval` - 轮询间隔
+/// * `notify_incoming` - 是否找出转入交易
+/// * `token` - 同时监控的代币合约地址
+/// * `exec` - 每笔转入执行的命令
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_watch(
+    provider: &RpcProvider,
+    address: &str,
+    interval: Duration,
+    notify_incoming: bool,
+    token: Option<&str>,
+    exec: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let watched: Address = address.parse()?;
+    let token = match token {
+        Some(token) => Some(watch::watched_token(provider, token.parse()?).await?),
+        None => None,
+    };
+    let options = watch::WatchOptions {
+        interval,
+        notify_incoming,
+        token,
+        exec,
+    };
+    watch::watch(provider, watched, &options).await
+}
+
+/// 对比 pending 和 latest 余额，需要时等待 latest 追上 pending
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 要查询的地址
+/// * `wait_secs` - 等待的超时时间（秒），`None` 表示不等待
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn show_pending(
+    provider: &RpcProvider,
+    address: Address,
+    wait_secs: Option<u64>,
+) -> Result<(), Box<dyn Error>> {
+    let balance = pending::PendingBalance::fetch(provider, address).await?;
+    info!(latest_wei = %balance.latest, pending_wei = %balance.pending, "pending balance fetched");
+    balance.print();
+
+    let Some(wait_secs) = wait_secs else {
+        return Ok(());
+    };
+    if balance.delta() == pending::PendingDelta::Same {
+        return Ok(());
+    }
+    let started = Instant::now();
+    let timeout = Duration::from_secs(wait_secs);
+    match pending::wait_for_pending(provider, address, pending::POLL_INTERVAL, timeout).await? {
+        Some(balance) => {
+            let latest = units::format_balance(balance.latest);
+            let waited = started.elapsed().as_secs();
+            println!("{}", style::ok(tf(Msg::PendingCaughtUp, &[&latest, &waited])));
+        }
+        None => println!("{}", style::warn(tf(Msg::PendingTimeout, &[&wait_secs]))),
+    }
     Ok(())
 }
 
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    cli.global.init();
+    units::init_precision(cli.precision);
 
+    // 创建 HTTP Provider
+    let rpc_url = network::current().rpc_url();
+    let provider = rpc::connect(rpc_url)?;
+
+    match cli.command {
+        Some(Command::Batch {
+            addresses,
+            sort,
+            desc,
+            top,
+            csv,
+        }) => {
+            let span = info_span!("batch", rpc_url, addresses = addresses.len());
+            let ranking = BatchRanking {
+                sort: sort.map(|key| (key, desc)),
+                top: top.map(|n| n as usize),
+            };
+            let result = run_batch(&provider, &addresses, &ranking, &csv);
+            if let Err(e) = result.instrument(span).await {
+                eprintln!("{}", style::err(tf(Msg::BatchFailed, &[&e])));
+            }
+        }
+        Some(Command::Portfolio {
+            address,
+            tokens,
+            csv,
+            json,
+        }) => {
+            let span = info_span!("portfolio", rpc_url, address);
+            let result = run_portfolio(&provider, &address, &tokens, &csv, &json);
+            if let Err(e) = result.instrument(span).await {
+                eprintln!("{}", style::err(tf(Msg::PortfolioFailed, &[&e])));
+                std::process::exit(1);
+            }
+        }
+        Some(Command::Recent { address, blocks }) => {
+            let span = info_span!("recent", rpc_url, address, blocks);
+            match run_recent(&provider, &address, blocks).instrument(span).await {
+                Ok(()) => {}
+                Err(e) if e.is::<Interrupted>() => {
+                    eprintln!("\n{}", style::warn(e));
+                    std::process::exit(INTERRUPTED_EXIT_CODE);
+                }
+                Err(e) => {
+                    eprintln!("{}", style::err(tf(Msg::RecentFailed, &[&e])));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Command::Diff {
+            address,
+            from_block,
+            to_block,
+            token,
+        }) => {
+            let span = info_span!("diff", rpc_url, address, from_block, to_block);
+            let diff = run_diff(&provider, &address, (from_block, to_block), token.as_deref());
+            if let Err(e) = diff.instrument(span).await {
+                eprintln!("{}", style::err(tf(Msg::DiffFailed, &[&e])));
+                std::process::exit(1);
+            }
+        }
+        Some(Command::Bench {
+            count,
+            rounds,
+            rpc_url: bench_rpc_url,
+        }) => {
+            let bench_rpc_url = bench_rpc_url.as_deref().unwrap_or(rpc_url);
+            if let Err(e) = bench::run_balance_bench(bench_rpc_url, count, rounds).await {
+                eprintln!("{}", style::err(tf(Msg::BenchFailed, &[&e])));
+            }
+        }
+        None => {
+            let input = cli.address.as_deref().unwrap_or(TEST_ADDRESS);
+
+            // 不是地址但像域名时按 ENS 名称处理，解析失败和余额查询失败分开报错
+            let address = if input.parse::<Address>().is_err() && input.contains('.') {
+                narrate!("{}", tf(Msg::EnsResolving, &[&input]));
+                let span = info_span!("resolve", ens_rpc_url = %cli.ens_rpc_url, name = input);
+                match resolve_ens(&cli.ens_rpc_url, input).instrument(span).await {
+                    Ok(resolved) => {
+                        let resolved = format!("{:?}", resolved);
+                        println!(
+                            "{}",
+                            style::ok(tf(Msg::EnsResolved, &[&input, &style::addr(&resolved)]))
+                        );
+                        resolved
+                    }
+                    Err(e) => {
+                        eprintln!("{}", style::err(tf(Msg::EnsNotFound, &[&input, &e])));
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                input.to_string()
+            };
+            let address = address.as_str();
+
+            if cli.consensus {
+                let rpc_urls = match cli.consensus_rpcs.is_empty() {
+                    true => network::current().rpc_urls().iter().map(|u| u.to_string()).collect(),
+                    false => cli.consensus_rpcs,
+                };
+                run_consensus(&rpc_urls, address).await;
+                return Ok(());
+            }
+
+            if let Some(secs) = cli.watch {
+                let interval = Duration::from_secs(secs);
+                let (notify, token, exec) = (cli.notify_incoming, cli.token.as_deref(), cli.exec);
+                let watch = run_watch(&provider, address, interval, notify, token, exec);
+                let span = info_span!("watch", rpc_url, address);
+                if let Err(e) = watch.instrument(span).await {
+                    eprintln!("{}", style::err(tf(Msg::WatchFailed, &[&e])));
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+
+            narrate!("{}", tf(Msg::Querying, &[&style::addr(address)]));
+
+            let span = info_span!("query", rpc_url, address);
+            match get_balance(&provider, address).instrument(span).await {
+                Ok(balance) => {
+                    // 将 wei 转换为 ETH，按 --precision 四舍五入；-v 时另外显示精确的 wei
+                    let balance_eth = units::format_balance(balance);
+                    println!("{}", style::ok(tf(Msg::Balance, &[&balance_eth])));
+                    if logging::is_verbose() {
+                        println!("{}", tf(Msg::BalanceWei, &[&units::group_digits(balance)]));
+                    }
+                    if cli.usd {
+                        print_usd(&provider, balance).await;
+                    }
+                    if (cli.show_pending || cli.wait_for_pending.is_some())
+                        && let Ok(parsed) = address.parse::<Address>()
+                        && let Err(e) = show_pending(&provider, parsed, cli.wait_for_pending).await
+                    {
+                        eprintln!("{}", style::err(tf(Msg::PendingFailed, &[&e])));
+                    }
+                    // get_balance 已经解析过地址，这里只会在二维码出错时失败
+                    if let Ok(parsed) = address.parse::<Address>()
+                        && let Err(e) = cli.qr.print(&parsed)
+                    {
+                        eprintln!("{}", style::err(tf(Msg::QrFailed, &[&e])));
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{}", style::err(tf(Msg::QueryFailed, &[&e])));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+//! level2 输出的消息目录
+
+arbi_common::define_messages! {
+    Querying => ("正在 L2（Arbitrum Sepolia）上查询地址 {} 的余额...", "Querying the balance of {} on L2 (Arbitrum Sepolia)..."),
+    Balance => ("余额: {} ETH", "Balance: {} ETH"),
+    BalanceWei => ("精确余额: {} wei", "Exact balance: {} wei"),
+    QueryFailed => ("查询余额失败: {}", "Failed to query balance: {}"),
+    QrFailed => ("无法生成二维码: {}", "Failed to render the QR code: {}"),
+    EnsResolving => ("正在 L1（以太坊主网）上解析 ENS 名称 {}...", "Resolving ENS name {} on L1 (Ethereum mainnet)..."),
+    EnsResolved => ("✓ ENS 解析（L1）: {} → {}", "✓ ENS resolved (L1): {} → {}"),
+    EnsNotFound => ("❌ 无法解析 ENS 名称 {}: {}", "❌ Could not resolve ENS name {}: {}"),
+    PendingSame => (
+        "pending 余额: {} ETH（与 latest 相同，没有未确认的交易）",
+        "Pending balance: {} ETH (same as latest, no unconfirmed transactions)"
+    ),
+    PendingIncoming => ("pending 余额: {} ETH（比 latest 多 +{} ETH）", "Pending balance: {} ETH (+{} ETH vs latest)"),
+    PendingIncomingHint => (
+        "  差额来自还没打包进区块的交易：有人刚给这个地址转账，确认后会计入 latest 余额",
+        "  The difference comes from transactions not yet in a block: incoming funds will show up in the latest balance once confirmed"
+    ),
+    PendingOutgoing => ("pending 余额: {} ETH（比 latest 少 -{} ETH）", "Pending balance: {} ETH (-{} ETH vs latest)"),
+    PendingOutgoingHint => (
+        "  差额来自还没打包进区块的交易：这个地址发出的交易（金额加 Gas 费）已从 pending 余额中扣除",
+        "  The difference comes from transactions not yet in a block: outgoing spends (value plus gas) are already deducted from the pending balance"
+    ),
+    PendingWaiting => ("latest 余额仍是 {} ETH，等待 pending 交易确认...", "Latest balance still {} ETH, waiting for pending transactions to confirm..."),
+    PendingCaughtUp => ("✓ latest 已追上 pending，余额: {} ETH（等待 {} 秒）", "✓ Latest caught up with pending, balance: {} ETH (waited {}s)"),
+    PendingTimeout => (
+        "⚠ {} 秒内 latest 没有追上 pending，交易可能还在排队或已被替换",
+        "⚠ Latest did not catch up with pending within {}s; the transaction may still be queued or was replaced"
+    ),
+    PendingFailed => ("查询 pending 余额失败: {}", "Failed to query the pending balance: {}"),
+    UsdValue => ("  ≈ {}（参考值）", "  ≈ {} (indicative)"),
+    UsdSource => (
+        "  按 Chainlink ETH/USD 喂价 {} 换算，价格 {} 前更新，仅供参考",
+        "  Converted at the Chainlink ETH/USD feed price {}, updated {} ago; for reference only"
+    ),
+    UsdNoFeed => (
+        "⚠ {} 上没有配置 ETH/USD 喂价，不显示美元价值（可用 {} 指定喂价合约）",
+        "⚠ No ETH/USD price feed is configured on {}, skipping the USD value (set {} to a feed contract)"
+    ),
+    UsdFailed => ("⚠ 无法读取 ETH/USD 喂价，不显示美元价值: {}", "⚠ Could not read the ETH/USD price feed, skipping the USD value: {}"),
+    ConsensusQuerying => ("正在 {} 个 RPC 节点上对比地址 {} 的余额...", "Cross-checking the balance of {} on {} RPC endpoints..."),
+    ConsensusTooFew => ("至少需要 2 个 RPC 节点才能对比", "At least 2 RPC endpoints are needed for a cross-check"),
+    ConsensusBlock => ("区块 #{}（取自第一个节点），{} 个节点:", "Block #{} (from the first endpoint), {} endpoints:"),
+    ConsensusEndpoint => ("{}: {}（节点最新区块 {}）", "{}: {} (endpoint head {})"),
+    ConsensusLagging => ("⚠ 落后: {}", "⚠ Lagging: {}"),
+    ConsensusInconsistent => ("❌ 不一致: {}", "❌ Inconsistent: {}"),
+    ConsensusFailed => ("❌ 查询失败: {}", "❌ Failed: {}"),
+    ConsensusUnanimous => ("✓ 所有节点一致，余额: {} ETH", "✓ All endpoints agree, balance: {} ETH"),
+    ConsensusMajority => ("⚠ {} / {} 个节点一致，多数余额: {} ETH", "⚠ {} / {} endpoints agree, majority balance: {} ETH"),
+    ConsensusNone => ("❌ 没有节点在该区块上返回余额", "❌ No endpoint returned a balance at this block"),
+    ConsensusCheckFailed => ("多节点对比失败: {}", "Cross-check failed: {}"),
+    BatchQuerying => ("正在批量查询 {} 个地址的余额...", "Querying balances of {} addresses..."),
+    BatchTopShare => (
+        "前 {} 个地址持有 {} ETH，占 {} 个地址合计 {} ETH 的 {}",
+        "The top {} addresses hold {} ETH out of the {} addresses' total of {} ETH ({})"
+    ),
+    BatchFailed => ("批量查询余额失败: {}", "Batch balance query failed: {}"),
+    MulticallFallback => (
+        "⚠ Multicall3 不可用（{}），改为并发查询",
+        "⚠ Multicall3 unavailable ({}), falling back to concurrent queries"
+    ),
+    PortfolioQuerying => ("正在查询地址 {} 的 ETH 和 {} 个代币的余额...", "Querying ETH and token balances of {} ({} tokens)..."),
+    PortfolioFailed => ("查询资产组合失败: {}", "Portfolio query failed: {}"),
+    TokenListUnreadable => ("无法读取代币清单 {}: {}", "Cannot read the token list {}: {}"),
+    TokenListLine => ("代币清单第 {} 行: `{}` 不是合法的地址: {}", "Token list line {}: `{}` is not a valid address: {}"),
+    PortfolioCallFailed => ("{}() 调用失败或没有返回数据", "{}() reverted or returned no data"),
+    PortfolioBadOutput => ("{}() 的返回值无法解析", "{}() returned an unexpected value"),
+    PortfolioPriceFailed => ("⚠ 无法读取 {} 的喂价，不计入合计: {}", "⚠ Could not read the price feed for {}, leaving it out of the total: {}"),
+    PortfolioAssetFailed => ("✗ 读取失败: {}", "✗ unavailable: {}"),
+    PortfolioTotal => ("合计 ≈ {}（{} / {} 项资产有喂价，仅供参考）", "Total ≈ {} ({} of {} assets priced, for reference only)"),
+    PortfolioNoPrices => ("没有配置喂价，不显示美元价值和占比", "No price feeds configured, skipping USD values and shares"),
+    PortfolioFailedCount => ("⚠ {} 项资产读取失败，未计入合计", "⚠ {} asset(s) could not be read and are left out of the total"),
+    WatchStarted => (
+        "开始监控，当前余额 {} ETH（区块 #{}），每 {} 秒轮询一次，Ctrl-C 退出",
+        "Watching from balance {} ETH (block #{}), polling every {}s; Ctrl-C to stop"
+    ),
+    WatchIncrease => ("区块 #{}: 余额增加 +{} ETH，现在 {} ETH", "Block #{}: balance up +{} ETH, now {} ETH"),
+    WatchDecrease => ("区块 #{}: 余额减少 -{} ETH，现在 {} ETH", "Block #{}: balance down -{} ETH, now {} ETH"),
+    WatchIncoming => ("📥 区块 #{}: 收到 {} {}，来自 {}", "📥 Block #{}: received {} {} from {}"),
+    WatchPollFailed => ("⚠ 轮询失败，稍后重试: {}", "⚠ Poll failed, retrying: {}"),
+    WatchScanFailed => ("⚠ 扫描区块 #{}–#{} 失败，下次轮询重新扫描: {}", "⚠ Scanning blocks #{}–#{} failed, will rescan on the next poll: {}"),
+    WatchBlockMissing => ("节点还没有区块 #{}", "The node does not have block #{} yet"),
+    WatchFailed => ("监控失败: {}", "Watch failed: {}"),
+    RecentScanning => ("正在扫描地址 {} 在区块 #{}–#{} 中的交易...", "Scanning transactions of {} in blocks #{}–#{}..."),
+    RecentProgress => ("已扫描 {} / {} 个区块，找到 {} 笔交易", "Scanned {} / {} blocks, found {} transactions"),
+    RecentReceiptMissing => ("节点没有交易 {} 的收据", "The node has no receipt for transaction {}"),
+    RecentNone => ("区块 #{}–#{} 中没有发出或收到的交易", "No transactions sent or received in blocks #{}–#{}"),
+    RecentIn => ("收到", "IN  "),
+    RecentOut => ("发出", "OUT "),
+    RecentSelf => ("自转", "SELF"),
+    RecentContractCreation => ("（部署合约）", "(contract creation)"),
+    RecentTxFailed => ("✗ 失败", "✗ failed"),
+    RecentNet => (
+        "区块 #{}–#{} 的净变化: {} ETH（收到 {}，发出含 Gas 费 {}；不含合约内部转账）",
+        "Net change over blocks #{}–#{}: {} ETH (received {}, sent incl. gas {}; internal transfers not included)"
+    ),
+    RecentFailed => ("列出最近的交易失败: {}", "Listing recent transactions failed: {}"),
+    DiffQuerying => ("正在查询 {} 余额：地址 {}，区块 #{} 和 #{}...", "Querying {} balance of {} at blocks #{} and #{}..."),
+    DiffRangeReversed => ("--from-block {} 大于 --to-block {}", "--from-block {} is greater than --to-block {}"),
+    DiffStateUnavailable => (
+        "节点没有区块 #{} 的状态（{}），读取历史余额需要归档节点，请换用归档 RPC",
+        "The node has no state for block #{} ({}); historical balances need an archive node, use an archive RPC"
+    ),
+    DiffAtBlock => ("区块 #{}（时间戳 {}）: {}", "Block #{} (timestamp {}): {}"),
+    DiffChange => ("变化: {}，间隔 {}", "Change: {} over {}"),
+    DiffPerDay => ("平均每天: {}", "Average per day: {}"),
+    DiffSameTimestamp => ("两个区块的时间戳相同，无法计算平均每天的变化", "Both blocks have the same timestamp; the average change per day cannot be computed"),
+    DiffFailed => ("对比余额失败: {}", "Balance diff failed: {}"),
+    BenchRpc => ("RPC: {}", "RPC: {}"),
+    BenchStart => (
+        "查询 {} 个地址的余额，每种方式运行 {} 轮...",
+        "Querying balances of {} addresses, {} rounds per strategy..."
+    ),
+    BenchSkipped => ("⚠ 跳过 {}（{}）", "⚠ Skipped {} ({})"),
+    BenchTitle => ("{} 个地址余额查询", "Balance lookups for {} addresses"),
+    BenchMismatch => (
+        "⚠ {} 的结果与 sequential 不一致（查询期间可能出了新区块）",
+        "⚠ {} returned different results than sequential (a new block may have arrived)"
+    ),
+    BenchFailed => ("基准测试失败: {}", "Benchmark failed: {}"),
+}
@@ -0,0 +1,198 @@
+//! 对比 `pending` 和 `latest` 两个区块标签的余额，显示还没确认的转入 / 转出
+
+use arbi_common::narrate;
+use arbi_common::style;
+use arbi_common::units;
+use ethers::providers::Middleware;
+use ethers::types::{Address, BlockId, BlockNumber, U256};
+use std::error::Error;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::messages::{Msg, t, tf};
+
+/// 等待 latest 追上 pending 时的轮询间隔
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// pending 相对 latest 的变化
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PendingDelta {
+    /// 两个标签的余额相同，没有未确认的交易
+    Same,
+    /// pending 比 latest 多：有未确认的转入
+    Incoming(U256),
+    /// pending 比 latest 少：有未确认的转出（金额加 Gas 费）
+    Outgoing(U256),
+}
+
+/// 计算 pending 相对 latest 的变化，两个方向都不会溢出
+///
+/// # 参数
+/// * `latest` - `latest` 标签的余额（wei）
+/// * `pending` - `pending` 标签的余额（wei）
+///
+/// # 返回
+/// * `PendingDelta` - 变化方向和差额
+pub fn pending_delta(latest: U256, pending: U256) -> PendingDelta {
+    match pending.cmp(&latest) {
+        std::cmp::Ordering::Equal => PendingDelta::Same,
+        std::cmp::Ordering::Greater => PendingDelta::Incoming(pending - latest),
+        std::cmp::Ordering::Less => PendingDelta::Outgoing(latest - pending),
+    }
+}
+
+/// 同一个地址在 `latest` 和 `pending` 两个标签下的余额
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PendingBalance {
+    pub latest: U256,
+    pub pending: U256,
+}
+
+impl PendingBalance {
+    /// 读取两个标签的余额
+    ///
+    /// # 参数
+    /// * `provider` - Provider 引用（任意 `Middleware`，测试时可以换成模拟的 Provider）
+    /// * `address` - 要查询的地址
+    ///
+    /// # 返回
+    /// * `Result<PendingBalance, Box<dyn Error>>` - 两个标签的余额（wei）
+    pub async fn fetch<M: Middleware + 'static>(
+        provider: &M,
+        address: Address,
+    ) -> Result<Self, Box<dyn Error>> {
+        let at = |tag| Some(BlockId::Number(tag));
+        let latest = provider.get_balance(address, at(BlockNumber::Latest)).await?;
+        let pending = provider.get_balance(address, at(BlockNumber::Pending)).await?;
+        Ok(Self { latest, pending })
+    }
+
+    /// pending 相对 latest 的变化
+    pub fn delta(&self) -> PendingDelta {
+        pending_delta(self.latest, self.pending)
+    }
+
+    /// 打印 pending 余额和差额，并解释差额的含义
+    pub fn print(&self) {
+        let pending = units::format_balance(self.pending);
+        match self.delta() {
+            PendingDelta::Same => println!("{}", tf(Msg::PendingSame, &[&pending])),
+            PendingDelta::Incoming(delta) => {
+                let delta = units::format_balance(delta);
+                println!("{}", style::ok(tf(Msg::PendingIncoming, &[&pending, &delta])));
+                println!("{}", t(Msg::PendingIncomingHint));
+            }
+            PendingDelta::Outgoing(delta) => {
+                let delta = units::format_balance(delta);
+                println!("{}", style::warn(tf(Msg::PendingOutgoing, &[&pending, &delta])));
+                println!("{}", t(Msg::PendingOutgoingHint));
+            }
+        }
+    }
+}
+
+/// 每隔 `interval` 重新读取两个标签的余额，直到 latest 与 pending 一致或超时
+///
+/// 用 tokio 的时钟计时，测试里可以用 `tokio::time::pause()` 控制时间。
+///
+/// # 参数
+/// * `provider` - Provider 引用（任意 `Middleware`）
+/// * `address` - 要查询的地址
+/// * `interval` - 轮询间隔
+/// * `timeout` - 最长等待时间
+///
+/// # 返回
+/// * `Result<Option<PendingBalance>, Box<dyn Error>>` - 一致时的余额；超时为 `None`
+pub async fn wait_for_pending<M: Middleware + 'static>(
+    provider: &M,
+    address: Address,
+    interval: Duration,
+    timeout: Duration,
+) -> Result<Option<PendingBalance>, Box<dyn Error>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let balance = PendingBalance::fetch(provider, address).await?;
+        if balance.delta() == PendingDelta::Same {
+            return Ok(Some(balance));
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(None);
+        }
+        narrate!("{}", tf(Msg::PendingWaiting, &[&units::format_balance(balance.latest)]));
+        tokio::time::sleep(interval.min(deadline - now)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::{MockProvider, Provider};
+
+    const ADDRESS: Address = Address::repeat_byte(0x11);
+
+    // 按顺序每轮返回 (latest, pending)；MockProvider 后进先出，所以倒着放
+    fn scripted(rounds: &[(u64, u64)]) -> (Provider<MockProvider>, MockProvider) {
+        let (provider, mock) = Provider::mocked();
+        for &(latest, pending) in rounds.iter().rev() {
+            mock.push(U256::from(pending)).unwrap();
+            mock.push(U256::from(latest)).unwrap();
+        }
+        (provider, mock)
+    }
+
+    fn balance(latest: u64, pending: u64) -> PendingBalance {
+        PendingBalance {
+            latest: U256::from(latest),
+            pending: U256::from(pending),
+        }
+    }
+
+    #[test]
+    fn delta_never_overflows() {
+        assert_eq!(pending_delta(U256::from(7), U256::from(7)), PendingDelta::Same);
+        assert_eq!(pending_delta(U256::zero(), U256::MAX), PendingDelta::Incoming(U256::MAX));
+        assert_eq!(pending_delta(U256::MAX, U256::zero()), PendingDelta::Outgoing(U256::MAX));
+        assert_eq!(balance(10, 4).delta(), PendingDelta::Outgoing(U256::from(6)));
+    }
+
+    #[tokio::test]
+    async fn fetch_reads_latest_then_pending() {
+        let (provider, mock) = scripted(&[(100, 250)]);
+        let fetched = PendingBalance::fetch(&provider, ADDRESS).await.unwrap();
+        assert_eq!(fetched, balance(100, 250));
+        assert_eq!(fetched.delta(), PendingDelta::Incoming(U256::from(150)));
+        mock.assert_request("eth_getBalance", (ADDRESS, "latest")).unwrap();
+        mock.assert_request("eth_getBalance", (ADDRESS, "pending")).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waits_until_latest_catches_up() {
+        let (provider, _mock) = scripted(&[(100, 40), (100, 40), (40, 40)]);
+        let started = Instant::now();
+        let interval = Duration::from_secs(2);
+        let settled = wait_for_pending(&provider, ADDRESS, interval, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(settled, Some(balance(40, 40)));
+        assert_eq!(started.elapsed(), Duration::from_secs(4));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gives_up_at_the_deadline() {
+        // 第 0、2 秒各读一次，最后一次睡到截止时间再读
+        let (provider, mock) = scripted(&[(100, 40), (100, 40), (100, 40)]);
+        let started = Instant::now();
+        let interval = Duration::from_secs(2);
+        let settled = wait_for_pending(&provider, ADDRESS, interval, Duration::from_secs(3))
+            .await
+            .unwrap();
+        assert_eq!(settled, None);
+        assert_eq!(started.elapsed(), Duration::from_secs(3));
+        for _ in 0..3 {
+            mock.assert_request("eth_getBalance", (ADDRESS, "latest")).unwrap();
+            mock.assert_request("eth_getBalance", (ADDRESS, "pending")).unwrap();
+        }
+        assert!(mock.assert_request("eth_getBalance", (ADDRESS, "latest")).is_err());
+    }
+}
@@ -0,0 +1,531 @@
+//! 资产组合：ETH 加上代币清单中每个代币的余额，按喂价换算美元并计算各自的占比
+
+use arbi_common::narrate;
+use arbi_common::price_feed;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use arbi_common::table::Table;
+use arbi_common::units;
+use ethers::abi::{Detokenize, Token, Tokenize};
+use ethers::contract::Contract;
+use ethers::prelude::Multicall;
+use ethers::providers::Middleware;
+use ethers::types::{Address, Bytes, U256, U512};
+use futures::future::join_all;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::messages::{Msg, t, tf};
+
+/// 读取代币余额需要的三个只读函数
+pub const ERC20_ABI: [&str; 3] = [
+    "function symbol() view returns (string)",
+    "function decimals() view returns (uint8)",
+    "function balanceOf(address) view returns (uint256)",
+];
+
+// 1 ETH = 10^18 wei
+const ETH_DECIMALS: u8 = 18;
+
+/// 代币清单中的一项
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenEntry {
+    pub address: Address,
+    /// 代币的 Chainlink USD 喂价合约，没有时不换算美元
+    pub usd_feed: Option<Address>,
+}
+
+/// 读取代币清单文件
+///
+/// 每行一个代币，格式为 `代币地址[,USD 喂价地址]`，空行和 `#` 开头的注释行会被跳过。
+///
+/// # 参数
+/// * `path` - 文件路径
+///
+/// # 返回
+/// * `Result<Vec<TokenEntry>, Box<dyn Error>>` - 解析后的代币列表；地址无效时返回错误
+pub fn load_token_list(path: &Path) -> Result<Vec<TokenEntry>, Box<dyn Error>> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| tf(Msg::TokenListUnreadable, &[&path.display(), &e]))?;
+    let mut entries = Vec::new();
+
+    for (line_no, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<Address>()
+                .map_err(|e| tf(Msg::TokenListLine, &[&(line_no + 1), &value.trim(), &e]))
+        };
+        let entry = match line.split_once(',') {
+            Some((address, feed)) => TokenEntry {
+                address: parse(address)?,
+                usd_feed: Some(parse(feed)?),
+            },
+            None => TokenEntry {
+                address: parse(line)?,
+                usd_feed: None,
+            },
+        };
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// 一项资产的查询结果
+#[derive(Clone, Debug)]
+pub struct Holding {
+    /// 资产名称：ETH 或代币的 symbol（读不到 symbol 时为缩写的合约地址）
+    pub asset: String,
+    /// 代币合约地址，ETH 为 `None`
+    pub token: Option<Address>,
+    pub decimals: u8,
+    /// 余额（最小单位），代币没有响应时为错误信息
+    pub balance: Result<U256, String>,
+    pub usd_feed: Option<Address>,
+}
+
+impl Holding {
+    /// 格式化后的余额：ETH 按 `--precision` 取整，代币显示精确值
+    pub fn formatted(&self) -> Option<String> {
+        let balance = *self.balance.as_ref().ok()?;
+        match self.token {
+            None => Some(units::format_balance(balance)),
+            Some(_) => units::format_exact(balance, self.decimals.into()).ok(),
+        }
+    }
+}
+
+// 把代币三个调用的结果合成一项资产；decimals 或 balanceOf 失败时整项标为失败
+fn token_holding(
+    entry: &TokenEntry,
+    symbol: Result<String, String>,
+    decimals: Result<u8, String>,
+    balance: Result<U256, String>,
+) -> Holding {
+    let (decimals, balance) = match (decimals, balance) {
+        (Ok(decimals), Ok(balance)) => (decimals, Ok(balance)),
+        (Err(e), _) | (_, Err(e)) => (0, Err(e)),
+    };
+    Holding {
+        asset: symbol.unwrap_or_else(|_| entry.address.to_string()),
+        token: Some(entry.address),
+        decimals,
+        balance,
+        usd_feed: entry.usd_feed,
+    }
+}
+
+fn eth_holding(balance: U256, eth_feed: Option<Address>) -> Holding {
+    Holding {
+        asset: "ETH".to_string(),
+        token: None,
+        decimals: ETH_DECIMALS,
+        balance: Ok(balance),
+        usd_feed: eth_feed,
+    }
+}
+
+// Multicall 中单个调用的结果：revert 或没有返回数据时为 Err
+fn decoded<T>(
+    result: Option<Result<Token, Bytes>>,
+    function: &str,
+    convert: impl FnOnce(Token) -> Option<T>,
+) -> Result<T, String> {
+    match result {
+        Some(Ok(token)) => convert(token).ok_or_else(|| tf(Msg::PortfolioBadOutput, &[&function])),
+        _ => Err(tf(Msg::PortfolioCallFailed, &[&function])),
+    }
+}
+
+/// 通过 Multicall3 在一次 `eth_call` 中读取 ETH 余额和每个代币的 symbol / decimals / balanceOf
+///
+/// 代币的调用都允许失败，某个代币没有响应时只影响它自己那一项。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `owner` - 要查询的地址
+/// * `tokens` - 代币清单
+/// * `eth_feed` - ETH/USD 喂价合约
+///
+/// # 返回
+/// * `Result<Vec<Holding>, Box<dyn Error>>` - ETH 在前、代币按清单顺序排列的资产
+pub async fn read_multicall(
+    provider: &RpcProvider,
+    owner: Address,
+    tokens: &[TokenEntry],
+    eth_feed: Option<Address>,
+) -> Result<Vec<Holding>, Box<dyn Error>> {
+    let client = Arc::new(provider.clone());
+    let abi = ethers::abi::parse_abi(&ERC20_ABI)?;
+    let mut multicall = Multicall::new(client.clone(), None).await?;
+    multicall.add_get_eth_balance(owner, false);
+    for entry in tokens {
+        let contract = Contract::new(entry.address, abi.clone(), client.clone());
+        multicall
+            .add_call(contract.method::<_, String>("symbol", ())?, true)
+            .add_call(contract.method::<_, u8>("decimals", ())?, true)
+            .add_call(contract.method::<_, U256>("balanceOf", owner)?, true);
+    }
+    let mut results = multicall.call_raw().await?.into_iter();
+
+    let eth = decoded(results.next(), "getEthBalance", |token| token.into_uint())?;
+    let mut holdings = vec![eth_holding(eth, eth_feed)];
+    for entry in tokens {
+        let symbol = decoded(results.next(), "symbol", |token| token.into_string());
+        let decimals = decoded(results.next(), "decimals", |token| {
+            token.into_uint().filter(|d| *d <= U256::from(u8::MAX)).map(|d| d.as_u32() as u8)
+        });
+        let balance = decoded(results.next(), "balanceOf", |token| token.into_uint());
+        holdings.push(token_holding(entry, symbol, decimals, balance));
+    }
+    Ok(holdings)
+}
+
+// 单独调用代币合约的一个只读函数，错误转成字符串
+async fn call_token<T: Detokenize, A: Tokenize>(
+    contract: &Contract<RpcProvider>,
+    function: &str,
+    args: A,
+) -> Result<T, String> {
+    let call = contract.method::<_, T>(function, args).map_err(|e| e.to_string())?;
+    call.call().await.map_err(|e| e.to_string())
+}
+
+/// 逐个代币并发读取，用于节点上没有部署 Multicall3 的情况（例如本地 anvil）
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `owner` - 要查询的地址
+/// * `tokens` - 代币清单
+/// * `eth_feed` - ETH/USD 喂价合约
+///
+/// # 返回
+/// * `Result<Vec<Holding>, Box<dyn Error>>` - 与 [`read_multicall`] 相同；只有 ETH 余额查询失败时返回错误
+pub async fn read_individually(
+    provider: &RpcProvider,
+    owner: Address,
+    tokens: &[TokenEntry],
+    eth_feed: Option<Address>,
+) -> Result<Vec<Holding>, Box<dyn Error>> {
+    let client = Arc::new(provider.clone());
+    let abi = ethers::abi::parse_abi(&ERC20_ABI)?;
+    let eth = provider.get_balance(owner, None).await?;
+
+    // 代币清单通常只有几项，不限制并发
+    let tokens = join_all(tokens.iter().map(|entry| {
+        let contract = Contract::new(entry.address, abi.clone(), client.clone());
+        async move {
+            let (symbol, decimals, balance) = tokio::join!(
+                call_token(&contract, "symbol", ()),
+                call_token(&contract, "decimals", ()),
+                call_token(&contract, "balanceOf", owner),
+            );
+            token_holding(entry, symbol, decimals, balance)
+        }
+    }))
+    .await;
+
+    let mut holdings = vec![eth_holding(eth, eth_feed)];
+    holdings.extend(tokens);
+    Ok(holdings)
+}
+
+/// 读取所有资产：优先使用 Multicall3，失败时退回逐个读取
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `owner` - 要查询的地址
+/// * `tokens` - 代币清单
+/// * `eth_feed` - ETH/USD 喂价合约
+///
+/// # 返回
+/// * `Result<Vec<Holding>, Box<dyn Error>>` - ETH 在前、代币按清单顺序排列的资产
+pub async fn read_holdings(
+    provider: &RpcProvider,
+    owner: Address,
+    tokens: &[TokenEntry],
+    eth_feed: Option<Address>,
+) -> Result<Vec<Holding>, Box<dyn Error>> {
+    match read_multicall(provider, owner, tokens, eth_feed).await {
+        Ok(holdings) => Ok(holdings),
+        Err(e) => {
+            narrate!("{}", style::warn(tf(Msg::MulticallFallback, &[&e])));
+            read_individually(provider, owner, tokens, eth_feed).await
+        }
+    }
+}
+
+/// 按各资产的喂价换算美元价值（美分）
+///
+/// 没有喂价或余额读取失败的资产为 `None`；喂价读取失败时打印警告，同样为 `None`。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `holdings` - 资产列表
+///
+/// # 返回
+/// * `Vec<Option<U512>>` - 与资产顺序一致的美元价值
+pub async fn usd_values(provider: &RpcProvider, holdings: &[Holding]) -> Vec<Option<U512>> {
+    join_all(holdings.iter().map(|holding| async move {
+        let (Ok(balance), Some(feed)) = (&holding.balance, holding.usd_feed) else {
+            return None;
+        };
+        match price_feed::latest_price(provider, feed).await {
+            Ok(price) => Some(price.value_cents(*balance, holding.decimals)),
+            Err(e) => {
+                narrate!("{}", style::warn(tf(Msg::PortfolioPriceFailed, &[&holding.asset, &e])));
+                None
+            }
+        }
+    }))
+    .await
+}
+
+/// 组合的合计和各资产的占比
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Summary {
+    /// 有美元价值的资产之和（美分）
+    pub total_cents: U512,
+    /// 与资产顺序一致的占比（万分之一）；没有美元价值或合计为 0 时为 `None`
+    pub shares: Vec<Option<u32>>,
+    /// 有美元价值的资产数量
+    pub priced: usize,
+}
+
+/// 计算合计和每项资产的占比
+///
+/// 没有价格的资产不计入合计，也没有占比。占比各自四舍五入，加起来可能与 100% 差 0.01%。
+///
+/// # 参数
+/// * `values` - 各资产的美元价值（美分）
+///
+/// # 返回
+/// * `Summary` - 合计和占比
+pub fn summarize(values: &[Option<U512>]) -> Summary {
+    let total_cents = values.iter().flatten().fold(U512::zero(), |sum, value| sum + value);
+    let shares = values
+        .iter()
+        .map(|value| value.and_then(|value| share_bps(value, total_cents)))
+        .collect();
+    Summary {
+        total_cents,
+        shares,
+        priced: values.iter().flatten().count(),
+    }
+}
+
+/// `value` 占 `total` 的比例，以万分之一为单位四舍五入
+///
+/// # 参数
+/// * `value` - 部分
+/// * `total` - 合计，不小于 `value`
+///
+/// # 返回
+/// * `Option<u32>` - 0 到 10000；合计为 0 时为 `None`
+pub fn share_bps(value: U512, total: U512) -> Option<u32> {
+    if total.is_zero() {
+        return None;
+    }
+    let bps = (value * U512::from(10_000) + total / 2) / total;
+    Some(bps.min(U512::from(10_000)).as_u32())
+}
+
+/// 把万分之一格式化为百分比，如 `1234` → `12.34%`
+pub fn format_bps(bps: u32) -> String {
+    format!("{}.{:02}%", bps / 100, bps % 100)
+}
+
+/// 生成导出用的表格，最后一行是合计
+///
+/// # 参数
+/// * `holdings` - 资产列表
+/// * `values` - 各资产的美元价值
+/// * `summary` - `summarize` 的结果
+///
+/// # 返回
+/// * `Table` - 列为 asset、token、balance_raw、balance、usd、share
+pub fn table(holdings: &[Holding], values: &[Option<U512>], summary: &Summary) -> Table {
+    let mut table = Table::new(["asset", "token", "balance_raw", "balance", "usd", "share"]);
+    for ((holding, value), share) in holdings.iter().zip(values).zip(&summary.shares) {
+        let token = holding.token.map(|token| format!("{:?}", token)).unwrap_or_default();
+        let (raw, formatted) = match &holding.balance {
+            Ok(balance) => (balance.to_string(), holding.formatted().unwrap_or_default()),
+            Err(e) => (String::new(), format!("error: {}", e)),
+        };
+        table.push([
+            holding.asset.clone(),
+            token,
+            raw,
+            formatted,
+            value.map(price_feed::format_cents).unwrap_or_default(),
+            share.map(format_bps).unwrap_or_default(),
+        ]);
+    }
+    let total = match summary.priced {
+        0 => String::new(),
+        _ => price_feed::format_cents(summary.total_cents),
+    };
+    table.push(["TOTAL", "", "", "", total.as_str(), ""]);
+    table
+}
+
+/// 打印资产表和合计
+///
+/// # 参数
+/// * `holdings` - 资产列表
+/// * `values` - 各资产的美元价值
+/// * `summary` - `summarize` 的结果
+pub fn print(holdings: &[Holding], values: &[Option<U512>], summary: &Summary) {
+    let width = holdings.iter().map(|holding| holding.asset.chars().count()).max().unwrap_or(0);
+    for ((holding, value), share) in holdings.iter().zip(values).zip(&summary.shares) {
+        let asset = format!("{:<width$}", holding.asset);
+        let (Ok(balance), Some(formatted)) = (&holding.balance, holding.formatted()) else {
+            let error = holding.balance.as_ref().err().map(String::as_str).unwrap_or_default();
+            println!("{}  {}", asset, style::err(tf(Msg::PortfolioAssetFailed, &[&error])));
+            continue;
+        };
+        let usd = value.map(price_feed::format_cents).unwrap_or_else(|| "-".to_string());
+        let share = share.map(format_bps).unwrap_or_default();
+        println!("{}  {:>24}  {:>30}  {:>14}  {:>7}", asset, formatted, balance, usd, share);
+    }
+
+    let failed = holdings.iter().filter(|holding| holding.balance.is_err()).count();
+    info!(
+        assets = holdings.len(),
+        failed,
+        priced = summary.priced,
+        total_cents = %summary.total_cents,
+        "portfolio summarized"
+    );
+    match summary.priced {
+        0 => println!("{}", t(Msg::PortfolioNoPrices)),
+        priced => {
+            let total = price_feed::format_cents(summary.total_cents);
+            let line = tf(Msg::PortfolioTotal, &[&total, &priced, &holdings.len()]);
+            println!("{}", style::ok(line));
+        }
+    }
+    if failed > 0 {
+        println!("{}", style::warn(tf(Msg::PortfolioFailedCount, &[&failed])));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USDC: Address = Address::repeat_byte(0x0c);
+    const FEED: Address = Address::repeat_byte(0xfe);
+
+    fn cents(values: &[Option<u64>]) -> Vec<Option<U512>> {
+        values.iter().map(|value| value.map(U512::from)).collect()
+    }
+
+    #[test]
+    fn shares_round_to_basis_points() {
+        let summary = summarize(&cents(&[Some(100_000), Some(50_000), None, Some(0)]));
+        assert_eq!(summary.total_cents, U512::from(150_000));
+        assert_eq!(summary.shares, [Some(6_667), Some(3_333), None, Some(0)]);
+        assert_eq!(summary.priced, 3);
+
+        // 各自四舍五入，合计可能差 0.01%
+        let thirds = summarize(&cents(&[Some(1), Some(1), Some(1)]));
+        assert_eq!(thirds.shares, [Some(3_333); 3]);
+        let halves = summarize(&cents(&[Some(1), Some(1)]));
+        assert_eq!(halves.shares, [Some(5_000); 2]);
+    }
+
+    #[test]
+    fn nothing_priced_has_no_shares() {
+        let summary = summarize(&cents(&[None, None]));
+        assert_eq!(summary.total_cents, U512::zero());
+        assert_eq!(summary.shares, [None, None]);
+        assert_eq!(summary.priced, 0);
+        // 有价格但合计为 0
+        assert_eq!(summarize(&cents(&[Some(0)])).shares, [None]);
+    }
+
+    #[test]
+    fn share_is_clamped_and_formatted_as_percent() {
+        assert_eq!(share_bps(U512::from(1), U512::from(20_000)), Some(1));
+        assert_eq!(share_bps(U512::from(1), U512::from(20_001)), Some(0));
+        assert_eq!(share_bps(U512::from(7), U512::from(5)), Some(10_000));
+        assert_eq!(share_bps(U512::one(), U512::zero()), None);
+        assert_eq!(format_bps(1_234), "12.34%");
+        assert_eq!(format_bps(5), "0.05%");
+        assert_eq!(format_bps(10_000), "100.00%");
+    }
+
+    #[test]
+    fn failed_token_calls_mark_the_whole_holding() {
+        let entry = TokenEntry {
+            address: USDC,
+            usd_feed: Some(FEED),
+        };
+        let (symbol, decimals) = (Err("reverted".into()), Err("no decimals".into()));
+        let failed = token_holding(&entry, symbol, decimals, Ok(U256::from(5)));
+        assert_eq!(failed.asset, USDC.to_string());
+        assert_eq!(failed.balance, Err("no decimals".to_string()));
+        assert_eq!(failed.formatted(), None);
+
+        let usdc = token_holding(&entry, Ok("USDC".into()), Ok(6), Ok(U256::from(1_500_000)));
+        assert_eq!(usdc.formatted().as_deref(), Some("1.5"));
+        assert_eq!(usdc.usd_feed, Some(FEED));
+    }
+
+    #[test]
+    fn table_ends_with_the_total() {
+        let entry = TokenEntry {
+            address: USDC,
+            usd_feed: None,
+        };
+        let holdings = [
+            eth_holding(U256::exp10(18), Some(FEED)),
+            token_holding(&entry, Ok("USDC".into()), Ok(6), Ok(U256::from(1_500_000))),
+            token_holding(&entry, Ok("BAD".into()), Ok(6), Err("timeout".into())),
+        ];
+        let values = cents(&[Some(300_000), Some(150), None]);
+        let summary = summarize(&values);
+        let csv = table(&holdings, &values, &summary).to_csv(false);
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows[0], "asset,token,balance_raw,balance,usd,share");
+        let eth = units::format_balance(U256::exp10(18));
+        assert_eq!(rows[1], format!("ETH,,1000000000000000000,{},$3000.00,99.95%", eth));
+        assert_eq!(rows[2], format!("USDC,{:?},1500000,1.5,$1.50,0.05%", USDC));
+        assert_eq!(rows[3], format!("BAD,{:?},,error: timeout,,", USDC));
+        assert_eq!(rows[4], "TOTAL,,,,$3001.50,");
+    }
+
+    #[test]
+    fn token_list_skips_comments_and_reports_the_line() {
+        let path = std::env::temp_dir().join(format!("arbi-tokens-{}.txt", std::process::id()));
+        let list = format!("# 稳定币\n\n{:?}, {:?}\n  {:?}\n", USDC, FEED, FEED);
+        fs::write(&path, list).unwrap();
+        let entries = load_token_list(&path).unwrap();
+        assert_eq!(
+            entries,
+            [
+                TokenEntry {
+                    address: USDC,
+                    usd_feed: Some(FEED),
+                },
+                TokenEntry {
+                    address: FEED,
+                    usd_feed: None,
+                },
+            ]
+        );
+
+        fs::write(&path, format!("{:?}\n0x1234\n", USDC)).unwrap();
+        let error = load_token_list(&path).unwrap_err().to_string();
+        assert!(error.starts_with(&tf(Msg::TokenListLine, &[&2, &"0x1234", &""])), "{error}");
+        fs::remove_file(&path).unwrap();
+    }
+}
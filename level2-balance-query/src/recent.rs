@@ -0,0 +1,366 @@
+//! 列出地址最近 N 个区块中发出或收到的交易，以及这段时间 ETH 的净变化
+//!
+//! 只看交易本身：合约内部转账（如从交易所合约提现）没有对应的交易，不会被列出，
+//! 所以净变化不一定等于这段时间的余额变化。
+
+use arbi_common::block_scan;
+use arbi_common::concurrency::{self, DEFAULT_CONCURRENCY};
+use arbi_common::logging;
+use arbi_common::progress;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use arbi_common::units;
+use ethers::providers::Middleware;
+use ethers::types::{Address, Block, H256, Transaction, TransactionReceipt, U256};
+use std::error::Error;
+
+use crate::messages::{Msg, t, tf};
+
+/// 默认扫描的区块数
+pub const DEFAULT_BLOCKS: u64 = 2000;
+
+/// 一次最多扫描的区块数
+pub const MAX_BLOCKS: u64 = 50_000;
+
+// 每个任务顺序读取的区块数，任务之间按 DEFAULT_CONCURRENCY 并发
+const SCAN_CHUNK_SIZE: u64 = 20;
+
+/// 交易相对于地址的方向
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// 别人转给地址
+    In,
+    /// 地址发出
+    Out,
+    /// 地址转给自己：金额不变，只付 Gas 费
+    SelfTransfer,
+}
+
+/// 一笔和地址有关的交易
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transfer {
+    pub block: u64,
+    /// 区块内的交易序号
+    pub index: u64,
+    pub tx_hash: H256,
+    pub direction: Direction,
+    /// 对方地址；部署合约的交易为 `None`
+    pub counterparty: Option<Address>,
+    pub value: U256,
+    /// 交易是否成功（来自收据）
+    pub success: bool,
+    /// 地址付的 Gas 费（`gas_used × effective_gas_price`），转入时为 0
+    pub fee: U256,
+}
+
+/// 一段时间内 ETH 的收入和支出
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NetChange {
+    pub received: U256,
+    /// 转出的金额加 Gas 费
+    pub spent: U256,
+}
+
+impl NetChange {
+    /// 带符号的净变化，如 `+0.5` / `-0.0001`
+    pub fn format(&self) -> String {
+        if self.received >= self.spent {
+            format!("+{}", units::format_balance(self.received - self.spent))
+        } else {
+            format!("-{}", units::format_balance(self.spent - self.received))
+        }
+    }
+}
+
+/// 判断交易和地址的关系
+///
+/// # 参数
+/// * `tx` - 交易
+/// * `address` - 地址
+///
+/// # 返回
+/// * `Option<Direction>` - 方向，交易与地址无关时为 `None`
+pub fn direction(tx: &Transaction, address: Address) -> Option<Direction> {
+    match (tx.from == address, tx.to == Some(address)) {
+        (true, true) => Some(Direction::SelfTransfer),
+        (true, false) => Some(Direction::Out),
+        (false, true) => Some(Direction::In),
+        (false, false) => None,
+    }
+}
+
+/// 从区块中找出和地址有关的交易
+///
+/// # 参数
+/// * `blocks` - 带完整交易的区块
+/// * `address` - 地址
+///
+/// # 返回
+/// * `Vec<(Direction, &Transaction)>` - 按区块和交易顺序排列
+pub fn matching(
+    blocks: &[Block<Transaction>],
+    address: Address,
+) -> Vec<(Direction, &Transaction)> {
+    blocks
+        .iter()
+        .flat_map(|block| &block.transactions)
+        .filter_map(|tx| direction(tx, address).map(|direction| (direction, tx)))
+        .collect()
+}
+
+/// 结合收据得到交易的状态和地址付的 Gas 费
+///
+/// # 参数
+/// * `direction` - 交易的方向
+/// * `tx` - 交易
+/// * `receipt` - 交易的收据
+///
+/// # 返回
+/// * `Transfer` - 交易明细
+pub fn transfer(direction: Direction, tx: &Transaction, receipt: &TransactionReceipt) -> Transfer {
+    // 转入的 Gas 费由对方付
+    let fee = match direction {
+        Direction::In => U256::zero(),
+        Direction::Out | Direction::SelfTransfer => {
+            let price = receipt.effective_gas_price.or(tx.gas_price).unwrap_or_default();
+            receipt.gas_used.unwrap_or_default().saturating_mul(price)
+        }
+    };
+    let counterparty = match direction {
+        Direction::In => Some(tx.from),
+        Direction::Out | Direction::SelfTransfer => tx.to,
+    };
+    Transfer {
+        block: tx.block_number.map(|n| n.as_u64()).unwrap_or_default(),
+        index: tx.transaction_index.map(|i| i.as_u64()).unwrap_or_default(),
+        tx_hash: tx.hash,
+        direction,
+        counterparty,
+        value: tx.value,
+        success: receipt.status.map(|status| status.as_u64() == 1).unwrap_or(true),
+        fee,
+    }
+}
+
+/// 计算净变化
+///
+/// 失败的交易不转移金额，但发送方仍然付了 Gas 费；转给自己的交易只算 Gas 费。
+///
+/// # 参数
+/// * `transfers` - 交易明细
+///
+/// # 返回
+/// * `NetChange` - 收入和支出
+pub fn net_change(transfers: &[Transfer]) -> NetChange {
+    let mut net = NetChange::default();
+    for transfer in transfers {
+        let value = if transfer.success { transfer.value } else { U256::zero() };
+        match transfer.direction {
+            Direction::In => net.received = net.received.saturating_add(value),
+            Direction::Out => net.spent = net.spent.saturating_add(value),
+            Direction::SelfTransfer => {}
+        }
+        net.spent = net.spent.saturating_add(transfer.fee);
+    }
+    net
+}
+
+/// 分段并发扫描 `[from, to]`，读取匹配交易的收据
+///
+/// 扫描时在 stderr 显示进度（-q 时不显示）；请求速率受 `--max-rps` 限制。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 地址
+/// * `from` - 起始区块（含）
+/// * `to` - 结束区块（含）
+///
+/// # 返回
+/// * `Result<Vec<Transfer>, Box<dyn Error>>` - 从新到旧排列的交易
+pub async fn scan(
+    provider: &RpcProvider,
+    address: Address,
+    from: u64,
+    to: u64,
+) -> Result<Vec<Transfer>, Box<dyn Error>> {
+    let matches = block_scan::scan_blocks(
+        provider,
+        (from, to),
+        SCAN_CHUNK_SIZE,
+        DEFAULT_CONCURRENCY,
+        |block| {
+            let matches = matching(std::slice::from_ref(block), address);
+            matches.into_iter().map(|(direction, tx)| (direction, tx.clone())).collect()
+        },
+        |scanned, total, found| {
+            progress!("{}", tf(Msg::RecentProgress, &[&scanned, &total, &found]));
+        },
+    )
+    .await;
+    logging::end_progress();
+    let matches = matches?;
+    let receipts = concurrency::run_bounded(&matches, DEFAULT_CONCURRENCY, |(_, tx)| async move {
+        match provider.get_transaction_receipt(tx.hash).await? {
+            Some(receipt) => Ok(receipt),
+            None => Err(tf(Msg::RecentReceiptMissing, &[&format!("{:?}", tx.hash)]).into()),
+        }
+    })
+    .await
+    .into_iter()
+    .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+    let mut transfers: Vec<Transfer> = matches
+        .iter()
+        .zip(&receipts)
+        .map(|((direction, tx), receipt)| transfer(*direction, tx, receipt))
+        .collect();
+    transfers.sort_by_key(|transfer| std::cmp::Reverse((transfer.block, transfer.index)));
+    Ok(transfers)
+}
+
+/// 打印交易明细（从新到旧）和净变化
+///
+/// # 参数
+/// * `transfers` - 交易明细
+/// * `from` - 起始区块
+/// * `to` - 结束区块
+pub fn print(transfers: &[Transfer], from: u64, to: u64) {
+    if transfers.is_empty() {
+        println!("{}", tf(Msg::RecentNone, &[&from, &to]));
+        return;
+    }
+    for transfer in transfers {
+        let (label, sign) = match transfer.direction {
+            Direction::In => (t(Msg::RecentIn), "+"),
+            Direction::Out => (t(Msg::RecentOut), "-"),
+            Direction::SelfTransfer => (t(Msg::RecentSelf), " "),
+        };
+        let counterparty = match transfer.counterparty {
+            Some(address) => style::addr(format!("{:?}", address)),
+            None => t(Msg::RecentContractCreation).to_string(),
+        };
+        let value = format!("{}{} ETH", sign, units::format_balance(transfer.value));
+        let status = match transfer.success {
+            true => style::ok("✓"),
+            false => style::err(t(Msg::RecentTxFailed)),
+        };
+        println!(
+            "#{:<10} {} {} {} {} {:?}",
+            transfer.block, label, counterparty, value, status, transfer.tx_hash
+        );
+    }
+    let net = net_change(transfers);
+    let spent = units::format_balance(net.spent);
+    let received = units::format_balance(net.received);
+    println!();
+    println!("{}", tf(Msg::RecentNet, &[&from, &to, &net.format(), &received, &spent]));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::U64;
+
+    const ME: Address = Address::repeat_byte(0xaa);
+    const OTHER: Address = Address::repeat_byte(0xbb);
+    const GWEI: u64 = 1_000_000_000;
+
+    fn tx(from: Address, to: Option<Address>, value: u64) -> Transaction {
+        Transaction {
+            hash: H256::from_low_u64_be(value),
+            from,
+            to,
+            value: U256::from(value),
+            block_number: Some(U64::from(100)),
+            transaction_index: Some(U64::from(3)),
+            gas_price: Some(U256::from(2 * GWEI)),
+            ..Default::default()
+        }
+    }
+
+    // gas 用了 21000；`effective_gas_price` 为 None 时退回交易的 gas_price
+    fn receipt(success: bool, effective_gas_price: Option<u64>) -> TransactionReceipt {
+        TransactionReceipt {
+            status: Some(U64::from(success as u64)),
+            gas_used: Some(U256::from(21_000)),
+            effective_gas_price: effective_gas_price.map(U256::from),
+            ..Default::default()
+        }
+    }
+
+    fn transfer_of(tx: &Transaction, receipt: &TransactionReceipt) -> Transfer {
+        transfer(direction(tx, ME).unwrap(), tx, receipt)
+    }
+
+    #[test]
+    fn direction_follows_from_and_to() {
+        assert_eq!(direction(&tx(OTHER, Some(ME), 1), ME), Some(Direction::In));
+        assert_eq!(direction(&tx(ME, Some(OTHER), 1), ME), Some(Direction::Out));
+        assert_eq!(direction(&tx(ME, Some(ME), 1), ME), Some(Direction::SelfTransfer));
+        assert_eq!(direction(&tx(ME, None, 1), ME), Some(Direction::Out));
+        assert_eq!(direction(&tx(OTHER, Some(OTHER), 1), ME), None);
+    }
+
+    #[test]
+    fn fee_is_charged_to_the_sender_only() {
+        let incoming = transfer_of(&tx(OTHER, Some(ME), 500), &receipt(true, Some(GWEI)));
+        assert_eq!(incoming.fee, U256::zero());
+        assert_eq!(incoming.counterparty, Some(OTHER));
+
+        let outgoing = transfer_of(&tx(ME, Some(OTHER), 500), &receipt(true, Some(GWEI)));
+        assert_eq!(outgoing.fee, U256::from(21_000 * GWEI));
+        assert_eq!(outgoing.counterparty, Some(OTHER));
+        assert_eq!((outgoing.block, outgoing.index), (100, 3));
+
+        // 老节点的收据没有 effectiveGasPrice
+        let legacy = transfer_of(&tx(ME, Some(OTHER), 500), &receipt(true, None));
+        assert_eq!(legacy.fee, U256::from(21_000 * 2 * GWEI));
+
+        let deploy = transfer_of(&tx(ME, None, 0), &receipt(true, Some(GWEI)));
+        assert_eq!(deploy.counterparty, None);
+    }
+
+    #[test]
+    fn self_transfers_only_cost_gas() {
+        let to_self = transfer_of(&tx(ME, Some(ME), 10 * GWEI), &receipt(true, Some(GWEI)));
+        assert_eq!(to_self.direction, Direction::SelfTransfer);
+        assert_eq!(to_self.counterparty, Some(ME));
+        let net = net_change(&[to_self]);
+        assert_eq!(net.received, U256::zero());
+        assert_eq!(net.spent, U256::from(21_000 * GWEI));
+    }
+
+    #[test]
+    fn failed_transactions_move_no_value_but_still_pay_gas() {
+        let failed_out = transfer_of(&tx(ME, Some(OTHER), 70 * GWEI), &receipt(false, Some(GWEI)));
+        let failed_in = transfer_of(&tx(OTHER, Some(ME), 90 * GWEI), &receipt(false, Some(GWEI)));
+        assert!(!failed_out.success && !failed_in.success);
+        let net = net_change(&[failed_out, failed_in]);
+        assert_eq!(net.received, U256::zero());
+        assert_eq!(net.spent, U256::from(21_000 * GWEI));
+    }
+
+    #[test]
+    fn net_change_sums_and_formats_with_a_sign() {
+        let eth = U256::exp10(18);
+        let transfers = [
+            transfer_of(&tx(OTHER, Some(ME), 2 * GWEI * GWEI), &receipt(true, Some(GWEI))),
+            transfer_of(&tx(ME, Some(OTHER), GWEI * GWEI / 2), &receipt(true, Some(GWEI))),
+        ];
+        let net = net_change(&transfers);
+        assert_eq!(net.received, eth * 2);
+        assert_eq!(net.spent, eth / 2 + U256::from(21_000 * GWEI));
+        let gain = eth * 2 - net.spent;
+        assert_eq!(net.format(), format!("+{}", units::format_balance(gain)));
+
+        let loss = NetChange {
+            received: U256::zero(),
+            spent: eth,
+        };
+        assert_eq!(loss.format(), format!("-{}", units::format_balance(eth)));
+        // 收据没有 status（拜占庭之前的格式）时按成功处理
+        let mut no_status = receipt(true, Some(GWEI));
+        no_status.status = None;
+        assert!(transfer_of(&tx(OTHER, Some(ME), 1), &no_status).success);
+    }
+}
@@ -0,0 +1,514 @@
+//! 持续监控地址的余额；`--notify-incoming` 时找出每次余额变化对应的转入交易
+
+use arbi_common::concurrency::{self, DEFAULT_CONCURRENCY};
+use arbi_common::exec::run_exec;
+use arbi_common::logs::{DEFAULT_CHUNK_SIZE, LogChunks, transfer_topic};
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use arbi_common::units;
+use ethers::contract::Contract;
+use ethers::providers::Middleware;
+use ethers::types::{Address, Block, Filter, H256, Log, Transaction, U256};
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::messages::{Msg, tf};
+use crate::pending::{PendingDelta, pending_delta};
+use crate::portfolio::ERC20_ABI;
+
+/// `--exec` 命令拿到的环境变量名前缀
+pub const EXEC_ENV_PREFIX: &str = "ARBI_INCOMING_";
+
+/// 一笔转入
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Incoming {
+    pub block: u64,
+    pub tx_hash: H256,
+    pub from: Address,
+    /// 金额（最小单位）
+    pub amount: U256,
+    /// 代币合约地址，ETH 为 `None`
+    pub token: Option<Address>,
+}
+
+/// 要监控的代币
+#[derive(Clone, Debug)]
+pub struct WatchedToken {
+    pub address: Address,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// 监控选项
+pub struct WatchOptions {
+    /// 轮询间隔
+    pub interval: Duration,
+    /// 是否找出转入交易
+    pub notify_incoming: bool,
+    /// 同时监控这个代币的 Transfer 事件
+    pub token: Option<WatchedToken>,
+    /// 每笔转入执行的命令
+    pub exec: Option<String>,
+}
+
+/// 上次扫描到 `last_scanned`、节点最新区块为 `head` 时，这次要扫描的区块范围
+///
+/// 轮询慢了几轮也会把中间的区块一次补齐；节点的最新区块回退（换到落后的节点）时不扫描，
+/// 等它追上后再从原来的位置继续。
+///
+/// # 参数
+/// * `last_scanned` - 上次扫描的最后一个区块
+/// * `head` - 节点的最新区块
+///
+/// # 返回
+/// * `Option<(u64, u64)>` - 起止区块（都包含），没有新区块时为 `None`
+pub fn scan_range(last_scanned: u64, head: u64) -> Option<(u64, u64)> {
+    (head > last_scanned).then(|| (last_scanned + 1, head))
+}
+
+/// 从区块的交易中找出 ETH 转入：`to` 是监控地址且金额不为 0
+///
+/// 只看交易本身，合约内部转账（如从交易所合约提现）没有对应的交易，不会被找到。
+///
+/// # 参数
+/// * `blocks` - 带完整交易的区块
+/// * `watched` - 监控的地址
+///
+/// # 返回
+/// * `Vec<Incoming>` - 按区块和交易顺序排列的转入
+pub fn incoming_from_blocks(blocks: &[Block<Transaction>], watched: Address) -> Vec<Incoming> {
+    let mut incoming: Vec<Incoming> = blocks
+        .iter()
+        .flat_map(|block| {
+            let number = block.number.map(|n| n.as_u64()).unwrap_or_default();
+            block
+                .transactions
+                .iter()
+                .filter(|tx| tx.to == Some(watched) && !tx.value.is_zero())
+                .map(move |tx| Incoming {
+                    block: number,
+                    tx_hash: tx.hash,
+                    from: tx.from,
+                    amount: tx.value,
+                    token: None,
+                })
+        })
+        .collect();
+    incoming.sort_by_key(|transfer| transfer.block);
+    incoming
+}
+
+/// 从事件日志中找出代币转入：代币合约发出、接收方是监控地址的 `Transfer`
+///
+/// 被重组移除的日志和格式不对的日志（如 ERC721 的 Transfer 把 tokenId 放在 topic 里）会被跳过。
+///
+/// # 参数
+/// * `logs` - 事件日志
+/// * `token` - 代币合约地址
+/// * `watched` - 监控的地址
+///
+/// # 返回
+/// * `Vec<Incoming>` - 按区块和日志顺序排列的转入
+pub fn incoming_from_logs(logs: &[Log], token: Address, watched: Address) -> Vec<Incoming> {
+    let topic = transfer_topic();
+    let mut incoming: Vec<(u64, U256, Incoming)> = logs
+        .iter()
+        .filter(|log| log.address == token && log.removed != Some(true))
+        .filter(|log| log.topics.len() == 3 && log.topics[0] == topic && log.data.len() == 32)
+        .filter(|log| Address::from(log.topics[2]) == watched)
+        .filter_map(|log| {
+            let transfer = Incoming {
+                block: log.block_number?.as_u64(),
+                tx_hash: log.transaction_hash?,
+                from: Address::from(log.topics[1]),
+                amount: U256::from_big_endian(&log.data),
+                token: Some(token),
+            };
+            Some((transfer.block, log.log_index.unwrap_or_default(), transfer))
+        })
+        .collect();
+    incoming.sort_by_key(|(block, index, _)| (*block, *index));
+    incoming.into_iter().map(|(_, _, transfer)| transfer).collect()
+}
+
+/// 读取代币的 symbol 和 decimals
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 代币合约地址
+///
+/// # 返回
+/// * `Result<WatchedToken, Box<dyn Error>>` - 代币信息
+pub async fn watched_token(
+    provider: &RpcProvider,
+    address: Address,
+) -> Result<WatchedToken, Box<dyn Error>> {
+    let abi = ethers::abi::parse_abi(&ERC20_ABI)?;
+    let contract = Contract::new(address, abi, Arc::new(provider.clone()));
+    let symbol: String = contract.method("symbol", ())?.call().await?;
+    let decimals: u8 = contract.method("decimals", ())?.call().await?;
+    Ok(WatchedToken {
+        address,
+        symbol,
+        decimals,
+    })
+}
+
+/// 扫描 `[from, to]` 中的所有区块，找出 ETH 转入和（指定代币时）代币转入
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `watched` - 监控的地址
+/// * `token` - 代币合约地址
+/// * `from` - 起始区块（含）
+/// * `to` - 结束区块（含）
+///
+/// # 返回
+/// * `Result<Vec<Incoming>, Box<dyn Error>>` - 按区块排列的转入；任何区块读取失败都返回错误，
+///   调用方下次从同一位置重新扫描
+pub async fn scan(
+    provider: &RpcProvider,
+    watched: Address,
+    token: Option<Address>,
+    from: u64,
+    to: u64,
+) -> Result<Vec<Incoming>, Box<dyn Error>> {
+    let numbers: Vec<u64> = (from..=to).collect();
+    let blocks = concurrency::run_bounded(&numbers, DEFAULT_CONCURRENCY, |number| async move {
+        match provider.get_block_with_txs(*number).await? {
+            Some(block) => Ok(block),
+            None => Err(tf(Msg::WatchBlockMissing, &[number]).into()),
+        }
+    })
+    .await
+    .into_iter()
+    .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+    let mut incoming = incoming_from_blocks(&blocks, watched);
+
+    if let Some(token) = token {
+        let filter = Filter::new()
+            .address(token)
+            .topic0(transfer_topic())
+            .topic2(H256::from(watched));
+        let mut chunks = LogChunks::new(provider, filter, from, to, DEFAULT_CHUNK_SIZE);
+        while let Some(chunk) = chunks.next().await? {
+            incoming.extend(incoming_from_logs(&chunk.logs, token, watched));
+        }
+        // 稳定排序：同一区块内 ETH 在前
+        incoming.sort_by_key(|transfer| transfer.block);
+    }
+    Ok(incoming)
+}
+
+// 格式化转入金额：ETH 按 --precision 取整，代币显示精确值
+fn format_amount(transfer: &Incoming, token: Option<&WatchedToken>) -> (String, String) {
+    match token.filter(|token| transfer.token == Some(token.address)) {
+        Some(token) => {
+            let amount = units::format_exact(transfer.amount, token.decimals.into());
+            (amount.unwrap_or_else(|_| transfer.amount.to_string()), token.symbol.clone())
+        }
+        None => (units::format_balance(transfer.amount), "ETH".to_string()),
+    }
+}
+
+/// `--exec` 命令拿到的环境变量
+///
+/// # 参数
+/// * `watched` - 监控的地址
+/// * `transfer` - 转入
+/// * `token` - 监控的代币
+///
+/// # 返回
+/// * `Vec<(String, String)>` - 变量名和值
+pub fn exec_env(
+    watched: Address,
+    transfer: &Incoming,
+    token: Option<&WatchedToken>,
+) -> Vec<(String, String)> {
+    let (amount, asset) = format_amount(transfer, token);
+    let token_address = transfer.token.map(|token| format!("{:?}", token)).unwrap_or_default();
+    [
+        ("TO", format!("{:?}", watched)),
+        ("FROM", format!("{:?}", transfer.from)),
+        ("AMOUNT", amount),
+        ("AMOUNT_RAW", transfer.amount.to_string()),
+        ("ASSET", asset),
+        ("TOKEN", token_address),
+        ("TX_HASH", format!("{:?}", transfer.tx_hash)),
+        ("BLOCK", transfer.block.to_string()),
+    ]
+    .into_iter()
+    .map(|(name, value)| (format!("{}{}", EXEC_ENV_PREFIX, name), value))
+    .collect()
+}
+
+// 打印余额变化
+fn print_delta(block: u64, before: U256, after: U256) {
+    let balance = units::format_balance(after);
+    match pending_delta(before, after) {
+        PendingDelta::Same => {}
+        PendingDelta::Incoming(delta) => {
+            let delta = units::format_balance(delta);
+            println!("{}", style::ok(tf(Msg::WatchIncrease, &[&block, &delta, &balance])));
+        }
+        PendingDelta::Outgoing(delta) => {
+            let delta = units::format_balance(delta);
+            println!("{}", style::warn(tf(Msg::WatchDecrease, &[&block, &delta, &balance])));
+        }
+    }
+}
+
+/// 持续监控地址，直到 Ctrl-C
+///
+/// 每次轮询读取最新区块上的余额，变化时打印差额。`notify_incoming` 时还会扫描上次轮询之后的
+/// 所有区块（轮询变慢也不会漏掉），打印每笔转入的发送方、金额和交易哈希，并执行 `exec` 命令。
+/// 某次扫描失败时不前移扫描位置，下次轮询重新扫描同一段。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `watched` - 监控的地址
+/// * `options` - 轮询间隔、是否找出转入、代币和命令
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 第一次读取失败时返回错误
+pub async fn watch(
+    provider: &RpcProvider,
+    watched: Address,
+    options: &WatchOptions,
+) -> Result<(), Box<dyn Error>> {
+    let mut last_block = provider.get_block_number().await?.as_u64();
+    let mut balance = provider.get_balance(watched, Some(last_block.into())).await?;
+    let secs = options.interval.as_secs();
+    println!("{}", tf(Msg::WatchStarted, &[&units::format_balance(balance), &last_block, &secs]));
+    let token = options.token.as_ref();
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(options.interval) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+
+        let head = match provider.get_block_number().await {
+            Ok(head) => head.as_u64(),
+            Err(e) => {
+                eprintln!("{}", style::warn(tf(Msg::WatchPollFailed, &[&e])));
+                continue;
+            }
+        };
+        let Some((from, to)) = scan_range(last_block, head) else {
+            continue;
+        };
+        match provider.get_balance(watched, Some(to.into())).await {
+            Ok(current) => {
+                print_delta(to, balance, current);
+                balance = current;
+            }
+            Err(e) => eprintln!("{}", style::warn(tf(Msg::WatchPollFailed, &[&e]))),
+        }
+        if !options.notify_incoming {
+            last_block = to;
+            continue;
+        }
+
+        let token_address = token.map(|token| token.address);
+        let incoming = match scan(provider, watched, token_address, from, to).await {
+            Ok(incoming) => incoming,
+            Err(e) => {
+                warn!(from, to, error = %e, "scan failed, retrying next poll");
+                eprintln!("{}", style::warn(tf(Msg::WatchScanFailed, &[&from, &to, &e])));
+                continue;
+            }
+        };
+        info!(from, to, incoming = incoming.len(), "blocks scanned");
+        last_block = to;
+        for transfer in &incoming {
+            let (amount, asset) = format_amount(transfer, token);
+            let from = style::addr(format!("{:?}", transfer.from));
+            let line = tf(Msg::WatchIncoming, &[&transfer.block, &amount, &asset, &from]);
+            println!("{}", style::ok(line));
+            println!("  tx {:?}", transfer.tx_hash);
+            if let Some(command) = &options.exec {
+                run_exec(command, exec_env(watched, transfer, token)).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use serde_json::{Value, json};
+
+    const WATCHED: Address = Address::repeat_byte(0xaa);
+    const SENDER: Address = Address::repeat_byte(0x5e);
+    const TOKEN: Address = Address::repeat_byte(0x70);
+
+    fn hash(n: u64) -> H256 {
+        H256::from_low_u64_be(n)
+    }
+
+    // (交易序号, to, 金额)
+    fn tx_json(block: u64, (index, to, value): (u64, Address, u64)) -> Value {
+        json!({
+            "hash": format!("{:?}", hash(block * 100 + index)),
+            "nonce": format!("{:#x}", index),
+            "blockNumber": format!("{:#x}", block),
+            "transactionIndex": format!("{:#x}", index),
+            "from": format!("{:?}", SENDER),
+            "to": format!("{:?}", to),
+            "value": format!("{:#x}", value),
+            "gas": "0x5208",
+            "gasPrice": "0x5f5e100",
+            "input": "0x",
+            "v": "0x0",
+            "r": "0x1",
+            "s": "0x1",
+        })
+    }
+
+    fn block_json(block: u64, txs: &[(u64, Address, u64)]) -> Value {
+        json!({
+            "number": format!("{:#x}", block),
+            "hash": format!("{:?}", hash(block)),
+            "transactions": txs.iter().map(|tx| tx_json(block, *tx)).collect::<Vec<_>>(),
+        })
+    }
+
+    fn word(amount: u64) -> Vec<u8> {
+        let mut word = [0u8; 32];
+        U256::from(amount).to_big_endian(&mut word);
+        word.to_vec()
+    }
+
+    fn transfer_log(block: u64, index: u64, to: Address, amount: u64) -> Log {
+        Log {
+            address: TOKEN,
+            topics: vec![transfer_topic(), H256::from(SENDER), H256::from(to)],
+            data: word(amount).into(),
+            block_number: Some(block.into()),
+            transaction_hash: Some(hash(block * 100 + 50 + index)),
+            log_index: Some(index.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn scan_range_covers_missed_blocks_and_ignores_rollback() {
+        assert_eq!(scan_range(100, 100), None);
+        assert_eq!(scan_range(100, 101), Some((101, 101)));
+        // 轮询慢了几轮，一次补齐
+        assert_eq!(scan_range(100, 105), Some((101, 105)));
+        // 换到落后的节点
+        assert_eq!(scan_range(100, 97), None);
+    }
+
+    #[test]
+    fn eth_transfers_are_attributed_to_their_block() {
+        let blocks: Vec<Block<Transaction>> = [
+            block_json(102, &[(0, WATCHED, 7), (1, WATCHED, 0), (2, TOKEN, 9)]),
+            block_json(101, &[(0, WATCHED, 5)]),
+        ]
+        .into_iter()
+        .map(|block| serde_json::from_value(block).unwrap())
+        .collect();
+        let incoming = incoming_from_blocks(&blocks, WATCHED);
+        let found: Vec<_> = incoming.iter().map(|t| (t.block, t.tx_hash, t.amount)).collect();
+        assert_eq!(found, [(101, hash(10_100), 5.into()), (102, hash(10_200), 7.into())]);
+        assert!(incoming.iter().all(|t| t.from == SENDER && t.token.is_none()));
+    }
+
+    #[test]
+    fn token_logs_skip_removed_foreign_and_nft_transfers() {
+        let mut removed = transfer_log(101, 0, WATCHED, 1);
+        removed.removed = Some(true);
+        let mut foreign = transfer_log(101, 1, WATCHED, 2);
+        foreign.address = SENDER;
+        let mut nft = transfer_log(101, 2, WATCHED, 3);
+        nft.topics.push(hash(3));
+        let logs = [
+            transfer_log(103, 0, WATCHED, 30),
+            removed,
+            foreign,
+            nft,
+            transfer_log(102, 4, WATCHED, 24),
+            transfer_log(102, 1, WATCHED, 21),
+            transfer_log(102, 2, SENDER, 99),
+        ];
+        let incoming = incoming_from_logs(&logs, TOKEN, WATCHED);
+        let found: Vec<_> = incoming.iter().map(|t| (t.block, t.amount.as_u64())).collect();
+        assert_eq!(found, [(102, 21), (102, 24), (103, 30)]);
+        assert!(incoming.iter().all(|t| t.token == Some(TOKEN)));
+    }
+
+    // 101～103 三个区块；`missing` 区块返回 null
+    fn node(missing: Option<u64>) -> MockRpc {
+        MockRpc::start(move |method, params| match method {
+            "eth_getBlockByNumber" => {
+                let hex = params[0].as_str().unwrap().trim_start_matches("0x");
+                let number = u64::from_str_radix(hex, 16).unwrap();
+                assert_eq!(params[1], json!(true));
+                match number {
+                    n if Some(n) == missing => Reply::Result(Value::Null),
+                    102 => Reply::Result(block_json(102, &[(0, WATCHED, 7)])),
+                    n => Reply::Result(block_json(n, &[])),
+                }
+            }
+            "eth_getLogs" => {
+                assert_eq!(params[0]["fromBlock"], json!("0x65"));
+                assert_eq!(params[0]["toBlock"], json!("0x67"));
+                let logs = [transfer_log(103, 0, WATCHED, 30), transfer_log(102, 3, WATCHED, 20)];
+                Reply::Result(serde_json::to_value(logs).unwrap())
+            }
+            _ => Reply::Error(format!("unexpected {}", method)),
+        })
+    }
+
+    #[tokio::test]
+    async fn scan_merges_eth_before_tokens_in_the_same_block() {
+        let rpc = node(None);
+        let incoming = scan(&rpc.provider(), WATCHED, Some(TOKEN), 101, 103).await.unwrap();
+        let found: Vec<_> = incoming
+            .iter()
+            .map(|t| (t.block, t.token, t.amount.as_u64()))
+            .collect();
+        assert_eq!(found, [(102, None, 7), (102, Some(TOKEN), 20), (103, Some(TOKEN), 30)]);
+        assert_eq!(rpc.count("eth_getBlockByNumber"), 3);
+
+        // 不监控代币时不查日志
+        let rpc = node(None);
+        let incoming = scan(&rpc.provider(), WATCHED, None, 101, 103).await.unwrap();
+        assert_eq!(incoming.len(), 1);
+        assert_eq!(rpc.count("eth_getLogs"), 0);
+    }
+
+    #[tokio::test]
+    async fn missing_block_fails_the_whole_range() {
+        let rpc = node(Some(102));
+        let error = scan(&rpc.provider(), WATCHED, None, 101, 103).await.unwrap_err();
+        assert_eq!(error.to_string(), tf(Msg::WatchBlockMissing, &[&102]));
+    }
+
+    #[test]
+    fn exec_env_describes_the_transfer() {
+        let logs = [transfer_log(102, 0, WATCHED, 1_500_000)];
+        let transfer = incoming_from_logs(&logs, TOKEN, WATCHED);
+        let token = WatchedToken {
+            address: TOKEN,
+            symbol: "USDC".to_string(),
+            decimals: 6,
+        };
+        let env = exec_env(WATCHED, &transfer[0], Some(&token));
+        let get = |name: &str| {
+            let name = format!("{}{}", EXEC_ENV_PREFIX, name);
+            env.iter().find(|(key, _)| *key == name).map(|(_, value)| value.as_str())
+        };
+        assert_eq!(get("AMOUNT"), Some("1.5"));
+        assert_eq!(get("AMOUNT_RAW"), Some("1500000"));
+        assert_eq!(get("ASSET"), Some("USDC"));
+        assert_eq!(get("BLOCK"), Some("102"));
+        assert_eq!(get("TOKEN").map(str::to_string), Some(format!("{:?}", TOKEN)));
+    }
+}
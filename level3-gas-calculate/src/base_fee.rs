@@ -0,0 +1,116 @@
+use arbi_common::rpc::RpcProvider;
+use ethers::providers::Middleware;
+use ethers::types::{BlockNumber, U256};
+use std::error::Error;
+
+use crate::messages::{Msg, t};
+
+// EIP-1559 参数：Gas 目标是 Gas 上限的 1/2，每个区块基础费最多变化 1/8
+const ELASTICITY_MULTIPLIER: u64 = 2;
+const BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+
+/// 最新区块的基础费和按 EIP-1559 公式预测的下一区块基础费
+pub struct NextBaseFee {
+    pub block_number: u64,
+    pub current: U256,
+    pub next: U256,
+    pub gas_used: U256,
+    pub gas_target: U256,
+}
+
+/// 按 EIP-1559 公式计算下一区块的基础费
+///
+/// Gas 使用量高于目标时基础费上涨，低于目标时下降，变化幅度与偏离目标的比例成正比，
+/// 最多 ±12.5%；上涨时至少涨 1 wei。
+///
+/// # 参数
+/// * `base_fee` - 当前区块基础费（wei）
+/// * `gas_used` - 当前区块 Gas 使用量
+/// * `gas_limit` - 当前区块 Gas 上限
+///
+/// # 返回
+/// * `U256` - 下一区块的基础费（wei）
+pub fn compute_next_base_fee(base_fee: U256, gas_used: U256, gas_limit: U256) -> U256 {
+    let gas_target = gas_limit / ELASTICITY_MULTIPLIER;
+    let denominator = U256::from(BASE_FEE_MAX_CHANGE_DENOMINATOR);
+
+    if gas_target.is_zero() || gas_used == gas_target {
+        base_fee
+    } else if gas_used > gas_target {
+        let delta = base_fee * (gas_used - gas_target) / gas_target / denominator;
+        base_fee + delta.max(U256::one())
+    } else {
+        let delta = base_fee * (gas_target - gas_used) / gas_target / denominator;
+        base_fee.saturating_sub(delta)
+    }
+}
+
+/// 读取最新区块并预测下一区块的基础费
+///
+/// # 参数
+/// * `provider` - Provider 引用
+///
+/// # 返回
+/// * `Result<Option<NextBaseFee>, Box<dyn Error>>` - 链不支持 EIP-1559（区块没有基础费）时为 `None`
+pub async fn next_base_fee(provider: &RpcProvider) -> Result<Option<NextBaseFee>, Box<dyn Error>> {
+    let block = provider
+        .get_block(BlockNumber::Latest)
+        .await?
+        .ok_or_else(|| t(Msg::LatestBlockMissing))?;
+
+    let Some(current) = block.base_fee_per_gas else {
+        return Ok(None);
+    };
+
+    Ok(Some(NextBaseFee {
+        block_number: block.number.map(|n| n.as_u64()).unwrap_or_default(),
+        current,
+        next: compute_next_base_fee(current, block.gas_used, block.gas_limit),
+        gas_used: block.gas_used,
+        gas_target: block.gas_limit / ELASTICITY_MULTIPLIER,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GWEI: u64 = 1_000_000_000;
+    const GAS_LIMIT: u64 = 30_000_000;
+
+    fn next(base_fee: u64, gas_used: u64, gas_limit: u64) -> U256 {
+        compute_next_base_fee(base_fee.into(), gas_used.into(), gas_limit.into())
+    }
+
+    #[test]
+    fn follows_block_fullness() {
+        // (当前基础费, Gas 使用量, 预期的下一区块基础费)
+        let cases = [
+            (10 * GWEI, GAS_LIMIT, 11_250_000_000),         // 满块：+12.5%
+            (10 * GWEI, GAS_LIMIT / 2, 10 * GWEI),          // 半满（正好是目标）：不变
+            (10 * GWEI, 0, 8_750_000_000),                  // 空块：-12.5%
+            (10 * GWEI, GAS_LIMIT * 3 / 4, 10_625_000_000), // 目标的 1.5 倍：+6.25%
+            (10 * GWEI, GAS_LIMIT / 4, 9_375_000_000),      // 目标的一半：-6.25%
+        ];
+        for (base_fee, gas_used, expected) in cases {
+            assert_eq!(next(base_fee, gas_used, GAS_LIMIT), U256::from(expected), "{gas_used}");
+        }
+    }
+
+    #[test]
+    fn floors_at_one_wei_increase_and_zero() {
+        // 算出的涨幅向下取整为 0 时至少涨 1 wei
+        assert_eq!(next(7, GAS_LIMIT, GAS_LIMIT), U256::from(8));
+        assert_eq!(next(0, GAS_LIMIT, GAS_LIMIT), U256::one());
+        // 跌幅向下取整，基础费不会低于 0
+        assert_eq!(next(7, 0, GAS_LIMIT), U256::from(7));
+        assert_eq!(next(8, 0, GAS_LIMIT), U256::from(7));
+        assert_eq!(next(0, 0, GAS_LIMIT), U256::zero());
+    }
+
+    #[test]
+    fn zero_gas_limit_keeps_base_fee() {
+        assert_eq!(next(10 * GWEI, 0, 0), U256::from(10 * GWEI));
+        assert_eq!(next(10 * GWEI, 1, 1), U256::from(10 * GWEI));
+    }
+}
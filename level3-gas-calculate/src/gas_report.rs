@@ -0,0 +1,194 @@
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::providers::Middleware;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{BlockNumber, U256};
+use ethers::utils::format_units;
+use std::error::Error;
+
+use crate::messages::{Msg, t, tf};
+
+/// 传统（legacy）定价：一个 Gas 价格，全部付给出块方
+pub struct LegacyEstimate {
+    pub gas_price: U256,
+    pub fee: U256,
+}
+
+/// EIP-1559 定价：基础费（销毁）+ 小费，按最高费用上限预留
+pub struct Eip1559Estimate {
+    pub base_fee: U256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    /// 预计实际支付：Gas × (基础费 + 小费)
+    pub expected_fee: U256,
+    /// 最多支付：Gas × 最高费用
+    pub max_fee: U256,
+}
+
+/// 同一笔交易在两种定价方式下的费用，链不支持的方式为 `None`
+pub struct GasReport {
+    pub gas: U256,
+    pub legacy: Option<LegacyEstimate>,
+    pub eip1559: Option<Eip1559Estimate>,
+}
+
+/// 哪种定价方式当前更便宜
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cheaper {
+    Legacy,
+    Eip1559,
+    Same,
+}
+
+impl GasReport {
+    /// 比较两种方式的预计费用，只支持一种时返回 `None`
+    pub fn cheaper(&self) -> Option<Cheaper> {
+        let legacy = self.legacy.as_ref()?.fee;
+        let eip1559 = self.eip1559.as_ref()?.expected_fee;
+        Some(if legacy < eip1559 {
+            Cheaper::Legacy
+        } else if eip1559 < legacy {
+            Cheaper::Eip1559
+        } else {
+            Cheaper::Same
+        })
+    }
+}
+
+/// 估算同一笔交易在 legacy 和 EIP-1559 定价下的费用（只估算，不发送）
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `tx` - 要估算的交易；设置了 Gas 限额时直接使用，否则调用 `eth_estimateGas`
+///
+/// # 返回
+/// * `Result<GasReport, Box<dyn Error>>` - 两种方式的费用，链不支持的方式为 `None`
+pub async fn gas_report(
+    provider: &RpcProvider,
+    tx: &TypedTransaction,
+) -> Result<GasReport, Box<dyn Error>> {
+    let gas = match tx.gas() {
+        Some(gas) => *gas,
+        None => provider.estimate_gas(tx, None).await?,
+    };
+
+    let legacy = provider
+        .get_gas_price()
+        .await
+        .ok()
+        .map(|gas_price| LegacyEstimate {
+            gas_price,
+            fee: gas * gas_price,
+        });
+
+    // 最新区块没有基础费说明链不支持 EIP-1559
+    let base_fee = provider
+        .get_block(BlockNumber::Latest)
+        .await?
+        .and_then(|block| block.base_fee_per_gas);
+    let eip1559 = match base_fee {
+        Some(base_fee) => match provider.estimate_eip1559_fees(None).await {
+            Ok((max_fee_per_gas, max_priority_fee_per_gas)) => {
+                // 实际单价不会超过最高费用
+                let effective = (base_fee + max_priority_fee_per_gas).min(max_fee_per_gas);
+                Some(Eip1559Estimate {
+                    base_fee,
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                    expected_fee: gas * effective,
+                    max_fee: gas * max_fee_per_gas,
+                })
+            }
+            Err(_) => None,
+        },
+        None => None,
+    };
+
+    Ok(GasReport {
+        gas,
+        legacy,
+        eip1559,
+    })
+}
+
+/// 并排打印两种定价方式的费用，链不支持的方式不显示对应的列
+///
+/// # 参数
+/// * `report` - `gas_report` 的结果
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+pub fn print_gas_report(report: &GasReport) -> Result<(), Box<dyn Error>> {
+    println!("{}", tf(Msg::ReportGas, &[&report.gas]));
+    if report.legacy.is_none() && report.eip1559.is_none() {
+        println!("{}", style::warn(t(Msg::ReportNoPricing)));
+        return Ok(());
+    }
+
+    let legacy = report.legacy.as_ref();
+    let eip1559 = report.eip1559.as_ref();
+
+    // 每行依次为 legacy 格子和 EIP-1559 格子，不适用的格子为 None（打印为空白）
+    let rows: Vec<(Msg, Option<String>, Option<String>)> = vec![
+        (
+            Msg::ReportGasPrice,
+            legacy.map(|l| format_units(l.gas_price, "gwei")).transpose()?,
+            None,
+        ),
+        (
+            Msg::ReportBaseFee,
+            None,
+            eip1559.map(|e| format_units(e.base_fee, "gwei")).transpose()?,
+        ),
+        (
+            Msg::ReportPriorityFee,
+            None,
+            eip1559.map(|e| format_units(e.max_priority_fee_per_gas, "gwei")).transpose()?,
+        ),
+        (
+            Msg::ReportMaxFeePerGas,
+            None,
+            eip1559.map(|e| format_units(e.max_fee_per_gas, "gwei")).transpose()?,
+        ),
+        (
+            Msg::ReportExpectedFee,
+            legacy.map(|l| format_units(l.fee, "ether")).transpose()?,
+            eip1559.map(|e| format_units(e.expected_fee, "ether")).transpose()?,
+        ),
+        (
+            Msg::ReportMaxFee,
+            legacy.map(|l| format_units(l.fee, "ether")).transpose()?,
+            eip1559.map(|e| format_units(e.max_fee, "ether")).transpose()?,
+        ),
+    ];
+
+    let cell = |value: Option<&str>| format!(" {:>24}", value.unwrap_or(""));
+    let mut header = format!("{:<28}", "");
+    if legacy.is_some() {
+        header += &cell(Some(t(Msg::ReportLegacy)));
+    }
+    if eip1559.is_some() {
+        header += &cell(Some(t(Msg::ReportEip1559)));
+    }
+    println!("{}", header);
+
+    for (label, legacy_cell, eip1559_cell) in &rows {
+        let mut line = format!("{:<28}", t(*label));
+        if legacy.is_some() {
+            line += &cell(legacy_cell.as_deref());
+        }
+        if eip1559.is_some() {
+            line += &cell(eip1559_cell.as_deref());
+        }
+        println!("{}", line);
+    }
+
+    match report.cheaper() {
+        Some(Cheaper::Legacy) => println!("{}", style::ok(t(Msg::ReportLegacyCheaper))),
+        Some(Cheaper::Eip1559) => println!("{}", style::ok(t(Msg::ReportEip1559Cheaper))),
+        Some(Cheaper::Same) => println!("{}", style::ok(t(Msg::ReportSameCost))),
+        None if legacy.is_none() => println!("{}", style::warn(t(Msg::ReportNoLegacy))),
+        None => println!("{}", style::warn(t(Msg::ReportNoEip1559))),
+    }
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use arbi_common::rpc::RpcProvider;
+use ethers::abi::Abi;
+use ethers::prelude::*;
+use ethers::types::{Address, U256};
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::Arc;
+
+// ArbGasInfo 预编译合约地址
+const ARB_GAS_INFO_ADDRESS: &str = "0x000000000000000000000000000000000000006C";
+
+const ARB_GAS_INFO_ABI: &str = r#"[
+    {
+        "inputs": [],
+        "name": "getPricesInWei",
+        "outputs": [
+            {"name": "", "type": "uint256"},
+            {"name": "", "type": "uint256"},
+            {"name": "", "type": "uint256"},
+            {"name": "", "type": "uint256"},
+            {"name": "", "type": "uint256"},
+            {"name": "", "type": "uint256"}
+        ],
+        "stateMutability": "view",
+        "type": "function"
+    }
+]"#;
+
+// L1 上每个非零 calldata 字节消耗 16 Gas，ArbOS 的每字节价格就是 16 × L1 基础费
+const L1_GAS_PER_BYTE: u64 = 16;
+
+// 已签名交易除 calldata 外的大致字节数（nonce、Gas 字段、to、value、签名等 RLP 编码）
+const TX_OVERHEAD_BYTES: u64 = 110;
+
+/// 与 L1 价格相关的 ArbGasInfo 价格（单位均为 wei）
+#[derive(Clone, Copy, Debug)]
+pub struct L1Prices {
+    /// 每笔交易固定的 L1 开销
+    pub per_l2_tx: U256,
+    /// 发布到 L1 的每字节 calldata
+    pub per_l1_calldata_byte: U256,
+}
+
+/// 一组 L1 基础费下的费用预测（单位均为 wei）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeProjection {
+    pub l1_base_fee: U256,
+    pub per_l1_calldata_byte: U256,
+    pub l1_cost: U256,
+    pub l2_cost: U256,
+    pub total: U256,
+}
+
+/// 要预测费用的交易
+pub struct TxShape {
+    /// L2 执行消耗的 Gas
+    pub l2_gas: u64,
+    /// 整笔交易发布到 L1 的字节数
+    pub tx_bytes: u64,
+}
+
+impl TxShape {
+    /// 按 calldata 计算交易的 Gas 和字节数：21000 基础 Gas，零字节 4 Gas，非零字节 16 Gas
+    ///
+    /// # 参数
+    /// * `calldata` - 交易 calldata（普通转账为空）
+    pub fn from_calldata(calldata: &[u8]) -> Self {
+        let calldata_gas: u64 = calldata
+            .iter()
+            .map(|byte| if *byte == 0 { 4 } else { L1_GAS_PER_BYTE })
+            .sum();
+        Self {
+            l2_gas: 21_000 + calldata_gas,
+            tx_bytes: TX_OVERHEAD_BYTES + calldata.len() as u64,
+        }
+    }
+}
+
+/// 从每字节价格反推 ArbOS 当前使用的 L1 基础费
+///
+/// # 参数
+/// * `per_l1_calldata_byte` - ArbGasInfo 的每字节价格（wei）
+///
+/// # 返回
+/// * `U256` - 隐含的 L1 基础费（wei）
+pub fn implied_l1_base_fee(per_l1_calldata_byte: U256) -> U256 {
+    per_l1_calldata_byte / L1_GAS_PER_BYTE
+}
+
+/// 计算交易在指定 L1 基础费下的费用
+///
+/// 模型：L1 数据费 = 每笔固定开销 + 交易字节数 × 每字节价格，两个价格都由 ArbOS 按 L1 基础费
+/// 线性定出，所以换成假设的 L1 基础费时，两者都按 `假设值 / 隐含值` 的比例缩放（先乘后除，
+/// 全程整数运算）；L2 执行费只和 L2 Gas 价格有关，保持不变。
+/// 隐含的 L1 基础费为 0 时无法按比例缩放，每字节价格直接取 16 × 假设值，固定开销不变。
+///
+/// # 参数
+/// * `prices` - 当前的 L1 价格
+/// * `l2_gas_price` - L2 Gas 价格（wei）
+/// * `tx` - 交易的 Gas 和字节数
+/// * `l1_base_fee` - 假设的 L1 基础费（wei），`None` 表示按当前价格计算
+///
+/// # 返回
+/// * `FeeProjection` - L1、L2 部分和总费用
+pub fn project(
+    prices: &L1Prices,
+    l2_gas_price: U256,
+    tx: &TxShape,
+    l1_base_fee: Option<U256>,
+) -> FeeProjection {
+    let implied = implied_l1_base_fee(prices.per_l1_calldata_byte);
+    let (l1_base_fee, per_l2_tx, per_byte) = match l1_base_fee {
+        None => (implied, prices.per_l2_tx, prices.per_l1_calldata_byte),
+        Some(target) if implied.is_zero() => {
+            (target, prices.per_l2_tx, target * L1_GAS_PER_BYTE)
+        }
+        Some(target) => (
+            target,
+            prices.per_l2_tx * target / implied,
+            prices.per_l1_calldata_byte * target / implied,
+        ),
+    };
+
+    let l1_cost = per_l2_tx + U256::from(tx.tx_bytes) * per_byte;
+    let l2_cost = U256::from(tx.l2_gas) * l2_gas_price;
+    FeeProjection {
+        l1_base_fee,
+        per_l1_calldata_byte: per_byte,
+        l1_cost,
+        l2_cost,
+        total: l1_cost + l2_cost,
+    }
+}
+
+/// 从 ArbGasInfo 读取当前的 L1 价格
+///
+/// # 参数
+/// * `provider` - Provider 引用
+///
+/// # 返回
+/// * `Result<L1Prices, Box<dyn Error>>` - 每笔固定开销和每字节价格
+pub async fn l1_prices(provider: &RpcProvider) -> Result<L1Prices, Box<dyn Error>> {
+    let arb_gas_info = Contract::new(
+        Address::from_str(ARB_GAS_INFO_ADDRESS)?,
+        serde_json::from_str::<Abi>(ARB_GAS_INFO_ABI)?,
+        Arc::new(provider.clone()),
+    );
+
+    let (per_l2_tx, per_l1_calldata_byte, _, _, _, _): (U256, U256, U256, U256, U256, U256) =
+        arb_gas_info.method("getPricesInWei", ())?.call().await?;
+
+    Ok(L1Prices {
+        per_l2_tx,
+        per_l1_calldata_byte,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GWEI: u64 = 1_000_000_000;
+
+    // 当前 L1 基础费 10 gwei：每字节 160 gwei，每笔固定开销 2000 gwei
+    fn prices() -> L1Prices {
+        L1Prices {
+            per_l2_tx: U256::from(2_000 * GWEI),
+            per_l1_calldata_byte: U256::from(160 * GWEI),
+        }
+    }
+
+    #[test]
+    fn shape_counts_zero_and_non_zero_bytes() {
+        let plain = TxShape::from_calldata(&[]);
+        assert_eq!((plain.l2_gas, plain.tx_bytes), (21_000, 110));
+        let mixed = TxShape::from_calldata(&[0, 0, 0xa9, 0x05]);
+        assert_eq!((mixed.l2_gas, mixed.tx_bytes), (21_000 + 4 * 2 + 16 * 2, 114));
+    }
+
+    #[test]
+    fn current_prices_are_used_as_is() {
+        let plain = TxShape::from_calldata(&[]);
+        let projection = project(&prices(), U256::from(GWEI / 100), &plain, None);
+        assert_eq!(projection.l1_base_fee, U256::from(10 * GWEI));
+        assert_eq!(projection.per_l1_calldata_byte, U256::from(160 * GWEI));
+        assert_eq!(projection.l1_cost, U256::from((2_000 + 110 * 160) * GWEI));
+        assert_eq!(projection.l2_cost, U256::from(21_000 * GWEI / 100));
+        assert_eq!(projection.total, projection.l1_cost + projection.l2_cost);
+    }
+
+    #[test]
+    fn hypothetical_base_fee_scales_l1_prices_only() {
+        let plain = TxShape::from_calldata(&[]);
+        let l2_gas_price = U256::from(GWEI / 100);
+        for (target, scale_num, scale_den) in [(30, 3, 1), (5, 1, 2), (1, 1, 10), (0, 0, 1)] {
+            let projection =
+                project(&prices(), l2_gas_price, &plain, Some(U256::from(target * GWEI)));
+            let scaled = |value: u64| U256::from(value * GWEI * scale_num / scale_den);
+            assert_eq!(projection.l1_base_fee, U256::from(target * GWEI), "{target}");
+            assert_eq!(projection.per_l1_calldata_byte, scaled(160), "{target}");
+            assert_eq!(projection.l1_cost, scaled(2_000 + 110 * 160), "{target}");
+            assert_eq!(projection.l2_cost, U256::from(21_000 * GWEI / 100), "{target}");
+        }
+    }
+
+    // 先乘后除：非整数倍的缩放不会先把比例截断成 0
+    #[test]
+    fn scaling_multiplies_before_dividing() {
+        let prices = L1Prices {
+            per_l2_tx: U256::from(7),
+            per_l1_calldata_byte: U256::from(48),
+        };
+        let tx = TxShape {
+            l2_gas: 0,
+            tx_bytes: 1,
+        };
+        // 隐含基础费 3 wei，假设值 2 wei：7 × 2 / 3 = 4，48 × 2 / 3 = 32
+        let projection = project(&prices, U256::zero(), &tx, Some(U256::from(2)));
+        assert_eq!(projection.l1_cost, U256::from(4 + 32));
+    }
+
+    #[test]
+    fn zero_implied_base_fee_uses_sixteen_gas_per_byte() {
+        let prices = L1Prices {
+            per_l2_tx: U256::from(500),
+            per_l1_calldata_byte: U256::zero(),
+        };
+        let tx = TxShape {
+            l2_gas: 0,
+            tx_bytes: 10,
+        };
+        let projection = project(&prices, U256::zero(), &tx, Some(U256::from(GWEI)));
+        assert_eq!(projection.per_l1_calldata_byte, U256::from(16 * GWEI));
+        assert_eq!(projection.l1_cost, U256::from(500 + 10 * 16 * GWEI));
+    }
+}
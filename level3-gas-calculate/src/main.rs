@@ -1,73 +1,276 @@
-use ethers::providers::{Http, Middleware, Provider};
-use ethers::types::U256;
-use ethers::utils::format_units;
+mod base_fee;
+mod gas_report;
+mod l1_projection;
+mod messages;
+mod tx_cost;
+mod watch;
+
+use arbi_common::cli::GlobalArgs;
+use arbi_common::narrate;
+use arbi_common::network;
+use arbi_common::rpc::{self, RpcProvider};
+use arbi_common::shutdown::{INTERRUPTED_EXIT_CODE, Interrupted};
+use arbi_common::style;
+use arbi_common::txhash::parse_tx_hash;
+use arbi_common::units;
+use clap::Parser;
+use ethers::providers::Middleware;
+use ethers::types::{Address, TransactionRequest, U256};
+use ethers::utils::{format_units, hex};
 use std::error::Error;
+use std::time::Duration;
+use tracing::{Instrument, info, info_span};
+
+use messages::{Msg, t, tf};
 
 // 基础 ETH 转账的 Gas 限额（行业通用值）
 const BASIC_TRANSFER_GAS_LIMIT: u64 = 21000;
 
+// 费用对比中模拟转账的接收地址（只估算，不发送）
+const REPORT_RECIPIENT: &str = "0x51F14ab69C8f748F72b6DB1Aa66875faf7c24Bd2";
+
+// 两次提醒之间的默认最短间隔（秒）
+const DEFAULT_ALERT_COOLDOWN_SECS: u64 = 300;
+
+/// Arbitrum 测试网 Gas 费计算工具
+#[derive(Parser)]
+#[command(about = "Arbitrum 测试网 Gas 费计算")]
+struct Cli {
+    #[command(flatten)]
+    global: GlobalArgs,
+
+    /// 假设 L1 基础费变为该值（Gwei），对比当前与假设情况下的交易费用
+    #[arg(long, value_name = "GWEI")]
+    l1_basefee_gwei: Option<String>,
+
+    /// 对比时使用的交易 calldata（十六进制），不传时按普通 ETH 转账计算
+    #[arg(long, value_name = "HEX", requires = "l1_basefee_gwei")]
+    calldata: Option<String>,
+
+    /// ETH 的美元价格，用于同时显示美元金额（默认读取 ARBI_ETH_USD，都没有时不显示）
+    #[arg(long, value_name = "USD")]
+    eth_usd: Option<String>,
+
+    /// 查看一笔已打包交易的 Gas 限额、实际使用量、单价和总费用（Arbitrum 上拆分出 L1 数据费）
+    #[arg(long, value_name = "HASH", conflicts_with_all = ["l1_basefee_gwei", "watch"])]
+    tx: Option<String>,
+
+    /// 持续采样基础费，每隔 SECS 秒（默认 10）打印一次，Ctrl-C 退出
+    #[arg(
+        long,
+        value_name = "SECS",
+        num_args = 0..=1,
+        default_missing_value = "10",
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with = "l1_basefee_gwei"
+    )]
+    watch: Option<u64>,
+
+    /// 基础费低于该值（Gwei）时提醒；之后要涨回阈值 × 1.1 以上才会再次提醒
+    #[arg(long, value_name = "GWEI", requires = "watch")]
+    alert_below_gwei: Option<String>,
+
+    /// 每次提醒执行的 shell 命令，价格通过 ARBI_GAS_* 环境变量传入
+    #[arg(long, value_name = "COMMAND", requires = "alert_below_gwei")]
+    exec: Option<String>,
+
+    /// 第一次提醒后以退出码 0 退出
+    #[arg(long, requires = "alert_below_gwei")]
+    once: bool,
+
+    /// 两次提醒之间的最短间隔（秒）
+    #[arg(
+        long,
+        value_name = "SECS",
+        default_value_t = DEFAULT_ALERT_COOLDOWN_SECS,
+        requires = "alert_below_gwei",
+        conflicts_with = "once"
+    )]
+    cooldown: u64,
+}
+
 /// 获取 Arbitrum 测试网的实时 Gas 价格
 ///
+/// # 参数
+/// * `provider` - Provider 引用
+///
 /// # 返回
 /// * `Result<U256, Box<dyn Error>>` - Gas 价格（单位：wei）
-async fn get_gas_price() -> Result<U256, Box<dyn Error>> {
-    // Arbitrum Sepolia 测试网 RPC URL
-    let rpc_url = "https://sepolia-rollup.arbitrum.io/rpc";
-
-    // 创建 HTTP Provider
-    let provider = Provider::<Http>::try_from(rpc_url)?;
-
+async fn get_gas_price(provider: &RpcProvider) -> Result<U256, Box<dyn Error>> {
     // 获取当前 Gas 价格
     let gas_price = provider.get_gas_price().await?;
 
     Ok(gas_price)
 }
 
-/// 计算预估转账 Gas 费
+/// 用 wei、Gwei、ETH 和（配置了价格时）美元对齐显示 Gas 价格和基础转账的 Gas 费
 ///
 /// # 参数
-/// * `gas_limit` - Gas 限额（可选，默认使用基础转账的 21000）
+/// * `gas_price` - Gas 价格（wei）
+/// * `eth_usd` - ETH 的美元价格，`None` 时省略美元一行
 ///
 /// # 返回
-/// * `Result<(String, String, String), Box<dyn Error>>` - (Gas价格(Gwei), Gas限额, Gas费(ETH))
-async fn calculate_gas_fee(gas_limit: Option<u64>) -> Result<(String, String, String), Box<dyn Error>> {
-    // 获取实时 Gas 价格
-    let gas_price = get_gas_price().await?;
-
-    // 使用提供的 Gas 限额，或默认使用基础转账的 21000
-    let gas_limit = gas_limit.unwrap_or(BASIC_TRANSFER_GAS_LIMIT);
-
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+fn print_price_units(gas_price: U256, eth_usd: Option<f64>) -> Result<(), Box<dyn Error>> {
     // 计算 Gas 费：Gas 价格 × Gas 限额
-    let gas_fee = gas_price * U256::from(gas_limit);
-
-    // 格式化输出
-    let gas_price_gwei = format_units(gas_price, "gwei")?;
-    let gas_fee_eth = format_units(gas_fee, "ether")?;
+    let gas_fee = gas_price * U256::from(BASIC_TRANSFER_GAS_LIMIT);
 
-    Ok((
-        gas_price_gwei,
-        gas_limit.to_string(),
-        gas_fee_eth,
-    ))
+    println!("{:<6} {:>28} {:>28}", "", t(Msg::UnitsGasPrice), t(Msg::UnitsTransferFee));
+    let row = |unit: &str, price: String, fee: String| {
+        println!("{:<6} {:>28} {:>28}", unit, price, fee);
+    };
+    row("wei", units::group_digits(gas_price), units::group_digits(gas_fee));
+    row("Gwei", units::format_exact(gas_price, 9)?, units::format_exact(gas_fee, 9)?);
+    row("ETH", units::format_exact(gas_price, 18)?, units::format_exact(gas_fee, 18)?);
+    match eth_usd {
+        Some(rate) => {
+            row("USD", units::format_usd(gas_price, rate), units::format_usd(gas_fee, rate));
+            narrate!("{}", tf(Msg::UnitsRate, &[&rate]));
+        }
+        None => narrate!("{}", tf(Msg::UnitsNoRate, &[&units::ETH_USD_ENV])),
+    }
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    println!("=== Arbitrum 测试网 Gas 费计算 ===\n");
+    let cli = Cli::parse();
+    cli.global.init();
+
+    narrate!("{}\n", t(Msg::Title));
+    let eth_usd = units::eth_usd_rate(cli.eth_usd.as_deref())?;
+
+    // 创建 HTTP Provider
+    let rpc_url = network::current().rpc_url();
+    let provider = rpc::connect(rpc_url)?;
+
+    if let Some(hash) = &cli.tx {
+        let hash = parse_tx_hash(hash).map_err(|e| tf(Msg::InvalidTxHash, &[&e]))?;
+        let (tx, receipt) = tx_cost::fetch(&provider, hash)
+            .instrument(info_span!("tx_cost", rpc_url))
+            .await?;
+        return tx_cost::print(hash, &tx_cost::tx_cost(&tx, &receipt), eth_usd);
+    }
+
+    if let Some(secs) = cli.watch {
+        let alert = match &cli.alert_below_gwei {
+            Some(gwei) => {
+                let threshold = units::parse_amount(gwei, "gwei")?;
+                Some(watch::GasAlert::new(threshold, Duration::from_secs(cli.cooldown)))
+            }
+            None => None,
+        };
+        let options = watch::WatchOptions {
+            interval: Duration::from_secs(secs),
+            alert,
+            exec: cli.exec,
+            once: cli.once,
+        };
+        match watch::watch(&provider, options).instrument(info_span!("watch", rpc_url)).await {
+            Ok(()) => return Ok(()),
+            Err(e) if e.is::<Interrupted>() => {
+                eprintln!("\n{}", style::warn(e));
+                std::process::exit(INTERRUPTED_EXIT_CODE);
+            }
+            Err(e) => return Err(e),
+        }
+    }
 
     // 1. 获取实时 Gas 价格
-    println!("正在获取实时 Gas 价格...");
-    let gas_price = get_gas_price().await?;
-    let gas_price_gwei = format_units(gas_price, "gwei")?;
-    println!("当前 Gas 价格: {} Gwei", gas_price_gwei);
-    println!("当前 Gas 价格 (wei): {}\n", gas_price);
-
-    // 2. 计算基础转账的 Gas 费
-    println!("--- 基础 ETH 转账 Gas 费计算 ---");
-    let (price, limit, fee) = calculate_gas_fee(None).await?;
-    println!("Gas 价格: {} Gwei", price);
-    println!("Gas 限额: {}", limit);
-    println!("预估 Gas 费: {} ETH\n", fee);
+    narrate!("{}", t(Msg::FetchingGasPrice));
+    let gas_price = get_gas_price(&provider)
+        .instrument(info_span!("estimate", rpc_url))
+        .await?;
+    info!(gas_price_wei = %gas_price, "gas price fetched");
+
+    // 2. 同时用各个单位显示 Gas 价格和基础转账的 Gas 费
+    narrate!("{}", tf(Msg::TransferSection, &[&BASIC_TRANSFER_GAS_LIMIT]));
+    print_price_units(gas_price, eth_usd)?;
+    println!();
+
+    // 3. 按 EIP-1559 公式预测下一区块的基础费
+    narrate!("{}", t(Msg::BaseFeeSection));
+    let prediction = base_fee::next_base_fee(&provider)
+        .instrument(info_span!("estimate", rpc_url))
+        .await?;
+    match prediction {
+        Some(p) => {
+            let gas_used_percent = if p.gas_target.is_zero() {
+                0.0
+            } else {
+                p.gas_used.as_u128() as f64 * 100.0 / p.gas_target.as_u128() as f64
+            };
+            println!("{}", tf(Msg::BaseFeeBlock, &[&p.block_number]));
+            println!("{}", tf(Msg::BaseFeeCurrent, &[&format_units(p.current, "gwei")?]));
+            println!(
+                "{}",
+                tf(
+                    Msg::BaseFeeGasUsed,
+                    &[&p.gas_used, &p.gas_target, &format!("{:.2}", gas_used_percent)]
+                )
+            );
+            println!(
+                "{}",
+                style::ok(tf(Msg::BaseFeeNext, &[&format_units(p.next, "gwei")?]))
+            );
+            narrate!("{}\n", t(Msg::BaseFeeArbitrumNote));
+        }
+        None => println!("{}\n", style::warn(t(Msg::NoBaseFee))),
+    }
+
+    // 4. 同一笔转账在 legacy 和 EIP-1559 定价下的费用对比
+    narrate!("{}", t(Msg::ReportSection));
+    let tx = TransactionRequest::new()
+        .to(REPORT_RECIPIENT.parse::<Address>()?)
+        .value(0)
+        .gas(BASIC_TRANSFER_GAS_LIMIT)
+        .into();
+    let report = gas_report::gas_report(&provider, &tx)
+        .instrument(info_span!("estimate", rpc_url))
+        .await?;
+    gas_report::print_gas_report(&report)?;
+    println!();
+
+    // 5. 假设 L1 基础费变化时的费用对比
+    if let Some(l1_basefee_gwei) = &cli.l1_basefee_gwei {
+        narrate!("{}", t(Msg::WhatIfSection));
+        let target = units::parse_amount(l1_basefee_gwei, "gwei")?;
+        let calldata = match &cli.calldata {
+            Some(data) => hex::decode(data.trim())?,
+            None => Vec::new(),
+        };
+        let tx = l1_projection::TxShape::from_calldata(&calldata);
+
+        let prices = l1_projection::l1_prices(&provider)
+            .instrument(info_span!("estimate", rpc_url))
+            .await?;
+        let current = l1_projection::project(&prices, gas_price, &tx, None);
+        let what_if = l1_projection::project(&prices, gas_price, &tx, Some(target));
+
+        println!("{}", tf(Msg::WhatIfTx, &[&tx.l2_gas, &tx.tx_bytes]));
+        println!("{:<24} {:>24} {:>24}", "", t(Msg::WhatIfCurrent), t(Msg::WhatIfHypothetical));
+        let rows = [
+            (Msg::WhatIfL1BaseFee, current.l1_base_fee, what_if.l1_base_fee, "gwei"),
+            (
+                Msg::WhatIfPerByte,
+                current.per_l1_calldata_byte,
+                what_if.per_l1_calldata_byte,
+                "wei",
+            ),
+            (Msg::WhatIfL1Cost, current.l1_cost, what_if.l1_cost, "ether"),
+            (Msg::WhatIfL2Cost, current.l2_cost, what_if.l2_cost, "ether"),
+            (Msg::WhatIfTotal, current.total, what_if.total, "ether"),
+        ];
+        for (label, now, then, unit) in rows {
+            println!(
+                "{:<24} {:>24} {:>24}",
+                t(label),
+                format_units(now, unit)?,
+                format_units(then, unit)?
+            );
+        }
+        narrate!("{}\n", t(Msg::WhatIfNote));
+    }
 
     Ok(())
 }
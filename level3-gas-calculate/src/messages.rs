@@ -0,0 +1,84 @@
+//! level3 输出的消息目录
+
+arbi_common::define_messages! {
+    Title => ("=== Arbitrum 测试网 Gas 费计算 ===", "=== Arbitrum Testnet Gas Fee Calculator ==="),
+    FetchingGasPrice => ("正在获取实时 Gas 价格...", "Fetching the current gas price..."),
+    TransferSection => (
+        "--- 当前 Gas 价格与基础 ETH 转账 Gas 费（Gas 限额 {}）---",
+        "--- Current gas price and basic ETH transfer fee (gas limit {}) ---"
+    ),
+    UnitsGasPrice => ("Gas 价格", "Gas price"),
+    UnitsTransferFee => ("转账 Gas 费", "Transfer fee"),
+    UnitsRate => ("（美元按 1 ETH = ${} 换算）", "(USD at 1 ETH = ${})"),
+    UnitsNoRate => ("（用 --eth-usd 或 {} 设置 ETH 价格后会同时显示美元）", "(set --eth-usd or {} to also show USD)"),
+    BaseFeeSection => ("--- 下一区块基础费预测（EIP-1559）---", "--- Next block base fee (EIP-1559) ---"),
+    LatestBlockMissing => ("节点没有返回最新区块", "The node returned no latest block"),
+    BaseFeeBlock => ("最新区块: {}", "Latest block: {}"),
+    BaseFeeCurrent => ("当前基础费: {} Gwei", "Current base fee: {} Gwei"),
+    BaseFeeGasUsed => ("Gas 使用量: {} / 目标 {}（{}%）", "Gas used: {} / target {} ({}%)"),
+    BaseFeeNext => ("预测下一区块基础费: {} Gwei", "Predicted next base fee: {} Gwei"),
+    BaseFeeArbitrumNote => (
+        "（使用量高于目标时上涨、低于目标时下降，每块最多 ±12.5%；Arbitrum 实际由 ArbOS 定价且有最低价，预测值仅作参考）",
+        "(Rises when usage is above target and falls when below, at most ±12.5% per block; Arbitrum actually prices gas in ArbOS with a floor, so treat this as a reference)"
+    ),
+    ReportSection => ("--- legacy 与 EIP-1559 定价对比（同一笔 ETH 转账）---", "--- Legacy vs EIP-1559 pricing (same ETH transfer) ---"),
+    ReportGas => ("Gas: {}", "Gas: {}"),
+    ReportLegacy => ("legacy", "Legacy"),
+    ReportEip1559 => ("EIP-1559", "EIP-1559"),
+    ReportGasPrice => ("Gas 价格 (Gwei)", "Gas price (gwei)"),
+    ReportBaseFee => ("基础费 (Gwei，销毁)", "Base fee (gwei, burned)"),
+    ReportPriorityFee => ("小费 (Gwei)", "Priority fee (gwei)"),
+    ReportMaxFeePerGas => ("最高单价 (Gwei)", "Max fee per gas (gwei)"),
+    ReportExpectedFee => ("预计支付 (ETH)", "Expected cost (ETH)"),
+    ReportMaxFee => ("最多支付 (ETH)", "Maximum cost (ETH)"),
+    ReportLegacyCheaper => ("✓ 当前 legacy 定价更便宜", "✓ Legacy pricing is cheaper right now"),
+    ReportEip1559Cheaper => ("✓ 当前 EIP-1559 定价更便宜（多预留的部分会退回）", "✓ EIP-1559 pricing is cheaper right now (the unused reserve is refunded)"),
+    ReportSameCost => ("✓ 两种定价当前费用相同", "✓ Both pricing modes cost the same right now"),
+    ReportNoLegacy => ("⚠ 节点不支持 eth_gasPrice，只显示 EIP-1559 定价", "⚠ The node does not support eth_gasPrice; showing EIP-1559 only"),
+    ReportNoEip1559 => ("⚠ 该链不支持 EIP-1559，只显示 legacy 定价", "⚠ This chain does not support EIP-1559; showing legacy only"),
+    ReportNoPricing => ("⚠ 两种定价方式都无法估算", "⚠ Neither pricing mode could be estimated"),
+    WhatIfSection => ("--- 假设 L1 基础费变化时的费用对比 ---", "--- What if the L1 base fee changes ---"),
+    WhatIfTx => ("交易: L2 执行 {} Gas，发布到 L1 约 {} 字节", "Transaction: {} L2 gas, ~{} bytes posted to L1"),
+    WhatIfCurrent => ("当前", "Current"),
+    WhatIfHypothetical => ("假设", "Hypothetical"),
+    WhatIfL1BaseFee => ("L1 基础费 (Gwei)", "L1 base fee (gwei)"),
+    WhatIfPerByte => ("每字节价格 (wei)", "Per byte (wei)"),
+    WhatIfL1Cost => ("L1 数据费 (ETH)", "L1 data fee (ETH)"),
+    WhatIfL2Cost => ("L2 执行费 (ETH)", "L2 execution (ETH)"),
+    WhatIfTotal => ("合计 (ETH)", "Total (ETH)"),
+    WhatIfNote => (
+        "（L1 基础费由每字节价格 ÷ 16 反推；L1 数据费随它按比例变化，L2 执行费不变）",
+        "(The L1 base fee is backed out as per-byte price ÷ 16; the L1 data fee scales with it while L2 execution stays the same)"
+    ),
+    WatchStarted => ("开始监控基础费，每 {} 秒采样一次，Ctrl-C 退出", "Watching the base fee every {}s, Ctrl-C to stop"),
+    WatchAlertStarted => (
+        "开始监控基础费：低于 {} Gwei 时提醒（涨回 {} Gwei 以上后才会再次提醒），每 {} 秒采样一次，Ctrl-C 退出",
+        "Watching the base fee: alert below {} Gwei (re-arms after it rises above {} Gwei), sampling every {}s, Ctrl-C to stop"
+    ),
+    WatchSample => ("区块 #{}: 基础费 {} Gwei", "Block #{}: base fee {} Gwei"),
+    WatchAlert => (
+        "🔔🔔🔔 区块 #{}: 基础费 {} Gwei，低于阈值 {} Gwei 🔔🔔🔔",
+        "🔔🔔🔔 Block #{}: base fee {} Gwei is below the {} Gwei threshold 🔔🔔🔔"
+    ),
+    WatchSampleFailed => ("⚠ 采样失败，稍后重试: {}", "⚠ Sampling failed, retrying: {}"),
+    InvalidTxHash => ("无效的交易哈希: {}", "Invalid transaction hash: {}"),
+    TxNotFound => ("节点上找不到交易 {}", "Transaction {} was not found on the node"),
+    TxPending => ("交易 {} 还没有打包，没有收据", "Transaction {} is not mined yet and has no receipt"),
+    TxCostSection => ("--- 交易 {} 的 Gas 使用情况 ---", "--- Gas usage of transaction {} ---"),
+    TxStatus => ("状态: {}", "Status: {}"),
+    TxSucceeded => ("成功", "succeeded"),
+    TxReverted => ("失败（Gas 照付）", "reverted (gas still paid)"),
+    TxGasLimit => ("Gas 限额: {}", "Gas limit: {}"),
+    TxGasUsed => ("实际使用: {}（占限额 {}%）", "Gas used: {} ({}% of the limit)"),
+    TxEffectivePrice => ("实际单价: {} Gwei", "Effective gas price: {} Gwei"),
+    TxTotalCost => ("总费用: {} ETH", "Total cost: {} ETH"),
+    TxTotalCostUsd => ("总费用: {} ETH（{}）", "Total cost: {} ETH ({})"),
+    TxL1Portion => ("其中 L1 数据: {} ETH（{}%，折合 {} Gas）", "L1 data: {} ETH ({}%, {} gas)"),
+    TxL1Fee => ("其中 L1 数据: {} ETH（{}%）", "L1 data: {} ETH ({}%)"),
+    TxL2Portion => ("其中 L2 执行: {} ETH（{} Gas）", "L2 execution: {} ETH ({} gas)"),
+    TxNoL1Fields => (
+        "（收据里没有 gasUsedForL1 / l1Fee 字段，无法拆分 L1 数据费；anvil 等非 Arbitrum 节点没有这些字段）",
+        "(The receipt has no gasUsedForL1 / l1Fee fields, so the L1 data fee cannot be split out; non-Arbitrum nodes such as anvil do not return them)"
+    ),
+    NoBaseFee => ("该链没有基础费（不支持 EIP-1559）", "No base fee (this chain does not support EIP-1559)"),
+}
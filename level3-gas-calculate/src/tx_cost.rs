@@ -0,0 +1,312 @@
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use arbi_common::units;
+use ethers::providers::Middleware;
+use ethers::types::{OtherFields, Transaction, TransactionReceipt, TxHash, U256};
+use ethers::utils::format_units;
+use serde_json::Value;
+use std::error::Error;
+
+use crate::messages::{Msg, t, tf};
+
+/// 收据里和 L1 数据费有关的扩展字段
+///
+/// Arbitrum 节点在收据里加了 `gasUsedForL1`（L1 数据费折算成的 L2 Gas，已包含在 `gasUsed` 里）；
+/// OP Stack 等链给出 `l1Fee`（wei），在 `gasUsed × 单价` 之外另收。anvil 等普通节点两个都没有。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct L1ReceiptFields {
+    pub gas_used_for_l1: Option<U256>,
+    pub l1_fee: Option<U256>,
+}
+
+// 解析收据扩展字段的数值：`0x` 十六进制字符串、十进制字符串或 JSON 数字，其他格式当作没有
+fn quantity(value: Option<&Value>) -> Option<U256> {
+    match value? {
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => U256::from_str_radix(hex, 16).ok(),
+            None => U256::from_dec_str(s).ok(),
+        },
+        Value::Number(n) => n.as_u64().map(U256::from),
+        _ => None,
+    }
+}
+
+/// 从收据的原始 JSON 扩展字段中读取 L1 数据费相关的字段，字段不存在或格式不对时为 `None`
+///
+/// # 参数
+/// * `other` - 收据里 ethers 不认识的字段（`TransactionReceipt::other`）
+///
+/// # 返回
+/// * `L1ReceiptFields` - 读到的字段
+pub fn l1_receipt_fields(other: &OtherFields) -> L1ReceiptFields {
+    L1ReceiptFields {
+        gas_used_for_l1: quantity(other.get("gasUsedForL1")),
+        l1_fee: quantity(other.get("l1Fee")),
+    }
+}
+
+/// 交易费用中属于 L1 数据的部分
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct L1Portion {
+    /// 折算成的 L2 Gas；收据只给出 `l1Fee` 时为 `None`
+    pub gas: Option<U256>,
+    /// 费用（wei）
+    pub fee: U256,
+}
+
+/// 一笔已打包交易的 Gas 使用情况
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxCost {
+    pub gas_limit: U256,
+    pub gas_used: U256,
+    /// 实际支付的单价（wei）
+    pub effective_gas_price: U256,
+    /// 总费用（wei）：`gas_used × effective_gas_price`，收据单独给出 `l1Fee` 时再加上它
+    pub total: U256,
+    /// 收据没有 L1 扩展字段时为 `None`
+    pub l1: Option<L1Portion>,
+    pub success: bool,
+}
+
+// `part` 占 `whole` 的百分比，`whole` 为 0 时为 0；收据字段由节点给出，可能超过 u128，
+// 所以换成 f64 再除
+fn percent(part: U256, whole: U256) -> f64 {
+    if whole.is_zero() {
+        return 0.0;
+    }
+    let to_f64 = |value: U256| value.to_string().parse::<f64>().unwrap_or_default();
+    to_f64(part) * 100.0 / to_f64(whole)
+}
+
+impl TxCost {
+    /// 实际使用的 Gas 占 Gas 限额的百分比
+    pub fn utilization_percent(&self) -> f64 {
+        percent(self.gas_used, self.gas_limit)
+    }
+
+    /// L1 数据费占总费用的百分比；收据没有 L1 扩展字段时为 `None`
+    pub fn l1_share_percent(&self) -> Option<f64> {
+        self.l1.map(|l1| percent(l1.fee, self.total))
+    }
+}
+
+/// 由交易和收据计算 Gas 使用情况
+///
+/// 有 `gasUsedForL1` 时（Arbitrum）L1 部分按实际单价折算，已包含在总费用里；只有 `l1Fee` 时
+/// 把它加到总费用上。旧节点的收据没有 `effectiveGasPrice`，这时用交易的 `gasPrice`。
+///
+/// # 参数
+/// * `tx` - 交易
+/// * `receipt` - 交易的收据
+///
+/// # 返回
+/// * `TxCost` - Gas 使用情况
+pub fn tx_cost(tx: &Transaction, receipt: &TransactionReceipt) -> TxCost {
+    let gas_used = receipt.gas_used.unwrap_or_default();
+    let effective_gas_price = receipt.effective_gas_price.or(tx.gas_price).unwrap_or_default();
+    let execution = gas_used.saturating_mul(effective_gas_price);
+    let fields = l1_receipt_fields(&receipt.other);
+    let (l1, total) = match (fields.gas_used_for_l1, fields.l1_fee) {
+        (Some(gas), _) => {
+            let fee = gas.saturating_mul(effective_gas_price);
+            (Some(L1Portion { gas: Some(gas), fee }), execution)
+        }
+        (None, Some(fee)) => (Some(L1Portion { gas: None, fee }), execution.saturating_add(fee)),
+        (None, None) => (None, execution),
+    };
+    TxCost {
+        gas_limit: tx.gas,
+        gas_used,
+        effective_gas_price,
+        total,
+        l1,
+        success: receipt.status.is_none_or(|status| status.as_u64() == 1),
+    }
+}
+
+/// 读取交易和收据
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `hash` - 交易哈希
+///
+/// # 返回
+/// * `Result<(Transaction, TransactionReceipt), Box<dyn Error>>` - 交易不存在或还没有打包时返回错误
+pub async fn fetch(
+    provider: &RpcProvider,
+    hash: TxHash,
+) -> Result<(Transaction, TransactionReceipt), Box<dyn Error>> {
+    let hash_str = format!("{:?}", hash);
+    let tx = provider
+        .get_transaction(hash)
+        .await?
+        .ok_or_else(|| tf(Msg::TxNotFound, &[&hash_str]))?;
+    let receipt = provider
+        .get_transaction_receipt(hash)
+        .await?
+        .ok_or_else(|| tf(Msg::TxPending, &[&hash_str]))?;
+    Ok((tx, receipt))
+}
+
+/// 打印 Gas 使用情况
+///
+/// # 参数
+/// * `hash` - 交易哈希
+/// * `cost` - `tx_cost` 的结果
+/// * `eth_usd` - ETH 的美元价格，`None` 时不显示美元
+pub fn print(hash: TxHash, cost: &TxCost, eth_usd: Option<f64>) -> Result<(), Box<dyn Error>> {
+    println!("{}", tf(Msg::TxCostSection, &[&format!("{:?}", hash)]));
+    match cost.success {
+        true => println!("{}", tf(Msg::TxStatus, &[&style::ok(t(Msg::TxSucceeded))])),
+        false => println!("{}", tf(Msg::TxStatus, &[&style::warn(t(Msg::TxReverted))])),
+    }
+    println!("{}", tf(Msg::TxGasLimit, &[&cost.gas_limit]));
+    let percent = format!("{:.2}", cost.utilization_percent());
+    println!("{}", tf(Msg::TxGasUsed, &[&cost.gas_used, &percent]));
+    let price = format_units(cost.effective_gas_price, "gwei")?;
+    println!("{}", tf(Msg::TxEffectivePrice, &[&price]));
+    let total = format_units(cost.total, "ether")?;
+    match eth_usd {
+        Some(rate) => {
+            let usd = units::format_usd(cost.total, rate);
+            println!("{}", tf(Msg::TxTotalCostUsd, &[&total, &usd]));
+        }
+        None => println!("{}", tf(Msg::TxTotalCost, &[&total])),
+    }
+
+    let (Some(l1), Some(share)) = (cost.l1, cost.l1_share_percent()) else {
+        println!("{}", t(Msg::TxNoL1Fields));
+        return Ok(());
+    };
+    let (fee, share) = (format_units(l1.fee, "ether")?, format!("{:.2}", share));
+    match l1.gas {
+        Some(gas) => println!("{}", tf(Msg::TxL1Portion, &[&fee, &share, &gas])),
+        None => println!("{}", tf(Msg::TxL1Fee, &[&fee, &share])),
+    }
+    let l2_fee = format_units(cost.total.saturating_sub(l1.fee), "ether")?;
+    let l2_gas = cost.gas_used.saturating_sub(l1.gas.unwrap_or_default());
+    println!("{}", tf(Msg::TxL2Portion, &[&l2_fee, &l2_gas]));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // 手写的收据和交易，字段形状照 Arbitrum Sepolia 和 anvil 的返回：
+    // gasUsed 0x1b7c8 = 112584，单价 0.1 Gwei，Gas 限额 0x249f0 = 150000
+    fn receipt(extra: Value) -> TransactionReceipt {
+        let mut receipt = json!({
+            "transactionHash": format!("{:?}", TxHash::from_low_u64_be(1)),
+            "transactionIndex": "0x1",
+            "blockHash": format!("{:?}", TxHash::from_low_u64_be(2)),
+            "blockNumber": "0x5b8d80",
+            "from": "0x1111111111111111111111111111111111111111",
+            "to": "0x2222222222222222222222222222222222222222",
+            "cumulativeGasUsed": "0x1b7c8",
+            "gasUsed": "0x1b7c8",
+            "effectiveGasPrice": "0x5f5e100",
+            "contractAddress": null,
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "status": "0x1",
+            "type": "0x2",
+        });
+        receipt.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(receipt).unwrap()
+    }
+
+    fn transaction() -> Transaction {
+        serde_json::from_value(json!({
+            "hash": format!("{:?}", TxHash::from_low_u64_be(1)),
+            "nonce": "0x7",
+            "from": "0x1111111111111111111111111111111111111111",
+            "to": "0x2222222222222222222222222222222222222222",
+            "value": "0x0",
+            "gas": "0x249f0",
+            "gasPrice": "0x5f5e100",
+            "input": "0x",
+            "v": "0x0",
+            "r": "0x0",
+            "s": "0x0",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn anvil_receipt_has_no_l1_fields() {
+        let cost = tx_cost(&transaction(), &receipt(json!({})));
+        assert_eq!(cost.l1, None);
+        assert_eq!(cost.total, U256::from(112_584u64 * 100_000_000));
+        assert_eq!(cost.l1_share_percent(), None);
+        assert!((cost.utilization_percent() - 75.056).abs() < 1e-9);
+        assert!(cost.success);
+    }
+
+    #[test]
+    fn arbitrum_gas_used_for_l1_in_every_encoding() {
+        for value in [json!("0x7530"), json!("30000"), json!(30000)] {
+            let arbitrum = json!({"gasUsedForL1": value, "l1BlockNumber": "0x6f1c2a"});
+            let cost = tx_cost(&transaction(), &receipt(arbitrum));
+            let l1 = cost.l1.unwrap();
+            assert_eq!(l1.gas, Some(U256::from(30_000)));
+            assert_eq!(l1.fee, U256::from(30_000u64 * 100_000_000));
+            // 已包含在 gasUsed 里，总费用不变
+            assert_eq!(cost.total, U256::from(112_584u64 * 100_000_000));
+            let share = cost.l1_share_percent().unwrap();
+            assert!((share - 30_000.0 * 100.0 / 112_584.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn l1_fee_in_every_encoding_is_added_to_the_total() {
+        let execution = 112_584u64 * 100_000_000;
+        for value in [json!("0x2540be400"), json!("10000000000"), json!(10_000_000_000u64)] {
+            let cost = tx_cost(&transaction(), &receipt(json!({"l1Fee": value})));
+            let fee = U256::from(10_000_000_000u64);
+            assert_eq!(cost.l1, Some(L1Portion { gas: None, fee }));
+            assert_eq!(cost.total, U256::from(execution) + fee);
+        }
+    }
+
+    #[test]
+    fn unreadable_l1_fields_are_ignored() {
+        for value in [json!("0xzz"), json!("-5"), json!(-5), json!(1.5), json!(null), json!([1])] {
+            let fields = json!({"gasUsedForL1": value.clone(), "l1Fee": value});
+            let cost = tx_cost(&transaction(), &receipt(fields));
+            assert_eq!(cost.l1, None);
+        }
+    }
+
+    #[test]
+    fn l1_fee_above_u128_does_not_panic() {
+        let huge = format!("0x{}", "f".repeat(40));
+        let cost = tx_cost(&transaction(), &receipt(json!({"l1Fee": huge})));
+        let fee = cost.l1.unwrap().fee;
+        assert!(fee > U256::from(u128::MAX));
+        let share = cost.l1_share_percent().unwrap();
+        assert!((share - 100.0).abs() < 1e-6);
+
+        // 总费用饱和时同样不会 panic
+        let cost = tx_cost(&transaction(), &receipt(json!({"l1Fee": format!("{:#x}", U256::MAX)})));
+        assert_eq!(cost.total, U256::MAX);
+        assert!(cost.l1_share_percent().unwrap() <= 100.0);
+    }
+
+    #[test]
+    fn utilization_handles_large_and_zero_limits() {
+        let cost = TxCost {
+            gas_limit: U256::MAX,
+            gas_used: U256::MAX / 2,
+            effective_gas_price: U256::zero(),
+            total: U256::zero(),
+            l1: Some(L1Portion { gas: None, fee: U256::zero() }),
+            success: true,
+        };
+        assert!((cost.utilization_percent() - 50.0).abs() < 1e-9);
+        assert_eq!(cost.l1_share_percent(), Some(0.0));
+        let cost = TxCost { gas_limit: U256::zero(), ..cost };
+        assert_eq!(cost.utilization_percent(), 0.0);
+    }
+}
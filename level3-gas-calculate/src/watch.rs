@@ -0,0 +1,254 @@
+//! 持续采样基础费；`--alert-below-gwei` 时在基础费跌破阈值时提醒
+
+use arbi_common::exec::run_exec;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::shutdown::Interrupted;
+use arbi_common::style;
+use ethers::providers::Middleware;
+use ethers::types::{BlockNumber, U256};
+use ethers::utils::format_units;
+use std::error::Error;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::info;
+
+use crate::messages::{Msg, t, tf};
+
+/// `--exec` 命令拿到的环境变量名前缀
+pub const EXEC_ENV_PREFIX: &str = "ARBI_GAS_";
+
+// 提醒之后，价格要回到阈值 × 11/10 以上才能再次提醒，避免在阈值附近来回波动时反复提醒
+const REARM_NUMERATOR: u64 = 11;
+const REARM_DENOMINATOR: u64 = 10;
+
+/// 阈值提醒的状态机
+///
+/// 初始为待触发：价格低于阈值时提醒一次，之后价格必须先涨回阈值 × 1.1 以上才重新待触发；
+/// 两次提醒之间还要间隔 `cooldown`，冷却期内跌破阈值会等冷却结束后再提醒。
+pub struct GasAlert {
+    threshold: U256,
+    rearm_above: U256,
+    cooldown: Duration,
+    armed: bool,
+    last_fired: Option<Instant>,
+}
+
+impl GasAlert {
+    /// 创建待触发的提醒
+    ///
+    /// # 参数
+    /// * `threshold` - 阈值（wei）
+    /// * `cooldown` - 两次提醒的最短间隔
+    pub fn new(threshold: U256, cooldown: Duration) -> Self {
+        let rearm_above = threshold.saturating_mul(REARM_NUMERATOR.into()) / REARM_DENOMINATOR;
+        Self {
+            threshold,
+            rearm_above,
+            cooldown,
+            armed: true,
+            last_fired: None,
+        }
+    }
+
+    /// 阈值（wei）
+    pub fn threshold(&self) -> U256 {
+        self.threshold
+    }
+
+    /// 提醒后价格要超过多少才重新待触发（wei）
+    pub fn rearm_above(&self) -> U256 {
+        self.rearm_above
+    }
+
+    /// 输入一次采样，判断这次是否提醒
+    ///
+    /// # 参数
+    /// * `price` - 采样到的价格（wei）
+    /// * `now` - 采样时间
+    ///
+    /// # 返回
+    /// * `bool` - 需要提醒时为 `true`
+    pub fn observe(&mut self, price: U256, now: Instant) -> bool {
+        if !self.armed {
+            self.armed = price > self.rearm_above;
+            return false;
+        }
+        let cooled = self
+            .last_fired
+            .is_none_or(|fired| now.saturating_duration_since(fired) >= self.cooldown);
+        if price < self.threshold && cooled {
+            self.armed = false;
+            self.last_fired = Some(now);
+            return true;
+        }
+        false
+    }
+}
+
+/// 监控选项
+pub struct WatchOptions {
+    /// 采样间隔
+    pub interval: Duration,
+    /// 基础费跌破阈值时提醒
+    pub alert: Option<GasAlert>,
+    /// 每次提醒执行的命令
+    pub exec: Option<String>,
+    /// 第一次提醒后退出
+    pub once: bool,
+}
+
+/// 读取最新区块号和基础费；链不支持 EIP-1559 时退回 `eth_gasPrice`
+///
+/// # 参数
+/// * `provider` - Provider 引用
+///
+/// # 返回
+/// * `Result<(u64, U256), Box<dyn Error>>` - 区块号和价格（wei）
+pub async fn sample(provider: &RpcProvider) -> Result<(u64, U256), Box<dyn Error>> {
+    let block = provider
+        .get_block(BlockNumber::Latest)
+        .await?
+        .ok_or_else(|| t(Msg::LatestBlockMissing))?;
+    let number = block.number.map(|n| n.as_u64()).unwrap_or_default();
+    let price = match block.base_fee_per_gas {
+        Some(base_fee) => base_fee,
+        None => provider.get_gas_price().await?,
+    };
+    Ok((number, price))
+}
+
+/// `--exec` 命令拿到的环境变量
+///
+/// # 参数
+/// * `block` - 区块号
+/// * `price` - 价格（wei）
+/// * `threshold` - 阈值（wei）
+///
+/// # 返回
+/// * `Vec<(String, String)>` - 变量名和值
+pub fn exec_env(block: u64, price: U256, threshold: U256) -> Vec<(String, String)> {
+    let gwei = |wei: U256| format_units(wei, "gwei").unwrap_or_else(|_| wei.to_string());
+    [
+        ("BLOCK", block.to_string()),
+        ("FEE_WEI", price.to_string()),
+        ("FEE_GWEI", gwei(price)),
+        ("THRESHOLD_GWEI", gwei(threshold)),
+    ]
+    .into_iter()
+    .map(|(name, value)| (format!("{}{}", EXEC_ENV_PREFIX, name), value))
+    .collect()
+}
+
+/// 持续采样基础费，直到 Ctrl-C（`once` 时直到第一次提醒）
+///
+/// 每次采样打印一行；设置了阈值时按 [`GasAlert`] 判断是否提醒，提醒时执行 `exec` 命令。
+/// 某次采样失败只打印警告，下次继续。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `options` - 采样间隔、阈值、命令和是否提醒后退出
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - `once` 时提醒前按 Ctrl-C 返回 [`Interrupted`]
+pub async fn watch(
+    provider: &RpcProvider,
+    mut options: WatchOptions,
+) -> Result<(), Box<dyn Error>> {
+    let secs = options.interval.as_secs();
+    match &options.alert {
+        Some(alert) => {
+            let threshold = format_units(alert.threshold(), "gwei")?;
+            let rearm = format_units(alert.rearm_above(), "gwei")?;
+            println!("{}", tf(Msg::WatchAlertStarted, &[&threshold, &rearm, &secs]));
+        }
+        None => println!("{}", tf(Msg::WatchStarted, &[&secs])),
+    }
+
+    loop {
+        match sample(provider).await {
+            Ok((block, price)) => {
+                let gwei = format_units(price, "gwei")?;
+                println!("{}", tf(Msg::WatchSample, &[&block, &gwei]));
+                if let Some(alert) = options.alert.as_mut()
+                    && alert.observe(price, Instant::now())
+                {
+                    let threshold = format_units(alert.threshold(), "gwei")?;
+                    info!(block, price_wei = %price, "gas alert fired");
+                    let line = tf(Msg::WatchAlert, &[&block, &gwei, &threshold]);
+                    println!("\n{}\n", style::ok(line));
+                    if let Some(command) = &options.exec {
+                        run_exec(command, exec_env(block, price, alert.threshold())).await;
+                    }
+                    if options.once {
+                        return Ok(());
+                    }
+                }
+            }
+            Err(e) => eprintln!("{}", style::warn(tf(Msg::WatchSampleFailed, &[&e]))),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(options.interval) => {}
+            _ = tokio::signal::ctrl_c() => {
+                return match options.once {
+                    true => Err(Interrupted.into()),
+                    false => Ok(()),
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 按 (距开始的秒数, 价格) 依次采样，返回每次是否提醒
+    fn run(alert: &mut GasAlert, samples: &[(u64, u64)]) -> Vec<bool> {
+        let start = Instant::now();
+        samples
+            .iter()
+            .map(|&(secs, price)| alert.observe(price.into(), start + Duration::from_secs(secs)))
+            .collect()
+    }
+
+    #[test]
+    fn rearm_level_is_ten_percent_above_threshold() {
+        let alert = GasAlert::new(U256::from(100), Duration::ZERO);
+        assert_eq!(alert.rearm_above(), U256::from(110));
+        let alert = GasAlert::new(U256::MAX, Duration::ZERO);
+        assert_eq!(alert.rearm_above(), U256::MAX / 10);
+    }
+
+    #[test]
+    fn fires_once_until_price_recovers_above_rearm_level() {
+        let mut alert = GasAlert::new(U256::from(100), Duration::ZERO);
+        let fired = run(
+            &mut alert,
+            &[(0, 120), (1, 100), (2, 99), (3, 90), (4, 105), (5, 99), (6, 110), (7, 99)],
+        );
+        // 等于阈值不提醒；提醒后回到 105、110 都不够重新待触发
+        assert_eq!(fired, [false, false, true, false, false, false, false, false]);
+
+        let fired = run(&mut alert, &[(8, 111), (9, 99), (10, 98)]);
+        assert_eq!(fired, [false, true, false]);
+    }
+
+    #[test]
+    fn rearmed_alert_waits_for_cooldown() {
+        let mut alert = GasAlert::new(U256::from(100), Duration::from_secs(60));
+        let fired = run(
+            &mut alert,
+            &[(0, 50), (10, 200), (20, 50), (59, 50), (60, 50), (61, 50)],
+        );
+        // 10 秒时已经重新待触发，但要到上次提醒 60 秒后才再次提醒
+        assert_eq!(fired, [true, false, false, false, true, false]);
+    }
+
+    #[test]
+    fn cooldown_alone_does_not_rearm() {
+        let mut alert = GasAlert::new(U256::from(100), Duration::from_secs(60));
+        let fired = run(&mut alert, &[(0, 50), (120, 50), (600, 50)]);
+        assert_eq!(fired, [true, false, false]);
+    }
+}
@@ -0,0 +1,306 @@
+use arbi_common::narrate;
+use arbi_common::network;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::shutdown::{Interrupted, Shutdown};
+use arbi_common::style;
+use ethers::abi::Abi;
+use ethers::prelude::*;
+use ethers::signers::LocalWallet;
+use ethers::types::{Address, Bytes, TransactionReceipt, U256};
+use ethers::utils::rlp::Rlp;
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{Instrument, Span, field, info_span};
+
+use crate::messages::{Msg, t, tf};
+
+// ArbAddressTable 预编译合约地址
+const ARB_ADDRESS_TABLE_ADDRESS: &str = "0x0000000000000000000000000000000000000066";
+
+// ArbAddressTable 的 ABI 片段
+const ARB_ADDRESS_TABLE_ABI: &str = r#"[
+    {
+        "inputs": [{"name": "addr", "type": "address"}],
+        "name": "register",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "nonpayable",
+        "type": "function"
+    },
+    {
+        "inputs": [{"name": "addr", "type": "address"}],
+        "name": "lookup",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [{"name": "index", "type": "uint256"}],
+        "name": "lookupIndex",
+        "outputs": [{"name": "", "type": "address"}],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [{"name": "addr", "type": "address"}],
+        "name": "compress",
+        "outputs": [{"name": "", "type": "bytes"}],
+        "stateMutability": "nonpayable",
+        "type": "function"
+    },
+    {
+        "inputs": [],
+        "name": "size",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "view",
+        "type": "function"
+    }
+]"#;
+
+// 未压缩的地址在 calldata 中占的字节数
+const ADDRESS_BYTES: usize = 20;
+
+/// `compress` 返回的压缩形式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compressed {
+    /// 已注册：RLP 编码的表内序号
+    Index(u64),
+    /// 未注册：RLP 编码的完整地址
+    Address(Address),
+}
+
+/// 解码 `compress` 返回的字节
+///
+/// 已注册的地址压缩成 RLP 编码的序号（序号小于 128 时只有 1 字节），未注册的地址编码成
+/// `0x94` 加 20 字节地址。
+///
+/// # 参数
+/// * `data` - `compress` 返回的字节
+///
+/// # 返回
+/// * `Result<Compressed, Box<dyn Error>>` - 序号或地址
+pub fn decode_compressed(data: &[u8]) -> Result<Compressed, Box<dyn Error>> {
+    let rlp = Rlp::new(data);
+    let payload = rlp.data()?;
+    if payload.len() == ADDRESS_BYTES {
+        return Ok(Compressed::Address(Address::from_slice(payload)));
+    }
+    let index: u64 = rlp.as_val()?;
+    Ok(Compressed::Index(index))
+}
+
+/// 一个地址在地址表中的情况
+pub struct AddressEntry {
+    pub address: Address,
+    /// 表内序号，未注册时为 `None`
+    pub index: Option<U256>,
+    /// `compress` 返回的压缩形式
+    pub compressed: Bytes,
+}
+
+impl AddressEntry {
+    /// 打印序号、压缩后的大小和节省的 calldata
+    pub fn print(&self) -> Result<(), Box<dyn Error>> {
+        println!("{}", tf(Msg::AddrTableAddress, &[&style::addr(self.address)]));
+        match self.index {
+            Some(index) => println!("{}", style::ok(tf(Msg::AddrTableIndex, &[&index]))),
+            None => println!("{}", style::warn(t(Msg::AddrTableNotRegistered))),
+        }
+
+        let form = match decode_compressed(&self.compressed)? {
+            Compressed::Index(index) => tf(Msg::AddrTableFormIndex, &[&index]),
+            Compressed::Address(_) => t(Msg::AddrTableFormAddress).to_string(),
+        };
+        println!(
+            "{}",
+            tf(Msg::AddrTableCompressed, &[&self.compressed, &self.compressed.len(), &form])
+        );
+        let saved = ADDRESS_BYTES.saturating_sub(self.compressed.len());
+        println!("\n{}", tf(Msg::AddrTableSavings, &[&saved]));
+        Ok(())
+    }
+}
+
+fn address_table<M: Middleware>(client: Arc<M>) -> Result<Contract<M>, Box<dyn Error>> {
+    Ok(Contract::new(
+        Address::from_str(ARB_ADDRESS_TABLE_ADDRESS)?,
+        serde_json::from_str::<Abi>(ARB_ADDRESS_TABLE_ABI)?,
+        client,
+    ))
+}
+
+/// 查询地址在表中的序号，未注册时 `lookup` 会 revert，这里返回 `None`
+///
+/// # 参数
+/// * `provider` - L2 Provider
+/// * `address` - 要查询的地址
+///
+/// # 返回
+/// * `Result<AddressEntry, Box<dyn Error>>` - 序号和压缩形式
+pub async fn lookup(
+    provider: &RpcProvider,
+    address: Address,
+) -> Result<AddressEntry, Box<dyn Error>> {
+    let contract = address_table(Arc::new(provider.clone()))?;
+    let index = match contract.method::<_, U256>("lookup", address)?.call().await {
+        Ok(index) => Some(index),
+        Err(e) if e.is_revert() => None,
+        Err(e) => return Err(e.into()),
+    };
+    let compressed: Bytes = contract.method("compress", address)?.call().await?;
+    Ok(AddressEntry {
+        address,
+        index,
+        compressed,
+    })
+}
+
+/// 按序号查询地址，序号超出表大小时 `lookupIndex` 会 revert，这里返回 `None`
+///
+/// # 参数
+/// * `provider` - L2 Provider
+/// * `index` - 表内序号
+///
+/// # 返回
+/// * `Result<(Option<Address>, U256), Box<dyn Error>>` - 地址和表的当前大小
+pub async fn lookup_index(
+    provider: &RpcProvider,
+    index: U256,
+) -> Result<(Option<Address>, U256), Box<dyn Error>> {
+    let contract = address_table(Arc::new(provider.clone()))?;
+    let size: U256 = contract.method("size", ())?.call().await?;
+    let address = match contract.method::<_, Address>("lookupIndex", index)?.call().await {
+        Ok(address) => Some(address),
+        Err(e) if e.is_revert() => None,
+        Err(e) => return Err(e.into()),
+    };
+    Ok((address, size))
+}
+
+/// 把地址注册到地址表，已注册时不发送交易
+///
+/// # 参数
+/// * `provider` - L2 Provider
+/// * `private_key` - 私钥（从环境变量读取）
+/// * `address` - 要注册的地址
+/// * `shutdown` - Ctrl-C 关闭信号
+///
+/// # 返回
+/// * `Result<Option<TransactionReceipt>, Box<dyn Error>>` - 注册交易的收据；已注册时为 `None`
+pub async fn register(
+    provider: &RpcProvider,
+    private_key: &str,
+    address: Address,
+    shutdown: &Shutdown,
+) -> Result<Option<TransactionReceipt>, Box<dyn Error>> {
+    if let Some(index) = lookup(provider, address).await?.index {
+        println!("{}", style::warn(tf(Msg::AddrTableAlreadyRegistered, &[&index])));
+        return Ok(None);
+    }
+
+    let chain_id = provider
+        .get_chainid()
+        .instrument(info_span!("connect"))
+        .await?;
+    Span::current().record("chain_id", chain_id.as_u64());
+    network::check_chain_id(chain_id.as_u64())?;
+
+    let wallet: LocalWallet = private_key.parse()?;
+    let client = Arc::new(SignerMiddleware::new(
+        provider.clone(),
+        network::signing_wallet(wallet, chain_id.as_u64())?,
+    ));
+    let contract = address_table(client)?;
+
+    narrate!("{}", tf(Msg::AddrTableRegistering, &[&style::addr(address)]));
+    let call = contract.method::<_, U256>("register", address)?;
+    let pending_tx = call
+        .send()
+        .instrument(info_span!("broadcast", address = ?address))
+        .await?;
+    let tx_hash = pending_tx.tx_hash();
+    Span::current().record("tx_hash", field::debug(tx_hash));
+    narrate!("{}", style::ok(tf(Msg::TxHashLine, &[&style::addr(format!("{:?}", tx_hash))])));
+
+    let receipt = tokio::select! {
+        receipt = pending_tx.instrument(info_span!("confirm")) => receipt?,
+        _ = shutdown.wait() => return Err(Interrupted.into()),
+    };
+    let receipt = receipt.ok_or_else(|| t(Msg::AddrTableNoReceipt))?;
+    if receipt.status != Some(1.into()) {
+        return Err(t(Msg::AddrTableReverted).into());
+    }
+    Ok(Some(receipt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use ethers::utils::{hex, keccak256};
+    use serde_json::json;
+
+    const WALLET: &str = "9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f";
+
+    #[test]
+    fn decodes_rlp_index_and_address_forms() {
+        let address = Address::from_str(WALLET).unwrap();
+        for (data, expected) in [
+            ("80", Compressed::Index(0)),
+            ("05", Compressed::Index(5)),
+            ("7f", Compressed::Index(127)),
+            ("8180", Compressed::Index(128)),
+            ("82012c", Compressed::Index(300)),
+            ("83010000", Compressed::Index(65536)),
+            (&format!("94{}", WALLET), Compressed::Address(address)),
+        ] {
+            let bytes = hex::decode(data).unwrap();
+            assert_eq!(decode_compressed(&bytes).unwrap(), expected, "{}", data);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_rlp() {
+        for data in ["", "82", "b8"] {
+            let bytes = hex::decode(data).unwrap();
+            assert!(decode_compressed(&bytes).is_err(), "{:?}", data);
+        }
+    }
+
+    // `compress` 返回 ABI 编码的 bytes；`index` 为 None 时 lookup revert（未注册）
+    fn node(index: Option<u64>, compressed: String) -> MockRpc {
+        let lookup = hex::encode(&keccak256("lookup(address)")[..4]);
+        MockRpc::start(move |method, params| {
+            let call = &params[0];
+            let data = call["input"].as_str().or(call["data"].as_str()).unwrap_or_default();
+            match method {
+                "eth_call" if data[2..].starts_with(&lookup) => match index {
+                    Some(index) => Reply::Result(json!(format!("0x{:064x}", index))),
+                    None => Reply::Error("execution reverted: AddressNotRegistered".into()),
+                },
+                "eth_call" => {
+                    let len = compressed.len() / 2;
+                    let padded = format!("{:0<64}", &compressed);
+                    Reply::Result(json!(format!("0x{:064x}{:064x}{}", 0x20, len, padded)))
+                }
+                _ => Reply::Error(format!("unexpected {}", method)),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn lookup_reports_registered_and_unregistered_addresses() {
+        let address = Address::from_str(WALLET).unwrap();
+
+        let rpc = node(Some(300), "82012c".into());
+        let entry = lookup(&rpc.provider(), address).await.unwrap();
+        assert_eq!(entry.index, Some(U256::from(300)));
+        assert_eq!(decode_compressed(&entry.compressed).unwrap(), Compressed::Index(300));
+
+        let rpc = node(None, format!("94{}", WALLET));
+        let entry = lookup(&rpc.provider(), address).await.unwrap();
+        assert_eq!(entry.index, None);
+        assert_eq!(entry.compressed.len(), 1 + ADDRESS_BYTES);
+        assert_eq!(decode_compressed(&entry.compressed).unwrap(), Compressed::Address(address));
+    }
+}
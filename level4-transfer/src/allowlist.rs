@@ -0,0 +1,124 @@
+//! 收款地址白名单：配置后只允许向名单中的地址转账，适合多人共用测试钱包的场景
+
+use ethers::types::Address;
+use std::error::Error;
+use std::fmt;
+
+use crate::messages::{Msg, tf};
+use crate::validate_address;
+
+/// 白名单的环境变量（也可以写在 .env 里），多个地址用逗号分隔
+pub const ALLOWLIST_ENV: &str = "ARBI_ALLOWED_RECIPIENTS";
+
+/// 收款地址白名单，为空时不做限制
+pub struct Allowlist {
+    addresses: Vec<Address>,
+}
+
+/// 接收地址不在白名单中时返回的错误
+#[derive(Debug)]
+pub struct RecipientNotAllowed(pub Address);
+
+impl fmt::Display for RecipientNotAllowed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let address = format!("{:?}", self.0);
+        write!(f, "{}", tf(Msg::RecipientNotAllowed, &[&address, &ALLOWLIST_ENV]))
+    }
+}
+
+impl Error for RecipientNotAllowed {}
+
+impl Allowlist {
+    /// 由 `ARBI_ALLOWED_RECIPIENTS` 和命令行的 `--allow` 组成白名单
+    ///
+    /// # 参数
+    /// * `extra` - `--allow` 追加的地址
+    ///
+    /// # 返回
+    /// * `Result<Allowlist, Box<dyn Error>>` - 白名单；有无效地址时返回错误，避免配置写错后悄悄失效
+    pub fn load(extra: &[String]) -> Result<Self, Box<dyn Error>> {
+        let configured = std::env::var(ALLOWLIST_ENV).unwrap_or_default();
+        let entries = configured
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .chain(extra.iter().map(|entry| entry.trim()));
+        let mut addresses = Vec::new();
+        for entry in entries {
+            let address = validate_address(entry)
+                .map_err(|e| tf(Msg::AllowlistInvalid, &[&entry, &e]))?;
+            addresses.push(address);
+        }
+        Ok(Self { addresses })
+    }
+
+    /// 检查接收地址
+    ///
+    /// # 参数
+    /// * `to` - 接收地址
+    ///
+    /// # 返回
+    /// * `Result<(), RecipientNotAllowed>` - 白名单为空或地址在名单中时通过
+    pub fn check(&self, to: Address) -> Result<(), RecipientNotAllowed> {
+        match self.addresses.is_empty() || self.addresses.contains(&to) {
+            true => Ok(()),
+            false => Err(RecipientNotAllowed(to)),
+        }
+    }
+
+    /// 是否配置了白名单
+    pub fn is_enabled(&self) -> bool {
+        !self.addresses.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECKSUMMED: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    fn allowlist(entries: &[&str]) -> Allowlist {
+        let addresses = entries.iter().map(|entry| validate_address(entry).unwrap()).collect();
+        Allowlist { addresses }
+    }
+
+    #[test]
+    fn empty_allowlist_allows_everyone() {
+        let allowlist = allowlist(&[]);
+        assert!(!allowlist.is_enabled());
+        assert!(allowlist.check(Address::repeat_byte(0x11)).is_ok());
+        assert!(allowlist.check(Address::zero()).is_ok());
+    }
+
+    #[test]
+    fn allows_listed_and_rejects_others() {
+        let allowlist = allowlist(&[CHECKSUMMED, "0x1111111111111111111111111111111111111111"]);
+        assert!(allowlist.is_enabled());
+        assert!(allowlist.check(Address::repeat_byte(0x11)).is_ok());
+        assert!(allowlist.check(validate_address(CHECKSUMMED).unwrap()).is_ok());
+
+        let stranger = Address::repeat_byte(0x22);
+        let error = allowlist.check(stranger).unwrap_err();
+        assert_eq!(error.0, stranger);
+        assert!(error.to_string().contains(ALLOWLIST_ENV), "{error}");
+    }
+
+    #[test]
+    fn matching_ignores_case() {
+        let lowercase = CHECKSUMMED.to_lowercase();
+        let uppercase = format!("0x{}", CHECKSUMMED[2..].to_uppercase());
+        for entry in [CHECKSUMMED, &lowercase, &uppercase, &CHECKSUMMED[2..]] {
+            let allowlist = allowlist(&[entry]);
+            for to in [CHECKSUMMED, &lowercase, &uppercase] {
+                assert!(allowlist.check(validate_address(to).unwrap()).is_ok(), "{entry} {to}");
+            }
+        }
+    }
+
+    #[test]
+    fn load_rejects_invalid_entries() {
+        let error = Allowlist::load(&["0x1234".to_string()]).err().unwrap();
+        assert!(error.to_string().contains("0x1234"), "{error}");
+    }
+}
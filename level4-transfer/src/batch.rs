@@ -0,0 +1,919 @@
+use arbi_common::narrate;
+use arbi_common::network;
+use arbi_common::retry::{self, Backoff, RetryBudget};
+use arbi_common::revert;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::shutdown::Shutdown;
+use arbi_common::style;
+use arbi_common::table::Table;
+use arbi_common::units;
+use clap::ValueEnum;
+use ethers::prelude::*;
+use ethers::providers::Middleware;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, BlockNumber, TransactionRequest, U256};
+use ethers::utils::format_units;
+use std::error::Error;
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use tracing::{Instrument, Span, info, info_span, warn};
+
+use crate::messages::{Msg, t, tf};
+use crate::{BASIC_TRANSFER_GAS_LIMIT, get_balance, get_gas_price, validate_address};
+
+/// fund 默认的广播记录文件
+pub const FUND_JOURNAL: &str = "fund.sent";
+
+// 节点的交易池里已经有这笔交易时的错误片段（小写），按已发送处理
+const ALREADY_KNOWN_ERROR: &str = "already known";
+
+// nonce 已被占用时的错误片段（小写）。只有重试时才可能是上一次广播（响应丢了）占用的，
+// 还要在节点上查到这笔交易才按已发送处理；第一次广播就遇到说明 nonce 被别的交易用掉了
+const NONCE_TOO_LOW_ERROR: &str = "nonce too low";
+
+/// 批量广播时每笔交易使用的 Gas 价格
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum GasPricing {
+    /// 全部使用开始时获取的价格（默认，不额外请求节点）
+    Initial,
+    /// 每笔发送前重新获取价格
+    PerTx,
+    /// 每笔发送前重新获取，只在价格上涨时采用新价格
+    OnRise,
+}
+
+/// 批量转账的选项
+pub struct BatchOptions {
+    /// 模拟失败时是否仍然广播
+    pub force: bool,
+    /// 每笔交易的 Gas 定价方式
+    pub pricing: GasPricing,
+    /// 单笔交易广播失败时的重试策略
+    pub backoff: Backoff,
+    /// 整批共用的重试次数上限
+    pub retry_budget: u32,
+}
+
+/// 批量转账中的一行：接收地址和金额
+pub struct BatchRow {
+    pub to: Address,
+    pub amount: U256,
+    pub amount_eth: String,
+}
+
+/// 单笔交易的模拟结果
+pub struct SimulationResult {
+    pub index: usize,
+    pub to: Address,
+    pub amount_eth: String,
+    /// 模拟失败的原因；`None` 表示模拟通过
+    pub failure: Option<String>,
+}
+
+/// 批量转账的执行结果
+pub struct BatchReport {
+    /// 文件中的转账总数
+    pub total: usize,
+    /// 已广播交易的哈希（按文件顺序）
+    pub sent: Vec<TxHash>,
+    /// 是否被 Ctrl-C 中断
+    pub interrupted: bool,
+    /// 广播失败而停止时，失败的那一笔（下标）和原因；它和后面的转账都没有发出
+    pub failure: Option<(usize, String)>,
+    /// 已广播交易的记录文件
+    pub journal_path: String,
+    /// 广播时用掉的重试次数和整批的重试预算
+    pub retries: (u32, u32),
+}
+
+impl BatchReport {
+    /// 打印已完成 / 剩余的汇总
+    pub fn print_summary(&self) {
+        println!("\n{}", t(Msg::BatchSummaryTitle));
+        println!(
+            "{}",
+            tf(Msg::BatchSummarySent, &[&self.sent.len(), &self.total])
+        );
+        println!(
+            "{}",
+            tf(Msg::BatchSummaryUnsent, &[&(self.total - self.sent.len())])
+        );
+        if let Some((index, error)) = &self.failure {
+            println!("{}", style::err(tf(Msg::BatchSummaryStopped, &[&(index + 1), error])));
+            println!("{}", tf(Msg::BatchSummaryLeftover, &[&(index + 1), &self.total]));
+        }
+        if self.retries.0 > 0 {
+            println!("{}", tf(Msg::BatchSummaryRetries, &[&self.retries.0, &self.retries.1]));
+        }
+        println!("{}", tf(Msg::BatchSummaryJournal, &[&self.journal_path]));
+    }
+
+    /// 逐个接收地址打印结果：已广播的给出交易哈希，没有发出的标为未发送
+    ///
+    /// # 参数
+    /// * `rows` - 转账列表（与 `sent` 按顺序对应）
+    pub fn print_recipients(&self, rows: &[BatchRow]) {
+        println!("\n{}", t(Msg::FundRecipientsTitle));
+        for (index, row) in rows.iter().enumerate() {
+            let to = style::addr(format!("{:?}", row.to));
+            match self.sent.get(index) {
+                Some(hash) => {
+                    let (number, hash) = (index + 1, format!("{:?}", hash));
+                    let sent = tf(Msg::FundRecipientSent, &[&number, &to, &row.amount_eth, &hash]);
+                    println!("  {}", style::ok(sent));
+                }
+                None => {
+                    let unsent = tf(Msg::FundRecipientUnsent, &[&(index + 1), &to]);
+                    println!("  {}", style::warn(unsent));
+                }
+            }
+        }
+    }
+
+    /// 每笔转账一行的表格：是否已广播和交易哈希，金额同时给出 wei 和 ETH
+    ///
+    /// # 参数
+    /// * `rows` - 批量转账文件中的转账（与 `sent` 按顺序对应）
+    ///
+    /// # 返回
+    /// * `Table` - 用于导出 CSV 的表格
+    pub fn to_table(&self, rows: &[BatchRow]) -> Table {
+        let header = ["index", "to", "amount_wei", "amount_eth", "status", "tx_hash"];
+        let mut table = Table::new(header);
+        for (index, row) in rows.iter().enumerate() {
+            let (status, tx_hash) = match self.sent.get(index) {
+                Some(hash) => ("sent", format!("{:?}", hash)),
+                None => ("unsent", String::new()),
+            };
+            table.push([
+                (index + 1).to_string(),
+                format!("{:?}", row.to),
+                row.amount.to_string(),
+                row.amount_eth.clone(),
+                status.to_string(),
+                tx_hash,
+            ]);
+        }
+        table
+    }
+}
+
+/// 广播记录文件，每行 `序号,接收地址,金额,交易哈希,状态`，写入后立即落盘
+///
+/// 每笔交易签名后、广播前先写一行 `signed`，广播结束后再写一行 `sent` 或 `failed`。
+/// 广播途中断线或进程被杀时，也能用 `signed` 行里的哈希去链上确认这笔交易是否发出。
+struct Journal {
+    file: File,
+}
+
+impl Journal {
+    /// 以追加方式打开记录文件
+    fn open(path: &str) -> Result<Self, Box<dyn Error>> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file })
+    }
+
+    /// 记录一笔交易的状态
+    fn record(
+        &mut self,
+        index: usize,
+        row: &BatchRow,
+        tx_hash: TxHash,
+        status: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        writeln!(
+            self.file,
+            "{},{:?},{},{:?},{}",
+            index + 1,
+            row.to,
+            row.amount_eth,
+            tx_hash,
+            status
+        )?;
+        self.file.sync_data()?;
+        Ok(())
+    }
+}
+
+/// 广播错误是否表示节点已经有这笔交易
+///
+/// # 参数
+/// * `message` - 节点返回的错误信息
+///
+/// # 返回
+/// * `bool` - 是 "already known" 时为 `true`
+fn already_broadcast(message: &str) -> bool {
+    message.to_lowercase().contains(ALREADY_KNOWN_ERROR)
+}
+
+// 广播错误是否是 nonce 已被占用
+fn nonce_too_low(message: &str) -> bool {
+    message.to_lowercase().contains(NONCE_TOO_LOW_ERROR)
+}
+
+/// 读取批量转账文件
+///
+/// 每行一笔转账，格式为 `接收地址,金额(ETH)`，空行和 `#` 开头的注释行会被跳过。
+///
+/// # 参数
+/// * `path` - 文件路径
+///
+/// # 返回
+/// * `Result<Vec<BatchRow>, Box<dyn Error>>` - 解析后的转账列表
+pub fn load_batch(path: &str) -> Result<Vec<BatchRow>, Box<dyn Error>> {
+    let content = fs::read_to_string(path)?;
+    let mut rows = Vec::new();
+
+    for (line_no, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (to, amount_eth) = line
+            .split_once(',')
+            .ok_or_else(|| tf(Msg::BatchLineFormat, &[&(line_no + 1)]))?;
+        let to = validate_address(to.trim())
+            .map_err(|e| tf(Msg::BatchLineAddress, &[&(line_no + 1), &e]))?;
+        let amount_eth = amount_eth.trim().to_string();
+        let amount = units::parse_ether_amount(&amount_eth)
+            .map_err(|e| tf(Msg::BatchLineAmount, &[&(line_no + 1), &e]))?;
+
+        rows.push(BatchRow {
+            to,
+            amount,
+            amount_eth,
+        });
+    }
+
+    if rows.is_empty() {
+        return Err(t(Msg::BatchEmpty).into());
+    }
+    Ok(rows)
+}
+
+/// 读取接收地址文件：每行一个地址，空行和 `#` 开头的注释行会被跳过
+///
+/// # 参数
+/// * `path` - 文件路径
+///
+/// # 返回
+/// * `Result<Vec<Address>, Box<dyn Error>>` - 地址列表
+pub fn load_recipients(path: &Path) -> Result<Vec<Address>, Box<dyn Error>> {
+    let content = fs::read_to_string(path)?;
+    let mut recipients = Vec::new();
+    for (line_no, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let address =
+            validate_address(line).map_err(|e| tf(Msg::BatchLineAddress, &[&(line_no + 1), &e]))?;
+        recipients.push(address);
+    }
+    Ok(recipients)
+}
+
+/// 给每个接收地址生成一笔相同金额的转账
+///
+/// # 参数
+/// * `recipients` - 接收地址
+/// * `amount_each` - 每个地址收到的金额（wei）
+///
+/// # 返回
+/// * `Result<Vec<BatchRow>, Box<dyn Error>>` - 转账列表；没有地址、地址重复或金额为 0 时返回错误
+pub fn fund_rows(
+    recipients: &[Address],
+    amount_each: U256,
+) -> Result<Vec<BatchRow>, Box<dyn Error>> {
+    if recipients.is_empty() {
+        return Err(t(Msg::FundNoRecipients).into());
+    }
+    if amount_each.is_zero() {
+        return Err(t(Msg::FundZeroAmount).into());
+    }
+    let mut seen = HashSet::new();
+    if let Some(duplicate) = recipients.iter().find(|address| !seen.insert(**address)) {
+        return Err(tf(Msg::FundDuplicateRecipient, &[&format!("{:?}", duplicate)]).into());
+    }
+    let amount_eth = format_units(amount_each, "ether")?;
+    Ok(recipients
+        .iter()
+        .map(|to| BatchRow {
+            to: *to,
+            amount: amount_each,
+            amount_eth: amount_eth.clone(),
+        })
+        .collect())
+}
+
+/// 从主钱包给多个账户各转相同金额（如给课堂上新生成的测试账户发测试币）
+///
+/// 先按当前 Gas 价格检查主钱包的余额够不够"金额 × 人数 + 每笔的 Gas 费"，不够时直接返回错误，
+/// 不广播任何交易；够的话走 [`transfer_batch`] 的流程：整批模拟、按顺序手动递增 nonce 广播、等待确认。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `private_key` - 主钱包的私钥
+/// * `rows` - [`fund_rows`] 生成的转账
+/// * `options` - 模拟失败时的处理、Gas 定价和重试设置
+/// * `journal_path` - 广播记录文件路径
+/// * `shutdown` - Ctrl-C 关闭信号
+///
+/// # 返回
+/// * `Result<BatchReport, Box<dyn Error>>` - 执行结果
+pub async fn fund_accounts(
+    provider: &RpcProvider,
+    private_key: &str,
+    rows: &[BatchRow],
+    options: &BatchOptions,
+    journal_path: &str,
+    shutdown: &Shutdown,
+) -> Result<BatchReport, Box<dyn Error>> {
+    let master = private_key.parse::<LocalWallet>()?.address();
+    let balance = provider
+        .get_balance(master, Some(BlockNumber::Pending.into()))
+        .await?;
+    let gas_price = get_gas_price(provider).await?;
+    let total = rows.iter().fold(U256::zero(), |total, row| total.saturating_add(row.amount));
+    let fees = gas_price
+        .saturating_mul(BASIC_TRANSFER_GAS_LIMIT.into())
+        .saturating_mul(rows.len().into());
+    info!(balance_wei = %balance, total_wei = %total, fees_wei = %fees, "funding checked");
+    if total.saturating_add(fees) > balance {
+        let reason = tf(
+            Msg::FundInsufficient,
+            &[
+                &units::format_balance(total),
+                &rows.len(),
+                &units::format_balance(fees),
+                &units::format_balance(balance),
+            ],
+        );
+        return Err(reason.into());
+    }
+    transfer_batch(provider, private_key, rows, options, journal_path, shutdown).await
+}
+
+/// 在广播前逐笔模拟整批转账
+///
+/// 每笔交易都用 `eth_call` 在 pending 区块状态上执行一次，捕获会 revert 的交易。
+/// `eth_call` 之间不会累积状态，所以这里同时按顺序累计"金额 + Gas 费"，
+/// 提前发现后面几笔会因为前面的转账花掉余额而失败的情况。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `from` - 发送地址
+/// * `rows` - 待发送的转账
+/// * `gas_price` - 使用的 Gas 价格
+///
+/// # 返回
+/// * `Result<Vec<SimulationResult>, Box<dyn Error>>` - 每笔交易的模拟结果
+pub async fn simulate_batch(
+    provider: &RpcProvider,
+    from: Address,
+    rows: &[BatchRow],
+    gas_price: U256,
+) -> Result<Vec<SimulationResult>, Box<dyn Error>> {
+    let gas_limit = U256::from(BASIC_TRANSFER_GAS_LIMIT);
+    let mut remaining = provider
+        .get_balance(from, Some(BlockNumber::Pending.into()))
+        .await?;
+    let mut results = Vec::with_capacity(rows.len());
+
+    for (index, row) in rows.iter().enumerate() {
+        let tx = TransactionRequest::new()
+            .from(from)
+            .to(row.to)
+            .value(row.amount)
+            .gas(gas_limit)
+            .gas_price(gas_price);
+
+        let failure = match provider
+            .call(&tx.into(), Some(BlockNumber::Pending.into()))
+            .await
+        {
+            Ok(_) => {
+                let cost = row.amount + gas_price * gas_limit;
+                if cost > remaining {
+                    let remaining_eth = format_units(remaining, "ether")?;
+                    Some(tf(Msg::SimBalanceExhausted, &[&remaining_eth]))
+                } else {
+                    remaining -= cost;
+                    None
+                }
+            }
+            Err(e) => match revert::call_error_reason(&e) {
+                Some(reason) => Some(reason),
+                // 不是执行失败而是网络等问题，直接中止整个模拟
+                None => return Err(e.into()),
+            },
+        };
+
+        results.push(SimulationResult {
+            index,
+            to: row.to,
+            amount_eth: row.amount_eth.clone(),
+            failure,
+        });
+    }
+
+    Ok(results)
+}
+
+/// 打印模拟结果
+///
+/// # 参数
+/// * `results` - `simulate_batch` 的返回值
+pub fn print_simulation(results: &[SimulationResult]) {
+    for result in results {
+        match &result.failure {
+            None => println!(
+                "  {}",
+                style::ok(format!(
+                    "✓ #{} {} ETH → {}",
+                    result.index + 1,
+                    result.amount_eth,
+                    style::addr(format!("{:?}", result.to))
+                ))
+            ),
+            Some(reason) => println!(
+                "  {}",
+                style::err(format!(
+                    "❌ #{} {} ETH → {:?}：{}",
+                    result.index + 1,
+                    result.amount_eth,
+                    result.to,
+                    reason
+                ))
+            ),
+        }
+    }
+}
+
+/// 执行批量转账：先整体模拟，全部通过（或指定 `force`）后才按顺序广播
+///
+/// 需要在 `batch` span 内调用，连接后会把 chain_id 记录到这个 span 上。
+/// 收到 Ctrl-C 时会等正在广播的那一笔完成，然后停止发送剩余的转账。
+/// 每笔交易先在本地签名，把交易哈希写入 `journal_path` 后才广播，中断后也能知道哪些可能已经发出。
+/// 广播遇到网络错误时按 `options.backoff` 重试，整批的重试总数不超过 `options.retry_budget`；
+/// 节点回复 "already known" 说明交易已经发出，按已发送处理；重试时回复 "nonce too low"，
+/// 且节点上查得到这笔交易，说明之前那次广播其实成功了，同样按已发送处理；
+/// 节点拒绝（余额不足等）或重试用完时不再发送后面的转账（nonce 必须连续），
+/// 在报告的 `failure` 中给出原因，已发出的交易照常等待确认。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `private_key` - 私钥（从环境变量读取）
+/// * `rows` - 待发送的转账
+/// * `options` - 模拟失败时的处理、Gas 定价和重试设置
+/// * `journal_path` - 广播记录文件路径
+/// * `shutdown` - Ctrl-C 关闭信号
+///
+/// # 返回
+/// * `Result<BatchReport, Box<dyn Error>>` - 执行结果
+pub async fn transfer_batch(
+    provider: &RpcProvider,
+    private_key: &str,
+    rows: &[BatchRow],
+    options: &BatchOptions,
+    journal_path: &str,
+    shutdown: &Shutdown,
+) -> Result<BatchReport, Box<dyn Error>> {
+    narrate!("\n{}\n", tf(Msg::BatchStart, &[&rows.len()]));
+
+    // 1. 加载钱包
+    let wallet: LocalWallet = private_key.parse()?;
+    let from_address = wallet.address();
+    let balance = get_balance(provider, from_address).await?;
+    info!(balance_wei = %balance, "balance fetched");
+    let balance_eth = units::format_balance(balance);
+    narrate!("{}", tf(Msg::BatchFromAddress, &[&style::addr(from_address)]));
+    narrate!("{}", style::ok(tf(Msg::CurrentBalance, &[&balance_eth])));
+
+    // 2. 获取实时 Gas 价格
+    let gas_price = get_gas_price(provider)
+        .instrument(info_span!("estimate"))
+        .await?;
+    info!(gas_price_wei = %gas_price, "gas price fetched");
+    let gas_price_gwei = format_units(gas_price, "gwei")?;
+    narrate!("\n{}", tf(Msg::BatchGasPrice, &[&gas_price_gwei]));
+
+    // 3. 广播前逐笔模拟
+    narrate!("\n{}", t(Msg::BatchSimulating));
+    let simulation = simulate_batch(provider, from_address, rows, gas_price)
+        .instrument(info_span!("simulate", transfers = rows.len()))
+        .await?;
+    print_simulation(&simulation);
+
+    let failed = simulation.iter().filter(|r| r.failure.is_some()).count();
+    if failed > 0 {
+        warn!(failed, "simulation failed");
+        if !options.force {
+            return Err(tf(Msg::SimFailedAbort, &[&failed]).into());
+        }
+        println!("{}", style::warn(tf(Msg::SimFailedForce, &[&failed])));
+    } else {
+        println!("{}", style::ok(t(Msg::SimAllPassed)));
+    }
+
+    let mut report = BatchReport {
+        total: rows.len(),
+        sent: Vec::with_capacity(rows.len()),
+        interrupted: false,
+        failure: None,
+        journal_path: journal_path.to_string(),
+        retries: (0, options.retry_budget),
+    };
+    if shutdown.is_requested() {
+        report.interrupted = true;
+        return Ok(report);
+    }
+
+    // 4. 按顺序广播，手动管理 nonce
+    narrate!("\n{}", tf(Msg::BatchSending, &[&journal_path]));
+    let mut journal = Journal::open(journal_path)?;
+    let chain_id = provider
+        .get_chainid()
+        .instrument(info_span!("connect"))
+        .await?;
+    Span::current().record("chain_id", chain_id.as_u64());
+    network::check_chain_id(chain_id.as_u64())?;
+    let wallet = network::signing_wallet(wallet, chain_id.as_u64())?;
+    let mut nonce = provider
+        .get_transaction_count(from_address, Some(BlockNumber::Pending.into()))
+        .await?;
+
+    let budget = RetryBudget::new(options.retry_budget);
+    let mut pending = Vec::with_capacity(rows.len());
+    let mut tx_gas_price = gas_price;
+    for (index, row) in rows.iter().enumerate() {
+        // 只在两笔之间检查，正在广播的那一笔总会完成
+        if shutdown.is_requested() {
+            report.interrupted = true;
+            break;
+        }
+
+        // Gas 价格暴涨时，按开始时的价格发出的交易可能一直卡住，这里按需重新定价
+        if options.pricing != GasPricing::Initial && index > 0 {
+            let latest = get_gas_price(provider)
+                .instrument(info_span!("estimate", index = index + 1))
+                .await?;
+            tx_gas_price = match options.pricing {
+                GasPricing::OnRise => tx_gas_price.max(latest),
+                _ => latest,
+            };
+            info!(index = index + 1, gas_price_wei = %tx_gas_price, "gas price refreshed");
+        }
+
+        let tx: TypedTransaction = TransactionRequest::new()
+            .from(from_address)
+            .to(row.to)
+            .value(row.amount)
+            .gas(BASIC_TRANSFER_GAS_LIMIT)
+            .gas_price(tx_gas_price)
+            .nonce(nonce)
+            .chain_id(chain_id.as_u64())
+            .into();
+        let signature = wallet.sign_transaction_sync(&tx)?;
+        let raw = tx.rlp_signed(&signature);
+        let tx_hash = tx.hash(&signature);
+        journal.record(index, row, tx_hash, "signed")?;
+
+        // 只重试网络错误：节点返回的错误放在 Ok 里原样带出来，不占用重试
+        let exhausted = budget.is_exhausted();
+        let mut attempt = 0;
+        let sent = retry::with_budget(options.backoff, &budget, || {
+            let (raw, retrying) = (raw.clone(), attempt > 0);
+            attempt += 1;
+            async move {
+                let e = match provider.send_raw_transaction(raw).await {
+                    Ok(_) => return Ok(Ok(())),
+                    Err(e) => e,
+                };
+                let Some(response) = RpcError::as_error_response(&e) else {
+                    return Err(e.into());
+                };
+                if already_broadcast(&response.message) {
+                    return Ok(Ok(()));
+                }
+                if retrying && nonce_too_low(&response.message) {
+                    // 查询失败按网络错误重试，下一次广播还会走到这里
+                    return match provider.get_transaction(tx_hash).await? {
+                        Some(_) => Ok(Ok(())),
+                        None => Ok(Err(e)),
+                    };
+                }
+                Ok(Err(e))
+            }
+        })
+        .instrument(info_span!("broadcast", index = index + 1, to = ?row.to, nonce = %nonce))
+        .await;
+        report.retries.0 = budget.used();
+        if !exhausted && budget.is_exhausted() {
+            warn!(retry_budget = options.retry_budget, "retry budget exhausted");
+            let exhausted = tf(Msg::BatchRetryBudgetExhausted, &[&options.retry_budget]);
+            println!("{}", style::warn(exhausted));
+        }
+        let error = match sent {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(e) => Some(e.to_string()),
+        };
+        if let Some(error) = error {
+            journal.record(index, row, tx_hash, "failed")?;
+            warn!(index = index + 1, tx_hash = ?tx_hash, error, "broadcast failed, stopping");
+            println!("{}", style::err(tf(Msg::BatchItemFailed, &[&(index + 1), &error])));
+            report.failure = Some((index, error));
+            break;
+        }
+        journal.record(index, row, tx_hash, "sent")?;
+        info!(index = index + 1, tx_hash = ?tx_hash, "transaction broadcast");
+        println!(
+            "{}",
+            style::ok(tf(
+                Msg::BatchItemSent,
+                &[
+                    &(index + 1),
+                    &style::addr(format!("{:?}", tx_hash)),
+                    &format_units(tx_gas_price, "gwei")?,
+                    &format_units(tx_gas_price * BASIC_TRANSFER_GAS_LIMIT, "ether")?,
+                ]
+            ))
+        );
+        report.sent.push(tx_hash);
+        pending.push(PendingTransaction::new(tx_hash, provider));
+        nonce += U256::one();
+    }
+
+    if report.interrupted {
+        return Ok(report);
+    }
+
+    // 5. 等待全部确认
+    narrate!("\n{}", t(Msg::BatchConfirming));
+    for (index, pending_tx) in pending.into_iter().enumerate() {
+        let span = info_span!("confirm", index = index + 1, tx_hash = ?pending_tx.tx_hash());
+        let receipt = tokio::select! {
+            receipt = pending_tx.instrument(span) => receipt?,
+            _ = shutdown.wait() => {
+                warn!("stopped waiting for confirmations");
+                println!("{}", style::warn(t(Msg::BatchStopWaiting)));
+                report.interrupted = true;
+                return Ok(report);
+            }
+        };
+        match receipt {
+            Some(receipt) => println!(
+                "{}",
+                style::ok(tf(
+                    Msg::BatchItemConfirmed,
+                    &[
+                        &(index + 1),
+                        &format!("{:?}", receipt.block_number),
+                        &format!("{:?}", receipt.status),
+                    ],
+                ))
+            ),
+            None => println!("{}", style::warn(tf(Msg::BatchItemNoReceipt, &[&(index + 1)]))),
+        }
+    }
+
+    narrate!("\n{}", t(Msg::BatchDone));
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use ethers::utils::keccak256;
+    use serde_json::{Value, json};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    // EIP-155 示例私钥，只用于测试
+    const PRIVATE_KEY: &str = "4646464646464646464646464646464646464646464646464646464646464646";
+
+    fn journal_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("arbi-{}-{}.sent", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path.display().to_string()
+    }
+
+    fn rows(count: u8) -> Vec<BatchRow> {
+        (1..=count)
+            .map(|i| BatchRow {
+                to: Address::repeat_byte(i),
+                amount: U256::exp10(15),
+                amount_eth: "0.001".to_string(),
+            })
+            .collect()
+    }
+
+    fn raw_tx_hash(params: &Value) -> TxHash {
+        let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+        keccak256(raw).into()
+    }
+
+    // Arbitrum Sepolia 上余额充足的账户；eth_sendRawTransaction 交给 `send` 决定，
+    // 参数是第几次广播（从 0 开始）和原始交易
+    fn node(send: impl Fn(usize, &Value) -> Reply + Send + Sync + 'static) -> MockRpc {
+        node_with(false, send)
+    }
+
+    // 同上；`known` 时任何哈希都查得到，并且已经在区块 0x10 上链
+    fn node_with(
+        known: bool,
+        send: impl Fn(usize, &Value) -> Reply + Send + Sync + 'static,
+    ) -> MockRpc {
+        let broadcasts = AtomicUsize::new(0);
+        MockRpc::start(move |method, params| match method {
+            "eth_getTransactionByHash" if known => Reply::Result(json!({
+                "hash": params[0],
+                "nonce": "0x0",
+                "from": "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+                "value": "0x0",
+                "gas": "0x5208",
+                "input": "0x",
+                "v": "0x0",
+                "r": "0x0",
+                "s": "0x0",
+                "blockNumber": "0x10",
+                "blockHash": format!("{:?}", TxHash::repeat_byte(0x10)),
+            })),
+            "eth_getTransactionReceipt" if known => Reply::Result(json!({
+                "transactionHash": params[0],
+                "transactionIndex": "0x0",
+                "blockNumber": "0x10",
+                "blockHash": format!("{:?}", TxHash::repeat_byte(0x10)),
+                "from": "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+                "cumulativeGasUsed": "0x5208",
+                "gasUsed": "0x5208",
+                "logs": [],
+                "logsBloom": format!("0x{}", "00".repeat(256)),
+                "status": "0x1",
+            })),
+            "eth_blockNumber" if known => Reply::Result(json!("0x10")),
+            "eth_chainId" => Reply::Result(json!("0x66eee")),
+            "eth_getBalance" => Reply::Result(json!("0x56bc75e2d63100000")),
+            "eth_gasPrice" => Reply::Result(json!("0x5f5e100")),
+            "eth_call" => Reply::Result(json!("0x")),
+            "eth_getTransactionCount" => Reply::Result(json!("0x0")),
+            "eth_sendRawTransaction" => send(broadcasts.fetch_add(1, Ordering::SeqCst), params),
+            _ => Reply::Result(Value::Null),
+        })
+    }
+
+    async fn run(node: &MockRpc, count: u8, journal: &str) -> BatchReport {
+        let options = BatchOptions {
+            force: false,
+            pricing: GasPricing::Initial,
+            backoff: Backoff {
+                retries: 3,
+                base_delay: Duration::from_millis(1),
+                max_delay: Duration::from_millis(1),
+            },
+            retry_budget: 2,
+        };
+        let provider = node.provider().interval(Duration::from_millis(10));
+        let shutdown = Shutdown::new();
+        transfer_batch(&provider, PRIVATE_KEY, &rows(count), &options, journal, &shutdown)
+            .await
+            .unwrap()
+    }
+
+    fn journal_lines(path: &str) -> Vec<String> {
+        let content = fs::read_to_string(path).unwrap();
+        let _ = fs::remove_file(path);
+        content.lines().map(str::to_string).collect()
+    }
+
+    #[tokio::test]
+    async fn journals_hash_before_broadcasting() {
+        let journal = journal_path("journal-first");
+        let (path, journaled) = (journal.clone(), Arc::new(AtomicBool::new(true)));
+        let seen = Arc::clone(&journaled);
+        let node = node(move |_, params| {
+            let line = format!("{:?},signed", raw_tx_hash(params));
+            let content = fs::read_to_string(&path).unwrap_or_default();
+            if !content.lines().any(|entry| entry.ends_with(&line)) {
+                seen.store(false, Ordering::SeqCst);
+            }
+            Reply::Result(json!(format!("{:?}", raw_tx_hash(params))))
+        });
+
+        let report = run(&node, 2, &journal).await;
+        assert!(journaled.load(Ordering::SeqCst));
+        assert_eq!(report.sent.len(), 2);
+        assert!(report.failure.is_none());
+
+        let lines = journal_lines(&journal);
+        let status: Vec<_> = lines.iter().map(|line| line.rsplit(',').next().unwrap()).collect();
+        assert_eq!(status, ["signed", "sent", "signed", "sent"]);
+        assert!(lines[1].contains(&format!("{:?}", report.sent[0])));
+    }
+
+    #[tokio::test]
+    async fn already_known_counts_as_sent() {
+        let journal = journal_path("already-known");
+        let node = node(|attempt, _| match attempt {
+            0 => Reply::Error("already known".to_string()),
+            _ => Reply::Error("insufficient funds for gas * price + value".to_string()),
+        });
+
+        let report = run(&node, 3, &journal).await;
+        assert_eq!(report.sent.len(), 1);
+        assert_eq!(report.retries.0, 0);
+        let (index, error) = report.failure.unwrap();
+        assert_eq!(index, 1);
+        assert!(error.contains("insufficient funds"), "{error}");
+        // 节点拒绝的交易不重试
+        assert_eq!(node.count("eth_sendRawTransaction"), 2);
+
+        let lines = journal_lines(&journal);
+        assert_eq!(lines.len(), 4);
+        assert!(lines[3].starts_with("2,") && lines[3].ends_with(",failed"), "{}", lines[3]);
+    }
+
+    #[tokio::test]
+    async fn nonce_too_low_on_first_attempt_is_a_failure() {
+        // nonce 被别的交易用掉了，即使节点查得到同一个哈希也不能算已发送
+        let journal = journal_path("nonce-first");
+        let node = node_with(true, |_, _| {
+            Reply::Error("nonce too low: next nonce 1, tx nonce 0".to_string())
+        });
+
+        let report = run(&node, 2, &journal).await;
+        assert!(report.sent.is_empty());
+        let (index, error) = report.failure.unwrap();
+        assert_eq!(index, 0);
+        assert!(error.contains("nonce too low"), "{error}");
+        assert_eq!(node.count("eth_getTransactionByHash"), 0);
+
+        let lines = journal_lines(&journal);
+        let status: Vec<_> = lines.iter().map(|line| line.rsplit(',').next().unwrap()).collect();
+        assert_eq!(status, ["signed", "failed"]);
+    }
+
+    #[tokio::test]
+    async fn nonce_too_low_on_retry_counts_as_sent_only_if_found() {
+        // 第一次广播的响应丢了，重试时节点说 nonce 已被占用
+        let lost_response = |attempt: usize, _: &Value| match attempt {
+            0 => Reply::Disconnect,
+            _ => Reply::Error("nonce too low".to_string()),
+        };
+
+        let journal = journal_path("nonce-retry-found");
+        let node = node_with(true, lost_response);
+        let report = run(&node, 1, &journal).await;
+        assert_eq!(report.sent.len(), 1);
+        assert!(report.failure.is_none());
+        let lines = journal_lines(&journal);
+        assert!(lines[1].ends_with(",sent"), "{}", lines[1]);
+
+        let journal = journal_path("nonce-retry-missing");
+        let node = node_with(false, lost_response);
+        let report = run(&node, 1, &journal).await;
+        assert!(report.sent.is_empty());
+        assert_eq!(report.failure.map(|(index, _)| index), Some(0));
+        journal_lines(&journal);
+    }
+
+    #[tokio::test]
+    async fn retry_budget_caps_total_attempts_and_leftover_is_reported() {
+        let journal = journal_path("retry-budget");
+        // 第一笔断线两次后成功，用完整批的 2 次重试；第二笔断线一次就不再重试
+        let node = node(|attempt, params| match attempt {
+            0 | 1 | 3 => Reply::Disconnect,
+            _ => Reply::Result(json!(format!("{:?}", raw_tx_hash(params)))),
+        });
+
+        let report = run(&node, 4, &journal).await;
+        assert_eq!(report.retries, (2, 2));
+        assert_eq!(report.sent.len(), 1);
+        assert_eq!(report.failure.as_ref().map(|(index, _)| *index), Some(1));
+        assert!(!report.interrupted);
+        assert_eq!(node.count("eth_sendRawTransaction"), 4);
+
+        // 第三、四笔没有签名也没有广播，汇总里作为未发送列出
+        let lines = journal_lines(&journal);
+        let status: Vec<_> = lines.iter().map(|line| line.rsplit(',').next().unwrap()).collect();
+        assert_eq!(status, ["signed", "sent", "signed", "failed"]);
+        assert_eq!(report.total - report.sent.len(), 3);
+    }
+
+    #[test]
+    fn recognizes_already_broadcast_errors() {
+        assert!(already_broadcast("already known"));
+        assert!(already_broadcast("Already Known"));
+        assert!(!already_broadcast("nonce too low"));
+        assert!(nonce_too_low("Nonce too low: next nonce 5, tx nonce 3"));
+        assert!(!already_broadcast("insufficient funds for gas * price + value"));
+        assert!(!already_broadcast("replacement transaction underpriced"));
+    }
+}
@@ -0,0 +1,724 @@
+use arbi_common::narrate;
+use arbi_common::network;
+use arbi_common::rpc::{RpcClient, RpcProvider};
+use arbi_common::shutdown::{Interrupted, Shutdown};
+use arbi_common::style;
+use ethers::abi::{Abi, Token};
+use ethers::prelude::*;
+use ethers::signers::LocalWallet;
+use ethers::types::{Address, Bytes, TxHash, U256};
+use ethers::utils::format_units;
+use serde_json::json;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{Instrument, Span, field, info, info_span, warn};
+
+use crate::get_gas_price;
+use crate::messages::{Msg, t, tf};
+use crate::retryable;
+
+// L1GatewayRouter 的 ABI 片段：查找代币对应的网关、计算 L2 代币地址、发起跨链转账
+const L1_GATEWAY_ROUTER_ABI: &str = r#"[
+    {
+        "inputs": [{"name": "_token", "type": "address"}],
+        "name": "getGateway",
+        "outputs": [{"name": "gateway", "type": "address"}],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [{"name": "l1ERC20", "type": "address"}],
+        "name": "calculateL2TokenAddress",
+        "outputs": [{"name": "", "type": "address"}],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [
+            {"name": "_token", "type": "address"},
+            {"name": "_to", "type": "address"},
+            {"name": "_amount", "type": "uint256"},
+            {"name": "_maxGas", "type": "uint256"},
+            {"name": "_gasPriceBid", "type": "uint256"},
+            {"name": "_data", "type": "bytes"}
+        ],
+        "name": "outboundTransfer",
+        "outputs": [{"name": "", "type": "bytes"}],
+        "stateMutability": "payable",
+        "type": "function"
+    }
+]"#;
+
+// L1 网关的 ABI 片段：L2 上对应的网关，以及 retryable ticket 在 L2 上执行的 calldata
+const L1_GATEWAY_ABI: &str = r#"[
+    {
+        "inputs": [],
+        "name": "counterpartGateway",
+        "outputs": [{"name": "", "type": "address"}],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [
+            {"name": "_token", "type": "address"},
+            {"name": "_from", "type": "address"},
+            {"name": "_to", "type": "address"},
+            {"name": "_amount", "type": "uint256"},
+            {"name": "_data", "type": "bytes"}
+        ],
+        "name": "getOutboundCalldata",
+        "outputs": [{"name": "outboundCalldata", "type": "bytes"}],
+        "stateMutability": "view",
+        "type": "function"
+    }
+]"#;
+
+// ERC20 中用到的 ABI 片段
+const ERC20_ABI: &str = r#"[
+    {
+        "inputs": [{"name": "account", "type": "address"}],
+        "name": "balanceOf",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [],
+        "name": "decimals",
+        "outputs": [{"name": "", "type": "uint8"}],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [
+            {"name": "owner", "type": "address"},
+            {"name": "spender", "type": "address"}
+        ],
+        "name": "allowance",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [
+            {"name": "spender", "type": "address"},
+            {"name": "amount", "type": "uint256"}
+        ],
+        "name": "approve",
+        "outputs": [{"name": "", "type": "bool"}],
+        "stateMutability": "nonpayable",
+        "type": "function"
+    }
+]"#;
+
+/// 跨链代币的进度，保存在状态文件中，流程中断后重新运行时据此续上
+///
+/// 授权交易不需要记录：重新运行时授权额度已经足够，会直接跳过。
+/// 跨链交易发出后必须记录，否则重新运行会再转一次。
+pub struct BridgeProgress {
+    pub token: Address,
+    pub amount: U256,
+    /// 发起跨链前 L2 代币的余额
+    pub l2_balance_before: U256,
+    /// L1 上 `outboundTransfer` 交易的哈希，还没发出时为 `None`
+    pub deposit_tx: Option<TxHash>,
+}
+
+impl BridgeProgress {
+    /// 读取状态文件，文件不存在时返回 `None`
+    ///
+    /// # 参数
+    /// * `path` - 状态文件路径
+    ///
+    /// # 返回
+    /// * `Result<Option<BridgeProgress>, Box<dyn Error>>` - 保存的进度
+    pub fn load(path: &Path) -> Result<Option<Self>, Box<dyn Error>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let field = |name: &str| {
+            value[name]
+                .as_str()
+                .ok_or_else(|| tf(Msg::BridgeBadState, &[&path.display(), &name]))
+        };
+        let deposit_tx = match value["depositTx"].as_str() {
+            Some(hash) => Some(TxHash::from_str(hash)?),
+            None => None,
+        };
+        Ok(Some(Self {
+            token: Address::from_str(field("token")?)?,
+            amount: U256::from_dec_str(field("amount")?)?,
+            l2_balance_before: U256::from_dec_str(field("l2BalanceBefore")?)?,
+            deposit_tx,
+        }))
+    }
+
+    /// 写入状态文件
+    ///
+    /// # 参数
+    /// * `path` - 状态文件路径
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let content = json!({
+            "token": format!("{:?}", self.token),
+            "amount": self.amount.to_string(),
+            "l2BalanceBefore": self.l2_balance_before.to_string(),
+            "depositTx": self.deposit_tx.map(|hash| format!("{:?}", hash)),
+        });
+        std::fs::write(path, serde_json::to_string_pretty(&content)?)?;
+        Ok(())
+    }
+}
+
+/// 跨链代币的选项
+pub struct BridgeOptions {
+    /// 进度状态文件
+    pub state_file: PathBuf,
+    /// 等待 L2 到账的最长时间
+    pub credit_timeout: Duration,
+    /// 轮询 L2 余额的间隔
+    pub poll_interval: Duration,
+}
+
+/// 跨链代币的结果
+pub struct BridgeOutcome {
+    /// L1 上 `outboundTransfer` 交易的哈希
+    pub l1_tx_hash: TxHash,
+    /// L2 上对应的代币地址
+    pub l2_token: Address,
+    /// 到账后的 L2 余额；等待超时时为 `None`
+    pub l2_balance_after: Option<U256>,
+    pub l2_balance_before: U256,
+    pub decimals: u8,
+    pub elapsed: Duration,
+}
+
+impl BridgeOutcome {
+    /// 打印跨链结果：L1 交易哈希、L2 代币地址和余额变化
+    pub fn print(&self) -> Result<(), Box<dyn Error>> {
+        println!("{}", tf(Msg::DepositL1TxHash, &[&style::addr(format!("{:?}", self.l1_tx_hash))]));
+        println!("{}", tf(Msg::BridgeL2Token, &[&style::addr(self.l2_token)]));
+        match self.l2_balance_after {
+            Some(after) => {
+                let delta = after.saturating_sub(self.l2_balance_before);
+                let delta = format_units(delta, self.decimals as u32)?;
+                println!(
+                    "{}",
+                    style::ok(tf(Msg::BridgeCredited, &[&self.elapsed.as_secs(), &delta]))
+                );
+            }
+            None => {
+                println!(
+                    "{}",
+                    style::warn(tf(Msg::DepositNotYetCredited, &[&self.elapsed.as_secs()]))
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 当前授权额度不足时才需要授权
+///
+/// # 参数
+/// * `allowance` - 当前授权给网关的额度
+/// * `amount` - 要跨链的数量
+///
+/// # 返回
+/// * `bool` - 需要发送 `approve` 时为 `true`
+pub fn needs_approval(allowance: U256, amount: U256) -> bool {
+    allowance < amount
+}
+
+/// 编码 `outboundTransfer` 的 `_data`：`abi.encode(maxSubmissionCost, extraData)`
+///
+/// 标准网关从中取出提交费，`extraData` 用于回调，这里为空。
+///
+/// # 参数
+/// * `max_submission_cost` - 最高提交费
+///
+/// # 返回
+/// * `Bytes` - 编码后的数据
+pub fn encode_gateway_data(max_submission_cost: U256) -> Bytes {
+    ethers::abi::encode(&[Token::Uint(max_submission_cost), Token::Bytes(Vec::new())]).into()
+}
+
+fn contract<M: Middleware>(
+    address: Address,
+    abi: &str,
+    client: Arc<M>,
+) -> Result<Contract<M>, Box<dyn Error>> {
+    Ok(Contract::new(address, serde_json::from_str::<Abi>(abi)?, client))
+}
+
+/// 读取 L2 代币余额，代币第一次跨链前 L2 上还没有部署合约，余额视为 0
+async fn l2_token_balance(
+    l2_provider: &RpcProvider,
+    l2_token: Address,
+    owner: Address,
+) -> Result<U256, Box<dyn Error>> {
+    if l2_provider.get_code(l2_token, None).await?.is_empty() {
+        return Ok(U256::zero());
+    }
+    let token = contract(l2_token, ERC20_ABI, Arc::new(l2_provider.clone()))?;
+    Ok(token.method("balanceOf", owner)?.call().await?)
+}
+
+/// 代币在 L1GatewayRouter 上的路由：负责的网关、L2 上对应的代币地址和小数位数
+struct TokenRoute {
+    gateway: Address,
+    l2_token: Address,
+    decimals: u8,
+}
+
+/// 查找代币的网关和 L2 地址；没有注册网关的代币返回错误
+async fn token_route<M: Middleware + 'static>(
+    router: &Contract<M>,
+    l1_token: &Contract<M>,
+) -> Result<TokenRoute, Box<dyn Error>> {
+    let token = l1_token.address();
+    let gateway: Address = router.method("getGateway", token)?.call().await?;
+    if gateway.is_zero() {
+        return Err(tf(Msg::BridgeNoGateway, &[&format!("{:?}", token)]).into());
+    }
+    let l2_token: Address = router.method("calculateL2TokenAddress", token)?.call().await?;
+    let decimals: u8 = l1_token.method("decimals", ())?.call().await?;
+    Ok(TokenRoute {
+        gateway,
+        l2_token,
+        decimals,
+    })
+}
+
+/// 授权额度不足时向网关（不是路由）授权，等待授权交易确认
+///
+/// # 返回
+/// * `Result<bool, Box<dyn Error>>` - 发送了授权交易时为 `true`，额度已足够时为 `false`
+async fn ensure_allowance(
+    l1_token: &Contract<SignerMiddleware<RpcProvider, LocalWallet>>,
+    from: Address,
+    gateway: Address,
+    amount: U256,
+    shutdown: &Shutdown,
+) -> Result<bool, Box<dyn Error>> {
+    let allowance: U256 = l1_token.method("allowance", (from, gateway))?.call().await?;
+    if !needs_approval(allowance, amount) {
+        narrate!("{}", style::ok(t(Msg::BridgeApprovalSufficient)));
+        return Ok(false);
+    }
+    let call = l1_token.method::<_, bool>("approve", (gateway, amount))?;
+    let pending_tx = call.send().instrument(info_span!("approve")).await?;
+    let hash = pending_tx.tx_hash();
+    narrate!("{}", tf(Msg::BridgeApproveTx, &[&style::addr(format!("{:?}", hash))]));
+    confirm(pending_tx, shutdown).await?;
+    narrate!("{}", style::ok(t(Msg::BridgeApproved)));
+    Ok(true)
+}
+
+/// 等待交易确认，status 不为 1 时返回错误
+async fn confirm(
+    pending_tx: PendingTransaction<'_, RpcClient>,
+    shutdown: &Shutdown,
+) -> Result<(), Box<dyn Error>> {
+    let receipt = tokio::select! {
+        receipt = pending_tx.instrument(info_span!("confirm")) => receipt?,
+        _ = shutdown.wait() => return Err(Interrupted.into()),
+    };
+    match receipt {
+        Some(receipt) if receipt.status == Some(1.into()) => Ok(()),
+        Some(_) => Err(t(Msg::BridgeReverted).into()),
+        None => Err(t(Msg::DepositL1NoReceipt).into()),
+    }
+}
+
+/// 通过 L1GatewayRouter 把标准 ERC20 从 L1 跨到 L2
+///
+/// 流程：`getGateway` 找到代币的网关 → 授权额度不足时向网关授权 → 用 retryable 估算器
+/// 计算 L2 执行参数 → 调用 `outboundTransfer` → 轮询 `calculateL2TokenAddress` 对应的
+/// L2 代币余额。每一步的交易哈希都会打印出来。
+///
+/// 进度保存在 `options.state_file` 中：授权后中断，重新运行会跳过已足够的授权；
+/// 跨链交易发出后中断，重新运行只继续等待到账，不会重复转账。到账后删除状态文件。
+///
+/// # 参数
+/// * `l1_provider` - L1 Provider
+/// * `l2_provider` - L2 Provider
+/// * `private_key` - 私钥（L1 和 L2 使用同一个地址）
+/// * `token` - L1 上的代币地址
+/// * `amount` - 跨链数量（代币最小单位）
+/// * `options` - 状态文件和等待参数
+/// * `shutdown` - Ctrl-C 关闭信号
+///
+/// # 返回
+/// * `Result<BridgeOutcome, Box<dyn Error>>` - 跨链结果
+pub async fn bridge_token(
+    l1_provider: &RpcProvider,
+    l2_provider: &RpcProvider,
+    private_key: &str,
+    token: Address,
+    amount: &str,
+    options: &BridgeOptions,
+    shutdown: &Shutdown,
+) -> Result<BridgeOutcome, Box<dyn Error>> {
+    // 1. 连接 L1，找到代币的网关和 L2 地址
+    narrate!("{}", t(Msg::DepositStepConnect));
+    let chain_id = l1_provider
+        .get_chainid()
+        .instrument(info_span!("connect"))
+        .await?;
+    Span::current().record("chain_id", chain_id.as_u64());
+    narrate!("{}", style::ok(tf(Msg::Connected, &[&chain_id])));
+    network::check_l1_chain_id(chain_id.as_u64())?;
+
+    let wallet: LocalWallet = private_key.parse()?;
+    let from = wallet.address();
+    let client = Arc::new(SignerMiddleware::new(
+        l1_provider.clone(),
+        network::signing_wallet(wallet, chain_id.as_u64())?,
+    ));
+    let router_address = Address::from_str(network::current().l1_gateway_router_address())?;
+    let router = contract(router_address, L1_GATEWAY_ROUTER_ABI, client.clone())?;
+    let l1_token = contract(token, ERC20_ABI, client.clone())?;
+
+    let TokenRoute {
+        gateway: gateway_address,
+        l2_token,
+        decimals,
+    } = token_route(&router, &l1_token).await?;
+    let amount = arbi_common::units::parse_amount(amount, decimals as u32)?;
+    narrate!("{}", style::ok(tf(Msg::BridgeGateway, &[&style::addr(gateway_address)])));
+    narrate!("{}", style::ok(tf(Msg::BridgeL2Token, &[&style::addr(l2_token)])));
+
+    // 状态文件记录的是另一笔跨链时不能续上，避免误把别的交易当成这一笔
+    let saved = BridgeProgress::load(&options.state_file)?;
+    let mut progress = match saved {
+        Some(saved) if saved.token == token && saved.amount == amount => {
+            narrate!("{}", style::warn(tf(Msg::BridgeResuming, &[&options.state_file.display()])));
+            saved
+        }
+        Some(_) => {
+            return Err(tf(Msg::BridgeStateMismatch, &[&options.state_file.display()]).into());
+        }
+        None => BridgeProgress {
+            token,
+            amount,
+            l2_balance_before: l2_token_balance(l2_provider, l2_token, from).await?,
+            deposit_tx: None,
+        },
+    };
+
+    let l1_tx_hash = match progress.deposit_tx {
+        Some(hash) => {
+            narrate!("{}", tf(Msg::BridgeAlreadySent, &[&style::addr(format!("{:?}", hash))]));
+            hash
+        }
+        None => {
+            // 2. 授权额度不足时向网关（不是路由）授权
+            narrate!("\n{}", t(Msg::BridgeStepApprove));
+            ensure_allowance(&l1_token, from, gateway_address, amount, shutdown).await?;
+
+            // 3. 网关在 L2 上执行的就是一个 retryable ticket，用估算器计算参数
+            narrate!("\n{}", t(Msg::RetryableEstimating));
+            let gateway = contract(gateway_address, L1_GATEWAY_ABI, client.clone())?;
+            let l2_gateway: Address = gateway.method("counterpartGateway", ())?.call().await?;
+            let calldata: Bytes = gateway
+                .method("getOutboundCalldata", (token, from, from, amount, Bytes::new()))?
+                .call()
+                .await?;
+            let max_fee_per_gas = get_gas_price(l2_provider).await? * 2;
+            let estimate = retryable::estimate_ticket(
+                l1_provider,
+                l2_provider,
+                gateway_address,
+                l2_gateway,
+                U256::zero(),
+                calldata,
+                max_fee_per_gas,
+            )
+            .await?;
+            narrate!(
+                "{}",
+                style::ok(tf(
+                    Msg::RetryableFees,
+                    &[
+                        &format_units(estimate.max_submission_cost, "ether")?,
+                        &format_units(estimate.max_gas_fee(), "ether")?,
+                        &format_units(estimate.deposit, "ether")?,
+                    ]
+                ))
+            );
+
+            // 4. 发起跨链，发出后立即记录，之后中断也不会重复转账
+            narrate!("\n{}", t(Msg::BridgeStepSend));
+            let call = router
+                .method::<_, Bytes>(
+                    "outboundTransfer",
+                    (
+                        token,
+                        from,
+                        amount,
+                        estimate.gas_limit,
+                        estimate.max_fee_per_gas,
+                        encode_gateway_data(estimate.max_submission_cost),
+                    ),
+                )?
+                .value(estimate.deposit);
+            let pending_tx = call
+                .send()
+                .instrument(info_span!("broadcast", token = ?token, amount = %amount))
+                .await?;
+            let hash = pending_tx.tx_hash();
+            progress.deposit_tx = Some(hash);
+            progress.save(&options.state_file)?;
+            Span::current().record("tx_hash", field::debug(hash));
+            info!("token deposit broadcast on L1");
+            narrate!("{}", style::ok(tf(Msg::TxHashLine, &[&style::addr(format!("{:?}", hash))])));
+            confirm(pending_tx, shutdown).await?;
+            hash
+        }
+    };
+
+    // 5. 轮询 L2 代币余额，直到到账或超时
+    narrate!("\n{}", t(Msg::DepositStepWait));
+    narrate!("{}", style::warn(t(Msg::DepositDelayNote)));
+    let started = Instant::now();
+    let l2_balance_after = async {
+        loop {
+            let balance = l2_token_balance(l2_provider, l2_token, from).await?;
+            if balance > progress.l2_balance_before {
+                info!(elapsed_secs = started.elapsed().as_secs(), "token deposit credited on L2");
+                return Ok::<_, Box<dyn Error>>(Some(balance));
+            }
+            if started.elapsed() >= options.credit_timeout {
+                warn!("token deposit not credited before timeout");
+                return Ok(None);
+            }
+            narrate!("{}", tf(Msg::DepositWaiting, &[&started.elapsed().as_secs()]));
+
+            tokio::select! {
+                _ = tokio::time::sleep(options.poll_interval) => {}
+                _ = shutdown.wait() => return Err(Interrupted.into()),
+            }
+        }
+    }
+    .instrument(info_span!("credit"))
+    .await?;
+
+    if l2_balance_after.is_some() {
+        std::fs::remove_file(&options.state_file).ok();
+    }
+    Ok(BridgeOutcome {
+        l1_tx_hash,
+        l2_token,
+        l2_balance_after,
+        l2_balance_before: progress.l2_balance_before,
+        decimals,
+        elapsed: started.elapsed(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use ethers::utils::{hex, keccak256};
+
+    // EIP-155 示例私钥，只用于测试
+    const PRIVATE_KEY: &str = "4646464646464646464646464646464646464646464646464646464646464646";
+
+    fn word(hex: &str) -> String {
+        format!("{:0>64}", hex)
+    }
+
+    fn selector(signature: &str) -> String {
+        format!("0x{}", hex::encode(&keccak256(signature)[..4]))
+    }
+
+    fn address_word(address: Address) -> String {
+        word(&hex::encode(address))
+    }
+
+    // 一个 L1 节点：代币 6 位小数，路由把它指向 `gateway`，当前授权额度为 `allowance`；
+    // 发出的交易立即以 status 1 上链
+    fn node(gateway: Address, allowance: u64) -> MockRpc {
+        let [get_gateway, calculate, decimals, allowance_of] = [
+            "getGateway(address)",
+            "calculateL2TokenAddress(address)",
+            "decimals()",
+            "allowance(address,address)",
+        ]
+        .map(selector);
+        MockRpc::start(move |method, params| {
+            let call = &params[0];
+            let data = call["input"].as_str().or(call["data"].as_str()).unwrap_or_default();
+            let result = match method {
+                "eth_call" if data.starts_with(&get_gateway) => address_word(gateway),
+                "eth_call" if data.starts_with(&calculate) => address_word(l2_token()),
+                "eth_call" if data.starts_with(&decimals) => word("6"),
+                "eth_call" if data.starts_with(&allowance_of) => {
+                    word(&format!("{:x}", allowance))
+                }
+                "eth_chainId" => return Reply::Result(json!("0xaa36a7")),
+                "eth_estimateGas" => return Reply::Result(json!("0xb411")),
+                "eth_gasPrice" | "eth_maxPriorityFeePerGas" => {
+                    return Reply::Result(json!("0x3b9aca00"));
+                }
+                "eth_getTransactionCount" | "eth_blockNumber" => {
+                    return Reply::Result(json!("0x1"));
+                }
+                "eth_getBlockByNumber" => {
+                    return Reply::Result(json!({
+                        "number": "0x1",
+                        "hash": format!("{:?}", TxHash::repeat_byte(0x01)),
+                        "baseFeePerGas": "0x3b9aca00",
+                        "transactions": [],
+                    }));
+                }
+                "eth_feeHistory" => {
+                    return Reply::Result(json!({
+                        "oldestBlock": "0x1",
+                        "baseFeePerGas": ["0x3b9aca00", "0x3b9aca00"],
+                        "gasUsedRatio": [0.5],
+                        "reward": [["0x3b9aca00"]],
+                    }));
+                }
+                "eth_sendRawTransaction" => {
+                    let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+                    return Reply::Result(json!(format!("{:?}", TxHash::from(keccak256(raw)))));
+                }
+                "eth_getTransactionByHash" => {
+                    return Reply::Result(json!({
+                        "hash": params[0],
+                        "nonce": "0x1",
+                        "from": format!("{:?}", Address::repeat_byte(0x11)),
+                        "value": "0x0",
+                        "gas": "0xb411",
+                        "input": "0x",
+                        "v": "0x0",
+                        "r": "0x0",
+                        "s": "0x0",
+                        "blockNumber": "0x1",
+                        "blockHash": format!("{:?}", TxHash::repeat_byte(0x01)),
+                    }));
+                }
+                "eth_getTransactionReceipt" => {
+                    return Reply::Result(json!({
+                        "transactionHash": params[0],
+                        "transactionIndex": "0x0",
+                        "blockNumber": "0x1",
+                        "blockHash": format!("{:?}", TxHash::repeat_byte(0x01)),
+                        "from": format!("{:?}", Address::repeat_byte(0x11)),
+                        "cumulativeGasUsed": "0xb411",
+                        "gasUsed": "0xb411",
+                        "logs": [],
+                        "logsBloom": format!("0x{}", "00".repeat(256)),
+                        "status": "0x1",
+                    }));
+                }
+                _ => return Reply::Error(format!("unexpected {}", method)),
+            };
+            Reply::Result(json!(format!("0x{}", result)))
+        })
+    }
+
+    fn token() -> Address {
+        Address::repeat_byte(0x7a)
+    }
+
+    fn l2_token() -> Address {
+        Address::repeat_byte(0x2b)
+    }
+
+    fn gateway() -> Address {
+        Address::repeat_byte(0x6a)
+    }
+
+    type Client = SignerMiddleware<RpcProvider, LocalWallet>;
+
+    fn contracts(rpc: &MockRpc) -> (Contract<Client>, Contract<Client>) {
+        let wallet: LocalWallet = PRIVATE_KEY.parse().unwrap();
+        let provider = rpc.provider().interval(Duration::from_millis(10));
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(11155111u64)));
+        let router = contract(Address::repeat_byte(0x52), L1_GATEWAY_ROUTER_ABI, client.clone());
+        (router.unwrap(), contract(token(), ERC20_ABI, client).unwrap())
+    }
+
+    // eth_call 请求里的 calldata
+    fn calls(rpc: &MockRpc, method: &str) -> Vec<String> {
+        rpc.requests()
+            .into_iter()
+            .filter(|(name, _)| name == method)
+            .map(|(_, params)| {
+                let call = &params[0];
+                call["input"].as_str().or(call["data"].as_str()).unwrap().to_string()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn route_asks_the_router_for_the_l2_token_address() {
+        let rpc = node(gateway(), 0);
+        let (router, l1_token) = contracts(&rpc);
+        let route = token_route(&router, &l1_token).await.unwrap();
+        assert_eq!(route.gateway, gateway());
+        assert_eq!(route.l2_token, l2_token());
+        assert_eq!(route.decimals, 6);
+
+        let calculate = selector("calculateL2TokenAddress(address)");
+        let expected = format!("{}{}", calculate, address_word(token()));
+        assert!(calls(&rpc, "eth_call").contains(&expected));
+    }
+
+    #[tokio::test]
+    async fn token_without_gateway_is_rejected() {
+        let rpc = node(Address::zero(), 0);
+        let (router, l1_token) = contracts(&rpc);
+        let error = token_route(&router, &l1_token).await.err().unwrap();
+        assert_eq!(error.to_string(), tf(Msg::BridgeNoGateway, &[&format!("{:?}", token())]));
+    }
+
+    #[tokio::test]
+    async fn sufficient_allowance_skips_approval() {
+        let shutdown = Shutdown::new();
+        for allowance in [1_500_000, 2_000_000] {
+            let rpc = node(gateway(), allowance);
+            let (_, l1_token) = contracts(&rpc);
+            let from = Address::repeat_byte(0x11);
+            let amount = U256::from(1_500_000);
+            let approved = ensure_allowance(&l1_token, from, gateway(), amount, &shutdown).await;
+            assert!(!approved.unwrap());
+            assert_eq!(rpc.count("eth_sendRawTransaction"), 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn insufficient_allowance_approves_the_gateway() {
+        let rpc = node(gateway(), 1_499_999);
+        let (_, l1_token) = contracts(&rpc);
+        let from = Address::repeat_byte(0x11);
+        let amount = U256::from(1_500_000);
+        let approved = ensure_allowance(&l1_token, from, gateway(), amount, &Shutdown::new()).await;
+        assert!(approved.unwrap());
+        assert_eq!(rpc.count("eth_sendRawTransaction"), 1);
+
+        // 授权对象是网关，不是路由
+        let approve = format!(
+            "{}{}{}",
+            selector("approve(address,uint256)"),
+            address_word(gateway()),
+            word("16e360"),
+        );
+        assert_eq!(calls(&rpc, "eth_estimateGas"), [approve]);
+    }
+
+    // abi.encode(maxSubmissionCost, bytes(""))
+    #[test]
+    fn gateway_data_encodes_submission_cost_and_empty_extra_data() {
+        let data = encode_gateway_data(U256::from(0x2386f26fc10000_u64));
+        let expected = [word("2386f26fc10000"), word("40"), word("0")].concat();
+        assert_eq!(hex::encode(data), expected);
+    }
+}
@@ -0,0 +1,527 @@
+use arbi_common::narrate;
+use arbi_common::rpc::{self, RpcProvider};
+use arbi_common::shutdown::{Interrupted, Shutdown};
+use arbi_common::style;
+use ethers::prelude::*;
+use ethers::providers::Middleware;
+use ethers::signers::LocalWallet;
+use ethers::types::{BlockNumber, TransactionReceipt, TransactionRequest, U64};
+use ethers::utils::format_units;
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+use tracing::{Instrument, Span, field, info, info_span, warn};
+
+use crate::messages::{Msg, t, tf};
+
+/// 已上链交易的摘要
+pub struct TransferResult {
+    pub tx_hash: TxHash,
+    pub block_number: Option<U64>,
+    pub gas_used: Option<U256>,
+    pub status: Option<U64>,
+    /// 发送前的最新区块；只有本次发送的交易才有，事后查询的为 `None`
+    pub submitted_block: Option<U64>,
+    /// 从广播到查到收据的时间，精度受收据轮询间隔限制
+    pub inclusion_delay: Option<Duration>,
+}
+
+impl From<&TransactionReceipt> for TransferResult {
+    fn from(receipt: &TransactionReceipt) -> Self {
+        Self {
+            tx_hash: receipt.transaction_hash,
+            block_number: receipt.block_number,
+            gas_used: receipt.gas_used,
+            status: receipt.status,
+            submitted_block: None,
+            inclusion_delay: None,
+        }
+    }
+}
+
+impl TransferResult {
+    /// 区块号、Gas 使用量和执行状态，每项一行
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            style::ok(t(Msg::TxConfirmed)),
+            tf(Msg::BlockNumber, &[&format!("{:?}", self.block_number)]),
+            tf(Msg::GasUsed, &[&format!("{:?}", self.gas_used)]),
+            tf(Msg::Status, &[&format!("{:?}", self.status)]),
+        ];
+        if let (Some(submitted), Some(included), Some(delay)) =
+            (self.submitted_block, self.block_number, self.inclusion_delay)
+        {
+            let blocks = included.saturating_sub(submitted).as_u64();
+            let msg = if blocks == 1 { Msg::InclusionOneBlock } else { Msg::InclusionBlocks };
+            lines.push(tf(msg, &[&blocks, &format!("{:.1}", delay.as_secs_f64())]));
+        }
+        lines
+    }
+
+    /// 打印 [`lines`](Self::lines)
+    pub fn print(&self) {
+        for line in self.lines() {
+            println!("{}", line);
+        }
+    }
+}
+
+/// 等待确认的结果
+pub enum ConfirmationOutcome {
+    /// 交易已上链
+    Confirmed(TransferResult),
+    /// 超时仍未确认；交易已经广播，可以保存哈希稍后再查
+    Pending { tx_hash: TxHash, elapsed: Duration },
+}
+
+// 等待收据的轮询间隔；Provider 默认 7 秒一次，而 Arbitrum 约 0.25 秒出一个块，
+// 按默认间隔测出的打包延迟几乎全是轮询等待
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// 每次重发把 Gas 价格提高的百分比（节点一般要求替换交易至少高 10%）
+const BUMP_PERCENT: u64 = 20;
+
+// 没有指定 Gas 价格上限时，最多提高到初始价格的倍数
+const DEFAULT_BUMP_CAP_MULTIPLIER: u64 = 3;
+
+/// 超时未确认时自动用同一个 nonce、更高的 Gas 价格重发
+#[derive(Clone, Copy, Debug)]
+pub struct AutoBump {
+    /// 最多重发的次数
+    pub max_bumps: u32,
+    /// Gas 价格上限，`None` 时为初始价格的 `DEFAULT_BUMP_CAP_MULTIPLIER` 倍
+    pub max_gas_price: Option<U256>,
+}
+
+/// 计算重发使用的 Gas 价格：上一次的价格提高 `BUMP_PERCENT`%，且不低于当前网络价格
+///
+/// # 参数
+/// * `previous` - 上一次发送的 Gas 价格
+/// * `network` - 当前网络 Gas 价格
+///
+/// # 返回
+/// * `U256` - 重发的 Gas 价格
+pub fn bumped_gas_price(previous: U256, network: U256) -> U256 {
+    (previous.saturating_mul((100 + BUMP_PERCENT).into()) / 100).max(network)
+}
+
+// 重发的 Gas 价格上限：没有指定时为初始价格的 `DEFAULT_BUMP_CAP_MULTIPLIER` 倍
+fn bump_cap(initial_price: U256, max_gas_price: Option<U256>) -> U256 {
+    max_gas_price.unwrap_or(initial_price.saturating_mul(DEFAULT_BUMP_CAP_MULTIPLIER.into()))
+}
+
+// 重发过程中出错时只提示并停止重发：已经广播的版本仍可能上链，调用方拿最后一个哈希稍后再查
+fn stop_bumping(error: &dyn fmt::Display) {
+    warn!(error = %error, "stopped bumping");
+    println!("{}", style::warn(tf(Msg::BumpFailed, &[error])));
+}
+
+/// 依次查询已发送的各个版本，返回第一个已上链的收据
+async fn find_receipt(
+    client: &SignerMiddleware<RpcProvider, LocalWallet>,
+    hashes: &[TxHash],
+) -> Result<Option<TransactionReceipt>, Box<dyn Error>> {
+    for hash in hashes {
+        if let Some(receipt) = client.get_transaction_receipt(*hash).await? {
+            return Ok(Some(receipt));
+        }
+    }
+    Ok(None)
+}
+
+/// 在 `timeout` 内轮询，直到任意一个版本上链
+async fn wait_for_any(
+    client: &SignerMiddleware<RpcProvider, LocalWallet>,
+    hashes: &[TxHash],
+    timeout: Duration,
+    shutdown: &Shutdown,
+) -> Result<Option<TransactionReceipt>, Box<dyn Error>> {
+    let started = Instant::now();
+    loop {
+        if let Some(receipt) = find_receipt(client, hashes).await? {
+            return Ok(Some(receipt));
+        }
+        if started.elapsed() >= timeout {
+            return Ok(None);
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(RECEIPT_POLL_INTERVAL) => {}
+            _ = shutdown.wait() => {
+                warn!("stopped waiting for confirmation");
+                println!("{}", style::warn(t(Msg::StopWaiting)));
+                for hash in hashes {
+                    println!("{}", tf(Msg::TxHashDetail, &[&style::addr(format!("{:?}", hash))]));
+                }
+                return Err(Interrupted.into());
+            }
+        }
+    }
+}
+
+fn confirmed(receipt: &TransactionReceipt) -> ConfirmationOutcome {
+    info!(
+        tx_hash = ?receipt.transaction_hash,
+        block_number = ?receipt.block_number,
+        gas_used = ?receipt.gas_used,
+        status = ?receipt.status,
+        "transaction confirmed"
+    );
+    ConfirmationOutcome::Confirmed(TransferResult::from(receipt))
+}
+
+/// 原交易超时未确认后，用同一个 nonce 逐次提高 Gas 价格重发，任意一个版本上链即停止
+///
+/// 新价格超过上限、重发次数用完或重发出错时不再重发，返回最后一个版本的哈希。
+async fn bump_until_confirmed(
+    client: &SignerMiddleware<RpcProvider, LocalWallet>,
+    mut tx: TransactionRequest,
+    first_hash: TxHash,
+    waited: Duration,
+    timeout: Duration,
+    auto_bump: AutoBump,
+    shutdown: &Shutdown,
+) -> Result<ConfirmationOutcome, Box<dyn Error>> {
+    let initial_price = tx.gas_price.unwrap_or_default();
+    let cap = bump_cap(initial_price, auto_bump.max_gas_price);
+    let started = Instant::now();
+    let mut hashes = vec![first_hash];
+
+    for bump in 1..=auto_bump.max_bumps {
+        let network_price = match client.get_gas_price().await {
+            Ok(price) => price,
+            Err(e) => {
+                stop_bumping(&e);
+                break;
+            }
+        };
+        let gas_price = bumped_gas_price(tx.gas_price.unwrap_or(initial_price), network_price);
+        if gas_price > cap {
+            let (price, cap) = (format_units(gas_price, "gwei")?, format_units(cap, "gwei")?);
+            println!("{}", style::warn(tf(Msg::BumpCapReached, &[&price, &cap])));
+            break;
+        }
+
+        tx = tx.gas_price(gas_price);
+        let hash = match client.send_transaction(tx.clone(), None).await {
+            Ok(pending_tx) => pending_tx.tx_hash(),
+            // 之前的版本恰好在这时上链，节点会拒绝同一个 nonce 的替换交易
+            Err(e) => {
+                if let Ok(Some(receipt)) = find_receipt(client, &hashes).await {
+                    return Ok(confirmed(&receipt));
+                }
+                stop_bumping(&e);
+                break;
+            }
+        };
+        hashes.push(hash);
+        warn!(bump, gas_price_wei = %gas_price, tx_hash = ?hash, "transaction bumped");
+        println!(
+            "{}",
+            style::warn(tf(
+                Msg::BumpSent,
+                &[
+                    &bump,
+                    &auto_bump.max_bumps,
+                    &format_units(gas_price, "gwei")?,
+                    &style::addr(format!("{:?}", hash)),
+                ]
+            ))
+        );
+
+        narrate!("{}", tf(Msg::StepConfirm, &[&timeout.as_secs()]));
+        match wait_for_any(client, &hashes, timeout, shutdown).await {
+            Ok(Some(receipt)) => return Ok(confirmed(&receipt)),
+            Ok(None) => {}
+            Err(e) if e.is::<Interrupted>() => return Err(e),
+            Err(e) => {
+                stop_bumping(&e);
+                break;
+            }
+        }
+    }
+
+    Ok(ConfirmationOutcome::Pending {
+        tx_hash: *hashes.last().unwrap_or(&first_hash),
+        elapsed: waited + started.elapsed(),
+    })
+}
+
+/// 签名并广播交易，然后在 `timeout` 内等待确认
+///
+/// 超时不算失败：返回 `Pending`，调用方拿着交易哈希稍后再查。设置了 `auto_bump` 时，
+/// 超时后改为用同一个 nonce、更高的 Gas 价格重发并继续等待。
+/// 等待期间收到 Ctrl-C 时返回 `Interrupted` 错误，交易本身仍会继续上链。
+/// 发送前记下最新区块，确认后在结果里带上经过了几个区块和多长时间才被打包。
+///
+/// # 参数
+/// * `client` - 签名客户端
+/// * `tx` - 已构建好的交易
+/// * `timeout` - 每个版本等待确认的最长时间
+/// * `auto_bump` - 超时后自动重发的设置
+/// * `shutdown` - Ctrl-C 关闭信号
+///
+/// # 返回
+/// * `Result<ConfirmationOutcome, Box<dyn Error>>` - 已确认的结果或仍在等待的交易哈希
+pub async fn send_and_wait(
+    client: &SignerMiddleware<RpcProvider, LocalWallet>,
+    mut tx: TransactionRequest,
+    timeout: Duration,
+    auto_bump: Option<AutoBump>,
+    shutdown: &Shutdown,
+) -> Result<ConfirmationOutcome, Box<dyn Error>> {
+    // 签名并发送交易
+    narrate!("\n{}", t(Msg::StepSend));
+    // 重发必须使用同一个 nonce，先固定下来；重发的价格和上限按初始价格计算，也要先确定
+    if auto_bump.is_some() && tx.nonce.is_none() {
+        let nonce = client
+            .get_transaction_count(client.address(), Some(BlockNumber::Pending.into()))
+            .await?;
+        tx = tx.nonce(nonce);
+    }
+    if auto_bump.is_some() && tx.gas_price.is_none() {
+        tx = tx.gas_price(client.get_gas_price().await?);
+    }
+    let to = tx.to.clone();
+    let value = tx.value.unwrap_or_default();
+    // 只用于显示打包延迟，查询失败不影响发送
+    let submitted_block = client.get_block_number().await.ok();
+    let sent_at = Instant::now();
+    let pending_tx = client
+        .send_transaction(tx.clone(), None)
+        .instrument(info_span!("broadcast", to = ?to, value_wei = %value))
+        .await?
+        .interval(RECEIPT_POLL_INTERVAL);
+    let tx_hash = pending_tx.tx_hash();
+    Span::current().record("tx_hash", field::debug(tx_hash));
+    info!("transaction broadcast");
+    narrate!("{}", style::ok(t(Msg::TxSent)));
+    narrate!("{}", style::ok(tf(Msg::TxHashLine, &[&style::addr(format!("{:?}", tx_hash))])));
+
+    // 等待交易确认
+    narrate!("\n{}", tf(Msg::StepConfirm, &[&timeout.as_secs()]));
+    let started = Instant::now();
+    let waited = tokio::select! {
+        waited = tokio::time::timeout(timeout, pending_tx.instrument(info_span!("confirm"))) => waited,
+        _ = shutdown.wait() => {
+            warn!("stopped waiting for confirmation");
+            println!("{}", style::warn(t(Msg::StopWaiting)));
+            println!("{}", tf(Msg::TxHashDetail, &[&style::addr(format!("{:?}", tx_hash))]));
+            return Err(Interrupted.into());
+        }
+    };
+
+    let mut outcome = match waited {
+        Ok(Ok(Some(receipt))) => confirmed(&receipt),
+        // 节点找不到这笔交易时 PendingTransaction 会返回 None，同样交给调用方稍后再查
+        Ok(Ok(None)) | Err(_) => {
+            let elapsed = started.elapsed();
+            warn!(elapsed_secs = elapsed.as_secs(), "confirmation timed out");
+            match auto_bump {
+                Some(auto_bump) => {
+                    bump_until_confirmed(client, tx, tx_hash, elapsed, timeout, auto_bump, shutdown)
+                        .await?
+                }
+                None => ConfirmationOutcome::Pending { tx_hash, elapsed },
+            }
+        }
+        Ok(Err(e)) => return Err(e.into()),
+    };
+
+    if let ConfirmationOutcome::Confirmed(result) = &mut outcome {
+        let delay = sent_at.elapsed();
+        result.submitted_block = submitted_block;
+        result.inclusion_delay = Some(delay);
+        info!(submitted_block = ?submitted_block, delay_ms = delay.as_millis() as u64, "inclusion");
+    }
+    Ok(outcome)
+}
+
+/// 链上查询到的交易状态
+pub enum TxStatus {
+    /// 已上链
+    Confirmed(TransferResult),
+    /// 节点知道这笔交易，但还没有打包
+    Pending,
+    /// 所有节点都查不到这笔交易
+    NotFound,
+}
+
+/// 在单个节点上查询交易状态
+async fn tx_status_at(rpc_url: &str, tx_hash: TxHash) -> Result<TxStatus, Box<dyn Error>> {
+    let provider = rpc::connect(rpc_url)?;
+
+    if let Some(receipt) = provider.get_transaction_receipt(tx_hash).await? {
+        return Ok(TxStatus::Confirmed(TransferResult::from(&receipt)));
+    }
+    match provider.get_transaction(tx_hash).await? {
+        Some(_) => Ok(TxStatus::Pending),
+        None => Ok(TxStatus::NotFound),
+    }
+}
+
+/// 查询之前提交的交易的状态
+///
+/// 依次尝试每个节点：某个节点查到收据就直接返回；只在内存池里看到则继续问下一个节点，
+/// 以防它已经在别处上链。单个节点丢了交易或连接失败都不影响其他节点的结果。
+///
+/// # 参数
+/// * `rpc_urls` - 依次尝试的 RPC 节点
+/// * `tx_hash` - 交易哈希
+///
+/// # 返回
+/// * `Result<TxStatus, Box<dyn Error>>` - 交易状态；所有节点都连接失败时返回最后一个错误
+pub async fn tx_status(rpc_urls: &[&str], tx_hash: TxHash) -> Result<TxStatus, Box<dyn Error>> {
+    let mut status = None;
+    let mut last_error = None;
+
+    for rpc_url in rpc_urls {
+        let span = info_span!("status", rpc_url = *rpc_url, tx_hash = ?tx_hash);
+        match tx_status_at(rpc_url, tx_hash).instrument(span).await {
+            Ok(TxStatus::Confirmed(result)) => return Ok(TxStatus::Confirmed(result)),
+            Ok(TxStatus::Pending) => status = Some(TxStatus::Pending),
+            Ok(TxStatus::NotFound) => {
+                status.get_or_insert(TxStatus::NotFound);
+            }
+            Err(e) => {
+                warn!(rpc_url = *rpc_url, error = %e, "status lookup failed");
+                println!("{}", style::warn(tf(Msg::StatusRpcFailed, &[rpc_url, &e])));
+                last_error = Some(e);
+            }
+        }
+    }
+
+    match (status, last_error) {
+        (Some(status), _) => Ok(status),
+        (None, Some(e)) => Err(e),
+        (None, None) => Ok(TxStatus::NotFound),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use ethers::utils::keccak256;
+    use serde_json::{Value, json};
+    use std::sync::Arc;
+
+    // EIP-155 示例私钥，只用于测试
+    const PRIVATE_KEY: &str = "4646464646464646464646464646464646464646464646464646464646464646";
+
+    const GWEI: u64 = 1_000_000_000;
+
+    fn gwei(n: u64) -> U256 {
+        U256::from(n * GWEI)
+    }
+
+    #[test]
+    fn bump_raises_by_twenty_percent_or_to_the_network_price() {
+        assert_eq!(bumped_gas_price(gwei(10), gwei(1)), gwei(12));
+        assert_eq!(bumped_gas_price(gwei(10), gwei(15)), gwei(15));
+        assert_eq!(bumped_gas_price(U256::zero(), gwei(2)), gwei(2));
+        assert_eq!(bumped_gas_price(U256::MAX, U256::zero()), U256::MAX / 100);
+    }
+
+    #[test]
+    fn default_cap_is_three_times_the_initial_price() {
+        assert_eq!(bump_cap(gwei(2), None), gwei(6));
+        assert_eq!(bump_cap(gwei(2), Some(gwei(4))), gwei(4));
+        assert_eq!(bump_cap(U256::MAX, None), U256::MAX);
+    }
+
+    // 交易永远不上链的节点；eth_sendRawTransaction 交给 `send` 决定，节点价格为 `network` Gwei
+    fn node(network: u64, send: impl Fn(&Value) -> Reply + Send + Sync + 'static) -> MockRpc {
+        MockRpc::start(move |method, params| match method {
+            "eth_chainId" => Reply::Result(json!("0x66eee")),
+            "eth_gasPrice" => Reply::Result(json!(format!("{:#x}", gwei(network)))),
+            "eth_sendRawTransaction" => send(params),
+            _ => Reply::Result(Value::Null),
+        })
+    }
+
+    fn accept(params: &Value) -> Reply {
+        let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+        Reply::Result(json!(format!("{:?}", TxHash::from(keccak256(raw)))))
+    }
+
+    async fn bump(node: &MockRpc, auto_bump: AutoBump) -> ConfirmationOutcome {
+        let wallet: LocalWallet = PRIVATE_KEY.parse().unwrap();
+        let client = SignerMiddleware::new(node.provider(), wallet.with_chain_id(421_614u64));
+        let tx = TransactionRequest::new()
+            .to(Address::repeat_byte(0x35))
+            .value(1)
+            .gas(21_000)
+            .gas_price(gwei(1))
+            .nonce(0);
+        let first = TxHash::repeat_byte(0x11);
+        let timeout = Duration::from_millis(1);
+        let shutdown = Shutdown::new();
+        bump_until_confirmed(&client, tx, first, Duration::ZERO, timeout, auto_bump, &shutdown)
+            .await
+            .unwrap()
+    }
+
+    fn pending_hash(outcome: ConfirmationOutcome) -> TxHash {
+        match outcome {
+            ConfirmationOutcome::Pending { tx_hash, .. } => tx_hash,
+            ConfirmationOutcome::Confirmed(_) => panic!("unexpected confirmation"),
+        }
+    }
+
+    #[tokio::test]
+    async fn stops_after_max_bumps_with_the_last_hash() {
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = Arc::clone(&sent);
+        let node = node(1, move |params| {
+            let reply = accept(params);
+            if let Reply::Result(hash) = &reply {
+                log.lock().unwrap().push(hash.as_str().unwrap().to_string());
+            }
+            reply
+        });
+        let auto_bump = AutoBump { max_bumps: 2, max_gas_price: None };
+
+        let hash = pending_hash(bump(&node, auto_bump).await);
+        let sent = sent.lock().unwrap().clone();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(format!("{:?}", hash), sent[1]);
+    }
+
+    #[tokio::test]
+    async fn stops_before_exceeding_the_cap() {
+        // 1 Gwei → 1.2 Gwei → 1.44 Gwei，上限 1.3 Gwei 只允许重发一次
+        let quiet = node(0, accept);
+        let cap = Some(U256::from(13 * GWEI / 10));
+        let auto_bump = AutoBump { max_bumps: 5, max_gas_price: cap };
+        bump(&quiet, auto_bump).await;
+        assert_eq!(quiet.count("eth_sendRawTransaction"), 1);
+
+        // 网络价格已经高于默认上限（3 Gwei）时一次也不重发
+        let busy = node(4, accept);
+        let auto_bump = AutoBump { max_bumps: 5, max_gas_price: None };
+        let hash = pending_hash(bump(&busy, auto_bump).await);
+        assert_eq!(hash, TxHash::repeat_byte(0x11));
+        assert_eq!(busy.count("eth_sendRawTransaction"), 0);
+    }
+
+    #[tokio::test]
+    async fn failed_replacement_keeps_the_broadcast_hash() {
+        let node = node(1, |_| Reply::Error("replacement transaction underpriced".into()));
+        let auto_bump = AutoBump { max_bumps: 3, max_gas_price: None };
+        let hash = pending_hash(bump(&node, auto_bump).await);
+        assert_eq!(hash, TxHash::repeat_byte(0x11));
+        assert_eq!(node.count("eth_sendRawTransaction"), 1);
+    }
+
+    #[tokio::test]
+    async fn gas_price_lookup_failure_keeps_the_broadcast_hash() {
+        let node = MockRpc::start(|method, _| match method {
+            "eth_gasPrice" => Reply::Error("upstream unavailable".into()),
+            _ => Reply::Result(Value::Null),
+        });
+        let auto_bump = AutoBump { max_bumps: 3, max_gas_price: None };
+        let hash = pending_hash(bump(&node, auto_bump).await);
+        assert_eq!(hash, TxHash::repeat_byte(0x11));
+        assert_eq!(node.count("eth_sendRawTransaction"), 0);
+    }
+}
@@ -0,0 +1,214 @@
+use arbi_common::narrate;
+use arbi_common::network;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::shutdown::{Interrupted, Shutdown};
+use arbi_common::style;
+use ethers::abi::Abi;
+use ethers::prelude::*;
+use ethers::signers::LocalWallet;
+use ethers::types::{Address, TxHash, U256};
+use ethers::utils::format_units;
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{Instrument, Span, field, info, info_span, warn};
+
+use crate::get_balance;
+use crate::messages::{Msg, t, tf};
+
+// Inbox 中充值用到的 ABI 片段
+const INBOX_ABI: &str = r#"[
+    {
+        "inputs": [],
+        "name": "depositEth",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "payable",
+        "type": "function"
+    }
+]"#;
+
+/// L1 → L2 充值的结果
+pub struct DepositOutcome {
+    /// L1 上 `depositEth()` 交易的哈希
+    pub l1_tx_hash: TxHash,
+    /// 发起充值前的 L2 余额
+    pub l2_balance_before: U256,
+    /// 到账后的 L2 余额；等待超时时为 `None`
+    pub l2_balance_after: Option<U256>,
+    /// 从 L1 交易确认到 L2 到账（或超时）经过的时间
+    pub elapsed: Duration,
+}
+
+impl DepositOutcome {
+    /// 打印充值结果：L1 交易哈希和 L2 余额变化
+    pub fn print(&self) -> Result<(), Box<dyn Error>> {
+        println!("{}", tf(Msg::DepositL1TxHash, &[&style::addr(format!("{:?}", self.l1_tx_hash))]));
+        match self.l2_balance_after {
+            Some(after) => {
+                let delta = after.saturating_sub(self.l2_balance_before);
+                println!(
+                    "{}",
+                    style::ok(tf(
+                        Msg::DepositCredited,
+                        &[&self.elapsed.as_secs(), &format_units(delta, "ether")?]
+                    ))
+                );
+                println!("{}", tf(Msg::DepositL2Balance, &[&format_units(after, "ether")?]));
+            }
+            None => {
+                println!(
+                    "{}",
+                    style::warn(tf(Msg::DepositNotYetCredited, &[&self.elapsed.as_secs()]))
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 通过 L1 Inbox 合约的 `depositEth()` 把 Sepolia ETH 充值到 Arbitrum Sepolia
+///
+/// 充值消息要等 L1 交易被 Arbitrum 的排序器读到后才会在 L2 执行，通常需要 10～15 分钟。
+/// L1 交易确认后按 `poll_interval` 轮询 L2 余额，余额增加即视为到账；超过 `credit_timeout`
+/// 仍未到账时返回 `l2_balance_after = None`，资金稍后仍会到账。
+///
+/// # 参数
+/// * `l1_provider` - L1（Ethereum Sepolia）Provider
+/// * `l2_provider` - L2（Arbitrum Sepolia）Provider
+/// * `private_key` - 私钥（L1 和 L2 使用同一个地址）
+/// * `amount` - 充值金额（wei）
+/// * `credit_timeout` - 等待 L2 到账的最长时间
+/// * `poll_interval` - 轮询 L2 余额的间隔
+/// * `shutdown` - Ctrl-C 关闭信号
+///
+/// # 返回
+/// * `Result<DepositOutcome, Box<dyn Error>>` - 充值结果
+pub async fn deposit_eth(
+    l1_provider: &RpcProvider,
+    l2_provider: &RpcProvider,
+    private_key: &str,
+    amount: U256,
+    credit_timeout: Duration,
+    poll_interval: Duration,
+    shutdown: &Shutdown,
+) -> Result<DepositOutcome, Box<dyn Error>> {
+    // 1. 连接 L1，创建签名客户端
+    narrate!("{}", t(Msg::DepositStepConnect));
+    let chain_id = l1_provider
+        .get_chainid()
+        .instrument(info_span!("connect"))
+        .await?;
+    Span::current().record("chain_id", chain_id.as_u64());
+    narrate!("{}", style::ok(tf(Msg::Connected, &[&chain_id])));
+    network::check_l1_chain_id(chain_id.as_u64())?;
+
+    let wallet: LocalWallet = private_key.parse()?;
+    let address = wallet.address();
+    let client = Arc::new(SignerMiddleware::new(
+        l1_provider.clone(),
+        network::signing_wallet(wallet, chain_id.as_u64())?,
+    ));
+    narrate!("{}", style::ok(tf(Msg::FromAddress, &[&style::addr(address)])));
+
+    // 2. 构建 depositEth() 调用并预估 L1 Gas
+    let inbox_address = network::current().inbox_address();
+    let inbox = Address::from_str(inbox_address)?;
+    let abi: Abi = serde_json::from_str(INBOX_ABI)?;
+    let contract = Contract::new(inbox, abi, client);
+    let call = contract.method::<_, U256>("depositEth", ())?.value(amount);
+
+    narrate!("\n{}", t(Msg::DepositStepCheck));
+    let (gas_limit, gas_price) = async {
+        let gas_limit = call.estimate_gas().await?;
+        let gas_price = l1_provider.get_gas_price().await?;
+        Ok::<_, Box<dyn Error>>((gas_limit, gas_price))
+    }
+    .instrument(info_span!("estimate"))
+    .await?;
+    let gas_fee = gas_limit * gas_price;
+
+    // 3. L1 余额必须覆盖充值金额 + L1 Gas 费
+    let l1_balance = get_balance(l1_provider, address).await?;
+    let required = amount + gas_fee;
+    let l1_balance_eth = format_units(l1_balance, "ether")?;
+    let amount_eth = format_units(amount, "ether")?;
+    let gas_fee_eth = format_units(gas_fee, "ether")?;
+    if l1_balance < required {
+        return Err(crate::messages::insufficient_balance(
+            &format_units(required, "ether")?,
+            &amount_eth,
+            &gas_fee_eth,
+            &l1_balance_eth,
+        )
+        .into());
+    }
+    narrate!("{}", style::ok(tf(Msg::DepositL1Balance, &[&l1_balance_eth])));
+    narrate!("{}", style::ok(tf(Msg::EstimatedFee, &[&gas_fee_eth])));
+
+    // 记录充值前的 L2 余额，之后按余额变化判断是否到账
+    let l2_balance_before = get_balance(l2_provider, address).await?;
+    narrate!(
+        "{}",
+        style::ok(tf(Msg::DepositL2Before, &[&format_units(l2_balance_before, "ether")?]))
+    );
+
+    // 4. 在 L1 上发送 depositEth()
+    narrate!("\n{}", tf(Msg::DepositStepSend, &[&amount_eth]));
+    let pending_tx = call
+        .send()
+        .instrument(info_span!("broadcast", inbox = inbox_address, value_wei = %amount))
+        .await?;
+    let l1_tx_hash = pending_tx.tx_hash();
+    Span::current().record("tx_hash", field::debug(l1_tx_hash));
+    info!("deposit broadcast on L1");
+    narrate!("{}", style::ok(tf(Msg::TxHashLine, &[&style::addr(format!("{:?}", l1_tx_hash))])));
+
+    let receipt = tokio::select! {
+        receipt = pending_tx.instrument(info_span!("confirm")) => receipt?,
+        _ = shutdown.wait() => return Err(Interrupted.into()),
+    };
+    match receipt {
+        Some(receipt) if receipt.status == Some(1.into()) => {
+            narrate!(
+                "{}",
+                style::ok(tf(Msg::DepositL1Confirmed, &[&format!("{:?}", receipt.block_number)]))
+            );
+        }
+        Some(_) => return Err(t(Msg::DepositL1Reverted).into()),
+        None => return Err(t(Msg::DepositL1NoReceipt).into()),
+    }
+
+    // 5. 轮询 L2 余额，直到到账或超时
+    narrate!("\n{}", t(Msg::DepositStepWait));
+    narrate!("{}", style::warn(t(Msg::DepositDelayNote)));
+    let started = Instant::now();
+    let l2_balance_after = async {
+        loop {
+            let balance = get_balance(l2_provider, address).await?;
+            if balance > l2_balance_before {
+                info!(elapsed_secs = started.elapsed().as_secs(), "deposit credited on L2");
+                return Ok::<_, Box<dyn Error>>(Some(balance));
+            }
+            if started.elapsed() >= credit_timeout {
+                warn!("deposit not credited before timeout");
+                return Ok(None);
+            }
+            narrate!("{}", tf(Msg::DepositWaiting, &[&started.elapsed().as_secs()]));
+
+            tokio::select! {
+                _ = tokio::time::sleep(poll_interval) => {}
+                _ = shutdown.wait() => return Err(Interrupted.into()),
+            }
+        }
+    }
+    .instrument(info_span!("credit"))
+    .await?;
+
+    Ok(DepositOutcome {
+        l1_tx_hash,
+        l2_balance_before,
+        l2_balance_after,
+        elapsed: started.elapsed(),
+    })
+}
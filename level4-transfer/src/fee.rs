@@ -0,0 +1,231 @@
+use arbi_common::rpc::RpcProvider;
+use ethers::abi::Abi;
+use ethers::prelude::*;
+use ethers::types::{Address, Bytes, U256};
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::Arc;
+
+// NodeInterface 虚拟合约地址，只能通过 eth_call 调用，链上并没有实际部署代码
+pub(crate) const NODE_INTERFACE_ADDRESS: &str = "0x00000000000000000000000000000000000000C8";
+
+const NODE_INTERFACE_ABI: &str = r#"[
+    {
+        "inputs": [
+            {"name": "to", "type": "address"},
+            {"name": "contractCreation", "type": "bool"},
+            {"name": "data", "type": "bytes"}
+        ],
+        "name": "gasEstimateComponents",
+        "outputs": [
+            {"name": "gasEstimate", "type": "uint64"},
+            {"name": "gasEstimateForL1", "type": "uint64"},
+            {"name": "baseFee", "type": "uint256"},
+            {"name": "l1BaseFeeEstimate", "type": "uint256"}
+        ],
+        "stateMutability": "payable",
+        "type": "function"
+    }
+]"#;
+
+/// NodeInterface `gasEstimateComponents` 的返回值
+#[derive(Clone, Copy, Debug)]
+pub struct GasComponents {
+    /// 总 Gas 估算（已包含 L1 数据费折算出的 Gas）
+    pub gas_estimate: u64,
+    /// 其中用于支付 L1 数据费的部分
+    pub gas_estimate_for_l1: u64,
+    /// 当前 L2 基础费（wei）
+    pub base_fee: U256,
+}
+
+/// Gas 费估算来源
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeeSource {
+    NodeInterface,
+    /// 预编译调用失败（如不带 Arbitrum 扩展的 anvil），退回固定 Gas 限额
+    Heuristic,
+}
+
+/// 一笔交易的 Gas 费估算结果
+#[derive(Clone, Copy, Debug)]
+pub struct FeeEstimate {
+    pub gas_limit: U256,
+    /// L1 数据费折算出的 Gas，退回固定限额时没有这一项
+    pub l1_gas: Option<U256>,
+    pub gas_price: U256,
+    pub total: U256,
+    pub source: FeeSource,
+}
+
+/// 根据 NodeInterface 的返回值计算 Gas 费，没有返回值时退回固定 Gas 限额
+///
+/// Arbitrum 的 L1 数据费是按 calldata 大小和 L1 基础费算的，不随 L2 Gas 价格线性变化，
+/// NodeInterface 会把它折算成 L2 Gas 算进 `gas_estimate`，所以总费用仍是 Gas × Gas 价格。
+/// 出价低于 L2 基础费的交易不会被打包，这里取两者中较高的一个。
+///
+/// # 参数
+/// * `components` - NodeInterface 的返回值，调用失败时为 `None`
+/// * `gas_price` - 节点给出的 Gas 价格（wei）
+/// * `fallback_gas_limit` - 退回时使用的固定 Gas 限额
+///
+/// # 返回
+/// * `FeeEstimate` - Gas 限额、L1 部分和总费用
+pub fn select_fee(
+    components: Option<GasComponents>,
+    gas_price: U256,
+    fallback_gas_limit: u64,
+) -> FeeEstimate {
+    match components {
+        Some(components) => {
+            let gas_price = gas_price.max(components.base_fee);
+            let gas_limit = U256::from(components.gas_estimate);
+            FeeEstimate {
+                gas_limit,
+                l1_gas: Some(U256::from(components.gas_estimate_for_l1)),
+                gas_price,
+                total: gas_limit * gas_price,
+                source: FeeSource::NodeInterface,
+            }
+        }
+        None => {
+            let gas_limit = U256::from(fallback_gas_limit);
+            FeeEstimate {
+                gas_limit,
+                l1_gas: None,
+                gas_price,
+                total: gas_limit * gas_price,
+                source: FeeSource::Heuristic,
+            }
+        }
+    }
+}
+
+/// 调用 NodeInterface 估算一笔交易的 Gas 组成
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `from` - 发送地址（余额检查按这个地址做）
+/// * `to` - 接收地址
+/// * `value` - 转账金额（wei）
+/// * `data` - 交易 calldata
+///
+/// # 返回
+/// * `Result<GasComponents, Box<dyn Error>>` - 总 Gas、L1 部分和基础费
+pub async fn gas_estimate_components(
+    provider: &RpcProvider,
+    from: Address,
+    to: Address,
+    value: U256,
+    data: Bytes,
+) -> Result<GasComponents, Box<dyn Error>> {
+    let node_interface = Contract::new(
+        Address::from_str(NODE_INTERFACE_ADDRESS)?,
+        serde_json::from_str::<Abi>(NODE_INTERFACE_ABI)?,
+        Arc::new(provider.clone()),
+    );
+
+    let (gas_estimate, gas_estimate_for_l1, base_fee, _l1_base_fee): (u64, u64, U256, U256) =
+        node_interface
+            .method("gasEstimateComponents", (to, false, data))?
+            .from(from)
+            .value(value)
+            .call()
+            .await?;
+
+    Ok(GasComponents {
+        gas_estimate,
+        gas_estimate_for_l1,
+        base_fee,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use serde_json::json;
+
+    const GWEI: u64 = 1_000_000_000;
+
+    fn components(base_fee_gwei: u64) -> GasComponents {
+        GasComponents {
+            gas_estimate: 410_000,
+            gas_estimate_for_l1: 389_000,
+            base_fee: U256::from(base_fee_gwei * GWEI / 100),
+        }
+    }
+
+    #[test]
+    fn node_interface_estimate_uses_its_gas_and_the_base_fee_floor() {
+        // 节点价格高于基础费：按节点价格
+        let estimate = select_fee(Some(components(1)), U256::from(GWEI / 10), 300_000);
+        assert_eq!(estimate.source, FeeSource::NodeInterface);
+        assert_eq!(estimate.gas_limit, U256::from(410_000));
+        assert_eq!(estimate.l1_gas, Some(U256::from(389_000)));
+        assert_eq!(estimate.gas_price, U256::from(GWEI / 10));
+        assert_eq!(estimate.total, U256::from(410_000 * GWEI / 10));
+
+        // 节点价格低于基础费：按基础费
+        let estimate = select_fee(Some(components(50)), U256::from(GWEI / 10), 300_000);
+        assert_eq!(estimate.gas_price, U256::from(GWEI / 2));
+        assert_eq!(estimate.total, U256::from(410_000 * GWEI / 2));
+    }
+
+    #[test]
+    fn missing_components_fall_back_to_the_fixed_limit() {
+        let estimate = select_fee(None, U256::from(GWEI / 10), 300_000);
+        assert_eq!(estimate.source, FeeSource::Heuristic);
+        assert_eq!(estimate.gas_limit, U256::from(300_000));
+        assert_eq!(estimate.l1_gas, None);
+        assert_eq!(estimate.total, U256::from(300_000 * GWEI / 10));
+    }
+
+    #[tokio::test]
+    async fn decodes_gas_estimate_components() {
+        // (gasEstimate, gasEstimateForL1, baseFee, l1BaseFeeEstimate)
+        let output = format!(
+            "0x{:064x}{:064x}{:064x}{:064x}",
+            410_000,
+            389_000,
+            GWEI / 100,
+            30 * GWEI
+        );
+        let rpc = MockRpc::start(move |method, _| match method {
+            "eth_call" => Reply::Result(json!(output)),
+            _ => Reply::Error(format!("unexpected {}", method)),
+        });
+        let (from, to) = (Address::repeat_byte(0x11), Address::repeat_byte(0x22));
+        let value = U256::exp10(15);
+        let components = gas_estimate_components(&rpc.provider(), from, to, value, Bytes::new())
+            .await
+            .unwrap();
+        assert_eq!(components.gas_estimate, 410_000);
+        assert_eq!(components.gas_estimate_for_l1, 389_000);
+        assert_eq!(components.base_fee, U256::from(GWEI / 100));
+
+        // 以发送方的身份、带着转账金额调用 NodeInterface
+        let (_, params) = rpc.requests().pop().unwrap();
+        let call = &params[0];
+        assert_eq!(call["to"], json!(NODE_INTERFACE_ADDRESS.to_lowercase()));
+        assert_eq!(call["from"], json!(format!("{:?}", from)));
+        assert_eq!(call["value"], json!(format!("{:#x}", value)));
+    }
+
+    // 没有 Arbitrum 扩展的节点（如 anvil）调用预编译会失败，交给调用方退回固定限额
+    #[tokio::test]
+    async fn node_without_precompile_is_an_error() {
+        let rpc = MockRpc::start(|_, _| Reply::Result(json!("0x")));
+        let result = gas_estimate_components(
+            &rpc.provider(),
+            Address::repeat_byte(0x11),
+            Address::repeat_byte(0x22),
+            U256::zero(),
+            Bytes::new(),
+        )
+        .await;
+        assert!(result.is_err());
+        let estimate = select_fee(result.ok(), U256::from(GWEI / 10), 300_000);
+        assert_eq!(estimate.source, FeeSource::Heuristic);
+    }
+}
@@ -0,0 +1,524 @@
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::abi::{Abi, RawLog, Token};
+use ethers::providers::Middleware;
+use ethers::types::{Address, Bytes, H256, TransactionReceipt, TxHash, U256};
+use ethers::utils::{format_units, keccak256, rlp};
+use std::error::Error;
+use std::str::FromStr;
+
+use crate::messages::{Msg, t, tf};
+use crate::retryable::{self, RetryableParams, TicketStatus};
+
+// ArbRetryableTx 预编译合约地址，ticket 创建成功后由它发出 RedeemScheduled 事件
+const ARB_RETRYABLE_TX_ADDRESS: &str = "0x000000000000000000000000000000000000006E";
+
+// Bridge 消息类型：retryable ticket 和 ETH 充值
+const L1_MESSAGE_TYPE_SUBMIT_RETRYABLE: u8 = 9;
+const L1_MESSAGE_TYPE_ETH_DEPOSIT: u8 = 12;
+
+// ArbitrumDepositTx 的交易类型前缀，ETH 充值在 L2 上的交易哈希就是这种交易的哈希
+const DEPOSIT_TX_TYPE: u8 = 0x64;
+
+// Inbox 发出的消息原文事件和 ArbRetryableTx 的自动 redeem 事件
+const MESSAGE_EVENTS_ABI: &str = r#"[
+    {
+        "anonymous": false,
+        "inputs": [
+            {"indexed": true, "name": "messageNum", "type": "uint256"},
+            {"indexed": false, "name": "data", "type": "bytes"}
+        ],
+        "name": "InboxMessageDelivered",
+        "type": "event"
+    },
+    {
+        "anonymous": false,
+        "inputs": [
+            {"indexed": true, "name": "ticketId", "type": "bytes32"},
+            {"indexed": true, "name": "retryTxHash", "type": "bytes32"},
+            {"indexed": true, "name": "sequenceNum", "type": "uint64"},
+            {"indexed": false, "name": "donatedGas", "type": "uint64"},
+            {"indexed": false, "name": "gasDonor", "type": "address"},
+            {"indexed": false, "name": "maxRefund", "type": "uint256"},
+            {"indexed": false, "name": "submissionFeeRefund", "type": "uint256"}
+        ],
+        "name": "RedeemScheduled",
+        "type": "event"
+    }
+]"#;
+
+/// 从 L1 消息中得到的 L2 交易
+pub enum L2Message {
+    /// ETH 充值，`tx_hash` 是 L2 上的充值交易哈希
+    EthDeposit {
+        tx_hash: TxHash,
+        to: Address,
+        value: U256,
+    },
+    /// retryable ticket，ticket ID 同时也是 L2 上创建 ticket 的交易哈希
+    Retryable { ticket_id: H256 },
+}
+
+/// 跨链消息当前所处的阶段
+pub enum MessageState {
+    /// L2 上还查不到对应交易，排序器通常 10～15 分钟后才会读到 L1 消息
+    NotYetOnL2,
+    /// ETH 已到账
+    Credited,
+    /// 创建 ticket 时自动 redeem 成功
+    AutoRedeemed { redeem_tx: TxHash },
+    /// 自动 redeem 失败（或没有执行），等待手动 redeem
+    AwaitingRedeem {
+        failed_redeem_tx: Option<TxHash>,
+        timeout: u64,
+    },
+    /// 自动 redeem 失败后已被手动 redeem
+    ManuallyRedeemed,
+    /// 超过有效期未被 redeem
+    Expired,
+}
+
+/// 一条 L1 → L2 消息的完整状态
+pub struct MessageReport {
+    pub message_number: U256,
+    pub message: L2Message,
+    pub state: MessageState,
+    /// L1 消息进入 Bridge 的时间（Unix 秒）
+    pub l1_timestamp: u64,
+    /// L2 交易（充值交易或创建 ticket 的交易）所在区块的时间
+    pub l2_timestamp: Option<u64>,
+    /// 自动 redeem 交易所在区块的时间
+    pub redeem_timestamp: Option<u64>,
+}
+
+/// 计算 ETH 充值在 L2 上的交易哈希
+///
+/// 哈希是 ArbitrumDepositTx 的 `keccak256(0x64 || rlp([chainId, requestId, from, to, value]))`，
+/// 其中 requestId 是补齐到 32 字节的消息序号，与 Arbitrum SDK 的 `calculateDepositTxId` 一致。
+///
+/// # 参数
+/// * `l2_chain_id` - L2 链 ID
+/// * `message_number` - Bridge 分配的消息序号
+/// * `from` - `MessageDelivered` 事件中的 sender
+/// * `to` - L2 收款地址
+/// * `value` - 充值金额（wei）
+///
+/// # 返回
+/// * `TxHash` - L2 充值交易哈希
+pub fn deposit_tx_hash(
+    l2_chain_id: U256,
+    message_number: U256,
+    from: Address,
+    to: Address,
+    value: U256,
+) -> TxHash {
+    let mut request_id = [0u8; 32];
+    message_number.to_big_endian(&mut request_id);
+
+    let mut stream = rlp::RlpStream::new_list(5);
+    stream.append(&l2_chain_id);
+    stream.append(&request_id.as_slice());
+    stream.append(&from);
+    stream.append(&to);
+    stream.append(&value);
+
+    let mut encoded = vec![DEPOSIT_TX_TYPE];
+    encoded.extend_from_slice(&stream.out());
+    H256::from(keccak256(encoded))
+}
+
+/// 解析 ETH 充值消息：`to`（20 字节）+ `value`（32 字节）
+///
+/// # 参数
+/// * `data` - `InboxMessageDelivered` 事件中的消息原文
+///
+/// # 返回
+/// * `Result<(Address, U256), Box<dyn Error>>` - 收款地址和金额
+pub fn parse_eth_deposit(data: &[u8]) -> Result<(Address, U256), Box<dyn Error>> {
+    if data.len() != 52 {
+        return Err(t(Msg::MessageBadData).into());
+    }
+    Ok((Address::from_slice(&data[..20]), U256::from_big_endian(&data[20..])))
+}
+
+/// retryable 消息原文中 ticket ID 需要、但 `RetryableParams` 里没有的字段
+pub struct RetryableMessage {
+    pub params: RetryableParams,
+    pub deposit_value: U256,
+    pub max_submission_cost: U256,
+    pub fee_refund_address: Address,
+    pub beneficiary: Address,
+}
+
+/// 解析 retryable 消息原文
+///
+/// Inbox 用 `abi.encodePacked` 依次写入 9 个 32 字节字段再接上 calldata：to、l2CallValue、
+/// 附带的 ETH、maxSubmissionCost、excessFeeRefundAddress、callValueRefundAddress、gasLimit、
+/// maxFeePerGas、calldata 长度。
+///
+/// # 参数
+/// * `data` - `InboxMessageDelivered` 事件中的消息原文
+///
+/// # 返回
+/// * `Result<RetryableMessage, Box<dyn Error>>` - 解析结果；长度不对时返回错误
+pub fn parse_retryable(data: &[u8]) -> Result<RetryableMessage, Box<dyn Error>> {
+    const HEAD: usize = 9 * 32;
+    if data.len() < HEAD {
+        return Err(t(Msg::MessageBadData).into());
+    }
+    let word = |i: usize| U256::from_big_endian(&data[i * 32..(i + 1) * 32]);
+    let address = |i: usize| Address::from_slice(&data[i * 32 + 12..(i + 1) * 32]);
+
+    let data_length = word(8);
+    if data_length != U256::from(data.len() - HEAD) {
+        return Err(t(Msg::MessageBadData).into());
+    }
+
+    Ok(RetryableMessage {
+        params: RetryableParams {
+            to: address(0),
+            l2_call_value: word(1),
+            gas_limit: word(6),
+            max_fee_per_gas: word(7),
+            data: Bytes::from(data[HEAD..].to_vec()),
+        },
+        deposit_value: word(2),
+        max_submission_cost: word(3),
+        fee_refund_address: address(4),
+        beneficiary: address(5),
+    })
+}
+
+/// 从 L1 收据中取出指定消息序号的 `InboxMessageDelivered` 消息原文
+fn inbox_message_data(
+    receipt: &TransactionReceipt,
+    message_number: U256,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let abi: Abi = serde_json::from_str(MESSAGE_EVENTS_ABI)?;
+    let event = abi.event("InboxMessageDelivered")?;
+    let signature = event.signature();
+
+    for log in &receipt.logs {
+        if log.topics.first() != Some(&signature) {
+            continue;
+        }
+        let parsed = event.parse_log(RawLog {
+            topics: log.topics.clone(),
+            data: log.data.to_vec(),
+        })?;
+        let mut number = None;
+        let mut data = None;
+        for param in parsed.params {
+            match (param.name.as_str(), param.value) {
+                ("messageNum", Token::Uint(n)) => number = Some(n),
+                ("data", Token::Bytes(bytes)) => data = Some(bytes),
+                _ => {}
+            }
+        }
+        if number == Some(message_number)
+            && let Some(data) = data
+        {
+            return Ok(data);
+        }
+    }
+    Err(t(Msg::MessageNoInboxData).into())
+}
+
+/// 从创建 ticket 的 L2 收据中找出自动 redeem 交易的哈希
+fn scheduled_redeem(
+    receipt: &TransactionReceipt,
+    ticket_id: H256,
+) -> Result<Option<TxHash>, Box<dyn Error>> {
+    let abi: Abi = serde_json::from_str(MESSAGE_EVENTS_ABI)?;
+    let signature = abi.event("RedeemScheduled")?.signature();
+    let precompile = Address::from_str(ARB_RETRYABLE_TX_ADDRESS)?;
+
+    Ok(receipt
+        .logs
+        .iter()
+        .find(|log| {
+            log.address == precompile
+                && log.topics.first() == Some(&signature)
+                && log.topics.get(1) == Some(&ticket_id)
+        })
+        .and_then(|log| log.topics.get(2).copied()))
+}
+
+/// 读取交易所在区块的时间戳
+async fn block_timestamp(
+    provider: &RpcProvider,
+    receipt: &TransactionReceipt,
+) -> Result<Option<u64>, Box<dyn Error>> {
+    let Some(number) = receipt.block_number else {
+        return Ok(None);
+    };
+    Ok(provider
+        .get_block(number)
+        .await?
+        .map(|block| block.timestamp.as_u64()))
+}
+
+/// 跟踪一笔 L1 充值 / retryable 交易在 L2 上的状态
+///
+/// 先从 L1 收据的 `MessageDelivered` 和 `InboxMessageDelivered` 事件得到消息序号和原文，
+/// 算出 L2 交易哈希（ETH 充值）或 ticket ID（retryable），再到 L2 上查询。
+///
+/// # 参数
+/// * `l1_provider` - L1 Provider
+/// * `l2_provider` - L2 Provider
+/// * `l1_tx_hash` - L1 交易哈希
+///
+/// # 返回
+/// * `Result<MessageReport, Box<dyn Error>>` - 消息状态和各阶段时间
+pub async fn message_status(
+    l1_provider: &RpcProvider,
+    l2_provider: &RpcProvider,
+    l1_tx_hash: TxHash,
+) -> Result<MessageReport, Box<dyn Error>> {
+    let receipt = l1_provider
+        .get_transaction_receipt(l1_tx_hash)
+        .await?
+        .ok_or_else(|| tf(Msg::MessageL1NotFound, &[&format!("{:?}", l1_tx_hash)]))?;
+    let delivered = retryable::delivered_message(&receipt)?;
+    let data = inbox_message_data(&receipt, delivered.message_number)?;
+    let l2_chain_id = l2_provider.get_chainid().await?;
+
+    let message = match delivered.kind {
+        L1_MESSAGE_TYPE_ETH_DEPOSIT => {
+            let (to, value) = parse_eth_deposit(&data)?;
+            L2Message::EthDeposit {
+                tx_hash: deposit_tx_hash(
+                    l2_chain_id,
+                    delivered.message_number,
+                    delivered.sender,
+                    to,
+                    value,
+                ),
+                to,
+                value,
+            }
+        }
+        L1_MESSAGE_TYPE_SUBMIT_RETRYABLE => {
+            let retryable = parse_retryable(&data)?;
+            L2Message::Retryable {
+                ticket_id: retryable::ticket_id(
+                    l2_chain_id,
+                    delivered.message_number,
+                    delivered.sender,
+                    delivered.l1_base_fee,
+                    retryable.deposit_value,
+                    retryable.max_submission_cost,
+                    retryable.fee_refund_address,
+                    retryable.beneficiary,
+                    &retryable.params,
+                ),
+            }
+        }
+        kind => return Err(tf(Msg::MessageUnsupportedKind, &[&kind]).into()),
+    };
+
+    let l2_hash = match &message {
+        L2Message::EthDeposit { tx_hash, .. } => *tx_hash,
+        L2Message::Retryable { ticket_id } => *ticket_id,
+    };
+    let mut report = MessageReport {
+        message_number: delivered.message_number,
+        message,
+        state: MessageState::NotYetOnL2,
+        l1_timestamp: delivered.timestamp,
+        l2_timestamp: None,
+        redeem_timestamp: None,
+    };
+
+    let Some(l2_receipt) = l2_provider.get_transaction_receipt(l2_hash).await? else {
+        return Ok(report);
+    };
+    report.l2_timestamp = block_timestamp(l2_provider, &l2_receipt).await?;
+
+    let L2Message::Retryable { ticket_id } = report.message else {
+        report.state = MessageState::Credited;
+        return Ok(report);
+    };
+
+    // 创建 ticket 的收据里有 RedeemScheduled 事件时，说明排序器尝试过自动 redeem
+    let redeem_receipt = match scheduled_redeem(&l2_receipt, ticket_id)? {
+        Some(redeem_tx) => l2_provider.get_transaction_receipt(redeem_tx).await?,
+        None => None,
+    };
+    if let Some(redeem_receipt) = &redeem_receipt
+        && redeem_receipt.status == Some(1.into())
+    {
+        report.redeem_timestamp = block_timestamp(l2_provider, redeem_receipt).await?;
+        report.state = MessageState::AutoRedeemed {
+            redeem_tx: redeem_receipt.transaction_hash,
+        };
+        return Ok(report);
+    }
+
+    report.state = match retryable::ticket_status(l2_provider, ticket_id).await? {
+        TicketStatus::Pending { timeout } => MessageState::AwaitingRedeem {
+            failed_redeem_tx: redeem_receipt.map(|r| r.transaction_hash),
+            timeout,
+        },
+        TicketStatus::Redeemed => MessageState::ManuallyRedeemed,
+        TicketStatus::Expired => MessageState::Expired,
+        TicketStatus::NotFound => MessageState::NotYetOnL2,
+    };
+    Ok(report)
+}
+
+impl MessageReport {
+    /// 打印消息信息、状态和各阶段的时间
+    pub fn print(&self) -> Result<(), Box<dyn Error>> {
+        println!("{}", tf(Msg::MessageNumber, &[&self.message_number]));
+        match &self.message {
+            L2Message::EthDeposit { tx_hash, to, value } => {
+                println!(
+                    "{}",
+                    tf(
+                        Msg::MessageEthDeposit,
+                        &[&format_units(*value, "ether")?, &style::addr(to)]
+                    )
+                );
+                println!("{}", tf(Msg::MessageL2TxHash, &[&style::addr(format!("{:?}", tx_hash))]));
+            }
+            L2Message::Retryable { ticket_id } => {
+                println!(
+                    "{}",
+                    tf(Msg::RetryableTicketId, &[&style::addr(format!("{:?}", ticket_id))])
+                );
+            }
+        }
+
+        println!("\n{}", tf(Msg::MessageHopL1, &[&self.l1_timestamp]));
+        if let Some(at) = self.l2_timestamp {
+            println!(
+                "{}",
+                tf(Msg::MessageHopL2, &[&at, &at.saturating_sub(self.l1_timestamp)])
+            );
+        }
+        if let Some(at) = self.redeem_timestamp {
+            println!("{}", tf(Msg::MessageHopRedeem, &[&at]));
+        }
+        println!();
+
+        match &self.state {
+            MessageState::NotYetOnL2 => println!("{}", style::warn(t(Msg::MessageNotYetOnL2))),
+            MessageState::Credited => println!("{}", style::ok(t(Msg::MessageCredited))),
+            MessageState::AutoRedeemed { redeem_tx } => println!(
+                "{}",
+                style::ok(tf(
+                    Msg::MessageAutoRedeemed,
+                    &[&style::addr(format!("{:?}", redeem_tx))]
+                ))
+            ),
+            MessageState::AwaitingRedeem {
+                failed_redeem_tx,
+                timeout,
+            } => {
+                if let Some(tx) = failed_redeem_tx {
+                    println!(
+                        "{}",
+                        style::err(tf(
+                            Msg::MessageAutoRedeemFailed,
+                            &[&style::addr(format!("{:?}", tx))]
+                        ))
+                    );
+                }
+                println!("{}", style::warn(tf(Msg::MessageAwaitingRedeem, &[&timeout])));
+            }
+            MessageState::ManuallyRedeemed => {
+                println!("{}", style::ok(t(Msg::MessageManuallyRedeemed)))
+            }
+            MessageState::Expired => println!("{}", style::err(t(Msg::TicketExpired))),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Inbox.depositEth 写入的消息原文：收款地址 + 0.01 ETH
+    const ETH_DEPOSIT_DATA: &str = concat!(
+        "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+        "000000000000000000000000000000000000000000000000002386f26fc10000",
+    );
+
+    // Inbox.createRetryableTicket 写入的消息原文：9 个 32 字节字段后接 4 字节 calldata
+    const RETRYABLE_DATA: &str = concat!(
+        "0000000000000000000000002222222222222222222222222222222222222222", // to
+        "00000000000000000000000000000000000000000000000000005af3107a4000", // l2CallValue
+        "000000000000000000000000000000000000000000000000002386f26fc10000", // deposit
+        "0000000000000000000000000000000000000000000000000000000000001000", // maxSubmissionCost
+        "0000000000000000000000004444444444444444444444444444444444444444", // excessFeeRefund
+        "0000000000000000000000003333333333333333333333333333333333333333", // callValueRefund
+        "00000000000000000000000000000000000000000000000000000000000186a0", // gasLimit
+        "0000000000000000000000000000000000000000000000000000000005f5e100", // maxFeePerGas
+        "0000000000000000000000000000000000000000000000000000000000000004", // data length
+        "d09de08a",                                                         // calldata
+    );
+
+    fn decode(data: &str) -> Vec<u8> {
+        ethers::utils::hex::decode(data).unwrap()
+    }
+
+    #[test]
+    fn parses_eth_deposit() {
+        let (to, value) = parse_eth_deposit(&decode(ETH_DEPOSIT_DATA)).unwrap();
+        let expected_to = Address::from_str("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").unwrap();
+        assert_eq!(to, expected_to);
+        assert_eq!(value, U256::exp10(16));
+
+        let data = decode(ETH_DEPOSIT_DATA);
+        assert!(parse_eth_deposit(&data[..51]).is_err());
+        assert!(parse_eth_deposit(&[data.as_slice(), &[0]].concat()).is_err());
+    }
+
+    #[test]
+    fn parses_retryable() {
+        let message = parse_retryable(&decode(RETRYABLE_DATA)).unwrap();
+        assert_eq!(message.params.to, Address::repeat_byte(0x22));
+        assert_eq!(message.params.l2_call_value, U256::exp10(14));
+        assert_eq!(message.params.gas_limit, U256::from(100_000));
+        assert_eq!(message.params.max_fee_per_gas, U256::exp10(8));
+        assert_eq!(message.params.data.as_ref(), [0xd0, 0x9d, 0xe0, 0x8a]);
+        assert_eq!(message.deposit_value, U256::exp10(16));
+        assert_eq!(message.max_submission_cost, U256::from(0x1000));
+        assert_eq!(message.fee_refund_address, Address::repeat_byte(0x44));
+        assert_eq!(message.beneficiary, Address::repeat_byte(0x33));
+    }
+
+    #[test]
+    fn rejects_retryable_with_wrong_length() {
+        let data = decode(RETRYABLE_DATA);
+        // calldata 比声明的长度短或长，以及连 9 个字段都不完整
+        assert!(parse_retryable(&data[..data.len() - 1]).is_err());
+        assert!(parse_retryable(&[data.as_slice(), &[0]].concat()).is_err());
+        assert!(parse_retryable(&data[..9 * 32 - 1]).is_err());
+    }
+
+    // 按 ArbitrumDepositTx 的字段顺序逐个写出 RLP 编码，与 deposit_tx_hash 的结果对照
+    #[test]
+    fn deposit_tx_hash_hashes_deposit_tx() {
+        let (to, value) = parse_eth_deposit(&decode(ETH_DEPOSIT_DATA)).unwrap();
+        let from = Address::repeat_byte(0x11);
+        let hash = deposit_tx_hash(U256::from(42_161), U256::from(1), from, to, value);
+
+        let preimage = decode(concat!(
+            "64",
+            "f856",
+            "82a4b1", // chainId
+            "a00000000000000000000000000000000000000000000000000000000000000001", // requestId
+            "941111111111111111111111111111111111111111", // from
+            "945aaeb6053f3e94c9b9a09f33669435e7ef1beaed", // to
+            "872386f26fc10000", // value
+        ));
+        assert_eq!(hash, H256::from(keccak256(preimage)));
+
+        // 消息序号不同，哈希也不同
+        let next = deposit_tx_hash(U256::from(42_161), U256::from(2), from, to, value);
+        assert_ne!(next, hash);
+    }
+}
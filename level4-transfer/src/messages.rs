@@ -0,0 +1,453 @@
+//! level4 输出的消息目录
+//!
+//! 关键流程的消息另外提供带类型参数的函数，调用方不用关心模板里占位符的顺序。
+
+use arbi_common::network;
+use ethers::types::TxHash;
+
+arbi_common::define_messages! {
+    Title => ("=== Arbitrum 测试网 ETH 转账工具 ===", "=== Arbitrum Testnet ETH Transfer Tool ==="),
+    MissingKey => ("错误: 未找到 PRIVATE_KEY 环境变量！", "Error: PRIVATE_KEY environment variable not found!"),
+    MissingKeyHelp => ("请通过以下方式之一设置私钥:", "Set the private key in one of the following ways:"),
+    MissingKeyDotenv => ("1. 创建 .env 文件，添加: PRIVATE_KEY=your_private_key_here", "1. Create a .env file containing: PRIVATE_KEY=your_private_key_here"),
+    MissingKeyWindows => ("2. 在命令行设置: set PRIVATE_KEY=your_private_key_here (Windows)", "2. Set it in the shell: set PRIVATE_KEY=your_private_key_here (Windows)"),
+    MissingKeyUnix => ("3. 在命令行设置: export PRIVATE_KEY=your_private_key_here (Unix/Linux/Mac)", "3. Set it in the shell: export PRIVATE_KEY=your_private_key_here (Unix/Linux/Mac)"),
+    HardcodedKeyWarning => ("⚠ 警告: 请勿将私钥硬编码在代码中！", "⚠ Warning: never hard-code private keys in source code!"),
+
+    TransferStart => ("=== 开始转账流程 ===", "=== Starting transfer ==="),
+    StepConnect => ("1. 连接到 Arbitrum Sepolia 测试网...", "1. Connecting to Arbitrum Sepolia testnet..."),
+    Connected => ("✓ 连接成功（Chain ID: {}）", "✓ Connected (chain ID: {})"),
+    StepLoadWallet => ("2. 加载钱包...", "2. Loading wallet..."),
+    FromAddress => ("✓ 发送地址: {}", "✓ Sender: {}"),
+    StepValidateRecipient => ("3. 验证接收地址...", "3. Validating recipient..."),
+    ToAddress => ("✓ 接收地址: {}", "✓ Recipient: {}"),
+    RecipientAllowed => ("✓ 接收地址在收款白名单中", "✓ Recipient is on the allowlist"),
+    RecipientNotAllowed => (
+        "接收地址 {} 不在收款白名单中（{} 或 --allow）",
+        "Recipient {} is not on the allowlist ({} or --allow)"
+    ),
+    AllowlistInvalid => ("收款白名单中的地址 {} 无效: {}", "Invalid allowlist address {}: {}"),
+    StepCheckBalance => ("4. 检查发送地址余额...", "4. Checking sender balance..."),
+    CurrentBalance => ("✓ 当前余额: {} ETH", "✓ Current balance: {} ETH"),
+    CurrentBalanceWei => ("  精确余额: {} wei", "  Exact balance: {} wei"),
+    StepAmount => ("5. 转账金额: {} ETH ({} wei)", "5. Amount: {} ETH ({} wei)"),
+    StepGasPrice => ("6. 获取实时 Gas 价格...", "6. Fetching the current gas price..."),
+    CurrentGasPrice => ("✓ 当前 Gas 价格: {} Gwei", "✓ Current gas price: {} Gwei"),
+    GasLimit => ("✓ Gas 限额: {}", "✓ Gas limit: {}"),
+    GasL1Component => ("✓ 其中 L1 数据费折算: {} Gas", "✓ Of which for L1 data: {} gas"),
+    FeeEstimateFallback => (
+        "⚠ NodeInterface 估算失败（{}），改用固定 Gas 限额 {}",
+        "⚠ NodeInterface estimate failed ({}); falling back to a fixed gas limit of {}"
+    ),
+    FeeHeuristic => (
+        "⚠ 固定限额没有单独计算 L1 数据费，估算值可能不准",
+        "⚠ The fixed limit does not price the L1 data fee separately; the estimate may be off"
+    ),
+    EstimatedFee => ("✓ 预估 Gas 费: {} ETH", "✓ Estimated gas fee: {} ETH"),
+    InsufficientBalance => (
+        "余额不足！需要 {} ETH（转账 {} + Gas 费 {}），但只有 {} ETH",
+        "Insufficient balance! Need {} ETH (amount {} + gas fee {}), but only have {} ETH"
+    ),
+    BalanceSufficient => ("✓ 余额充足", "✓ Balance is sufficient"),
+    StepPrepare => ("7. 准备交易...", "7. Preparing transaction..."),
+    TxBuilt => ("✓ 交易已构建", "✓ Transaction built"),
+    StepSend => ("8. 签名并发送交易...", "8. Signing and sending transaction..."),
+    TxSent => ("✓ 交易已发送！", "✓ Transaction sent!"),
+    TxHashLine => ("✓ 交易哈希: {}", "✓ Transaction hash: {}"),
+    StepConfirm => ("9. 等待交易确认（最多 {} 秒）...", "9. Waiting for confirmation (up to {}s)..."),
+    StopWaiting => ("⚠ 已停止等待确认，交易已发送并会继续上链", "⚠ Stopped waiting for confirmation; the transaction was sent and will still be mined"),
+    TxHashDetail => ("  - 交易哈希: {}", "  - Transaction hash: {}"),
+    TxConfirmed => ("✓ 交易已确认！", "✓ Transaction confirmed!"),
+    BlockNumber => ("  - 区块号: {}", "  - Block number: {}"),
+    GasUsed => ("  - Gas 使用: {}", "  - Gas used: {}"),
+    Status => ("  - 状态: {}", "  - Status: {}"),
+    InclusionOneBlock => ("  - 打包延迟: 提交后第 {} 个区块 / {} 秒", "  - Inclusion: included after {} block / {}s"),
+    InclusionBlocks => ("  - 打包延迟: 提交后第 {} 个区块 / {} 秒", "  - Inclusion: included after {} blocks / {}s"),
+    TransferDone => ("=== 转账完成 ===", "=== Transfer complete ==="),
+    TransferSucceeded => ("✅ 转账成功！", "✅ Transfer succeeded!"),
+    TxHash => ("交易哈希: {}", "Transaction hash: {}"),
+    ViewTx => ("查看交易: {}", "View transaction: {}"),
+    TransferInterrupted => ("⚠ 已中断，可稍后在区块浏览器查看交易状态", "⚠ Interrupted; check the transaction status in the block explorer later"),
+    BumpSent => (
+        "⚠ 超时未确认，第 {}/{} 次以 {} Gwei 重发（同一 nonce）: {}",
+        "⚠ Not confirmed in time; bump {}/{} resent at {} Gwei (same nonce): {}"
+    ),
+    BumpCapReached => (
+        "⚠ 下一次重发需要 {} Gwei，超过上限 {} Gwei，停止重发",
+        "⚠ The next bump would need {} Gwei, above the {} Gwei cap; no more bumps"
+    ),
+    BumpFailed => ("⚠ 重发失败，停止重发: {}", "⚠ Bumping failed, no more bumps: {}"),
+    ZeroValuePrompt => ("⚠ 将发送 0 ETH，这笔交易只会花掉 Gas。继续吗？[y/N] ", "⚠ Sending 0 ETH; this only pays gas. Continue? [y/N] "),
+    ZeroValueNotInteractive => ("输入不是终端，无法确认 0 ETH 转账；确实要发送请加 --yes", "stdin is not a terminal, so the 0 ETH transfer cannot be confirmed; pass --yes to send it anyway"),
+    ZeroValueCancelled => ("已取消 0 ETH 转账", "0 ETH transfer cancelled"),
+    StillPending => ("⏳ 等待 {} 秒后交易仍未确认", "⏳ Transaction still pending after {}s"),
+    CheckLater => ("稍后可用 --status {} 查询交易状态", "Still pending, check later with --status {}"),
+    StatusTitle => ("=== 查询交易状态 ===", "=== Transaction status ==="),
+    StatusPending => ("⏳ 交易已在内存池中，尚未确认，请稍后再查", "⏳ Transaction is in the mempool but not yet confirmed; check again later"),
+    StatusNotFound => ("❌ 所有节点都查不到交易 {}", "❌ Transaction {} was not found on any endpoint"),
+    StatusRpcFailed => ("⚠ 节点 {} 查询失败: {}", "⚠ Lookup failed on {}: {}"),
+    StatusFailed => ("❌ 查询交易状态失败: {}", "❌ Failed to check transaction status: {}"),
+    TransferFailed => ("❌ 转账失败: {}", "❌ Transfer failed: {}"),
+
+    BatchInterrupted => ("⚠ 批量转账被中断", "⚠ Batch transfer interrupted"),
+    BatchSucceeded => ("✅ 批量转账成功！", "✅ Batch transfer succeeded!"),
+    BatchFailed => ("❌ 批量转账失败: {}", "❌ Batch transfer failed: {}"),
+    BatchSummaryTitle => ("--- 批量转账汇总 ---", "--- Batch transfer summary ---"),
+    BatchSummarySent => ("已广播: {} / {} 笔", "Broadcast: {} / {}"),
+    BatchSummaryUnsent => ("未发送: {} 笔", "Not sent: {}"),
+    BatchSummaryRetries => ("重试: {} 次（整批上限 {} 次）", "Retries: {} (batch limit {})"),
+    BatchSummaryJournal => ("广播记录: {}", "Broadcast journal: {}"),
+    BatchSummaryStopped => ("广播在第 {} 笔停止: {}", "Broadcast stopped at #{}: {}"),
+    BatchSummaryLeftover => ("第 {} 至 {} 笔没有发出，可以去掉已发出的行后重新运行", "Transfers #{} to #{} were not sent; remove the sent lines and run again"),
+    BatchLineFormat => ("第 {} 行格式错误，应为 `地址,金额`", "Line {}: malformed, expected `address,amount`"),
+    BatchLineAddress => ("第 {} 行地址无效: {}", "Line {}: invalid address: {}"),
+    BatchLineAmount => ("第 {} 行金额无效: {}", "Line {}: invalid amount: {}"),
+    BatchEmpty => ("批量转账文件中没有任何转账", "The batch file contains no transfers"),
+    SimBalanceExhausted => ("前面的转账执行后余额不足（剩余 {} ETH）", "Insufficient balance after earlier transfers ({} ETH left)"),
+    BatchStart => ("=== 开始批量转账流程（共 {} 笔）===", "=== Starting batch transfer ({} transfers) ==="),
+    BatchFromAddress => ("1. 发送地址: {}", "1. Sender: {}"),
+    BatchGasPrice => ("2. 当前 Gas 价格: {} Gwei", "2. Current gas price: {} Gwei"),
+    BatchSimulating => ("3. 模拟整批交易（pending 区块状态）...", "3. Simulating the whole batch (pending block state)..."),
+    SimFailedAbort => (
+        "{} 笔交易模拟失败，未广播任何交易（使用 --force 强制发送）",
+        "{} transfers failed simulation; nothing was broadcast (use --force to send anyway)"
+    ),
+    SimFailedForce => ("⚠ {} 笔交易模拟失败，因 --force 仍继续广播", "⚠ {} transfers failed simulation; broadcasting anyway because of --force"),
+    SimAllPassed => ("✓ 全部模拟通过", "✓ All transfers passed simulation"),
+    BatchSending => ("4. 签名并发送交易（记录写入 {}）...", "4. Signing and sending transactions (journal: {})..."),
+    BatchItemSent => (
+        "  ✓ #{} 已发送: {}（Gas 价格 {} Gwei，最高 Gas 费 {} ETH）",
+        "  ✓ #{} sent: {} (gas price {} Gwei, max gas fee {} ETH)"
+    ),
+    BatchItemFailed => ("  ✗ #{} 广播失败: {}", "  ✗ #{} broadcast failed: {}"),
+    BatchRetryBudgetExhausted => (
+        "  ⚠ 整批的 {} 次重试已用完，后面的广播失败不再重试",
+        "  ⚠ The batch's {} retries are used up; further broadcast failures will not be retried"
+    ),
+    BatchConfirming => ("5. 等待交易确认...", "5. Waiting for confirmations..."),
+    BatchStopWaiting => ("  ⚠ 已停止等待确认，已广播的交易仍会继续上链", "  ⚠ Stopped waiting for confirmations; broadcast transactions will still be mined"),
+    BatchItemConfirmed => ("  ✓ #{} 已确认，区块 {}，状态 {}", "  ✓ #{} confirmed in block {}, status {}"),
+    BatchItemNoReceipt => ("  ⚠ #{} 已发送，但未收到确认收据", "  ⚠ #{} sent, but no receipt was received"),
+    FundNoRecipients => ("没有指定接收地址", "No recipients given"),
+    FundZeroAmount => ("每个账户的金额必须大于 0", "The amount per account must be greater than 0"),
+    FundDuplicateRecipient => ("接收地址重复: {}", "Duplicate recipient: {}"),
+    FundInsufficient => (
+        "主钱包余额不足：需要 {} ETH 转给 {} 个账户，加上最多 {} ETH 的 Gas 费，但余额只有 {} ETH；没有发送任何交易",
+        "Insufficient master balance: {} ETH for {} accounts plus up to {} ETH in gas is needed, but the balance is {} ETH; nothing was sent"
+    ),
+    FundRecipientsTitle => ("--- 每个账户的结果 ---", "--- Per-recipient results ---"),
+    FundRecipientSent => ("✓ #{} {} ← {} ETH，交易 {}", "✓ #{} {} ← {} ETH, tx {}"),
+    FundRecipientUnsent => ("⚠ #{} {} 未发送", "⚠ #{} {} not sent"),
+    BatchDone => ("=== 批量转账完成 ===", "=== Batch transfer complete ==="),
+
+    DepositStart => ("=== 开始 L1 → L2 充值流程 ===", "=== Starting L1 → L2 deposit ==="),
+    DepositStepConnect => ("1. 连接到 Ethereum Sepolia（L1）...", "1. Connecting to Ethereum Sepolia (L1)..."),
+    DepositStepCheck => ("2. 检查 L1 余额和 Gas 费...", "2. Checking L1 balance and gas fee..."),
+    DepositL1Balance => ("✓ L1 余额: {} ETH", "✓ L1 balance: {} ETH"),
+    DepositL2Before => ("✓ 充值前 L2 余额: {} ETH", "✓ L2 balance before deposit: {} ETH"),
+    DepositStepSend => ("3. 调用 Inbox.depositEth() 充值 {} ETH...", "3. Calling Inbox.depositEth() with {} ETH..."),
+    DepositL1Confirmed => ("✓ L1 交易已确认，区块 {}", "✓ L1 transaction confirmed in block {}"),
+    DepositL1Reverted => ("L1 充值交易执行失败（status = 0）", "L1 deposit transaction reverted (status = 0)"),
+    DepositL1NoReceipt => ("L1 充值交易已发送，但未收到确认收据", "L1 deposit transaction sent, but no receipt was received"),
+    DepositStepWait => ("4. 等待 L2 到账...", "4. Waiting for the deposit to land on L2..."),
+    DepositDelayNote => (
+        "⏳ 充值消息要等 Arbitrum 排序器读取到 L1 交易后才会在 L2 执行，通常需要 10～15 分钟",
+        "⏳ The deposit executes on L2 only after the Arbitrum sequencer picks up the L1 transaction, usually 10-15 minutes"
+    ),
+    DepositWaiting => ("  ... 已等待 {} 秒，L2 余额还没有变化", "  ... {}s elapsed, L2 balance unchanged"),
+    DepositL1TxHash => ("L1 交易哈希: {}", "L1 transaction hash: {}"),
+    DepositCredited => ("✅ 充值已到账（L1 确认后 {} 秒），L2 余额增加 {} ETH", "✅ Deposit credited {}s after L1 confirmation; L2 balance increased by {} ETH"),
+    DepositL2Balance => ("当前 L2 余额: {} ETH", "Current L2 balance: {} ETH"),
+    DepositNotYetCredited => (
+        "⏳ 等待 {} 秒后仍未到账，资金稍后会自动到账，可以再查询 L2 余额",
+        "⏳ Not credited after {}s; funds will still arrive, check the L2 balance later"
+    ),
+    DepositInterrupted => ("⚠ 已中断；如果 L1 交易已发送，资金仍会到账", "⚠ Interrupted; if the L1 transaction was sent, the funds will still arrive"),
+    DepositFailed => ("❌ 充值失败: {}", "❌ Deposit failed: {}"),
+
+    DecodeFailed => ("❌ 解码失败: {}", "❌ Decode failed: {}"),
+
+    BridgeStart => ("=== 开始 ERC20 L1 → L2 跨链流程 ===", "=== Starting ERC20 L1 → L2 bridge ==="),
+    BridgeNoGateway => ("L1GatewayRouter 没有为代币 {} 配置网关", "L1GatewayRouter has no gateway for token {}"),
+    BridgeGateway => ("✓ 代币网关: {}", "✓ Token gateway: {}"),
+    BridgeL2Token => ("L2 代币地址: {}", "L2 token address: {}"),
+    BridgeResuming => ("⚠ 从状态文件 {} 继续上次的跨链", "⚠ Resuming the previous bridge from state file {}"),
+    BridgeStateMismatch => (
+        "状态文件 {} 记录的是另一笔跨链（代币或数量不同），请先确认那一笔是否到账，再删除该文件或用 --state-file 指定新文件",
+        "State file {} belongs to a different bridge (token or amount differs); check whether it arrived, then delete the file or pass a new --state-file"
+    ),
+    BridgeBadState => ("状态文件 {} 缺少字段 {}", "State file {} is missing field {}"),
+    BridgeAlreadySent => ("跨链交易 {} 已经发出，只继续等待到账", "Bridge transaction {} was already sent; only waiting for it to arrive"),
+    BridgeStepApprove => ("2. 检查网关的授权额度...", "2. Checking the gateway allowance..."),
+    BridgeApproveTx => ("授权交易哈希: {}", "Approval transaction hash: {}"),
+    BridgeApproved => ("✓ 授权已确认", "✓ Approval confirmed"),
+    BridgeApprovalSufficient => ("✓ 授权额度已足够，跳过授权", "✓ Allowance already sufficient; skipping approval"),
+    BridgeStepSend => ("3. 调用 L1GatewayRouter.outboundTransfer()...", "3. Calling L1GatewayRouter.outboundTransfer()..."),
+    BridgeReverted => ("L1 交易执行失败（status = 0）", "L1 transaction reverted (status = 0)"),
+    BridgeCredited => ("✅ 代币已到账（L1 确认后 {} 秒），L2 余额增加 {}", "✅ Tokens credited {}s after L1 confirmation; L2 balance increased by {}"),
+    BridgeInterrupted => (
+        "⚠ 已中断；重新运行同一命令会继续：授权已足够时跳过授权，已发出的跨链交易记录在 {} 中，不会重复发送",
+        "⚠ Interrupted; rerun the same command to resume: a sufficient allowance skips approval, and a sent bridge transaction is recorded in {} and never sent twice"
+    ),
+    BridgeFailed => ("❌ 跨链失败: {}", "❌ Bridge failed: {}"),
+
+    WithdrawStart => ("=== 开始 L2 → L1 提现流程 ===", "=== Starting L2 → L1 withdrawal ==="),
+    WithdrawStepConnect => ("1. 连接到 Arbitrum Sepolia（L2）...", "1. Connecting to Arbitrum Sepolia (L2)..."),
+    WithdrawDestination => ("✓ L1 收款地址: {}", "✓ L1 destination: {}"),
+    WithdrawStepCheck => ("2. 检查 L2 余额和 Gas 费...", "2. Checking L2 balance and gas fee..."),
+    WithdrawStepSend => ("3. 调用 ArbSys.withdrawEth() 提现 {} ETH...", "3. Calling ArbSys.withdrawEth() with {} ETH..."),
+    WithdrawConfirmed => ("✓ L2 交易已确认，区块 {}", "✓ L2 transaction confirmed in block {}"),
+    WithdrawReverted => ("提现交易执行失败（status = 0）", "Withdrawal transaction reverted (status = 0)"),
+    WithdrawNoReceipt => ("提现交易已发送，但未收到确认收据", "Withdrawal transaction sent, but no receipt was received"),
+    WithdrawStepClaim => ("4. 解码 L2ToL1Tx 事件，保存领取信息...", "4. Decoding the L2ToL1Tx event and saving claim data..."),
+    WithdrawNoEvent => ("收据中没有 ArbSys 的 L2ToL1Tx 事件", "The receipt contains no ArbSys L2ToL1Tx event"),
+    WithdrawMissingField => ("L2ToL1Tx 事件缺少字段 {}", "L2ToL1Tx event is missing field {}"),
+    WithdrawInitiated => ("✅ 提现已发起！", "✅ Withdrawal initiated!"),
+    WithdrawPosition => ("出站消息位置（position）: {}", "Outbox position: {}"),
+    WithdrawClaimSaved => ("领取信息已保存: {}", "Claim data saved to: {}"),
+    WithdrawDisputeWarning => (
+        "⚠ 资金不会立即到账 L1：需要等挑战期结束（主网约 7 天），再用保存的领取信息在 L1 Outbox 上执行领取",
+        "⚠ Funds do NOT arrive on L1 yet: wait for the dispute window to pass (about 7 days on mainnet), then execute the claim on the L1 Outbox using the saved claim data"
+    ),
+    WithdrawInterrupted => ("⚠ 已中断；如果交易已发送，可稍后用 --status 查询", "⚠ Interrupted; if the transaction was sent, check it later with --status"),
+    WithdrawBadClaim => ("领取信息文件 {} 缺少字段 {}", "Claim file {} is missing field {}"),
+    ExecuteStart => ("=== 在 L1 Outbox 上执行提现 ===", "=== Executing the withdrawal on the L1 Outbox ==="),
+    ExecuteStepConnect => ("1. 连接到 L1...", "1. Connecting to L1..."),
+    ExecuteStepCheck => ("2. 检查消息是否已执行、是否已确认...", "2. Checking whether the message is executed and confirmed..."),
+    ExecuteScanProgress => ("  已查找 L1 区块 {}–{}（每段 {} 个区块）", "  Scanned L1 blocks {}–{} ({} blocks per chunk)"),
+    ExecuteConfirmedCount => ("✓ Outbox 已确认 {} 条出站消息，本消息序号 {}", "✓ The Outbox has confirmed {} outgoing messages; this one is #{}"),
+    ExecuteStepProof => ("3. 通过 NodeInterface.constructOutboxProof 构造证明...", "3. Building the proof with NodeInterface.constructOutboxProof..."),
+    ExecuteProofReady => ("✓ 证明共 {} 个节点，根 {}", "✓ Proof has {} nodes, root {}"),
+    ExecuteStepSend => ("4. 调用 Outbox.executeTransaction()...", "4. Calling Outbox.executeTransaction()..."),
+    ExecuteBadSource => ("{} 既不是存在的文件，也不是交易哈希", "{} is neither an existing file nor a transaction hash"),
+    ExecuteL2TxNotFound => ("L2 上查不到交易 {} 的收据", "No receipt for transaction {} on L2"),
+    ExecuteBadPosition => ("出站消息位置 {} 不是叶子节点", "Outbox position {} is not a leaf"),
+    ExecuteProofMismatch => (
+        "证明的叶子哈希 {} 与保存的事件哈希 {} 不一致，领取信息可能不对应这笔提现",
+        "Proof leaf {} does not match the saved event hash {}; the claim data may belong to another withdrawal"
+    ),
+    ExecuteMissingL2Block => ("L2 上查不到区块 {} 或它的 sendCount", "L2 block {} or its sendCount was not found"),
+    ExecuteReverted => ("executeTransaction 执行失败（status = 0）", "executeTransaction reverted (status = 0)"),
+    ExecuteNoReceipt => ("executeTransaction 已发送，但未收到确认收据", "executeTransaction sent, but no receipt was received"),
+    ExecuteDone => ("✅ 提现已在 L1 上执行，资金已到账！", "✅ Withdrawal executed on L1; funds delivered!"),
+    ExecuteAlreadyExecuted => ("✅ 这笔提现已经执行过了", "✅ This withdrawal has already been executed"),
+    ExecuteNotConfirmed => (
+        "⏳ 包含这笔提现的 L2 状态还没在 L1 上确认：挑战期还剩约 {} 个 L1 区块（约 {} 小时），之后还要等下一次断言确认",
+        "⏳ The L2 state with this withdrawal is not confirmed on L1 yet: about {} L1 blocks (~{} h) of the challenge window remain, then the next assertion must be confirmed"
+    ),
+    ExecuteInterrupted => ("⚠ 已中断；如果交易已发送，可稍后重新运行，已执行的提现会被识别", "⚠ Interrupted; if the transaction was sent, rerun later and an executed withdrawal will be detected"),
+    WithdrawalStatusSummary => ("提现 {} ETH 到 {}，发起时的 L1 区块 {}", "Withdrawal of {} ETH to {}, initiated at L1 block {}"),
+    WithdrawalStatusExecuted => ("✅ 已在 L1 上领取", "✅ Already claimed on L1"),
+    WithdrawalStatusClaimable => (
+        "✅ 挑战期已结束，可以领取：cargo run -- execute-withdrawal {}",
+        "✅ The challenge period is over; claim it with: cargo run -- execute-withdrawal {}"
+    ),
+    WithdrawalStatusAwaitingAssertion => (
+        "⏳ 挑战期已过，正在等待包含这笔提现的断言在 L1 上确认（通常不到 1 小时）",
+        "⏳ The challenge period has passed; waiting for the assertion containing this withdrawal to be confirmed on L1 (usually under an hour)"
+    ),
+    WithdrawalStatusPending => (
+        "⏳ 还不能领取：挑战期还剩约 {} 个 L1 区块（约 {} 小时 / {} 天），之后还要等断言确认",
+        "⏳ Not claimable yet: about {} L1 blocks (~{} h / {} days) of the challenge period remain, then the assertion must be confirmed"
+    ),
+    WithdrawalStatusFailed => ("❌ 查询提现状态失败: {}", "❌ Querying the withdrawal status failed: {}"),
+    ExecuteFailed => ("❌ 执行提现失败: {}", "❌ Executing the withdrawal failed: {}"),
+    WithdrawFailed => ("❌ 提现失败: {}", "❌ Withdrawal failed: {}"),
+
+    RetryableStart => ("=== 创建 retryable ticket ===", "=== Creating a retryable ticket ==="),
+    RetryableStepFee => ("2. 估算提交费...", "2. Estimating the submission fee..."),
+    RetryableFees => (
+        "✓ 最高提交费 {} ETH，L2 最高 Gas 费 {} ETH，共需附带 {} ETH",
+        "✓ Max submission cost {} ETH, max L2 gas fee {} ETH, total deposit {} ETH"
+    ),
+    RetryableStepSend => ("3. 调用 Inbox.createRetryableTicket()...", "3. Calling Inbox.createRetryableTicket()..."),
+    RetryableNoMessage => ("L1 收据中没有 Bridge 的 MessageDelivered 事件", "The L1 receipt contains no Bridge MessageDelivered event"),
+    RetryableCreated => ("✅ retryable ticket 已提交！", "✅ Retryable ticket submitted!"),
+    RetryableL1TxHash => ("L1 交易哈希: {}", "L1 transaction hash: {}"),
+    RetryableTicketId => ("Ticket ID: {}", "Ticket ID: {}"),
+    RetryableCheckLater => (
+        "大约 10～15 分钟后可用 `retryable status {}` 查看 L2 上是否自动执行成功",
+        "In about 10-15 minutes, run `retryable status {}` to see whether it auto-redeemed on L2"
+    ),
+    RetryableEstimating => ("估算提交费（L1）和 L2 执行 Gas（NodeInterface）...", "Estimating the submission fee (L1) and L2 execution gas (NodeInterface)..."),
+    RetryableEstimateSubmission => (
+        "maxSubmissionCost: {} ETH（calldata {} 字节，L1 base fee {} Gwei，已含余量）",
+        "maxSubmissionCost: {} ETH (calldata {} bytes, L1 base fee {} Gwei, margin included)"
+    ),
+    RetryableEstimateGasLimit => ("gasLimit:          {}", "gasLimit:          {}"),
+    RetryableEstimateMaxFee => ("maxFeePerGas:      {} Gwei", "maxFeePerGas:      {} Gwei"),
+    RetryableEstimateGasFee => ("L2 最高 Gas 费:    {} ETH", "Max L2 gas fee:    {} ETH"),
+    RetryableEstimateDeposit => ("需要随 ticket 附带的 L1 ETH: {} ETH", "L1 ETH to send with the ticket: {} ETH"),
+    RetryableEstimateCommand => (
+        "按此估算创建:\n  cargo run -- retryable create {} --l2-call-value {} --data {} --gas-limit {} --max-fee-per-gas {}",
+        "To create with these values:\n  cargo run -- retryable create {} --l2-call-value {} --data {} --gas-limit {} --max-fee-per-gas {}"
+    ),
+    RetryableInvalidId => ("无效的 ticket ID: {}", "Invalid ticket ID: {}"),
+    RetryableInvalidData => ("无效的 calldata: {}", "Invalid calldata: {}"),
+    RetryableRedeeming => ("📝 redeem ticket {}...", "📝 Redeeming ticket {}..."),
+    RetryableNoReceipt => ("交易已发送，但未收到确认收据", "Transaction sent, but no receipt was received"),
+    RetryableRedeemed => ("✅ redeem 成功，区块 {}", "✅ Redeemed in block {}"),
+    RetryableRedeemReverted => ("❌ redeem 交易执行失败（status = 0），可以调高 Gas 后重试", "❌ Redeem reverted (status = 0); retry with more gas"),
+    RetryableKeepingAlive => ("📝 keepalive ticket {}...", "📝 Extending ticket {} with keepalive..."),
+    RetryableKeepaliveReverted => ("❌ keepalive 交易执行失败（status = 0）", "❌ Keepalive reverted (status = 0)"),
+    RetryableKeptAlive => ("✅ keepalive 成功，新的过期时间 {}", "✅ Keepalive succeeded; new expiry {}"),
+    RetryableKeepaliveGone => ("ticket 已不存在或已过期，无法 keepalive", "The ticket is gone or expired and cannot be kept alive"),
+    RetryableKeepaliveRefused => (
+        "当前不能 keepalive：过期时间距现在已超过一个有效期，临近过期时再延长",
+        "Keepalive is not allowed yet: the expiry is more than one lifetime away; extend it closer to expiry"
+    ),
+    TicketInfoTimeout => ("过期时间:       {}（L2 时间 {}）", "Expires at:     {} (L2 time {})"),
+    TicketInfoRemaining => ("剩余时间:       {} 小时 {} 分钟", "Time left:      {} h {} min"),
+    TicketInfoBeneficiary => ("受益人:         {}（可以 cancel 这个 ticket）", "Beneficiary:    {} (can cancel this ticket)"),
+    TicketInfoLifetime => ("keepalive 延长: {} 天", "Keepalive adds: {} days"),
+    TicketInfoKeepaliveCost => (
+        "keepalive 费用: 约 {} ETH（{} Gas × {} Gwei）",
+        "Keepalive cost: about {} ETH ({} gas × {} Gwei)"
+    ),
+    TicketInfoExpired => (
+        "❌ ticket 已过期（L2 时间已过 {}），等待清理，无法再 redeem 或 keepalive",
+        "❌ Ticket expired (L2 time is past {}) and is awaiting cleanup; it can no longer be redeemed or kept alive"
+    ),
+    TicketPending => (
+        "⏳ ticket 尚未执行，可以手动 redeem，过期时间 {}（还剩约 {} 小时）",
+        "⏳ Ticket not executed yet and can be redeemed manually; expires at {} (about {} hours left)"
+    ),
+    TicketRedeemed => ("✅ ticket 已执行", "✅ Ticket has been redeemed"),
+    TicketExpired => ("❌ ticket 已过期，无法再 redeem", "❌ Ticket has expired and can no longer be redeemed"),
+    TicketNotFound => ("❌ L2 上查不到 ticket {}（L1 交易可能还没被排序器读取）", "❌ Ticket {} not found on L2 (the sequencer may not have picked up the L1 transaction yet)"),
+    MessageInvalidHash => ("无效的 L1 交易哈希: {}", "Invalid L1 transaction hash: {}"),
+    MessageL1NotFound => ("L1 上查不到交易 {} 的收据", "No receipt for transaction {} on L1"),
+    MessageNoInboxData => ("L1 收据中没有 Inbox 的 InboxMessageDelivered 事件", "The L1 receipt contains no Inbox InboxMessageDelivered event"),
+    MessageBadData => ("Inbox 消息原文格式不对", "Malformed Inbox message data"),
+    MessageUnsupportedKind => (
+        "不支持的消息类型 {}（只支持 ETH 充值和 retryable ticket）",
+        "Unsupported message kind {} (only ETH deposits and retryable tickets are supported)"
+    ),
+    MessageNumber => ("消息序号: {}", "Message number: {}"),
+    MessageEthDeposit => ("ETH 充值: {} ETH → {}", "ETH deposit: {} ETH → {}"),
+    MessageL2TxHash => ("L2 交易哈希: {}", "L2 transaction hash: {}"),
+    MessageHopL1 => ("L1 提交时间:    {}", "Submitted on L1: {}"),
+    MessageHopL2 => ("L2 执行时间:    {}（L1 提交后 {} 秒）", "Executed on L2:  {} ({} s after L1)"),
+    MessageHopRedeem => ("自动 redeem 时间: {}", "Auto-redeemed:   {}"),
+    MessageNotYetOnL2 => ("⏳ L2 上还没有对应交易，排序器通常 10～15 分钟后读取 L1 消息", "⏳ Not on L2 yet; the sequencer usually picks up L1 messages after 10-15 minutes"),
+    MessageCredited => ("✅ ETH 已在 L2 到账", "✅ ETH has been credited on L2"),
+    MessageAutoRedeemed => ("✅ ticket 已自动 redeem，交易 {}", "✅ Ticket was auto-redeemed in transaction {}"),
+    MessageAutoRedeemFailed => ("❌ 自动 redeem 失败，交易 {}", "❌ Auto-redeem failed in transaction {}"),
+    MessageAwaitingRedeem => (
+        "⏳ ticket 等待手动 redeem（retryable redeem <ticket ID>），过期时间 {}",
+        "⏳ Ticket is awaiting a manual redeem (retryable redeem <ticket ID>); expires at {}"
+    ),
+    MessageManuallyRedeemed => ("✅ 自动 redeem 失败后已被手动 redeem", "✅ Redeemed manually after the auto-redeem failed"),
+    MessageStatusFailed => ("❌ 查询跨链消息状态失败: {}", "❌ Failed to check the cross-chain message: {}"),
+    RetryableFailed => ("❌ retryable 操作失败: {}", "❌ Retryable operation failed: {}"),
+
+    AddrTableAddress => ("地址:     {}", "Address:    {}"),
+    AddrTableIndex => ("✓ 已注册，表内序号 {}", "✓ Registered at index {}"),
+    AddrTableNotRegistered => ("⚠ 未注册（lookup 会 revert），可以用 addr-table register 注册", "⚠ Not registered (lookup reverts); register it with addr-table register"),
+    AddrTableFormIndex => ("RLP 编码的序号 {}", "RLP-encoded index {}"),
+    AddrTableFormAddress => ("RLP 编码的完整地址", "RLP-encoded full address"),
+    AddrTableCompressed => ("压缩形式: {}（{} 字节，{}）", "Compressed: {} ({} bytes, {})"),
+    AddrTableSavings => (
+        "Arbitrum 的 L1 数据费按 calldata 字节收取，注册后合约可以接收序号代替 20 字节地址，每次引用节省 {} 字节",
+        "Arbitrum charges the L1 data fee per calldata byte; once registered, contracts can accept the index instead of the 20-byte address, saving {} bytes per reference"
+    ),
+    AddrTableIndexAddress => ("✓ 序号 {} 对应地址 {}", "✓ Index {} maps to {}"),
+    AddrTableIndexNotFound => ("序号 {} 不存在，地址表目前共有 {} 个地址", "Index {} does not exist; the table currently holds {} addresses"),
+    AddrTableAlreadyRegistered => ("地址已注册（序号 {}），不需要再发送交易", "Address is already registered (index {}); no transaction needed"),
+    AddrTableRegistering => ("调用 ArbAddressTable.register({})...", "Calling ArbAddressTable.register({})..."),
+    AddrTableNoReceipt => ("register 交易已发送，但未收到确认收据", "Register transaction sent, but no receipt was received"),
+    AddrTableReverted => ("register 交易已上链但执行失败（status = 0）", "Register transaction was mined but reverted (status = 0)"),
+    AddrTableRegistered => ("✅ 注册成功", "✅ Registered"),
+    AddrTableFailed => ("❌ 地址表操作失败: {}", "❌ Address table operation failed: {}"),
+}
+
+/// 余额不足的错误信息（金额均为 ETH）
+pub fn insufficient_balance(required: &str, amount: &str, fee: &str, balance: &str) -> String {
+    tf(Msg::InsufficientBalance, &[&required, &amount, &fee, &balance])
+}
+
+/// "交易哈希: 0x..." 一行
+pub fn tx_hash(tx_hash: &TxHash) -> String {
+    tf(Msg::TxHash, &[&arbi_common::style::addr(format!("{:?}", tx_hash))])
+}
+
+/// "查看交易: <区块浏览器链接>" 一行
+pub fn view_tx(tx_hash: &TxHash) -> String {
+    tf(Msg::ViewTx, &[&format!("{}/tx/{:?}", network::current().explorer_url(), tx_hash)])
+}
+
+/// 转账确认后的结尾：成功提示、交易哈希和区块浏览器链接
+pub fn transfer_summary(tx_hash: &TxHash) -> String {
+    let succeeded = arbi_common::style::ok(t(Msg::TransferSucceeded));
+    format!("{}\n{}\n\n{}", succeeded, self::tx_hash(tx_hash), view_tx(tx_hash))
+}
+
+// 输出快照：措辞或格式的改动都会让这里失败，需要在评审时一并确认
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::confirm::TransferResult;
+    use arbi_common::i18n::{Lang, with_lang};
+    use ethers::types::{U64, U256};
+    use std::time::Duration;
+
+    fn hash() -> TxHash {
+        TxHash::repeat_byte(0xab)
+    }
+
+    fn confirmed() -> TransferResult {
+        TransferResult {
+            tx_hash: hash(),
+            block_number: Some(U64::from(123_456)),
+            gas_used: Some(U256::from(21_000)),
+            status: Some(U64::one()),
+            submitted_block: Some(U64::from(123_454)),
+            inclusion_delay: Some(Duration::from_millis(520)),
+        }
+    }
+
+    #[test]
+    fn transfer_summary_snapshot() {
+        let render = || [confirmed().lines().join("\n"), transfer_summary(&hash())].join("\n\n");
+        assert_eq!(
+            with_lang(Lang::Zh, render),
+            "✓ 交易已确认！
+  - 区块号: Some(123456)
+  - Gas 使用: Some(21000)
+  - 状态: Some(1)
+  - 打包延迟: 提交后第 2 个区块 / 0.5 秒
+
+✅ 转账成功！
+交易哈希: 0xabababababababababababababababababababababababababababababababab
+
+查看交易: https://sepolia.arbiscan.io/tx/0xabababababababababababababababababababababababababababababababab"
+        );
+
+        assert_eq!(
+            with_lang(Lang::En, render),
+            "✓ Transaction confirmed!
+  - Block number: Some(123456)
+  - Gas used: Some(21000)
+  - Status: Some(1)
+  - Inclusion: included after 2 blocks / 0.5s
+
+✅ Transfer succeeded!
+Transaction hash: 0xabababababababababababababababababababababababababababababababab
+
+View transaction: https://sepolia.arbiscan.io/tx/0xabababababababababababababababababababababababababababababababab"
+        );
+    }
+
+    #[test]
+    fn insufficient_balance_snapshot() {
+        let error = || insufficient_balance("1.000021", "1", "0.000021", "0.5");
+        assert_eq!(
+            with_lang(Lang::Zh, error),
+            "余额不足！需要 1.000021 ETH（转账 1 + Gas 费 0.000021），但只有 0.5 ETH"
+        );
+        assert_eq!(
+            with_lang(Lang::En, error),
+            "Insufficient balance! Need 1.000021 ETH (amount 1 + gas fee 0.000021), but only have 0.5 ETH"
+        );
+    }
+}
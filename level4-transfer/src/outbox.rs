@@ -0,0 +1,536 @@
+use arbi_common::logs::LogChunks;
+use arbi_common::narrate;
+use arbi_common::network;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::shutdown::{Interrupted, Shutdown};
+use arbi_common::style;
+use ethers::abi::{Abi, Token};
+use ethers::prelude::*;
+use ethers::signers::LocalWallet;
+use ethers::types::{Address, Filter, H256, TxHash, U256};
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{Instrument, Span, field, info, info_span};
+
+use crate::fee::NODE_INTERFACE_ADDRESS;
+use crate::messages::{Msg, t, tf};
+use crate::withdraw::WithdrawalClaim;
+
+// L1 Outbox 中领取提现用到的 ABI 片段
+const OUTBOX_ABI: &str = r#"[
+    {
+        "inputs": [],
+        "name": "rollup",
+        "outputs": [{"name": "", "type": "address"}],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [{"name": "index", "type": "uint256"}],
+        "name": "isSpent",
+        "outputs": [{"name": "", "type": "bool"}],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [
+            {"name": "proof", "type": "bytes32[]"},
+            {"name": "index", "type": "uint256"},
+            {"name": "l2Sender", "type": "address"},
+            {"name": "to", "type": "address"},
+            {"name": "l2Block", "type": "uint256"},
+            {"name": "l1Block", "type": "uint256"},
+            {"name": "l2Timestamp", "type": "uint256"},
+            {"name": "value", "type": "uint256"},
+            {"name": "data", "type": "bytes"}
+        ],
+        "name": "executeTransaction",
+        "outputs": [],
+        "stateMutability": "nonpayable",
+        "type": "function"
+    },
+    {
+        "anonymous": false,
+        "inputs": [
+            {"indexed": true, "name": "outputRoot", "type": "bytes32"},
+            {"indexed": true, "name": "l2BlockHash", "type": "bytes32"}
+        ],
+        "name": "SendRootUpdated",
+        "type": "event"
+    }
+]"#;
+
+// Rollup 合约中查询挑战期长度的 ABI 片段
+const ROLLUP_ABI: &str = r#"[
+    {
+        "inputs": [],
+        "name": "confirmPeriodBlocks",
+        "outputs": [{"name": "", "type": "uint64"}],
+        "stateMutability": "view",
+        "type": "function"
+    }
+]"#;
+
+// NodeInterface 中构造 Outbox 证明的 ABI 片段
+const NODE_INTERFACE_OUTBOX_ABI: &str = r#"[
+    {
+        "inputs": [
+            {"name": "size", "type": "uint64"},
+            {"name": "leaf", "type": "uint64"}
+        ],
+        "name": "constructOutboxProof",
+        "outputs": [
+            {"name": "send", "type": "bytes32"},
+            {"name": "root", "type": "bytes32"},
+            {"name": "proof", "type": "bytes32[]"}
+        ],
+        "stateMutability": "view",
+        "type": "function"
+    }
+]"#;
+
+// 最多向前查找的 L1 区块数；Outbox 大约每小时更新一次，这个范围足够覆盖
+const SEND_ROOT_SCAN_BLOCKS: u64 = 100_000;
+
+// L1 出块间隔（秒），用来把剩余区块数换算成时间
+const L1_BLOCK_TIME_SECS: u64 = 12;
+
+/// `constructOutboxProof` 的返回值
+pub struct OutboxProof {
+    /// 叶子哈希，应该等于 `L2ToL1Tx` 事件里的 `hash`
+    pub send: H256,
+    /// 默克尔根，应该是 Outbox 已确认的某个 sendRoot
+    pub root: H256,
+    pub proof: Vec<H256>,
+}
+
+/// 领取提现的结果
+pub enum ExecuteOutcome {
+    /// 已在 L1 上执行，附 L1 交易哈希
+    Executed(TxHash),
+    /// 这条消息之前已经执行过
+    AlreadyExecuted,
+    /// 包含这条消息的 L2 状态还没有在 L1 上确认，`remaining_blocks` 为挑战期还剩的 L1 区块数
+    NotConfirmed { remaining_blocks: u64 },
+}
+
+/// 提现在 L1 Outbox 上的状态
+pub enum WithdrawalStatus {
+    /// 已在 L1 上执行，资金已领取
+    Executed,
+    /// 包含这条消息的 L2 状态已在 L1 上确认，可以领取；`send_count` 为 Outbox 已确认的出站消息总数
+    Claimable { send_count: u64 },
+    /// 还没确认，`remaining_blocks` 为挑战期还剩的 L1 区块数；为 0 时挑战期已过，在等断言确认
+    Pending { remaining_blocks: u64 },
+}
+
+/// L1 区块数大约对应的小时数
+pub fn blocks_to_hours(blocks: u64) -> f64 {
+    (blocks * L1_BLOCK_TIME_SECS) as f64 / 3600.0
+}
+
+/// 把 `L2ToL1Tx` 事件中的出站消息位置转成默克尔树的叶子序号
+///
+/// Nitro 的 position 高 64 位是层级，叶子的层级为 0，低位就是叶子序号。
+///
+/// # 参数
+/// * `claim` - 领取信息
+///
+/// # 返回
+/// * `Result<u64, Box<dyn Error>>` - 叶子序号；不是叶子时返回错误
+pub fn leaf_index(claim: &WithdrawalClaim) -> Result<u64, Box<dyn Error>> {
+    if claim.position > U256::from(u64::MAX) {
+        return Err(tf(Msg::ExecuteBadPosition, &[&claim.position]).into());
+    }
+    Ok(claim.position.as_u64())
+}
+
+/// 按 `Outbox.executeTransaction` 的参数顺序组装调用参数
+///
+/// 证明必须对应领取信息里保存的那条消息：`send` 与事件的 `hash` 不一致时返回错误，
+/// 避免用错误的字段发送一笔注定失败的交易。
+///
+/// # 参数
+/// * `claim` - 领取信息
+/// * `proof` - NodeInterface 返回的证明
+///
+/// # 返回
+/// * `Result<Vec<Token>, Box<dyn Error>>` - `executeTransaction` 的参数
+pub fn execute_args(
+    claim: &WithdrawalClaim,
+    proof: &OutboxProof,
+) -> Result<Vec<Token>, Box<dyn Error>> {
+    let mut expected = [0u8; 32];
+    claim.hash.to_big_endian(&mut expected);
+    if proof.send != H256(expected) {
+        let (send, expected) = (format!("{:?}", proof.send), format!("{:?}", H256(expected)));
+        return Err(tf(Msg::ExecuteProofMismatch, &[&send, &expected]).into());
+    }
+
+    Ok(vec![
+        Token::Array(proof.proof.iter().map(|node| Token::FixedBytes(node.0.to_vec())).collect()),
+        Token::Uint(claim.position),
+        Token::Address(claim.caller),
+        Token::Address(claim.destination),
+        Token::Uint(claim.arb_block_num),
+        Token::Uint(claim.eth_block_num),
+        Token::Uint(claim.timestamp),
+        Token::Uint(claim.callvalue),
+        Token::Bytes(claim.data.to_vec()),
+    ])
+}
+
+/// 在 L2 上通过 NodeInterface 构造出站消息的默克尔证明
+///
+/// # 参数
+/// * `provider` - L2 Provider
+/// * `size` - 已确认的出站消息总数（默克尔树大小）
+/// * `leaf` - 叶子序号
+///
+/// # 返回
+/// * `Result<OutboxProof, Box<dyn Error>>` - 证明
+pub async fn construct_proof(
+    provider: &RpcProvider,
+    size: u64,
+    leaf: u64,
+) -> Result<OutboxProof, Box<dyn Error>> {
+    let node_interface = Contract::new(
+        Address::from_str(NODE_INTERFACE_ADDRESS)?,
+        serde_json::from_str::<Abi>(NODE_INTERFACE_OUTBOX_ABI)?,
+        Arc::new(provider.clone()),
+    );
+    let (send, root, proof): (H256, H256, Vec<H256>) = node_interface
+        .method("constructOutboxProof", (size, leaf))?
+        .call()
+        .await?;
+    Ok(OutboxProof { send, root, proof })
+}
+
+/// 查找 Outbox 最近一次确认的 L2 状态中的出站消息总数
+///
+/// 从最新的 L1 区块向前分段查找 `SendRootUpdated` 事件，取对应 L2 区块头里的 `sendCount`。
+///
+/// # 参数
+/// * `l1_provider` - L1 Provider
+/// * `l2_provider` - L2 Provider
+/// * `outbox` - Outbox 地址
+/// * `chunk_size` - 每次 `eth_getLogs` 查询的 L1 区块数，节点拒绝时自动减半
+///
+/// # 返回
+/// * `Result<Option<u64>, Box<dyn Error>>` - 出站消息总数，查找范围内没有事件时为 `None`
+pub async fn confirmed_send_count(
+    l1_provider: &RpcProvider,
+    l2_provider: &RpcProvider,
+    outbox: Address,
+    chunk_size: u64,
+) -> Result<Option<u64>, Box<dyn Error>> {
+    let abi = serde_json::from_str::<Abi>(OUTBOX_ABI)?;
+    let signature = abi.event("SendRootUpdated")?.signature();
+    let latest = l1_provider.get_block_number().await?.as_u64();
+    let earliest = latest.saturating_sub(SEND_ROOT_SCAN_BLOCKS - 1);
+    let filter = Filter::new().address(outbox).topic0(signature);
+    let mut chunks =
+        LogChunks::new(l1_provider, filter, earliest, latest, chunk_size).newest_first();
+
+    while let Some(chunk) = chunks.next().await? {
+        let size = chunks.chunk_size();
+        narrate!("{}", tf(Msg::ExecuteScanProgress, &[&chunk.from, &chunk.to, &size]));
+        if let Some(l2_block_hash) = chunk.logs.last().and_then(|log| log.topics.get(2)) {
+            let missing = || tf(Msg::ExecuteMissingL2Block, &[&format!("{:?}", l2_block_hash)]);
+            let block = l2_provider.get_block(*l2_block_hash).await?.ok_or_else(missing)?;
+            let send_count = block
+                .other
+                .get("sendCount")
+                .and_then(|value| value.as_str())
+                .ok_or_else(missing)?;
+            return Ok(Some(U256::from_str(send_count)?.as_u64()));
+        }
+    }
+    Ok(None)
+}
+
+/// 估算挑战期还剩多少个 L1 区块
+///
+/// 挑战期从发起提现时的 L1 区块（事件里的 `ethBlockNum`）算起，长度为 Rollup 的
+/// `confirmPeriodBlocks`。挑战期结束后还要等下一次断言被确认，所以这只是下限。
+async fn remaining_challenge_blocks(
+    l1_provider: &RpcProvider,
+    outbox: &Contract<RpcProvider>,
+    claim: &WithdrawalClaim,
+) -> Result<u64, Box<dyn Error>> {
+    let rollup_address: Address = outbox.method("rollup", ())?.call().await?;
+    let rollup = Contract::new(
+        rollup_address,
+        serde_json::from_str::<Abi>(ROLLUP_ABI)?,
+        Arc::new(l1_provider.clone()),
+    );
+    let confirm_period: u64 = rollup.method("confirmPeriodBlocks", ())?.call().await?;
+    let current = l1_provider.get_block_number().await?.as_u64();
+    Ok((claim.eth_block_num.as_u64() + confirm_period).saturating_sub(current))
+}
+
+/// 查询提现在 L1 Outbox 上的状态
+///
+/// L2 → L1 消息的流程：L2 上调用 `ArbSys.withdrawEth` 产生 `L2ToL1Tx` 事件，消息进入出站默克尔树；
+/// 包含它的 L2 状态以断言的形式提交到 L1 Rollup 合约，挑战期（`confirmPeriodBlocks`，
+/// 测试网和主网约 6.4 天）内无人挑战才会被确认，Outbox 随之记录新的 sendRoot，之后任何人都可以
+/// 用默克尔证明调用 `executeTransaction` 领取。
+///
+/// 需要两个节点：L2 节点读取提现交易收据和区块头里的 `sendCount`；L1 节点读取 Outbox 的
+/// `isSpent`、`SendRootUpdated` 日志和 Rollup 的 `confirmPeriodBlocks`。
+///
+/// 局限：只向前查找最近 10 万个 L1 区块内的 `SendRootUpdated`（按 `chunk_size` 分段查询）；
+/// 剩余时间按 12 秒一个 L1 区块估算，并且只算到挑战期结束，不含等待断言确认的时间（通常不到 1 小时）。
+///
+/// # 参数
+/// * `l1_provider` - L1 Provider
+/// * `l2_provider` - L2 Provider
+/// * `claim` - 提现的领取信息
+/// * `chunk_size` - 查找 `SendRootUpdated` 时每次查询的 L1 区块数
+///
+/// # 返回
+/// * `Result<WithdrawalStatus, Box<dyn Error>>` - 状态
+pub async fn withdrawal_status(
+    l1_provider: &RpcProvider,
+    l2_provider: &RpcProvider,
+    claim: &WithdrawalClaim,
+    chunk_size: u64,
+) -> Result<WithdrawalStatus, Box<dyn Error>> {
+    let outbox_address = Address::from_str(network::current().outbox_address())?;
+    let outbox = Contract::new(
+        outbox_address,
+        serde_json::from_str::<Abi>(OUTBOX_ABI)?,
+        Arc::new(l1_provider.clone()),
+    );
+    let leaf = leaf_index(claim)?;
+
+    let spent: bool = outbox.method("isSpent", claim.position)?.call().await?;
+    if spent {
+        return Ok(WithdrawalStatus::Executed);
+    }
+    let send_count =
+        confirmed_send_count(l1_provider, l2_provider, outbox_address, chunk_size).await?;
+    match send_count.filter(|count| leaf < *count) {
+        Some(send_count) => Ok(WithdrawalStatus::Claimable { send_count }),
+        None => {
+            let remaining_blocks = remaining_challenge_blocks(l1_provider, &outbox, claim).await?;
+            Ok(WithdrawalStatus::Pending { remaining_blocks })
+        }
+    }
+}
+
+/// 在 L1 Outbox 上执行挑战期已结束的提现
+///
+/// 先确认消息没有被执行过、已经包含在 Outbox 确认的状态里，再向 NodeInterface 要证明，
+/// 核对证明与保存的事件一致后发送 `executeTransaction`。还没确认时不发送交易，
+/// 返回挑战期剩余的区块数；估算 Gas 时 revert（例如确认状态刚好变化）也按未确认处理。
+///
+/// 需要在 `execute` span 内调用，连接成功后和广播后会把 chain_id / tx_hash 记录到这个 span 上。
+///
+/// # 参数
+/// * `l1_provider` - L1 Provider
+/// * `l2_provider` - L2 Provider
+/// * `private_key` - 私钥（从环境变量读取）
+/// * `claim` - 提现时保存的领取信息
+/// * `chunk_size` - 查找 `SendRootUpdated` 时每次查询的 L1 区块数
+/// * `shutdown` - Ctrl-C 关闭信号
+///
+/// # 返回
+/// * `Result<ExecuteOutcome, Box<dyn Error>>` - 执行结果
+pub async fn execute_withdrawal(
+    l1_provider: &RpcProvider,
+    l2_provider: &RpcProvider,
+    private_key: &str,
+    claim: &WithdrawalClaim,
+    chunk_size: u64,
+    shutdown: &Shutdown,
+) -> Result<ExecuteOutcome, Box<dyn Error>> {
+    // 1. 连接 L1
+    narrate!("{}", t(Msg::ExecuteStepConnect));
+    let chain_id = l1_provider
+        .get_chainid()
+        .instrument(info_span!("connect"))
+        .await?;
+    Span::current().record("chain_id", chain_id.as_u64());
+    narrate!("{}\n", style::ok(tf(Msg::Connected, &[&chain_id])));
+    network::check_l1_chain_id(chain_id.as_u64())?;
+
+    let outbox_address = Address::from_str(network::current().outbox_address())?;
+    let outbox = Contract::new(
+        outbox_address,
+        serde_json::from_str::<Abi>(OUTBOX_ABI)?,
+        Arc::new(l1_provider.clone()),
+    );
+    let leaf = leaf_index(claim)?;
+
+    // 2. 检查是否已执行、是否已确认
+    narrate!("{}", t(Msg::ExecuteStepCheck));
+    let send_count = match withdrawal_status(l1_provider, l2_provider, claim, chunk_size).await? {
+        WithdrawalStatus::Executed => return Ok(ExecuteOutcome::AlreadyExecuted),
+        WithdrawalStatus::Pending { remaining_blocks } => {
+            return Ok(ExecuteOutcome::NotConfirmed { remaining_blocks });
+        }
+        WithdrawalStatus::Claimable { send_count } => send_count,
+    };
+    narrate!("{}", style::ok(tf(Msg::ExecuteConfirmedCount, &[&send_count, &leaf])));
+
+    // 3. 构造证明并核对
+    narrate!("\n{}", t(Msg::ExecuteStepProof));
+    let proof = construct_proof(l2_provider, send_count, leaf).await?;
+    let args = execute_args(claim, &proof)?;
+    narrate!(
+        "{}",
+        style::ok(tf(
+            Msg::ExecuteProofReady,
+            &[&proof.proof.len(), &format!("{:?}", proof.root)]
+        ))
+    );
+
+    // 4. 发送 executeTransaction
+    narrate!("\n{}", t(Msg::ExecuteStepSend));
+    let wallet: LocalWallet = private_key.parse()?;
+    let client = Arc::new(SignerMiddleware::new(
+        l1_provider.clone(),
+        network::signing_wallet(wallet, chain_id.as_u64())?,
+    ));
+    let signed_outbox =
+        Contract::new(outbox_address, serde_json::from_str::<Abi>(OUTBOX_ABI)?, client);
+    let call = signed_outbox.method::<_, ()>("executeTransaction", args.as_slice())?;
+    match call.estimate_gas().await {
+        Ok(_) => {}
+        Err(e) if e.is_revert() => {
+            info!(error = %e, "executeTransaction reverted during estimation");
+            let remaining_blocks = remaining_challenge_blocks(l1_provider, &outbox, claim).await?;
+            return Ok(ExecuteOutcome::NotConfirmed { remaining_blocks });
+        }
+        Err(e) => return Err(e.into()),
+    }
+    let pending_tx = call
+        .send()
+        .instrument(info_span!("broadcast", position = %claim.position))
+        .await?;
+    let tx_hash = pending_tx.tx_hash();
+    Span::current().record("tx_hash", field::debug(tx_hash));
+    narrate!("{}", style::ok(tf(Msg::TxHashLine, &[&style::addr(format!("{:?}", tx_hash))])));
+
+    let receipt = tokio::select! {
+        receipt = pending_tx.instrument(info_span!("confirm")) => receipt?,
+        _ = shutdown.wait() => return Err(Interrupted.into()),
+    };
+    match receipt {
+        Some(receipt) if receipt.status == Some(1.into()) => {
+            Ok(ExecuteOutcome::Executed(receipt.transaction_hash))
+        }
+        Some(_) => Err(t(Msg::ExecuteReverted).into()),
+        None => Err(t(Msg::ExecuteNoReceipt).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use ethers::types::Bytes;
+    use ethers::utils::hex;
+    use serde_json::json;
+
+    const WALLET: &str = "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f";
+
+    fn claim() -> WithdrawalClaim {
+        let wallet = Address::from_str(WALLET).unwrap();
+        WithdrawalClaim {
+            l2_tx_hash: TxHash::repeat_byte(0xab),
+            caller: wallet,
+            destination: wallet,
+            hash: U256::from(0x5b1d),
+            position: U256::from(0x7c3e),
+            arb_block_num: U256::from(0xa4b0f1),
+            eth_block_num: U256::from(0x6c1f2a),
+            timestamp: U256::from(0x66b0a5c0_u64),
+            callvalue: U256::exp10(16),
+            data: Bytes::from(vec![0xde, 0xad]),
+        }
+    }
+
+    fn proof(send: u64) -> OutboxProof {
+        OutboxProof {
+            send: H256::from_low_u64_be(send),
+            root: H256::repeat_byte(0xee),
+            proof: vec![H256::repeat_byte(0x11), H256::repeat_byte(0x22)],
+        }
+    }
+
+    #[test]
+    fn leaf_index_requires_a_leaf_position() {
+        assert_eq!(leaf_index(&claim()).unwrap(), 0x7c3e);
+        let mut inner = claim();
+        inner.position = U256::from(1) << 192 | U256::from(5);
+        let error = leaf_index(&inner).unwrap_err();
+        assert_eq!(error.to_string(), tf(Msg::ExecuteBadPosition, &[&inner.position]));
+    }
+
+    // 按 executeTransaction 的参数顺序手工写出的 calldata（不含选择器）
+    #[test]
+    fn execute_args_follow_the_outbox_abi() {
+        let args = execute_args(&claim(), &proof(0x5b1d)).unwrap();
+        let abi: Abi = serde_json::from_str(OUTBOX_ABI).unwrap();
+        let call = abi.function("executeTransaction").unwrap().encode_input(&args).unwrap();
+        let word = |hex: &str| format!("{:0>64}", hex);
+        let expected = [
+            word("120"), // proof 的偏移
+            word("7c3e"), // index
+            word(&WALLET[2..]), // l2Sender
+            word(&WALLET[2..]), // to
+            word("a4b0f1"), // l2Block
+            word("6c1f2a"), // l1Block
+            word("66b0a5c0"), // l2Timestamp
+            word("2386f26fc10000"), // value
+            word("180"), // data 的偏移
+            word("2"), // proof 的长度
+            "11".repeat(32),
+            "22".repeat(32),
+            word("2"), // data 的长度
+            format!("{:0<64}", "dead"),
+        ]
+        .concat();
+        assert_eq!(hex::encode(&call[..4]), "08635a95");
+        assert_eq!(hex::encode(&call[4..]), expected);
+    }
+
+    #[test]
+    fn proof_for_another_message_is_rejected() {
+        let error = execute_args(&claim(), &proof(0x5b1e)).unwrap_err();
+        let send = format!("{:?}", H256::from_low_u64_be(0x5b1e));
+        let expected = format!("{:?}", H256::from_low_u64_be(0x5b1d));
+        assert_eq!(error.to_string(), tf(Msg::ExecuteProofMismatch, &[&send, &expected]));
+    }
+
+    #[tokio::test]
+    async fn construct_proof_decodes_node_interface_output() {
+        let word = |hex: &str| format!("{:0>64}", hex);
+        let output = [
+            word("5b1d"), // send
+            "ee".repeat(32), // root
+            word("60"), // proof 的偏移
+            word("2"),
+            "11".repeat(32),
+            "22".repeat(32),
+        ]
+        .concat();
+        let rpc = MockRpc::start(move |method, _| match method {
+            "eth_call" => Reply::Result(json!(format!("0x{}", output))),
+            _ => Reply::Error(format!("unexpected {}", method)),
+        });
+
+        let decoded = construct_proof(&rpc.provider(), 0x8000, 0x7c3e).await.unwrap();
+        assert_eq!(decoded.send, H256::from_low_u64_be(0x5b1d));
+        assert_eq!(decoded.root, H256::repeat_byte(0xee));
+        assert_eq!(decoded.proof, proof(0).proof);
+
+        // 调用参数是 (size, leaf)
+        let (_, params) = rpc.requests().pop().unwrap();
+        let input = params[0]["input"].as_str().or(params[0]["data"].as_str()).unwrap();
+        assert!(input.ends_with(&format!("{}{}", word("8000"), word("7c3e"))), "{}", input);
+    }
+}
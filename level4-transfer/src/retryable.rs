@@ -0,0 +1,1063 @@
+use arbi_common::narrate;
+use arbi_common::network;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::shutdown::{Interrupted, Shutdown};
+use arbi_common::style;
+use ethers::abi::{Abi, RawLog, Token};
+use ethers::prelude::*;
+use ethers::signers::LocalWallet;
+use ethers::types::{Address, Bytes, H256, TransactionReceipt, U256};
+use ethers::utils::{format_units, keccak256, rlp};
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{Instrument, Span, field, info, info_span};
+
+use crate::fee::NODE_INTERFACE_ADDRESS;
+use crate::get_balance;
+use crate::messages::{Msg, t, tf};
+
+// ArbRetryableTx 预编译合约地址
+const ARB_RETRYABLE_TX_ADDRESS: &str = "0x000000000000000000000000000000000000006E";
+
+// ArbitrumSubmitRetryableTx 的交易类型前缀，ticket ID 就是这种交易的哈希
+const SUBMIT_RETRYABLE_TX_TYPE: u8 = 0x69;
+
+// 提交费在估算值上加的余量（百分比），避免 L1 base fee 上涨导致 InsufficientSubmissionCost
+const SUBMISSION_FEE_MARGIN_PERCENT: u64 = 30;
+
+// Inbox 中创建 retryable ticket 用到的 ABI 片段
+const INBOX_ABI: &str = r#"[
+    {
+        "inputs": [
+            {"name": "to", "type": "address"},
+            {"name": "l2CallValue", "type": "uint256"},
+            {"name": "maxSubmissionCost", "type": "uint256"},
+            {"name": "excessFeeRefundAddress", "type": "address"},
+            {"name": "callValueRefundAddress", "type": "address"},
+            {"name": "gasLimit", "type": "uint256"},
+            {"name": "maxFeePerGas", "type": "uint256"},
+            {"name": "data", "type": "bytes"}
+        ],
+        "name": "createRetryableTicket",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "payable",
+        "type": "function"
+    },
+    {
+        "inputs": [
+            {"name": "dataLength", "type": "uint256"},
+            {"name": "baseFee", "type": "uint256"}
+        ],
+        "name": "calculateRetryableSubmissionFee",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "view",
+        "type": "function"
+    }
+]"#;
+
+// NodeInterface 虚拟合约中估算 retryable ticket L2 执行 Gas 的方法，只能通过 eth_estimateGas 调用
+const NODE_INTERFACE_RETRYABLE_ABI: &str = r#"[
+    {
+        "inputs": [
+            {"name": "sender", "type": "address"},
+            {"name": "deposit", "type": "uint256"},
+            {"name": "to", "type": "address"},
+            {"name": "l2CallValue", "type": "uint256"},
+            {"name": "excessFeeRefundAddress", "type": "address"},
+            {"name": "callValueRefundAddress", "type": "address"},
+            {"name": "data", "type": "bytes"}
+        ],
+        "name": "estimateRetryableTicket",
+        "outputs": [],
+        "stateMutability": "nonpayable",
+        "type": "function"
+    }
+]"#;
+
+// 估算 L2 Gas 时假设附带的 ETH 比 L2 调用金额多出 1 ETH，保证估算时发送方余额充足
+const ESTIMATE_DEPOSIT_PADDING_WEI: u64 = 1_000_000_000_000_000_000;
+
+// Bridge 合约在 Inbox 提交消息时发出的事件，包含消息序号、别名后的发送方和 L1 base fee
+const BRIDGE_EVENTS_ABI: &str = r#"[
+    {
+        "anonymous": false,
+        "inputs": [
+            {"indexed": true, "name": "messageIndex", "type": "uint256"},
+            {"indexed": true, "name": "beforeInboxAcc", "type": "bytes32"},
+            {"indexed": false, "name": "inbox", "type": "address"},
+            {"indexed": false, "name": "kind", "type": "uint8"},
+            {"indexed": false, "name": "sender", "type": "address"},
+            {"indexed": false, "name": "messageDataHash", "type": "bytes32"},
+            {"indexed": false, "name": "baseFeeL1", "type": "uint256"},
+            {"indexed": false, "name": "timestamp", "type": "uint64"}
+        ],
+        "name": "MessageDelivered",
+        "type": "event"
+    }
+]"#;
+
+// ArbRetryableTx 预编译合约的 ABI 片段
+const ARB_RETRYABLE_TX_ABI: &str = r#"[
+    {
+        "inputs": [{"name": "ticketId", "type": "bytes32"}],
+        "name": "redeem",
+        "outputs": [{"name": "", "type": "bytes32"}],
+        "stateMutability": "nonpayable",
+        "type": "function"
+    },
+    {
+        "inputs": [{"name": "ticketId", "type": "bytes32"}],
+        "name": "getTimeout",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [{"name": "ticketId", "type": "bytes32"}],
+        "name": "getBeneficiary",
+        "outputs": [{"name": "", "type": "address"}],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [{"name": "ticketId", "type": "bytes32"}],
+        "name": "keepalive",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "nonpayable",
+        "type": "function"
+    },
+    {
+        "inputs": [],
+        "name": "getLifetime",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "view",
+        "type": "function"
+    }
+]"#;
+
+/// 创建 retryable ticket 的参数
+pub struct RetryableParams {
+    /// L2 上的目标地址
+    pub to: Address,
+    /// 随 L2 调用转过去的 ETH（wei）
+    pub l2_call_value: U256,
+    /// L2 执行的 Gas 限额
+    pub gas_limit: U256,
+    /// L2 执行的最高 Gas 价格（wei）
+    pub max_fee_per_gas: U256,
+    /// L2 调用的 calldata
+    pub data: Bytes,
+}
+
+/// 计算需要随 `createRetryableTicket` 附带的 L1 ETH
+///
+/// L1 上附带的 ETH 必须覆盖：L2 调用转账的金额 + 提交费 + L2 执行的最高 Gas 费。
+///
+/// # 参数
+/// * `l2_call_value` - L2 调用转账的金额
+/// * `max_submission_cost` - 最高提交费
+/// * `gas_limit` - L2 Gas 限额
+/// * `max_fee_per_gas` - L2 最高 Gas 价格
+///
+/// # 返回
+/// * `U256` - 需要附带的金额（wei）
+pub fn required_deposit(
+    l2_call_value: U256,
+    max_submission_cost: U256,
+    gas_limit: U256,
+    max_fee_per_gas: U256,
+) -> U256 {
+    l2_call_value + max_submission_cost + gas_limit * max_fee_per_gas
+}
+
+/// 在估算的提交费上加余量
+///
+/// # 参数
+/// * `estimated` - `calculateRetryableSubmissionFee` 返回的提交费
+///
+/// # 返回
+/// * `U256` - 实际使用的最高提交费
+pub fn with_submission_margin(estimated: U256) -> U256 {
+    estimated * (100 + SUBMISSION_FEE_MARGIN_PERCENT) / 100
+}
+
+/// 创建 retryable ticket 前的费用估算结果
+pub struct RetryableEstimate {
+    /// calldata 的字节数，提交费按它计算
+    pub data_length: usize,
+    /// 估算时的 L1 base fee（wei）
+    pub l1_base_fee: U256,
+    /// 加上余量后的最高提交费（wei）
+    pub max_submission_cost: U256,
+    /// 建议的 L2 Gas 限额
+    pub gas_limit: U256,
+    /// L2 最高 Gas 价格（wei）
+    pub max_fee_per_gas: U256,
+    /// 需要随 `createRetryableTicket` 附带的 L1 ETH（wei）
+    pub deposit: U256,
+}
+
+impl RetryableEstimate {
+    /// 把 L1 提交费和 L2 Gas 估算汇总成需要附带的 ETH
+    ///
+    /// 提交费加上与 `create_ticket` 相同的余量，附带金额用 `required_deposit` 计算，
+    /// 两处得出的数字保持一致。
+    ///
+    /// # 参数
+    /// * `data_length` - calldata 的字节数
+    /// * `l1_base_fee` - 估算时的 L1 base fee
+    /// * `submission_fee` - `calculateRetryableSubmissionFee` 返回的提交费
+    /// * `gas_limit` - `estimateRetryableTicket` 估算出的 L2 Gas
+    /// * `max_fee_per_gas` - L2 最高 Gas 价格
+    /// * `l2_call_value` - L2 调用转账的金额
+    ///
+    /// # 返回
+    /// * `RetryableEstimate` - 汇总后的估算结果
+    pub fn new(
+        data_length: usize,
+        l1_base_fee: U256,
+        submission_fee: U256,
+        gas_limit: U256,
+        max_fee_per_gas: U256,
+        l2_call_value: U256,
+    ) -> Self {
+        let max_submission_cost = with_submission_margin(submission_fee);
+        let deposit =
+            required_deposit(l2_call_value, max_submission_cost, gas_limit, max_fee_per_gas);
+        Self {
+            data_length,
+            l1_base_fee,
+            max_submission_cost,
+            gas_limit,
+            max_fee_per_gas,
+            deposit,
+        }
+    }
+
+    /// L2 执行最多花费的 Gas 费（wei）
+    pub fn max_gas_fee(&self) -> U256 {
+        self.gas_limit * self.max_fee_per_gas
+    }
+}
+
+/// 读取 L1 最新区块的 base fee，并按 calldata 长度计算提交费（不含余量）
+///
+/// # 参数
+/// * `l1_provider` - L1 Provider
+/// * `inbox` - Inbox 合约实例
+/// * `data_length` - calldata 的字节数
+///
+/// # 返回
+/// * `Result<(U256, U256), Box<dyn Error>>` - L1 base fee 和提交费
+async fn submission_fee<M: Middleware + 'static>(
+    l1_provider: &RpcProvider,
+    inbox: &Contract<M>,
+    data_length: usize,
+) -> Result<(U256, U256), Box<dyn Error>> {
+    let l1_base_fee = l1_provider
+        .get_block(BlockNumber::Latest)
+        .await?
+        .and_then(|block| block.base_fee_per_gas)
+        .unwrap_or_default();
+    let fee: U256 = inbox
+        .method("calculateRetryableSubmissionFee", (U256::from(data_length), l1_base_fee))?
+        .call()
+        .instrument(info_span!("estimate"))
+        .await?;
+    Ok((l1_base_fee, fee))
+}
+
+/// 估算创建 retryable ticket 需要的提交费、L2 Gas 和附带的 L1 ETH，不发送交易
+///
+/// 提交费在 L1 上通过 Inbox 的 `calculateRetryableSubmissionFee` 按 calldata 长度计算；
+/// L2 Gas 通过 NodeInterface 的 `estimateRetryableTicket` 估算，节点会模拟 ticket 在 L2 上的
+/// 自动执行（发送方按 L1 地址传入，节点内部会做别名转换）。
+///
+/// # 参数
+/// * `l1_provider` - L1 Provider
+/// * `l2_provider` - L2 Provider
+/// * `from` - L1 上的发送方，同时作为两个退款地址
+/// * `to` - L2 上的目标地址
+/// * `l2_call_value` - L2 调用转账的金额
+/// * `data` - L2 调用的 calldata
+/// * `max_fee_per_gas` - L2 最高 Gas 价格
+///
+/// # 返回
+/// * `Result<RetryableEstimate, Box<dyn Error>>` - 汇总后的估算结果
+pub async fn estimate_ticket(
+    l1_provider: &RpcProvider,
+    l2_provider: &RpcProvider,
+    from: Address,
+    to: Address,
+    l2_call_value: U256,
+    data: Bytes,
+    max_fee_per_gas: U256,
+) -> Result<RetryableEstimate, Box<dyn Error>> {
+    let inbox = Contract::new(
+        Address::from_str(network::current().inbox_address())?,
+        serde_json::from_str::<Abi>(INBOX_ABI)?,
+        Arc::new(l1_provider.clone()),
+    );
+    let (l1_base_fee, fee) = submission_fee(l1_provider, &inbox, data.len()).await?;
+
+    let node_interface = Contract::new(
+        Address::from_str(NODE_INTERFACE_ADDRESS)?,
+        serde_json::from_str::<Abi>(NODE_INTERFACE_RETRYABLE_ABI)?,
+        Arc::new(l2_provider.clone()),
+    );
+    let data_length = data.len();
+    let deposit = l2_call_value + U256::from(ESTIMATE_DEPOSIT_PADDING_WEI);
+    let gas_limit = node_interface
+        .method::<_, ()>(
+            "estimateRetryableTicket",
+            (from, deposit, to, l2_call_value, from, from, data),
+        )?
+        .from(from)
+        .estimate_gas()
+        .instrument(info_span!("estimate_l2_gas"))
+        .await?;
+
+    Ok(RetryableEstimate::new(
+        data_length,
+        l1_base_fee,
+        fee,
+        gas_limit,
+        max_fee_per_gas,
+        l2_call_value,
+    ))
+}
+
+/// 计算 retryable ticket ID
+///
+/// ticket ID 是 L2 上 ArbitrumSubmitRetryableTx 交易的哈希：`keccak256(0x69 || rlp([...]))`，
+/// 字段顺序与 Nitro 中该交易类型的定义一致。`to` 为零地址时编码为空（合约创建）。
+///
+/// # 参数
+/// * `l2_chain_id` - L2 链 ID
+/// * `message_number` - Bridge 分配的消息序号
+/// * `from` - 别名后的发送方（`MessageDelivered` 事件中的 sender）
+/// * `l1_base_fee` - 消息提交时的 L1 base fee
+/// * `deposit_value` - L1 上附带的 ETH
+/// * `max_submission_cost` - 最高提交费
+/// * `fee_refund_address` - 多余费用的退款地址（excessFeeRefundAddress）
+/// * `beneficiary` - L2 调用金额的退款地址，也是可以取消 ticket 的地址（callValueRefundAddress）
+/// * `params` - 创建 ticket 的参数
+///
+/// # 返回
+/// * `H256` - ticket ID
+#[allow(clippy::too_many_arguments)]
+pub fn ticket_id(
+    l2_chain_id: U256,
+    message_number: U256,
+    from: Address,
+    l1_base_fee: U256,
+    deposit_value: U256,
+    max_submission_cost: U256,
+    fee_refund_address: Address,
+    beneficiary: Address,
+    params: &RetryableParams,
+) -> H256 {
+    let mut request_id = [0u8; 32];
+    message_number.to_big_endian(&mut request_id);
+
+    let mut stream = rlp::RlpStream::new_list(13);
+    stream.append(&l2_chain_id);
+    stream.append(&request_id.as_slice());
+    stream.append(&from);
+    stream.append(&l1_base_fee);
+    stream.append(&deposit_value);
+    stream.append(&params.max_fee_per_gas);
+    stream.append(&params.gas_limit);
+    if params.to.is_zero() {
+        stream.append_empty_data();
+    } else {
+        stream.append(&params.to);
+    }
+    stream.append(&params.l2_call_value);
+    stream.append(&beneficiary);
+    stream.append(&max_submission_cost);
+    stream.append(&fee_refund_address);
+    stream.append(&params.data.as_ref());
+
+    let mut encoded = vec![SUBMIT_RETRYABLE_TX_TYPE];
+    encoded.extend_from_slice(&stream.out());
+    H256::from(keccak256(encoded))
+}
+
+/// Bridge `MessageDelivered` 事件中的消息信息
+pub struct DeliveredMessage {
+    pub message_number: U256,
+    /// 消息类型（9 为 retryable ticket，12 为 ETH 充值）
+    pub kind: u8,
+    pub sender: Address,
+    pub l1_base_fee: U256,
+    /// 消息进入 Bridge 时的 L1 时间戳（Unix 秒）
+    pub timestamp: u64,
+}
+
+/// 从 L1 收据中找出 Bridge 的 `MessageDelivered` 事件
+///
+/// # 参数
+/// * `receipt` - L1 交易收据
+///
+/// # 返回
+/// * `Result<DeliveredMessage, Box<dyn Error>>` - 消息信息；收据中没有该事件时返回错误
+pub fn delivered_message(
+    receipt: &TransactionReceipt,
+) -> Result<DeliveredMessage, Box<dyn Error>> {
+    let abi: Abi = serde_json::from_str(BRIDGE_EVENTS_ABI)?;
+    let event = abi.event("MessageDelivered")?;
+    let signature = event.signature();
+
+    let log = receipt
+        .logs
+        .iter()
+        .find(|log| log.topics.first() == Some(&signature))
+        .ok_or_else(|| t(Msg::RetryableNoMessage))?;
+    let parsed = event.parse_log(RawLog {
+        topics: log.topics.clone(),
+        data: log.data.to_vec(),
+    })?;
+    let value = |name: &str| {
+        parsed
+            .params
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.value.clone())
+    };
+    let uint = |name: &str| value(name).and_then(|v| v.into_uint());
+
+    let sender = value("sender").and_then(|v| v.into_address());
+
+    match (
+        uint("messageIndex"),
+        uint("kind"),
+        sender,
+        uint("baseFeeL1"),
+        uint("timestamp"),
+    ) {
+        (Some(message_number), Some(kind), Some(sender), Some(l1_base_fee), Some(timestamp)) => {
+            Ok(DeliveredMessage {
+                message_number,
+                kind: kind.low_u32() as u8,
+                sender,
+                l1_base_fee,
+                timestamp: timestamp.as_u64(),
+            })
+        }
+        _ => Err(t(Msg::RetryableNoMessage).into()),
+    }
+}
+
+/// 在 L1 Inbox 上创建 retryable ticket
+///
+/// 提交费通过 `calculateRetryableSubmissionFee` 按当前 L1 base fee 估算并加上余量；
+/// 交易确认后从 Bridge 的 `MessageDelivered` 事件中取出消息序号，计算出 ticket ID。
+///
+/// 需要在 `retryable` span 内调用，广播后会把 tx_hash 记录到这个 span 上。
+///
+/// # 参数
+/// * `l1_provider` - L1 Provider
+/// * `l2_provider` - L2 Provider（读取 L2 链 ID）
+/// * `private_key` - 私钥（从环境变量读取）
+/// * `params` - 创建 ticket 的参数
+/// * `shutdown` - Ctrl-C 关闭信号
+///
+/// # 返回
+/// * `Result<(TxHash, H256), Box<dyn Error>>` - L1 交易哈希和 ticket ID
+pub async fn create_ticket(
+    l1_provider: &RpcProvider,
+    l2_provider: &RpcProvider,
+    private_key: &str,
+    params: &RetryableParams,
+    shutdown: &Shutdown,
+) -> Result<(TxHash, H256), Box<dyn Error>> {
+    // 1. 连接 L1 / L2
+    narrate!("{}", t(Msg::DepositStepConnect));
+    let (l1_chain_id, l2_chain_id) = async {
+        let l1_chain_id = l1_provider.get_chainid().await?;
+        let l2_chain_id = l2_provider.get_chainid().await?;
+        Ok::<_, Box<dyn Error>>((l1_chain_id, l2_chain_id))
+    }
+    .instrument(info_span!("connect"))
+    .await?;
+    Span::current().record("chain_id", l2_chain_id.as_u64());
+    narrate!("{}", style::ok(tf(Msg::Connected, &[&l1_chain_id])));
+    network::check_l1_chain_id(l1_chain_id.as_u64())?;
+    network::check_chain_id(l2_chain_id.as_u64())?;
+
+    let wallet: LocalWallet = private_key.parse()?;
+    let from_address = wallet.address();
+    let client = Arc::new(SignerMiddleware::new(
+        l1_provider.clone(),
+        network::signing_wallet(wallet, l1_chain_id.as_u64())?,
+    ));
+    let inbox = Contract::new(
+        Address::from_str(network::current().inbox_address())?,
+        serde_json::from_str::<Abi>(INBOX_ABI)?,
+        client,
+    );
+    narrate!("{}", style::ok(tf(Msg::FromAddress, &[&style::addr(from_address)])));
+
+    // 2. 估算提交费，计算需要附带的 ETH
+    narrate!("\n{}", t(Msg::RetryableStepFee));
+    let (_, estimated) = submission_fee(l1_provider, &inbox, params.data.len()).await?;
+    let max_submission_cost = with_submission_margin(estimated);
+    let deposit = required_deposit(
+        params.l2_call_value,
+        max_submission_cost,
+        params.gas_limit,
+        params.max_fee_per_gas,
+    );
+    narrate!(
+        "{}",
+        style::ok(tf(
+            Msg::RetryableFees,
+            &[
+                &format_units(max_submission_cost, "ether")?,
+                &format_units(params.gas_limit * params.max_fee_per_gas, "ether")?,
+                &format_units(deposit, "ether")?,
+            ]
+        ))
+    );
+
+    // 3. 检查 L1 余额
+    let call = inbox
+        .method::<_, U256>(
+            "createRetryableTicket",
+            (
+                params.to,
+                params.l2_call_value,
+                max_submission_cost,
+                from_address,
+                from_address,
+                params.gas_limit,
+                params.max_fee_per_gas,
+                params.data.clone(),
+            ),
+        )?
+        .value(deposit);
+    let gas_fee = call.estimate_gas().await? * l1_provider.get_gas_price().await?;
+    let balance = get_balance(l1_provider, from_address).await?;
+    if balance < deposit + gas_fee {
+        return Err(crate::messages::insufficient_balance(
+            &format_units(deposit + gas_fee, "ether")?,
+            &format_units(deposit, "ether")?,
+            &format_units(gas_fee, "ether")?,
+            &format_units(balance, "ether")?,
+        )
+        .into());
+    }
+    narrate!("{}", style::ok(tf(Msg::DepositL1Balance, &[&format_units(balance, "ether")?])));
+
+    // 4. 发送并等待 L1 确认
+    narrate!("\n{}", t(Msg::RetryableStepSend));
+    let pending_tx = call
+        .send()
+        .instrument(info_span!("broadcast", to = ?params.to, value_wei = %deposit))
+        .await?;
+    let l1_tx_hash = pending_tx.tx_hash();
+    Span::current().record("tx_hash", field::debug(l1_tx_hash));
+    info!("retryable ticket submitted on L1");
+    narrate!("{}", style::ok(tf(Msg::TxHashLine, &[&style::addr(format!("{:?}", l1_tx_hash))])));
+
+    let receipt = tokio::select! {
+        receipt = pending_tx.instrument(info_span!("confirm")) => receipt?,
+        _ = shutdown.wait() => return Err(Interrupted.into()),
+    };
+    let receipt = match receipt {
+        Some(receipt) if receipt.status == Some(1.into()) => receipt,
+        Some(_) => return Err(t(Msg::DepositL1Reverted).into()),
+        None => return Err(t(Msg::DepositL1NoReceipt).into()),
+    };
+
+    // 5. 由 MessageDelivered 事件计算 ticket ID
+    let message = delivered_message(&receipt)?;
+    let id = ticket_id(
+        l2_chain_id,
+        message.message_number,
+        message.sender,
+        message.l1_base_fee,
+        deposit,
+        max_submission_cost,
+        from_address,
+        from_address,
+        params,
+    );
+    info!(ticket_id = ?id, message_number = %message.message_number, "retryable ticket created");
+    Ok((l1_tx_hash, id))
+}
+
+/// retryable ticket 的状态
+pub enum TicketStatus {
+    /// 还可以手动 redeem，`timeout` 为过期时间（Unix 秒）
+    Pending { timeout: u64 },
+    /// 已执行成功（自动或手动 redeem），ticket 已被删除
+    Redeemed,
+    /// 超过有效期未被 redeem，资金退回到 callValueRefundAddress
+    Expired,
+    /// L2 上查不到这个 ticket
+    NotFound,
+}
+
+/// 当前 Unix 时间（秒）
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// 查询 retryable ticket 的状态
+///
+/// `getTimeout` 只对还存在的 ticket 返回过期时间；ticket 被 redeem 或过期清理后调用会 revert，
+/// 这时再按 ticket 创建时间加上有效期（`getLifetime`）区分"已执行"和"已过期"。
+///
+/// # 参数
+/// * `provider` - L2 Provider
+/// * `ticket_id` - ticket ID
+///
+/// # 返回
+/// * `Result<TicketStatus, Box<dyn Error>>` - ticket 状态
+pub async fn ticket_status(
+    provider: &RpcProvider,
+    ticket_id: H256,
+) -> Result<TicketStatus, Box<dyn Error>> {
+    let client = Arc::new(provider.clone());
+    let contract = Contract::new(
+        Address::from_str(ARB_RETRYABLE_TX_ADDRESS)?,
+        serde_json::from_str::<Abi>(ARB_RETRYABLE_TX_ABI)?,
+        client,
+    );
+
+    match contract.method::<_, U256>("getTimeout", ticket_id.0)?.call().await {
+        Ok(timeout) if timeout.as_u64() > now_secs() => {
+            return Ok(TicketStatus::Pending {
+                timeout: timeout.as_u64(),
+            });
+        }
+        Ok(_) => return Ok(TicketStatus::Expired),
+        Err(e) if e.is_revert() => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    // ticket 已不存在：看它是什么时候创建的
+    let Some(receipt) = provider.get_transaction_receipt(ticket_id).await? else {
+        return Ok(TicketStatus::NotFound);
+    };
+    let created_at = match receipt.block_number {
+        Some(number) => provider
+            .get_block(number)
+            .await?
+            .map(|block| block.timestamp.as_u64())
+            .unwrap_or_default(),
+        None => return Ok(TicketStatus::NotFound),
+    };
+    let lifetime: U256 = contract.method("getLifetime", ())?.call().await?;
+
+    if created_at + lifetime.as_u64() < now_secs() {
+        Ok(TicketStatus::Expired)
+    } else {
+        Ok(TicketStatus::Redeemed)
+    }
+}
+
+/// 仍存在于 L2 上的 ticket 的有效期信息
+pub struct TicketInfo {
+    /// 过期时间（Unix 秒）
+    pub timeout: u64,
+    /// 最新 L2 区块的时间（Unix 秒），用它而不是本机时间判断是否过期
+    pub now: u64,
+    /// 可以 cancel 这个 ticket 的受益人（callValueRefundAddress）
+    pub beneficiary: Address,
+    /// 每次 keepalive 延长的时间（秒）
+    pub lifetime: u64,
+    /// keepalive 交易的 Gas 估算；ArbRetryableTx 拒绝延长时为 `None`
+    pub keepalive_gas: Option<U256>,
+}
+
+impl TicketInfo {
+    /// 距离过期还剩的秒数
+    ///
+    /// # 返回
+    /// * `Option<u64>` - 剩余秒数，已过期时为 `None`
+    pub fn remaining(&self) -> Option<u64> {
+        self.timeout.checked_sub(self.now).filter(|secs| *secs > 0)
+    }
+}
+
+/// `ticket_info` 的结果：ticket 还在，或者已经不在了以及原因
+pub enum TicketLookup {
+    Live(TicketInfo),
+    Gone(TicketStatus),
+}
+
+/// 查询 ticket 的过期时间、受益人和 keepalive 费用
+///
+/// `getTimeout` / `getBeneficiary` 对已执行或已清理的 ticket 会 revert，这时交给
+/// `ticket_status` 区分"已执行"、"已过期"和"查不到"。keepalive 只能在过期时间距现在不到
+/// 一个有效期时调用，否则 Gas 估算会 revert，记为不可延长。
+///
+/// # 参数
+/// * `provider` - L2 Provider
+/// * `ticket_id` - ticket ID
+///
+/// # 返回
+/// * `Result<TicketLookup, Box<dyn Error>>` - ticket 信息或它不在的原因
+pub async fn ticket_info(
+    provider: &RpcProvider,
+    ticket_id: H256,
+) -> Result<TicketLookup, Box<dyn Error>> {
+    let contract = Contract::new(
+        Address::from_str(ARB_RETRYABLE_TX_ADDRESS)?,
+        serde_json::from_str::<Abi>(ARB_RETRYABLE_TX_ABI)?,
+        Arc::new(provider.clone()),
+    );
+
+    let timeout = match contract.method::<_, U256>("getTimeout", ticket_id.0)?.call().await {
+        Ok(timeout) => timeout.as_u64(),
+        Err(e) if e.is_revert() => {
+            return Ok(TicketLookup::Gone(ticket_status(provider, ticket_id).await?));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let beneficiary: Address = contract.method("getBeneficiary", ticket_id.0)?.call().await?;
+    let lifetime: U256 = contract.method("getLifetime", ())?.call().await?;
+    let now = provider
+        .get_block(BlockNumber::Latest)
+        .await?
+        .map(|block| block.timestamp.as_u64())
+        .unwrap_or_else(now_secs);
+    let keepalive_gas = match contract
+        .method::<_, U256>("keepalive", ticket_id.0)?
+        .estimate_gas()
+        .await
+    {
+        Ok(gas) => Some(gas),
+        Err(e) if e.is_revert() => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(TicketLookup::Live(TicketInfo {
+        timeout,
+        now,
+        beneficiary,
+        lifetime: lifetime.as_u64(),
+        keepalive_gas,
+    }))
+}
+
+/// 用私钥签名并发送一笔 ArbRetryableTx 调用，等待收据
+async fn send_ticket_call(
+    provider: &RpcProvider,
+    private_key: &str,
+    method: &str,
+    ticket_id: H256,
+    shutdown: &Shutdown,
+) -> Result<TransactionReceipt, Box<dyn Error>> {
+    let chain_id = provider
+        .get_chainid()
+        .instrument(info_span!("connect"))
+        .await?;
+    Span::current().record("chain_id", chain_id.as_u64());
+    network::check_chain_id(chain_id.as_u64())?;
+
+    let wallet: LocalWallet = private_key.parse()?;
+    let client = Arc::new(SignerMiddleware::new(
+        provider.clone(),
+        network::signing_wallet(wallet, chain_id.as_u64())?,
+    ));
+    let contract = Contract::new(
+        Address::from_str(ARB_RETRYABLE_TX_ADDRESS)?,
+        serde_json::from_str::<Abi>(ARB_RETRYABLE_TX_ABI)?,
+        client,
+    );
+
+    let call = contract.method::<_, Token>(method, ticket_id.0)?;
+    let pending_tx = call
+        .send()
+        .instrument(info_span!("broadcast", ticket_id = ?ticket_id))
+        .await?;
+    let tx_hash = pending_tx.tx_hash();
+    Span::current().record("tx_hash", field::debug(tx_hash));
+    narrate!("{}", style::ok(tf(Msg::TxHashLine, &[&style::addr(format!("{:?}", tx_hash))])));
+
+    let receipt = tokio::select! {
+        receipt = pending_tx.instrument(info_span!("confirm")) => receipt?,
+        _ = shutdown.wait() => return Err(Interrupted.into()),
+    };
+    receipt.ok_or_else(|| t(Msg::RetryableNoReceipt).into())
+}
+
+/// 在 L2 上为 ticket 调用 keepalive，把过期时间延长一个有效期
+///
+/// # 参数
+/// * `provider` - L2 Provider
+/// * `private_key` - 私钥（从环境变量读取）
+/// * `ticket_id` - ticket ID
+/// * `shutdown` - Ctrl-C 关闭信号
+///
+/// # 返回
+/// * `Result<TransactionReceipt, Box<dyn Error>>` - keepalive 交易的收据
+pub async fn keepalive_ticket(
+    provider: &RpcProvider,
+    private_key: &str,
+    ticket_id: H256,
+    shutdown: &Shutdown,
+) -> Result<TransactionReceipt, Box<dyn Error>> {
+    narrate!("{}", tf(Msg::RetryableKeepingAlive, &[&style::addr(format!("{:?}", ticket_id))]));
+    send_ticket_call(provider, private_key, "keepalive", ticket_id, shutdown).await
+}
+
+/// 在 L2 上手动 redeem 自动执行失败的 ticket
+///
+/// # 参数
+/// * `provider` - L2 Provider
+/// * `private_key` - 私钥（从环境变量读取）
+/// * `ticket_id` - ticket ID
+/// * `shutdown` - Ctrl-C 关闭信号
+///
+/// # 返回
+/// * `Result<TransactionReceipt, Box<dyn Error>>` - redeem 交易的收据
+pub async fn redeem_ticket(
+    provider: &RpcProvider,
+    private_key: &str,
+    ticket_id: H256,
+    shutdown: &Shutdown,
+) -> Result<TransactionReceipt, Box<dyn Error>> {
+    narrate!("{}", tf(Msg::RetryableRedeeming, &[&style::addr(format!("{:?}", ticket_id))]));
+    send_ticket_call(provider, private_key, "redeem", ticket_id, shutdown).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use serde_json::{Value, json};
+
+    fn gwei(n: u64) -> U256 {
+        U256::from(n) * U256::exp10(9)
+    }
+
+    #[test]
+    fn required_deposit_covers_value_submission_and_gas() {
+        let one_eth = U256::exp10(18);
+        let deposit = required_deposit(one_eth, gwei(300_000), U256::from(100_000), gwei(1));
+        assert_eq!(deposit, one_eth + gwei(300_000) + gwei(100_000));
+
+        let zero = U256::zero();
+        assert_eq!(required_deposit(zero, zero, U256::from(21_000), zero), zero);
+    }
+
+    #[test]
+    fn submission_margin_adds_thirty_percent_rounding_down() {
+        assert_eq!(with_submission_margin(U256::from(1_000)), U256::from(1_300));
+        assert_eq!(with_submission_margin(U256::from(7)), U256::from(9));
+        assert_eq!(with_submission_margin(U256::zero()), U256::zero());
+    }
+
+    // 按 Nitro 中 ArbitrumSubmitRetryableTx 的字段顺序逐个写出 RLP 编码，与 ticket_id 的结果对照
+    #[test]
+    fn ticket_id_hashes_submit_retryable_tx() {
+        let address = |byte: u8| Address::repeat_byte(byte);
+        let params = RetryableParams {
+            to: address(0x22),
+            l2_call_value: U256::zero(),
+            gas_limit: U256::from(100_000),
+            max_fee_per_gas: gwei(1) / 10,
+            data: Bytes::from(vec![0xde, 0xad]),
+        };
+        let id = ticket_id(
+            U256::from(42_161),
+            U256::from(1),
+            address(0x11),
+            gwei(1),
+            U256::from(0x1000),
+            U256::from(0x1000),
+            address(0x44),
+            address(0x33),
+            &params,
+        );
+
+        let preimage = ethers::utils::hex::decode(concat!(
+            "69",
+            "f890",
+            "82a4b1", // chainId
+            "a00000000000000000000000000000000000000000000000000000000000000001", // requestId
+            "941111111111111111111111111111111111111111", // from
+            "843b9aca00", // l1BaseFee
+            "821000", // deposit
+            "8405f5e100", // gasFeeCap
+            "830186a0", // gas
+            "942222222222222222222222222222222222222222", // retryTo
+            "80", // retryValue
+            "943333333333333333333333333333333333333333", // beneficiary
+            "821000", // maxSubmissionFee
+            "944444444444444444444444444444444444444444", // feeRefundAddr
+            "82dead", // retryData
+        ))
+        .unwrap();
+        assert_eq!(id, H256::from(keccak256(preimage)));
+
+        // to 为零地址时编码为空字符串（合约创建），ID 随之改变
+        let create = RetryableParams { to: Address::zero(), ..params };
+        let create_id = ticket_id(
+            U256::from(42_161),
+            U256::from(1),
+            address(0x11),
+            gwei(1),
+            U256::from(0x1000),
+            U256::from(0x1000),
+            address(0x44),
+            address(0x33),
+            &create,
+        );
+        assert_ne!(create_id, id);
+    }
+
+    const LIFETIME: u64 = 7 * 24 * 3600;
+
+    fn ticket_info_at(timeout: u64, now: u64) -> TicketInfo {
+        TicketInfo {
+            timeout,
+            now,
+            beneficiary: Address::zero(),
+            lifetime: LIFETIME,
+            keepalive_gas: None,
+        }
+    }
+
+    #[test]
+    fn remaining_lifetime_counts_down_to_expiry() {
+        assert_eq!(ticket_info_at(1_000 + LIFETIME, 1_000).remaining(), Some(LIFETIME));
+        assert_eq!(ticket_info_at(1_001, 1_000).remaining(), Some(1));
+        // 正好到期和已经过期都算过期
+        assert_eq!(ticket_info_at(1_000, 1_000).remaining(), None);
+        assert_eq!(ticket_info_at(999, 1_000).remaining(), None);
+    }
+
+    // ArbRetryableTx 的一个状态：`timeout` 为 None 时 getTimeout revert（ticket 已不存在），
+    // `created_at` 为 None 时查不到 ticket 的收据
+    struct Ticket {
+        timeout: Option<u64>,
+        created_at: Option<u64>,
+        keepalive: bool,
+    }
+
+    fn ticket(timeout: Option<u64>, created_at: Option<u64>, keepalive: bool) -> Ticket {
+        Ticket {
+            timeout,
+            created_at,
+            keepalive,
+        }
+    }
+
+    fn word(value: u64) -> String {
+        format!("0x{:064x}", value)
+    }
+
+    fn selector(signature: &str) -> String {
+        format!("0x{}", ethers::utils::hex::encode(&keccak256(signature)[..4]))
+    }
+
+    fn node(ticket: Ticket) -> MockRpc {
+        let get_timeout = selector("getTimeout(bytes32)");
+        let get_lifetime = selector("getLifetime()");
+        let get_beneficiary = selector("getBeneficiary(bytes32)");
+        let reverted = || Reply::Error("execution reverted".into());
+        MockRpc::start(move |method, params| {
+            let call = &params[0];
+            let data = call["input"].as_str().or(call["data"].as_str()).unwrap_or_default();
+            match method {
+                "eth_call" if data.starts_with(&get_timeout) => match ticket.timeout {
+                    Some(timeout) => Reply::Result(json!(word(timeout))),
+                    None => reverted(),
+                },
+                "eth_call" if data.starts_with(&get_lifetime) => {
+                    Reply::Result(json!(word(LIFETIME)))
+                }
+                "eth_call" if data.starts_with(&get_beneficiary) => {
+                    Reply::Result(json!(word(0x33)))
+                }
+                "eth_estimateGas" if ticket.keepalive => Reply::Result(json!("0x186a0")),
+                "eth_estimateGas" => reverted(),
+                "eth_getTransactionReceipt" => match ticket.created_at {
+                    Some(_) => Reply::Result(json!({
+                        "transactionHash": params[0],
+                        "transactionIndex": "0x0",
+                        "blockNumber": "0x10",
+                        "blockHash": format!("{:?}", H256::repeat_byte(0x10)),
+                        "from": format!("{:?}", Address::repeat_byte(0x11)),
+                        "cumulativeGasUsed": "0x0",
+                        "gasUsed": "0x0",
+                        "logs": [],
+                        "logsBloom": format!("0x{}", "00".repeat(256)),
+                        "status": "0x1",
+                    })),
+                    None => Reply::Result(Value::Null),
+                },
+                "eth_getBlockByNumber" => {
+                    let timestamp = ticket.created_at.unwrap_or_else(now_secs);
+                    Reply::Result(json!({
+                        "number": "0x10",
+                        "hash": format!("{:?}", H256::repeat_byte(0x10)),
+                        "timestamp": format!("{:#x}", timestamp),
+                        "transactions": [],
+                    }))
+                }
+                _ => Reply::Error(format!("unexpected {}", method)),
+            }
+        })
+    }
+
+    async fn status(ticket: Ticket) -> TicketStatus {
+        ticket_status(&node(ticket).provider(), H256::repeat_byte(0xaa)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn live_ticket_is_pending_until_its_timeout() {
+        let future = now_secs() + 3600;
+        let pending = status(ticket(Some(future), None, true)).await;
+        assert!(matches!(pending, TicketStatus::Pending { timeout } if timeout == future));
+
+        let past = status(ticket(Some(now_secs() - 1), None, true)).await;
+        assert!(matches!(past, TicketStatus::Expired));
+    }
+
+    // getTimeout revert 说明 ticket 已被删除：按创建时间加有效期区分已执行和已过期
+    #[tokio::test]
+    async fn reverted_lookup_is_classified_by_creation_time() {
+        let recent = status(ticket(None, Some(now_secs() - 60), false)).await;
+        assert!(matches!(recent, TicketStatus::Redeemed));
+
+        let stale = status(ticket(None, Some(now_secs() - LIFETIME - 60), false)).await;
+        assert!(matches!(stale, TicketStatus::Expired));
+
+        let unknown = status(ticket(None, None, false)).await;
+        assert!(matches!(unknown, TicketStatus::NotFound));
+    }
+
+    #[tokio::test]
+    async fn ticket_info_reports_remaining_time_and_keepalive() {
+        let timeout = now_secs() + 3600;
+        for keepalive in [true, false] {
+            let rpc = node(ticket(Some(timeout), None, keepalive));
+            let lookup = ticket_info(&rpc.provider(), H256::repeat_byte(0xaa)).await.unwrap();
+            let TicketLookup::Live(info) = lookup else {
+                panic!("ticket 应该还在");
+            };
+            assert_eq!(info.timeout, timeout);
+            assert_eq!(info.beneficiary, Address::from_low_u64_be(0x33));
+            assert_eq!(info.lifetime, LIFETIME);
+            assert!(info.remaining().is_some_and(|secs| secs <= 3600));
+            // keepalive 的 Gas 估算 revert 时记为不可延长
+            assert_eq!(info.keepalive_gas, keepalive.then(|| U256::from(100_000)));
+        }
+
+        let rpc = node(ticket(None, Some(now_secs()), false));
+        let lookup = ticket_info(&rpc.provider(), H256::repeat_byte(0xaa)).await.unwrap();
+        assert!(matches!(lookup, TicketLookup::Gone(TicketStatus::Redeemed)));
+    }
+}
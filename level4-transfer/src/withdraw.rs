@@ -0,0 +1,413 @@
+use arbi_common::narrate;
+use arbi_common::network;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::shutdown::{Interrupted, Shutdown};
+use arbi_common::style;
+use arbi_common::txhash::parse_tx_hash;
+use ethers::abi::{Abi, RawLog, Token};
+use ethers::prelude::*;
+use ethers::signers::LocalWallet;
+use ethers::types::{Address, Bytes, TransactionReceipt, TxHash, U256};
+use ethers::utils::format_units;
+use serde_json::json;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{Instrument, Span, field, info, info_span};
+
+use crate::get_balance;
+use crate::messages::{Msg, t, tf};
+
+// ArbSys 预编译合约地址
+const ARBSYS_ADDRESS: &str = "0x0000000000000000000000000000000000000064";
+
+// ArbSys 中提现用到的 ABI 片段
+const ARBSYS_ABI: &str = r#"[
+    {
+        "inputs": [{"name": "destination", "type": "address"}],
+        "name": "withdrawEth",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "payable",
+        "type": "function"
+    },
+    {
+        "anonymous": false,
+        "inputs": [
+            {"indexed": false, "name": "caller", "type": "address"},
+            {"indexed": true, "name": "destination", "type": "address"},
+            {"indexed": true, "name": "hash", "type": "uint256"},
+            {"indexed": true, "name": "position", "type": "uint256"},
+            {"indexed": false, "name": "arbBlockNum", "type": "uint256"},
+            {"indexed": false, "name": "ethBlockNum", "type": "uint256"},
+            {"indexed": false, "name": "timestamp", "type": "uint256"},
+            {"indexed": false, "name": "callvalue", "type": "uint256"},
+            {"indexed": false, "name": "data", "type": "bytes"}
+        ],
+        "name": "L2ToL1Tx",
+        "type": "event"
+    }
+]"#;
+
+/// 在 L1 上领取提现时需要的信息，对应 `L2ToL1Tx` 事件
+pub struct WithdrawalClaim {
+    pub l2_tx_hash: TxHash,
+    pub caller: Address,
+    pub destination: Address,
+    pub hash: U256,
+    /// 出站消息在 Outbox 默克尔树中的位置，领取时用它查询证明
+    pub position: U256,
+    pub arb_block_num: U256,
+    pub eth_block_num: U256,
+    pub timestamp: U256,
+    pub callvalue: U256,
+    pub data: Bytes,
+}
+
+impl WithdrawalClaim {
+    /// 把领取信息写成 JSON 文件
+    ///
+    /// # 参数
+    /// * `dir` - 保存目录，文件名为 `withdrawal-<L2 交易哈希>.json`
+    ///
+    /// # 返回
+    /// * `Result<PathBuf, Box<dyn Error>>` - 文件路径
+    pub fn save(&self, dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        let path = dir.join(format!("withdrawal-{:?}.json", self.l2_tx_hash));
+        let content = json!({
+            "l2TxHash": format!("{:?}", self.l2_tx_hash),
+            "caller": format!("{:?}", self.caller),
+            "destination": format!("{:?}", self.destination),
+            "hash": self.hash.to_string(),
+            "position": self.position.to_string(),
+            "arbBlockNum": self.arb_block_num.to_string(),
+            "ethBlockNum": self.eth_block_num.to_string(),
+            "timestamp": self.timestamp.to_string(),
+            "callvalue": self.callvalue.to_string(),
+            "data": self.data.to_string(),
+        });
+        std::fs::write(&path, serde_json::to_string_pretty(&content)?)?;
+        Ok(path)
+    }
+
+    /// 读取 `save` 写出的领取信息文件
+    ///
+    /// # 参数
+    /// * `path` - 文件路径
+    ///
+    /// # 返回
+    /// * `Result<WithdrawalClaim, Box<dyn Error>>` - 领取信息；缺少字段时返回错误
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let field = |name: &str| {
+            value[name]
+                .as_str()
+                .ok_or_else(|| tf(Msg::WithdrawBadClaim, &[&path.display(), &name]))
+        };
+        let uint = |name: &str| -> Result<U256, Box<dyn Error>> {
+            Ok(U256::from_dec_str(field(name)?)?)
+        };
+        Ok(Self {
+            l2_tx_hash: TxHash::from_str(field("l2TxHash")?)?,
+            caller: Address::from_str(field("caller")?)?,
+            destination: Address::from_str(field("destination")?)?,
+            hash: uint("hash")?,
+            position: uint("position")?,
+            arb_block_num: uint("arbBlockNum")?,
+            eth_block_num: uint("ethBlockNum")?,
+            timestamp: uint("timestamp")?,
+            callvalue: uint("callvalue")?,
+            data: Bytes::from_str(field("data")?)?,
+        })
+    }
+}
+
+/// 取得要领取的提现：`source` 是已存在的文件时读取领取信息文件，否则当作 L2 提现交易哈希，
+/// 从收据重新解码 `L2ToL1Tx` 事件
+///
+/// # 参数
+/// * `provider` - L2 Provider
+/// * `source` - 领取信息文件路径或 L2 交易哈希
+///
+/// # 返回
+/// * `Result<WithdrawalClaim, Box<dyn Error>>` - 领取信息
+pub async fn resolve_claim(
+    provider: &RpcProvider,
+    source: &str,
+) -> Result<WithdrawalClaim, Box<dyn Error>> {
+    let path = Path::new(source);
+    if path.exists() {
+        return WithdrawalClaim::load(path);
+    }
+    let tx_hash = parse_tx_hash(source).map_err(|_| tf(Msg::ExecuteBadSource, &[&source]))?;
+    let receipt = provider
+        .get_transaction_receipt(tx_hash)
+        .await?
+        .ok_or_else(|| tf(Msg::ExecuteL2TxNotFound, &[&source]))?;
+    decode_withdrawal(&receipt)
+}
+
+/// 解析 ArbSys ABI
+fn arbsys_abi() -> Result<Abi, Box<dyn Error>> {
+    Ok(serde_json::from_str(ARBSYS_ABI)?)
+}
+
+/// 从提现交易的收据中解码 `L2ToL1Tx` 事件
+///
+/// # 参数
+/// * `receipt` - `withdrawEth` 交易的收据
+///
+/// # 返回
+/// * `Result<WithdrawalClaim, Box<dyn Error>>` - 领取信息；收据中没有该事件时返回错误
+pub fn decode_withdrawal(receipt: &TransactionReceipt) -> Result<WithdrawalClaim, Box<dyn Error>> {
+    let abi = arbsys_abi()?;
+    let event = abi.event("L2ToL1Tx")?;
+    let arbsys = Address::from_str(ARBSYS_ADDRESS)?;
+
+    let log = receipt
+        .logs
+        .iter()
+        .find(|log| log.address == arbsys && log.topics.first() == Some(&event.signature()))
+        .ok_or_else(|| t(Msg::WithdrawNoEvent))?;
+    let parsed = event.parse_log(RawLog {
+        topics: log.topics.clone(),
+        data: log.data.to_vec(),
+    })?;
+
+    let param = |name: &str| {
+        parsed
+            .params
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.value.clone())
+            .ok_or_else(|| tf(Msg::WithdrawMissingField, &[&name]))
+    };
+    let address = |name: &str| -> Result<Address, Box<dyn Error>> {
+        param(name)?
+            .into_address()
+            .ok_or_else(|| tf(Msg::WithdrawMissingField, &[&name]).into())
+    };
+    let uint = |name: &str| -> Result<U256, Box<dyn Error>> {
+        param(name)?
+            .into_uint()
+            .ok_or_else(|| tf(Msg::WithdrawMissingField, &[&name]).into())
+    };
+    let data = match param("data")? {
+        Token::Bytes(bytes) => Bytes::from(bytes),
+        _ => return Err(tf(Msg::WithdrawMissingField, &[&"data"]).into()),
+    };
+
+    Ok(WithdrawalClaim {
+        l2_tx_hash: receipt.transaction_hash,
+        caller: address("caller")?,
+        destination: address("destination")?,
+        hash: uint("hash")?,
+        position: uint("position")?,
+        arb_block_num: uint("arbBlockNum")?,
+        eth_block_num: uint("ethBlockNum")?,
+        timestamp: uint("timestamp")?,
+        callvalue: uint("callvalue")?,
+        data,
+    })
+}
+
+/// 调用 ArbSys 的 `withdrawEth(destination)` 发起 L2 → L1 提现
+///
+/// 交易确认后解码 `L2ToL1Tx` 事件，把领取所需的信息保存到 `claim_dir`。
+/// 资金要等挑战期结束、在 L1 Outbox 上执行领取后才会到账。
+///
+/// 需要在 `withdraw` span 内调用，连接成功后和广播后会把 chain_id / tx_hash 记录到这个 span 上。
+///
+/// # 参数
+/// * `provider` - L2 Provider
+/// * `private_key` - 私钥（从环境变量读取）
+/// * `destination` - L1 收款地址，`None` 时使用发送地址
+/// * `amount` - 提现金额（wei）
+/// * `claim_dir` - 领取信息的保存目录
+/// * `shutdown` - Ctrl-C 关闭信号
+///
+/// # 返回
+/// * `Result<(WithdrawalClaim, PathBuf), Box<dyn Error>>` - 领取信息和保存路径
+pub async fn withdraw_eth(
+    provider: &RpcProvider,
+    private_key: &str,
+    destination: Option<Address>,
+    amount: U256,
+    claim_dir: &Path,
+    shutdown: &Shutdown,
+) -> Result<(WithdrawalClaim, PathBuf), Box<dyn Error>> {
+    // 1. 连接 L2，创建签名客户端
+    narrate!("{}", t(Msg::WithdrawStepConnect));
+    let chain_id = provider
+        .get_chainid()
+        .instrument(info_span!("connect"))
+        .await?;
+    Span::current().record("chain_id", chain_id.as_u64());
+    narrate!("{}\n", style::ok(tf(Msg::Connected, &[&chain_id])));
+    network::check_chain_id(chain_id.as_u64())?;
+
+    let wallet: LocalWallet = private_key.parse()?;
+    let from_address = wallet.address();
+    let destination = destination.unwrap_or(from_address);
+    narrate!("{}", style::ok(tf(Msg::FromAddress, &[&style::addr(from_address)])));
+    narrate!("{}", style::ok(tf(Msg::WithdrawDestination, &[&style::addr(destination)])));
+
+    // 2. 检查余额：提现金额 + Gas 费
+    let client = Arc::new(SignerMiddleware::new(
+        provider.clone(),
+        network::signing_wallet(wallet, chain_id.as_u64())?,
+    ));
+    let contract = Contract::new(Address::from_str(ARBSYS_ADDRESS)?, arbsys_abi()?, client);
+    let call = contract
+        .method::<_, U256>("withdrawEth", destination)?
+        .value(amount);
+
+    narrate!("\n{}", t(Msg::WithdrawStepCheck));
+    let (gas_limit, gas_price) = async {
+        let gas_limit = call.estimate_gas().await?;
+        let gas_price = provider.get_gas_price().await?;
+        Ok::<_, Box<dyn Error>>((gas_limit, gas_price))
+    }
+    .instrument(info_span!("estimate"))
+    .await?;
+    let gas_fee = gas_limit * gas_price;
+    let balance = get_balance(provider, from_address).await?;
+    let balance_eth = format_units(balance, "ether")?;
+    let amount_eth = format_units(amount, "ether")?;
+    let gas_fee_eth = format_units(gas_fee, "ether")?;
+    if balance < amount + gas_fee {
+        return Err(crate::messages::insufficient_balance(
+            &format_units(amount + gas_fee, "ether")?,
+            &amount_eth,
+            &gas_fee_eth,
+            &balance_eth,
+        )
+        .into());
+    }
+    narrate!("{}", style::ok(tf(Msg::CurrentBalance, &[&balance_eth])));
+    narrate!("{}", style::ok(tf(Msg::EstimatedFee, &[&gas_fee_eth])));
+
+    // 3. 发送 withdrawEth 并等待确认
+    narrate!("\n{}", tf(Msg::WithdrawStepSend, &[&amount_eth]));
+    let pending_tx = call
+        .send()
+        .instrument(info_span!("broadcast", destination = ?destination, value_wei = %amount))
+        .await?;
+    let tx_hash = pending_tx.tx_hash();
+    Span::current().record("tx_hash", field::debug(tx_hash));
+    info!("withdrawal broadcast");
+    narrate!("{}", style::ok(tf(Msg::TxHashLine, &[&style::addr(format!("{:?}", tx_hash))])));
+
+    let receipt = tokio::select! {
+        receipt = pending_tx.instrument(info_span!("confirm")) => receipt?,
+        _ = shutdown.wait() => return Err(Interrupted.into()),
+    };
+    let receipt = match receipt {
+        Some(receipt) if receipt.status == Some(1.into()) => receipt,
+        Some(_) => return Err(t(Msg::WithdrawReverted).into()),
+        None => return Err(t(Msg::WithdrawNoReceipt).into()),
+    };
+    narrate!(
+        "{}",
+        style::ok(tf(Msg::WithdrawConfirmed, &[&format!("{:?}", receipt.block_number)]))
+    );
+
+    // 4. 解码 L2ToL1Tx 事件并保存领取信息
+    narrate!("\n{}", t(Msg::WithdrawStepClaim));
+    let claim = decode_withdrawal(&receipt)?;
+    let path = claim.save(claim_dir)?;
+    info!(position = %claim.position, path = %path.display(), "withdrawal claim saved");
+    Ok((claim, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // keccak256("L2ToL1Tx(address,address,uint256,uint256,uint256,uint256,uint256,uint256,bytes)")
+    const L2_TO_L1_TX_TOPIC: &str =
+        "0x3e7aafa77dbf186b7fd488006beff893744caa3c4f6f299e8a709fa2087374fc";
+    const WALLET: &str = "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f";
+
+    // 一笔 0.01 ETH withdrawEth 交易的收据，日志按 ArbSys 的事件布局手工编码
+    fn receipt(log_address: &str) -> TransactionReceipt {
+        let word = |hex: &str| format!("{:0>64}", hex);
+        let data = [
+            word(&WALLET[2..]),     // caller
+            word("a4b0f1"),         // arbBlockNum
+            word("6c1f2a"),         // ethBlockNum
+            word("66b0a5c0"),       // timestamp
+            word("2386f26fc10000"), // callvalue
+            word("c0"),             // data 的偏移
+            word("0"),              // data 的长度
+        ]
+        .concat();
+        serde_json::from_value(json!({
+            "transactionHash": format!("{:?}", TxHash::repeat_byte(0xab)),
+            "transactionIndex": "0x1",
+            "blockNumber": "0xa4b0f1",
+            "blockHash": format!("{:?}", TxHash::repeat_byte(0x10)),
+            "from": WALLET,
+            "cumulativeGasUsed": "0x1d8a8",
+            "gasUsed": "0x1d8a8",
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "status": "0x1",
+            "logs": [{
+                "address": log_address,
+                "topics": [
+                    L2_TO_L1_TX_TOPIC,
+                    format!("0x{}", word(&WALLET[2..])), // destination
+                    format!("0x{}", word("5b1d")),       // hash
+                    format!("0x{}", word("7c3e")),       // position
+                ],
+                "data": format!("0x{}", data),
+            }],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn event_signature_matches_arbsys() {
+        let abi = arbsys_abi().unwrap();
+        let signature = abi.event("L2ToL1Tx").unwrap().signature();
+        assert_eq!(format!("{:?}", signature), L2_TO_L1_TX_TOPIC);
+    }
+
+    #[test]
+    fn decodes_l2_to_l1_tx_from_receipt() {
+        let claim = decode_withdrawal(&receipt(ARBSYS_ADDRESS)).unwrap();
+        let wallet = Address::from_str(WALLET).unwrap();
+        assert_eq!(claim.l2_tx_hash, TxHash::repeat_byte(0xab));
+        assert_eq!(claim.caller, wallet);
+        assert_eq!(claim.destination, wallet);
+        assert_eq!(claim.hash, U256::from(0x5b1d));
+        assert_eq!(claim.position, U256::from(0x7c3e));
+        assert_eq!(claim.arb_block_num, U256::from(0xa4b0f1));
+        assert_eq!(claim.eth_block_num, U256::from(0x6c1f2a));
+        assert_eq!(claim.timestamp, U256::from(0x66b0a5c0_u64));
+        assert_eq!(claim.callvalue, U256::exp10(16));
+        assert!(claim.data.is_empty());
+    }
+
+    // 其他合约发出的同名事件不算
+    #[test]
+    fn ignores_events_from_other_contracts() {
+        let other = "0x0000000000000000000000000000000000000065";
+        let error = decode_withdrawal(&receipt(other)).err().unwrap();
+        assert_eq!(error.to_string(), t(Msg::WithdrawNoEvent));
+    }
+
+    #[test]
+    fn claim_file_round_trips() {
+        let dir = std::env::temp_dir().join(format!("arbi-withdraw-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let claim = decode_withdrawal(&receipt(ARBSYS_ADDRESS)).unwrap();
+        let path = claim.save(&dir).unwrap();
+        let loaded = WithdrawalClaim::load(&path).unwrap();
+        assert_eq!(loaded.l2_tx_hash, claim.l2_tx_hash);
+        assert_eq!(loaded.position, claim.position);
+        assert_eq!(loaded.callvalue, claim.callvalue);
+        assert_eq!(loaded.data, claim.data);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
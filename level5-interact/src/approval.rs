@@ -0,0 +1,209 @@
+use arbi_common::concurrency::{self, DEFAULT_CONCURRENCY};
+use arbi_common::narrate;
+use arbi_common::style;
+use ethers::prelude::*;
+use ethers::types::{Address, U256};
+use ethers::utils::format_units;
+use std::error::Error;
+use std::sync::Arc;
+use tracing::{Instrument, info, info_span, warn};
+
+use crate::messages::{Msg, t, tf};
+use crate::token;
+
+/// 读取单个 spender 的授权额度
+///
+/// # 参数
+/// * `contract` - ERC20 合约实例
+/// * `owner` - 授权人地址
+/// * `spender` - 被授权地址
+///
+/// # 返回
+/// * `Result<U256, Box<dyn Error>>` - 授权额度（原始单位）
+pub async fn allowance<M: Middleware + 'static>(
+    contract: &Contract<M>,
+    owner: Address,
+    spender: Address,
+) -> Result<U256, Box<dyn Error>> {
+    let amount: U256 = contract
+        .method("allowance", (owner, spender))?
+        .call()
+        .await?;
+    Ok(amount)
+}
+
+/// 批量读取多个 spender 的授权额度：优先通过 Multicall3 一次读取，不可用时退回并发调用
+///
+/// # 参数
+/// * `contract` - ERC20 合约实例
+/// * `owner` - 授权人地址
+/// * `spenders` - 被授权地址列表
+///
+/// # 返回
+/// * `Result<Vec<U256>, Box<dyn Error>>` - 与输入顺序一致的授权额度
+pub async fn allowances<M: Middleware + 'static>(
+    contract: &Contract<M>,
+    owner: Address,
+    spenders: &[Address],
+) -> Result<Vec<U256>, Box<dyn Error>> {
+    let multicall_result: Result<Vec<U256>, Box<dyn Error>> = async {
+        let mut multicall = Multicall::new(contract.client(), None).await?;
+        for spender in spenders {
+            multicall.add_call(
+                contract.method::<_, U256>("allowance", (owner, *spender))?,
+                false,
+            );
+        }
+        Ok(multicall.call_array().await?)
+    }
+    .await;
+
+    match multicall_result {
+        Ok(amounts) => Ok(amounts),
+        Err(e) => {
+            narrate!("{}", style::warn(tf(Msg::MulticallFallback, &[&e])));
+            concurrency::run_bounded(spenders, DEFAULT_CONCURRENCY, |spender| {
+                allowance(contract, owner, *spender)
+            })
+            .await
+            .into_iter()
+            .collect()
+        }
+    }
+}
+
+/// 以表格形式打印授权额度，无限授权（`U256::MAX`）用警告色标出
+///
+/// # 参数
+/// * `spenders` - 被授权地址列表
+/// * `amounts` - 对应的授权额度
+/// * `decimals` - 代币精度
+/// * `symbol` - 代币符号
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+pub fn print_allowances(
+    spenders: &[Address],
+    amounts: &[U256],
+    decimals: u8,
+    symbol: &str,
+) -> Result<(), Box<dyn Error>> {
+    println!("{:<44} {}", t(Msg::AllowanceSpender), t(Msg::AllowanceHeader));
+
+    let mut unlimited = 0;
+    for (spender, amount) in spenders.iter().zip(amounts) {
+        let spender = format!("{:?}", spender);
+        if *amount == U256::MAX {
+            unlimited += 1;
+            println!(
+                "{:<44} {}",
+                spender,
+                style::warn(t(Msg::UnlimitedAllowance))
+            );
+        } else {
+            println!(
+                "{:<44} {} {}",
+                spender,
+                format_units(*amount, decimals as u32)?,
+                symbol
+            );
+        }
+    }
+
+    if unlimited > 0 {
+        println!(
+            "\n{}",
+            style::warn(tf(Msg::UnlimitedWarning, &[&unlimited, &symbol]))
+        );
+    }
+
+    Ok(())
+}
+
+/// 发送 `approve(spender, 0)` 撤销单个授权，并等待交易确认
+///
+/// # 参数
+/// * `wallet` - 签名客户端
+/// * `token` - 代币合约地址
+/// * `spender` - 被授权地址
+///
+/// # 返回
+/// * `Result<TxHash, Box<dyn Error>>` - 撤销交易的哈希
+pub async fn revoke<M: Middleware + 'static>(
+    wallet: Arc<M>,
+    token: Address,
+    spender: Address,
+) -> Result<TxHash, Box<dyn Error>> {
+    let contract = token::erc20_contract(wallet, token)?;
+
+    narrate!("\n{}", tf(Msg::Revoking, &[&style::addr(format!("{:?}", spender))]));
+    let call = contract.method::<_, bool>("approve", (spender, U256::zero()))?;
+    let pending_tx = call
+        .send()
+        .instrument(info_span!("broadcast", token = ?token, spender = ?spender))
+        .await?;
+    let tx_hash = pending_tx.tx_hash();
+    info!(tx_hash = ?tx_hash, "revoke broadcast");
+    println!("{}", style::ok(tf(Msg::TxSent, &[&style::addr(format!("{:?}", tx_hash))])));
+
+    match pending_tx
+        .instrument(info_span!("confirm", tx_hash = ?tx_hash))
+        .await?
+    {
+        Some(receipt) => {
+            info!(tx_hash = ?tx_hash, block_number = ?receipt.block_number, "revoke confirmed");
+            println!(
+                "{}",
+                style::ok(tf(Msg::TxConfirmed, &[&format!("{:?}", receipt.block_number)]))
+            )
+        }
+        None => {
+            warn!(tx_hash = ?tx_hash, "no receipt received");
+            println!("{}", style::warn(t(Msg::NoReceipt)))
+        }
+    }
+
+    Ok(tx_hash)
+}
+
+/// 把所有非零授权重置为 0，已经是 0 的直接跳过，不浪费 Gas
+///
+/// # 参数
+/// * `wallet` - 签名客户端
+/// * `token` - 代币合约地址
+/// * `spenders` - 被授权地址列表
+/// * `amounts` - 对应的当前授权额度
+///
+/// # 返回
+/// * `Result<Vec<(Address, TxHash)>, Box<dyn Error>>` - 每个被撤销的 spender 及其撤销交易哈希
+pub async fn revoke_all<M: Middleware + 'static>(
+    wallet: Arc<M>,
+    token: Address,
+    spenders: &[Address],
+    amounts: &[U256],
+) -> Result<Vec<(Address, TxHash)>, Box<dyn Error>> {
+    let mut revoked = Vec::new();
+
+    for (spender, amount) in spenders.iter().zip(amounts) {
+        if amount.is_zero() {
+            narrate!("{}", tf(Msg::RevokeSkipped, &[&style::addr(format!("{:?}", spender))]));
+            continue;
+        }
+
+        let tx_hash = revoke(wallet.clone(), token, *spender).await?;
+        revoked.push((*spender, tx_hash));
+    }
+
+    Ok(revoked)
+}
+
+/// 打印撤销结果：每个 spender 对应的交易哈希
+///
+/// # 参数
+/// * `revoked` - `revoke_all` 的返回值
+pub fn print_revoked(revoked: &[(Address, TxHash)]) {
+    println!("\n{}", style::ok(tf(Msg::RevokedCount, &[&revoked.len()])));
+    for (spender, tx_hash) in revoked {
+        println!("  {:?}  {:?}", spender, tx_hash);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::messages::{Msg, tf};
+use crate::token;
+use arbi_common::bench::{self, BenchResult};
+use arbi_common::narrate;
+use arbi_common::rpc;
+use arbi_common::style;
+use ethers::types::{Address, BlockNumber, U256};
+use std::error::Error;
+use std::sync::Arc;
+
+/// 对比四种批量读取 ERC20 余额方式的耗时
+///
+/// 基准测试调用的就是 `token-balances` 命令使用的函数。建议在 fork 了 Arbitrum Sepolia 的
+/// 本地 anvil 上运行（`anvil --fork-url ...`），这样代币合约和 Multicall3 都存在，
+/// 结果也不受公共 RPC 网络抖动影响。
+///
+/// # 参数
+/// * `rpc_url` - RPC 节点地址
+/// * `token` - 代币合约地址
+/// * `count` - 查询的持有人数量
+/// * `rounds` - 每种方式运行的轮数
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+pub async fn run_token_bench(
+    rpc_url: &str,
+    token: Address,
+    count: usize,
+    rounds: usize,
+) -> Result<(), Box<dyn Error>> {
+    let provider = Arc::new(rpc::connect(rpc_url)?);
+    let contract = token::erc20_contract(provider, token)?;
+    let holders = bench::bench_addresses(count);
+
+    narrate!("{}", tf(Msg::BenchRpc, &[&rpc_url]));
+    narrate!("{}", tf(Msg::BenchStart, &[&count, &rounds]));
+
+    let mut results: Vec<BenchResult> = Vec::new();
+    let mut outputs: Vec<(&'static str, Vec<U256>)> = Vec::new();
+
+    let (result, output) = bench::measure("sequential", rounds, || {
+        token::balances_of_sequential(&contract, &holders, BlockNumber::Latest)
+    })
+    .await?;
+    results.push(result);
+    outputs.push(("sequential", output));
+
+    let (result, output) = bench::measure("concurrent", rounds, || {
+        token::balances_of_concurrent(&contract, &holders, BlockNumber::Latest)
+    })
+    .await?;
+    results.push(result);
+    outputs.push(("concurrent", output));
+
+    let multicall = bench::measure("multicall", rounds, || {
+        token::balances_of_multicall(&contract, &holders, BlockNumber::Latest)
+    })
+    .await;
+    match multicall {
+        Ok((result, output)) => {
+            results.push(result);
+            outputs.push(("multicall", output));
+        }
+        Err(e) => println!("{}", style::warn(tf(Msg::BenchSkipped, &[&"multicall", &e]))),
+    }
+
+    match bench::measure("batched-http", rounds, || {
+        token::balances_of_batched(rpc_url, &contract, &holders, BlockNumber::Latest)
+    })
+    .await
+    {
+        Ok((result, output)) => {
+            results.push(result);
+            outputs.push(("batched-http", output));
+        }
+        Err(e) => println!("{}", style::warn(tf(Msg::BenchSkipped, &[&"batched-http", &e]))),
+    }
+
+    bench::print_table(&tf(Msg::BenchTitle, &[&count]), &results);
+
+    // 各方式读取的是同一个状态，结果应当一致
+    let (_, expected) = &outputs[0];
+    for (name, output) in &outputs[1..] {
+        if output != expected {
+            println!("{}", style::warn(tf(Msg::BenchMismatch, &[name])));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use arbi_common::concurrency::{self, DEFAULT_CONCURRENCY};
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::providers::Middleware;
+use ethers::types::Address;
+use std::error::Error;
+
+use crate::messages::{Msg, t};
+
+// EIP-170 规定的合约运行时字节码上限（24KB）
+pub const EIP170_CODE_SIZE_LIMIT: usize = 24_576;
+
+// 达到上限的这个百分比即视为"接近上限"
+const NEAR_LIMIT_PERCENT: usize = 90;
+
+/// 单个地址的字节码大小
+pub struct CodeSize {
+    pub address: Address,
+    pub size: usize,
+}
+
+impl CodeSize {
+    /// 没有字节码的地址就是普通外部账户（EOA）
+    pub fn is_eoa(&self) -> bool {
+        self.size == 0
+    }
+
+    /// 是否接近 EIP-170 上限
+    pub fn is_near_limit(&self) -> bool {
+        self.size * 100 >= EIP170_CODE_SIZE_LIMIT * NEAR_LIMIT_PERCENT
+    }
+}
+
+/// 批量查询多个地址的合约字节码大小
+///
+/// Multicall3 没有提供读取其他地址字节码长度（EXTCODESIZE）的方法，
+/// 所以这里并发发起 `get_code` 请求（同时最多 `DEFAULT_CONCURRENCY` 个），而不是走 Multicall3 聚合。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `addrs` - 要查询的地址列表
+///
+/// # 返回
+/// * `Result<Vec<CodeSize>, Box<dyn Error>>` - 与输入顺序一致的字节码大小
+pub async fn code_sizes(
+    provider: &RpcProvider,
+    addrs: &[Address],
+) -> Result<Vec<CodeSize>, Box<dyn Error>> {
+    let codes = concurrency::run_bounded(addrs, DEFAULT_CONCURRENCY, |addr| {
+        provider.get_code(*addr, None)
+    })
+    .await
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(addrs
+        .iter()
+        .zip(codes)
+        .map(|(address, code)| CodeSize {
+            address: *address,
+            size: code.len(),
+        })
+        .collect())
+}
+
+/// 以表格形式打印字节码大小，并标出接近 EIP-170 上限的合约
+///
+/// # 参数
+/// * `sizes` - `code_sizes` 的查询结果
+pub fn print_code_sizes(sizes: &[CodeSize]) {
+    println!(
+        "{:<44} {:>12} {:>10}",
+        t(Msg::CodeSizeAddress),
+        t(Msg::CodeSizeSize),
+        t(Msg::CodeSizeOfLimit)
+    );
+    for item in sizes {
+        if item.is_eoa() {
+            println!("{:<44} {:>12}", format!("{:?}", item.address), t(Msg::CodeSizeEoa));
+            continue;
+        }
+
+        let percent = item.size as f64 * 100.0 / EIP170_CODE_SIZE_LIMIT as f64;
+        let marker = if item.is_near_limit() {
+            format!("  {}", style::warn(t(Msg::CodeSizeNearLimit)))
+        } else {
+            String::new()
+        };
+        println!(
+            "{:<44} {:>12} {:>9.1}%{}",
+            format!("{:?}", item.address),
+            item.size,
+            percent,
+            marker
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use serde_json::json;
+
+    // Arbitrum One 上的 USDC 代理合约和一个普通账户
+    const CONTRACT: &str = "0xaf88d065e77c8cc2239327c5edb3a432268e5831";
+    const EOA: &str = "0x1111111111111111111111111111111111111111";
+    const RUNTIME_CODE: &str =
+        "0x60806040526004361061001e5760003560e01c80635c60da1b14610023575b600080fd";
+
+    #[tokio::test]
+    async fn reports_contract_size_and_eoa() {
+        let rpc = MockRpc::start(|method, params| match (method, params[0].as_str()) {
+            ("eth_getCode", Some(CONTRACT)) => Reply::Result(json!(RUNTIME_CODE)),
+            ("eth_getCode", Some(_)) => Reply::Result(json!("0x")),
+            _ => Reply::Error(format!("unexpected {}", method)),
+        });
+        let addrs = [EOA.parse().unwrap(), CONTRACT.parse().unwrap()];
+
+        let sizes = code_sizes(&rpc.provider(), &addrs).await.unwrap();
+
+        assert_eq!(sizes.len(), 2);
+        assert_eq!(sizes[0].address, addrs[0]);
+        assert_eq!(sizes[0].size, 0);
+        assert!(sizes[0].is_eoa());
+        assert_eq!(sizes[1].address, addrs[1]);
+        assert_eq!(sizes[1].size, (RUNTIME_CODE.len() - 2) / 2);
+        assert!(!sizes[1].is_eoa());
+        assert!(!sizes[1].is_near_limit());
+    }
+
+    #[test]
+    fn near_limit_starts_at_ninety_percent_of_eip170() {
+        let size = |size| CodeSize {
+            address: Address::zero(),
+            size,
+        };
+        assert_eq!(EIP170_CODE_SIZE_LIMIT, 24 * 1024);
+        assert!(!size(22_118).is_near_limit());
+        assert!(size(22_119).is_near_limit());
+        assert!(size(EIP170_CODE_SIZE_LIMIT).is_near_limit());
+        assert!(!size(0).is_near_limit());
+    }
+}
@@ -0,0 +1,84 @@
+use arbi_common::style;
+use ethers::abi::{Abi, RawLog, Token};
+use ethers::types::{Address, TransactionReceipt};
+use ethers::utils::format_units;
+
+use crate::messages::{Msg, t, tf};
+
+/// 按 ABI 解码出的一条事件日志
+pub struct DecodedLog {
+    /// 发出事件的合约地址
+    pub address: Address,
+    /// 事件名称，如 `Transfer`
+    pub name: String,
+    /// 事件参数（参数名, 值），顺序与 ABI 中的定义一致
+    pub params: Vec<(String, Token)>,
+}
+
+/// 用给定的 ABI 解码交易收据里的所有日志
+///
+/// 按 topic0（事件签名哈希）匹配 ABI 中的事件；匹配不到的日志（比如其他合约发出的事件）
+/// 或者解码失败的日志直接跳过，不会报错。代币转账传 `token::erc20_abi()` 即可。
+///
+/// # 参数
+/// * `receipt` - 交易收据
+/// * `abi` - 用来解码的合约 ABI
+///
+/// # 返回
+/// * `Vec<DecodedLog>` - 成功解码的事件，顺序与收据中的日志一致
+pub fn decode_receipt_logs(receipt: &TransactionReceipt, abi: &Abi) -> Vec<DecodedLog> {
+    receipt
+        .logs
+        .iter()
+        .filter_map(|log| {
+            let topic0 = log.topics.first()?;
+            let event = abi.events().find(|event| event.signature() == *topic0)?;
+            let parsed = event
+                .parse_log(RawLog {
+                    topics: log.topics.clone(),
+                    data: log.data.to_vec(),
+                })
+                .ok()?;
+
+            Some(DecodedLog {
+                address: log.address,
+                name: event.name.clone(),
+                params: parsed.params.into_iter().map(|p| (p.name, p.value)).collect(),
+            })
+        })
+        .collect()
+}
+
+/// 把单个事件参数格式化成便于阅读的字符串
+///
+/// 地址用带 0x 的十六进制；整数在给出小数位数时按代币单位显示，否则显示原始值。
+fn format_token(token: &Token, decimals: Option<u8>) -> String {
+    match token {
+        Token::Address(addr) => style::addr(format!("{:?}", addr)),
+        Token::Uint(value) => match decimals.map(|d| format_units(*value, d as u32)) {
+            Some(Ok(formatted)) => format!("{} ({})", formatted, value),
+            _ => value.to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+/// 打印解码出的事件
+///
+/// # 参数
+/// * `logs` - `decode_receipt_logs` 的结果
+/// * `decimals` - 代币小数位数，用来换算 `uint` 参数（不是代币事件时传 `None`）
+pub fn print_decoded_logs(logs: &[DecodedLog], decimals: Option<u8>) {
+    if logs.is_empty() {
+        println!("\n{}", t(Msg::NoEvents));
+        return;
+    }
+
+    println!("\n{}", tf(Msg::EventsHeader, &[&logs.len()]));
+    for log in logs {
+        println!("  {} @ {}", style::ok(&log.name), style::addr(format!("{:?}", log.address)));
+        for (name, value) in &log.params {
+            println!("    {:<10} {}", name, format_token(value, decimals));
+        }
+    }
+}
@@ -1,16 +1,31 @@
+mod approval;
+mod bench;
+mod code_size;
+mod events;
+mod messages;
+mod simulate;
+mod state_override;
+mod token;
+
+use arbi_common::cli::{CsvArgs, GlobalArgs};
+use arbi_common::env;
+use arbi_common::narrate;
+use arbi_common::network;
+use arbi_common::rpc::{self, RpcProvider};
+use arbi_common::style;
+use arbi_common::table::Table;
+use arbi_common::units;
+use clap::{Parser, Subcommand};
 use ethers::prelude::*;
 use ethers::abi::Abi;
-use ethers::providers::{Http, Provider};
-use ethers::types::Address;
+use ethers::types::{Address, BlockNumber};
 use ethers::utils::format_units;
 use std::error::Error;
 use std::str::FromStr;
 use std::sync::Arc;
+use tracing::{Instrument, Span, field, info, info_span};
 
-const RPC_URL: &str = "https://sepolia-rollup.arbitrum.io/rpc";
-
-// Arbitrum Sepolia 测试网上的 USDC 测试代币合约地址
-const USDC_CONTRACT_ADDRESS: &str = "0x75faf114eafb1BDbe2F0316DF893fd58CE46AA4d";
+use messages::{Msg, t, tf};
 
 // ERC20 标准 ABI
 const ERC20_ABI: &str = r#"[
@@ -27,66 +42,610 @@ const ERC20_ABI: &str = r#"[
         "name": "symbol",
         "outputs": [{"name": "", "type": "string"}],
         "type": "function"
+    },
+    {
+        "constant": true,
+        "inputs": [],
+        "name": "decimals",
+        "outputs": [{"name": "", "type": "uint8"}],
+        "type": "function"
+    },
+    {
+        "constant": true,
+        "inputs": [{"name": "owner", "type": "address"}],
+        "name": "balanceOf",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "type": "function"
+    },
+    {
+        "constant": true,
+        "inputs": [
+            {"name": "owner", "type": "address"},
+            {"name": "spender", "type": "address"}
+        ],
+        "name": "allowance",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "type": "function"
+    },
+    {
+        "constant": false,
+        "inputs": [
+            {"name": "spender", "type": "address"},
+            {"name": "amount", "type": "uint256"}
+        ],
+        "name": "approve",
+        "outputs": [{"name": "", "type": "bool"}],
+        "type": "function"
+    },
+    {
+        "constant": false,
+        "inputs": [
+            {"name": "to", "type": "address"},
+            {"name": "value", "type": "uint256"}
+        ],
+        "name": "transfer",
+        "outputs": [{"name": "", "type": "bool"}],
+        "type": "function"
+    },
+    {
+        "anonymous": false,
+        "inputs": [
+            {"indexed": true, "name": "from", "type": "address"},
+            {"indexed": true, "name": "to", "type": "address"},
+            {"indexed": false, "name": "value", "type": "uint256"}
+        ],
+        "name": "Transfer",
+        "type": "event"
+    },
+    {
+        "anonymous": false,
+        "inputs": [
+            {"indexed": true, "name": "owner", "type": "address"},
+            {"indexed": true, "name": "spender", "type": "address"},
+            {"indexed": false, "name": "value", "type": "uint256"}
+        ],
+        "name": "Approval",
+        "type": "event"
     }
-   
 ]"#;
 
 /// 查询 ERC20 代币的基本信息
 ///
 /// # 参数
+/// * `provider` - 共享的 Provider
 /// * `contract_address` - 合约地址
 ///
 /// # 返回
 /// * `Result<(), Box<dyn Error>>` - 执行结果
-async fn query_erc20_info(contract_address: &str) -> Result<(), Box<dyn Error>> {
-    println!("=== Arbitrum 测试网合约交互演示 ===\n");
+async fn query_erc20_info(
+    provider: Arc<RpcProvider>,
+    contract_address: &str,
+) -> Result<(), Box<dyn Error>> {
+    narrate!("{}\n", t(Msg::Title));
 
-    // 1. 创建 Provider
-    println!("1. 连接到 Arbitrum Sepolia 测试网...");
-    let provider = Provider::<Http>::try_from(RPC_URL)?;
-    let provider = Arc::new(provider);
-    println!("✓ 连接成功\n");
+    // 1. 检查 Provider 连接
+    narrate!("{}", t(Msg::StepConnect));
+    let chain_id = provider.get_chainid().instrument(info_span!("connect")).await?;
+    Span::current().record("chain_id", chain_id.as_u64());
+    info!("connected");
+    narrate!("{}\n", style::ok(tf(Msg::Connected, &[&chain_id])));
 
     // 2. 解析合约地址
-    println!("2. 加载合约...");
+    narrate!("{}", t(Msg::StepLoadContract));
     let address = Address::from_str(contract_address)?;
-    println!("✓ 合约地址: {}", address);
+    narrate!("{}", style::ok(tf(Msg::ContractAddress, &[&style::addr(address)])));
 
     // 3. 解析 ABI
     let abi: Abi = serde_json::from_str(ERC20_ABI)?;
-    println!("✓ ABI 加载成功\n");
+    narrate!("{}\n", style::ok(t(Msg::AbiLoaded)));
 
     // 4. 创建合约实例
     let contract = Contract::new(address, abi, provider.clone());
-    println!("3. 合约实例已创建\n");
+    narrate!("{}\n", t(Msg::ContractCreated));
 
     // 5. 调用合约的只读方法
-    println!("4. 查询合约信息...\n");
+    narrate!("{}\n", t(Msg::StepQuery));
 
     // 查询代币名称
-    println!("📝 调用 name() 方法...");
-    let name: String = contract.method("name", ())?.call().await?;
-    println!("✓ 代币名称: {}", name);
+    narrate!("{}", tf(Msg::CallingMethod, &[&"name"]));
+    let name = token::read_metadata(&contract, "name").await?;
+    println!("{}", style::ok(messages::token_name(&name)));
 
     // 查询代币符号
-    println!("\n📝 调用 symbol() 方法...");
-    let symbol: String = contract.method("symbol", ())?.call().await?;
-    println!("✓ 代币符号: {}", symbol);
+    narrate!("\n{}", tf(Msg::CallingMethod, &[&"symbol"]));
+    let symbol = token::read_metadata(&contract, "symbol").await?;
+    println!("{}", style::ok(messages::token_symbol(&symbol)));
+
+    Ok(())
+}
+
+/// Arbitrum 测试网合约交互工具
+#[derive(Parser)]
+#[command(about = "Arbitrum 测试网合约交互演示")]
+struct Cli {
+    #[command(flatten)]
+    global: GlobalArgs,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 对比多个地址的合约字节码大小（EIP-170 上限 24KB）
+    CodeSizes {
+        /// 要查询的地址列表
+        #[arg(required = true)]
+        addresses: Vec<String>,
+    },
+    /// 批量读取多个地址的 ERC20 代币余额
+    TokenBalances {
+        /// 持有人地址列表
+        #[arg(required = true)]
+        holders: Vec<String>,
+        /// 代币合约地址（默认为当前网络的 USDC）
+        #[arg(long)]
+        token: Option<String>,
+        /// 读取指定区块时的余额（区块号或 latest / safe / finalized 等标签，历史区块需要归档节点）
+        #[arg(long, value_name = "BLOCK")]
+        at_block: Option<String>,
+        /// 用 eth_call 状态覆盖把持有人的余额改成指定数量再读取（`地址=数量`，可重复）；
+        /// 只是模拟，不会上链，需要节点支持状态覆盖
+        #[arg(long, value_name = "HOLDER=AMOUNT")]
+        fake_balance: Vec<String>,
+        /// 余额 mapping 声明在 Solidity 合约的第几个存储槽（默认自动探测）
+        #[arg(long, value_name = "SLOT", requires = "fake_balance")]
+        balance_slot: Option<u64>,
+        #[command(flatten)]
+        csv: CsvArgs,
+    },
+    /// 批量查询授权额度，标出无限授权
+    Allowances {
+        /// 被授权的 spender 地址列表
+        #[arg(required = true)]
+        spenders: Vec<String>,
+        /// 授权人地址（使用 --revoke-all 时默认为 PRIVATE_KEY 对应的地址）
+        #[arg(long)]
+        owner: Option<String>,
+        /// 代币合约地址（默认为当前网络的 USDC）
+        #[arg(long)]
+        token: Option<String>,
+        /// 把所有非零授权重置为 0（需要 PRIVATE_KEY）
+        #[arg(long)]
+        revoke_all: bool,
+    },
+    /// 撤销 PRIVATE_KEY 对应地址给指定 spender 的授权（授权为 0 的跳过）
+    Revoke {
+        /// 要撤销授权的 spender 地址列表
+        #[arg(required = true)]
+        spenders: Vec<String>,
+        /// 代币合约地址（默认为当前网络的 USDC）
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// 用 PRIVATE_KEY 对应的地址转账 ERC20 代币，并打印交易发出的事件
+    Transfer {
+        /// 收款地址
+        to: String,
+        /// 转账数量（按代币单位，如 1.5）
+        amount: String,
+        /// 代币合约地址（默认为当前网络的 USDC）
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// 演示 revert 原因的提取：用 eth_call 模拟一个一定会失败的调用并解码原因（从不广播交易）
+    ///
+    /// 默认调用代币合约的 transfer(from, type(uint256).max)，转出数量超过任何余额，一定会 revert。
+    SimulateRevert {
+        /// 被调用的合约地址（默认为当前网络的 USDC）
+        #[arg(long)]
+        token: Option<String>,
+        /// 模拟的调用方（eth_call 不需要签名）
+        #[arg(long, default_value = simulate::DEFAULT_FROM)]
+        from: String,
+        /// 改用自定义 calldata（十六进制），调用 --token 指定的合约
+        #[arg(long, value_name = "HEX")]
+        data: Option<String>,
+    },
+    /// 对比顺序 / 并发 / Multicall / 批量 HTTP 四种方式读取代币余额的耗时
+    Bench {
+        /// 查询的持有人数量
+        #[arg(long, default_value_t = 50)]
+        count: usize,
+        /// 每种方式运行的轮数
+        #[arg(long, default_value_t = 3)]
+        rounds: usize,
+        /// 代币合约地址（默认为当前网络的 USDC）
+        #[arg(long)]
+        token: Option<String>,
+        /// RPC 节点地址（默认为当前网络的节点，fork 出来的 anvil 可传 http://127.0.0.1:8545）
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+}
+
+/// 解析命令行传入的地址列表
+///
+/// # 参数
+/// * `addresses` - 地址字符串列表
+///
+/// # 返回
+/// * `Result<Vec<Address>, Box<dyn Error>>` - 解析后的地址
+fn parse_addresses(addresses: &[String]) -> Result<Vec<Address>, Box<dyn Error>> {
+    let addrs = addresses
+        .iter()
+        .map(|addr| Address::from_str(addr))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(addrs)
+}
+
+/// 查询并打印多个地址的字节码大小
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `addresses` - 地址字符串列表
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_code_sizes(provider: &RpcProvider, addresses: &[String]) -> Result<(), Box<dyn Error>> {
+    let addrs = parse_addresses(addresses)?;
+
+    narrate!("{}\n", tf(Msg::QueryingCodeSizes, &[&addrs.len()]));
+    let sizes = code_size::code_sizes(provider, &addrs).await?;
+    code_size::print_code_sizes(&sizes);
 
     Ok(())
 }
 
+/// 批量读取并打印多个地址的代币余额
+///
+/// # 参数
+/// * `provider` - 共享的 Provider
+/// * `token` - 代币合约地址
+/// * `holders` - 持有人地址字符串列表
+/// * `at_block` - 读取哪个区块的余额，`None` 时为最新区块
+/// * `fake_balances` - 通过状态覆盖模拟的余额（`地址=数量`），不在 `holders` 中的地址会追加进去
+/// * `balance_slot` - 余额 mapping 的 Solidity 存储槽，`None` 时自动探测
+/// * `csv` - CSV 导出选项
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_token_balances(
+    provider: Arc<RpcProvider>,
+    token: &str,
+    holders: &[String],
+    at_block: Option<&str>,
+    fake_balances: &[String],
+    balance_slot: Option<u64>,
+    csv: &CsvArgs,
+) -> Result<(), Box<dyn Error>> {
+    let mut holders = parse_addresses(holders)?;
+    let block = match at_block {
+        Some(block) => {
+            BlockNumber::from_str(block).map_err(|e| tf(Msg::InvalidBlock, &[&block, &e]))?
+        }
+        None => BlockNumber::Latest,
+    };
+    if let BlockNumber::Number(number) = block {
+        let latest = provider.get_block_number().await?;
+        if number > latest {
+            return Err(tf(Msg::AtBlockInFuture, &[&number, &latest]).into());
+        }
+    }
+    let contract = token::erc20_contract(provider.clone(), Address::from_str(token)?)?;
+
+    // 代币元数据读取最新状态，快照区块只影响余额
+    let symbol = token::read_metadata(&contract, "symbol").await?;
+    let decimals: u8 = contract.method("decimals", ())?.call().await?;
+    narrate!("{}", tf(Msg::QueryingTokenBalances, &[&holders.len(), &symbol]));
+    if let Some(block) = at_block {
+        narrate!("{}", tf(Msg::QueryingAtBlock, &[&block]));
+    }
+    narrate!();
+
+    let fakes = fake_balances
+        .iter()
+        .map(|fake| state_override::parse_fake_balance(fake, decimals))
+        .collect::<Result<Vec<_>, _>>()?;
+    let balances = if fakes.is_empty() {
+        token::balances_of(&contract, &holders, block).await?
+    } else {
+        for (holder, _) in &fakes {
+            if !holders.contains(holder) {
+                holders.push(*holder);
+            }
+        }
+        let (token, block) = (contract.address(), BlockId::Number(block));
+        state_override::check_support(&provider, block).await?;
+        let slot = match balance_slot {
+            Some(slot) => state_override::BalanceSlot::solidity(slot.into()),
+            None => {
+                narrate!("{}", t(Msg::BalanceSlotProbing));
+                state_override::find_balance_slot(&provider, token, fakes[0].0, block).await?
+            }
+        };
+        info!(%slot, "balance slot");
+        narrate!("{}", tf(Msg::BalanceSlotUsed, &[&slot]));
+        narrate!("{}\n", style::warn(t(Msg::FakeBalanceNote)));
+        let state = state_override::fake_balances(token, slot, &fakes);
+        state_override::balances_of(&provider, token, &holders, block, &state).await?
+    };
+    let mut table = Table::new(["holder", "token", "symbol", "balance_raw", "balance"]);
+    for (holder, balance) in holders.iter().zip(balances) {
+        let formatted = format_units(balance, decimals as u32)?;
+        let simulated = match fakes.iter().any(|(fake, _)| fake == holder) {
+            true => t(Msg::FakeBalanceMark),
+            false => "",
+        };
+        println!("{:?}  {} {}{}", holder, formatted, symbol, simulated);
+        table.push([
+            format!("{:?}", holder),
+            format!("{:?}", contract.address()),
+            symbol.clone(),
+            balance.to_string(),
+            formatted,
+        ]);
+    }
+
+    csv.write(&table)
+}
+
+/// 从 PRIVATE_KEY 环境变量加载钱包，并与 Provider 绑定成可签名的客户端
+///
+/// # 参数
+/// * `provider` - 共享的 Provider
+///
+/// # 返回
+/// * `Result<Arc<SignerMiddleware<RpcProvider, LocalWallet>>, Box<dyn Error>>` - 签名客户端
+async fn signer_client(
+    provider: Arc<RpcProvider>,
+) -> Result<Arc<SignerMiddleware<RpcProvider, LocalWallet>>, Box<dyn Error>> {
+    dotenv::dotenv().ok(); // 加载 .env 文件（如果存在）
+
+    let private_key = env::var("PRIVATE_KEY").ok_or(t(Msg::MissingKey))?;
+    let wallet: LocalWallet = private_key.parse()?;
+    let chain_id = provider.get_chainid().instrument(info_span!("connect")).await?;
+    Span::current().record("chain_id", chain_id.as_u64());
+    network::check_chain_id(chain_id.as_u64())?;
+
+    let wallet = network::signing_wallet(wallet, chain_id.as_u64())?;
+    let client = SignerMiddleware::new((*provider).clone(), wallet);
+    Ok(Arc::new(client))
+}
+
+/// 查询并打印授权额度，可选地撤销所有非零授权
+///
+/// # 参数
+/// * `provider` - 共享的 Provider
+/// * `token` - 代币合约地址
+/// * `owner` - 授权人地址（撤销时可省略）
+/// * `spenders` - 被授权地址字符串列表
+/// * `revoke_all` - 是否撤销所有非零授权
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_allowances(
+    provider: Arc<RpcProvider>,
+    token: &str,
+    owner: Option<&str>,
+    spenders: &[String],
+    revoke_all: bool,
+) -> Result<(), Box<dyn Error>> {
+    let token = Address::from_str(token)?;
+    let spenders = parse_addresses(spenders)?;
+
+    let client = if revoke_all {
+        Some(signer_client(provider.clone()).await?)
+    } else {
+        None
+    };
+    let owner = match (owner, &client) {
+        (Some(owner), Some(client)) if Address::from_str(owner)? != client.address() => {
+            return Err(t(Msg::OwnerMismatch).into());
+        }
+        (Some(owner), _) => Address::from_str(owner)?,
+        (None, Some(client)) => client.address(),
+        (None, None) => return Err(t(Msg::OwnerRequired).into()),
+    };
+
+    let contract = token::erc20_contract(provider, token)?;
+    let symbol = token::read_metadata(&contract, "symbol").await?;
+    let decimals: u8 = contract.method("decimals", ())?.call().await?;
+
+    narrate!(
+        "{}\n",
+        tf(
+            Msg::QueryingAllowances,
+            &[&style::addr(format!("{:?}", owner)), &spenders.len(), &symbol]
+        )
+    );
+    let amounts = approval::allowances(&contract, owner, &spenders).await?;
+    approval::print_allowances(&spenders, &amounts, decimals, &symbol)?;
+
+    if let Some(client) = client {
+        let revoked = approval::revoke_all(client, token, &spenders, &amounts).await?;
+        approval::print_revoked(&revoked);
+    }
+
+    Ok(())
+}
+
+/// 撤销 PRIVATE_KEY 对应地址给多个 spender 的授权
+///
+/// # 参数
+/// * `provider` - 共享的 Provider
+/// * `token` - 代币合约地址
+/// * `spenders` - 被授权地址字符串列表
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_revoke(
+    provider: Arc<RpcProvider>,
+    token: &str,
+    spenders: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let token = Address::from_str(token)?;
+    let spenders = parse_addresses(spenders)?;
+    let client = signer_client(provider.clone()).await?;
+
+    // 先读取当前授权，只对非零的发送交易
+    let contract = token::erc20_contract(provider, token)?;
+    let amounts = approval::allowances(&contract, client.address(), &spenders).await?;
+
+    let revoked = approval::revoke_all(client, token, &spenders, &amounts).await?;
+    approval::print_revoked(&revoked);
+
+    Ok(())
+}
+
+/// 转账 ERC20 代币，确认后解码收据中的事件并打印
+///
+/// # 参数
+/// * `provider` - 共享的 Provider
+/// * `token` - 代币合约地址
+/// * `to` - 收款地址
+/// * `amount` - 转账数量（按代币单位）
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_transfer(
+    provider: Arc<RpcProvider>,
+    token: &str,
+    to: &str,
+    amount: &str,
+) -> Result<(), Box<dyn Error>> {
+    let token = Address::from_str(token)?;
+    let to = Address::from_str(to)?;
+    let client = signer_client(provider.clone()).await?;
+
+    let contract = token::erc20_contract(provider, token)?;
+    let symbol = token::read_metadata(&contract, "symbol").await?;
+    let decimals: u8 = contract.method("decimals", ())?.call().await?;
+    let value = units::parse_amount(amount, decimals as u32)?;
+
+    narrate!("{}", messages::transferring_token(amount, &symbol, &to));
+    let receipt = token::transfer_token(client, token, to, value).await?;
+    if receipt.status != Some(1.into()) {
+        return Err(t(Msg::TransferReverted).into());
+    }
+
+    let logs = events::decode_receipt_logs(&receipt, &token::erc20_abi()?);
+    events::print_decoded_logs(&logs, Some(decimals));
+    println!("\n{}", style::ok(t(Msg::TransferSucceeded)));
+
+    Ok(())
+}
+
+/// 模拟一个会 revert 的调用，演示 revert 原因的提取
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `contract` - 被调用的合约地址
+/// * `from` - 模拟的调用方
+/// * `data` - 自定义 calldata，`None` 时调用 `transfer(from, type(uint256).max)`
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_simulate_revert(
+    provider: &RpcProvider,
+    contract: &str,
+    from: &str,
+    data: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let contract = Address::from_str(contract)?;
+    let from = Address::from_str(from)?;
+    let call = match data {
+        Some(data) => simulate::RevertCall::custom(contract, from, &data)?,
+        None => simulate::RevertCall::over_transfer(contract, from)?,
+    };
+    narrate!("{}\n", t(Msg::SimulateTitle));
+    simulate::simulate_revert(provider, &call).await
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    println!("使用 Arbitrum Sepolia 测试网上的 USDC 测试代币\n");
+    let cli = Cli::parse();
+    cli.global.init();
+
+    // 创建共享的 Provider
+    let rpc_url = network::current().rpc_url();
+    let provider = Arc::new(rpc::connect(rpc_url)?);
+    let usdc = network::current().usdc_address();
 
-    match query_erc20_info(USDC_CONTRACT_ADDRESS).await {
-        Ok(_) => println!("\n✅ 查询成功！"),
-        Err(e) => {
-            eprintln!("\n❌ 查询失败: {}", e);
-            std::process::exit(1);
+    // chain_id 在连接后记录到这个 span 上，JSON 日志的每一行都会带上
+    let span = info_span!("command", rpc_url, chain_id = field::Empty);
+    let result = async {
+        match cli.command {
+            Some(Command::CodeSizes { addresses }) => run_code_sizes(&provider, &addresses).await,
+            Some(Command::TokenBalances {
+                holders,
+                token,
+                at_block,
+                fake_balance,
+                balance_slot,
+                csv,
+            }) => {
+                let token = token.as_deref().unwrap_or(usdc);
+                let at_block = at_block.as_deref();
+                run_token_balances(
+                    provider,
+                    token,
+                    &holders,
+                    at_block,
+                    &fake_balance,
+                    balance_slot,
+                    &csv,
+                )
+                .await
+            }
+            Some(Command::Allowances {
+                spenders,
+                owner,
+                token,
+                revoke_all,
+            }) => {
+                let token = token.as_deref().unwrap_or(usdc);
+                run_allowances(provider, token, owner.as_deref(), &spenders, revoke_all).await
+            }
+            Some(Command::Revoke { spenders, token }) => {
+                run_revoke(provider, token.as_deref().unwrap_or(usdc), &spenders).await
+            }
+            Some(Command::Transfer { to, amount, token }) => {
+                run_transfer(provider, token.as_deref().unwrap_or(usdc), &to, &amount).await
+            }
+            Some(Command::SimulateRevert { token, from, data }) => {
+                run_simulate_revert(&provider, token.as_deref().unwrap_or(usdc), &from, data).await
+            }
+            Some(Command::Bench {
+                count,
+                rounds,
+                token,
+                rpc_url: bench_rpc_url,
+            }) => match Address::from_str(token.as_deref().unwrap_or(usdc)) {
+                Ok(token) => {
+                    let bench_rpc_url = bench_rpc_url.as_deref().unwrap_or(rpc_url);
+                    bench::run_token_bench(bench_rpc_url, token, count, rounds).await
+                }
+                Err(e) => Err(e.into()),
+            },
+            None => {
+                narrate!("{}\n", tf(Msg::UsingUsdc, &[&network::current().name()]));
+
+                let result = query_erc20_info(provider, usdc).await;
+                if result.is_ok() {
+                    println!("\n{}", style::ok(t(Msg::QuerySucceeded)));
+                }
+                result
+            }
         }
     }
+    .instrument(span)
+    .await;
+
+    if let Err(e) = result {
+        eprintln!("\n{}", style::err(tf(Msg::QueryFailed, &[&e])));
+        std::process::exit(1);
+    }
 
     Ok(())
 }
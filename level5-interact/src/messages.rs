@@ -0,0 +1,145 @@
+//! level5 输出的消息目录
+//!
+//! 关键流程的消息另外提供带类型参数的函数，调用方不用关心模板里占位符的顺序。
+
+use ethers::types::Address;
+
+arbi_common::define_messages! {
+    Title => ("=== Arbitrum 测试网合约交互演示 ===", "=== Arbitrum Testnet Contract Interaction Demo ==="),
+    UsingUsdc => ("使用 {} 上的 USDC 合约", "Using the USDC contract on {}"),
+    StepConnect => ("1. 连接到 Arbitrum Sepolia 测试网...", "1. Connecting to Arbitrum Sepolia testnet..."),
+    Connected => ("✓ 连接成功（Chain ID: {}）", "✓ Connected (chain ID: {})"),
+    StepLoadContract => ("2. 加载合约...", "2. Loading contract..."),
+    ContractAddress => ("✓ 合约地址: {}", "✓ Contract address: {}"),
+    AbiLoaded => ("✓ ABI 加载成功", "✓ ABI loaded"),
+    ContractCreated => ("3. 合约实例已创建", "3. Contract instance created"),
+    StepQuery => ("4. 查询合约信息...", "4. Querying contract info..."),
+    CallingMethod => ("📝 调用 {}() 方法...", "📝 Calling {}()..."),
+    TokenName => ("✓ 代币名称: {}", "✓ Token name: {}"),
+    TokenSymbol => ("✓ 代币符号: {}", "✓ Token symbol: {}"),
+    QuerySucceeded => ("✅ 查询成功！", "✅ Query succeeded!"),
+    QueryFailed => ("❌ 查询失败: {}", "❌ Query failed: {}"),
+
+    MissingKey => ("未找到 PRIVATE_KEY 环境变量，请在 .env 文件中配置", "PRIVATE_KEY environment variable not found; set it in the .env file"),
+    OwnerMismatch => (
+        "--owner 与 PRIVATE_KEY 对应的地址不一致，只能撤销自己的授权",
+        "--owner does not match the PRIVATE_KEY address; you can only revoke your own approvals"
+    ),
+    OwnerRequired => ("请通过 --owner 指定授权人地址", "Specify the token owner with --owner"),
+    HistoricalStateUnavailable => (
+        "节点没有区块 {} 的状态（{}），读取历史余额需要归档节点，请换用归档 RPC",
+        "The node has no state for block {} ({}); historical balances need an archive node, use an archive RPC"
+    ),
+    AtBlockInFuture => ("区块 {} 还没有产生（最新区块 {}）", "Block {} does not exist yet (latest is {})"),
+    InvalidBlock => ("无效的区块: {}（{}）", "Invalid block: {} ({})"),
+    QueryingAtBlock => ("快照区块: {}", "Snapshot block: {}"),
+    MulticallFallback => ("⚠ Multicall3 不可用（{}），改为并发调用", "⚠ Multicall3 unavailable ({}), falling back to concurrent calls"),
+    InvalidFakeBalance => ("无效的 --fake-balance `{}`: {}", "Invalid --fake-balance `{}`: {}"),
+    FakeBalanceFormat => ("格式应为 `地址=数量`", "expected `address=amount`"),
+    StateOverrideUnsupported => (
+        "RPC 节点不支持 eth_call 的状态覆盖参数（{}），请换用支持的节点（如 geth、Nitro 或本地 anvil）",
+        "The RPC node does not support eth_call state overrides ({}); use a node that does (e.g. geth, Nitro or a local anvil)"
+    ),
+    StateOverrideIgnored => (
+        "RPC 节点接受了状态覆盖参数但没有应用，无法模拟余额，请换用支持的节点",
+        "The RPC node accepted the state override but did not apply it; use a node that supports overrides"
+    ),
+    BalanceSlotNotFound => (
+        "找不到代币 {} 的余额存储槽（已探测 Solidity 0-{}、OpenZeppelin v5 命名空间和 Vyper 布局），请用 --balance-slot 指定",
+        "Could not find the balance storage slot of token {} (probed Solidity 0-{}, the OpenZeppelin v5 namespace and Vyper layouts); pass --balance-slot"
+    ),
+    BalanceSlotProbing => ("正在探测余额 mapping 的存储槽...", "Probing for the balance mapping's storage slot..."),
+    BalanceSlotUsed => ("余额 mapping 位置: 槽 {}", "Balance mapping: slot {}"),
+    FakeBalanceNote => (
+        "⚠ 以下余额包含 eth_call 状态覆盖的模拟值，只在这次查询中生效，链上余额没有变化",
+        "⚠ These balances include simulated eth_call state overrides; they apply to this query only and nothing changed on chain"
+    ),
+    FakeBalanceMark => ("（模拟）", " (simulated)"),
+
+    QueryingCodeSizes => ("正在查询 {} 个地址的字节码大小...", "Querying bytecode size of {} addresses..."),
+    CodeSizeAddress => ("地址", "Address"),
+    CodeSizeSize => ("字节码大小", "Code size"),
+    CodeSizeOfLimit => ("占上限", "Of limit"),
+    CodeSizeEoa => ("0（EOA）", "0 (EOA)"),
+    CodeSizeNearLimit => ("⚠ 接近 EIP-170 上限", "⚠ Near the EIP-170 limit"),
+
+    QueryingTokenBalances => ("正在读取 {} 个地址的 {} 余额...", "Reading {} balances of {} addresses..."),
+    EncodeBalanceOfFailed => ("无法编码 balanceOf 调用", "Failed to encode the balanceOf call"),
+    BalanceOfBadLength => ("balanceOf 返回数据长度异常: {} 字节", "balanceOf returned an unexpected length: {} bytes"),
+
+    QueryingAllowances => ("正在查询 {} 对 {} 个地址的 {} 授权...", "Querying {}'s {} approvals for {} spenders..."),
+    AllowanceSpender => ("被授权地址", "Spender"),
+    AllowanceHeader => ("授权额度", "Allowance"),
+    UnlimitedAllowance => ("⚠ 无限授权", "⚠ Unlimited"),
+    UnlimitedWarning => (
+        "⚠ 发现 {} 个无限授权，对应合约可以随时转走全部 {}，建议撤销",
+        "⚠ Found {} unlimited approvals; those contracts can move all of your {} at any time, consider revoking"
+    ),
+    Revoking => ("📝 撤销 {} 的授权...", "📝 Revoking approval for {}..."),
+    TxSent => ("✓ 交易已发送: {}", "✓ Transaction sent: {}"),
+    TxConfirmed => ("✓ 已确认，区块 {}", "✓ Confirmed in block {}"),
+    NoReceipt => ("⚠ 交易已发送，但未收到确认收据", "⚠ Transaction sent, but no receipt was received"),
+    RevokeSkipped => ("- {} 的授权已经是 0，跳过", "- Allowance for {} is already 0, skipping"),
+    RevokedCount => ("✓ 共撤销 {} 个授权", "✓ Revoked {} approvals"),
+
+    TransferringToken => ("📝 转账 {} {} 到 {}...", "📝 Transferring {} {} to {}..."),
+    TransferNoReceipt => ("交易已发送，但未收到确认收据", "Transaction sent, but no receipt was received"),
+    TransferReverted => ("交易已上链但执行失败（status = 0）", "Transaction was mined but reverted (status = 0)"),
+    TransferSucceeded => ("✅ 代币转账成功！", "✅ Token transfer succeeded!"),
+    EventsHeader => ("发出的事件（{} 个）:", "Emitted events ({}):"),
+    NoEvents => ("交易没有发出可解码的事件", "The transaction emitted no decodable events"),
+
+    BenchRpc => ("RPC: {}", "RPC: {}"),
+    BenchStart => ("读取 {} 个地址的代币余额，每种方式运行 {} 轮...", "Reading token balances of {} addresses, {} rounds per strategy..."),
+    BenchSkipped => ("⚠ 跳过 {}（{}）", "⚠ Skipped {} ({})"),
+    BenchTitle => ("{} 个地址代币余额读取", "Token balance reads for {} addresses"),
+    BenchMismatch => (
+        "⚠ {} 的结果与 sequential 不一致（查询期间可能出了新区块）",
+        "⚠ {} results differ from sequential (a new block may have landed during the run)"
+    ),
+    SimulateTitle => ("=== 模拟 revert（只用 eth_call，不会广播任何交易）===", "=== Simulating a revert (eth_call only, nothing is broadcast) ==="),
+    SimulateContract => ("合约:       {}", "Contract:     {}"),
+    SimulateFrom => ("调用方:     {}", "From:         {}"),
+    SimulateCall => ("调用:       {}", "Call:         {}"),
+    SimulateCustomCall => ("自定义 calldata，选择器 {}", "custom calldata, selector {}"),
+    SimulateEmptyCall => ("空 calldata（触发 fallback / receive）", "empty calldata (hits fallback / receive)"),
+    SimulateInvalidData => ("无效的 calldata 十六进制: {}", "Invalid calldata hex: {}"),
+    SimulateNoRevert => ("⚠ 调用没有 revert，返回值: {}（换一个一定会失败的调用再试）", "⚠ The call did not revert; it returned {} (try a call that is sure to fail)"),
+    SimulateReverted => ("✓ 调用按预期 revert，节点拒绝执行", "✓ The call reverted as expected"),
+    SimulateNodeMessage => ("1. 节点返回的错误信息: {}", "1. Node error message: {}"),
+    SimulateRevertData => ("2. revert 数据（{} 字节）: {}", "2. Revert data ({} bytes): {}"),
+    SimulateNoRevertData => ("2. 节点没有返回 revert 数据，只能使用错误信息", "2. The node returned no revert data; only the message is available"),
+    SimulateReason => ("3. 解码出的原因: {}", "3. Decoded reason: {}"),
+}
+
+/// 代币信息报告中的名称一行
+pub fn token_name(name: &str) -> String {
+    tf(Msg::TokenName, &[&name])
+}
+
+/// 代币信息报告中的符号一行
+pub fn token_symbol(symbol: &str) -> String {
+    tf(Msg::TokenSymbol, &[&symbol])
+}
+
+/// 代币转账开始时的说明
+pub fn transferring_token(amount: &str, symbol: &str, to: &Address) -> String {
+    tf(
+        Msg::TransferringToken,
+        &[&amount, &symbol, &arbi_common::style::addr(format!("{:?}", to))],
+    )
+}
+
+// 输出快照：措辞或格式的改动都会让这里失败，需要在评审时一并确认
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::i18n::{Lang, with_lang};
+
+    #[test]
+    fn token_info_report_snapshot() {
+        let report = || [token_name("USD Coin"), token_symbol("USDC")].join("\n");
+        assert_eq!(with_lang(Lang::Zh, report), "✓ 代币名称: USD Coin\n✓ 代币符号: USDC");
+        assert_eq!(with_lang(Lang::En, report), "✓ Token name: USD Coin\n✓ Token symbol: USDC");
+    }
+}
@@ -0,0 +1,123 @@
+//! 演示 revert 原因的提取：用 `eth_call` 调用一个一定会失败的函数，只模拟、从不广播交易
+
+use arbi_common::revert;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::abi::Tokenizable;
+use ethers::providers::{Middleware, RpcError};
+use ethers::types::{Address, Bytes, TransactionRequest, U256};
+use ethers::utils::hex;
+use std::error::Error;
+
+use crate::messages::{Msg, t, tf};
+use crate::token;
+
+/// 默认的模拟调用方：众所周知的销毁地址，没有人持有它的私钥
+///
+/// `eth_call` 不需要签名，调用方可以是任意地址。
+pub const DEFAULT_FROM: &str = "0x000000000000000000000000000000000000dEaD";
+
+/// 要模拟的调用
+pub struct RevertCall {
+    pub contract: Address,
+    pub from: Address,
+    pub data: Bytes,
+    /// 显示给用户的调用说明
+    pub description: String,
+}
+
+impl RevertCall {
+    /// 默认调用：`transfer(from, type(uint256).max)`
+    ///
+    /// 转出的数量是 uint256 的最大值，任何地址的余额都不可能这么多，所以代币合约一定会 revert
+    /// （通常是 "transfer amount exceeds balance"，新版 OpenZeppelin 为自定义错误 `ERC20InsufficientBalance`）。
+    ///
+    /// # 参数
+    /// * `contract` - 代币合约地址
+    /// * `from` - 模拟的调用方
+    ///
+    /// # 返回
+    /// * `Result<RevertCall, Box<dyn Error>>` - 要模拟的调用
+    pub fn over_transfer(contract: Address, from: Address) -> Result<Self, Box<dyn Error>> {
+        let data = token::erc20_abi()?
+            .function("transfer")?
+            .encode_input(&[from.into_token(), U256::MAX.into_token()])?;
+        Ok(Self {
+            contract,
+            from,
+            data: data.into(),
+            description: format!("transfer({:?}, {})", from, U256::MAX),
+        })
+    }
+
+    /// 自定义调用：直接使用给定的 calldata
+    ///
+    /// # 参数
+    /// * `contract` - 合约地址
+    /// * `from` - 模拟的调用方
+    /// * `data` - calldata（十六进制）
+    ///
+    /// # 返回
+    /// * `Result<RevertCall, Box<dyn Error>>` - 要模拟的调用
+    pub fn custom(contract: Address, from: Address, data: &str) -> Result<Self, Box<dyn Error>> {
+        let data: Bytes = data.trim().parse().map_err(|_| tf(Msg::SimulateInvalidData, &[&data]))?;
+        let description = match data.get(..4) {
+            Some(selector) => tf(Msg::SimulateCustomCall, &[&hex::encode_prefixed(selector)]),
+            None => t(Msg::SimulateEmptyCall).to_string(),
+        };
+        Ok(Self {
+            contract,
+            from,
+            data,
+            description,
+        })
+    }
+}
+
+/// 用 `eth_call` 执行调用，逐步打印节点返回的错误、revert 数据和解码出的原因
+///
+/// 只调用 `eth_call`，不签名、不广播。调用意外成功时打印警告；
+/// 网络错误等不是执行失败的错误原样返回。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `call` - 要模拟的调用
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+pub async fn simulate_revert(
+    provider: &RpcProvider,
+    call: &RevertCall,
+) -> Result<(), Box<dyn Error>> {
+    println!("{}", tf(Msg::SimulateContract, &[&style::addr(format!("{:?}", call.contract))]));
+    println!("{}", tf(Msg::SimulateFrom, &[&format!("{:?}", call.from)]));
+    println!("{}", tf(Msg::SimulateCall, &[&call.description]));
+
+    let tx = TransactionRequest::new()
+        .from(call.from)
+        .to(call.contract)
+        .data(call.data.clone());
+    let error = match provider.call(&tx.into(), None).await {
+        Ok(output) => {
+            println!("\n{}", style::warn(tf(Msg::SimulateNoRevert, &[&output])));
+            return Ok(());
+        }
+        Err(error) => error,
+    };
+    // 没有 JSON-RPC 错误响应说明请求本身失败了（网络等），不是合约 revert
+    let Some(response) = error.as_error_response() else {
+        return Err(error.into());
+    };
+
+    println!("\n{}", style::ok(t(Msg::SimulateReverted)));
+    println!("{}", tf(Msg::SimulateNodeMessage, &[&response.message]));
+    match response.as_revert_data() {
+        Some(data) if !data.is_empty() => {
+            println!("{}", tf(Msg::SimulateRevertData, &[&data.len(), &data]));
+        }
+        _ => println!("{}", t(Msg::SimulateNoRevertData)),
+    }
+    let reason = revert::call_error_reason(&error).unwrap_or_default();
+    println!("{}", style::ok(tf(Msg::SimulateReason, &[&reason])));
+    Ok(())
+}
@@ -0,0 +1,288 @@
+//! 用 `eth_call` 的状态覆盖（state override）预览"假如地址持有某个数量的代币"时的余额
+//!
+//! `eth_call` 的第三个参数可以临时改写任意账户的代码、余额和存储，只在这次调用中生效，
+//! 不需要私钥也不会上链。ERC20 的余额存在合约存储的一个 mapping 里，
+//! 找到持有人对应的存储槽并写入想要的数值，`balanceOf` 就会返回这个数值。
+//!
+//! # 余额存储槽的位置
+//!
+//! `mapping(address => uint256)` 声明在第 `p` 个槽时，持有人 `h` 的余额存放在：
+//! - Solidity：`keccak256(abi.encode(h, p))`
+//! - Vyper：`keccak256(abi.encode(p, h))`（键和槽的顺序相反）
+//!
+//! 常见代币的 `p`：OpenZeppelin ERC20 v4 为 0；可升级版（ERC20Upgradeable v4，Arbitrum
+//! 桥接代币）在 `Initializable` 和 50 个槽的 `__gap` 之后为 51；WETH9 为 3；Circle 的 USDC
+//! （FiatToken）为 9；OpenZeppelin v5 使用 ERC-7201 命名空间存储，`p` 为
+//! `keccak256(abi.encode(uint256(keccak256("openzeppelin.storage.ERC20")) - 1)) & ~0xff`。
+//! 代理合约的存储在代理地址上，覆盖代币（代理）地址即可。
+//!
+//! 不需要事先知道 `p`：[`find_balance_slot`] 依次把候选槽改成一个特殊值，
+//! 看 `balanceOf` 是否返回它，第一个命中的就是余额 mapping 的位置。
+
+use arbi_common::concurrency::{self, DEFAULT_CONCURRENCY};
+use arbi_common::rpc::RpcProvider;
+use arbi_common::units;
+use ethers::abi::{Token, encode};
+use ethers::providers::{RawCall, RpcError, spoof};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, BlockId, Bytes, H256, TransactionRequest, U256};
+use ethers::utils::{hex, keccak256};
+use std::error::Error;
+use std::fmt;
+
+use crate::messages::{Msg, t, tf};
+
+/// 自动探测时尝试的最大 Solidity 槽号（可升级的 OpenZeppelin 代币在 51）
+pub const MAX_PROBE_SLOT: u64 = 64;
+
+// Vyper 代币的状态变量一般不多，只探测前几个槽
+const MAX_VYPER_PROBE_SLOT: u64 = 10;
+
+// 探测时写入的值：不可能是真实余额，`balanceOf` 返回它就说明找到了槽
+const PROBE_SENTINEL: &[u8] = b"arbi balance slot probe";
+
+// 检查节点是否支持状态覆盖的合约代码：PUSH1 0x2a PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN，返回 42
+const PROBE_CODE: &str = "602a60005260206000f3";
+const PROBE_CODE_RESULT: u64 = 42;
+
+// 用来放探测代码的地址，没有人会在这里部署合约
+const PROBE_ADDRESS: Address = Address::repeat_byte(0xa5);
+
+/// mapping 键和槽号拼接的顺序
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// `keccak256(abi.encode(key, slot))`
+    Solidity,
+    /// `keccak256(abi.encode(slot, key))`
+    Vyper,
+}
+
+/// 余额 mapping 在合约存储中的位置
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BalanceSlot {
+    pub slot: U256,
+    pub layout: Layout,
+}
+
+impl BalanceSlot {
+    /// Solidity 合约中声明在第 `slot` 个槽的余额 mapping
+    pub fn solidity(slot: U256) -> Self {
+        Self {
+            slot,
+            layout: Layout::Solidity,
+        }
+    }
+
+    /// 持有人的余额所在的存储槽
+    ///
+    /// # 参数
+    /// * `holder` - 持有人地址
+    ///
+    /// # 返回
+    /// * `H256` - 存储槽
+    pub fn key(&self, holder: Address) -> H256 {
+        let (holder, slot) = (Token::Address(holder), Token::Uint(self.slot));
+        let encoded = match self.layout {
+            Layout::Solidity => encode(&[holder, slot]),
+            Layout::Vyper => encode(&[slot, holder]),
+        };
+        H256::from(keccak256(encoded))
+    }
+}
+
+impl fmt::Display for BalanceSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let layout = match self.layout {
+            Layout::Solidity => "Solidity",
+            Layout::Vyper => "Vyper",
+        };
+        // ERC-7201 命名空间的槽号是个哈希，按十六进制显示
+        if self.slot > U256::from(u64::MAX) {
+            write!(f, "{:#x} ({})", self.slot, layout)
+        } else {
+            write!(f, "{} ({})", self.slot, layout)
+        }
+    }
+}
+
+/// OpenZeppelin v5 ERC20 的 ERC-7201 命名空间存储位置，`_balances` 是其中第一个字段
+pub fn oz_erc20_namespace() -> U256 {
+    let id = U256::from_big_endian(&keccak256("openzeppelin.storage.ERC20")) - 1;
+    let location = U256::from_big_endian(&keccak256(encode(&[Token::Uint(id)])));
+    location & !U256::from(0xff)
+}
+
+// 按可能性排列的候选位置：Solidity 的前几个槽、OpenZeppelin v5 命名空间、Vyper 的前几个槽
+fn candidates() -> Vec<BalanceSlot> {
+    let solidity = (0..=MAX_PROBE_SLOT).map(|slot| BalanceSlot::solidity(slot.into()));
+    let vyper = (0..=MAX_VYPER_PROBE_SLOT).map(|slot| BalanceSlot {
+        slot: slot.into(),
+        layout: Layout::Vyper,
+    });
+    solidity
+        .chain(std::iter::once(BalanceSlot::solidity(oz_erc20_namespace())))
+        .chain(vyper)
+        .collect()
+}
+
+/// `balanceOf(holder)` 的调用数据
+pub fn balance_of_call(token: Address, holder: Address) -> TypedTransaction {
+    let mut data = keccak256("balanceOf(address)")[..4].to_vec();
+    data.extend(encode(&[Token::Address(holder)]));
+    TransactionRequest::new().to(token).data(Bytes::from(data)).into()
+}
+
+/// 带状态覆盖调用 `balanceOf(holder)`
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `token` - 代币合约地址
+/// * `holder` - 持有人地址
+/// * `block` - 在哪个区块的状态上执行
+/// * `state` - 状态覆盖
+///
+/// # 返回
+/// * `Result<U256, Box<dyn Error>>` - 覆盖后的余额
+pub async fn balance_of(
+    provider: &RpcProvider,
+    token: Address,
+    holder: Address,
+    block: BlockId,
+    state: &spoof::State,
+) -> Result<U256, Box<dyn Error>> {
+    let tx = balance_of_call(token, holder);
+    let output = provider.call_raw(&tx).block(block).state(state).await?;
+    if output.len() < 32 {
+        return Err(tf(Msg::BalanceOfBadLength, &[&output.len()]).into());
+    }
+    Ok(U256::from_big_endian(&output[..32]))
+}
+
+/// 确认节点支持并真正应用了状态覆盖
+///
+/// 给一个空地址覆盖一段返回 42 的代码再调用它：节点不认识第三个参数时会报错，
+/// 忽略覆盖时返回空结果，两种情况都返回说明原因的错误。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `block` - 在哪个区块的状态上执行
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 支持时为 `Ok`
+pub async fn check_support(provider: &RpcProvider, block: BlockId) -> Result<(), Box<dyn Error>> {
+    let mut state = spoof::State::default();
+    state.account(PROBE_ADDRESS).code(hex::decode(PROBE_CODE)?.into());
+    let tx = TransactionRequest::new().to(PROBE_ADDRESS).into();
+    let expected = H256::from_low_u64_be(PROBE_CODE_RESULT);
+    match provider.call_raw(&tx).block(block).state(&state).await {
+        Ok(output) if output.as_ref() == expected.as_bytes() => Ok(()),
+        Ok(_) => Err(t(Msg::StateOverrideIgnored).into()),
+        Err(e) => match e.as_error_response() {
+            Some(response) => Err(tf(Msg::StateOverrideUnsupported, &[&response.message]).into()),
+            None => Err(e.into()),
+        },
+    }
+}
+
+/// 找出代币余额 mapping 的存储位置
+///
+/// 按 [`candidates`] 的顺序，每次并发探测 `DEFAULT_CONCURRENCY` 个位置：把 `holder` 在该位置
+/// 对应的槽改成一个特殊值，`balanceOf(holder)` 返回这个值就说明找到了。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `token` - 代币合约地址
+/// * `holder` - 用来探测的持有人地址（任意地址都可以）
+/// * `block` - 在哪个区块的状态上执行
+///
+/// # 返回
+/// * `Result<BalanceSlot, Box<dyn Error>>` - 余额 mapping 的位置；都没有命中时返回错误
+pub async fn find_balance_slot(
+    provider: &RpcProvider,
+    token: Address,
+    holder: Address,
+    block: BlockId,
+) -> Result<BalanceSlot, Box<dyn Error>> {
+    let sentinel = U256::from_big_endian(PROBE_SENTINEL);
+    let candidates = candidates();
+    for chunk in candidates.chunks(DEFAULT_CONCURRENCY) {
+        let probes = concurrency::run_bounded(chunk, DEFAULT_CONCURRENCY, |candidate| async move {
+            let mut state = spoof::State::default();
+            let value = H256::from(<[u8; 32]>::from(sentinel));
+            state.account(token).store(candidate.key(holder), value);
+            balance_of(provider, token, holder, block, &state).await
+        })
+        .await;
+        // 改写的只是 mapping 中的一个槽，balanceOf 出错说明合约本身有问题，不再继续探测
+        let probes = probes.into_iter().collect::<Result<Vec<_>, _>>()?;
+        if let Some((candidate, _)) = chunk.iter().zip(probes).find(|(_, b)| *b == sentinel) {
+            return Ok(*candidate);
+        }
+    }
+    Err(tf(Msg::BalanceSlotNotFound, &[&format!("{:?}", token), &MAX_PROBE_SLOT]).into())
+}
+
+/// 解析 `地址=数量` 形式的模拟余额
+///
+/// # 参数
+/// * `value` - 命令行参数
+/// * `decimals` - 代币的小数位数
+///
+/// # 返回
+/// * `Result<(Address, U256), Box<dyn Error>>` - 持有人和最小单位的余额
+pub fn parse_fake_balance(value: &str, decimals: u8) -> Result<(Address, U256), Box<dyn Error>> {
+    let invalid = |e: &dyn fmt::Display| tf(Msg::InvalidFakeBalance, &[&value, &e]);
+    let (holder, amount) = value
+        .split_once('=')
+        .ok_or_else(|| invalid(&t(Msg::FakeBalanceFormat)))?;
+    let holder = holder.trim().parse().map_err(|e| invalid(&e))?;
+    let amount = units::parse_amount(amount, decimals as u32).map_err(|e| invalid(&e))?;
+    Ok((holder, amount))
+}
+
+/// 带状态覆盖并发读取所有持有人的余额
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `token` - 代币合约地址
+/// * `holders` - 持有人地址列表
+/// * `block` - 在哪个区块的状态上执行
+/// * `state` - 状态覆盖
+///
+/// # 返回
+/// * `Result<Vec<U256>, Box<dyn Error>>` - 与输入顺序一致的余额
+pub async fn balances_of(
+    provider: &RpcProvider,
+    token: Address,
+    holders: &[Address],
+    block: BlockId,
+    state: &spoof::State,
+) -> Result<Vec<U256>, Box<dyn Error>> {
+    let balances = concurrency::run_bounded(holders, DEFAULT_CONCURRENCY, |holder| {
+        balance_of(provider, token, *holder, block, state)
+    })
+    .await;
+    balances.into_iter().collect()
+}
+
+/// 把每个持有人的余额覆盖为指定数值
+///
+/// # 参数
+/// * `token` - 代币合约地址
+/// * `slot` - 余额 mapping 的位置
+/// * `balances` - 持有人和覆盖后的余额（最小单位）
+///
+/// # 返回
+/// * `spoof::State` - 传给 `eth_call` 的状态覆盖
+pub fn fake_balances(
+    token: Address,
+    slot: BalanceSlot,
+    balances: &[(Address, U256)],
+) -> spoof::State {
+    let mut state = spoof::State::default();
+    let account = state.account(token);
+    for (holder, balance) in balances {
+        account.store(slot.key(*holder), H256::from(<[u8; 32]>::from(*balance)));
+    }
+    state
+}
@@ -0,0 +1,296 @@
+use arbi_common::concurrency::{self, DEFAULT_CONCURRENCY};
+use arbi_common::narrate;
+use arbi_common::rpc::{RpcProvider, is_pruned_state_error};
+use arbi_common::rpc_batch;
+use arbi_common::style;
+use ethers::abi::{Abi, AbiError};
+use ethers::prelude::*;
+use ethers::types::{Address, BlockNumber, Bytes, U256};
+use serde_json::json;
+use std::error::Error;
+use std::sync::Arc;
+use tracing::{Instrument, info, info_span};
+
+use crate::ERC20_ABI;
+use crate::messages::{Msg, t, tf};
+
+// 早期的代币（如 MKR）把 name() / symbol() 声明成 bytes32，按 string 解码会失败
+const BYTES32_METADATA_ABI: [&str; 2] = [
+    "function name() view returns (bytes32)",
+    "function symbol() view returns (bytes32)",
+];
+
+/// 解析 ERC20 ABI（包含 `Transfer` / `Approval` 事件，可用于解码收据日志）
+///
+/// # 返回
+/// * `Result<Abi, Box<dyn Error>>` - ERC20 ABI
+pub fn erc20_abi() -> Result<Abi, Box<dyn Error>> {
+    Ok(serde_json::from_str(ERC20_ABI)?)
+}
+
+/// 创建 ERC20 合约实例
+///
+/// # 参数
+/// * `client` - 共享的 Provider（只读）或签名客户端（需要发送交易时）
+/// * `token` - 代币合约地址
+///
+/// # 返回
+/// * `Result<Contract<M>, Box<dyn Error>>` - 合约实例
+pub fn erc20_contract<M: Middleware>(client: Arc<M>, token: Address) -> Result<Contract<M>, Box<dyn Error>> {
+    Ok(Contract::new(token, erc20_abi()?, client))
+}
+
+/// 读取 `name()` 或 `symbol()`，兼容返回 `bytes32` 的非标准代币
+///
+/// 先按标准的 `string` 解码；返回值解码失败时改用 `bytes32` ABI 重新调用，
+/// 去掉末尾补齐的 0 字节后按 UTF-8 转成字符串。
+///
+/// # 参数
+/// * `contract` - ERC20 合约实例
+/// * `method` - `"name"` 或 `"symbol"`
+///
+/// # 返回
+/// * `Result<String, Box<dyn Error>>` - 名称或符号
+pub async fn read_metadata<M: Middleware + 'static>(
+    contract: &Contract<M>,
+    method: &str,
+) -> Result<String, Box<dyn Error>> {
+    match contract.method::<_, String>(method, ())?.call().await {
+        Ok(text) => Ok(text),
+        Err(ContractError::DecodingError(_) | ContractError::AbiError(_)) => {
+            info!(method, token = ?contract.address(), "string decode failed, retrying as bytes32");
+            let abi = ethers::abi::parse_abi(&BYTES32_METADATA_ABI)?;
+            let fallback = Contract::new(contract.address(), abi, contract.client());
+            let raw: [u8; 32] = fallback.method(method, ())?.call().await?;
+            let end = raw.iter().rposition(|&byte| byte != 0).map_or(0, |last| last + 1);
+            Ok(String::from_utf8_lossy(&raw[..end]).into_owned())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 发送 `transfer(to, value)` 转账代币，并等待交易确认
+///
+/// # 参数
+/// * `wallet` - 签名客户端
+/// * `token` - 代币合约地址
+/// * `to` - 收款地址
+/// * `value` - 转账数量（代币最小单位）
+///
+/// # 返回
+/// * `Result<TransactionReceipt, Box<dyn Error>>` - 交易收据
+pub async fn transfer_token<M: Middleware + 'static>(
+    wallet: Arc<M>,
+    token: Address,
+    to: Address,
+    value: U256,
+) -> Result<TransactionReceipt, Box<dyn Error>> {
+    let contract = erc20_contract(wallet, token)?;
+
+    let call = contract.method::<_, bool>("transfer", (to, value))?;
+    let pending_tx = call
+        .send()
+        .instrument(info_span!("broadcast", token = ?token, to = ?to))
+        .await?;
+    let tx_hash = pending_tx.tx_hash();
+    info!(tx_hash = ?tx_hash, "transfer broadcast");
+    println!("{}", style::ok(tf(Msg::TxSent, &[&style::addr(format!("{:?}", tx_hash))])));
+
+    let receipt = pending_tx
+        .instrument(info_span!("confirm", tx_hash = ?tx_hash))
+        .await?
+        .ok_or_else(|| t(Msg::TransferNoReceipt))?;
+    info!(tx_hash = ?tx_hash, block_number = ?receipt.block_number, "transfer confirmed");
+    narrate!(
+        "{}",
+        style::ok(tf(Msg::TxConfirmed, &[&format!("{:?}", receipt.block_number)]))
+    );
+
+    Ok(receipt)
+}
+
+/// 逐个顺序调用 `balanceOf`
+///
+/// # 参数
+/// * `contract` - ERC20 合约实例
+/// * `holders` - 持有人地址列表
+/// * `block` - 读取哪个区块的余额
+///
+/// # 返回
+/// * `Result<Vec<U256>, Box<dyn Error>>` - 与输入顺序一致的原始余额
+pub async fn balances_of_sequential(
+    contract: &Contract<RpcProvider>,
+    holders: &[Address],
+    block: BlockNumber,
+) -> Result<Vec<U256>, Box<dyn Error>> {
+    let mut balances = Vec::with_capacity(holders.len());
+    for holder in holders {
+        let balance: U256 = contract.method("balanceOf", *holder)?.block(block).call().await?;
+        balances.push(balance);
+    }
+    Ok(balances)
+}
+
+/// 并发发起 `balanceOf` 调用，同时最多 `DEFAULT_CONCURRENCY` 个
+///
+/// # 参数
+/// * `contract` - ERC20 合约实例
+/// * `holders` - 持有人地址列表
+/// * `block` - 读取哪个区块的余额
+///
+/// # 返回
+/// * `Result<Vec<U256>, Box<dyn Error>>` - 与输入顺序一致的原始余额
+pub async fn balances_of_concurrent(
+    contract: &Contract<RpcProvider>,
+    holders: &[Address],
+    block: BlockNumber,
+) -> Result<Vec<U256>, Box<dyn Error>> {
+    let calls = holders
+        .iter()
+        .map(|holder| {
+            let call = contract.method::<_, U256>("balanceOf", *holder)?;
+            Ok::<_, AbiError>(call.block(block))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let balances = concurrency::run_bounded(&calls, DEFAULT_CONCURRENCY, |call| call.call()).await;
+    Ok(balances.into_iter().collect::<Result<_, _>>()?)
+}
+
+/// 通过 Multicall3 在一次 `eth_call` 中读取所有 `balanceOf`
+///
+/// 读取早于 Multicall3 部署的区块时调用会失败，`balances_of` 会退回并发调用。
+///
+/// # 参数
+/// * `contract` - ERC20 合约实例
+/// * `holders` - 持有人地址列表
+/// * `block` - 读取哪个区块的余额
+///
+/// # 返回
+/// * `Result<Vec<U256>, Box<dyn Error>>` - 与输入顺序一致的原始余额
+pub async fn balances_of_multicall(
+    contract: &Contract<RpcProvider>,
+    holders: &[Address],
+    block: BlockNumber,
+) -> Result<Vec<U256>, Box<dyn Error>> {
+    let mut multicall = Multicall::new(contract.client(), None).await?.block(block);
+    for holder in holders {
+        multicall.add_call(contract.method::<_, U256>("balanceOf", *holder)?, false);
+    }
+    let balances: Vec<U256> = multicall.call_array().await?;
+    Ok(balances)
+}
+
+/// 把所有 `balanceOf` 的 `eth_call` 打包成一个 JSON-RPC 批量请求
+///
+/// # 参数
+/// * `rpc_url` - RPC 节点地址
+/// * `contract` - ERC20 合约实例（用于编码调用数据）
+/// * `holders` - 持有人地址列表
+/// * `block` - 读取哪个区块的余额
+///
+/// # 返回
+/// * `Result<Vec<U256>, Box<dyn Error>>` - 与输入顺序一致的原始余额
+pub async fn balances_of_batched(
+    rpc_url: &str,
+    contract: &Contract<RpcProvider>,
+    holders: &[Address],
+    block: BlockNumber,
+) -> Result<Vec<U256>, Box<dyn Error>> {
+    let mut calls = Vec::with_capacity(holders.len());
+    for holder in holders {
+        let data = contract
+            .method::<_, U256>("balanceOf", *holder)?
+            .calldata()
+            .ok_or(t(Msg::EncodeBalanceOfFailed))?;
+        let tx = json!({ "to": contract.address(), "data": data });
+        calls.push(("eth_call", json!([tx, block])));
+    }
+
+    let results = rpc_batch::batch_call(rpc_url, &calls).await?;
+    let mut balances = Vec::with_capacity(results.len());
+    for result in results {
+        let raw: Bytes = serde_json::from_value(result)?;
+        if raw.len() < 32 {
+            return Err(tf(Msg::BalanceOfBadLength, &[&raw.len()]).into());
+        }
+        balances.push(U256::from_big_endian(&raw[..32]));
+    }
+    Ok(balances)
+}
+
+/// 批量读取代币余额：优先使用 Multicall3，节点上没有部署时（例如本地 anvil）退回并发调用
+///
+/// 读取历史区块需要归档节点；节点已裁剪那个区块的状态时返回说明原因的错误。
+///
+/// # 参数
+/// * `contract` - ERC20 合约实例
+/// * `holders` - 持有人地址列表
+/// * `block` - 读取哪个区块的余额
+///
+/// # 返回
+/// * `Result<Vec<U256>, Box<dyn Error>>` - 与输入顺序一致的原始余额
+pub async fn balances_of(
+    contract: &Contract<RpcProvider>,
+    holders: &[Address],
+    block: BlockNumber,
+) -> Result<Vec<U256>, Box<dyn Error>> {
+    let balances = match balances_of_multicall(contract, holders, block).await {
+        Ok(balances) => Ok(balances),
+        Err(e) => {
+            narrate!("{}", style::warn(tf(Msg::MulticallFallback, &[&e])));
+            balances_of_concurrent(contract, holders, block).await
+        }
+    };
+    balances.map_err(|e| match block {
+        BlockNumber::Number(number) if is_pruned_state_error(&e.to_string()) => {
+            tf(Msg::HistoricalStateUnavailable, &[&number, &e]).into()
+        }
+        _ => e,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use ethers::abi::{Token, encode};
+
+    // 让 symbol() 固定返回一段原始返回值的节点
+    async fn symbol_returning(raw: Vec<u8>) -> String {
+        let rpc = MockRpc::start(move |method, _| match method {
+            "eth_call" => Reply::Result(json!(Bytes::from(raw.clone()))),
+            _ => Reply::Error(format!("unexpected {}", method)),
+        });
+        let token = Address::repeat_byte(0x11);
+        let contract = erc20_contract(Arc::new(rpc.provider()), token).unwrap();
+        read_metadata(&contract, "symbol").await.unwrap()
+    }
+
+    // 左对齐、右侧补 0 的 bytes32
+    fn bytes32(prefix: &[u8]) -> Vec<u8> {
+        let mut word = prefix.to_vec();
+        word.resize(32, 0);
+        word
+    }
+
+    #[tokio::test]
+    async fn reads_standard_string_symbol() {
+        let raw = encode(&[Token::String("USDC".into())]);
+        assert_eq!(symbol_returning(raw).await, "USDC");
+    }
+
+    // MKR 的 symbol() 返回 bytes32("MKR")
+    #[tokio::test]
+    async fn falls_back_to_bytes32_and_trims_padding() {
+        assert_eq!(symbol_returning(bytes32(b"MKR")).await, "MKR");
+        assert_eq!(symbol_returning(bytes32(b"")).await, "");
+        // 只去掉末尾的 0，中间的 0 字节保留
+        assert_eq!(symbol_returning(bytes32(b"A\0B")).await, "A\0B");
+    }
+
+    #[tokio::test]
+    async fn invalid_utf8_is_replaced_not_an_error() {
+        let symbol = symbol_returning(bytes32(&[0xff, 0xfe, b'X'])).await;
+        assert_eq!(symbol, "\u{fffd}\u{fffd}X");
+    }
+}
@@ -0,0 +1,107 @@
+use arbi_common::style;
+use ethers::abi::Token;
+use ethers::prelude::*;
+use std::error::Error;
+
+use crate::counter;
+use crate::messages::{Msg, t, tf};
+
+// 参与对比的调用：函数名和参数
+fn sample_calls() -> [(&'static str, Vec<Token>); 4] {
+    [
+        ("increment", vec![]),
+        ("setNumber", vec![Token::Uint(42.into())]),
+        ("addNumber", vec![Token::Uint(7.into())]),
+        ("mulNumber", vec![Token::Uint(3.into())]),
+    ]
+}
+
+/// 同一个调用在两种实现上的 Gas 估算
+pub struct GasSample {
+    pub function: &'static str,
+    pub stylus: U256,
+    pub solidity: U256,
+}
+
+impl GasSample {
+    /// Stylus 相对 Solidity 节省的 Gas 百分比，为负时表示 Stylus 更贵
+    pub fn saving_percent(&self) -> f64 {
+        if self.solidity.is_zero() {
+            return 0.0;
+        }
+        let stylus = self.stylus.as_u128() as f64;
+        let solidity = self.solidity.as_u128() as f64;
+        (solidity - stylus) / solidity * 100.0
+    }
+}
+
+/// 估算一次调用的 Gas
+async fn estimate<M: Middleware + 'static>(
+    contract: &Contract<M>,
+    function: &str,
+    args: Vec<Token>,
+) -> Result<U256, Box<dyn Error>> {
+    contract
+        .method::<_, ()>(function, args)?
+        .estimate_gas()
+        .await
+        .map_err(|e| counter::explain_error(contract.address(), e))
+}
+
+/// 对同样的几次调用分别估算 Stylus 和 Solidity 计数器的 Gas
+///
+/// 两边的 calldata 完全相同，Arbitrum 估算结果里的 L1 数据费部分也相同，差值就是执行成本的差异。
+///
+/// # 参数
+/// * `stylus` - Stylus 计数器
+/// * `solidity` - Solidity 计数器
+///
+/// # 返回
+/// * `Result<Vec<GasSample>, Box<dyn Error>>` - 每个调用的估算结果
+pub async fn compare_gas<M: Middleware + 'static>(
+    stylus: &Contract<M>,
+    solidity: &Contract<M>,
+) -> Result<Vec<GasSample>, Box<dyn Error>> {
+    let mut samples = Vec::new();
+    for (function, args) in sample_calls() {
+        samples.push(GasSample {
+            function,
+            stylus: estimate(stylus, function, args.clone()).await?,
+            solidity: estimate(solidity, function, args).await?,
+        });
+    }
+    Ok(samples)
+}
+
+/// 打印 Gas 对比表
+///
+/// # 参数
+/// * `samples` - `compare_gas` 的结果
+pub fn print_comparison(samples: &[GasSample]) {
+    println!(
+        "{:<12} {:>12} {:>12} {:>10}",
+        t(Msg::ColFunction),
+        t(Msg::ColStylus),
+        t(Msg::ColSolidity),
+        t(Msg::ColSaving)
+    );
+    for sample in samples {
+        // 先补齐宽度再上色，颜色控制符不计入对齐
+        let saving = format!("{:+.1}%", sample.saving_percent());
+        let saving = format!("{:>10}", saving);
+        let saving = if sample.stylus <= sample.solidity {
+            style::ok(saving)
+        } else {
+            style::warn(saving)
+        };
+        println!(
+            "{:<12} {:>12} {:>12} {}",
+            sample.function, sample.stylus, sample.solidity, saving
+        );
+    }
+    println!("\n{}", t(Msg::ComparisonNote));
+
+    let total_stylus = samples.iter().fold(U256::zero(), |sum, s| sum + s.stylus);
+    let total_solidity = samples.iter().fold(U256::zero(), |sum, s| sum + s.solidity);
+    println!("{}", tf(Msg::ComparisonTotal, &[&total_stylus, &total_solidity]));
+}
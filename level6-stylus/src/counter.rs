@@ -0,0 +1,100 @@
+use ethers::abi::Abi;
+use ethers::prelude::*;
+use ethers::utils::id;
+use std::error::Error;
+use std::sync::Arc;
+
+use crate::messages::{Msg, tf};
+
+// Stylus 计数器示例（cargo stylus new 生成的模板）的 ABI。
+// 对调用方来说 Stylus 合约就是普通合约，等价的 Solidity 计数器使用同一份 ABI
+const COUNTER_ABI: &str = include_str!("../abi/counter.json");
+
+// Stylus 程序部署后的字节码以 0xEFF000 开头，后面是压缩过的 WASM
+const STYLUS_CODE_PREFIX: [u8; 3] = [0xEF, 0xF0, 0x00];
+
+// ArbWasm 在程序还不能执行时返回的错误
+const ACTIVATION_ERRORS: [&str; 3] = [
+    "ProgramNotActivated()",
+    "ProgramNeedsUpgrade(uint16,uint16)",
+    "ProgramExpired(uint64)",
+];
+
+/// 判断字节码是否为 Stylus 程序
+///
+/// # 参数
+/// * `code` - `eth_getCode` 返回的字节码
+///
+/// # 返回
+/// * `bool` - 以 Stylus 前缀开头时为 `true`
+pub fn is_stylus_code(code: &[u8]) -> bool {
+    code.starts_with(&STYLUS_CODE_PREFIX)
+}
+
+/// 用计数器 ABI 创建合约实例，Stylus 和 Solidity 计数器共用
+///
+/// # 参数
+/// * `address` - 合约地址
+/// * `client` - Provider 或签名客户端
+///
+/// # 返回
+/// * `Result<Contract<M>, Box<dyn Error>>` - 合约实例
+pub fn counter<M: Middleware>(
+    address: Address,
+    client: Arc<M>,
+) -> Result<Contract<M>, Box<dyn Error>> {
+    let abi: Abi = serde_json::from_str(COUNTER_ABI)?;
+    Ok(Contract::new(address, abi, client))
+}
+
+/// 调用失败是否因为 Stylus 程序还没有激活（或需要重新激活）
+///
+/// Stylus 程序部署后还要调用 ArbWasm 的 `activateProgram` 把 WASM 编译成机器码才能执行，
+/// 未激活、版本过旧或激活已过期时调用会 revert。
+///
+/// # 参数
+/// * `err` - 合约调用返回的错误
+///
+/// # 返回
+/// * `bool` - revert 数据是 ArbWasm 的激活错误，或节点错误信息中提到未激活时为 `true`
+pub fn is_activation_error<M: Middleware>(err: &ContractError<M>) -> bool {
+    if let Some(data) = err.as_revert()
+        && data.len() >= 4
+    {
+        return ACTIVATION_ERRORS.iter().any(|sig| data[..4] == id(sig));
+    }
+    err.to_string().to_lowercase().contains("not activated")
+}
+
+/// 把合约调用错误转成给用户看的错误，未激活时给出激活方法
+///
+/// # 参数
+/// * `address` - 被调用的合约地址
+/// * `err` - 合约调用返回的错误
+///
+/// # 返回
+/// * `Box<dyn Error>` - 转换后的错误
+pub fn explain_error<M: Middleware + 'static>(address: Address, err: ContractError<M>) -> Box<dyn Error> {
+    if is_activation_error(&err) {
+        tf(Msg::NotActivated, &[&format!("{:?}", address)]).into()
+    } else {
+        err.into()
+    }
+}
+
+/// 读取计数器的当前值
+///
+/// # 参数
+/// * `contract` - 计数器合约实例
+///
+/// # 返回
+/// * `Result<U256, Box<dyn Error>>` - `number()` 的返回值
+pub async fn number<M: Middleware + 'static>(
+    contract: &Contract<M>,
+) -> Result<U256, Box<dyn Error>> {
+    contract
+        .method::<_, U256>("number", ())?
+        .call()
+        .await
+        .map_err(|e| explain_error(contract.address(), e))
+}
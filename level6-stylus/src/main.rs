@@ -0,0 +1,234 @@
+mod compare;
+mod counter;
+mod messages;
+
+use arbi_common::cli::GlobalArgs;
+use arbi_common::env;
+use arbi_common::narrate;
+use arbi_common::network;
+use arbi_common::rpc::{self, RpcProvider};
+use arbi_common::style;
+use clap::{Parser, Subcommand, ValueEnum};
+use ethers::prelude::*;
+use ethers::signers::LocalWallet;
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{Instrument, Span, field, info_span};
+
+use messages::{Msg, t, tf};
+
+// 未通过命令行传入地址时读取的环境变量
+const STYLUS_ADDRESS_ENV: &str = "STYLUS_COUNTER_ADDRESS";
+const SOLIDITY_ADDRESS_ENV: &str = "SOLIDITY_COUNTER_ADDRESS";
+
+/// Arbitrum Stylus 合约交互工具
+#[derive(Parser)]
+#[command(about = "Arbitrum Stylus（WASM）合约交互与 Gas 对比")]
+struct Cli {
+    #[command(flatten)]
+    global: GlobalArgs,
+
+    /// Stylus 计数器合约地址（默认读取 STYLUS_COUNTER_ADDRESS）
+    #[arg(long, global = true)]
+    stylus: Option<String>,
+
+    /// 等价的 Solidity 计数器合约地址（默认读取 SOLIDITY_COUNTER_ADDRESS）
+    #[arg(long, global = true)]
+    solidity: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 读取两个计数器并对比同样调用的 Gas 估算（默认）
+    Compare,
+    /// 调用 increment() 写入计数器（需要 PRIVATE_KEY）
+    Increment {
+        /// 要写入的计数器
+        #[arg(long, value_enum, default_value_t = Target::Stylus)]
+        target: Target,
+    },
+}
+
+/// 计数器的实现
+#[derive(Clone, Copy, ValueEnum)]
+enum Target {
+    Stylus,
+    Solidity,
+}
+
+impl Target {
+    fn name(self) -> &'static str {
+        match self {
+            Target::Stylus => "Stylus",
+            Target::Solidity => "Solidity",
+        }
+    }
+}
+
+/// 读取合约地址：命令行优先，其次环境变量
+///
+/// # 参数
+/// * `cli` - 命令行传入的地址
+/// * `target` - 哪一个计数器
+///
+/// # 返回
+/// * `Result<Address, Box<dyn Error>>` - 解析后的地址
+fn counter_address(cli: Option<&str>, target: Target) -> Result<Address, Box<dyn Error>> {
+    let (flag, env) = match target {
+        Target::Stylus => ("--stylus", STYLUS_ADDRESS_ENV),
+        Target::Solidity => ("--solidity", SOLIDITY_ADDRESS_ENV),
+    };
+    let address = cli
+        .map(str::to_string)
+        .or_else(|| std::env::var(env).ok())
+        .ok_or_else(|| tf(Msg::MissingAddress, &[&target.name(), &flag, &env]))?;
+    Ok(Address::from_str(address.trim())?)
+}
+
+/// 检查 Stylus 地址上确实部署了 Stylus 程序
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - Stylus 计数器地址
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 没有代码时返回错误；不是 Stylus 程序时只打印警告
+async fn check_stylus_code(provider: &RpcProvider, address: Address) -> Result<(), Box<dyn Error>> {
+    let code = provider.get_code(address, None).await?;
+    if code.is_empty() {
+        return Err(tf(Msg::NoCode, &[&format!("{:?}", address)]).into());
+    }
+    if counter::is_stylus_code(&code) {
+        narrate!("{}", style::ok(t(Msg::StylusCodeDetected)));
+    } else {
+        eprintln!("{}", style::warn(tf(Msg::NotStylusCode, &[&format!("{:?}", address)])));
+    }
+    Ok(())
+}
+
+/// 读取两个计数器的值并打印 Gas 对比
+///
+/// # 参数
+/// * `provider` - 共享的 Provider
+/// * `stylus` - Stylus 计数器地址
+/// * `solidity` - Solidity 计数器地址
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_compare(
+    provider: Arc<RpcProvider>,
+    stylus: Address,
+    solidity: Address,
+) -> Result<(), Box<dyn Error>> {
+    check_stylus_code(&provider, stylus).await?;
+    let stylus = counter::counter(stylus, provider.clone())?;
+    let solidity = counter::counter(solidity, provider)?;
+
+    narrate!("{}", t(Msg::StepRead));
+    println!("{}", tf(Msg::CurrentNumber, &[&"Stylus  ", &counter::number(&stylus).await?]));
+    println!("{}", tf(Msg::CurrentNumber, &[&"Solidity", &counter::number(&solidity).await?]));
+
+    narrate!("{}", t(Msg::StepCompare));
+    let samples = compare::compare_gas(&stylus, &solidity).await?;
+    compare::print_comparison(&samples);
+    Ok(())
+}
+
+/// 通过签名客户端调用 increment()，确认后打印 Gas 用量和新的值
+///
+/// # 参数
+/// * `provider` - 共享的 Provider
+/// * `address` - 计数器地址
+/// * `target` - 计数器的实现
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_increment(
+    provider: Arc<RpcProvider>,
+    address: Address,
+    target: Target,
+) -> Result<(), Box<dyn Error>> {
+    if let Target::Stylus = target {
+        check_stylus_code(&provider, address).await?;
+    }
+
+    let private_key = env::var("PRIVATE_KEY").ok_or(t(Msg::MissingKey))?;
+    let wallet: LocalWallet = private_key.parse()?;
+    let chain_id = provider.get_chainid().instrument(info_span!("connect")).await?;
+    Span::current().record("chain_id", chain_id.as_u64());
+    narrate!("{}", style::ok(tf(Msg::Connected, &[&chain_id])));
+    network::check_chain_id(chain_id.as_u64())?;
+
+    let wallet = network::signing_wallet(wallet, chain_id.as_u64())?;
+    let client = Arc::new(SignerMiddleware::new((*provider).clone(), wallet));
+    let contract = counter::counter(address, client)?;
+
+    narrate!("\n{}", tf(Msg::Incrementing, &[&target.name()]));
+    let call = contract.method::<_, ()>("increment", ())?;
+    let pending = call
+        .send()
+        .instrument(info_span!("broadcast"))
+        .await
+        .map_err(|e| counter::explain_error(address, e))?;
+    let tx_hash = pending.tx_hash();
+    println!("{}", tf(Msg::TxHash, &[&style::addr(format!("{:?}", tx_hash))]));
+
+    let receipt = pending
+        .instrument(info_span!("confirm"))
+        .await?
+        .ok_or_else(|| tf(Msg::TxDropped, &[&format!("{:?}", tx_hash)]))?;
+    if receipt.status != Some(1.into()) {
+        return Err(t(Msg::TxReverted).into());
+    }
+
+    let gas_used = receipt.gas_used.unwrap_or_default();
+    let number = counter::number(&contract).await?;
+    println!("{}", style::ok(tf(Msg::IncrementDone, &[&gas_used, &number])));
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    cli.global.init();
+    dotenv::dotenv().ok(); // 加载 .env 文件（如果存在），地址和私钥都可以配置在里面
+
+    narrate!("{}\n", t(Msg::Title));
+
+    let rpc_url = network::current().rpc_url();
+    let provider = Arc::new(rpc::connect(rpc_url)?);
+
+    let span = info_span!("command", rpc_url, chain_id = field::Empty);
+    let result = async {
+        match cli.command.unwrap_or(Command::Compare) {
+            Command::Compare => {
+                let stylus = counter_address(cli.stylus.as_deref(), Target::Stylus)?;
+                let solidity = counter_address(cli.solidity.as_deref(), Target::Solidity)?;
+                narrate!("{}", tf(Msg::StylusContract, &[&style::addr(stylus)]));
+                narrate!("{}", tf(Msg::SolidityContract, &[&style::addr(solidity)]));
+                run_compare(provider, stylus, solidity).await
+            }
+            Command::Increment { target } => {
+                let address = match target {
+                    Target::Stylus => cli.stylus.as_deref(),
+                    Target::Solidity => cli.solidity.as_deref(),
+                };
+                let address = counter_address(address, target)?;
+                run_increment(provider, address, target).await
+            }
+        }
+    }
+    .instrument(span)
+    .await;
+
+    if let Err(e) = result {
+        eprintln!("\n{}", style::err(tf(Msg::CommandFailed, &[&e])));
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+//! level6 输出的消息目录
+
+arbi_common::define_messages! {
+    Title => ("=== Arbitrum Stylus 合约交互演示 ===", "=== Arbitrum Stylus Contract Interaction Demo ==="),
+    Connected => ("✓ 连接成功（Chain ID: {}）", "✓ Connected (chain ID: {})"),
+    StylusContract => ("Stylus 计数器:   {}", "Stylus counter:   {}"),
+    SolidityContract => ("Solidity 计数器: {}", "Solidity counter: {}"),
+    MissingAddress => (
+        "缺少{}计数器地址：用 {} 传入，或在 .env 中设置 {}",
+        "Missing the {} counter address: pass {} or set {} in .env"
+    ),
+    NoCode => ("地址 {} 上没有合约代码，请确认网络和地址", "No contract code at {}; check the network and address"),
+    NotStylusCode => (
+        "⚠ {} 的字节码不是以 0xEFF000 开头，看起来不是 Stylus 程序",
+        "⚠ The bytecode at {} does not start with 0xEFF000; it does not look like a Stylus program"
+    ),
+    StylusCodeDetected => ("✓ 字节码以 0xEFF000 开头，是 Stylus（WASM）程序", "✓ Bytecode starts with 0xEFF000: a Stylus (WASM) program"),
+    NotActivated => (
+        "Stylus 程序 {} 尚未激活（或需要重新激活）。部署后要调用 ArbWasm.activateProgram 编译 WASM 才能执行，可以运行 `cargo stylus activate --address <地址>`，或部署时不要加 --no-activate",
+        "Stylus program {} is not activated (or needs reactivation). After deployment ArbWasm.activateProgram must compile the WASM before it can run; use `cargo stylus activate --address <address>`, or deploy without --no-activate"
+    ),
+    StepRead => ("\n1. 通过同一份 ABI 读取两个计数器...", "\n1. Reading both counters through the same ABI..."),
+    CurrentNumber => ("{} number() = {}", "{} number() = {}"),
+    StepCompare => ("\n2. 估算同样调用的 Gas...\n", "\n2. Estimating gas for the same calls...\n"),
+    ColFunction => ("函数", "Function"),
+    ColStylus => ("Stylus", "Stylus"),
+    ColSolidity => ("Solidity", "Solidity"),
+    ColSaving => ("节省", "Saving"),
+    ComparisonNote => (
+        "两边 calldata 相同，估算中的 L1 数据费部分也相同，差值来自执行成本：计数器这类简单逻辑差别不大，计算密集的逻辑 Stylus 优势更明显",
+        "Calldata is identical, so the L1 data fee part of each estimate is too and the difference is execution cost: simple logic like a counter differs little, compute-heavy logic favours Stylus much more"
+    ),
+    ComparisonTotal => ("合计: Stylus {} / Solidity {}", "Total: Stylus {} / Solidity {}"),
+    Incrementing => ("调用 {} 计数器的 increment()...", "Calling increment() on the {} counter..."),
+    TxHash => ("交易哈希: {}", "Transaction hash: {}"),
+    IncrementDone => ("✅ 已上链，使用 Gas {}，number() 现在为 {}", "✅ Mined using {} gas; number() is now {}"),
+    TxReverted => ("交易已上链但执行失败（status = 0）", "Transaction was mined but reverted (status = 0)"),
+    TxDropped => ("交易 {} 没有返回收据", "Transaction {} returned no receipt"),
+    MissingKey => ("未设置 PRIVATE_KEY 环境变量，可以在 .env 文件中配置", "PRIVATE_KEY is not set; you can configure it in a .env file"),
+    CommandFailed => ("❌ 执行失败: {}", "❌ Failed: {}"),
+}
@@ -0,0 +1,332 @@
+use arbi_common::concurrency;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::providers::{Middleware, ProviderError};
+use ethers::types::{Address, BlockId, TxHash, U256};
+use ethers::utils::{format_units, to_checksum};
+use serde_json::{Value, json};
+use std::error::Error;
+use std::future::Future;
+
+use crate::messages::{Msg, t, tf};
+
+/// 扫描近期区块时同时进行的请求数
+const SCAN_CONCURRENCY: usize = 8;
+
+/// 在 `[lo, hi]` 中二分查找第一个让 `predicate` 为真的区块
+///
+/// `predicate` 必须对区块号单调：某个区块为真之后，后面的区块都为真（比如"该区块结束时 nonce 大于 0"）。
+/// 先探测 `hi`，为假时直接返回 `None`；之后每次把范围减半，所以总共最多
+/// ⌈log2(hi - lo + 1)⌉ + 1 次调用（Arbitrum One 目前约 30 次）。
+///
+/// # 参数
+/// * `lo` - 起始区块（含）
+/// * `hi` - 结束区块（含）
+/// * `predicate` - 区块上的条件，通常需要一次 RPC 调用
+///
+/// # 返回
+/// * `Result<Option<u64>, Box<dyn Error>>` - 第一个满足条件的区块；整个范围都不满足时为 `None`
+pub async fn first_block<F, Fut>(
+    lo: u64,
+    hi: u64,
+    mut predicate: F,
+) -> Result<Option<u64>, Box<dyn Error>>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<bool, Box<dyn Error>>>,
+{
+    if lo > hi || !predicate(hi).await? {
+        return Ok(None);
+    }
+    // 不变式：hi 满足条件，lo 之前的区块都不满足
+    let (mut lo, mut hi) = (lo, hi);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if predicate(mid).await? {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    Ok(Some(hi))
+}
+
+/// 第一笔发出的交易
+pub enum FirstOutgoing {
+    /// nonce 为 0，还没有发过交易
+    None,
+    /// 合约账户的 nonce 记录的是它创建的合约数，不对应发出的交易
+    Contract,
+    Found {
+        block: u64,
+        timestamp: u64,
+        /// 区块中没有找到对应交易时为 `None`（例如 nonce 被其他机制改变）
+        tx_hash: Option<TxHash>,
+    },
+    /// 节点不支持查询历史状态等原因导致查找失败
+    Unavailable(String),
+}
+
+/// 最近若干区块中的活动
+pub struct RecentActivity {
+    pub from_block: u64,
+    pub to_block: u64,
+    pub sent: usize,
+    pub received: usize,
+    /// 最近一次出现的区块
+    pub last_seen: Option<u64>,
+}
+
+/// 账户概况
+pub struct AccountProfile {
+    pub address: Address,
+    pub balance: U256,
+    pub nonce: U256,
+    pub code_size: usize,
+    pub first_outgoing: FirstOutgoing,
+    pub activity: RecentActivity,
+}
+
+/// 二分查找 nonce 第一次大于 0 的区块，就是第一笔发出交易所在的区块
+///
+/// 需要节点能查询历史区块上的 nonce（归档节点）。
+async fn first_outgoing(
+    provider: &RpcProvider,
+    address: Address,
+    latest: u64,
+) -> Result<FirstOutgoing, Box<dyn Error>> {
+    let sent_by = |block: u64| async move {
+        let nonce = provider
+            .get_transaction_count(address, Some(BlockId::Number(block.into())))
+            .await?;
+        Ok::<_, Box<dyn Error>>(!nonce.is_zero())
+    };
+    let Some(number) = first_block(0, latest, sent_by).await? else {
+        return Ok(FirstOutgoing::None);
+    };
+    let block = provider
+        .get_block_with_txs(number)
+        .await?
+        .ok_or_else(|| tf(Msg::BlockNotFound, &[&number]))?;
+    let tx_hash = block
+        .transactions
+        .iter()
+        .find(|tx| tx.from == address)
+        .map(|tx| tx.hash);
+    Ok(FirstOutgoing::Found {
+        block: number,
+        timestamp: block.timestamp.as_u64(),
+        tx_hash,
+    })
+}
+
+/// 扫描最近的区块，统计发出和收到的交易数
+async fn recent_activity(
+    provider: &RpcProvider,
+    address: Address,
+    latest: u64,
+    blocks: u64,
+) -> Result<RecentActivity, Box<dyn Error>> {
+    let from_block = latest.saturating_sub(blocks.saturating_sub(1));
+    let mut activity = RecentActivity {
+        from_block,
+        to_block: latest,
+        sent: 0,
+        received: 0,
+        last_seen: None,
+    };
+    // 每个区块只留下 (发出数, 收到数)，不把几千个区块的交易都留在内存里
+    let count = |number: u64| async move {
+        let block = provider.get_block_with_txs(number).await?;
+        let txs = block.map(|block| block.transactions).unwrap_or_default();
+        let sent = txs.iter().filter(|tx| tx.from == address).count();
+        let received = txs.iter().filter(|tx| tx.to == Some(address)).count();
+        Ok::<_, ProviderError>((sent, received))
+    };
+    let counts = concurrency::run_bounded(from_block..=latest, SCAN_CONCURRENCY, count).await;
+    for (number, counts) in (from_block..=latest).zip(counts) {
+        let (sent, received) = counts?;
+        activity.sent += sent;
+        activity.received += received;
+        if sent + received > 0 {
+            activity.last_seen = Some(number);
+        }
+    }
+    Ok(activity)
+}
+
+/// 汇总账户概况
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 账户地址
+/// * `blocks` - 扫描最近多少个区块统计活动
+///
+/// # 返回
+/// * `Result<AccountProfile, Box<dyn Error>>` - 概况；查找第一笔交易失败时只在结果里标记，不返回错误
+pub async fn account_profile(
+    provider: &RpcProvider,
+    address: Address,
+    blocks: u64,
+) -> Result<AccountProfile, Box<dyn Error>> {
+    let latest = provider.get_block_number().await?.as_u64();
+    let at = Some(BlockId::Number(latest.into()));
+    let balance = provider.get_balance(address, at).await?;
+    let nonce = provider.get_transaction_count(address, at).await?;
+    let code_size = provider.get_code(address, at).await?.len();
+
+    let first_outgoing = if code_size > 0 {
+        FirstOutgoing::Contract
+    } else if nonce.is_zero() {
+        FirstOutgoing::None
+    } else {
+        first_outgoing(provider, address, latest)
+            .await
+            .unwrap_or_else(|e| FirstOutgoing::Unavailable(e.to_string()))
+    };
+    let activity = recent_activity(provider, address, latest, blocks).await?;
+
+    Ok(AccountProfile {
+        address,
+        balance,
+        nonce,
+        code_size,
+        first_outgoing,
+        activity,
+    })
+}
+
+impl AccountProfile {
+    /// 打印概况
+    pub fn print(&self) -> Result<(), Box<dyn Error>> {
+        let address = to_checksum(&self.address, None);
+        println!("{}", tf(Msg::AccountTitle, &[&style::addr(address)]));
+        println!("{}", tf(Msg::AccountBalance, &[&format_units(self.balance, "ether")?]));
+        println!("{}", tf(Msg::AccountNonce, &[&self.nonce]));
+        let kind = match self.code_size {
+            0 => t(Msg::AccountEoa).to_string(),
+            size => tf(Msg::AccountContract, &[&size]),
+        };
+        println!("{}", tf(Msg::AccountKind, &[&kind]));
+
+        let first = match &self.first_outgoing {
+            FirstOutgoing::None => t(Msg::AccountNeverSent).to_string(),
+            FirstOutgoing::Contract => t(Msg::AccountFirstContract).to_string(),
+            FirstOutgoing::Found {
+                block,
+                timestamp,
+                tx_hash,
+            } => {
+                let hash = tx_hash.map(|hash| format!("  {:?}", hash)).unwrap_or_default();
+                tf(Msg::AccountFirstFound, &[&block, &timestamp, &hash])
+            }
+            FirstOutgoing::Unavailable(reason) => {
+                style::warn(tf(Msg::AccountFirstUnavailable, &[&reason]))
+            }
+        };
+        println!("{}", tf(Msg::AccountFirstOutgoing, &[&first]));
+
+        let activity = &self.activity;
+        println!(
+            "{}",
+            tf(
+                Msg::AccountActivity,
+                &[&activity.from_block, &activity.to_block, &activity.sent, &activity.received]
+            )
+        );
+        if let Some(block) = activity.last_seen {
+            println!("{}", tf(Msg::AccountLastSeen, &[&block]));
+        }
+        Ok(())
+    }
+
+    /// 以 JSON 表示概况，金额为 wei 的十进制字符串
+    pub fn to_json(&self) -> Value {
+        let first_outgoing = match &self.first_outgoing {
+            FirstOutgoing::None => json!({ "status": "none" }),
+            FirstOutgoing::Contract => json!({ "status": "contract" }),
+            FirstOutgoing::Found {
+                block,
+                timestamp,
+                tx_hash,
+            } => json!({
+                "status": "found",
+                "block": block,
+                "timestamp": timestamp,
+                "tx_hash": tx_hash,
+            }),
+            FirstOutgoing::Unavailable(reason) => {
+                json!({ "status": "unavailable", "error": reason })
+            }
+        };
+        let activity = &self.activity;
+        json!({
+            "address": to_checksum(&self.address, None),
+            "balance_wei": self.balance.to_string(),
+            "nonce": self.nonce.as_u64(),
+            "is_contract": self.code_size > 0,
+            "code_size": self.code_size,
+            "first_outgoing": first_outgoing,
+            "recent": {
+                "from_block": activity.from_block,
+                "to_block": activity.to_block,
+                "sent": activity.sent,
+                "received": activity.received,
+                "last_seen": activity.last_seen,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    // 模拟账户在 `first_tx` 区块发出第一笔交易后的 nonce，并记录查询次数
+    async fn find(lo: u64, hi: u64, first_tx: u64) -> (Option<u64>, u32) {
+        let calls = Cell::new(0);
+        let nonce_after = |block: u64| {
+            calls.set(calls.get() + 1);
+            async move { Ok::<_, Box<dyn Error>>(U256::from((block >= first_tx) as u64)) }
+        };
+        let found = first_block(lo, hi, |block| {
+            let nonce = nonce_after(block);
+            async move { Ok(!nonce.await?.is_zero()) }
+        })
+        .await
+        .unwrap();
+        (found, calls.get())
+    }
+
+    #[tokio::test]
+    async fn finds_first_block_with_few_calls() {
+        let latest = 250_000_000;
+        for first_tx in [0, 1, 12_345_678, latest - 1, latest] {
+            let (found, calls) = find(0, latest, first_tx).await;
+            assert_eq!(found, Some(first_tx));
+            // ⌈log2(250_000_001)⌉ + 1
+            assert!(calls <= 29, "{first_tx}: {calls} calls");
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_none_when_never_true() {
+        let (found, calls) = find(100, 200, 201).await;
+        assert_eq!((found, calls), (None, 1));
+        assert_eq!(find(200, 100, 0).await, (None, 0));
+    }
+
+    #[tokio::test]
+    async fn respects_range_bounds() {
+        // 条件在 lo 之前就已为真时返回 lo
+        assert_eq!(find(100, 200, 50).await.0, Some(100));
+        assert_eq!(find(7, 7, 7).await, (Some(7), 1));
+    }
+
+    #[tokio::test]
+    async fn propagates_predicate_errors() {
+        let result = first_block(0, 10, |_| async { Err("node error".into()) }).await;
+        assert_eq!(result.unwrap_err().to_string(), "node error");
+    }
+}
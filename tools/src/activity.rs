@@ -0,0 +1,322 @@
+use arbi_common::explorer;
+use arbi_common::rpc::{RpcProvider, is_pruned_state_error};
+use arbi_common::style;
+use ethers::providers::Middleware;
+use ethers::types::{Address, BlockId, TxHash};
+use serde_json::{Value, json};
+use std::error::Error;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::account::first_block;
+use crate::history::{self, HistoryEntry};
+use crate::json_cache;
+use crate::messages::{Msg, t, tf};
+
+// 区块浏览器的索引会落后节点一些区块，最近这些区块的结果不写入缓存，下次重新查询
+const EXPLORER_LAG_BLOCKS: u64 = 1000;
+
+/// 查找账户活动的方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActivityMethod {
+    /// 区块浏览器 API：统计账户发出和收到的全部外部交易
+    Explorer,
+    /// 按 nonce 二分查找区块：只能统计账户发出的交易，需要归档节点
+    NonceSearch,
+}
+
+impl ActivityMethod {
+    /// 缓存文件中使用的名称
+    fn as_str(self) -> &'static str {
+        match self {
+            ActivityMethod::Explorer => "explorer",
+            ActivityMethod::NonceSearch => "nonce",
+        }
+    }
+}
+
+/// 一笔交易所在的区块
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActivityPoint {
+    pub block: u64,
+    /// 区块时间（Unix 秒）
+    pub timestamp: u64,
+    /// 区块中没有找到对应交易时为 `None`
+    pub tx_hash: Option<TxHash>,
+}
+
+impl ActivityPoint {
+    fn to_json(self) -> Value {
+        json!({
+            "block": self.block,
+            "timestamp": self.timestamp,
+            "tx_hash": self.tx_hash.map(|h| format!("{:?}", h)),
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        Some(Self {
+            block: value["block"].as_u64()?,
+            timestamp: value["timestamp"].as_u64()?,
+            tx_hash: value["tx_hash"].as_str().and_then(|s| TxHash::from_str(s).ok()),
+        })
+    }
+}
+
+/// 账户第一笔和最近一笔交易，以及交易总数
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountActivity {
+    /// 没有任何交易时为 `None`
+    pub first: Option<ActivityPoint>,
+    pub last: Option<ActivityPoint>,
+    pub tx_count: u64,
+    pub method: ActivityMethod,
+    /// 统计截至的区块（含）
+    pub as_of_block: u64,
+    /// 是否完全来自本地缓存（缓存之后没有新区块）
+    pub cached: bool,
+}
+
+impl AccountActivity {
+    fn to_json(&self) -> Value {
+        json!({
+            "method": self.method.as_str(),
+            "as_of_block": self.as_of_block,
+            "tx_count": self.tx_count,
+            "first": self.first.map(ActivityPoint::to_json),
+            "last": self.last.map(ActivityPoint::to_json),
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        let method = match value["method"].as_str()? {
+            "explorer" => ActivityMethod::Explorer,
+            "nonce" => ActivityMethod::NonceSearch,
+            _ => return None,
+        };
+        Some(Self {
+            first: ActivityPoint::from_json(&value["first"]),
+            last: ActivityPoint::from_json(&value["last"]),
+            tx_count: value["tx_count"].as_u64()?,
+            method,
+            as_of_block: value["as_of_block"].as_u64()?,
+            cached: true,
+        })
+    }
+
+    /// 把缓存之后新区块中的活动合并进来
+    ///
+    /// 第一笔交易以较早的为准，最近一笔以较新的为准，交易数相加。
+    ///
+    /// # 参数
+    /// * `newer` - `as_of_block + 1` 到最新区块之间的活动
+    ///
+    /// # 返回
+    /// * `AccountActivity` - 截至 `newer.as_of_block` 的活动
+    pub fn merge(self, newer: AccountActivity) -> AccountActivity {
+        AccountActivity {
+            first: self.first.or(newer.first),
+            last: newer.last.or(self.last),
+            tx_count: self.tx_count + newer.tx_count,
+            method: newer.method,
+            as_of_block: newer.as_of_block,
+            cached: false,
+        }
+    }
+}
+
+/// 汇总区块浏览器返回的交易中截至 `as_of_block` 的部分
+///
+/// # 参数
+/// * `entries` - 交易，顺序不限
+/// * `as_of_block` - 统计截至的区块（含），之后的交易不计入
+///
+/// # 返回
+/// * `AccountActivity` - 第一笔、最近一笔交易和交易数
+pub fn summarize(entries: &[HistoryEntry], as_of_block: u64) -> AccountActivity {
+    let settled: Vec<&HistoryEntry> =
+        entries.iter().filter(|entry| entry.block <= as_of_block).collect();
+    let point = |entry: &&HistoryEntry| ActivityPoint {
+        block: entry.block,
+        timestamp: entry.timestamp,
+        tx_hash: Some(entry.hash),
+    };
+    AccountActivity {
+        first: settled.iter().min_by_key(|entry| entry.block).map(point),
+        last: settled.iter().max_by_key(|entry| entry.block).map(point),
+        tx_count: settled.len() as u64,
+        method: ActivityMethod::Explorer,
+        as_of_block,
+        cached: false,
+    }
+}
+
+/// 二分查找账户发出 nonce 为 `nonce` 的交易所在的区块，并在区块里找出这笔交易
+async fn find_nonce(
+    provider: &RpcProvider,
+    address: Address,
+    nonce: u64,
+    (lo, hi): (u64, u64),
+) -> Result<Option<ActivityPoint>, Box<dyn Error>> {
+    // 区块结束时的 nonce 大于 `nonce`，说明这笔交易已经上链
+    let sent = |block: u64| async move {
+        let count = provider
+            .get_transaction_count(address, Some(BlockId::Number(block.into())))
+            .await
+            .map_err(|e| match is_pruned_state_error(&e.to_string()) {
+                true => tf(Msg::ActivityStateUnavailable, &[&block, &e]),
+                false => e.to_string(),
+            })?;
+        Ok::<_, Box<dyn Error>>(count.as_u64() > nonce)
+    };
+    let Some(number) = first_block(lo, hi, sent).await? else {
+        return Ok(None);
+    };
+    let block = provider
+        .get_block_with_txs(number)
+        .await?
+        .ok_or_else(|| tf(Msg::BlockNotFound, &[&number]))?;
+    let tx_hash = block
+        .transactions
+        .iter()
+        .find(|tx| tx.from == address && tx.nonce.as_u64() == nonce)
+        .map(|tx| tx.hash);
+    Ok(Some(ActivityPoint {
+        block: number,
+        timestamp: block.timestamp.as_u64(),
+        tx_hash,
+    }))
+}
+
+/// 没有 API key 时按 nonce 查找 `[from_block, to_block]` 中账户发出的第一笔和最后一笔交易
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 账户地址
+/// * `(from_block, to_block)` - 区块范围
+/// * `base_nonce` - `from_block` 之前已经发出的交易数
+async fn activity_from_nonce(
+    provider: &RpcProvider,
+    address: Address,
+    (from_block, to_block): (u64, u64),
+    base_nonce: u64,
+) -> Result<AccountActivity, Box<dyn Error>> {
+    let at = Some(BlockId::Number(to_block.into()));
+    let nonce = provider.get_transaction_count(address, at).await?.as_u64();
+    let (first, last) = match nonce.checked_sub(1).filter(|last| *last >= base_nonce) {
+        Some(last_nonce) => {
+            let range = (from_block, to_block);
+            let first = find_nonce(provider, address, base_nonce, range).await?;
+            let last = match last_nonce == base_nonce {
+                true => first,
+                false => find_nonce(provider, address, last_nonce, range).await?,
+            };
+            (first, last)
+        }
+        None => (None, None),
+    };
+    Ok(AccountActivity {
+        first,
+        last,
+        tx_count: nonce.saturating_sub(base_nonce),
+        method: ActivityMethod::NonceSearch,
+        as_of_block: to_block,
+        cached: false,
+    })
+}
+
+/// 查找账户第一笔和最近一笔交易，以及交易总数
+///
+/// 配置了 `ARBISCAN_API_KEY` 时用区块浏览器 API 统计发出和收到的全部外部交易；否则按 nonce
+/// 二分查找区块，只能统计账户发出的交易，并且需要归档节点。结果写入缓存文件，下次只查询缓存
+/// 之后的新区块；缓存用的查找方式与这次不同时重新查询。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 账户地址
+/// * `cache_path` - 缓存文件路径
+///
+/// # 返回
+/// * `Result<AccountActivity, Box<dyn Error>>` - 账户的活动
+pub async fn account_activity(
+    provider: &RpcProvider,
+    address: Address,
+    cache_path: &Path,
+) -> Result<AccountActivity, Box<dyn Error>> {
+    let api_key = explorer::api_key();
+    let method = match api_key {
+        Some(_) => ActivityMethod::Explorer,
+        None => ActivityMethod::NonceSearch,
+    };
+    let cached = json_cache::get(cache_path, address)
+        .as_ref()
+        .and_then(AccountActivity::from_json)
+        .filter(|cached| cached.method == method);
+
+    let latest = provider.get_block_number().await?.as_u64();
+    if let Some(cached) = cached.as_ref().filter(|cached| cached.as_of_block >= latest) {
+        return Ok(cached.clone());
+    }
+    let from_block = cached.as_ref().map_or(0, |cached| cached.as_of_block + 1);
+
+    // 显示的结果截至最新区块；写入缓存的结果不包括浏览器可能还没有索引完的最近区块
+    let (newer, settled) = match api_key {
+        Some(key) => {
+            let entries = history::history_from_explorer(&key, address, from_block, latest);
+            let entries = entries.await?;
+            let settled_block = latest.saturating_sub(EXPLORER_LAG_BLOCKS).max(from_block.saturating_sub(1));
+            (summarize(&entries, latest), summarize(&entries, settled_block))
+        }
+        None => {
+            eprintln!("{}", style::warn(tf(Msg::ActivityNonceOnly, &[&explorer::API_KEY_ENV])));
+            let base_nonce = cached.as_ref().map_or(0, |cached| cached.tx_count);
+            let newer = activity_from_nonce(provider, address, (from_block, latest), base_nonce);
+            let newer = newer.await?;
+            (newer.clone(), newer)
+        }
+    };
+    let (activity, settled) = match cached {
+        Some(cached) => (cached.clone().merge(newer), cached.merge(settled)),
+        None => (newer, settled),
+    };
+
+    if let Err(e) = json_cache::put(cache_path, address, settled.to_json()) {
+        eprintln!("{}", style::warn(tf(Msg::OriginCacheFailed, &[&e])));
+    }
+    Ok(activity)
+}
+
+/// 打印账户的活动
+///
+/// # 参数
+/// * `activity` - `account_activity` 的结果
+pub fn print_activity(activity: &AccountActivity) {
+    let (Some(first), Some(last)) = (activity.first, activity.last) else {
+        println!("{}", tf(Msg::ActivityNone, &[&activity.as_of_block]));
+        print_source(activity);
+        return;
+    };
+    for (label, point) in [(Msg::ActivityFirst, first), (Msg::ActivityLast, last)] {
+        let hash = match point.tx_hash {
+            Some(hash) => style::addr(format!("{:?}", hash)),
+            None => t(Msg::ActivityHashMissing).to_string(),
+        };
+        println!("{}", tf(label, &[&point.block, &point.timestamp, &hash]));
+    }
+    let count = match activity.method {
+        ActivityMethod::Explorer => tf(Msg::ActivityCount, &[&activity.tx_count]),
+        ActivityMethod::NonceSearch => tf(Msg::ActivityCountSent, &[&activity.tx_count]),
+    };
+    println!("{}", count);
+    print_source(activity);
+}
+
+// 数据来源和统计截至的区块
+fn print_source(activity: &AccountActivity) {
+    let source = match (activity.method, activity.cached) {
+        (_, true) => t(Msg::OriginSourceCache),
+        (ActivityMethod::Explorer, false) => t(Msg::OriginSourceExplorer),
+        (ActivityMethod::NonceSearch, false) => t(Msg::ActivitySourceNonce),
+    };
+    println!("{}", tf(Msg::ActivitySource, &[&source, &activity.as_of_block]));
+}
@@ -0,0 +1,132 @@
+use arbi_common::style;
+use ethers::types::Address;
+use ethers::utils::to_checksum;
+use std::error::Error;
+use std::str::FromStr;
+
+use crate::messages::{Msg, t, tf};
+
+/// 输入地址的大小写校验结果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChecksumState {
+    /// 大小写混合，且符合 EIP-55 校验
+    Valid,
+    /// 大小写混合，但不符合 EIP-55 校验（很可能抄错了某一位）
+    Invalid,
+    /// 全小写或全大写，不带校验信息
+    Unchecked,
+}
+
+/// 地址规范化的结果
+pub struct NormalizedAddress {
+    /// EIP-55 校验格式
+    pub checksummed: String,
+    /// 全小写格式
+    pub lowercase: String,
+    pub checksum: ChecksumState,
+}
+
+/// 校验并规范化一个地址，不需要连接节点
+///
+/// EIP-55 把地址的 Keccak 哈希编码进十六进制字母的大小写里；只有大小写混合的输入才带校验信息，
+/// 全小写或全大写的输入只能检查长度和字符。
+///
+/// # 参数
+/// * `input` - 地址字符串（可带或不带 0x 前缀）
+///
+/// # 返回
+/// * `Result<NormalizedAddress, Box<dyn Error>>` - 规范化结果；长度或字符不合法时返回错误
+pub fn normalize(input: &str) -> Result<NormalizedAddress, Box<dyn Error>> {
+    let input = input.trim();
+    let hex = input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix("0X"))
+        .unwrap_or(input);
+    if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(tf(Msg::InvalidAddress, &[&input]).into());
+    }
+
+    let address = Address::from_str(hex)?;
+    let checksummed = to_checksum(&address, None);
+    let has_lower = hex.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = hex.chars().any(|c| c.is_ascii_uppercase());
+    let checksum = if !(has_lower && has_upper) {
+        ChecksumState::Unchecked
+    } else if checksummed[2..] == *hex {
+        ChecksumState::Valid
+    } else {
+        ChecksumState::Invalid
+    };
+
+    Ok(NormalizedAddress {
+        checksummed,
+        lowercase: format!("{:?}", address),
+        checksum,
+    })
+}
+
+/// 打印规范化结果
+///
+/// # 参数
+/// * `normalized` - `normalize` 的返回值
+pub fn print_normalized(normalized: &NormalizedAddress) {
+    println!("{}", tf(Msg::NormalizeChecksummed, &[&style::addr(&normalized.checksummed)]));
+    println!("{}", tf(Msg::NormalizeLowercase, &[&normalized.lowercase]));
+    match normalized.checksum {
+        ChecksumState::Valid => println!("{}", style::ok(t(Msg::ChecksumValid))),
+        ChecksumState::Invalid => println!("{}", style::err(t(Msg::ChecksumInvalid))),
+        ChecksumState::Unchecked => println!("{}", style::warn(t(Msg::ChecksumUnchecked))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // EIP-55 规范里的示例地址
+    const CHECKSUMMED: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    #[test]
+    fn accepts_valid_checksum() {
+        let normalized = normalize(CHECKSUMMED).unwrap();
+        assert_eq!(normalized.checksum, ChecksumState::Valid);
+        assert_eq!(normalized.checksummed, CHECKSUMMED);
+        assert_eq!(normalized.lowercase, CHECKSUMMED.to_lowercase());
+
+        // 不带前缀、0X 前缀和首尾空白都接受
+        let upper_prefix = format!("0X{}", &CHECKSUMMED[2..]);
+        let padded = format!(" {CHECKSUMMED}\n");
+        for input in [&CHECKSUMMED[2..], &upper_prefix, &padded] {
+            assert_eq!(normalize(input).unwrap().checksum, ChecksumState::Valid, "{input}");
+        }
+    }
+
+    #[test]
+    fn rejects_bad_checksum() {
+        // 只翻转一个字母的大小写
+        let flipped = CHECKSUMMED.replacen("aAeb", "aaeb", 1);
+        let normalized = normalize(&flipped).unwrap();
+        assert_eq!(normalized.checksum, ChecksumState::Invalid);
+        assert_eq!(normalized.checksummed, CHECKSUMMED);
+    }
+
+    #[test]
+    fn single_case_input_is_unchecked() {
+        let upper = format!("0x{}", CHECKSUMMED[2..].to_uppercase());
+        for input in [CHECKSUMMED.to_lowercase(), upper] {
+            let normalized = normalize(&input).unwrap();
+            assert_eq!(normalized.checksum, ChecksumState::Unchecked, "{input}");
+            assert_eq!(normalized.checksummed, CHECKSUMMED);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_length_and_characters() {
+        let too_short = &CHECKSUMMED[..41];
+        let too_long = format!("{CHECKSUMMED}00");
+        let non_hex = CHECKSUMMED.replacen('5', "g", 1);
+        for input in [too_short, &too_long, &non_hex, "", "0x"] {
+            assert!(normalize(input).is_err(), "{input}");
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use arbi_common::table::Table;
+use ethers::providers::Middleware;
+use ethers::types::{Address, BlockId, BlockNumber, Transaction, U64};
+use ethers::utils::format_units;
+use std::error::Error;
+use std::str::FromStr;
+
+use crate::messages::{Msg, t, tf};
+
+/// 区块中的交易（可能已经按地址过滤）
+pub struct BlockTxs {
+    pub number: Option<U64>,
+    /// 区块中的交易总数（过滤前）
+    pub total: usize,
+    pub txs: Vec<Transaction>,
+}
+
+impl BlockTxs {
+    /// 全部（过滤后的）交易的表格，不受打印数量限制；金额同时给出 wei 和 ETH
+    pub fn to_table(&self) -> Result<Table, Box<dyn Error>> {
+        let mut table = Table::new(["block", "hash", "from", "to", "value_wei", "value_eth"]);
+        for tx in &self.txs {
+            table.push([
+                tx.block_number.map(|n| n.to_string()).unwrap_or_default(),
+                format!("{:?}", tx.hash),
+                format!("{:?}", tx.from),
+                tx.to.map(|to| format!("{:?}", to)).unwrap_or_default(),
+                tx.value.to_string(),
+                format_units(tx.value, "ether")?,
+            ]);
+        }
+        Ok(table)
+    }
+}
+
+/// 解析区块标识：`latest` 等标签、十进制区块号或 `0x` 开头的十六进制区块号
+///
+/// # 参数
+/// * `block` - 区块标识字符串
+///
+/// # 返回
+/// * `Result<BlockId, Box<dyn Error>>` - 解析后的区块标识
+pub fn parse_block_id(block: &str) -> Result<BlockId, Box<dyn Error>> {
+    let block = block.trim();
+    let invalid = || tf(Msg::InvalidBlockId, &[&block]);
+
+    // ethers 的 BlockNumber::from_str 会把纯数字当成十六进制，所以数字自己解析
+    let number = if let Some(hex) = block.strip_prefix("0x") {
+        BlockNumber::Number(u64::from_str_radix(hex, 16).map_err(|_| invalid())?.into())
+    } else if block.chars().all(|c| c.is_ascii_digit()) && !block.is_empty() {
+        BlockNumber::Number(block.parse::<u64>().map_err(|_| invalid())?.into())
+    } else {
+        BlockNumber::from_str(&block.to_ascii_lowercase()).map_err(|_| invalid())?
+    };
+
+    Ok(number.into())
+}
+
+/// 读取区块中的全部交易，可选只保留发送方或接收方为指定地址的交易
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `block_id` - 区块标识
+/// * `filter` - 过滤地址（`None` 表示不过滤）
+///
+/// # 返回
+/// * `Result<BlockTxs, Box<dyn Error>>` - 区块号、交易总数和（过滤后的）交易
+pub async fn block_txs(
+    provider: &RpcProvider,
+    block_id: BlockId,
+    filter: Option<Address>,
+) -> Result<BlockTxs, Box<dyn Error>> {
+    let block = provider
+        .get_block_with_txs(block_id)
+        .await?
+        .ok_or_else(|| tf(Msg::BlockNotFound, &[&format!("{:?}", block_id)]))?;
+
+    let total = block.transactions.len();
+    let txs = match filter {
+        Some(addr) => block
+            .transactions
+            .into_iter()
+            .filter(|tx| tx.from == addr || tx.to == Some(addr))
+            .collect(),
+        None => block.transactions,
+    };
+
+    Ok(BlockTxs {
+        number: block.number,
+        total,
+        txs,
+    })
+}
+
+/// 打印区块交易，超过 `limit` 笔时只打印前面的部分并提示剩余数量
+///
+/// # 参数
+/// * `block` - `block_txs` 的返回值
+/// * `filter` - 查询时使用的过滤地址
+/// * `limit` - 最多打印的交易数
+pub fn print_block_txs(block: &BlockTxs, filter: Option<Address>, limit: usize) -> Result<(), Box<dyn Error>> {
+    let number = block.number.map(|n| n.to_string()).unwrap_or_else(|| "pending".to_string());
+    let hex = block.number.map(|n| format!("{:#x}", n)).unwrap_or_else(|| "-".to_string());
+    println!("{}", tf(Msg::BlockHeader, &[&number, &hex, &block.total]));
+    if let Some(addr) = filter {
+        let addr = style::addr(format!("{:?}", addr));
+        println!("{}", tf(Msg::BlockFilter, &[&addr, &block.txs.len()]));
+    }
+    println!();
+
+    if block.txs.is_empty() {
+        let msg = if block.total == 0 { Msg::BlockEmpty } else { Msg::BlockNoMatch };
+        println!("{}", t(msg));
+        return Ok(());
+    }
+
+    println!(
+        "{:<66} {:<42} {:<42} {}",
+        t(Msg::BlockTxHash),
+        t(Msg::BlockTxFrom),
+        t(Msg::BlockTxTo),
+        t(Msg::BlockTxValue)
+    );
+    for tx in block.txs.iter().take(limit) {
+        let to = match tx.to {
+            Some(to) => format!("{:?}", to),
+            None => t(Msg::ContractCreation).to_string(),
+        };
+        println!(
+            "{:<66} {:<42} {:<42} {}",
+            format!("{:?}", tx.hash),
+            format!("{:?}", tx.from),
+            to,
+            format_units(tx.value, "ether")?
+        );
+    }
+
+    if block.txs.len() > limit {
+        println!("{}", tf(Msg::MoreTxs, &[&(block.txs.len() - limit)]));
+    }
+
+    Ok(())
+}
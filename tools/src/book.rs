@@ -0,0 +1,241 @@
+//! 地址簿：给常用地址起名字，保存在本地 JSON 文件里
+//!
+//! 文件是一个 JSON 对象，键是名字，值是 EIP-55 格式的地址。`arbi book list --names-only`
+//! 每行输出一个名字，生成的 shell 补全脚本调用它来补全 `book show` / `book remove` 的名字。
+
+use arbi_common::style;
+use arbi_common::table;
+use clap_complete::Shell;
+use ethers::types::Address;
+use ethers::utils::to_checksum;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::messages::{Msg, t, tf};
+
+/// 地址簿，名字按字典序排列
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AddressBook {
+    entries: BTreeMap<String, Address>,
+}
+
+impl AddressBook {
+    /// 读取地址簿文件，文件不存在时为空
+    ///
+    /// # 参数
+    /// * `path` - 地址簿文件路径
+    ///
+    /// # 返回
+    /// * `Result<AddressBook, Box<dyn Error>>` - 地址簿；文件格式不对时返回错误，不会当作空的覆盖掉
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        let invalid = || tf(Msg::BookInvalidFile, &[&path.display()]);
+        let value: Value = serde_json::from_str(&text).map_err(|_| invalid())?;
+        let object = value.as_object().ok_or_else(invalid)?;
+        let mut entries = BTreeMap::new();
+        for (name, address) in object {
+            let address = address.as_str().and_then(|s| Address::from_str(s).ok());
+            entries.insert(name.clone(), address.ok_or_else(invalid)?);
+        }
+        Ok(Self { entries })
+    }
+
+    /// 原子地写回地址簿文件，目录不存在时自动创建
+    ///
+    /// # 参数
+    /// * `path` - 地址簿文件路径
+    ///
+    /// # 返回
+    /// * `Result<(), Box<dyn Error>>` - 写入结果
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let object: Map<String, Value> = self
+            .entries
+            .iter()
+            .map(|(name, address)| (name.clone(), Value::String(to_checksum(address, None))))
+            .collect();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(&Value::Object(object))?;
+        table::write_atomic(path, text.as_bytes())?;
+        Ok(())
+    }
+
+    /// 添加或更新一个名字
+    ///
+    /// 名字不能为空，也不能含空白字符，否则 shell 补全会把它拆开。
+    ///
+    /// # 参数
+    /// * `name` - 名字
+    /// * `address` - 地址
+    ///
+    /// # 返回
+    /// * `Result<Option<Address>, Box<dyn Error>>` - 这个名字原来对应的地址
+    pub fn insert(
+        &mut self,
+        name: &str,
+        address: Address,
+    ) -> Result<Option<Address>, Box<dyn Error>> {
+        if name.is_empty() || name.chars().any(char::is_whitespace) {
+            return Err(tf(Msg::BookInvalidName, &[&name]).into());
+        }
+        Ok(self.entries.insert(name.to_string(), address))
+    }
+
+    /// 删除一个名字，返回它原来对应的地址
+    pub fn remove(&mut self, name: &str) -> Option<Address> {
+        self.entries.remove(name)
+    }
+
+    /// 查找一个名字对应的地址
+    pub fn get(&self, name: &str) -> Option<Address> {
+        self.entries.get(name).copied()
+    }
+
+    /// 每行一个名字，按字典序排列，末尾带换行；地址簿为空时为空字符串
+    pub fn names_only(&self) -> String {
+        self.entries.keys().map(|name| format!("{}\n", name)).collect()
+    }
+
+    /// 打印全部名字和地址
+    pub fn print(&self) {
+        if self.entries.is_empty() {
+            println!("{}", t(Msg::BookEmpty));
+            return;
+        }
+        let width = self.entries.keys().map(|name| name.chars().count()).max().unwrap_or(0);
+        for (name, address) in &self.entries {
+            println!("{:<width$}  {}", name, style::addr(to_checksum(address, None)));
+        }
+    }
+}
+
+/// 补全 `book show` / `book remove` 名字的 shell 片段，附加在 clap 生成的补全脚本之后
+///
+/// bash、zsh 包一层原来的补全函数，fish 追加一条 `complete` 规则，都调用
+/// `arbi book list --names-only`；PowerShell 和 elvish 只有静态补全。
+///
+/// # 参数
+/// * `shell` - 目标 shell
+///
+/// # 返回
+/// * `Option<&'static str>` - 脚本片段；不支持的 shell 为 `None`
+pub fn name_completion(shell: Shell) -> Option<&'static str> {
+    match shell {
+        Shell::Bash => Some(BASH_NAMES),
+        Shell::Zsh => Some(ZSH_NAMES),
+        Shell::Fish => Some(FISH_NAMES),
+        _ => None,
+    }
+}
+
+const BASH_NAMES: &str = r#"
+_arbi_book_names() {
+    local i
+    for ((i = 1; i < COMP_CWORD - 1; i++)); do
+        if [[ ${COMP_WORDS[i]} == book ]]; then
+            if [[ ${COMP_WORDS[i + 1]} == @(show|remove) && $((i + 2)) -eq $COMP_CWORD ]]; then
+                local names
+                names=$(arbi book list --names-only 2>/dev/null)
+                COMPREPLY=($(compgen -W "$names" -- "${COMP_WORDS[COMP_CWORD]}"))
+                return
+            fi
+            break
+        fi
+    done
+    _arbi "$@"
+}
+complete -F _arbi_book_names -o bashdefault -o default arbi
+"#;
+
+const ZSH_NAMES: &str = r#"
+_arbi_book_names() {
+    local i=${words[(i)book]}
+    if (( CURRENT == i + 2 )) && [[ ${words[i + 1]} == (show|remove) ]]; then
+        local -a names
+        names=(${(f)"$(arbi book list --names-only 2>/dev/null)"})
+        compadd -a names
+        return
+    fi
+    _arbi "$@"
+}
+compdef _arbi_book_names arbi
+"#;
+
+const FISH_NAMES: &str = r#"
+complete -c arbi -f \
+    -n "__fish_seen_subcommand_from book; and __fish_seen_subcommand_from show remove" \
+    -a "(arbi book list --names-only 2>/dev/null)"
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("arbi-book-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn names_only_is_one_plain_name_per_line() {
+        let mut book = AddressBook::default();
+        assert_eq!(book.names_only(), "");
+        book.insert("treasury", Address::from_low_u64_be(2)).unwrap();
+        book.insert("alice", Address::from_low_u64_be(1)).unwrap();
+        book.insert("bob.eth", Address::from_low_u64_be(3)).unwrap();
+        assert_eq!(book.names_only(), "alice\nbob.eth\ntreasury\n");
+    }
+
+    #[test]
+    fn rejects_names_the_shell_would_split() {
+        let mut book = AddressBook::default();
+        for name in ["", "my wallet", "tab\tname"] {
+            let error = book.insert(name, Address::zero()).unwrap_err();
+            assert_eq!(error.to_string(), tf(Msg::BookInvalidName, &[&name]));
+        }
+    }
+
+    #[test]
+    fn round_trips_through_the_file() {
+        let dir = scratch("round-trip");
+        let path = dir.join("book.json");
+        assert_eq!(AddressBook::load(&path).unwrap(), AddressBook::default());
+
+        let mut book = AddressBook::default();
+        let alice = Address::from_str("0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap();
+        assert_eq!(book.insert("alice", Address::zero()).unwrap(), None);
+        assert_eq!(book.insert("alice", alice).unwrap(), Some(Address::zero()));
+        book.save(&path).unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.contains(&to_checksum(&alice, None)), "{text}");
+        let mut loaded = AddressBook::load(&path).unwrap();
+        assert_eq!(loaded.get("alice"), Some(alice));
+        assert_eq!(loaded.remove("alice"), Some(alice));
+        assert_eq!(loaded.remove("alice"), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn malformed_file_is_an_error() {
+        let dir = scratch("malformed");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("book.json");
+        for text in ["{ not json", "[]", r#"{"alice": "0x1234"}"#, r#"{"alice": 1}"#] {
+            fs::write(&path, text).unwrap();
+            let error = AddressBook::load(&path).unwrap_err();
+            assert_eq!(error.to_string(), tf(Msg::BookInvalidFile, &[&path.display()]));
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,338 @@
+use ethers::abi::{Abi, Function, Token};
+use ethers::types::{Address, I256, U256};
+use ethers::utils::{hex, to_checksum};
+use std::error::Error;
+
+use crate::messages::{Msg, t, tf};
+
+// 地址左边补 12 个 0 字节成为一个 32 字节的字
+const ADDRESS_PADDING: usize = 12;
+
+/// calldata 按选择器拆开后的结果
+pub struct DecodedCalldata<'a> {
+    pub selector: [u8; 4],
+    /// 匹配到的函数和解码出的参数；没有匹配的函数时为 `None`
+    pub call: Option<(&'a Function, Vec<Token>)>,
+    /// 选择器之后的 32 字节字
+    pub words: Vec<[u8; 32]>,
+    /// 末尾不足 32 字节的部分
+    pub trailing: Vec<u8>,
+}
+
+/// 读取候选函数：`--abi` 的 JSON ABI 文件，或 `--sig` 的人类可读签名
+///
+/// 签名可以带参数名，如 `transfer(address to, uint256 amount)`，前面的 `function` 可省略。
+///
+/// # 参数
+/// * `abi` - JSON ABI 文件路径
+/// * `signature` - 函数签名
+///
+/// # 返回
+/// * `Result<Vec<Function>, Box<dyn Error>>` - 候选函数，两者都没给时为空
+pub fn load_functions(
+    abi: Option<&str>,
+    signature: Option<&str>,
+) -> Result<Vec<Function>, Box<dyn Error>> {
+    let mut functions = Vec::new();
+    if let Some(path) = abi {
+        let abi: Abi = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        functions.extend(abi.functions().cloned());
+    }
+    if let Some(signature) = signature {
+        let signature = signature.trim();
+        let line = match signature.starts_with("function ") {
+            true => signature.to_string(),
+            false => format!("function {}", signature),
+        };
+        let abi = ethers::abi::parse_abi(&[line.as_str()])
+            .map_err(|e| tf(Msg::CalldataInvalidSignature, &[&signature, &e]))?;
+        functions.extend(abi.functions().cloned());
+    }
+    Ok(functions)
+}
+
+/// 按选择器匹配函数并解码参数，不做任何 I/O
+///
+/// # 参数
+/// * `data` - calldata
+/// * `functions` - 候选函数
+///
+/// # 返回
+/// * `Result<DecodedCalldata, Box<dyn Error>>` - 拆分结果；不足 4 字节或参数与签名不符时返回错误
+pub fn decode<'a>(
+    data: &[u8],
+    functions: &'a [Function],
+) -> Result<DecodedCalldata<'a>, Box<dyn Error>> {
+    let Some((selector, args)) = data.split_first_chunk::<4>() else {
+        return Err(tf(Msg::CalldataTooShort, &[&data.len()]).into());
+    };
+    let call = match functions.iter().find(|f| f.short_signature() == *selector) {
+        Some(function) => {
+            let tokens = function
+                .decode_input(args)
+                .map_err(|e| tf(Msg::CalldataArgsFailed, &[&signature(function), &e]))?;
+            Some((function, tokens))
+        }
+        None => None,
+    };
+    let (chunks, trailing) = args.as_chunks::<32>();
+    Ok(DecodedCalldata {
+        selector: *selector,
+        call,
+        words: chunks.to_vec(),
+        trailing: trailing.to_vec(),
+    })
+}
+
+// 规范签名 `name(types)`；`Function::signature` 在有返回值时还会带上 `:(outputs)`
+fn signature(function: &Function) -> String {
+    let inputs: Vec<String> = function.inputs.iter().map(|param| param.kind.to_string()).collect();
+    format!("{}({})", function.name, inputs.join(","))
+}
+
+/// 按类型格式化解码出的参数：地址用 EIP-55 校验格式，整数用十进制，字节用十六进制
+///
+/// # 参数
+/// * `token` - 参数值
+///
+/// # 返回
+/// * `String` - 显示文本
+pub fn format_token(token: &Token) -> String {
+    match token {
+        Token::Address(address) => to_checksum(address, None),
+        Token::Uint(value) => value.to_string(),
+        Token::Int(value) => I256::from_raw(*value).to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(text) => format!("{:?}", text),
+        Token::Bytes(bytes) | Token::FixedBytes(bytes) => hex::encode_prefixed(bytes),
+        Token::Array(items) | Token::FixedArray(items) => {
+            let items: Vec<String> = items.iter().map(format_token).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Token::Tuple(items) => {
+            let items: Vec<String> = items.iter().map(format_token).collect();
+            format!("({})", items.join(", "))
+        }
+    }
+}
+
+/// 没有 ABI 时猜测一个 32 字节字的含义
+///
+/// 不超过 2^64 的按小整数显示（可能是金额、数量，也可能是动态参数的偏移或长度），
+/// 绝对值不超过 2^63 的负数按补码显示成负整数；高 12 字节为 0 的其余值看起来像地址；
+/// 其他情况不作猜测。
+///
+/// # 参数
+/// * `word` - 32 字节字
+///
+/// # 返回
+/// * `Option<String>` - 猜测的含义；无法判断时为 `None`
+pub fn guess_word(word: &[u8; 32]) -> Option<String> {
+    let value = U256::from_big_endian(word);
+    if value <= U256::from(u64::MAX) {
+        return Some(tf(Msg::CalldataGuessInt, &[&value]));
+    }
+    let signed = I256::from_raw(value);
+    if signed.is_negative() && signed >= I256::from(i64::MIN) {
+        return Some(tf(Msg::CalldataGuessInt, &[&signed]));
+    }
+    if word[..ADDRESS_PADDING].iter().all(|&byte| byte == 0) {
+        let address = Address::from_slice(&word[ADDRESS_PADDING..]);
+        return Some(tf(Msg::CalldataGuessAddress, &[&to_checksum(&address, None)]));
+    }
+    None
+}
+
+impl DecodedCalldata<'_> {
+    /// 打印函数和参数；没有匹配的函数时逐字打印并猜测类型
+    pub fn print(&self) {
+        let selector = hex::encode_prefixed(self.selector);
+        let Some((function, tokens)) = &self.call else {
+            println!("{}", tf(Msg::CalldataSelector, &[&selector, &t(Msg::CalldataNoMatch)]));
+            self.print_words();
+            return;
+        };
+        println!("{}", tf(Msg::CalldataSelector, &[&selector, &signature(function)]));
+        for (index, (param, token)) in function.inputs.iter().zip(tokens).enumerate() {
+            let name = match param.name.is_empty() {
+                true => format!("#{}", index),
+                false => param.name.clone(),
+            };
+            println!("  {} ({}): {}", name, param.kind, format_token(token));
+        }
+    }
+
+    fn print_words(&self) {
+        // 偏移从参数区开始算（不含选择器），和动态参数的偏移字一致
+        for (index, word) in self.words.iter().enumerate() {
+            let offset = index * 32;
+            let guess = guess_word(word).map(|guess| format!("  {}", guess)).unwrap_or_default();
+            println!("  [{:#06x}] {}{}", offset, hex::encode(word), guess);
+        }
+        if !self.trailing.is_empty() {
+            let trailing = hex::encode_prefixed(&self.trailing);
+            println!("{}", tf(Msg::CalldataTrailing, &[&self.trailing.len(), &trailing]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function(signature: &str) -> Vec<Function> {
+        load_functions(None, Some(signature)).unwrap()
+    }
+
+    // 选择器加上手写的 32 字节字（十六进制，左补 0；字符串和字节内容右补 0）
+    fn calldata(function: &Function, words: &[&str]) -> Vec<u8> {
+        let mut data = function.short_signature().to_vec();
+        for word in words {
+            let word = match word.strip_prefix('>') {
+                Some(right) => format!("{:0<64}", right),
+                None => format!("{:0>64}", word),
+            };
+            data.extend(hex::decode(word).unwrap());
+        }
+        data
+    }
+
+    fn decoded(functions: &[Function], data: &[u8]) -> Vec<String> {
+        let decoded = decode(data, functions).unwrap();
+        let (_, tokens) = decoded.call.expect("no matching function");
+        tokens.iter().map(format_token).collect()
+    }
+
+    #[test]
+    fn decodes_bytes_and_string() {
+        let functions = function("post(bytes payload, string memo)");
+        let data = calldata(
+            &functions[0],
+            &[
+                "40",        // payload 的偏移
+                "80",        // memo 的偏移
+                "4",         // payload 长度
+                ">deadbeef", // payload
+                "6",         // memo 长度
+                ">e4bda0e5a5bd",
+            ],
+        );
+        assert_eq!(decoded(&functions, &data), ["0xdeadbeef", "\"你好\""]);
+    }
+
+    #[test]
+    fn decodes_dynamic_and_fixed_arrays() {
+        let functions = function("split(uint256[] amounts, address[2] pair, int8 bias)");
+        let minus_one = "f".repeat(64); // bias = -1
+        let data = calldata(
+            &functions[0],
+            &[
+                "80", // amounts 的偏移，头部 4 个字
+                "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+                "1",
+                &minus_one,
+                "3",
+                "1",
+                "de0b6b3a7640000",
+                "0",
+            ],
+        );
+        assert_eq!(
+            decoded(&functions, &data),
+            [
+                "[1, 1000000000000000000, 0]",
+                "[0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed, \
+                 0x0000000000000000000000000000000000000001]",
+                "-1",
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_nested_tuples_with_dynamic_members() {
+        // 人类可读签名不支持嵌套元组，这里走 `--abi` 的 JSON ABI 文件
+        let abi = r#"[{
+            "type": "function",
+            "name": "fill",
+            "stateMutability": "nonpayable",
+            "inputs": [{
+                "name": "order",
+                "type": "tuple",
+                "components": [
+                    {"name": "maker", "type": "address"},
+                    {
+                        "name": "items",
+                        "type": "tuple[]",
+                        "components": [
+                            {"name": "id", "type": "uint256"},
+                            {"name": "tag", "type": "string"}
+                        ]
+                    }
+                ]
+            }],
+            "outputs": []
+        }]"#;
+        let path = std::env::temp_dir().join(format!("arbi-calldata-{}.json", std::process::id()));
+        std::fs::write(&path, abi).unwrap();
+        let functions = load_functions(path.to_str(), None).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(signature(&functions[0]), "fill((address,(uint256,string)[]))");
+        let data = calldata(
+            &functions[0],
+            &[
+                "20", // order 的偏移
+                "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+                "40", // items 相对 order 开头的偏移
+                "1",  // items 长度
+                "20", // items[0] 相对元素区开头的偏移
+                "7",
+                "40", // tag 相对 items[0] 开头的偏移
+                "3",
+                ">616263",
+            ],
+        );
+        assert_eq!(
+            decoded(&functions, &data),
+            ["(0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed, [(7, \"abc\")])"]
+        );
+    }
+
+    #[test]
+    fn arguments_that_do_not_fit_the_signature_are_an_error() {
+        let functions = function("post(bytes payload, string memo)");
+        // memo 的偏移指向数据之外
+        let data = calldata(&functions[0], &["40", "400", "0"]);
+        let error = decode(&data, &functions).err().unwrap().to_string();
+        assert!(error.starts_with(&tf(Msg::CalldataArgsFailed, &[&"post(bytes,string)", &""])));
+
+        let error = decode(&[0xa9, 0x05], &functions).err().unwrap();
+        assert_eq!(error.to_string(), tf(Msg::CalldataTooShort, &[&2]));
+    }
+
+    #[test]
+    fn unknown_selector_keeps_words_and_trailing_bytes() {
+        let data = hex::decode(format!("12345678{:064x}{:064x}abcd", 0x40, 3)).unwrap();
+        let functions = function("post(bytes payload)");
+        let decoded = decode(&data, &functions).unwrap();
+        assert_eq!(decoded.selector, [0x12, 0x34, 0x56, 0x78]);
+        assert!(decoded.call.is_none());
+        assert_eq!(decoded.words.len(), 2);
+        assert_eq!(decoded.trailing, [0xab, 0xcd]);
+    }
+
+    #[test]
+    fn guesses_small_ints_negatives_and_addresses() {
+        let word = |hex: &str| -> [u8; 32] {
+            hex::decode(format!("{:0>64}", hex)).unwrap().try_into().unwrap()
+        };
+        assert_eq!(guess_word(&word("40")), Some(tf(Msg::CalldataGuessInt, &[&64])));
+        assert_eq!(guess_word(&word(&"f".repeat(64))), Some(tf(Msg::CalldataGuessInt, &[&-1])));
+        let address = "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+        let checksummed = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert_eq!(
+            guess_word(&word(address)),
+            Some(tf(Msg::CalldataGuessAddress, &[&checksummed]))
+        );
+        assert_eq!(guess_word(&word(&"ab".repeat(32))), None);
+    }
+}
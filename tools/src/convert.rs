@@ -0,0 +1,192 @@
+use arbi_common::units;
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+use std::error::Error;
+
+use crate::messages::{Msg, t, tf};
+
+/// 换算支持的单位
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Unit {
+    Wei,
+    Gwei,
+    #[value(alias = "ether")]
+    Eth,
+    /// 代币的最小单位（合约里存的整数）
+    Raw,
+    /// 代币按 `--decimals` 换算后的数量
+    Token,
+}
+
+impl Unit {
+    fn name(self) -> &'static str {
+        match self {
+            Unit::Wei => "wei",
+            Unit::Gwei => "gwei",
+            Unit::Eth => "eth",
+            Unit::Raw => "raw",
+            Unit::Token => "token",
+        }
+    }
+
+    /// 单位的小数位数；`token` 需要 `--decimals`
+    ///
+    /// # 参数
+    /// * `token_decimals` - 代币小数位数
+    ///
+    /// # 返回
+    /// * `Result<u32, Box<dyn Error>>` - 小数位数
+    pub fn decimals(self, token_decimals: Option<u32>) -> Result<u32, Box<dyn Error>> {
+        match self {
+            Unit::Wei | Unit::Raw => Ok(0),
+            Unit::Gwei => Ok(9),
+            Unit::Eth => Ok(18),
+            Unit::Token => token_decimals.ok_or_else(|| t(Msg::ConvertNeedsDecimals).into()),
+        }
+    }
+}
+
+/// 确定来源和目标单位：都没给时只要有 `--decimals` 就是代币 raw → token
+///
+/// # 参数
+/// * `from` - 来源单位
+/// * `to` - 目标单位
+/// * `decimals` - 代币小数位数
+///
+/// # 返回
+/// * `Result<(Unit, Unit), Box<dyn Error>>` - 来源和目标单位
+pub fn resolve_units(
+    from: Option<Unit>,
+    to: Option<Unit>,
+    decimals: Option<u32>,
+) -> Result<(Unit, Unit), Box<dyn Error>> {
+    match (from, to, decimals) {
+        (Some(from), Some(to), _) => Ok((from, to)),
+        (None, None, Some(_)) => Ok((Unit::Raw, Unit::Token)),
+        _ => Err(t(Msg::ConvertMissingUnits).into()),
+    }
+}
+
+/// 换算并打印精确结果
+///
+/// # 参数
+/// * `amount` - 金额
+/// * `from` - 来源单位
+/// * `to` - 目标单位
+/// * `decimals` - 代币小数位数
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+pub fn print_conversion(
+    amount: &str,
+    from: Unit,
+    to: Unit,
+    decimals: Option<u32>,
+) -> Result<(), Box<dyn Error>> {
+    let result = units::convert(amount, from.decimals(decimals)?, to.decimals(decimals)?)?;
+    println!("{}", tf(Msg::ConvertResult, &[&amount.trim(), &from.name(), &result, &to.name()]));
+    Ok(())
+}
+
+/// 以 JSON 打印金额在所有单位下的表示；没有 `--decimals` 时不含 raw / token
+///
+/// # 参数
+/// * `amount` - 金额
+/// * `from` - 来源单位
+/// * `decimals` - 代币小数位数
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+pub fn print_json(amount: &str, from: Unit, decimals: Option<u32>) -> Result<(), Box<dyn Error>> {
+    let base = units::parse_amount(amount, from.decimals(decimals)?)?;
+    let mut object = Map::new();
+    object.insert("input".into(), Value::String(amount.trim().to_string()));
+    object.insert("unit".into(), Value::String(from.name().to_string()));
+    if let Some(decimals) = decimals {
+        object.insert("decimals".into(), Value::from(decimals));
+    }
+    for unit in Unit::value_variants() {
+        if decimals.is_none() && matches!(unit, Unit::Raw | Unit::Token) {
+            continue;
+        }
+        let value = units::format_exact(base, unit.decimals(decimals)?)?;
+        object.insert(unit.name().into(), Value::String(value));
+    }
+    println!("{}", Value::Object(object));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(amount: &str, from: Unit, to: Unit, decimals: Option<u32>) -> String {
+        units::convert(amount, from.decimals(decimals).unwrap(), to.decimals(decimals).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn conversion_table() {
+        for (amount, from, to, decimals, expected) in [
+            ("0.005", Unit::Eth, Unit::Wei, None, "5000000000000000"),
+            ("2500000", Unit::Gwei, Unit::Eth, None, "0.0025"),
+            ("1", Unit::Eth, Unit::Gwei, None, "1000000000"),
+            ("1", Unit::Wei, Unit::Gwei, None, "0.000000001"),
+            ("1500000", Unit::Raw, Unit::Token, Some(6), "1.5"),
+            ("1.5", Unit::Token, Unit::Raw, Some(6), "1500000"),
+            ("42", Unit::Raw, Unit::Token, Some(0), "42"),
+            ("1", Unit::Token, Unit::Eth, Some(18), "1"),
+        ] {
+            assert_eq!(convert(amount, from, to, decimals), expected, "{} {:?}", amount, from);
+        }
+    }
+
+    #[test]
+    fn round_trips_through_every_unit() {
+        let all = [Unit::Wei, Unit::Gwei, Unit::Eth, Unit::Raw, Unit::Token];
+        for wei in ["1", "1000000000", "5000000000000000", "123456789012345678901"] {
+            for unit in all {
+                let there = convert(wei, Unit::Wei, unit, Some(6));
+                let back = convert(&there, unit, Unit::Wei, Some(6));
+                assert_eq!(back, wei, "{} via {:?}", wei, unit);
+            }
+        }
+    }
+
+    #[test]
+    fn token_units_need_decimals() {
+        for unit in [Unit::Wei, Unit::Raw] {
+            assert_eq!(unit.decimals(None).unwrap(), 0);
+        }
+        let error = Unit::Token.decimals(None).unwrap_err();
+        assert_eq!(error.to_string(), t(Msg::ConvertNeedsDecimals));
+    }
+
+    #[test]
+    fn resolve_units_defaults_to_raw_token() {
+        let units = resolve_units(None, None, Some(6)).unwrap();
+        assert_eq!(units, (Unit::Raw, Unit::Token));
+        let units = resolve_units(Some(Unit::Eth), Some(Unit::Wei), None).unwrap();
+        assert_eq!(units, (Unit::Eth, Unit::Wei));
+        for (from, to) in [(None, None), (Some(Unit::Eth), None), (None, Some(Unit::Wei))] {
+            let error = resolve_units(from, to, None).unwrap_err();
+            assert_eq!(error.to_string(), t(Msg::ConvertMissingUnits));
+        }
+    }
+
+    #[test]
+    fn rejects_excess_precision_overflow_and_hex() {
+        let wei = Unit::Wei.decimals(None).unwrap();
+        let raw6 = Unit::Token.decimals(Some(6)).unwrap();
+        for (amount, decimals) in [
+            ("1.5", wei),
+            ("0.0000001", raw6),
+            ("0x10", wei),
+            ("1e18", wei),
+            ("-1", wei),
+            ("115792089237316195423570985008687907853269984665640564039457584007913129639936", wei),
+        ] {
+            assert!(units::convert(amount, decimals, 18).is_err(), "{}", amount);
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use arbi_common::metrics;
+use arbi_common::rpc::RpcProvider;
+use ethers::providers::Middleware;
+use ethers::types::{Address, U256};
+use ethers::utils::format_units;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Style, Stylize};
+use ratatui::widgets::{Block, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::io::IsTerminal;
+use std::time::{Duration, Instant};
+
+use crate::messages::{Msg, t, tf};
+
+// 区块流最多保留的区块数
+const MAX_FEED: usize = 20;
+
+// 等待按键的间隔，决定界面响应按键的速度
+const KEY_POLL: Duration = Duration::from_millis(200);
+
+/// 区块流中的一个区块
+pub struct BlockSummary {
+    pub number: u64,
+    pub timestamp: u64,
+    pub tx_count: usize,
+    pub gas_used: U256,
+}
+
+/// 面板上显示的数据，每次刷新时更新
+#[derive(Default)]
+pub struct DashboardState {
+    pub address: Option<Address>,
+    pub balance: Option<U256>,
+    pub gas_price: Option<U256>,
+    /// 最新的区块在前
+    pub blocks: VecDeque<BlockSummary>,
+    /// 最近一次刷新失败的原因，刷新成功后清空
+    pub last_error: Option<String>,
+}
+
+impl DashboardState {
+    pub fn new(address: Option<Address>) -> Self {
+        Self {
+            address,
+            ..Self::default()
+        }
+    }
+
+    fn latest_block(&self) -> Option<u64> {
+        self.blocks.front().map(|block| block.number)
+    }
+
+    /// 刷新余额、Gas 价格和新区块，失败时保留旧数据并记录原因
+    ///
+    /// # 参数
+    /// * `provider` - Provider 引用
+    ///
+    /// # 返回
+    /// * `Vec<u64>` - 这次新加入区块流的区块号
+    pub async fn refresh(&mut self, provider: &RpcProvider) -> Vec<u64> {
+        match self.try_refresh(provider).await {
+            Ok(new_blocks) => {
+                self.last_error = None;
+                new_blocks
+            }
+            Err(e) => {
+                self.last_error = Some(e.to_string());
+                Vec::new()
+            }
+        }
+    }
+
+    async fn try_refresh(&mut self, provider: &RpcProvider) -> Result<Vec<u64>, Box<dyn Error>> {
+        let gas_price = provider.get_gas_price().await?;
+        metrics::set_gas_price(gas_price);
+        self.gas_price = Some(gas_price);
+        if let Some(address) = self.address {
+            let balance = provider.get_balance(address, None).await?;
+            metrics::set_balance(address, balance);
+            self.balance = Some(balance);
+        }
+
+        // 只拉取上次之后的区块，第一次或落后太多时只取最近的 MAX_FEED 个
+        let latest = provider.get_block_number().await?.as_u64();
+        let first = match self.latest_block() {
+            Some(seen) => (seen + 1).max(latest.saturating_sub(MAX_FEED as u64 - 1)),
+            None => latest.saturating_sub(MAX_FEED as u64 - 1),
+        };
+        let mut new_blocks = Vec::new();
+        for number in first..=latest {
+            let Some(block) = provider.get_block(number).await? else {
+                continue;
+            };
+            self.blocks.push_front(BlockSummary {
+                number,
+                timestamp: block.timestamp.as_u64(),
+                tx_count: block.transactions.len(),
+                gas_used: block.gas_used,
+            });
+            new_blocks.push(number);
+        }
+        self.blocks.truncate(MAX_FEED);
+        Ok(new_blocks)
+    }
+
+    fn balance_text(&self) -> String {
+        match (self.address, self.balance) {
+            (None, _) => t(Msg::DashboardNoWallet).to_string(),
+            (Some(_), Some(balance)) => {
+                let eth = format_units(balance, "ether").unwrap_or_default();
+                tf(Msg::DashboardEth, &[&eth])
+            }
+            (Some(_), None) => "-".to_string(),
+        }
+    }
+
+    fn gas_price_text(&self) -> String {
+        self.gas_price
+            .and_then(|price| format_units(price, "gwei").ok())
+            .map(|gwei| tf(Msg::DashboardGwei, &[&gwei]))
+            .unwrap_or_else(|| "-".to_string())
+    }
+}
+
+/// 画一帧：上面是钱包和 Gas 价格，下面是区块流，最底部是状态栏
+fn draw(frame: &mut Frame, state: &DashboardState, interval: Duration) {
+    let [summary_area, blocks_area, status_area] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let wallet = state
+        .address
+        .map(|address| format!("{:?}", address))
+        .unwrap_or_else(|| "-".to_string());
+    let summary = Paragraph::new(vec![
+        format!("{} {}", t(Msg::DashboardWallet), wallet).into(),
+        format!("{} {}", t(Msg::DashboardBalance), state.balance_text()).into(),
+        format!("{} {}", t(Msg::DashboardGasPrice), state.gas_price_text()).into(),
+    ])
+    .block(Block::bordered().title(t(Msg::DashboardTitle)));
+    frame.render_widget(summary, summary_area);
+
+    let rows = state.blocks.iter().map(|block| {
+        Row::new(vec![
+            block.number.to_string(),
+            block.timestamp.to_string(),
+            block.tx_count.to_string(),
+            block.gas_used.to_string(),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(6),
+            Constraint::Min(10),
+        ],
+    )
+    .header(
+        Row::new(vec![
+            t(Msg::DashboardColBlock),
+            t(Msg::DashboardColTime),
+            t(Msg::DashboardColTxs),
+            t(Msg::DashboardColGasUsed),
+        ])
+        .style(Style::new().bold()),
+    )
+    .block(Block::bordered().title(t(Msg::DashboardBlocks)));
+    frame.render_widget(table, blocks_area);
+
+    let status = match &state.last_error {
+        Some(e) => Paragraph::new(tf(Msg::DashboardRefreshFailed, &[e])).red(),
+        None => Paragraph::new(tf(Msg::DashboardHelp, &[&interval.as_secs()])).dark_gray(),
+    };
+    frame.render_widget(status, status_area);
+}
+
+/// 处理一次按键，返回是否退出
+fn should_quit() -> Result<bool, Box<dyn Error>> {
+    if !event::poll(KEY_POLL)? {
+        return Ok(false);
+    }
+    // 原始模式下 Ctrl-C 不再产生信号，要当作普通按键处理
+    Ok(match event::read()? {
+        Event::Key(key) if key.kind == KeyEventKind::Press => {
+            let ctrl_c = key.code == KeyCode::Char('c')
+                && key.modifiers.contains(KeyModifiers::CONTROL);
+            ctrl_c || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+        }
+        _ => false,
+    })
+}
+
+async fn run_tui(
+    terminal: &mut DefaultTerminal,
+    provider: &RpcProvider,
+    state: &mut DashboardState,
+    interval: Duration,
+) -> Result<(), Box<dyn Error>> {
+    let mut next_refresh = Instant::now();
+    loop {
+        if Instant::now() >= next_refresh {
+            state.refresh(provider).await;
+            next_refresh = Instant::now() + interval;
+        }
+        terminal.draw(|frame| draw(frame, state, interval))?;
+        if should_quit()? {
+            return Ok(());
+        }
+    }
+}
+
+/// 输出不是终端时退化为定期打印一行摘要，直到 Ctrl-C
+async fn run_plain(
+    provider: &RpcProvider,
+    state: &mut DashboardState,
+    interval: Duration,
+) -> Result<(), Box<dyn Error>> {
+    loop {
+        let new_blocks = state.refresh(provider).await;
+        match &state.last_error {
+            Some(e) => eprintln!("{}", tf(Msg::DashboardRefreshFailed, &[e])),
+            None => println!(
+                "{}",
+                tf(
+                    Msg::DashboardLine,
+                    &[
+                        &state.latest_block().unwrap_or_default(),
+                        &new_blocks.len(),
+                        &state.gas_price_text(),
+                        &state.balance_text(),
+                    ],
+                )
+            ),
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// 显示钱包余额、Gas 价格和新区块的实时面板，按 `q` 退出
+///
+/// 输出是终端时用 ratatui 画全屏界面，否则（如重定向到文件）每隔一段时间打印一行摘要。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 要显示余额的钱包地址
+/// * `interval` - 刷新间隔
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+pub async fn run_dashboard(
+    provider: &RpcProvider,
+    address: Option<Address>,
+    interval: Duration,
+) -> Result<(), Box<dyn Error>> {
+    let mut state = DashboardState::new(address);
+    if !std::io::stdout().is_terminal() {
+        return run_plain(provider, &mut state, interval).await;
+    }
+
+    let mut terminal = ratatui::try_init()?;
+    let result = run_tui(&mut terminal, provider, &mut state, interval).await;
+    ratatui::restore();
+    result
+}
@@ -0,0 +1,354 @@
+use arbi_common::rpc::RpcProvider;
+use arbi_common::units::group_digits;
+use ethers::abi::{Abi, Token};
+use ethers::prelude::*;
+use ethers::providers::Middleware;
+use ethers::types::{Address, BlockNumber, U256};
+use ethers::utils::format_units;
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::messages::{Msg, t, tf};
+
+// ArbGasInfo 预编译合约地址
+const ARB_GAS_INFO_ADDRESS: &str = "0x000000000000000000000000000000000000006C";
+
+const ARB_GAS_INFO_ABI: &str = r#"[
+    {
+        "inputs": [],
+        "name": "getPricesInWei",
+        "outputs": [
+            {"name": "", "type": "uint256"},
+            {"name": "", "type": "uint256"},
+            {"name": "", "type": "uint256"},
+            {"name": "", "type": "uint256"},
+            {"name": "", "type": "uint256"},
+            {"name": "", "type": "uint256"}
+        ],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [],
+        "name": "getL1BaseFeeEstimate",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [],
+        "name": "getCurrentTxL1GasFees",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "view",
+        "type": "function"
+    }
+]"#;
+
+// 已签名交易除 calldata 外的大致字节数（nonce、Gas 字段、to、value、签名等 RLP 编码）
+const TX_OVERHEAD_BYTES: u64 = 110;
+
+/// `getPricesInWei` 返回的六个价格（单位均为 wei）
+#[derive(Clone, Copy, Debug)]
+pub struct PricesInWei {
+    /// 每笔交易固定的 L1 开销
+    pub per_l2_tx: U256,
+    /// 发布到 L1 的每字节 calldata
+    pub per_l1_calldata_byte: U256,
+    /// 每个新分配的存储槽
+    pub per_storage_allocation: U256,
+    /// 每单位 L2 Gas 的基础价格
+    pub per_arb_gas_base: U256,
+    /// 每单位 L2 Gas 的拥堵附加费
+    pub per_arb_gas_congestion: U256,
+    /// 每单位 L2 Gas 的总价格（基础 + 拥堵）
+    pub per_arb_gas_total: U256,
+}
+
+impl PricesInWei {
+    /// 从 `getPricesInWei` 返回的 ABI 元组解码
+    ///
+    /// # 参数
+    /// * `tokens` - 解码后的返回值，应为 6 个 uint256
+    ///
+    /// # 返回
+    /// * `Result<PricesInWei, Box<dyn Error>>` - 数量或类型不对时返回错误
+    pub fn from_tokens(tokens: Vec<Token>) -> Result<Self, Box<dyn Error>> {
+        let values = tokens
+            .into_iter()
+            .map(Token::into_uint)
+            .collect::<Option<Vec<_>>>()
+            .filter(|values| values.len() == 6)
+            .ok_or_else(|| t(Msg::FeesBadTuple))?;
+
+        Ok(Self {
+            per_l2_tx: values[0],
+            per_l1_calldata_byte: values[1],
+            per_storage_allocation: values[2],
+            per_arb_gas_base: values[3],
+            per_arb_gas_congestion: values[4],
+            per_arb_gas_total: values[5],
+        })
+    }
+}
+
+/// 读取到的 Arbitrum 费用参数
+pub struct ArbFees {
+    pub prices: PricesInWei,
+    pub l1_base_fee_estimate: U256,
+    /// 在 eth_call 里读到的是这次调用本身的 L1 费用，部分节点不支持时为 `None`
+    pub current_tx_l1_gas_fees: Option<U256>,
+    /// 最新区块的基础费，链不支持 EIP-1559 时为 `None`
+    pub block_base_fee: Option<U256>,
+}
+
+/// 示例交易
+pub struct Example {
+    pub name: Msg,
+    /// L2 执行消耗的 Gas
+    pub l2_gas: u64,
+    /// calldata 字节数
+    pub calldata_bytes: u64,
+}
+
+/// 报告中计算的三种示例交易
+pub const EXAMPLES: [Example; 3] = [
+    Example {
+        name: Msg::FeesExamplePlain,
+        l2_gas: 21_000,
+        calldata_bytes: 0,
+    },
+    // transfer(address,uint256)：4 字节选择器 + 两个 32 字节参数，执行约 35000 Gas
+    Example {
+        name: Msg::FeesExampleErc20,
+        l2_gas: 35_000,
+        calldata_bytes: 68,
+    },
+    // 21000 基础 Gas + 每个非零字节 16 Gas 的 calldata 内在 Gas
+    Example {
+        name: Msg::FeesExampleCalldata,
+        l2_gas: 21_000 + 16 * 4096,
+        calldata_bytes: 4096,
+    },
+];
+
+/// 示例交易的费用拆分（单位均为 wei）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CostBreakdown {
+    pub l2: U256,
+    pub l1: U256,
+    pub total: U256,
+}
+
+/// 计算示例交易的费用
+///
+/// L2 部分按执行 Gas × L2 Gas 价格计算；L1 部分按每笔交易固定开销加上整笔交易的字节数 ×
+/// 每字节价格计算。实际链上会先压缩再计费，所以这里得到的 L1 部分是上限。
+///
+/// # 参数
+/// * `prices` - `getPricesInWei` 的价格
+/// * `l2_gas_price` - 每单位 L2 Gas 的价格（wei）
+/// * `example` - 示例交易
+///
+/// # 返回
+/// * `CostBreakdown` - L2、L1 部分和总费用
+pub fn illustrative_cost(
+    prices: &PricesInWei,
+    l2_gas_price: U256,
+    example: &Example,
+) -> CostBreakdown {
+    let l2 = U256::from(example.l2_gas) * l2_gas_price;
+    let tx_bytes = U256::from(TX_OVERHEAD_BYTES + example.calldata_bytes);
+    let l1 = prices.per_l2_tx + tx_bytes * prices.per_l1_calldata_byte;
+
+    CostBreakdown {
+        l2,
+        l1,
+        total: l2 + l1,
+    }
+}
+
+/// 从 ArbGasInfo 和最新区块读取费用参数
+///
+/// # 参数
+/// * `provider` - Provider 引用
+///
+/// # 返回
+/// * `Result<ArbFees, Box<dyn Error>>` - 费用参数
+pub async fn arb_fees(provider: &RpcProvider) -> Result<ArbFees, Box<dyn Error>> {
+    let abi: Abi = serde_json::from_str(ARB_GAS_INFO_ABI)?;
+    let arb_gas_info = Contract::new(
+        Address::from_str(ARB_GAS_INFO_ADDRESS)?,
+        abi.clone(),
+        Arc::new(provider.clone()),
+    );
+
+    // 先取原始返回数据再按 ABI 解码，解码失败时能给出明确的错误
+    let function = abi.function("getPricesInWei")?;
+    let raw = provider
+        .call(
+            &TransactionRequest::new()
+                .to(Address::from_str(ARB_GAS_INFO_ADDRESS)?)
+                .data(function.encode_input(&[])?)
+                .into(),
+            None,
+        )
+        .await?;
+    let prices = PricesInWei::from_tokens(function.decode_output(&raw)?)?;
+
+    let l1_base_fee_estimate: U256 =
+        arb_gas_info.method("getL1BaseFeeEstimate", ())?.call().await?;
+    let current_tx_l1_gas_fees = arb_gas_info
+        .method::<_, U256>("getCurrentTxL1GasFees", ())?
+        .call()
+        .await
+        .ok();
+
+    let block_base_fee = provider
+        .get_block(BlockNumber::Latest)
+        .await?
+        .and_then(|block| block.base_fee_per_gas);
+
+    Ok(ArbFees {
+        prices,
+        l1_base_fee_estimate,
+        current_tx_l1_gas_fees,
+        block_base_fee,
+    })
+}
+
+/// 打印费用报告：逐项解释价格，再列出示例交易的费用
+///
+/// # 参数
+/// * `fees` - `arb_fees` 读取的费用参数
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+pub fn print_report(fees: &ArbFees) -> Result<(), Box<dyn Error>> {
+    let prices = &fees.prices;
+    let gwei = |value: U256| format_units(value, "gwei");
+
+    println!("{}", t(Msg::FeesTitle));
+    println!(
+        "{}",
+        tf(
+            Msg::FeesPerL2Gas,
+            &[
+                &gwei(prices.per_arb_gas_total)?,
+                &gwei(prices.per_arb_gas_base)?,
+                &gwei(prices.per_arb_gas_congestion)?
+            ]
+        )
+    );
+    match fees.block_base_fee {
+        Some(base_fee) => println!("{}", tf(Msg::FeesBlockBaseFee, &[&gwei(base_fee)?])),
+        None => println!("{}", t(Msg::FeesNoBlockBaseFee)),
+    }
+    println!("{}", tf(Msg::FeesPerL2Tx, &[&group_digits(prices.per_l2_tx)]));
+    println!("{}", tf(Msg::FeesPerCalldataByte, &[&group_digits(prices.per_l1_calldata_byte)]));
+    println!("{}", tf(Msg::FeesPerStorage, &[&group_digits(prices.per_storage_allocation)]));
+    println!("{}", tf(Msg::FeesL1BaseFee, &[&gwei(fees.l1_base_fee_estimate)?]));
+    if let Some(l1_fees) = fees.current_tx_l1_gas_fees {
+        println!("{}", tf(Msg::FeesCurrentTxL1, &[&group_digits(l1_fees)]));
+    }
+
+    // 区块基础费就是实际收取的 L2 Gas 价格，拿不到时用 ArbGasInfo 的总价格
+    let l2_gas_price = fees.block_base_fee.unwrap_or(prices.per_arb_gas_total);
+
+    println!("\n{}", t(Msg::FeesExamplesTitle));
+    println!(
+        "{:<24} {:>10} {:>10} {:>22} {:>22} {:>22}",
+        t(Msg::FeesColExample),
+        t(Msg::FeesColL2Gas),
+        t(Msg::FeesColBytes),
+        t(Msg::FeesColL2Cost),
+        t(Msg::FeesColL1Cost),
+        t(Msg::FeesColTotal)
+    );
+    for example in &EXAMPLES {
+        let cost = illustrative_cost(prices, l2_gas_price, example);
+        println!(
+            "{:<24} {:>10} {:>10} {:>22} {:>22} {:>22}",
+            t(example.name),
+            example.l2_gas,
+            example.calldata_bytes,
+            format_units(cost.l2, "ether")?,
+            format_units(cost.l1, "ether")?,
+            format_units(cost.total, "ether")?
+        );
+    }
+    println!("{}", tf(Msg::FeesExamplesNote, &[&TX_OVERHEAD_BYTES]));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // getPricesInWei 的一次返回值：6 个 uint256，依次为 6e9、42011836、2e12、1e7、0、1e7 wei
+    const PRICES_OUTPUT: &str = concat!(
+        "0000000000000000000000000000000000000000000000000000000165a0bc00",
+        "0000000000000000000000000000000000000000000000000000000002810cbc",
+        "000000000000000000000000000000000000000000000000000001d1a94a2000",
+        "0000000000000000000000000000000000000000000000000000000000989680",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000989680",
+    );
+
+    fn prices() -> PricesInWei {
+        let abi: Abi = serde_json::from_str(ARB_GAS_INFO_ABI).unwrap();
+        let function = abi.function("getPricesInWei").unwrap();
+        let raw = ethers::utils::hex::decode(PRICES_OUTPUT).unwrap();
+        PricesInWei::from_tokens(function.decode_output(&raw).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn decodes_the_six_prices_in_order() {
+        let prices = prices();
+        assert_eq!(prices.per_l2_tx, U256::from(6_000_000_000u64));
+        assert_eq!(prices.per_l1_calldata_byte, U256::from(42_011_836));
+        assert_eq!(prices.per_storage_allocation, U256::from(2_000_000_000_000u64));
+        assert_eq!(prices.per_arb_gas_base, U256::from(10_000_000));
+        assert_eq!(prices.per_arb_gas_congestion, U256::zero());
+        assert_eq!(prices.per_arb_gas_total, U256::from(10_000_000));
+    }
+
+    #[test]
+    fn rejects_tuples_of_the_wrong_shape() {
+        let uint = |n: u64| Token::Uint(U256::from(n));
+        let five = vec![uint(1); 5];
+        let mut wrong_type = vec![uint(1); 6];
+        wrong_type[2] = Token::Bool(true);
+        for tokens in [five, wrong_type, Vec::new()] {
+            let error = PricesInWei::from_tokens(tokens).unwrap_err();
+            assert_eq!(error.to_string(), t(Msg::FeesBadTuple));
+        }
+    }
+
+    #[test]
+    fn illustrative_cost_splits_l2_and_l1() {
+        let prices = prices();
+        let gas_price = prices.per_arb_gas_total;
+        let per_byte = prices.per_l1_calldata_byte.as_u64();
+        for (example, l2_gas, bytes) in [
+            (&EXAMPLES[0], 21_000, 110),
+            (&EXAMPLES[1], 35_000, 178),
+            (&EXAMPLES[2], 21_000 + 16 * 4096, 110 + 4096),
+        ] {
+            let cost = illustrative_cost(&prices, gas_price, example);
+            assert_eq!(cost.l2, U256::from(l2_gas * 10_000_000u64));
+            assert_eq!(cost.l1, U256::from(6_000_000_000 + bytes * per_byte));
+            assert_eq!(cost.total, cost.l2 + cost.l1);
+        }
+    }
+
+    #[test]
+    fn plain_transfer_cost_matches_hand_calculation() {
+        let cost = illustrative_cost(&prices(), U256::from(10_000_000), &EXAMPLES[0]);
+        // L2：21000 × 0.01 gwei；L1：6 gwei + 110 字节 × 42011836 wei
+        assert_eq!(cost.l2, U256::from(210_000_000_000u64));
+        assert_eq!(cost.l1, U256::from(10_621_301_960u64));
+        assert_eq!(cost.total, U256::from(220_621_301_960u64));
+    }
+}
@@ -0,0 +1,375 @@
+use arbi_common::metrics;
+use arbi_common::retry::{self, Backoff};
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::providers::Middleware;
+use ethers::types::BlockNumber;
+use serde_json::json;
+use std::error::Error;
+use std::time::Duration;
+
+use crate::messages::{Msg, t, tf};
+
+/// 某一时刻 `latest`、`safe`、`finalized` 三个标签对应的区块
+#[derive(Clone, Copy, Debug)]
+pub struct FinalitySnapshot {
+    pub latest: u64,
+    /// latest 区块的时间（Unix 秒）
+    pub latest_timestamp: u64,
+    pub safe: u64,
+    pub finalized: u64,
+    /// finalized 区块的时间（Unix 秒）
+    pub finalized_timestamp: u64,
+}
+
+/// 一次快照算出的差距
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FinalityLag {
+    /// latest 与 safe 相差的区块数
+    pub safe_blocks: u64,
+    /// latest 与 finalized 相差的区块数
+    pub finalized_blocks: u64,
+    /// 由区块时间戳算出的平均出块间隔（秒），区块号相同时为 `None`
+    pub block_time: Option<f64>,
+}
+
+impl FinalityLag {
+    /// 按平均出块间隔把区块数换算成分钟
+    ///
+    /// # 参数
+    /// * `blocks` - 区块数
+    ///
+    /// # 返回
+    /// * `Option<f64>` - 大约的分钟数，不知道出块间隔时为 `None`
+    pub fn minutes(&self, blocks: u64) -> Option<f64> {
+        self.block_time.map(|secs| blocks as f64 * secs / 60.0)
+    }
+}
+
+/// 计算 safe / finalized 落后 latest 的区块数和平均出块间隔
+///
+/// 标签之间的顺序理论上是 finalized ≤ safe ≤ latest，节点之间不同步时可能短暂颠倒，
+/// 这里按 0 处理。
+///
+/// # 参数
+/// * `snapshot` - 三个标签的读数
+///
+/// # 返回
+/// * `FinalityLag` - 差距
+pub fn compute_lag(snapshot: &FinalitySnapshot) -> FinalityLag {
+    let finalized_blocks = snapshot.latest.saturating_sub(snapshot.finalized);
+    let block_time = (finalized_blocks > 0).then(|| {
+        snapshot.latest_timestamp.saturating_sub(snapshot.finalized_timestamp) as f64
+            / finalized_blocks as f64
+    });
+    FinalityLag {
+        safe_blocks: snapshot.latest.saturating_sub(snapshot.safe),
+        finalized_blocks,
+        block_time,
+    }
+}
+
+/// finalized 落后的时间是否超过阈值
+///
+/// # 参数
+/// * `lag` - 差距
+/// * `threshold_minutes` - 阈值（分钟）
+///
+/// # 返回
+/// * `bool` - 超过阈值时为 `true`；不知道出块间隔时不报警
+pub fn exceeds_threshold(lag: &FinalityLag, threshold_minutes: f64) -> bool {
+    lag.minutes(lag.finalized_blocks)
+        .is_some_and(|minutes| minutes > threshold_minutes)
+}
+
+/// 本次运行期间 finalized 差距的滚动统计
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LagStats {
+    pub samples: u64,
+    pub min_blocks: u64,
+    pub max_blocks: u64,
+    sum_blocks: u64,
+    /// 超过阈值的次数
+    pub alerts: u64,
+}
+
+impl LagStats {
+    /// 记录一次差距
+    ///
+    /// # 参数
+    /// * `lag` - 这次的差距
+    /// * `alert` - 这次是否超过阈值
+    pub fn record(&mut self, lag: &FinalityLag, alert: bool) {
+        let blocks = lag.finalized_blocks;
+        self.min_blocks = if self.samples == 0 { blocks } else { self.min_blocks.min(blocks) };
+        self.max_blocks = self.max_blocks.max(blocks);
+        self.sum_blocks += blocks;
+        self.samples += 1;
+        self.alerts += u64::from(alert);
+    }
+
+    /// 平均差距（区块数），还没有样本时为 0
+    pub fn mean_blocks(&self) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        self.sum_blocks as f64 / self.samples as f64
+    }
+}
+
+/// 同时读取三个标签对应的区块
+///
+/// # 参数
+/// * `provider` - Provider 引用
+///
+/// # 返回
+/// * `Result<FinalitySnapshot, Box<dyn Error>>` - 读数；节点不支持 safe / finalized 标签时返回错误
+pub async fn snapshot(provider: &RpcProvider) -> Result<FinalitySnapshot, Box<dyn Error>> {
+    let (latest, safe, finalized) = tokio::try_join!(
+        provider.get_block(BlockNumber::Latest),
+        provider.get_block(BlockNumber::Safe),
+        provider.get_block(BlockNumber::Finalized),
+    )?;
+    let number = |block: Option<ethers::types::Block<_>>, tag: &str| {
+        block
+            .and_then(|block| Some((block.number?.as_u64(), block.timestamp.as_u64())))
+            .ok_or_else(|| tf(Msg::FinalityMissingTag, &[&tag]))
+    };
+    let (latest, latest_timestamp) = number(latest, "latest")?;
+    let (safe, _) = number(safe, "safe")?;
+    let (finalized, finalized_timestamp) = number(finalized, "finalized")?;
+    Ok(FinalitySnapshot {
+        latest,
+        latest_timestamp,
+        safe,
+        finalized,
+        finalized_timestamp,
+    })
+}
+
+fn format_minutes(minutes: Option<f64>) -> String {
+    minutes
+        .map(|minutes| format!("{:.1}", minutes))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// 打印一次快照：人类可读的一行，或 `--json` 时的一个 JSON 对象
+fn print_snapshot(
+    snapshot: &FinalitySnapshot,
+    lag: &FinalityLag,
+    stats: &LagStats,
+    alert: bool,
+    as_json: bool,
+) {
+    if as_json {
+        let line = json!({
+            "latest": snapshot.latest,
+            "safe": snapshot.safe,
+            "finalized": snapshot.finalized,
+            "safeLagBlocks": lag.safe_blocks,
+            "finalizedLagBlocks": lag.finalized_blocks,
+            "finalizedLagMinutes": lag.minutes(lag.finalized_blocks),
+            "blockTimeSecs": lag.block_time,
+            "alert": alert,
+            "samples": stats.samples,
+            "minLagBlocks": stats.min_blocks,
+            "maxLagBlocks": stats.max_blocks,
+            "meanLagBlocks": stats.mean_blocks(),
+        });
+        println!("{}", line);
+        return;
+    }
+
+    println!(
+        "{}",
+        tf(
+            Msg::FinalityLine,
+            &[
+                &snapshot.latest,
+                &snapshot.safe,
+                &lag.safe_blocks,
+                &format_minutes(lag.minutes(lag.safe_blocks)),
+                &snapshot.finalized,
+                &lag.finalized_blocks,
+                &format_minutes(lag.minutes(lag.finalized_blocks)),
+            ]
+        )
+    );
+    if alert {
+        println!("{}", style::warn(t(Msg::FinalityAlert)));
+    }
+}
+
+fn print_stats(stats: &LagStats) {
+    let mean = format!("{:.1}", stats.mean_blocks());
+    println!(
+        "{}",
+        tf(
+            Msg::FinalityStats,
+            &[&stats.samples, &stats.min_blocks, &mean, &stats.max_blocks, &stats.alerts]
+        )
+    );
+}
+
+/// 定期读取 latest / safe / finalized 之间的差距，finalized 落后过多时报警
+///
+/// finalized 要等批次发布到 L1 并且 L1 区块最终确定，落后时间异常变长通常说明
+/// batch poster 或 L1 出了问题。每次读取失败会按退避策略重试，仍失败时跳过这一轮。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `interval` - 两次读取的间隔
+/// * `threshold_minutes` - finalized 落后的报警阈值（分钟）
+/// * `once` - 只读取一次
+/// * `as_json` - 每次输出一行 JSON
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - `once` 时读取失败返回错误；持续监控时 Ctrl-C 退出
+pub async fn monitor(
+    provider: &RpcProvider,
+    interval: Duration,
+    threshold_minutes: f64,
+    once: bool,
+    as_json: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut stats = LagStats::default();
+    if !as_json && !once {
+        println!("{}", tf(Msg::FinalityStart, &[&interval.as_secs(), &threshold_minutes]));
+    }
+    loop {
+        match retry::with_backoff(Backoff::default(), || snapshot(provider)).await {
+            Ok(snapshot) => {
+                let lag = compute_lag(&snapshot);
+                let seconds = snapshot
+                    .latest_timestamp
+                    .saturating_sub(snapshot.finalized_timestamp);
+                metrics::set_finality_lag(lag.safe_blocks, lag.finalized_blocks, seconds);
+                let alert = exceeds_threshold(&lag, threshold_minutes);
+                stats.record(&lag, alert);
+                print_snapshot(&snapshot, &lag, &stats, alert, as_json);
+            }
+            Err(e) if once => return Err(e),
+            Err(e) => eprintln!("{}", style::err(tf(Msg::FinalityPollFailed, &[&e]))),
+        }
+        if once {
+            return Ok(());
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = tokio::signal::ctrl_c() => {
+                if !as_json {
+                    println!();
+                    print_stats(&stats);
+                }
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use serde_json::Value;
+
+    // latest 在 1_000_000，时间 1_700_000_000；safe 和 finalized 各落后若干块
+    fn snap(safe_behind: u64, finalized_behind: u64, seconds_behind: u64) -> FinalitySnapshot {
+        FinalitySnapshot {
+            latest: 1_000_000,
+            latest_timestamp: 1_700_000_000,
+            safe: 1_000_000 - safe_behind,
+            finalized: 1_000_000 - finalized_behind,
+            finalized_timestamp: 1_700_000_000 - seconds_behind,
+        }
+    }
+
+    #[test]
+    fn lag_and_block_time_come_from_latest_and_finalized() {
+        // 4800 块 1200 秒，平均 0.25 秒一块，finalized 落后 20 分钟
+        let lag = compute_lag(&snap(120, 4_800, 1_200));
+        assert_eq!(lag.safe_blocks, 120);
+        assert_eq!(lag.finalized_blocks, 4_800);
+        assert_eq!(lag.block_time, Some(0.25));
+        assert_eq!(lag.minutes(lag.finalized_blocks), Some(20.0));
+        assert_eq!(lag.minutes(lag.safe_blocks), Some(0.5));
+    }
+
+    #[test]
+    fn reordered_tags_count_as_no_lag() {
+        let mut reordered = snap(0, 0, 0);
+        reordered.safe += 3;
+        reordered.finalized += 5;
+        let lag = compute_lag(&reordered);
+        assert_eq!(lag.safe_blocks, 0);
+        assert_eq!(lag.finalized_blocks, 0);
+        assert_eq!(lag.block_time, None);
+        assert_eq!(lag.minutes(10), None);
+    }
+
+    #[test]
+    fn threshold_is_strict_and_silent_without_block_time() {
+        let lag = compute_lag(&snap(0, 4_800, 1_200));
+        assert!(exceeds_threshold(&lag, 19.9));
+        assert!(!exceeds_threshold(&lag, 20.0));
+        assert!(!exceeds_threshold(&lag, 60.0));
+        // 区块号相同，不知道出块间隔，不报警
+        assert!(!exceeds_threshold(&compute_lag(&snap(0, 0, 0)), 0.0));
+    }
+
+    #[test]
+    fn stats_track_min_mean_max_and_alerts() {
+        let mut stats = LagStats::default();
+        assert_eq!(stats.mean_blocks(), 0.0);
+        for (blocks, alert) in [(300, false), (100, false), (800, true)] {
+            stats.record(&compute_lag(&snap(0, blocks, blocks / 4)), alert);
+        }
+        assert_eq!(stats.samples, 3);
+        assert_eq!(stats.min_blocks, 100);
+        assert_eq!(stats.max_blocks, 800);
+        assert_eq!(stats.mean_blocks(), 400.0);
+        assert_eq!(stats.alerts, 1);
+    }
+
+    // 按标签返回区块；`finalized` 为 None 时模拟不支持这个标签的节点
+    fn node(finalized: Option<(u64, u64)>) -> MockRpc {
+        MockRpc::start(move |method, params| {
+            let block = match (method, params[0].as_str()) {
+                ("eth_getBlockByNumber", Some("latest")) => Some((1_000_000, 1_700_000_000)),
+                ("eth_getBlockByNumber", Some("safe")) => Some((999_880, 1_699_999_970)),
+                ("eth_getBlockByNumber", Some("finalized")) => finalized,
+                _ => None,
+            };
+            match block {
+                Some((number, timestamp)) => Reply::Result(serde_json::json!({
+                    "number": format!("{:#x}", number),
+                    "timestamp": format!("{:#x}", timestamp),
+                    "hash": format!("{:?}", ethers::types::H256::from_low_u64_be(number)),
+                    "transactions": [],
+                })),
+                None => Reply::Result(Value::Null),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn snapshot_reads_all_three_tags() {
+        let rpc = node(Some((995_200, 1_699_998_800)));
+        let snapshot = snapshot(&rpc.provider()).await.unwrap();
+        assert_eq!(snapshot.latest, 1_000_000);
+        assert_eq!(snapshot.safe, 999_880);
+        assert_eq!(snapshot.finalized, 995_200);
+        let lag = compute_lag(&snapshot);
+        assert_eq!(lag.finalized_blocks, 4_800);
+        assert_eq!(lag.block_time, Some(0.25));
+        assert_eq!(rpc.count("eth_getBlockByNumber"), 3);
+    }
+
+    #[tokio::test]
+    async fn missing_tag_is_named_in_the_error() {
+        let rpc = node(None);
+        let error = snapshot(&rpc.provider()).await.unwrap_err();
+        assert_eq!(error.to_string(), tf(Msg::FinalityMissingTag, &[&"finalized"]));
+    }
+}
@@ -0,0 +1,502 @@
+use arbi_common::block_scan;
+use arbi_common::concurrency;
+use arbi_common::explorer;
+use arbi_common::logging;
+use arbi_common::progress;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use arbi_common::table::Table;
+use ethers::providers::Middleware;
+use ethers::types::{Address, TransactionReceipt, TxHash, U256};
+use ethers::utils::format_units;
+use serde_json::Value;
+use std::error::Error;
+use std::future::Future;
+use std::str::FromStr;
+
+use crate::history::{self, Direction};
+use crate::messages::{Msg, t, tf};
+
+// 逐块扫描时每段的区块数，每段内按顺序读取
+const SCAN_CHUNK_SIZE: u64 = 100;
+
+// 同时扫描的段数 / 同时读取的收据数，太大容易触发公共节点的限流
+const SCAN_CONCURRENCY: usize = 8;
+
+// 没有 API key 时逐块扫描的最大区块数（Arbitrum 约 4 个区块每秒，5 万个区块约 3.5 小时）
+const MAX_SCAN_BLOCKS: u64 = 50_000;
+
+// 扫描超过这么多区块时在 stderr 打印进度
+const PROGRESS_MIN_BLOCKS: u64 = 1000;
+
+// 按最近交易统计时每页读取的交易数；浏览器只允许翻到第 10000 条，所以最多读取 100 页
+const RECENT_PAGE_SIZE: usize = 100;
+const RECENT_MAX_PAGES: usize = 10_000 / RECENT_PAGE_SIZE;
+
+/// 按最近交易统计时最多统计的交易数
+pub const MAX_RECENT_TXS: u64 = 1000;
+
+/// 一笔交易的 Gas 花费
+pub struct GasEntry {
+    pub block: u64,
+    pub hash: TxHash,
+    pub gas_used: U256,
+    pub effective_gas_price: U256,
+    /// 执行是否成功；失败的交易同样付了 Gas
+    pub success: bool,
+}
+
+impl GasEntry {
+    /// 实际支付的 Gas 费（wei）
+    pub fn fee(&self) -> U256 {
+        self.gas_used * self.effective_gas_price
+    }
+
+    /// 从收据读取；缺少 Gas 字段的收据（如尚未打包）返回 `None`
+    fn from_receipt(receipt: &TransactionReceipt) -> Option<Self> {
+        Some(Self {
+            block: receipt.block_number?.as_u64(),
+            hash: receipt.transaction_hash,
+            gas_used: receipt.gas_used?,
+            effective_gas_price: receipt.effective_gas_price?,
+            success: receipt.status.is_some_and(|status| status.as_u64() == 1),
+        })
+    }
+}
+
+/// 最近 `n` 笔发出交易的 Gas 花费
+pub struct GasSpent {
+    /// 从新到旧排列的已打包交易
+    pub entries: Vec<GasEntry>,
+    /// 跳过的未打包交易数
+    pub pending: usize,
+    /// 读完浏览器允许的全部页仍没有找够 `n` 笔，账户可能还有更早的交易
+    pub scan_limited: bool,
+}
+
+/// Gas 花费汇总
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct GasTotals {
+    pub count: usize,
+    pub total: U256,
+    pub succeeded: usize,
+    pub succeeded_fee: U256,
+    pub reverted: usize,
+    pub reverted_fee: U256,
+}
+
+impl GasTotals {
+    /// 汇总每笔交易的 Gas 费，按成功 / 失败分开统计
+    ///
+    /// # 参数
+    /// * `entries` - 交易列表
+    ///
+    /// # 返回
+    /// * `GasTotals` - 汇总结果
+    pub fn of(entries: &[GasEntry]) -> Self {
+        let mut totals = Self::default();
+        for entry in entries {
+            let fee = entry.fee();
+            totals.count += 1;
+            totals.total += fee;
+            if entry.success {
+                totals.succeeded += 1;
+                totals.succeeded_fee += fee;
+            } else {
+                totals.reverted += 1;
+                totals.reverted_fee += fee;
+            }
+        }
+        totals
+    }
+
+    /// 平均每笔的 Gas 费（向下取整），没有交易时为 `None`
+    pub fn average(&self) -> Option<U256> {
+        (self.count > 0).then(|| self.total / U256::from(self.count))
+    }
+}
+
+/// 没有 API key 时分段并发扫描区块，找出账户发出的交易
+///
+/// 结果按区块号排列；区块多时在 stderr 打印进度（-q 时不打印）。
+async fn outgoing_from_blocks(
+    provider: &RpcProvider,
+    account: Address,
+    from: u64,
+    to: u64,
+) -> Result<Vec<TxHash>, Box<dyn Error>> {
+    if to - from >= MAX_SCAN_BLOCKS {
+        let reason = tf(Msg::HistoryRangeTooLarge, &[&MAX_SCAN_BLOCKS, &explorer::API_KEY_ENV]);
+        return Err(reason.into());
+    }
+    let show_progress = to - from + 1 >= PROGRESS_MIN_BLOCKS;
+    let hashes = block_scan::scan_blocks(
+        provider,
+        (from, to),
+        SCAN_CHUNK_SIZE,
+        SCAN_CONCURRENCY,
+        |block| {
+            let sent = block.transactions.iter().filter(|tx| tx.from == account);
+            sent.map(|tx| tx.hash).collect()
+        },
+        |scanned, total, found| {
+            if show_progress {
+                progress!("{}", tf(Msg::GasReportProgress, &[&scanned, &total, &found]));
+            }
+        },
+    )
+    .await;
+    if show_progress {
+        logging::end_progress();
+    }
+    hashes
+}
+
+/// 读取账户在区块范围内发出的交易的 Gas 花费
+///
+/// 配置了 `ARBISCAN_API_KEY` 时用区块浏览器列出交易，否则分段并发扫描区块（最多 5 万个区块）。
+/// 两种方式都从收据读取 `gas_used × effective_gas_price`。
+/// 只统计账户发出的交易，因为转入的交易由对方付 Gas。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 账户地址
+/// * `from` - 起始区块（含）
+/// * `to` - 结束区块（含）
+///
+/// # 返回
+/// * `Result<Vec<GasEntry>, Box<dyn Error>>` - 按区块号排列的交易
+pub async fn gas_entries(
+    provider: &RpcProvider,
+    address: Address,
+    from: u64,
+    to: u64,
+) -> Result<Vec<GasEntry>, Box<dyn Error>> {
+    if from > to {
+        return Err(tf(Msg::GasUsageBadRange, &[&from, &to]).into());
+    }
+    let hashes = match explorer::api_key() {
+        Some(key) => history::history_from_explorer(&key, address, from, to)
+            .await?
+            .into_iter()
+            .filter(|entry| entry.direction != Direction::In)
+            .map(|entry| entry.hash)
+            .collect(),
+        None => {
+            eprintln!("{}", style::warn(tf(Msg::GasReportScanning, &[&explorer::API_KEY_ENV])));
+            outgoing_from_blocks(provider, address, from, to).await?
+        }
+    };
+
+    let receipts = concurrency::run_bounded(hashes, SCAN_CONCURRENCY, |hash| {
+        provider.get_transaction_receipt(hash)
+    })
+    .await
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()?;
+    let mut entries: Vec<GasEntry> =
+        receipts.iter().flatten().filter_map(GasEntry::from_receipt).collect();
+    entries.sort_by_key(|entry| entry.block);
+    Ok(entries)
+}
+
+/// 通过区块浏览器从新到旧列出账户发出的最近 `n` 笔交易
+///
+/// 浏览器的列表同时包含转入的交易，按页读取直到找够 `n` 笔发出的交易或没有更多交易。
+/// 返回的布尔值表示是否因为达到 `RECENT_MAX_PAGES` 而停止。
+async fn recent_outgoing(
+    api_key: &str,
+    account: Address,
+    n: usize,
+) -> Result<(Vec<TxHash>, bool), Box<dyn Error>> {
+    let address = format!("{:?}", account);
+    let offset = RECENT_PAGE_SIZE.to_string();
+    collect_outgoing(account, n, |page| {
+        let (address, offset) = (&address, &offset);
+        async move {
+            let page = page.to_string();
+            let params = [
+                ("module", "account"),
+                ("action", "txlist"),
+                ("address", address.as_str()),
+                ("page", &page),
+                ("offset", offset.as_str()),
+                ("sort", "desc"),
+            ];
+            explorer::get(api_key, &params).await
+        }
+    })
+    .await
+}
+
+// 按页（从 1 开始）读取交易列表，收集 `account` 发出的前 `n` 笔交易；
+// 第二个返回值表示读到最后一页仍是满页、没有找够
+async fn collect_outgoing<F, Fut>(
+    account: Address,
+    n: usize,
+    mut fetch_page: F,
+) -> Result<(Vec<TxHash>, bool), Box<dyn Error>>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = Result<Value, Box<dyn Error>>>,
+{
+    let mut hashes = Vec::new();
+    for page in 1..=RECENT_MAX_PAGES {
+        let result = fetch_page(page).await?;
+        let items = result.as_array().cloned().unwrap_or_default();
+        for item in &items {
+            let from = item["from"].as_str().and_then(|s| Address::from_str(s).ok());
+            let hash = item["hash"].as_str().and_then(|s| TxHash::from_str(s).ok());
+            if from == Some(account)
+                && let Some(hash) = hash
+            {
+                hashes.push(hash);
+            }
+            if hashes.len() == n {
+                return Ok((hashes, false));
+            }
+        }
+        if items.len() < RECENT_PAGE_SIZE {
+            return Ok((hashes, false));
+        }
+    }
+    Ok((hashes, true))
+}
+
+/// 读取账户最近发出的 `n` 笔交易的 Gas 花费
+///
+/// 用区块浏览器列出交易（需要 `ARBISCAN_API_KEY`），再从收据读取
+/// `gas_used × effective_gas_price`。还没有打包的交易没有收据（或收据缺少 Gas 字段），跳过不计。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 账户地址
+/// * `n` - 统计最近多少笔交易
+///
+/// # 返回
+/// * `Result<GasSpent, Box<dyn Error>>` - 交易、跳过的未确认交易数，以及是否达到浏览器的翻页上限
+pub async fn gas_spent(
+    provider: &RpcProvider,
+    address: Address,
+    n: usize,
+) -> Result<GasSpent, Box<dyn Error>> {
+    let api_key = explorer::api_key()
+        .ok_or_else(|| tf(Msg::GasSpentNeedsKey, &[&explorer::API_KEY_ENV]))?;
+    let (hashes, scan_limited) = recent_outgoing(&api_key, address, n).await?;
+
+    let receipts = concurrency::run_bounded(&hashes, SCAN_CONCURRENCY, |hash| {
+        provider.get_transaction_receipt(*hash)
+    })
+    .await
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()?;
+    let mut entries: Vec<GasEntry> =
+        receipts.iter().flatten().filter_map(GasEntry::from_receipt).collect();
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.block));
+    let pending = hashes.len() - entries.len();
+    Ok(GasSpent {
+        entries,
+        pending,
+        scan_limited,
+    })
+}
+
+/// 逐笔明细的表格，Gas 价格和手续费同时给出 wei 和格式化后的值
+///
+/// # 参数
+/// * `entries` - 交易列表
+///
+/// # 返回
+/// * `Result<Table, Box<dyn Error>>` - 用于导出 CSV 的表格
+pub fn to_table(entries: &[GasEntry]) -> Result<Table, Box<dyn Error>> {
+    let mut table = Table::new([
+        "block",
+        "hash",
+        "gas_used",
+        "gas_price_wei",
+        "gas_price_gwei",
+        "fee_wei",
+        "fee_eth",
+        "success",
+    ]);
+    for entry in entries {
+        table.push([
+            entry.block.to_string(),
+            format!("{:?}", entry.hash),
+            entry.gas_used.to_string(),
+            entry.effective_gas_price.to_string(),
+            format_units(entry.effective_gas_price, "gwei")?,
+            entry.fee().to_string(),
+            format_units(entry.fee(), "ether")?,
+            entry.success.to_string(),
+        ]);
+    }
+    Ok(table)
+}
+
+/// 打印逐笔明细和汇总，失败交易的 Gas 用警告色标出
+///
+/// # 参数
+/// * `address` - 账户地址
+/// * `range` - 区块范围（含两端）
+/// * `entries` - 交易列表
+pub fn print_report(
+    address: Address,
+    (from, to): (u64, u64),
+    entries: &[GasEntry],
+) -> Result<(), Box<dyn Error>> {
+    let title = tf(Msg::GasReportTitle, &[&style::addr(format!("{:?}", address)), &from, &to]);
+    println!("{}", title);
+    if entries.is_empty() {
+        println!("{}", t(Msg::GasReportEmpty));
+        return Ok(());
+    }
+    print_breakdown(entries)
+}
+
+/// 打印最近 `n` 笔交易的逐笔明细和汇总
+///
+/// # 参数
+/// * `address` - 账户地址
+/// * `n` - 要求统计的交易数
+/// * `spent` - `gas_spent` 的结果
+pub fn print_spent(address: Address, n: usize, spent: &GasSpent) -> Result<(), Box<dyn Error>> {
+    let GasSpent {
+        entries,
+        pending,
+        scan_limited,
+    } = spent;
+    let found = entries.len() + pending;
+    println!("{}", tf(Msg::GasSpentTitle, &[&style::addr(format!("{:?}", address)), &n]));
+    if *pending > 0 {
+        println!("{}", style::warn(tf(Msg::GasSpentPendingSkipped, &[pending])));
+    }
+    // 达到翻页上限时不知道账户一共发出过多少笔，不能说“一共只有”
+    if *scan_limited {
+        let listed = RECENT_MAX_PAGES * RECENT_PAGE_SIZE;
+        println!("{}", style::warn(tf(Msg::GasSpentScanLimit, &[&listed, &found])));
+    }
+    if entries.is_empty() {
+        if found == 0 && !scan_limited {
+            println!("{}", t(Msg::GasSpentEmpty));
+        }
+        return Ok(());
+    }
+    if found < n && !scan_limited {
+        println!("{}", tf(Msg::GasSpentFewer, &[&found]));
+    }
+    print_breakdown(entries)
+}
+
+// 逐笔明细和汇总，失败交易的 Gas 用警告色标出
+fn print_breakdown(entries: &[GasEntry]) -> Result<(), Box<dyn Error>> {
+    println!(
+        "{:>10}  {:<66}  {:>10}  {:>12}  {:>22}",
+        t(Msg::GasUsageColBlock),
+        t(Msg::GasReportColHash),
+        t(Msg::GasUsageColGasUsed),
+        t(Msg::GasReportColPrice),
+        t(Msg::GasReportColFee)
+    );
+    for entry in entries {
+        let line = format!(
+            "{:>10}  {:<66}  {:>10}  {:>12}  {:>22}",
+            entry.block,
+            format!("{:?}", entry.hash),
+            entry.gas_used,
+            format_units(entry.effective_gas_price, "gwei")?,
+            format_units(entry.fee(), "ether")?
+        );
+        match entry.success {
+            true => println!("{}", line),
+            false => println!("{}  {}", style::warn(line), style::warn(t(Msg::GasReportReverted))),
+        }
+    }
+
+    let totals = GasTotals::of(entries);
+    let average = totals.average().unwrap_or_default();
+    println!();
+    let total = format_units(totals.total, "ether")?;
+    println!("{}", tf(Msg::GasReportTotal, &[&total, &totals.count]));
+    println!("{}", tf(Msg::GasReportAverage, &[&format_units(average, "ether")?]));
+    let succeeded = format_units(totals.succeeded_fee, "ether")?;
+    println!("{}", tf(Msg::GasReportSucceeded, &[&totals.succeeded, &succeeded]));
+    let reverted = tf(
+        Msg::GasReportRevertedTotal,
+        &[&totals.reverted, &format_units(totals.reverted_fee, "ether")?],
+    );
+    match totals.reverted {
+        0 => println!("{}", reverted),
+        _ => println!("{}", style::warn(reverted)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::cell::Cell;
+
+    fn me() -> Address {
+        Address::from_low_u64_be(1)
+    }
+
+    // 一页交易：`outgoing` 笔由 `me` 发出，其余是转入
+    fn page(size: usize, outgoing: usize, page: usize) -> Value {
+        let items: Vec<Value> = (0..size)
+            .map(|i| {
+                let from = if i < outgoing { me() } else { Address::from_low_u64_be(2) };
+                json!({
+                    "from": format!("{:?}", from),
+                    "hash": format!("{:?}", TxHash::from_low_u64_be((page * 1000 + i) as u64)),
+                })
+            })
+            .collect();
+        Value::Array(items)
+    }
+
+    async fn collect(n: usize, pages: impl Fn(usize) -> Value) -> (Vec<TxHash>, bool, usize) {
+        let fetched = Cell::new(0);
+        let (hashes, limited) = collect_outgoing(me(), n, |number| {
+            fetched.set(fetched.get() + 1);
+            let result = pages(number);
+            async move { Ok(result) }
+        })
+        .await
+        .unwrap();
+        (hashes, limited, fetched.get())
+    }
+
+    #[tokio::test]
+    async fn stops_once_n_outgoing_are_found() {
+        let (hashes, limited, fetched) = collect(3, |n| page(RECENT_PAGE_SIZE, 2, n)).await;
+        assert_eq!(hashes.len(), 3);
+        assert_eq!(hashes[2], TxHash::from_low_u64_be(2000));
+        assert_eq!((limited, fetched), (false, 2));
+    }
+
+    #[tokio::test]
+    async fn short_page_means_the_list_is_complete() {
+        let pages = |n| match n {
+            1 => page(RECENT_PAGE_SIZE, 1, n),
+            _ => page(5, 1, n),
+        };
+        let (hashes, limited, fetched) = collect(10, pages).await;
+        assert_eq!((hashes.len(), limited, fetched), (2, false, 2));
+    }
+
+    #[tokio::test]
+    async fn full_last_page_reports_the_scan_limit() {
+        let (hashes, limited, fetched) = collect(1000, |n| page(RECENT_PAGE_SIZE, 1, n)).await;
+        assert_eq!(hashes.len(), RECENT_MAX_PAGES);
+        assert_eq!((limited, fetched), (true, RECENT_MAX_PAGES));
+    }
+
+    #[tokio::test]
+    async fn finding_n_on_the_last_page_is_not_limited() {
+        let n = RECENT_MAX_PAGES;
+        let (hashes, limited, _) = collect(n, |n| page(RECENT_PAGE_SIZE, 1, n)).await;
+        assert_eq!((hashes.len(), limited), (n, false));
+    }
+}
@@ -0,0 +1,156 @@
+use arbi_common::concurrency;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::providers::Middleware;
+use ethers::types::U256;
+use std::error::Error;
+
+use crate::messages::{Msg, t, tf};
+
+// 同时进行的区块请求数，太大容易触发公共节点的限流
+const FETCH_CONCURRENCY: usize = 8;
+
+// 柱状图最长的字符数
+const BAR_WIDTH: usize = 40;
+
+// Arbitrum 区块头里的 Gas 上限是固定的占位值（2^50），并不代表真实容量
+const ARBITRUM_NOMINAL_GAS_LIMIT: u64 = 1 << 50;
+
+/// 一个区块的 Gas 使用情况
+pub struct BlockUsage {
+    pub number: u64,
+    /// 区块时间（Unix 秒）
+    pub timestamp: u64,
+    pub gas_used: U256,
+    pub gas_limit: U256,
+}
+
+impl BlockUsage {
+    /// Gas 使用率（0 到 1）
+    pub fn ratio(&self) -> f64 {
+        if self.gas_limit.is_zero() {
+            return 0.0;
+        }
+        u256_to_f64(self.gas_used) / u256_to_f64(self.gas_limit)
+    }
+}
+
+fn u256_to_f64(value: U256) -> f64 {
+    value.to_string().parse().unwrap_or_default()
+}
+
+/// 计算采样步长：区块数超过 `max_samples` 时每隔 N 个区块取一个
+///
+/// # 参数
+/// * `from` - 起始区块（含）
+/// * `to` - 结束区块（含）
+/// * `max_samples` - 最多读取的区块数
+///
+/// # 返回
+/// * `u64` - 步长，至少为 1
+pub fn sample_step(from: u64, to: u64, max_samples: u64) -> u64 {
+    let blocks = to.saturating_sub(from) + 1;
+    blocks.div_ceil(max_samples.max(1)).max(1)
+}
+
+/// 并发读取区块范围内（按步长采样）每个区块的 Gas 用量和 Gas 上限
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `from` - 起始区块（含）
+/// * `to` - 结束区块（含）
+/// * `step` - 采样步长，1 表示读取每个区块
+///
+/// # 返回
+/// * `Result<Vec<BlockUsage>, Box<dyn Error>>` - 按区块号升序排列，节点查不到的区块会被跳过
+pub async fn gas_usage(
+    provider: &RpcProvider,
+    from: u64,
+    to: u64,
+    step: u64,
+) -> Result<Vec<BlockUsage>, Box<dyn Error>> {
+    if from > to {
+        return Err(tf(Msg::GasUsageBadRange, &[&from, &to]).into());
+    }
+
+    // run_bounded 按输入顺序返回，结果天然按区块号排列
+    let numbers = (from..=to).step_by(step.max(1) as usize);
+    let blocks = concurrency::run_bounded(numbers, FETCH_CONCURRENCY, |number| {
+        provider.get_block(number)
+    })
+    .await
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(blocks
+        .into_iter()
+        .flatten()
+        .filter_map(|block| {
+            Some(BlockUsage {
+                number: block.number?.as_u64(),
+                timestamp: block.timestamp.as_u64(),
+                gas_used: block.gas_used,
+                gas_limit: block.gas_limit,
+            })
+        })
+        .collect())
+}
+
+/// 打印 Gas 使用率的 ASCII 柱状图
+///
+/// 柱子长度按范围内使用率最高的区块缩放，这样 Arbitrum 上使用率极低时也能看出起伏；
+/// 百分比仍是相对区块 Gas 上限的真实值。
+///
+/// # 参数
+/// * `usages` - `gas_usage` 的返回值
+/// * `step` - 采样步长
+pub fn print_chart(usages: &[BlockUsage], step: u64) {
+    if usages.is_empty() {
+        println!("{}", t(Msg::GasUsageEmpty));
+        return;
+    }
+    if step > 1 {
+        println!("{}", style::warn(tf(Msg::GasUsageSampled, &[&step])));
+    }
+    if usages.iter().any(|usage| usage.gas_limit >= U256::from(ARBITRUM_NOMINAL_GAS_LIMIT)) {
+        println!("{}", style::warn(t(Msg::GasUsageNominalLimit)));
+    }
+
+    let max_ratio = usages.iter().map(BlockUsage::ratio).fold(0.0, f64::max);
+    println!(
+        "\n{:<12} {:<12} {:>14} {:>10}  {}",
+        t(Msg::GasUsageColBlock),
+        t(Msg::GasUsageColTime),
+        t(Msg::GasUsageColGasUsed),
+        t(Msg::GasUsageColRatio),
+        t(Msg::GasUsageColChart)
+    );
+    for usage in usages {
+        let ratio = usage.ratio();
+        let width = if max_ratio > 0.0 {
+            (ratio / max_ratio * BAR_WIDTH as f64).round() as usize
+        } else {
+            0
+        };
+        println!(
+            "{:<12} {:<12} {:>14} {:>9.4}%  {}",
+            usage.number,
+            usage.timestamp,
+            usage.gas_used,
+            ratio * 100.0,
+            "█".repeat(width)
+        );
+    }
+
+    let average = usages.iter().map(BlockUsage::ratio).sum::<f64>() / usages.len() as f64;
+    let busiest = usages
+        .iter()
+        .max_by_key(|usage| usage.gas_used)
+        .map(|usage| usage.number)
+        .unwrap_or_default();
+    let summary = tf(
+        Msg::GasUsageSummary,
+        &[&usages.len(), &format!("{:.4}", average * 100.0), &busiest],
+    );
+    println!("\n{}", summary);
+}
@@ -0,0 +1,354 @@
+use ethers::abi::ParamType;
+use ethers::abi::param_type::Reader;
+use ethers::types::{Address, H256, I256, U256};
+use ethers::utils::{hex, keccak256};
+use std::error::Error;
+use std::str::FromStr;
+
+use crate::messages::{Msg, tf};
+
+/// 计算 UTF-8 字符串的 keccak256 哈希
+///
+/// # 参数
+/// * `text` - 任意字符串，按 UTF-8 字节计算
+///
+/// # 返回
+/// * `H256` - 32 字节哈希
+pub fn keccak_text(text: &str) -> H256 {
+    H256::from(keccak256(text.as_bytes()))
+}
+
+/// 计算十六进制字节串的 keccak256 哈希
+///
+/// # 参数
+/// * `input` - 十六进制字符串（可带 0x 前缀）
+///
+/// # 返回
+/// * `Result<H256, Box<dyn Error>>` - 32 字节哈希；不是合法十六进制时返回错误
+pub fn keccak_hex(input: &str) -> Result<H256, Box<dyn Error>> {
+    let bytes = hex::decode(input.trim()).map_err(|e| tf(Msg::InvalidHex, &[&input, &e]))?;
+    Ok(H256::from(keccak256(bytes)))
+}
+
+/// 计算函数签名的 4 字节选择器
+///
+/// 选择器是规范签名（函数名加参数类型，不含空格和参数名）keccak256 哈希的前 4 个字节，
+/// 所以这里先去掉空格再计算，`transfer(address, uint256)` 和 `transfer(address,uint256)` 结果相同。
+///
+/// # 参数
+/// * `signature` - 函数签名，如 `transfer(address,uint256)`
+///
+/// # 返回
+/// * `Result<(String, [u8; 4]), Box<dyn Error>>` - 规范签名和选择器
+pub fn selector(signature: &str) -> Result<(String, [u8; 4]), Box<dyn Error>> {
+    let canonical: String = signature.chars().filter(|c| !c.is_whitespace()).collect();
+    let well_formed = match canonical.find('(') {
+        Some(open) => open > 0 && canonical.ends_with(')'),
+        None => false,
+    };
+    if !well_formed {
+        return Err(tf(Msg::InvalidSignature, &[&signature]).into());
+    }
+
+    let hash = keccak256(canonical.as_bytes());
+    Ok((canonical, [hash[0], hash[1], hash[2], hash[3]]))
+}
+
+/// 按 `abi.encodePacked` 规则编码一组 `类型:值`，如 `address:0x..,uint256:5`
+///
+/// 类型用 ethers 的 ABI 类型解析器读取。紧凑编码的规则：
+/// - 整数、`bytesN`、`address`、`bool` 按类型本身的宽度拼接，不补齐到 32 字节（`uint8` 占 1 字节）；
+/// - `bytes`、`string` 直接拼接原始字节，不带长度；
+/// - 数组的每个元素补齐到 32 字节。
+///
+/// 和 Solidity 一样不支持结构体、嵌套数组和动态类型的数组。数组写成 `uint256[]:[1,2,3]`，
+/// 值里有逗号的字符串用双引号括起来，如 `string:"a,b"`。
+///
+/// # 参数
+/// * `input` - 逗号分隔的 `类型:值` 列表
+///
+/// # 返回
+/// * `Result<Vec<u8>, Box<dyn Error>>` - 紧凑编码后的字节；类型或值无效时返回错误
+pub fn encode_packed(input: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let items = split_top_level(input);
+    if items.is_empty() {
+        return Err(tf(Msg::PackedInvalidItem, &[&input]).into());
+    }
+    let mut packed = Vec::new();
+    for item in items {
+        let (ty, value) = item
+            .split_once(':')
+            .ok_or_else(|| tf(Msg::PackedInvalidItem, &[&item]))?;
+        let ty = parse_type(ty.trim())?;
+        packed.extend(encode_value(&ty, value.trim(), false)?);
+    }
+    Ok(packed)
+}
+
+// 按顶层的逗号拆分，方括号和双引号里的逗号不拆
+fn split_top_level(input: &str) -> Vec<&str> {
+    let (mut items, mut depth, mut quoted, mut start) = (Vec::new(), 0usize, false, 0);
+    for (index, c) in input.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '[' if !quoted => depth += 1,
+            ']' if !quoted => depth = depth.saturating_sub(1),
+            ',' if !quoted && depth == 0 => {
+                items.push(input[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(input[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+// 解析类型名；解析器会把不认识的名称当成 enum（uint8），这里要求解析结果和输入一致
+fn parse_type(name: &str) -> Result<ParamType, Box<dyn Error>> {
+    let invalid = || tf(Msg::PackedInvalidType, &[&name]);
+    let ty = Reader::read(name).map_err(|_| invalid())?;
+    let (base, suffix) = name.split_at(name.find('[').unwrap_or(name.len()));
+    let base = match base {
+        "uint" => "uint256",
+        "int" => "int256",
+        base => base,
+    };
+    if ty.to_string() != format!("{}{}", base, suffix) {
+        return Err(invalid().into());
+    }
+    Ok(ty)
+}
+
+// 编码一个值；数组元素（`in_array`）补齐到 32 字节
+fn encode_value(ty: &ParamType, value: &str, in_array: bool) -> Result<Vec<u8>, Box<dyn Error>> {
+    let invalid = || tf(Msg::PackedInvalidValue, &[&value, ty]);
+    let width = |size: usize| if in_array { 32 } else { size };
+    let bytes = match ty {
+        ParamType::Address => {
+            let address = Address::from_str(value).map_err(|_| invalid())?;
+            left_pad(address.as_bytes(), width(20))
+        }
+        ParamType::Bool => match value {
+            "true" => left_pad(&[1], width(1)),
+            "false" => left_pad(&[0], width(1)),
+            _ => return Err(invalid().into()),
+        },
+        ParamType::Uint(bits) => {
+            let size = integer_size(ty, *bits)?;
+            let number = match value.strip_prefix("0x") {
+                Some(digits) => U256::from_str_radix(digits, 16).ok(),
+                None => U256::from_dec_str(value).ok(),
+            };
+            let number = number.ok_or_else(invalid)?;
+            if number.bits() > *bits {
+                return Err(tf(Msg::PackedOutOfRange, &[&value, ty]).into());
+            }
+            word_tail(number, width(size))
+        }
+        ParamType::Int(bits) => {
+            let size = integer_size(ty, *bits)?;
+            let number = I256::from_dec_str(value).map_err(|_| invalid())?;
+            if *bits < 256 {
+                let limit = I256::from_raw(U256::one() << (bits - 1));
+                if number < -limit || number >= limit {
+                    return Err(tf(Msg::PackedOutOfRange, &[&value, ty]).into());
+                }
+            }
+            word_tail(number.into_raw(), width(size))
+        }
+        ParamType::FixedBytes(size) => {
+            if !(1..=32).contains(size) {
+                return Err(tf(Msg::PackedInvalidType, &[ty]).into());
+            }
+            let mut bytes = hex::decode(value).map_err(|_| invalid())?;
+            if bytes.len() != *size {
+                return Err(invalid().into());
+            }
+            bytes.resize(width(*size), 0);
+            bytes
+        }
+        ParamType::Bytes => hex::decode(value).map_err(|_| invalid())?,
+        ParamType::String => {
+            let text = value.strip_prefix('"').and_then(|v| v.strip_suffix('"'));
+            text.unwrap_or(value).as_bytes().to_vec()
+        }
+        ParamType::Array(inner) | ParamType::FixedArray(inner, _) => {
+            if in_array || inner.is_dynamic() || matches!(**inner, ParamType::FixedArray(..)) {
+                return Err(tf(Msg::PackedUnsupported, &[ty]).into());
+            }
+            let elements = value
+                .strip_prefix('[')
+                .and_then(|v| v.strip_suffix(']'))
+                .ok_or_else(invalid)?;
+            let elements = split_top_level(elements);
+            if let ParamType::FixedArray(_, len) = ty
+                && elements.len() != *len
+            {
+                return Err(tf(Msg::PackedArrayLength, &[ty, len, &elements.len()]).into());
+            }
+            let mut bytes = Vec::with_capacity(elements.len() * 32);
+            for element in elements {
+                bytes.extend(encode_value(inner, element, true)?);
+            }
+            bytes
+        }
+        ParamType::Tuple(_) => return Err(tf(Msg::PackedUnsupported, &[ty]).into()),
+    };
+    Ok(bytes)
+}
+
+// 整数类型的字节数：位数必须是 8 的倍数，范围 8～256
+fn integer_size(ty: &ParamType, bits: usize) -> Result<usize, Box<dyn Error>> {
+    match bits.is_multiple_of(8) && (8..=256).contains(&bits) {
+        true => Ok(bits / 8),
+        false => Err(tf(Msg::PackedInvalidType, &[ty]).into()),
+    }
+}
+
+// 256 位整数大端序的最后 `size` 个字节（负数已是补码）
+fn word_tail(value: U256, size: usize) -> Vec<u8> {
+    let mut word = [0u8; 32];
+    value.to_big_endian(&mut word);
+    word[32 - size..].to_vec()
+}
+
+fn left_pad(bytes: &[u8], size: usize) -> Vec<u8> {
+    let mut padded = vec![0u8; size - bytes.len()];
+    padded.extend_from_slice(bytes);
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMPTY_HASH: &str = "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+
+    #[test]
+    fn keccak_known_vectors() {
+        assert_eq!(hex::encode(keccak_text("")), EMPTY_HASH);
+        assert_eq!(
+            hex::encode(keccak_text("hello")),
+            "1c8aff950685c2ed4bc3174f3472287b56d9517b9c948127319a09a7a36deac8"
+        );
+        assert_eq!(
+            hex::encode(keccak_text("Transfer(address,address,uint256)")),
+            "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+        );
+        assert_eq!(hex::encode(keccak_hex("0x").unwrap()), EMPTY_HASH);
+        assert_eq!(keccak_hex("68656c6c6f").unwrap(), keccak_text("hello"));
+        assert!(keccak_hex("0xzz").is_err());
+    }
+
+    #[test]
+    fn selector_known_vectors() {
+        for (signature, expected) in [
+            ("transfer(address,uint256)", "a9059cbb"),
+            ("balanceOf(address)", "70a08231"),
+            ("approve(address,uint256)", "095ea7b3"),
+        ] {
+            let (canonical, selector) = selector(signature).unwrap();
+            assert_eq!(canonical, signature);
+            assert_eq!(hex::encode(selector), expected, "{signature}");
+        }
+    }
+
+    #[test]
+    fn selector_ignores_whitespace() {
+        let (canonical, selector) = selector(" transfer( address, uint256 )\n").unwrap();
+        assert_eq!(canonical, "transfer(address,uint256)");
+        assert_eq!(hex::encode(selector), "a9059cbb");
+    }
+
+    #[test]
+    fn selector_rejects_malformed_signature() {
+        for signature in ["", "transfer", "(address)", "transfer(address", "transfer)("] {
+            assert!(selector(signature).is_err(), "{signature}");
+        }
+    }
+
+    fn packed(input: &str) -> String {
+        hex::encode(encode_packed(input).unwrap())
+    }
+
+    fn word(tail: &str) -> String {
+        format!("{:0>64}", tail)
+    }
+
+    #[test]
+    fn encode_packed_matches_solidity_docs_example() {
+        // abi.encodePacked(int8(-1), bytes1(0x42), uint16(0x03), string("Hello, world!"))
+        assert_eq!(
+            packed(r#"int8:-1,bytes1:0x42,uint16:3,string:"Hello, world!""#),
+            "ff42000348656c6c6f2c20776f726c6421"
+        );
+    }
+
+    #[test]
+    fn encode_packed_uses_type_width() {
+        let address = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert_eq!(
+            packed(&format!("address:{address},uint8:5,bool:true,uint:0x0100")),
+            format!("{}0501{}", &address[2..].to_lowercase(), word("100"))
+        );
+        assert_eq!(packed("bytes:0xdeadbeef,string:abc"), "deadbeef616263");
+    }
+
+    #[test]
+    fn encode_packed_handles_int_sign() {
+        assert_eq!(packed("int16:-2"), "fffe");
+        assert_eq!(packed("int16:-32768,int16:32767"), "80007fff");
+        assert_eq!(packed("int24:-1"), "ffffff");
+        assert_eq!(packed("int:-1"), "f".repeat(64));
+        for input in ["int8:128", "int8:-129", "uint8:256", "int8:0x10", "uint8:-1"] {
+            assert!(encode_packed(input).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn encode_packed_pads_array_elements() {
+        // 数组元素和 abi.encode 一样补齐到 32 字节：整数和地址左补 0，bytesN 右补 0，负数符号扩展
+        assert_eq!(packed("uint8[]:[1,2]"), format!("{}{}", word("1"), word("2")));
+        assert_eq!(packed("bool[2]:[true,false]"), format!("{}{}", word("1"), word("0")));
+        assert_eq!(packed("int8[]:[-1]"), "f".repeat(64));
+        assert_eq!(packed("bytes2[]:[0xabcd]"), format!("abcd{}", "0".repeat(60)));
+        assert_eq!(
+            packed("address[]:[0x1111111111111111111111111111111111111111]"),
+            word(&"11".repeat(20))
+        );
+        assert_eq!(packed("uint8[]:[]"), "");
+    }
+
+    #[test]
+    fn encode_packed_splits_on_top_level_commas_only() {
+        assert_eq!(
+            packed(r#"string:"a,b",uint8[]:[1, 2],uint8:3"#),
+            format!("612c62{}{}03", word("1"), word("2"))
+        );
+        assert_eq!(packed(r#"string:"[x,y]""#), hex::encode("[x,y]"));
+    }
+
+    #[test]
+    fn encode_packed_rejects_unsupported_input() {
+        for input in [
+            "",
+            "uint8",
+            "uint7:1",
+            "uint264:1",
+            "foo:1",
+            "uint8[][]:[[1]]",
+            "string[]:[a]",
+            "bytes[]:[0x01]",
+            "(uint8,bool):(1,true)",
+            "uint8[3]:[1,2]",
+            "bytes2:0xab",
+            "bool:1",
+            "address:0x1234",
+            "uint8[]:1,2",
+        ] {
+            assert!(encode_packed(input).is_err(), "{input}");
+        }
+    }
+}
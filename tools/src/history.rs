@@ -0,0 +1,287 @@
+use arbi_common::explorer;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use arbi_common::table::Table;
+use ethers::providers::Middleware;
+use ethers::types::{Address, TxHash, U256};
+use ethers::utils::format_units;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::messages::{Msg, tf};
+
+// 区块浏览器每页返回的交易数
+const PAGE_SIZE: usize = 1000;
+
+// 没有 API key 时逐块扫描的最大区块数，Arbitrum 出块很快，范围太大会非常慢
+const MAX_SCAN_BLOCKS: u64 = 5000;
+
+// CSV 表头
+const CSV_HEADER: [&str; 9] = [
+    "block",
+    "timestamp",
+    "hash",
+    "direction",
+    "counterparty",
+    "value_wei",
+    "value_eth",
+    "gas_fee_wei",
+    "gas_fee_eth",
+];
+
+/// 交易相对于查询账户的方向
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    In,
+    Out,
+    /// 发给自己
+    SelfTransfer,
+}
+
+impl Direction {
+    /// 交易或转账相对于账户的方向
+    pub fn of(account: Address, from: Address, to: Option<Address>) -> Self {
+        match (from == account, to == Some(account)) {
+            (true, true) => Direction::SelfTransfer,
+            (true, false) => Direction::Out,
+            _ => Direction::In,
+        }
+    }
+
+    /// CSV 中使用的名称
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Direction::In => "in",
+            Direction::Out => "out",
+            Direction::SelfTransfer => "self",
+        }
+    }
+}
+
+/// 一笔历史交易
+pub struct HistoryEntry {
+    pub block: u64,
+    /// 区块时间（Unix 秒）
+    pub timestamp: u64,
+    pub hash: TxHash,
+    pub direction: Direction,
+    /// 对方地址；部署合约时为新合约地址
+    pub counterparty: Option<Address>,
+    pub value: U256,
+    /// 账户付出的 Gas 费，转入的交易由对方付费，记为 0
+    pub gas_fee: U256,
+}
+
+impl HistoryEntry {
+    fn new(
+        account: Address,
+        tx: (Address, Option<Address>),
+        block: u64,
+        timestamp: u64,
+        hash: TxHash,
+        value: U256,
+        gas_fee: U256,
+    ) -> Self {
+        let (from, to) = tx;
+        let direction = Direction::of(account, from, to);
+        let counterparty = match direction {
+            Direction::In => Some(from),
+            Direction::Out | Direction::SelfTransfer => to,
+        };
+        let gas_fee = if direction == Direction::In { U256::zero() } else { gas_fee };
+        Self {
+            block,
+            timestamp,
+            hash,
+            direction,
+            counterparty,
+            value,
+            gas_fee,
+        }
+    }
+
+    /// 从区块浏览器 `txlist` 的一条记录解析
+    fn from_explorer(account: Address, item: &Value) -> Option<Self> {
+        let field = |key: &str| item[key].as_str().filter(|s| !s.is_empty());
+        let number = |key: &str| field(key).and_then(|s| U256::from_dec_str(s).ok());
+
+        let from = Address::from_str(field("from")?).ok()?;
+        let to = field("to")
+            .or_else(|| field("contractAddress"))
+            .and_then(|s| Address::from_str(s).ok());
+        Some(Self::new(
+            account,
+            (from, to),
+            field("blockNumber")?.parse().ok()?,
+            field("timeStamp")?.parse().ok()?,
+            TxHash::from_str(field("hash")?).ok()?,
+            number("value")?,
+            number("gasUsed")? * number("gasPrice")?,
+        ))
+    }
+}
+
+/// 通过区块浏览器 API 分页读取账户的交易
+///
+/// 浏览器只允许翻到第 10000 条左右，所以每读满一页就把起始区块移到这一页最后一笔交易所在的
+/// 区块重新开始。同一区块的交易可能在两页里都出现，按交易哈希去重。
+pub async fn history_from_explorer(
+    api_key: &str,
+    account: Address,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<HistoryEntry>, Box<dyn Error>> {
+    let address = format!("{:?}", account);
+    let end_block = to_block.to_string();
+    let offset = PAGE_SIZE.to_string();
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    let (mut start_block, mut page) = (from_block, 1u64);
+
+    loop {
+        let start = start_block.to_string();
+        let page_str = page.to_string();
+        let result = explorer::get(
+            api_key,
+            &[
+                ("module", "account"),
+                ("action", "txlist"),
+                ("address", &address),
+                ("startblock", &start),
+                ("endblock", &end_block),
+                ("page", &page_str),
+                ("offset", &offset),
+                ("sort", "asc"),
+            ],
+        )
+        .await?;
+        let items = result.as_array().cloned().unwrap_or_default();
+
+        let mut last_block = start_block;
+        for item in &items {
+            let entry = HistoryEntry::from_explorer(account, item)
+                .ok_or_else(|| tf(Msg::HistoryBadRecord, &[item]))?;
+            last_block = entry.block;
+            if seen.insert(entry.hash) {
+                entries.push(entry);
+            }
+        }
+
+        if items.len() < PAGE_SIZE {
+            break;
+        }
+        // 一整页都在同一个区块里时只能继续翻页
+        if last_block > start_block {
+            start_block = last_block;
+            page = 1;
+        } else {
+            page += 1;
+        }
+    }
+
+    Ok(entries)
+}
+
+/// 没有 API key 时逐块读取交易并按地址过滤
+///
+/// 只能找到账户作为发送方或接收方的外部交易，合约内部转给它的 ETH 不会出现。
+async fn history_from_blocks(
+    provider: &RpcProvider,
+    account: Address,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<HistoryEntry>, Box<dyn Error>> {
+    if to_block.saturating_sub(from_block) >= MAX_SCAN_BLOCKS {
+        let reason = tf(Msg::HistoryRangeTooLarge, &[&MAX_SCAN_BLOCKS, &explorer::API_KEY_ENV]);
+        return Err(reason.into());
+    }
+
+    let mut entries = Vec::new();
+    for number in from_block..=to_block {
+        let Some(block) = provider.get_block_with_txs(number).await? else {
+            continue;
+        };
+        let timestamp = block.timestamp.as_u64();
+        for tx in block.transactions {
+            if tx.from != account && tx.to != Some(account) {
+                continue;
+            }
+            let receipt = provider.get_transaction_receipt(tx.hash).await?;
+            let to = tx.to.or_else(|| receipt.as_ref().and_then(|r| r.contract_address));
+            let gas_fee = receipt
+                .and_then(|r| Some(r.gas_used? * r.effective_gas_price?))
+                .unwrap_or_default();
+            entries.push(HistoryEntry::new(
+                account,
+                (tx.from, to),
+                number,
+                timestamp,
+                tx.hash,
+                tx.value,
+                gas_fee,
+            ));
+        }
+    }
+    Ok(entries)
+}
+
+/// 把交易整理成表格，金额同时给出 wei 和 ETH
+fn history_table(entries: &[HistoryEntry]) -> Result<Table, Box<dyn Error>> {
+    let mut table = Table::new(CSV_HEADER);
+    for entry in entries {
+        table.push([
+            entry.block.to_string(),
+            entry.timestamp.to_string(),
+            format!("{:?}", entry.hash),
+            entry.direction.as_str().to_string(),
+            entry.counterparty.map(|a| format!("{:?}", a)).unwrap_or_default(),
+            entry.value.to_string(),
+            format_units(entry.value, "ether")?,
+            entry.gas_fee.to_string(),
+            format_units(entry.gas_fee, "ether")?,
+        ]);
+    }
+    Ok(table)
+}
+
+/// 导出账户在区块范围内的交易历史到 CSV
+///
+/// 配置了 `ARBISCAN_API_KEY` 时用区块浏览器 API 分页读取，否则逐块扫描（范围不能超过
+/// 5000 个区块）。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 账户地址
+/// * `range` - 起始区块（含）和结束区块（含，`None` 表示最新区块）
+/// * `path` - CSV 输出路径
+/// * `bom` - 是否在开头加 UTF-8 BOM
+///
+/// # 返回
+/// * `Result<usize, Box<dyn Error>>` - 写入的交易数
+pub async fn export_history(
+    provider: &RpcProvider,
+    address: Address,
+    (from_block, to_block): (u64, Option<u64>),
+    path: &Path,
+    bom: bool,
+) -> Result<usize, Box<dyn Error>> {
+    let to_block = match to_block {
+        Some(block) => block,
+        None => provider.get_block_number().await?.as_u64(),
+    };
+
+    let mut entries = match explorer::api_key() {
+        Some(key) => history_from_explorer(&key, address, from_block, to_block).await?,
+        None => {
+            eprintln!("{}", style::warn(tf(Msg::HistoryScanning, &[&explorer::API_KEY_ENV])));
+            history_from_blocks(provider, address, from_block, to_block).await?
+        }
+    };
+    entries.sort_by_key(|entry| entry.block);
+
+    history_table(&entries)?.write_csv(path, bom)?;
+    Ok(entries.len())
+}
@@ -0,0 +1,205 @@
+//! 自动发现钱包持有的代币：扫描转入该地址的 ERC20 Transfer 日志，不需要事先配置代币列表
+
+use arbi_common::concurrency;
+use arbi_common::logs::{LogChunks, transfer_topic};
+use arbi_common::rpc::RpcProvider;
+use arbi_common::table::Table;
+use arbi_common::units;
+use ethers::abi::Tokenizable;
+use ethers::providers::Middleware;
+use ethers::types::{Address, BlockId, Filter, H256, TransactionRequest, U256};
+use ethers::utils::format_units;
+use std::error::Error;
+
+use crate::inspect::{self, TokenMeta};
+use crate::messages::{Msg, t, tf};
+
+/// 一次最多扫描的区块数（Arbitrum 约 0.25 秒一个区块，100 万个区块约 3 天）
+///
+/// 不过滤合约地址的 `eth_getLogs` 很重，范围太大时要发几百上千次请求。
+pub const MAX_DISCOVER_BLOCKS: u64 = 1_000_000;
+
+// 同时读取余额的请求数
+const BALANCE_CONCURRENCY: usize = 8;
+
+// ERC20 的 Transfer 有 3 个 topic；ERC721 的 tokenId 也是 indexed，有 4 个，不算代币
+const ERC20_TRANSFER_TOPICS: usize = 3;
+
+// CSV 表头
+const CSV_HEADER: [&str; 4] = ["token", "symbol", "balance_raw", "balance"];
+
+/// 一个余额不为 0 的代币
+pub struct TokenHolding {
+    pub token: Address,
+    /// 符号和小数位数，读不到时为 `None`
+    pub meta: Option<TokenMeta>,
+    /// 最小单位的余额
+    pub balance: U256,
+}
+
+impl TokenHolding {
+    fn symbol(&self) -> String {
+        match &self.meta {
+            Some(meta) => meta.symbol.clone(),
+            None => format!("{:?}", self.token),
+        }
+    }
+
+    fn amount(&self) -> String {
+        let decimals = self.meta.as_ref().map(|meta| meta.decimals).unwrap_or_default();
+        units::format_exact(self.balance, decimals).unwrap_or_else(|_| self.balance.to_string())
+    }
+}
+
+/// 自动发现的持仓
+pub struct Holdings {
+    pub address: Address,
+    /// 扫描的区块范围（含两端），余额也在结束区块读取
+    pub range: (u64, u64),
+    /// 原生 ETH 余额（wei）
+    pub native: U256,
+    /// 范围内收到过的代币合约数
+    pub discovered: usize,
+    /// 读不到 `balanceOf` 的合约数（发出了同样签名的 Transfer 事件，但不是 ERC20）
+    pub unreadable: usize,
+    /// 余额不为 0 的代币，按符号排序
+    pub tokens: Vec<TokenHolding>,
+}
+
+// 读取 `balanceOf(owner)`；合约不存在、不是 ERC20 或返回值无法解码时为 `None`
+async fn balance_of(
+    provider: &RpcProvider,
+    token: Address,
+    owner: Address,
+    block: BlockId,
+) -> Option<U256> {
+    let abi = ethers::abi::parse_abi(&["function balanceOf(address) view returns (uint256)"]);
+    let function = abi.ok()?.function("balanceOf").ok()?.clone();
+    let data = function.encode_input(&[owner.into_token()]).ok()?;
+    let tx = TransactionRequest::new().to(token).data(data).into();
+    let output = provider.call(&tx, Some(block)).await.ok()?;
+    function.decode_output(&output).ok()?.first()?.clone().into_uint()
+}
+
+/// 发现地址持有的代币并读取当前余额
+///
+/// 从 `from_block` 到最新区块，按 `chunk_size` 分段查询 `to` 为该地址的 Transfer 日志
+/// （节点报告范围太大时自动减半），收集发出日志的合约，再读取这些合约的 `balanceOf`，
+/// 去掉余额为 0 的。只能发现范围内收到过的代币：更早收到、之后没有再转入的代币不会出现。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 钱包地址
+/// * `from_block` - 起始区块（含），与最新区块的距离不能超过 [`MAX_DISCOVER_BLOCKS`]
+/// * `chunk_size` - 每次 `eth_getLogs` 查询的区块数
+///
+/// # 返回
+/// * `Result<Holdings, Box<dyn Error>>` - 原生余额和余额不为 0 的代币；范围太大时返回错误
+pub async fn discover_tokens(
+    provider: &RpcProvider,
+    address: Address,
+    from_block: u64,
+    chunk_size: u64,
+) -> Result<Holdings, Box<dyn Error>> {
+    let latest = provider.get_block_number().await?.as_u64();
+    if from_block > latest {
+        return Err(tf(Msg::GasUsageBadRange, &[&from_block, &latest]).into());
+    }
+    if latest - from_block >= MAX_DISCOVER_BLOCKS {
+        let reason = tf(Msg::HoldingsRangeTooLarge, &[&from_block, &latest, &MAX_DISCOVER_BLOCKS]);
+        return Err(reason.into());
+    }
+
+    let filter = Filter::new()
+        .topic0(transfer_topic())
+        .topic2(H256::from(address));
+    let mut chunks = LogChunks::new(provider, filter, from_block, latest, chunk_size);
+    let mut contracts: Vec<Address> = Vec::new();
+    while let Some(chunk) = chunks.next().await? {
+        for log in chunk.logs {
+            if log.topics.len() == ERC20_TRANSFER_TOPICS && !contracts.contains(&log.address) {
+                contracts.push(log.address);
+            }
+        }
+    }
+
+    let block = BlockId::Number(latest.into());
+    let native = provider.get_balance(address, Some(block)).await?;
+    // 读不到余额的合约返回 Err，不影响其他合约
+    let balances = concurrency::run_bounded(&contracts, BALANCE_CONCURRENCY, |&token| async move {
+        let balance = balance_of(provider, token, address, block).await;
+        balance.map(|balance| (token, balance)).ok_or(token)
+    })
+    .await;
+
+    let mut tokens = Vec::new();
+    let mut unreadable = 0;
+    for balance in balances {
+        match balance {
+            Ok((token, balance)) if !balance.is_zero() => tokens.push(TokenHolding {
+                token,
+                meta: inspect::token_meta(provider, token).await,
+                balance,
+            }),
+            Ok(_) => {}
+            Err(_) => unreadable += 1,
+        }
+    }
+    tokens.sort_by_key(|holding| (holding.symbol().to_lowercase(), holding.token));
+
+    Ok(Holdings {
+        address,
+        range: (from_block, latest),
+        native,
+        discovered: contracts.len(),
+        unreadable,
+        tokens,
+    })
+}
+
+impl Holdings {
+    /// 打印原生余额和发现的代币
+    pub fn print(&self) -> Result<(), Box<dyn Error>> {
+        println!("{}", tf(Msg::HoldingsTitle, &[&format!("{:?}", self.address)]));
+        println!("{}", tf(Msg::HoldingsRange, &[&self.range.0, &self.range.1]));
+        println!("{}", tf(Msg::HoldingsNative, &[&format_units(self.native, "ether")?]));
+        println!();
+        if self.tokens.is_empty() {
+            println!("{}", tf(Msg::HoldingsNone, &[&self.discovered]));
+        } else {
+            println!("{}", tf(Msg::HoldingsFound, &[&self.discovered, &self.tokens.len()]));
+            println!(
+                "  {:<10}  {:>28}  {}",
+                t(Msg::TokenHistoryColToken),
+                t(Msg::HoldingsColBalance),
+                t(Msg::HoldingsColContract)
+            );
+            for holding in &self.tokens {
+                println!(
+                    "  {:<10}  {:>28}  {:?}",
+                    holding.symbol(),
+                    holding.amount(),
+                    holding.token
+                );
+            }
+        }
+        if self.unreadable > 0 {
+            println!("{}", tf(Msg::HoldingsUnreadable, &[&self.unreadable]));
+        }
+        Ok(())
+    }
+
+    /// 余额不为 0 的代币的表格，余额同时给出最小单位的整数和按小数位数格式化后的值
+    pub fn to_table(&self) -> Table {
+        let mut table = Table::new(CSV_HEADER);
+        for holding in &self.tokens {
+            table.push([
+                format!("{:?}", holding.token),
+                holding.symbol(),
+                holding.balance.to_string(),
+                holding.amount(),
+            ]);
+        }
+        table
+    }
+}
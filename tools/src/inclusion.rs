@@ -0,0 +1,313 @@
+use arbi_common::retry::{self, Backoff};
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::abi::Abi;
+use ethers::prelude::*;
+use ethers::types::{Address, H256, TxHash};
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::messages::{Msg, t, tf};
+
+// NodeInterface 虚拟合约地址，只能通过 eth_call 调用
+const NODE_INTERFACE_ADDRESS: &str = "0x00000000000000000000000000000000000000C8";
+
+const NODE_INTERFACE_ABI: &str = r#"[
+    {
+        "inputs": [{"name": "blockNum", "type": "uint64"}],
+        "name": "findBatchContainingBlock",
+        "outputs": [{"name": "batch", "type": "uint64"}],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [{"name": "blockHash", "type": "bytes32"}],
+        "name": "getL1Confirmations",
+        "outputs": [{"name": "confirmations", "type": "uint64"}],
+        "stateMutability": "view",
+        "type": "function"
+    }
+]"#;
+
+/// 交易所在 L2 区块的批次状态
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchStatus {
+    /// 区块还没有被打包进任何已发布到 L1 的批次
+    AwaitingBatch,
+    /// 已发布到 L1 的批次号和批次所在 L1 区块的确认数
+    Posted { batch: u64, confirmations: u64 },
+}
+
+impl BatchStatus {
+    /// 是否已经达到目标确认数
+    ///
+    /// # 参数
+    /// * `target` - 目标 L1 确认数
+    ///
+    /// # 返回
+    /// * `bool` - 已发布且确认数不少于目标时为 `true`
+    pub fn reached(&self, target: u64) -> bool {
+        matches!(self, BatchStatus::Posted { confirmations, .. } if *confirmations >= target)
+    }
+}
+
+/// 交易所在的 L2 区块
+#[derive(Clone, Copy, Debug)]
+pub struct L2Block {
+    pub number: u64,
+    pub hash: H256,
+}
+
+/// 找到交易所在的 L2 区块
+///
+/// # 参数
+/// * `provider` - L2 Provider
+/// * `tx_hash` - 交易哈希
+///
+/// # 返回
+/// * `Result<L2Block, Box<dyn Error>>` - 区块号和区块哈希；交易不存在或还没上链时返回错误
+pub async fn tx_block(provider: &RpcProvider, tx_hash: TxHash) -> Result<L2Block, Box<dyn Error>> {
+    let receipt = provider
+        .get_transaction_receipt(tx_hash)
+        .await?
+        .ok_or_else(|| tf(Msg::InclusionTxNotFound, &[&format!("{:?}", tx_hash)]))?;
+    match (receipt.block_number, receipt.block_hash) {
+        (Some(number), Some(hash)) => Ok(L2Block {
+            number: number.as_u64(),
+            hash,
+        }),
+        _ => Err(tf(Msg::InclusionTxNotFound, &[&format!("{:?}", tx_hash)]).into()),
+    }
+}
+
+/// 查询区块所在批次和 L1 确认数
+///
+/// 区块还没有进入已发布的批次时，`findBatchContainingBlock` 会 revert，这里返回
+/// `AwaitingBatch` 而不是错误。
+///
+/// # 参数
+/// * `provider` - L2 Provider
+/// * `block` - L2 区块
+///
+/// # 返回
+/// * `Result<BatchStatus, Box<dyn Error>>` - 批次状态
+pub async fn batch_status(
+    provider: &RpcProvider,
+    block: L2Block,
+) -> Result<BatchStatus, Box<dyn Error>> {
+    let node_interface = Contract::new(
+        Address::from_str(NODE_INTERFACE_ADDRESS)?,
+        serde_json::from_str::<Abi>(NODE_INTERFACE_ABI)?,
+        Arc::new(provider.clone()),
+    );
+    let batch = match node_interface
+        .method::<_, u64>("findBatchContainingBlock", block.number)?
+        .call()
+        .await
+    {
+        Ok(batch) => batch,
+        Err(e) if e.is_revert() => return Ok(BatchStatus::AwaitingBatch),
+        Err(e) => return Err(e.into()),
+    };
+    let confirmations: u64 = node_interface
+        .method("getL1Confirmations", block.hash)?
+        .call()
+        .await?;
+    Ok(BatchStatus::Posted {
+        batch,
+        confirmations,
+    })
+}
+
+/// 打印批次状态
+pub fn print_status(block: &L2Block, status: &BatchStatus, target: u64) {
+    println!("{}", tf(Msg::InclusionBlock, &[&block.number, &format!("{:?}", block.hash)]));
+    match status {
+        BatchStatus::AwaitingBatch => println!("{}", style::warn(t(Msg::InclusionAwaitingBatch))),
+        BatchStatus::Posted {
+            batch,
+            confirmations,
+        } => {
+            println!("{}", style::ok(tf(Msg::InclusionPosted, &[batch])));
+            let line = tf(Msg::InclusionConfirmations, &[confirmations, &target]);
+            if status.reached(target) {
+                println!("{}", style::ok(line));
+            } else {
+                println!("{}", line);
+            }
+        }
+    }
+}
+
+/// 查询交易所在批次的 L1 状态，`wait` 时轮询直到确认数达到 `target`
+///
+/// 每次查询失败按退避策略重试；等待期间 Ctrl-C 会停止等待并返回最后一次的状态。
+///
+/// # 参数
+/// * `provider` - L2 Provider
+/// * `tx_hash` - 交易哈希
+/// * `target` - 目标 L1 确认数
+/// * `wait` - 是否等待到达目标
+/// * `interval` - 轮询间隔
+///
+/// # 返回
+/// * `Result<BatchStatus, Box<dyn Error>>` - 最后一次查询到的状态
+pub async fn l1_inclusion(
+    provider: &RpcProvider,
+    tx_hash: TxHash,
+    target: u64,
+    wait: bool,
+    interval: Duration,
+) -> Result<BatchStatus, Box<dyn Error>> {
+    let block = tx_block(provider, tx_hash).await?;
+    let mut last = None;
+    loop {
+        let status =
+            retry::with_backoff(Backoff::default(), || batch_status(provider, block)).await?;
+        if last != Some(status) {
+            print_status(&block, &status, target);
+            last = Some(status);
+        }
+        if !wait || status.reached(target) {
+            return Ok(status);
+        }
+
+        println!("{}", tf(Msg::InclusionWaiting, &[&interval.as_secs()]));
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(status),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use ethers::utils::{hex, keccak256};
+    use serde_json::{Value, json};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    const BLOCK: u64 = 0xa4b0f1;
+
+    fn selector(signature: &str) -> String {
+        format!("0x{}", hex::encode(&keccak256(signature)[..4]))
+    }
+
+    fn receipt() -> Value {
+        json!({
+            "transactionHash": format!("{:?}", TxHash::repeat_byte(0xab)),
+            "transactionIndex": "0x1",
+            "blockNumber": format!("{:#x}", BLOCK),
+            "blockHash": format!("{:?}", H256::repeat_byte(0x10)),
+            "from": format!("{:?}", Address::repeat_byte(0x11)),
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "status": "0x1",
+            "logs": [],
+        })
+    }
+
+    // 前 `unbatched` 次 findBatchContainingBlock 会 revert；之后批次 0x1234，
+    // 每次查询确认数增加 10
+    fn node(unbatched: u64) -> MockRpc {
+        let finds = AtomicU64::new(0);
+        let confirmations = AtomicU64::new(0);
+        MockRpc::start(move |method, params| {
+            if method == "eth_getTransactionReceipt" {
+                return match params[0].as_str() {
+                    Some(hash) if hash == format!("{:?}", TxHash::repeat_byte(0xab)) => {
+                        Reply::Result(receipt())
+                    }
+                    _ => Reply::Result(Value::Null),
+                };
+            }
+            let call = &params[0];
+            let input = call["input"].as_str().or(call["data"].as_str()).unwrap_or("");
+            if input.starts_with(&selector("findBatchContainingBlock(uint64)")) {
+                assert_eq!(input[10..], format!("{:064x}", BLOCK));
+                if finds.fetch_add(1, Ordering::SeqCst) < unbatched {
+                    return Reply::Error("execution reverted: block not yet in batch".into());
+                }
+                Reply::Result(json!(format!("0x{:064x}", 0x1234)))
+            } else if input.starts_with(&selector("getL1Confirmations(bytes32)")) {
+                assert_eq!(input[10..], format!("{:x}", H256::repeat_byte(0x10)));
+                let count = confirmations.fetch_add(10, Ordering::SeqCst) + 10;
+                Reply::Result(json!(format!("0x{:064x}", count)))
+            } else {
+                Reply::Error(format!("unexpected {}", method))
+            }
+        })
+    }
+
+    #[test]
+    fn reached_needs_a_posted_batch_with_enough_confirmations() {
+        let posted = |confirmations| BatchStatus::Posted {
+            batch: 7,
+            confirmations,
+        };
+        assert!(!BatchStatus::AwaitingBatch.reached(0));
+        assert!(!posted(63).reached(64));
+        assert!(posted(64).reached(64));
+        assert!(posted(0).reached(0));
+    }
+
+    #[tokio::test]
+    async fn finds_the_block_of_a_mined_transaction() {
+        let rpc = node(0);
+        let block = tx_block(&rpc.provider(), TxHash::repeat_byte(0xab)).await.unwrap();
+        assert_eq!(block.number, BLOCK);
+        assert_eq!(block.hash, H256::repeat_byte(0x10));
+
+        let missing = TxHash::repeat_byte(0xcd);
+        let error = tx_block(&rpc.provider(), missing).await.unwrap_err();
+        let expected = tf(Msg::InclusionTxNotFound, &[&format!("{:?}", missing)]);
+        assert_eq!(error.to_string(), expected);
+    }
+
+    #[tokio::test]
+    async fn revert_means_the_block_awaits_a_batch() {
+        let rpc = node(1);
+        let provider = rpc.provider();
+        let block = tx_block(&provider, TxHash::repeat_byte(0xab)).await.unwrap();
+        assert_eq!(batch_status(&provider, block).await.unwrap(), BatchStatus::AwaitingBatch);
+        assert_eq!(
+            batch_status(&provider, block).await.unwrap(),
+            BatchStatus::Posted {
+                batch: 0x1234,
+                confirmations: 10,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn without_wait_returns_after_one_query() {
+        let rpc = node(1);
+        let tx = TxHash::repeat_byte(0xab);
+        let interval = Duration::from_millis(10);
+        let status = l1_inclusion(&rpc.provider(), tx, 30, false, interval).await.unwrap();
+        assert_eq!(status, BatchStatus::AwaitingBatch);
+        assert_eq!(rpc.count("eth_call"), 1);
+    }
+
+    #[tokio::test]
+    async fn wait_polls_until_the_target_is_reached() {
+        // 第一轮还没进批次，之后确认数 10、20、30
+        let rpc = node(1);
+        let tx = TxHash::repeat_byte(0xab);
+        let interval = Duration::from_millis(10);
+        let status = l1_inclusion(&rpc.provider(), tx, 30, true, interval).await.unwrap();
+        assert_eq!(
+            status,
+            BatchStatus::Posted {
+                batch: 0x1234,
+                confirmations: 30,
+            }
+        );
+        // 1 次 revert，加上 3 轮各两次调用
+        assert_eq!(rpc.count("eth_call"), 7);
+    }
+}
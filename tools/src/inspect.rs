@@ -0,0 +1,681 @@
+use arbi_common::explorer;
+use arbi_common::logs::transfer_topic;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::abi::{Abi, Function, LogParam, RawLog};
+use ethers::providers::Middleware;
+use ethers::types::{
+    Address, Block, BlockId, Log, Transaction, TransactionReceipt, TransactionRequest, TxHash,
+    I256, U256,
+};
+use ethers::utils::{format_units, hex, to_checksum};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use tracing::debug;
+
+use crate::calldata::{self, format_token};
+use crate::messages::{Msg, t, tf};
+
+// 内置的 ERC20 ABI，没有其他 ABI 时也能解码常见的代币调用和事件
+const ERC20_ABI: [&str; 5] = [
+    "function transfer(address to, uint256 value) returns (bool)",
+    "function approve(address spender, uint256 value) returns (bool)",
+    "function transferFrom(address from, address to, uint256 value) returns (bool)",
+    "event Transfer(address indexed from, address indexed to, uint256 value)",
+    "event Approval(address indexed owner, address indexed spender, uint256 value)",
+];
+
+// 内置的 ERC721 ABI；Transfer / Approval 事件的 topic 和 ERC20 相同，区别在 tokenId 是 indexed
+const ERC721_ABI: [&str; 6] = [
+    "function safeTransferFrom(address from, address to, uint256 tokenId)",
+    "function safeTransferFrom(address from, address to, uint256 tokenId, bytes data)",
+    "function setApprovalForAll(address operator, bool approved)",
+    "event Transfer(address indexed from, address indexed to, uint256 indexed tokenId)",
+    "event Approval(address indexed owner, address indexed approved, uint256 indexed tokenId)",
+    "event ApprovalForAll(address indexed owner, address indexed operator, bool approved)",
+];
+
+// 读取代币显示信息用的 ABI
+const TOKEN_META_ABI: [&str; 2] = [
+    "function symbol() view returns (string)",
+    "function decimals() view returns (uint8)",
+];
+
+// ERC20 的 Transfer 有 3 个 topic（value 在 data 里），ERC721 有 4 个（tokenId 也是 indexed）
+const ERC20_TRANSFER_TOPICS: usize = 3;
+const ERC721_TRANSFER_TOPICS: usize = 4;
+
+/// 按合约地址查找 ABI：先用区块浏览器上按地址取到的，再试 `--abi` 文件和内置的 ERC20 / ERC721
+pub struct AbiBook {
+    by_address: HashMap<Address, Abi>,
+    shared: Vec<Abi>,
+}
+
+impl AbiBook {
+    /// 读取 `--abi` 文件，加上内置 ABI
+    ///
+    /// # 参数
+    /// * `files` - JSON ABI 文件路径，对所有合约都会尝试
+    ///
+    /// # 返回
+    /// * `Result<AbiBook, Box<dyn Error>>` - ABI 集合
+    pub fn new(files: &[String]) -> Result<Self, Box<dyn Error>> {
+        let mut shared = Vec::new();
+        for path in files {
+            shared.push(serde_json::from_str(&std::fs::read_to_string(path)?)?);
+        }
+        shared.push(ethers::abi::parse_abi(&ERC20_ABI)?);
+        shared.push(ethers::abi::parse_abi(&ERC721_ABI)?);
+        Ok(Self {
+            by_address: HashMap::new(),
+            shared,
+        })
+    }
+
+    /// 从区块浏览器获取已验证合约的 ABI（尽力而为）
+    ///
+    /// 没有配置 API key 时什么都不做；合约未验证或请求失败时跳过，这些合约退回到共享 ABI。
+    ///
+    /// # 参数
+    /// * `addresses` - 合约地址
+    ///
+    /// # 返回
+    /// * `usize` - 取到 ABI 的合约数
+    pub async fn fetch_verified(&mut self, addresses: &[Address]) -> usize {
+        let Some(api_key) = explorer::api_key() else {
+            return 0;
+        };
+        for address in addresses {
+            if self.by_address.contains_key(address) {
+                continue;
+            }
+            let param = format!("{:?}", address);
+            let params = [("module", "contract"), ("action", "getabi"), ("address", &param)];
+            let abi = match explorer::get(&api_key, &params).await {
+                Ok(result) => result.as_str().and_then(|text| serde_json::from_str(text).ok()),
+                Err(e) => {
+                    debug!(address = %param, error = %e, "explorer ABI unavailable");
+                    None
+                }
+            };
+            if let Some(abi) = abi {
+                self.by_address.insert(*address, abi);
+            }
+        }
+        self.by_address.len()
+    }
+
+    fn candidates(&self, address: Address) -> impl Iterator<Item = &Abi> {
+        self.by_address.get(&address).into_iter().chain(&self.shared)
+    }
+
+    /// 合约可能的函数，按优先级排列（`calldata::decode` 取第一个匹配的）
+    pub fn functions(&self, address: Address) -> Vec<Function> {
+        self.candidates(address)
+            .flat_map(|abi| abi.functions().cloned())
+            .collect()
+    }
+
+    /// 解码一条日志；topic 数量和 indexed 参数对不上的事件会被跳过
+    ///
+    /// # 参数
+    /// * `log` - 日志
+    ///
+    /// # 返回
+    /// * `Option<(String, Vec<LogParam>)>` - 事件名和参数；没有匹配的事件时为 `None`
+    pub fn decode_log(&self, log: &Log) -> Option<(String, Vec<LogParam>)> {
+        let topic0 = *log.topics.first()?;
+        let raw = RawLog {
+            topics: log.topics.clone(),
+            data: log.data.to_vec(),
+        };
+        self.candidates(log.address)
+            .flat_map(|abi| abi.events())
+            .filter(|event| !event.anonymous && event.signature() == topic0)
+            .find_map(|event| {
+                let parsed = event.parse_log(raw.clone()).ok()?;
+                Some((event.name.clone(), parsed.params))
+            })
+    }
+}
+
+/// 价值流动涉及的资产
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Asset {
+    Eth,
+    Erc20(Address),
+    /// ERC721 按 NFT 个数计
+    Erc721(Address),
+}
+
+impl Asset {
+    fn token(self) -> Option<Address> {
+        match self {
+            Asset::Eth => None,
+            Asset::Erc20(token) | Asset::Erc721(token) => Some(token),
+        }
+    }
+}
+
+/// 一个地址在一种资产上的净变化
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Flow {
+    pub asset: Asset,
+    pub account: Address,
+    /// 正数为流入，负数为流出
+    pub net: I256,
+}
+
+fn to_signed(value: U256) -> I256 {
+    I256::try_from(value).unwrap_or(I256::MAX)
+}
+
+/// 汇总交易的价值流动，按地址轧差
+///
+/// ETH 只统计交易本身的 `value`（合约内部转出的 ETH 需要 trace，这里不包含）；
+/// 代币按 `Transfer` 事件统计：3 个 topic 的是 ERC20，按 data 里的数量计；
+/// 4 个 topic 的是 ERC721，每个 tokenId 计 1。同一个地址转进又转出的部分互相抵消，
+/// 净变化为 0 的地址不出现在结果里。铸造和销毁表现为零地址的流出 / 流入。
+///
+/// # 参数
+/// * `from` - 发送方
+/// * `to` - 接收方，部署合约时为新合约地址
+/// * `value` - 交易的 ETH 金额；交易失败时应传 0（失败交易的金额和日志都会回滚）
+/// * `logs` - 收据里的日志
+///
+/// # 返回
+/// * `Vec<Flow>` - 按资产、地址排序的净变化，ETH 在最前
+pub fn net_flows(from: Address, to: Option<Address>, value: U256, logs: &[Log]) -> Vec<Flow> {
+    let mut totals: BTreeMap<(Asset, Address), I256> = BTreeMap::new();
+    let mut add = |asset: Asset, sender: Address, receiver: Address, amount: I256| {
+        let entry = totals.entry((asset, sender)).or_insert(I256::zero());
+        *entry = entry.saturating_sub(amount);
+        let entry = totals.entry((asset, receiver)).or_insert(I256::zero());
+        *entry = entry.saturating_add(amount);
+    };
+
+    if let Some(to) = to
+        && !value.is_zero()
+    {
+        add(Asset::Eth, from, to, to_signed(value));
+    }
+    let transfer = transfer_topic();
+    for log in logs.iter().filter(|log| log.topics.first() == Some(&transfer)) {
+        let (Some(sender), Some(receiver)) = (log.topics.get(1), log.topics.get(2)) else {
+            continue;
+        };
+        let (sender, receiver) = (Address::from(*sender), Address::from(*receiver));
+        match log.topics.len() {
+            ERC20_TRANSFER_TOPICS if log.data.len() == 32 => {
+                let amount = to_signed(U256::from_big_endian(&log.data));
+                add(Asset::Erc20(log.address), sender, receiver, amount);
+            }
+            ERC721_TRANSFER_TOPICS => {
+                add(Asset::Erc721(log.address), sender, receiver, I256::one());
+            }
+            _ => {}
+        }
+    }
+
+    totals
+        .into_iter()
+        .filter(|(_, net)| !net.is_zero())
+        .map(|((asset, account), net)| Flow {
+            asset,
+            account,
+            net,
+        })
+        .collect()
+}
+
+/// 代币的显示信息
+pub struct TokenMeta {
+    pub symbol: String,
+    pub decimals: u32,
+}
+
+/// 读取代币的符号和小数位数（尽力而为），失败时返回 `None`，按原始整数显示
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `token` - 代币合约地址
+///
+/// # 返回
+/// * `Option<TokenMeta>` - 符号和小数位数
+pub async fn token_meta(provider: &RpcProvider, token: Address) -> Option<TokenMeta> {
+    let abi = ethers::abi::parse_abi(&TOKEN_META_ABI).ok()?;
+    let call = |name: &str| {
+        let data = abi.function(name).ok()?.encode_input(&[]).ok()?;
+        Some(TransactionRequest::new().to(token).data(data).into())
+    };
+    let symbol = provider.call(&call("symbol")?, None).await.ok()?;
+    let symbol = abi.function("symbol").ok()?.decode_output(&symbol).ok()?;
+    let symbol = symbol.first()?.clone().into_string()?;
+    let decimals = match provider.call(&call("decimals")?, None).await {
+        Ok(output) => {
+            let tokens = abi.function("decimals").ok()?.decode_output(&output).ok()?;
+            tokens.first()?.clone().into_uint()?.as_u32()
+        }
+        // ERC721 没有 decimals
+        Err(_) => 0,
+    };
+    Some(TokenMeta { symbol, decimals })
+}
+
+/// 交易、收据和所在区块
+pub struct Inspection {
+    pub tx: Transaction,
+    pub receipt: TransactionReceipt,
+    pub block: Option<Block<TxHash>>,
+}
+
+impl Inspection {
+    /// 读取交易、收据和区块
+    ///
+    /// # 参数
+    /// * `provider` - Provider 引用
+    /// * `hash` - 交易哈希
+    ///
+    /// # 返回
+    /// * `Result<Inspection, Box<dyn Error>>` - 交易不存在或还没上链时返回错误
+    pub async fn fetch(provider: &RpcProvider, hash: TxHash) -> Result<Self, Box<dyn Error>> {
+        let not_found = || tf(Msg::InspectNotMined, &[&format!("{:?}", hash)]);
+        let tx = provider.get_transaction(hash).await?.ok_or_else(not_found)?;
+        let receipt = provider
+            .get_transaction_receipt(hash)
+            .await?
+            .ok_or_else(not_found)?;
+        let block = match receipt.block_number {
+            Some(number) => provider.get_block(BlockId::Number(number.into())).await?,
+            None => None,
+        };
+        Ok(Self { tx, receipt, block })
+    }
+
+    /// 交易是否执行成功
+    pub fn succeeded(&self) -> bool {
+        self.receipt.status.is_none_or(|status| !status.is_zero())
+    }
+
+    /// 交易涉及的合约：接收方和所有发出日志的合约（去重，按出现顺序）
+    pub fn contracts(&self) -> Vec<Address> {
+        let mut contracts: Vec<Address> = Vec::new();
+        let emitters = self.receipt.logs.iter().map(|log| log.address);
+        for address in self.tx.to.into_iter().chain(emitters) {
+            if !contracts.contains(&address) {
+                contracts.push(address);
+            }
+        }
+        contracts
+    }
+
+    /// 这笔交易的价值流动
+    pub fn flows(&self) -> Vec<Flow> {
+        let to = self.tx.to.or(self.receipt.contract_address);
+        let value = match self.succeeded() {
+            true => self.tx.value,
+            false => U256::zero(),
+        };
+        net_flows(self.tx.from, to, value, &self.receipt.logs)
+    }
+}
+
+fn hex_u64(value: Option<&serde_json::Value>) -> Option<u64> {
+    let value = value?.as_str()?;
+    u64::from_str_radix(value.trim_start_matches("0x"), 16).ok()
+}
+
+fn gwei(value: U256) -> Result<String, Box<dyn Error>> {
+    Ok(format!("{} Gwei", format_units(value, "gwei")?))
+}
+
+/// 打印完整报告
+///
+/// # 参数
+/// * `inspection` - 交易、收据和区块
+/// * `book` - ABI 集合
+/// * `tokens` - 代币的显示信息，没有的按原始整数显示
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+pub fn print_report(
+    inspection: &Inspection,
+    book: &AbiBook,
+    tokens: &HashMap<Address, TokenMeta>,
+) -> Result<(), Box<dyn Error>> {
+    let Inspection { tx, receipt, block } = inspection;
+
+    println!("{}", tf(Msg::InspectTitle, &[&format!("{:?}", tx.hash)]));
+    let status = match inspection.succeeded() {
+        true => style::ok(t(Msg::InspectSuccess)),
+        false => style::err(t(Msg::InspectReverted)),
+    };
+    println!("{}", tf(Msg::InspectStatus, &[&status]));
+    println!("{}", tf(Msg::InspectFrom, &[&style::addr(to_checksum(&tx.from, None))]));
+    match (tx.to, receipt.contract_address) {
+        (Some(to), _) => {
+            println!("{}", tf(Msg::InspectTo, &[&style::addr(to_checksum(&to, None))]))
+        }
+        (None, Some(created)) => {
+            let created = style::addr(to_checksum(&created, None));
+            println!("{}", tf(Msg::InspectCreated, &[&created]))
+        }
+        (None, None) => println!("{}", tf(Msg::InspectTo, &[&t(Msg::ContractCreation)])),
+    }
+    println!("{}", tf(Msg::InspectValue, &[&format_units(tx.value, "ether")?]));
+    println!("{}", tf(Msg::InspectNonce, &[&tx.nonce]));
+
+    // 区块
+    println!("\n{}", t(Msg::InspectBlockTitle));
+    let index = receipt.transaction_index.as_u64();
+    match block {
+        Some(block) => {
+            let number = block.number.unwrap_or_default();
+            let count = block.transactions.len();
+            println!("{}", tf(Msg::InspectBlock, &[&number, &index, &count]));
+            println!("{}", tf(Msg::InspectTimestamp, &[&block.timestamp]));
+            if let Some(base_fee) = block.base_fee_per_gas {
+                println!("{}", tf(Msg::InspectBaseFee, &[&gwei(base_fee)?]));
+            }
+            if let Some(l1_block) = hex_u64(block.other.get("l1BlockNumber")) {
+                println!("{}", tf(Msg::InspectL1Block, &[&l1_block]));
+            }
+        }
+        None => {
+            let number = receipt.block_number.unwrap_or_default();
+            println!("{}", tf(Msg::InspectBlock, &[&number, &index, &"?"]));
+        }
+    }
+
+    // Gas
+    println!("\n{}", t(Msg::InspectGasTitle));
+    let gas_used = receipt.gas_used.unwrap_or_default();
+    let price = receipt.effective_gas_price.or(tx.gas_price).unwrap_or_default();
+    println!("{}", tf(Msg::InspectGasUsed, &[&gas_used, &tx.gas]));
+    println!("{}", tf(Msg::InspectEffectivePrice, &[&gwei(price)?]));
+    if let (Some(max_fee), Some(priority)) = (tx.max_fee_per_gas, tx.max_priority_fee_per_gas) {
+        println!("{}", tf(Msg::InspectMaxFees, &[&gwei(max_fee)?, &gwei(priority)?]));
+    }
+    if let Some(l1_gas) = hex_u64(receipt.other.get("gasUsedForL1")) {
+        let l2_gas = gas_used.saturating_sub(l1_gas.into());
+        println!("{}", tf(Msg::InspectGasSplit, &[&l2_gas, &l1_gas]));
+    }
+    println!("{}", tf(Msg::InspectTotalCost, &[&format_units(gas_used * price, "ether")?]));
+
+    // 调用
+    println!("\n{}", t(Msg::InspectCallTitle));
+    match (tx.to, tx.input.len()) {
+        (_, 0) => println!("  {}", t(Msg::TraceNoCalldata)),
+        (None, len) => println!("  {}", tf(Msg::InspectInitCode, &[&len])),
+        (Some(to), _) => {
+            let functions = book.functions(to);
+            match calldata::decode(&tx.input, &functions) {
+                Ok(decoded) => decoded.print(),
+                // 匹配到的函数解码失败（ABI 和实际合约不一致）时显示原始数据
+                Err(e) => {
+                    println!("  {}", style::warn(e));
+                    println!("  {}", tx.input);
+                }
+            }
+        }
+    }
+
+    // 事件（按合约分组）
+    println!("\n{}", tf(Msg::InspectLogsTitle, &[&receipt.logs.len()]));
+    let mut groups: Vec<(Address, Vec<&Log>)> = Vec::new();
+    for log in &receipt.logs {
+        match groups.iter_mut().find(|(address, _)| *address == log.address) {
+            Some((_, logs)) => logs.push(log),
+            None => groups.push((log.address, vec![log])),
+        }
+    }
+    for (address, logs) in groups {
+        let label = tokens
+            .get(&address)
+            .map(|meta| format!(" ({})", meta.symbol))
+            .unwrap_or_default();
+        println!("  {}{}", style::addr(to_checksum(&address, None)), label);
+        for log in logs {
+            print_log(log, book);
+        }
+    }
+
+    // 价值流动
+    let flows = inspection.flows();
+    println!("\n{}", t(Msg::InspectFlowsTitle));
+    if flows.is_empty() {
+        println!("  {}", t(Msg::InspectNoFlows));
+    }
+    for flow in &flows {
+        let amount = format_flow(flow, tokens)?;
+        println!("  {:<44} {}", to_checksum(&flow.account, None), amount);
+    }
+    if !inspection.succeeded() {
+        println!("  {}", style::warn(t(Msg::InspectRevertedFlows)));
+    }
+    Ok(())
+}
+
+fn print_log(log: &Log, book: &AbiBook) {
+    let index = log.log_index.map(|index| index.to_string()).unwrap_or_default();
+    match book.decode_log(log) {
+        Some((name, params)) => {
+            let params: Vec<String> = params
+                .iter()
+                .map(|param| format!("{}={}", param.name, format_token(&param.value)))
+                .collect();
+            println!("    [{}] {}({})", index, name, params.join(", "));
+        }
+        None => {
+            let topics: Vec<String> =
+                log.topics.iter().map(|topic| format!("{:?}", topic)).collect();
+            println!("    [{}] {}", index, t(Msg::InspectUnknownEvent));
+            println!("        topics: {}", topics.join(", "));
+            println!("        data:   {}", hex::encode_prefixed(&log.data));
+        }
+    }
+}
+
+fn format_flow(
+    flow: &Flow,
+    tokens: &HashMap<Address, TokenMeta>,
+) -> Result<String, Box<dyn Error>> {
+    let sign = if flow.net.is_negative() { "-" } else { "+" };
+    let magnitude = flow.net.unsigned_abs();
+    let text = match flow.asset {
+        Asset::Eth => format!("{} ETH", format_units(magnitude, "ether")?),
+        Asset::Erc721(token) => {
+            let unit = tokens.get(&token).map_or("NFT", |meta| meta.symbol.as_str());
+            format!("{} {}", magnitude, unit)
+        }
+        Asset::Erc20(token) => match tokens.get(&token) {
+            Some(meta) => format!("{} {}", format_units(magnitude, meta.decimals)?, meta.symbol),
+            None => format!("{} ({})", magnitude, to_checksum(&token, None)),
+        },
+    };
+    Ok(format!("{}{}", sign, text))
+}
+
+/// 价值流动里出现的代币合约
+pub fn flow_tokens(flows: &[Flow]) -> Vec<Address> {
+    let mut tokens: Vec<Address> = Vec::new();
+    for token in flows.iter().filter_map(|flow| flow.asset.token()) {
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{H256, U64};
+
+    const USER: u64 = 0x01;
+    const ROUTER: u64 = 0x02;
+    const POOL: u64 = 0x03;
+    const USDC: u64 = 0x10;
+    const WETH: u64 = 0x11;
+    const NFT: u64 = 0x12;
+
+    fn account(n: u64) -> Address {
+        Address::from_low_u64_be(n)
+    }
+
+    fn log(token: u64, topics: Vec<H256>, data: Vec<u8>) -> Log {
+        Log {
+            address: account(token),
+            topics,
+            data: data.into(),
+            ..Default::default()
+        }
+    }
+
+    fn erc20(token: u64, from: u64, to: u64, amount: u64) -> Log {
+        let topics = vec![transfer_topic(), account(from).into(), account(to).into()];
+        let mut data = [0u8; 32];
+        U256::from(amount).to_big_endian(&mut data);
+        log(token, topics, data.to_vec())
+    }
+
+    fn erc721(token: u64, from: u64, to: u64, id: u64) -> Log {
+        let topics = vec![
+            transfer_topic(),
+            account(from).into(),
+            account(to).into(),
+            H256::from_low_u64_be(id),
+        ];
+        log(token, topics, Vec::new())
+    }
+
+    fn flow(asset: Asset, account_id: u64, net: i64) -> Flow {
+        Flow {
+            asset,
+            account: account(account_id),
+            net: I256::from(net),
+        }
+    }
+
+    #[test]
+    fn swap_through_a_router_nets_out_the_pass_through() {
+        // 用户付 1 ETH 给 router，router 把 WETH 给池子，池子的 USDC 经 router 转给用户
+        let eth = 1_000_000_000_000_000_000;
+        let logs = [
+            erc20(WETH, ROUTER, POOL, eth),
+            erc20(USDC, POOL, ROUTER, 3_000_000_000),
+            erc20(USDC, ROUTER, USER, 3_000_000_000),
+        ];
+        let flows = net_flows(account(USER), Some(account(ROUTER)), U256::from(eth), &logs);
+        let eth = eth as i64;
+        assert_eq!(
+            flows,
+            [
+                flow(Asset::Eth, USER, -eth),
+                flow(Asset::Eth, ROUTER, eth),
+                flow(Asset::Erc20(account(USDC)), USER, 3_000_000_000),
+                flow(Asset::Erc20(account(USDC)), POOL, -3_000_000_000),
+                flow(Asset::Erc20(account(WETH)), ROUTER, -eth),
+                flow(Asset::Erc20(account(WETH)), POOL, eth),
+            ]
+        );
+        assert_eq!(flow_tokens(&flows), [account(USDC), account(WETH)]);
+    }
+
+    #[test]
+    fn nfts_count_one_per_token_id_and_mints_come_from_zero() {
+        let logs = [
+            erc721(NFT, 0, USER, 5),
+            erc721(NFT, 0, USER, 6),
+            erc721(NFT, USER, 0, 5),
+            erc721(NFT, USER, ROUTER, 6),
+            erc721(NFT, ROUTER, USER, 6),
+        ];
+        let flows = net_flows(account(USER), Some(account(NFT)), U256::zero(), &logs);
+        assert_eq!(
+            flows,
+            [flow(Asset::Erc721(account(NFT)), 0, -1), flow(Asset::Erc721(account(NFT)), USER, 1)]
+        );
+    }
+
+    #[test]
+    fn ignores_logs_that_are_not_plain_transfers() {
+        let mut approval = erc20(USDC, USER, ROUTER, 5);
+        approval.topics[0] = H256::repeat_byte(0x8c);
+        let mut long_data = erc20(USDC, USER, ROUTER, 5);
+        long_data.data = vec![0u8; 64].into();
+        let mut short_topics = erc20(USDC, USER, ROUTER, 5);
+        short_topics.topics.truncate(2);
+        let logs = [approval, long_data, short_topics];
+        assert!(net_flows(account(USER), Some(account(USDC)), U256::zero(), &logs).is_empty());
+    }
+
+    #[test]
+    fn amounts_beyond_i256_saturate() {
+        let logs = [erc20(USDC, USER, POOL, 0)];
+        let mut huge = logs[0].clone();
+        huge.data = vec![0xff; 32].into();
+        let flows = net_flows(account(USER), None, U256::MAX, &[huge]);
+        assert_eq!(flows[0].net, I256::MIN + I256::one());
+        assert_eq!(flows[1].net, I256::MAX);
+    }
+
+    fn inspection(to: Option<u64>, status: u64, created: Option<u64>) -> Inspection {
+        let tx = Transaction {
+            from: account(USER),
+            to: to.map(account),
+            value: U256::from(500),
+            ..Default::default()
+        };
+        let receipt = TransactionReceipt {
+            status: Some(U64::from(status)),
+            contract_address: created.map(account),
+            logs: vec![erc20(USDC, POOL, USER, 7)],
+            ..Default::default()
+        };
+        Inspection {
+            tx,
+            receipt,
+            block: None,
+        }
+    }
+
+    #[test]
+    fn failed_transactions_move_no_eth() {
+        let failed = inspection(Some(ROUTER), 0, None);
+        assert!(!failed.succeeded());
+        assert_eq!(
+            failed.flows(),
+            [
+                flow(Asset::Erc20(account(USDC)), USER, 7),
+                flow(Asset::Erc20(account(USDC)), POOL, -7),
+            ]
+        );
+        // 部署合约时 ETH 流向新合约
+        let deploy = inspection(None, 1, Some(0x42));
+        assert_eq!(
+            deploy.flows()[..2],
+            [flow(Asset::Eth, USER, -500), flow(Asset::Eth, 0x42, 500)]
+        );
+    }
+
+    #[test]
+    fn formats_flows_with_token_metadata_when_known() {
+        let tokens = HashMap::from([(
+            account(USDC),
+            TokenMeta {
+                symbol: "USDC".to_string(),
+                decimals: 6,
+            },
+        )]);
+        let format = |flow: Flow| format_flow(&flow, &tokens).unwrap();
+        let eth = 1_500_000_000_000_000_000;
+        assert_eq!(format(flow(Asset::Eth, USER, -eth)), "-1.500000000000000000 ETH");
+        assert_eq!(format(flow(Asset::Erc20(account(USDC)), USER, 2_500_000)), "+2.500000 USDC");
+        assert_eq!(
+            format(flow(Asset::Erc20(account(WETH)), USER, -3)),
+            format!("-3 ({})", to_checksum(&account(WETH), None))
+        );
+        assert_eq!(format(flow(Asset::Erc721(account(NFT)), USER, 2)), "+2 NFT");
+    }
+}
@@ -0,0 +1,110 @@
+//! 以地址为键的 JSON 缓存文件
+//!
+//! `origin` 和 `activity` 把查询结果按地址存在同一种格式的文件里：一个 JSON 对象，
+//! 键是带 0x 的小写地址。文件不存在或格式不对时当作空缓存；写入时整个文件原子替换，
+//! 中途出错不会留下写了一半的缓存。
+
+use arbi_common::table;
+use ethers::types::Address;
+use serde_json::{Map, Value};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+// 缓存里的键
+fn key(address: Address) -> String {
+    format!("{:?}", address)
+}
+
+// 读取整个缓存文件，文件不存在或格式不对时当作空缓存
+fn load(path: &Path) -> Map<String, Value> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+        .and_then(|value| value.as_object().cloned())
+        .unwrap_or_default()
+}
+
+/// 读取一个地址的缓存条目
+///
+/// # 参数
+/// * `path` - 缓存文件路径
+/// * `address` - 地址
+///
+/// # 返回
+/// * `Option<Value>` - 缓存的条目；没有缓存时为 `None`
+pub fn get(path: &Path, address: Address) -> Option<Value> {
+    load(path).remove(&key(address))
+}
+
+/// 写入一个地址的缓存条目，保留文件里的其他条目
+///
+/// 目录不存在时自动创建；通过临时文件加改名原子地替换缓存文件。
+///
+/// # 参数
+/// * `path` - 缓存文件路径
+/// * `address` - 地址
+/// * `entry` - 要缓存的条目
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 写入结果
+pub fn put(path: &Path, address: Address, entry: Value) -> Result<(), Box<dyn Error>> {
+    let mut cache = load(path);
+    cache.insert(key(address), entry);
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let text = serde_json::to_string_pretty(&Value::Object(cache))?;
+    table::write_atomic(path, text.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    // 每个测试一个独立目录
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("arbi-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn put_keeps_other_entries_and_creates_directories() {
+        let dir = scratch("put");
+        let path = dir.join("nested").join("cache.json");
+        let (a, b) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+
+        assert_eq!(get(&path, a), None);
+        put(&path, a, json!({"block": 1})).unwrap();
+        put(&path, b, json!({"block": 2})).unwrap();
+        put(&path, a, json!({"block": 3})).unwrap();
+
+        assert_eq!(get(&path, a), Some(json!({"block": 3})));
+        assert_eq!(get(&path, b), Some(json!({"block": 2})));
+        // 只剩缓存文件本身，没有遗留的临时文件
+        let files: Vec<_> = fs::read_dir(path.parent().unwrap()).unwrap().collect();
+        assert_eq!(files.len(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn unreadable_cache_is_treated_as_empty() {
+        let dir = scratch("corrupt");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cache.json");
+        let address = Address::from_low_u64_be(7);
+
+        fs::write(&path, "{ not json").unwrap();
+        assert_eq!(get(&path, address), None);
+        fs::write(&path, "[1, 2]").unwrap();
+        assert_eq!(get(&path, address), None);
+
+        put(&path, address, json!(true)).unwrap();
+        assert_eq!(get(&path, address), Some(json!(true)));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
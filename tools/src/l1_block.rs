@@ -0,0 +1,303 @@
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::abi::{Abi, Token};
+use ethers::providers::Middleware;
+use ethers::types::{Address, BlockId, BlockNumber, Bytes, TransactionRequest, U256};
+use std::error::Error;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::messages::{Msg, t, tf};
+
+// ArbSys 预编译合约地址
+const ARBSYS_ADDRESS: &str = "0x0000000000000000000000000000000000000064";
+
+const ARBSYS_ABI: &str = r#"[
+    {
+        "inputs": [],
+        "name": "arbBlockNumber",
+        "outputs": [{"name": "", "type": "uint256"}],
+        "stateMutability": "view",
+        "type": "function"
+    }
+]"#;
+
+// 创建合约的初始化代码：NUMBER PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN，直接返回 block.number。
+// 在 Arbitrum 上 EVM 里的 block.number 是排序器看到的 L1 区块号，用 eth_call 执行就能读到，
+// 不需要部署合约
+const BLOCK_NUMBER_INIT_CODE: [u8; 9] = [0x43, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3];
+
+/// 同一个 L2 区块上的几种区块号读数
+#[derive(Clone, Copy, Debug)]
+pub struct BlockReading {
+    /// 区块头里的区块号（L2 高度）
+    pub l2_header: u64,
+    /// ArbSys.arbBlockNumber()，同样是 L2 高度
+    pub arb_block_number: u64,
+    /// 区块头的 l1BlockNumber 扩展字段，节点不返回时为 `None`
+    pub l1_header: Option<u64>,
+    /// EVM 中 block.number 的值（Arbitrum 上是 L1 区块号）
+    pub l1_evm: u64,
+}
+
+impl BlockReading {
+    /// 两个 L1 读数不一致时返回 `(区块头, EVM)`；区块头没有该字段时不比较
+    pub fn l1_mismatch(&self) -> Option<(u64, u64)> {
+        match self.l1_header {
+            Some(header) if header != self.l1_evm => Some((header, self.l1_evm)),
+            _ => None,
+        }
+    }
+}
+
+/// 采样期间 L2 和 L1 区块号各前进了多少
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Advance {
+    pub l2_blocks: u64,
+    pub l1_blocks: u64,
+}
+
+impl Advance {
+    /// 根据第一次和最后一次读数计算前进的区块数
+    pub fn between(first: &BlockReading, last: &BlockReading) -> Self {
+        Self {
+            l2_blocks: last.l2_header.saturating_sub(first.l2_header),
+            l1_blocks: last.l1_evm.saturating_sub(first.l1_evm),
+        }
+    }
+
+    /// 平均每前进一个 L1 区块对应多少个 L2 区块，L1 没有前进时为 `None`
+    pub fn l2_per_l1(&self) -> Option<f64> {
+        (self.l1_blocks > 0).then(|| self.l2_blocks as f64 / self.l1_blocks as f64)
+    }
+}
+
+/// 把 uint256 返回值解码成 u64
+///
+/// # 参数
+/// * `abi` - 合约 ABI
+/// * `function` - 函数名
+/// * `output` - eth_call 的返回数据
+///
+/// # 返回
+/// * `Result<u64, Box<dyn Error>>` - 解码后的数值
+pub fn decode_u64(abi: &Abi, function: &str, output: &[u8]) -> Result<u64, Box<dyn Error>> {
+    match abi.function(function)?.decode_output(output)?.as_slice() {
+        [Token::Uint(value)] if *value <= U256::from(u64::MAX) => Ok(value.as_u64()),
+        _ => Err(tf(Msg::L1BlockBadOutput, &[&function]).into()),
+    }
+}
+
+/// 读取最新 L2 区块上的几种区块号
+///
+/// # 参数
+/// * `provider` - Provider 引用
+///
+/// # 返回
+/// * `Result<BlockReading, Box<dyn Error>>` - 同一区块上的 L2 / L1 读数
+pub async fn read_block_numbers(provider: &RpcProvider) -> Result<BlockReading, Box<dyn Error>> {
+    let block = provider
+        .get_block(BlockNumber::Latest)
+        .await?
+        .ok_or_else(|| tf(Msg::BlockNotFound, &[&"latest"]))?;
+    let l2_header = block
+        .number
+        .ok_or_else(|| tf(Msg::BlockNotFound, &[&"latest"]))?
+        .as_u64();
+    let l1_header = block
+        .other
+        .get("l1BlockNumber")
+        .and_then(|value| value.as_str())
+        .and_then(|value| u64::from_str_radix(value.trim_start_matches("0x"), 16).ok());
+
+    // 两次调用都固定在同一个区块上，避免读数之间出块
+    let at = Some(BlockId::Number(l2_header.into()));
+
+    let abi: Abi = serde_json::from_str(ARBSYS_ABI)?;
+    let call = TransactionRequest::new()
+        .to(Address::from_str(ARBSYS_ADDRESS)?)
+        .data(abi.function("arbBlockNumber")?.encode_input(&[])?);
+    let output = provider.call(&call.into(), at).await?;
+    let arb_block_number = decode_u64(&abi, "arbBlockNumber", &output)?;
+
+    let create = TransactionRequest::new().data(Bytes::from(BLOCK_NUMBER_INIT_CODE.to_vec()));
+    let output = provider.call(&create.into(), at).await?;
+    if output.len() != 32 {
+        return Err(tf(Msg::L1BlockBadOutput, &[&"block.number"]).into());
+    }
+    let l1_evm = U256::from_big_endian(&output);
+    if l1_evm > U256::from(u64::MAX) {
+        return Err(tf(Msg::L1BlockBadOutput, &[&"block.number"]).into());
+    }
+
+    Ok(BlockReading {
+        l2_header,
+        arb_block_number,
+        l1_header,
+        l1_evm: l1_evm.as_u64(),
+    })
+}
+
+/// 打印一次读数并解释各个数字的含义
+///
+/// # 参数
+/// * `reading` - `read_block_numbers` 的结果
+pub fn print_reading(reading: &BlockReading) {
+    println!("{}", tf(Msg::L1BlockL2Header, &[&reading.l2_header]));
+    println!("{}", tf(Msg::L1BlockArbBlockNumber, &[&reading.arb_block_number]));
+    match reading.l1_header {
+        Some(l1) => println!("{}", tf(Msg::L1BlockL1Header, &[&l1])),
+        None => println!("{}", tf(Msg::L1BlockL1Header, &[&t(Msg::L1BlockMissingField)])),
+    }
+    println!("{}", tf(Msg::L1BlockL1Evm, &[&reading.l1_evm]));
+
+    if reading.arb_block_number != reading.l2_header {
+        println!(
+            "{}",
+            style::warn(tf(
+                Msg::L1BlockL2Mismatch,
+                &[&reading.l2_header, &reading.arb_block_number]
+            ))
+        );
+    }
+    match reading.l1_mismatch() {
+        Some((header, evm)) => {
+            println!("{}", style::warn(tf(Msg::L1BlockL1Mismatch, &[&header, &evm])))
+        }
+        None if reading.l1_header.is_some() => println!("{}", style::ok(t(Msg::L1BlockL1Agree))),
+        None => {}
+    }
+}
+
+/// 按固定间隔多次采样，统计 L1 区块号相对 L2 区块的前进频率
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `samples` - 采样次数（至少 2 次才能统计）
+/// * `interval` - 两次采样之间的间隔
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+pub async fn sample(
+    provider: &RpcProvider,
+    samples: usize,
+    interval: Duration,
+) -> Result<(), Box<dyn Error>> {
+    let mut readings = Vec::with_capacity(samples);
+    for i in 0..samples {
+        if i > 0 {
+            tokio::time::sleep(interval).await;
+        }
+        let reading = read_block_numbers(provider).await?;
+        println!("{}", tf(Msg::L1BlockSample, &[&(i + 1), &reading.l2_header, &reading.l1_evm]));
+        readings.push(reading);
+    }
+
+    let (Some(first), Some(last)) = (readings.first(), readings.last()) else {
+        return Ok(());
+    };
+    let advance = Advance::between(first, last);
+    println!(
+        "\n{}",
+        tf(Msg::L1BlockAdvance, &[&advance.l2_blocks, &advance.l1_blocks])
+    );
+    match advance.l2_per_l1() {
+        Some(ratio) => println!("{}", tf(Msg::L1BlockRatio, &[&format!("{:.1}", ratio)])),
+        None => println!("{}", t(Msg::L1BlockNoL1Advance)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use serde_json::{Value, json};
+
+    fn reading(l2: u64, arb: u64, l1_header: Option<u64>, l1_evm: u64) -> BlockReading {
+        BlockReading {
+            l2_header: l2,
+            arb_block_number: arb,
+            l1_header,
+            l1_evm,
+        }
+    }
+
+    #[test]
+    fn l1_mismatch_only_when_header_disagrees() {
+        assert_eq!(reading(100, 100, Some(7), 7).l1_mismatch(), None);
+        assert_eq!(reading(100, 100, None, 7).l1_mismatch(), None);
+        assert_eq!(reading(100, 100, Some(8), 7).l1_mismatch(), Some((8, 7)));
+    }
+
+    #[test]
+    fn advance_counts_blocks_between_samples() {
+        let first = reading(1_000, 1_000, None, 500);
+        let last = reading(1_048, 1_048, None, 502);
+        let advance = Advance::between(&first, &last);
+        assert_eq!(advance, Advance { l2_blocks: 48, l1_blocks: 2 });
+        assert_eq!(advance.l2_per_l1(), Some(24.0));
+
+        let still = Advance::between(&first, &first);
+        assert_eq!(still.l2_per_l1(), None);
+        // 节点切换后读数回退时不会下溢
+        assert_eq!(Advance::between(&last, &first).l2_blocks, 0);
+    }
+
+    #[test]
+    fn decode_u64_rejects_values_beyond_u64() {
+        let abi: Abi = serde_json::from_str(ARBSYS_ABI).unwrap();
+        let word = |value: U256| ethers::abi::encode(&[Token::Uint(value)]);
+        let value = decode_u64(&abi, "arbBlockNumber", &word(U256::from(0x0a4b0f1)));
+        assert_eq!(value.unwrap(), 0x0a4b0f1);
+        let too_big = U256::from(u64::MAX) + 1;
+        let error = decode_u64(&abi, "arbBlockNumber", &word(too_big)).unwrap_err();
+        assert_eq!(error.to_string(), tf(Msg::L1BlockBadOutput, &[&"arbBlockNumber"]));
+        assert!(decode_u64(&abi, "arbBlockNumber", &[0; 4]).is_err());
+    }
+
+    // 最新区块 0xa4b0f1，区块头带 l1BlockNumber；ArbSys 和 EVM 的 block.number 都在这个区块上读
+    fn node(l1_header: Option<&'static str>) -> MockRpc {
+        MockRpc::start(move |method, params| match method {
+            "eth_getBlockByNumber" => {
+                let mut block = json!({
+                    "number": "0xa4b0f1",
+                    "hash": format!("{:?}", ethers::types::H256::repeat_byte(0x10)),
+                    "transactions": [],
+                });
+                if let Some(l1) = l1_header {
+                    block["l1BlockNumber"] = json!(l1);
+                }
+                Reply::Result(block)
+            }
+            "eth_call" if params[1] != json!("0xa4b0f1") => {
+                Reply::Error(format!("unexpected block {}", params[1]))
+            }
+            "eth_call" if params[0].get("to").is_some_and(|to| !to.is_null()) => {
+                Reply::Result(json!(format!("0x{:064x}", 0xa4b0f1)))
+            }
+            "eth_call" => Reply::Result(json!(format!("0x{:064x}", 0x6c1f2a))),
+            _ => Reply::Result(Value::Null),
+        })
+    }
+
+    #[tokio::test]
+    async fn reads_every_number_from_the_same_block() {
+        let rpc = node(Some("0x6c1f2a"));
+        let reading = read_block_numbers(&rpc.provider()).await.unwrap();
+        assert_eq!(reading.l2_header, 0xa4b0f1);
+        assert_eq!(reading.arb_block_number, 0xa4b0f1);
+        assert_eq!(reading.l1_header, Some(0x6c1f2a));
+        assert_eq!(reading.l1_evm, 0x6c1f2a);
+        assert_eq!(reading.l1_mismatch(), None);
+        assert_eq!(rpc.count("eth_call"), 2);
+    }
+
+    #[tokio::test]
+    async fn header_without_l1_field_is_not_compared() {
+        let rpc = node(None);
+        let reading = read_block_numbers(&rpc.provider()).await.unwrap();
+        assert_eq!(reading.l1_header, None);
+        assert_eq!(reading.l1_evm, 0x6c1f2a);
+    }
+}
@@ -0,0 +1,1308 @@
+mod account;
+mod activity;
+mod address;
+mod block;
+mod book;
+mod calldata;
+mod convert;
+mod dashboard;
+mod fees;
+mod finality;
+mod gas_report;
+mod gas_usage;
+mod hash;
+mod history;
+mod holdings;
+mod inclusion;
+mod inspect;
+mod json_cache;
+mod l1_block;
+mod messages;
+mod origin;
+mod pending;
+mod sequencer;
+mod setup;
+mod standard;
+mod token_history;
+mod trace;
+mod vanity;
+mod wallet;
+
+use arbi_common::cli::{CsvArgs, GlobalArgs, QrArgs};
+use arbi_common::concurrency::{self, DEFAULT_CONCURRENCY};
+use arbi_common::env;
+use arbi_common::keys;
+use arbi_common::logs;
+use arbi_common::metrics;
+use arbi_common::network;
+use arbi_common::rawtx;
+use arbi_common::rpc::{self, RpcProvider};
+use arbi_common::style;
+use arbi_common::txhash::parse_tx_hash;
+use clap::{ArgGroup, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use ethers::providers::Middleware;
+use ethers::signers::Signer;
+use ethers::types::{Address, H256};
+use ethers::utils::{hex, keccak256, to_checksum};
+use std::collections::HashMap;
+use std::error::Error;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{Instrument, info_span};
+
+use messages::{Msg, t, tf};
+
+/// Arbitrum 测试网探索工具集
+#[derive(Parser)]
+#[command(name = "arbi", about = "Arbitrum 测试网探索工具集")]
+struct Cli {
+    /// RPC 节点地址（默认为当前网络的节点）
+    #[arg(long, global = true)]
+    rpc_url: Option<String>,
+
+    #[command(flatten)]
+    global: GlobalArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 列出区块中的交易，可按地址过滤
+    BlockTxs {
+        /// 区块：latest / pending / finalized、十进制或 0x 开头的十六进制区块号
+        #[arg(default_value = "latest")]
+        block: String,
+        /// 只显示发送方或接收方为该地址的交易
+        #[arg(long)]
+        address: Option<String>,
+        /// 最多打印的交易数（CSV 包含全部交易）
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+        #[command(flatten)]
+        csv: CsvArgs,
+    },
+    /// 解码交易的调用、事件和价值流动，并给出 Gas 和区块信息
+    Inspect {
+        /// 交易哈希
+        tx_hash: String,
+        /// JSON ABI 文件（可重复），对所有合约都会尝试
+        #[arg(long)]
+        abi: Vec<String>,
+        /// 不从区块浏览器获取已验证合约的 ABI（默认在配置了 ARBISCAN_API_KEY 时获取）
+        #[arg(long)]
+        no_fetch: bool,
+    },
+    /// 通过 ERC165 supportsInterface 判断合约是 ERC721 还是 ERC1155，帮助选择读取用的 ABI
+    DetectStandard {
+        /// 合约地址
+        address: String,
+    },
+    /// 获取交易的调用树（需要节点支持 debug_traceTransaction 或 trace_transaction）
+    Trace {
+        /// 交易哈希
+        tx_hash: String,
+        /// JSON ABI 文件，用来把函数选择器解码成函数签名
+        #[arg(long)]
+        abi: Option<String>,
+        /// 最大显示深度，更深的调用折叠显示
+        #[arg(long, default_value_t = 8)]
+        max_depth: usize,
+    },
+    /// 读取 ArbGasInfo 预编译合约，逐项解释 Arbitrum 费用并估算示例交易的费用
+    ArbFees,
+    /// 对比 L2 区块高度和 Arbitrum 上看到的 L1 区块号
+    L1Block {
+        /// 采样次数，大于 1 时统计 L1 区块号相对 L2 区块的前进频率
+        #[arg(long, default_value_t = 1)]
+        samples: usize,
+        /// 两次采样之间的间隔秒数
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+    },
+    /// 定期比较 latest / safe / finalized 区块，finalized 落后过多时报警（Ctrl-C 退出时打印统计）
+    FinalityMonitor {
+        /// 两次读取的间隔秒数
+        #[arg(long, default_value_t = 30)]
+        interval: u64,
+        /// finalized 落后超过多少分钟时报警
+        #[arg(long, default_value_t = 90.0)]
+        threshold_minutes: f64,
+        /// 只读取一次
+        #[arg(long)]
+        once: bool,
+        /// 每次输出一行 JSON
+        #[arg(long)]
+        json: bool,
+        /// 在该地址提供 Prometheus 指标（/metrics），如 0.0.0.0:9898
+        #[arg(long, value_name = "ADDR")]
+        metrics_listen: Option<SocketAddr>,
+    },
+    /// 根据最新区块的时间检查排序器是否在正常出块
+    SequencerStatus {
+        /// 超过多少秒没有新区块视为停滞
+        #[arg(long, default_value_t = 60)]
+        stale_after: u64,
+    },
+    /// 查询交易所在区块是否已打包进发布到 L1 的批次，以及该批次的 L1 确认数
+    L1Inclusion {
+        /// 交易哈希
+        tx_hash: String,
+        /// 等待到确认数达到 --confirmations 为止
+        #[arg(long)]
+        wait: bool,
+        /// 目标 L1 确认数（以太坊约 64 个区块、两个 epoch 后最终确定）
+        #[arg(long, default_value_t = 64)]
+        confirmations: u64,
+        /// 等待时的轮询间隔秒数
+        #[arg(long, default_value_t = 30)]
+        interval: u64,
+    },
+    /// 查找合约的部署交易和部署者（配置 ARBISCAN_API_KEY 时用浏览器 API，否则二分查找区块）
+    ContractOrigin {
+        /// 合约地址
+        address: String,
+        /// 结果缓存文件
+        #[arg(long, default_value = ".arbi/contract-origins.json")]
+        cache_file: PathBuf,
+    },
+    /// 用 ASCII 柱状图显示一段区块的 Gas 使用率（区块多时按步长采样）
+    GasUsage {
+        /// 起始区块（含，默认为结束区块前 99 个区块）
+        #[arg(long)]
+        from_block: Option<u64>,
+        /// 结束区块（含，默认为最新区块）
+        #[arg(long)]
+        to_block: Option<u64>,
+        /// 最多读取的区块数，范围更大时每隔 N 个区块取一个
+        #[arg(long, default_value_t = 200)]
+        max_samples: u64,
+    },
+    /// 统计账户在区块范围内发出的交易花掉的 Gas 费（配置 ARBISCAN_API_KEY 时用浏览器 API，否则分段扫描）
+    GasReport {
+        /// 账户地址
+        address: String,
+        /// 起始区块（含）
+        #[arg(long)]
+        from_block: u64,
+        /// 结束区块（含，默认为最新区块）
+        #[arg(long)]
+        to_block: Option<u64>,
+        #[command(flatten)]
+        csv: CsvArgs,
+    },
+    /// 统计账户最近发出的 N 笔交易花掉的 Gas 费（需要 ARBISCAN_API_KEY）
+    GasSpent {
+        /// 账户地址
+        address: String,
+        /// 统计最近多少笔交易
+        #[arg(
+            long,
+            default_value_t = 20,
+            value_parser = clap::value_parser!(u64).range(1..=gas_report::MAX_RECENT_TXS)
+        )]
+        last: u64,
+        #[command(flatten)]
+        csv: CsvArgs,
+    },
+    /// 列出账户在区块范围内的 ERC20 转账和每个代币的净变化（分段 eth_getLogs）
+    TokenHistory {
+        /// 账户地址
+        address: String,
+        /// 代币合约地址（可重复，默认读取 ARBI_TOKENS，都没有时使用当前网络的 USDC）
+        #[arg(long)]
+        token: Vec<String>,
+        /// 起始区块（含）
+        #[arg(long)]
+        from_block: u64,
+        /// 结束区块（含，默认为最新区块）
+        #[arg(long)]
+        to_block: Option<u64>,
+        /// 每次 eth_getLogs 查询的区块数，节点报告范围太大时自动减半
+        #[arg(
+            long,
+            default_value_t = logs::DEFAULT_CHUNK_SIZE,
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        chunk_size: u64,
+        #[command(flatten)]
+        csv: CsvArgs,
+    },
+    /// 自动发现地址持有的代币：扫描转入该地址的 ERC20 Transfer 日志，显示原生余额和余额不为 0 的代币
+    Holdings {
+        /// 钱包地址
+        address: String,
+        /// 起始区块（含），与最新区块的距离不能超过 100 万个区块
+        #[arg(long)]
+        from_block: u64,
+        /// 每次 eth_getLogs 查询的区块数，节点报告范围太大时自动减半
+        #[arg(
+            long,
+            default_value_t = logs::DEFAULT_CHUNK_SIZE,
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        chunk_size: u64,
+        #[command(flatten)]
+        csv: CsvArgs,
+    },
+    /// 导出账户的交易历史到 CSV（配置 ARBISCAN_API_KEY 时用浏览器 API，否则逐块扫描）
+    ExportHistory {
+        /// 账户地址
+        address: String,
+        /// 起始区块（含）
+        #[arg(long, default_value_t = 0)]
+        from_block: u64,
+        /// 结束区块（含，默认为最新区块）
+        #[arg(long)]
+        to_block: Option<u64>,
+        /// CSV 输出路径
+        #[arg(long, short, default_value = "history.csv")]
+        output: PathBuf,
+        /// CSV 开头加 UTF-8 BOM，让 Excel 正确显示中文
+        #[arg(long)]
+        bom: bool,
+    },
+    /// 账户概况：余额、nonce、是否为合约、第一笔发出的交易（需要归档节点）和最近的活动
+    Account {
+        /// 账户地址
+        address: String,
+        /// 统计最近多少个区块中的交易
+        #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(u64).range(1..=5000))]
+        blocks: u64,
+        /// 以 JSON 输出
+        #[arg(long)]
+        json: bool,
+    },
+    /// 账户的第一笔和最近一笔交易及交易总数（配置 ARBISCAN_API_KEY 时用浏览器 API，否则按 nonce 二分查找区块）
+    AccountActivity {
+        /// 账户地址
+        address: String,
+        /// 结果缓存文件，下次只查询缓存之后的新区块
+        #[arg(long, default_value = ".arbi/account-activity.json")]
+        cache_file: PathBuf,
+    },
+    /// 实时面板：钱包余额、Gas 价格和新区块，按 q 退出（输出不是终端时定期打印摘要）
+    Dashboard {
+        /// 要显示余额的地址（默认使用 PRIVATE_KEY 对应的地址）
+        #[arg(long)]
+        address: Option<String>,
+        /// 刷新间隔秒数
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+        /// 在该地址提供 Prometheus 指标（/metrics），如 0.0.0.0:9898
+        #[arg(long, value_name = "ADDR")]
+        metrics_listen: Option<SocketAddr>,
+    },
+    /// 通过 WebSocket 订阅待处理交易（Arbitrum 没有公共内存池，多数节点不会推送），Ctrl-C 退出时打印计数
+    WatchPending {
+        /// WebSocket 节点地址（默认为当前网络的公共 WebSocket 节点）
+        #[arg(long)]
+        ws_url: Option<String>,
+        /// 读取并打印每笔交易的 from / to / value
+        #[arg(long)]
+        details: bool,
+        /// 只显示涉及该地址的交易（需要读取详情）
+        #[arg(long)]
+        address: Option<String>,
+        /// 每秒最多读取详情的次数，超出部分只计数
+        #[arg(long, default_value_t = 10)]
+        max_fetch_per_sec: u32,
+    },
+    /// 校验地址并输出 EIP-55 校验格式和小写格式（离线，不连接节点）
+    Normalize {
+        /// 要校验的地址
+        address: String,
+    },
+    /// 计算 keccak256 哈希或函数选择器（离线，不连接节点）
+    #[command(group(
+        ArgGroup::new("input")
+            .required(true)
+            .args(["keccak", "keccak_hex", "selector", "abi_encode_packed"])
+    ))]
+    Hash {
+        /// 计算 UTF-8 字符串的 keccak256
+        #[arg(long, value_name = "TEXT")]
+        keccak: Option<String>,
+        /// 计算十六进制字节串的 keccak256
+        #[arg(long, value_name = "HEX")]
+        keccak_hex: Option<String>,
+        /// 计算函数签名的 4 字节选择器，如 "transfer(address,uint256)"
+        #[arg(long, value_name = "SIGNATURE")]
+        selector: Option<String>,
+        /// 按 abi.encodePacked 编码逗号分隔的 类型:值（如 "address:0x..,uint256:5"），输出字节和 keccak256
+        #[arg(long, value_name = "VALUES")]
+        abi_encode_packed: Option<String>,
+    },
+    /// 在所有 CPU 核心上随机生成私钥，直到地址匹配指定前缀 / 后缀（离线，不连接节点）
+    #[command(group(
+        ArgGroup::new("pattern").required(true).multiple(true).args(["prefix", "suffix"])
+    ))]
+    Vanity {
+        /// 地址前缀（十六进制，可以带 0x）
+        #[arg(long)]
+        prefix: Option<String>,
+        /// 地址后缀（十六进制）
+        #[arg(long)]
+        suffix: Option<String>,
+        /// 区分大小写，按 EIP-55 校验格式匹配（难度按字母个数翻倍）
+        #[arg(long)]
+        checksum: bool,
+        /// 线程数（默认为 CPU 核心数）
+        #[arg(long)]
+        threads: Option<usize>,
+        /// keystore 文件的保存目录，密码从 KEYSTORE_PASSWORD 环境变量读取
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: PathBuf,
+        /// 把私钥直接打印到终端，不写 keystore（不安全）
+        #[arg(long)]
+        print_key: bool,
+    },
+    /// 精确换算 wei / gwei / eth 和代币的最小单位 / 数量（离线，不连接节点）
+    Convert {
+        /// 金额，可以用 `_` 或千分位 `,` 分隔
+        #[arg(allow_negative_numbers = true)]
+        amount: String,
+        /// 来源单位（有 --decimals 时默认 raw）
+        #[arg(value_enum)]
+        from: Option<convert::Unit>,
+        /// 目标单位（有 --decimals 时默认 token）
+        #[arg(value_enum)]
+        to: Option<convert::Unit>,
+        /// 代币的小数位数，用于 raw / token 单位（如 USDC 为 6）
+        #[arg(long, value_parser = clap::value_parser!(u32).range(0..=77))]
+        decimals: Option<u32>,
+        /// 以 JSON 输出所有单位下的表示
+        #[arg(long)]
+        json: bool,
+    },
+    /// 解码已签名的原始交易，恢复发送方并打印所有字段（离线，不连接节点）
+    DecodeRaw {
+        /// 十六进制（`eth_sendRawTransaction` 的参数），或保存它的文件路径
+        #[arg(value_name = "HEX_OR_FILE")]
+        input: String,
+    },
+    /// 按 ABI 或函数签名解码 calldata；没有 ABI 时逐字显示并猜测类型
+    DecodeCalldata {
+        /// calldata 十六进制（不给时用 --from-tx）
+        #[arg(required_unless_present = "from_tx", conflicts_with = "from_tx")]
+        data: Option<String>,
+        /// JSON ABI 文件，按选择器匹配函数
+        #[arg(long)]
+        abi: Option<String>,
+        /// 函数签名，可带参数名，如 "transfer(address to, uint256 amount)"
+        #[arg(long)]
+        sig: Option<String>,
+        /// 读取链上交易的 input 作为 calldata（需要连接节点）
+        #[arg(long, value_name = "HASH")]
+        from_tx: Option<String>,
+    },
+    /// 生成新钱包并导出加密 keystore，或查看 keystore 的地址（离线，不连接节点）
+    Wallet {
+        #[command(subcommand)]
+        action: WalletAction,
+    },
+    /// 首次使用向导：生成新钱包，加密保存为 keystore，写入 .env 模板（交互式，不连接节点）
+    Setup {
+        /// keystore 文件的保存目录
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: PathBuf,
+        /// .env 模板的路径，已存在时确认后才覆盖
+        #[arg(long, default_value = ".env")]
+        env_file: PathBuf,
+    },
+    /// 地址簿：给常用地址起名字（保存在本地 JSON 文件里，离线）
+    Book {
+        /// 地址簿文件
+        #[arg(long, default_value = ".arbi/address-book.json")]
+        file: PathBuf,
+        #[command(subcommand)]
+        action: BookAction,
+    },
+    /// 生成 shell 补全脚本（bash / zsh / fish / powershell / elvish）
+    ///
+    /// bash、zsh、fish 的脚本还会调用 `arbi book list --names-only` 补全地址簿里的名字。
+    Completions {
+        /// 目标 shell
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+#[derive(Subcommand)]
+enum BookAction {
+    /// 添加一个名字，已存在时改为新地址
+    Add {
+        /// 名字（不能含空白字符）
+        name: String,
+        /// 地址
+        address: String,
+    },
+    /// 列出全部名字和地址
+    List {
+        /// 只输出名字，每行一个（shell 补全使用）
+        #[arg(long)]
+        names_only: bool,
+    },
+    /// 显示一个名字对应的地址
+    Show {
+        /// 名字
+        name: String,
+        #[command(flatten)]
+        qr: QrArgs,
+    },
+    /// 删除一个名字
+    Remove {
+        /// 名字
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum WalletAction {
+    /// 随机生成私钥，加密保存为 V3 keystore（密码从 KEYSTORE_PASSWORD 环境变量读取）
+    New {
+        /// 先生成 12 个单词的 BIP-39 助记词，再派生第一个账户（助记词只打印这一次）
+        #[arg(long)]
+        mnemonic: bool,
+        /// keystore 文件的保存目录
+        #[arg(long, default_value = "keystore")]
+        dir: PathBuf,
+        #[command(flatten)]
+        qr: QrArgs,
+    },
+    /// 随机生成一次性的测试钱包，直接显示地址和私钥，不保存（仅限测试网，绝不能用于主网资金）
+    Generate {
+        /// 先生成 12 个单词的 BIP-39 助记词，再派生第一个账户，同时显示助记词
+        #[arg(long)]
+        mnemonic: bool,
+        /// 生成的钱包个数，每个钱包都是独立随机生成的
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..=100))]
+        count: u32,
+    },
+    /// 解密 keystore，只显示地址（密码从 KEYSTORE_PASSWORD 环境变量读取）
+    Show {
+        /// keystore 文件路径
+        keystore: PathBuf,
+        #[command(flatten)]
+        qr: QrArgs,
+    },
+    /// 显示私钥（PRIVATE_KEY）或助记词（MNEMONIC）对应的地址、余额和 nonce，未设置时提示输入
+    Address {
+        /// 从助记词按 m/44'/60'/0'/0/<index> 派生，而不是读取私钥
+        #[arg(long)]
+        mnemonic: bool,
+        /// 起始派生序号
+        #[arg(long, default_value_t = 0, requires = "mnemonic")]
+        index: u32,
+        /// 列出从起始序号开始的前 N 个地址
+        #[arg(
+            long,
+            default_value_t = 1,
+            requires = "mnemonic",
+            value_parser = clap::value_parser!(u32).range(1..=100)
+        )]
+        count: u32,
+    },
+}
+
+/// 执行 wallet 子命令；只有 `wallet address` 会连接节点
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `action` - 子命令
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_wallet(provider: &RpcProvider, action: WalletAction) -> Result<(), Box<dyn Error>> {
+    match action {
+        WalletAction::New { mnemonic, dir, qr } => {
+            let password = wallet::password_from_env()?;
+            let new_wallet = wallet::generate(&dir, &password, mnemonic)?;
+            wallet::print_new(&new_wallet);
+            qr.print(&new_wallet.address)
+        }
+        WalletAction::Generate { mnemonic, count } => {
+            let wallets = (0..count)
+                .map(|_| wallet::generate_throwaway(mnemonic))
+                .collect::<Result<Vec<_>, _>>()?;
+            wallet::print_throwaway(&wallets);
+            Ok(())
+        }
+        WalletAction::Show { keystore, qr } => {
+            let password = wallet::password_from_env()?;
+            let address = wallet::keystore_address(&keystore, &password)?;
+            println!("{}", tf(Msg::WalletAddress, &[&to_checksum(&address, None)]));
+            qr.print(&address)
+        }
+        WalletAction::Address {
+            mnemonic,
+            index,
+            count,
+        } => run_wallet_address(provider, mnemonic, index, count).await,
+    }
+}
+
+/// 显示私钥或助记词派生出的地址，以及它们当前的余额和 nonce
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `mnemonic` - 是否读取助记词
+/// * `index` - 起始派生序号
+/// * `count` - 派生的地址个数
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_wallet_address(
+    provider: &RpcProvider,
+    mnemonic: bool,
+    index: u32,
+    count: u32,
+) -> Result<(), Box<dyn Error>> {
+    let addresses = if mnemonic {
+        let phrase = wallet::read_secret("MNEMONIC", Msg::WalletMnemonicPrompt)?;
+        (index..index.saturating_add(count))
+            .map(|i| Ok((Some(i), keys::mnemonic_wallet(&phrase, i)?.address())))
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?
+    } else {
+        let key = wallet::read_secret("PRIVATE_KEY", Msg::WalletKeyPrompt)?;
+        vec![(None, keys::parse_private_key(&key)?.address())]
+    };
+
+    let accounts = concurrency::run_bounded(addresses, DEFAULT_CONCURRENCY, |(index, address)| {
+        wallet::account_info(provider, index, address)
+    })
+    .await
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()?;
+    wallet::print_accounts(&accounts);
+    Ok(())
+}
+
+/// 换算单位并打印结果
+///
+/// # 参数
+/// * `amount` - 金额
+/// * `from` - 来源单位
+/// * `to` - 目标单位
+/// * `decimals` - 代币小数位数
+/// * `json` - 是否以 JSON 输出所有单位
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+fn run_convert(
+    amount: &str,
+    from: Option<convert::Unit>,
+    to: Option<convert::Unit>,
+    decimals: Option<u32>,
+    json: bool,
+) -> Result<(), Box<dyn Error>> {
+    if json {
+        let from = match (from, decimals) {
+            (Some(from), _) => from,
+            (None, Some(_)) => convert::Unit::Raw,
+            (None, None) => return Err(t(Msg::ConvertMissingUnits).into()),
+        };
+        return convert::print_json(amount, from, decimals);
+    }
+    let (from, to) = convert::resolve_units(from, to, decimals)?;
+    convert::print_conversion(amount, from, to, decimals)
+}
+
+/// 读取并打印区块中的交易
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `block` - 区块标识字符串
+/// * `address` - 过滤地址字符串
+/// * `limit` - 最多打印的交易数
+/// * `csv` - CSV 导出选项
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_block_txs(
+    provider: &RpcProvider,
+    block: &str,
+    address: Option<&str>,
+    limit: usize,
+    csv: &CsvArgs,
+) -> Result<(), Box<dyn Error>> {
+    let block_id = block::parse_block_id(block)?;
+    let filter = address.map(Address::from_str).transpose()?;
+
+    let txs = block::block_txs(provider, block_id, filter).await?;
+    block::print_block_txs(&txs, filter, limit)?;
+    csv.write(&txs.to_table()?)
+}
+
+/// 获取并打印交易的调用树
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `tx_hash` - 交易哈希字符串
+/// * `abi` - 可选的 JSON ABI 文件路径
+/// * `max_depth` - 最大显示深度
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_trace(
+    provider: &RpcProvider,
+    tx_hash: &str,
+    abi: Option<&str>,
+    max_depth: usize,
+) -> Result<(), Box<dyn Error>> {
+    let tx_hash = parse_tx_hash(tx_hash).map_err(|e| tf(Msg::InvalidTxHash, &[&e]))?;
+    let selectors = match abi {
+        Some(path) => trace::load_selectors(path)?,
+        None => HashMap::new(),
+    };
+
+    let root = trace::call_trace(provider, tx_hash).await?;
+    trace::print_call_tree(&root, &selectors, max_depth);
+    Ok(())
+}
+
+/// 读取交易、收据和区块，解码后打印完整报告
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `tx_hash` - 交易哈希
+/// * `abi_files` - `--abi` 指定的 JSON ABI 文件
+/// * `no_fetch` - 不从区块浏览器获取 ABI
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_inspect(
+    provider: &RpcProvider,
+    tx_hash: &str,
+    abi_files: &[String],
+    no_fetch: bool,
+) -> Result<(), Box<dyn Error>> {
+    let hash = parse_tx_hash(tx_hash).map_err(|e| tf(Msg::InvalidTxHash, &[&e]))?;
+    let mut book = inspect::AbiBook::new(abi_files)?;
+    let inspection = inspect::Inspection::fetch(provider, hash).await?;
+    if !no_fetch {
+        let fetched = book.fetch_verified(&inspection.contracts()).await;
+        if fetched > 0 {
+            println!("{}\n", tf(Msg::InspectAbisFetched, &[&fetched]));
+        }
+    }
+    let mut tokens = HashMap::new();
+    for token in inspect::flow_tokens(&inspection.flows()) {
+        if let Some(meta) = inspect::token_meta(provider, token).await {
+            tokens.insert(token, meta);
+        }
+    }
+    inspect::print_report(&inspection, &book, &tokens)
+}
+
+/// 按 ABI 或函数签名解码并打印 calldata
+///
+/// # 参数
+/// * `data` - calldata
+/// * `abi` - JSON ABI 文件路径
+/// * `sig` - 函数签名
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+fn run_decode_calldata(
+    data: &[u8],
+    abi: Option<&str>,
+    sig: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let functions = calldata::load_functions(abi, sig)?;
+    calldata::decode(data, &functions)?.print();
+    Ok(())
+}
+
+/// 读取链上交易的 input 并解码
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `tx_hash` - 交易哈希
+/// * `abi` - JSON ABI 文件路径
+/// * `sig` - 函数签名
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_decode_tx_calldata(
+    provider: &RpcProvider,
+    tx_hash: &str,
+    abi: Option<&str>,
+    sig: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let hash = parse_tx_hash(tx_hash).map_err(|e| tf(Msg::InvalidTxHash, &[&e]))?;
+    let tx = provider
+        .get_transaction(hash)
+        .await?
+        .ok_or_else(|| tf(Msg::CalldataTxNotFound, &[&format!("{:?}", hash)]))?;
+    run_decode_calldata(&tx.input, abi, sig)
+}
+
+/// 读取并解释区块号，需要时继续采样
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `samples` - 采样次数
+/// * `interval` - 采样间隔秒数
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_l1_block(
+    provider: &RpcProvider,
+    samples: usize,
+    interval: u64,
+) -> Result<(), Box<dyn Error>> {
+    let reading = l1_block::read_block_numbers(provider).await?;
+    l1_block::print_reading(&reading);
+
+    if samples > 1 {
+        println!();
+        l1_block::sample(provider, samples, Duration::from_secs(interval)).await?;
+    }
+    Ok(())
+}
+
+/// 读取区块范围内的 Gas 使用率并画出柱状图
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `from_block` - 起始区块，`None` 时取结束区块前 99 个区块
+/// * `to_block` - 结束区块，`None` 时为最新区块
+/// * `max_samples` - 最多读取的区块数
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run_gas_usage(
+    provider: &RpcProvider,
+    from_block: Option<u64>,
+    to_block: Option<u64>,
+    max_samples: u64,
+) -> Result<(), Box<dyn Error>> {
+    let to = match to_block {
+        Some(to) => to,
+        None => provider.get_block_number().await?.as_u64(),
+    };
+    let from = from_block.unwrap_or(to.saturating_sub(99));
+    let step = gas_usage::sample_step(from, to, max_samples);
+
+    println!("{}", tf(Msg::GasUsageFetching, &[&from, &to]));
+    let usages = gas_usage::gas_usage(provider, from, to, step).await?;
+    gas_usage::print_chart(&usages, step);
+    Ok(())
+}
+
+/// 面板显示余额的地址：命令行优先，其次 PRIVATE_KEY 对应的地址
+///
+/// # 参数
+/// * `address` - 命令行传入的地址
+///
+/// # 返回
+/// * `Result<Option<Address>, Box<dyn Error>>` - 都没有配置时为 `None`
+fn dashboard_address(address: Option<&str>) -> Result<Option<Address>, Box<dyn Error>> {
+    if let Some(address) = address {
+        let parsed = Address::from_str(address).map_err(|_| tf(Msg::InvalidAddress, &[&address]))?;
+        return Ok(Some(parsed));
+    }
+    dotenv::dotenv().ok(); // 加载 .env 文件（如果存在）
+    match env::var("PRIVATE_KEY") {
+        Some(key) => Ok(Some(keys::parse_private_key(&key)?.address())),
+        None => Ok(None),
+    }
+}
+
+/// 计算并打印哈希或函数选择器，几个输入由 clap 保证只会传一个
+///
+/// # 参数
+/// * `keccak` - UTF-8 字符串
+/// * `keccak_hex` - 十六进制字节串
+/// * `selector` - 函数签名
+/// * `packed` - 要紧凑编码的 `类型:值` 列表
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+fn run_hash(
+    keccak: Option<&str>,
+    keccak_hex: Option<&str>,
+    selector: Option<&str>,
+    packed: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    if let Some(text) = keccak {
+        println!("{:?}", hash::keccak_text(text));
+    } else if let Some(input) = keccak_hex {
+        println!("{:?}", hash::keccak_hex(input)?);
+    } else if let Some(signature) = selector {
+        let (canonical, selector) = hash::selector(signature)?;
+        println!("{}", tf(Msg::Selector, &[&hex::encode_prefixed(selector), &canonical]));
+    } else if let Some(values) = packed {
+        let bytes = hash::encode_packed(values)?;
+        println!("{}", tf(Msg::PackedBytes, &[&hex::encode_prefixed(&bytes)]));
+        let hash = H256::from(keccak256(&bytes));
+        println!("{}", tf(Msg::PackedKeccak, &[&format!("{:?}", hash)]));
+    }
+    Ok(())
+}
+
+/// 搜索靓号地址，找到后把私钥加密写入 keystore（或按要求直接打印）
+///
+/// 密码在开始搜索前读取，避免找到后才发现没有配置。
+///
+/// # 参数
+/// * `pattern` - 已校验的模式
+/// * `threads` - 线程数，`None` 时为 CPU 核心数
+/// * `keystore_dir` - keystore 保存目录
+/// * `print_key` - 直接打印私钥，不写 keystore
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果；Ctrl-C 停止不算失败
+async fn run_vanity(
+    pattern: vanity::Pattern,
+    threads: Option<usize>,
+    keystore_dir: &Path,
+    print_key: bool,
+) -> Result<(), Box<dyn Error>> {
+    dotenv::dotenv().ok(); // 加载 .env 文件（如果存在）
+    let password = match print_key {
+        true => None,
+        false => Some(wallet::password_from_env().map_err(|_| t(Msg::VanityNoPassword))?),
+    };
+    let threads = threads.unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    });
+
+    let difficulty = format!("{:.0}", pattern.difficulty());
+    println!("{}", tf(Msg::VanityDifficulty, &[&difficulty]));
+    println!("{}", tf(Msg::VanitySearching, &[&threads]));
+
+    match vanity::search(pattern, threads).await {
+        vanity::VanityOutcome::Found {
+            key,
+            address,
+            stats,
+        } => {
+            vanity::print_stats(&stats);
+            match password {
+                Some(password) => {
+                    let path = wallet::save_keystore(keystore_dir, &key, &password)?;
+                    vanity::print_found(&address, Some(&path), None);
+                }
+                None => vanity::print_found(&address, None, Some(&key)),
+            }
+        }
+        vanity::VanityOutcome::Stopped(stats) => {
+            println!("{}", style::warn(t(Msg::VanityStopped)));
+            vanity::print_stats(&stats);
+        }
+    }
+    Ok(())
+}
+
+/// 生成 shell 补全脚本
+///
+/// # 参数
+/// * `shell` - 目标 shell
+/// * `out` - 写入的位置
+fn write_completions(shell: Shell, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+    clap_complete::generate(shell, &mut Cli::command(), "arbi", out);
+    match book::name_completion(shell) {
+        Some(names) => out.write_all(names.as_bytes()),
+        None => Ok(()),
+    }
+}
+
+/// 执行地址簿子命令
+///
+/// # 参数
+/// * `file` - 地址簿文件
+/// * `action` - 要执行的操作
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+fn run_book(file: &Path, action: BookAction) -> Result<(), Box<dyn Error>> {
+    let mut book = book::AddressBook::load(file)?;
+    match action {
+        BookAction::Add { name, address } => {
+            let address = Address::from_str(&address)
+                .map_err(|_| tf(Msg::InvalidAddress, &[&address]))?;
+            book.insert(&name, address)?;
+            book.save(file)?;
+            println!("{}", style::ok(tf(Msg::BookAdded, &[&name, &to_checksum(&address, None)])));
+            Ok(())
+        }
+        BookAction::List { names_only: true } => {
+            print!("{}", book.names_only());
+            Ok(())
+        }
+        BookAction::List { names_only: false } => {
+            book.print();
+            Ok(())
+        }
+        BookAction::Show { name, qr } => {
+            let address = book.get(&name).ok_or_else(|| tf(Msg::BookNotFound, &[&name]))?;
+            println!("{}", tf(Msg::WalletAddress, &[&to_checksum(&address, None)]));
+            qr.print(&address)
+        }
+        BookAction::Remove { name } => {
+            book.remove(&name).ok_or_else(|| tf(Msg::BookNotFound, &[&name]))?;
+            book.save(file)?;
+            println!("{}", style::ok(tf(Msg::BookRemoved, &[&name])));
+            Ok(())
+        }
+    }
+}
+
+/// 执行子命令
+///
+/// 创建 Provider 不会发出请求，离线子命令（地址校验、哈希、单位换算等）不会连接节点。
+///
+/// # 参数
+/// * `command` - 子命令
+/// * `provider` - Provider 引用
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果
+async fn run(command: Command, provider: &RpcProvider) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::BlockTxs {
+            block,
+            address,
+            limit,
+            csv,
+        } => run_block_txs(provider, &block, address.as_deref(), limit, &csv).await,
+        Command::Inspect {
+            tx_hash,
+            abi,
+            no_fetch,
+        } => run_inspect(provider, &tx_hash, &abi, no_fetch).await,
+        Command::DetectStandard { address } => {
+            let address = Address::from_str(&address)
+                .map_err(|_| tf(Msg::InvalidAddress, &[&address]))?;
+            let detection = standard::detect_standard(provider, address).await?;
+            standard::print_detection(address, detection);
+            Ok(())
+        }
+        Command::Trace {
+            tx_hash,
+            abi,
+            max_depth,
+        } => run_trace(provider, &tx_hash, abi.as_deref(), max_depth).await,
+        Command::ArbFees => {
+            let fees = fees::arb_fees(provider).await?;
+            fees::print_report(&fees)
+        }
+        Command::L1Block { samples, interval } => run_l1_block(provider, samples, interval).await,
+        Command::FinalityMonitor {
+            interval,
+            threshold_minutes,
+            once,
+            json,
+            metrics_listen,
+        } => {
+            let interval = Duration::from_secs(interval);
+            let monitor = finality::monitor(provider, interval, threshold_minutes, once, json);
+            metrics::serve_while(metrics_listen, monitor).await
+        }
+        Command::SequencerStatus { stale_after } => {
+            let stale_after = Duration::from_secs(stale_after);
+            let status = sequencer::sequencer_status(provider, stale_after).await?;
+            sequencer::print_status(&status);
+            Ok(())
+        }
+        Command::L1Inclusion {
+            tx_hash,
+            wait,
+            confirmations,
+            interval,
+        } => {
+            let hash = parse_tx_hash(&tx_hash).map_err(|e| tf(Msg::InvalidTxHash, &[&e]))?;
+            let interval = Duration::from_secs(interval);
+            inclusion::l1_inclusion(provider, hash, confirmations, wait, interval).await?;
+            Ok(())
+        }
+        Command::ContractOrigin {
+            address,
+            cache_file,
+        } => {
+            let address = Address::from_str(&address)
+                .map_err(|_| tf(Msg::InvalidAddress, &[&address]))?;
+            let origin = origin::contract_origin(provider, address, &cache_file).await?;
+            origin::print_origin(&origin);
+            Ok(())
+        }
+        Command::GasUsage {
+            from_block,
+            to_block,
+            max_samples,
+        } => run_gas_usage(provider, from_block, to_block, max_samples).await,
+        Command::GasReport {
+            address,
+            from_block,
+            to_block,
+            csv,
+        } => {
+            let address = Address::from_str(&address)
+                .map_err(|_| tf(Msg::InvalidAddress, &[&address]))?;
+            let to_block = match to_block {
+                Some(block) => block,
+                None => provider.get_block_number().await?.as_u64(),
+            };
+            let entries =
+                gas_report::gas_entries(provider, address, from_block, to_block).await?;
+            gas_report::print_report(address, (from_block, to_block), &entries)?;
+            csv.write(&gas_report::to_table(&entries)?)
+        }
+        Command::GasSpent {
+            address,
+            last,
+            csv,
+        } => {
+            let address = Address::from_str(&address)
+                .map_err(|_| tf(Msg::InvalidAddress, &[&address]))?;
+            let last = last as usize;
+            let spent = gas_report::gas_spent(provider, address, last).await?;
+            gas_report::print_spent(address, last, &spent)?;
+            csv.write(&gas_report::to_table(&spent.entries)?)
+        }
+        Command::TokenHistory {
+            address,
+            token,
+            from_block,
+            to_block,
+            chunk_size,
+            csv,
+        } => {
+            let address = Address::from_str(&address)
+                .map_err(|_| tf(Msg::InvalidAddress, &[&address]))?;
+            let tokens = token_history::resolve_tokens(&token)?;
+            let to_block = match to_block {
+                Some(block) => block,
+                None => provider.get_block_number().await?.as_u64(),
+            };
+            let range = (from_block, to_block);
+            let transfers =
+                token_history::token_history(provider, address, &tokens, range, chunk_size)
+                    .await?;
+            let mut metas = HashMap::new();
+            for token in tokens {
+                if let Some(meta) = inspect::token_meta(provider, token).await {
+                    metas.insert(token, meta);
+                }
+            }
+            token_history::print_statement(&transfers, &metas);
+            csv.write(&token_history::to_table(&transfers, &metas))
+        }
+        Command::Holdings {
+            address,
+            from_block,
+            chunk_size,
+            csv,
+        } => {
+            let address = Address::from_str(&address)
+                .map_err(|_| tf(Msg::InvalidAddress, &[&address]))?;
+            let holdings =
+                holdings::discover_tokens(provider, address, from_block, chunk_size).await?;
+            holdings.print()?;
+            csv.write(&holdings.to_table())
+        }
+        Command::ExportHistory {
+            address,
+            from_block,
+            to_block,
+            output,
+            bom,
+        } => {
+            let address = Address::from_str(&address)
+                .map_err(|_| tf(Msg::InvalidAddress, &[&address]))?;
+            let range = (from_block, to_block);
+            let count = history::export_history(provider, address, range, &output, bom).await?;
+            println!("{}", style::ok(tf(Msg::HistoryExported, &[&count, &output.display()])));
+            Ok(())
+        }
+        Command::Account {
+            address,
+            blocks,
+            json,
+        } => {
+            let address = Address::from_str(&address)
+                .map_err(|_| tf(Msg::InvalidAddress, &[&address]))?;
+            let profile = account::account_profile(provider, address, blocks).await?;
+            match json {
+                true => println!("{}", serde_json::to_string_pretty(&profile.to_json())?),
+                false => profile.print()?,
+            }
+            Ok(())
+        }
+        Command::AccountActivity {
+            address,
+            cache_file,
+        } => {
+            let address = Address::from_str(&address)
+                .map_err(|_| tf(Msg::InvalidAddress, &[&address]))?;
+            let activity = activity::account_activity(provider, address, &cache_file).await?;
+            activity::print_activity(&activity);
+            Ok(())
+        }
+        Command::Dashboard {
+            address,
+            interval,
+            metrics_listen,
+        } => {
+            let address = dashboard_address(address.as_deref())?;
+            let interval = Duration::from_secs(interval);
+            let dashboard = dashboard::run_dashboard(provider, address, interval);
+            metrics::serve_while(metrics_listen, dashboard).await
+        }
+        Command::WatchPending {
+            ws_url,
+            details,
+            address,
+            max_fetch_per_sec,
+        } => {
+            let filter = address
+                .map(|address| {
+                    Address::from_str(&address)
+                        .map_err(|_| tf(Msg::InvalidAddress, &[&address]))
+                })
+                .transpose()?;
+            let ws_url = ws_url.as_deref().unwrap_or(network::current().ws_url());
+            let stats =
+                pending::watch_pending(ws_url, details, filter, max_fetch_per_sec).await?;
+            pending::print_stats(&stats, filter);
+            Ok(())
+        }
+        Command::Normalize { address } => {
+            let normalized = address::normalize(&address)?;
+            address::print_normalized(&normalized);
+            if normalized.checksum == address::ChecksumState::Invalid {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        Command::Hash {
+            keccak,
+            keccak_hex,
+            selector,
+            abi_encode_packed,
+        } => run_hash(
+            keccak.as_deref(),
+            keccak_hex.as_deref(),
+            selector.as_deref(),
+            abi_encode_packed.as_deref(),
+        ),
+        Command::Vanity {
+            prefix,
+            suffix,
+            checksum,
+            threads,
+            keystore_dir,
+            print_key,
+        } => {
+            let pattern = vanity::Pattern::new(
+                prefix.as_deref().unwrap_or_default(),
+                suffix.as_deref().unwrap_or_default(),
+                checksum,
+            )?;
+            run_vanity(pattern, threads, &keystore_dir, print_key).await
+        }
+        Command::Convert {
+            amount,
+            from,
+            to,
+            decimals,
+            json,
+        } => run_convert(&amount, from, to, decimals, json),
+        Command::DecodeRaw { input } => rawtx::load(&input)
+            .and_then(|raw| rawtx::decode_raw(&raw))
+            .and_then(|decoded| decoded.print()),
+        Command::DecodeCalldata {
+            data,
+            abi,
+            sig,
+            from_tx,
+        } => match (data, from_tx) {
+            (Some(data), _) => {
+                let bytes =
+                    hex::decode(data.trim()).map_err(|e| tf(Msg::InvalidHex, &[&data, &e]))?;
+                run_decode_calldata(&bytes, abi.as_deref(), sig.as_deref())
+            }
+            (None, Some(hash)) => {
+                run_decode_tx_calldata(provider, &hash, abi.as_deref(), sig.as_deref()).await
+            }
+            (None, None) => Err(t(Msg::CalldataMissingInput).into()),
+        },
+        Command::Wallet { action } => run_wallet(provider, action).await,
+        Command::Setup {
+            keystore_dir,
+            env_file,
+        } => setup::run(&keystore_dir, &env_file),
+        Command::Book { file, action } => run_book(&file, action),
+        Command::Completions { shell } => Ok(write_completions(shell, &mut std::io::stdout())?),
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    cli.global.init();
+
+    // 创建 HTTP Provider
+    let rpc_url = cli.rpc_url.as_deref().unwrap_or(network::current().rpc_url());
+    let provider = rpc::connect(rpc_url)?;
+
+    let span = info_span!("command", rpc_url);
+    if let Err(e) = run(cli.command, &provider).instrument(span).await {
+        eprintln!("\n{}", style::err(tf(Msg::CommandFailed, &[&e])));
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ValueEnum;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn completions_cover_every_shell_and_subcommand() {
+        for shell in Shell::value_variants() {
+            let mut script = Vec::new();
+            write_completions(*shell, &mut script).unwrap();
+            let script = String::from_utf8(script).expect("completion script is UTF-8");
+            for name in ["block-txs", "account-activity", "gas-spent", "book", "completions"] {
+                assert!(script.contains(name), "{} 的补全脚本缺少 {}", shell, name);
+            }
+        }
+    }
+
+    #[test]
+    fn book_names_complete_where_the_shell_supports_it() {
+        for shell in Shell::value_variants() {
+            let mut script = Vec::new();
+            write_completions(*shell, &mut script).unwrap();
+            let script = String::from_utf8(script).unwrap();
+            let dynamic = matches!(shell, Shell::Bash | Shell::Zsh | Shell::Fish);
+            let helper = script.contains("arbi book list --names-only");
+            assert_eq!(helper, dynamic, "{}", shell);
+        }
+    }
+
+    #[test]
+    fn names_only_flag_parses() {
+        let cli = Cli::try_parse_from(["arbi", "book", "list", "--names-only"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Book { action: BookAction::List { names_only: true }, .. }
+        ));
+    }
+}
@@ -0,0 +1,476 @@
+//! arbi 工具集输出的消息目录
+
+arbi_common::define_messages! {
+    CommandFailed => ("❌ 执行失败: {}", "❌ Failed: {}"),
+
+    InvalidBlockId => (
+        "无效的区块: {}（支持 latest / pending / finalized / safe / earliest、十进制或 0x 开头的十六进制区块号）",
+        "Invalid block: {} (use latest / pending / finalized / safe / earliest, a decimal or 0x-prefixed hex number)"
+    ),
+    BlockNotFound => ("未找到区块 {}", "Block {} not found"),
+    BlockHeader => ("区块 {}（{}），共 {} 笔交易", "Block {} ({}), {} transactions"),
+    BlockFilter => ("只显示涉及 {} 的交易：{} 笔", "Showing transactions involving {}: {}"),
+    BlockEmpty => ("区块中没有交易", "The block has no transactions"),
+    BlockNoMatch => ("没有涉及该地址的交易", "No transactions involve this address"),
+    BlockTxHash => ("交易哈希", "Tx hash"),
+    BlockTxFrom => ("发送方", "From"),
+    BlockTxTo => ("接收方", "To"),
+    BlockTxValue => ("金额 (ETH)", "Value (ETH)"),
+    ContractCreation => ("（创建合约）", "(contract creation)"),
+    MoreTxs => ("... 还有 {} 笔", "... and {} more"),
+
+    FeesBadTuple => ("getPricesInWei 返回值应为 6 个 uint256", "getPricesInWei should return 6 uint256 values"),
+    FeesTitle => ("=== Arbitrum 费用报告（ArbGasInfo）===", "=== Arbitrum fee report (ArbGasInfo) ==="),
+    FeesPerL2Gas => (
+        "每单位 L2 Gas: {} Gwei（基础 {} + 拥堵附加 {}）",
+        "Per L2 gas: {} gwei (base {} + congestion {})"
+    ),
+    FeesBlockBaseFee => ("最新区块基础费: {} Gwei（实际收取的 L2 Gas 价格）", "Latest block base fee: {} gwei (the L2 gas price actually charged)"),
+    FeesNoBlockBaseFee => ("最新区块没有基础费（链不支持 EIP-1559）", "The latest block has no base fee (no EIP-1559 on this chain)"),
+    FeesPerL2Tx => ("每笔交易固定的 L1 开销: {} wei", "Fixed L1 overhead per transaction: {} wei"),
+    FeesPerCalldataByte => ("发布到 L1 的每字节 calldata: {} wei", "Per calldata byte posted to L1: {} wei"),
+    FeesPerStorage => ("每个新分配的存储槽: {} wei", "Per new storage slot: {} wei"),
+    FeesL1BaseFee => ("L1 基础费估算: {} Gwei", "L1 base fee estimate: {} gwei"),
+    FeesCurrentTxL1 => ("本次查询调用的 L1 Gas 费: {} wei", "L1 gas fees of this query call: {} wei"),
+    FeesExamplesTitle => ("示例交易费用（ETH）:", "Illustrative costs (ETH):"),
+    FeesColExample => ("交易", "Transaction"),
+    FeesColL2Gas => ("L2 Gas", "L2 gas"),
+    FeesColBytes => ("calldata", "calldata"),
+    FeesColL2Cost => ("L2 执行费", "L2 execution"),
+    FeesColL1Cost => ("L1 数据费", "L1 data"),
+    FeesColTotal => ("合计", "Total"),
+    FeesExamplePlain => ("ETH 转账", "ETH transfer"),
+    FeesExampleErc20 => ("ERC20 transfer", "ERC20 transfer"),
+    FeesExampleCalldata => ("4KB calldata", "4KB calldata"),
+    FeesExamplesNote => (
+        "L1 数据费按未压缩大小（calldata + 约 {} 字节交易开销）计算，实际链上压缩后会更低",
+        "L1 data fees use the uncompressed size (calldata + ~{} bytes of tx overhead); the chain compresses, so real fees are lower"
+    ),
+
+    L1BlockBadOutput => ("{} 的返回值格式不对", "Unexpected return data from {}"),
+    L1BlockL2Header => ("区块头区块号（L2 高度）:          {}", "Header block number (L2 height):      {}"),
+    L1BlockArbBlockNumber => ("ArbSys.arbBlockNumber()（L2 高度）: {}", "ArbSys.arbBlockNumber() (L2 height):  {}"),
+    L1BlockL1Header => ("区块头 l1BlockNumber（L1 视角）:   {}", "Header l1BlockNumber (L1 view):       {}"),
+    L1BlockL1Evm => ("EVM 中的 block.number（L1 视角）:  {}", "EVM block.number (L1 view):           {}"),
+    L1BlockMissingField => ("（节点未返回）", "(not returned by the node)"),
+    L1BlockL2Mismatch => ("⚠ L2 高度不一致：区块头 {}，ArbSys {}", "⚠ L2 heights disagree: header {}, ArbSys {}"),
+    L1BlockL1Mismatch => ("⚠ L1 读数不一致：区块头 {}，EVM {}", "⚠ L1 readings disagree: header {}, EVM {}"),
+    L1BlockL1Agree => ("✓ 两个 L1 读数一致", "✓ Both L1 readings agree"),
+    L1BlockSample => ("采样 {}: L2 {}  L1 {}", "Sample {}: L2 {}  L1 {}"),
+    L1BlockAdvance => ("期间 L2 前进 {} 个区块，L1 前进 {} 个区块", "L2 advanced {} blocks while L1 advanced {}"),
+    L1BlockRatio => ("平均每 {} 个 L2 区块 L1 区块号前进 1", "The L1 block number advanced once every {} L2 blocks"),
+    L1BlockNoL1Advance => ("采样期间 L1 区块号没有变化，可以增大 --samples 或 --interval", "The L1 block number did not change; try a larger --samples or --interval"),
+
+    OriginNoCode => ("地址 {} 上没有合约代码（普通账户或尚未部署）", "No contract code at {} (an EOA or not deployed yet)"),
+    OriginExplorerFallback => ("⚠ 区块浏览器查询失败（{}），改用二分查找区块", "⚠ Explorer lookup failed ({}); falling back to a block binary search"),
+    OriginCacheFailed => ("⚠ 写入缓存失败: {}", "⚠ Failed to write the cache: {}"),
+    OriginDeployer => ("部署者:   {}", "Deployer:       {}"),
+    OriginBlock => ("创建区块: {}", "Creation block: {}"),
+    OriginTxHash => ("部署交易: {}", "Creation tx:    {}"),
+    OriginInternal => ("（由其他合约内部创建，区块中没有对应的部署交易）", "(created internally by another contract; no deployment tx in the block)"),
+    OriginSource => ("来源:     {}", "Source:         {}"),
+    OriginSourceExplorer => ("区块浏览器 API", "block explorer API"),
+    OriginSourceSearch => ("二分查找区块", "block binary search"),
+    OriginSourceCache => ("本地缓存", "local cache"),
+
+    GasUsageBadRange => ("起始区块 {} 大于结束区块 {}", "Start block {} is after end block {}"),
+    GasUsageFetching => ("读取区块 {} 到 {} 的 Gas 使用情况...", "Fetching gas usage for blocks {} to {}..."),
+    GasUsageEmpty => ("范围内没有查到区块", "No blocks found in the range"),
+    GasUsageSampled => ("⚠ 区块较多，每 {} 个区块采样一个（用 --max-samples 调整）", "⚠ Large range: sampling every {} blocks (adjust with --max-samples)"),
+    GasUsageNominalLimit => (
+        "⚠ Arbitrum 区块头中的 Gas 上限是固定的占位值，使用率会非常低；拥堵体现在 Gas 用量的起伏和基础费上，柱子按范围内最高值缩放",
+        "⚠ Arbitrum block headers carry a nominal placeholder gas limit, so utilization is tiny; congestion shows up in gas-used swings and the base fee, and bars are scaled to the range maximum"
+    ),
+    GasUsageColBlock => ("区块", "Block"),
+    GasUsageColTime => ("时间戳", "Timestamp"),
+    GasUsageColGasUsed => ("Gas 用量", "Gas used"),
+    GasUsageColRatio => ("使用率", "Usage"),
+    GasUsageColChart => ("柱状图", "Chart"),
+    GasUsageSummary => ("共 {} 个区块，平均使用率 {}%，Gas 用量最高的区块 {}", "{} blocks, average usage {}%, busiest block {}"),
+
+    FinalityMissingTag => ("节点没有返回 {} 区块（可能不支持该标签）", "The node returned no {} block (the tag may be unsupported)"),
+    FinalityStart => (
+        "每 {} 秒读取一次 latest / safe / finalized，finalized 落后超过 {} 分钟时报警，按 Ctrl-C 退出\n",
+        "Polling latest / safe / finalized every {}s, alerting when finalized lags more than {} minutes; press Ctrl-C to stop\n"
+    ),
+    FinalityLine => (
+        "latest {}  safe {}（落后 {} 块，约 {} 分钟）  finalized {}（落后 {} 块，约 {} 分钟）",
+        "latest {}  safe {} ({} blocks, ~{} min behind)  finalized {} ({} blocks, ~{} min behind)"
+    ),
+    SequencerNoBlock => ("节点没有返回最新区块", "The node did not return the latest block"),
+    SequencerHealthy => ("✅ 排序器正常（最新区块 {} 前产生，区块号 {}）", "✅ Sequencer healthy (last block {} ago, #{})"),
+    SequencerStale => (
+        "⚠ 最新区块 #{} 已是 {} 前产生，超过 {} 秒没有新区块，排序器可能出现问题",
+        "⚠ Latest block #{} is {} old, no new block for over {}s; the sequencer may be having issues"
+    ),
+    SequencerStaleHint => (
+        "Arbitrum 由单一排序器出块；排序器停摆时交易仍可经 L1 delayed inbox 强制打包（约 24 小时后）。也可能只是节点落后，可换一个 --rpc-url 再试",
+        "Arbitrum blocks come from a single sequencer; if it is down, transactions can still be force-included via the L1 delayed inbox (after about 24h). The node itself may also be lagging, so try another --rpc-url"
+    ),
+    FinalityAlert => (
+        "⚠ finalized 落后超过阈值：可能是 batch poster 暂停发布批次，或 L1 迟迟没有最终确定",
+        "⚠ Finalized lag is above the threshold: the batch poster may have stalled, or L1 is slow to finalize"
+    ),
+    FinalityPollFailed => ("读取失败，跳过这一轮: {}", "Poll failed, skipping this round: {}"),
+    FinalityStats => (
+        "共 {} 次采样，finalized 落后区块数：最小 {}，平均 {}，最大 {}；报警 {} 次",
+        "{} samples; finalized lag in blocks: min {}, mean {}, max {}; {} alerts"
+    ),
+
+    PendingConnectFailed => ("无法连接 WebSocket 节点 {}: {}", "Cannot connect to WebSocket node {}: {}"),
+    PendingUnsupported => (
+        "节点 {} 不支持订阅待处理交易（newPendingTransactions）: {}",
+        "Node {} does not support pending transaction subscriptions (newPendingTransactions): {}"
+    ),
+    PendingSubscribed => ("✓ 已订阅 {} 的待处理交易，按 Ctrl-C 退出", "✓ Subscribed to pending transactions on {}; press Ctrl-C to stop"),
+    PendingStreamClosed => ("⚠ 节点关闭了订阅", "⚠ The node closed the subscription"),
+    PendingFetchFailed => ("⚠ 读取交易 {} 失败: {}", "⚠ Failed to fetch transaction {}: {}"),
+    PendingNothingSeen => (
+        "⚠ 没有收到任何待处理交易：Arbitrum 的交易直接发给排序器，没有公共内存池，多数节点不会推送；可以换用以太坊 Sepolia 的 WebSocket 节点观察",
+        "⚠ No pending transactions received: Arbitrum transactions go straight to the sequencer with no public mempool, so most nodes push nothing; try an Ethereum Sepolia WebSocket node instead"
+    ),
+    PendingSummary => (
+        "共收到 {} 个交易哈希，读取详情 {} 次，限流跳过 {} 个，读取时已上链或被丢弃 {} 个",
+        "Received {} transaction hashes, fetched {} details, skipped {} by rate limit, {} already mined or dropped"
+    ),
+    PendingMatched => ("其中涉及过滤地址的交易: {} 笔", "Transactions involving the filter address: {}"),
+
+    InclusionTxNotFound => ("交易 {} 不存在或还没有上链", "Transaction {} was not found or is not mined yet"),
+    InclusionBlock => ("L2 区块: {}（{}）", "L2 block: {} ({})"),
+    InclusionAwaitingBatch => (
+        "⏳ 等待打包：区块还没有进入已发布到 L1 的批次，排序器通常几分钟内发布一批",
+        "⏳ Awaiting batch: the block is not in any batch posted to L1 yet; the sequencer usually posts within minutes"
+    ),
+    InclusionPosted => ("✓ 已发布到 L1，批次号 {}", "✓ Posted to L1 in batch {}"),
+    InclusionConfirmations => ("L1 确认数: {}（目标 {}）", "L1 confirmations: {} (target {})"),
+    InclusionWaiting => ("  ... {} 秒后再查询", "  ... checking again in {}s"),
+
+    HistoryScanning => (
+        "⚠ 未设置 {}，逐块扫描交易（较慢，且找不到合约内部转入的 ETH）",
+        "⚠ {} is not set; scanning blocks one by one (slow, and misses ETH sent by internal calls)"
+    ),
+    HistoryRangeTooLarge => (
+        "逐块扫描最多支持 {} 个区块，请缩小 --from-block / --to-block 范围或设置 {}",
+        "Block scanning supports at most {} blocks; narrow --from-block / --to-block or set {}"
+    ),
+    HistoryBadRecord => ("区块浏览器返回了无法解析的交易记录: {}", "The block explorer returned an unparseable record: {}"),
+    HistoryExported => ("✅ 已导出 {} 笔交易到 {}", "✅ Exported {} transactions to {}"),
+
+    GasReportScanning => (
+        "⚠ 未设置 {}，分段扫描区块查找发出的交易（较慢）",
+        "⚠ {} is not set; scanning blocks in chunks for outgoing transactions (slow)"
+    ),
+    GasReportProgress => ("已扫描 {} / {} 个区块，找到 {} 笔交易", "Scanned {} / {} blocks, found {} transactions"),
+    GasReportTitle => ("=== {} 的 Gas 花费（区块 {} - {}）===", "=== Gas spent by {} (blocks {} - {}) ==="),
+    GasReportEmpty => ("范围内没有该地址发出的交易", "No outgoing transactions from this address in the range"),
+    GasReportColHash => ("交易哈希", "Transaction hash"),
+    GasReportColPrice => ("价格 (Gwei)", "Price (Gwei)"),
+    GasReportColFee => ("费用 (ETH)", "Fee (ETH)"),
+    GasReportReverted => ("失败", "reverted"),
+    GasReportTotal => ("总花费:   {} ETH（{} 笔交易）", "Total:     {} ETH ({} transactions)"),
+    GasReportAverage => ("平均每笔: {} ETH", "Average:   {} ETH per transaction"),
+    GasReportSucceeded => ("成功:     {} 笔，{} ETH", "Succeeded: {}, {} ETH"),
+    GasSpentNeedsKey => ("按最近交易统计需要区块浏览器 API，请设置 {}", "Listing recent transactions needs the block explorer API; set {}"),
+    GasSpentTitle => ("=== {} 最近 {} 笔发出交易的 Gas 花费 ===", "=== Gas spent by {} over the last {} outgoing transactions ==="),
+    GasSpentPendingSkipped => ("⚠ 跳过 {} 笔还没有打包的交易", "⚠ Skipped {} transactions that are not mined yet"),
+    GasSpentEmpty => ("该地址没有发出过交易", "This address has not sent any transactions"),
+    GasSpentFewer => ("（该地址一共只发出过 {} 笔交易）", "(the address has only sent {} transactions in total)"),
+    GasSpentScanLimit => ("⚠ 已达到扫描上限：区块浏览器最多列出 {} 笔交易，其中只有 {} 笔是该地址发出的，更早的交易没有统计", "⚠ Scan limit reached: the block explorer lists at most {} transactions and only {} of them were sent by this address; older ones are not counted"),
+    GasReportRevertedTotal => ("失败:     {} 笔，{} ETH（白花的 Gas）", "Reverted:  {}, {} ETH (gas spent for nothing)"),
+
+    TokenHistoryEmpty => ("范围内没有该地址的代币转账", "No token transfers for this address in the range"),
+    TokenHistoryColDirection => ("方向", "Dir"),
+    TokenHistoryColCounterparty => ("对方地址", "Counterparty"),
+    TokenHistoryColAmount => ("数量", "Amount"),
+    TokenHistoryColToken => ("代币", "Token"),
+    TokenHistoryIn => ("转入", "in"),
+    TokenHistoryOut => ("转出", "out"),
+    TokenHistorySelf => ("自转", "self"),
+    TokenHistoryNetTitle => ("净变化:", "Net change:"),
+    TokenHistoryNet => ("  {}: 转入 {}，转出 {}，净 {}", "  {}: in {}, out {}, net {}"),
+    HoldingsTitle => ("=== {} 的持仓 ===", "=== Holdings of {} ==="),
+    HoldingsRange => (
+        "代币来自区块 {}–{} 中转入该地址的 Transfer 日志，余额按结束区块读取",
+        "Tokens found in Transfer logs into this address in blocks {}–{}; balances read at the end block"
+    ),
+    HoldingsNative => ("原生 ETH: {}", "Native ETH: {}"),
+    HoldingsFound => ("收到过 {} 个代币合约，{} 个余额不为 0:", "Received {} token contracts, {} with a non-zero balance:"),
+    HoldingsNone => ("收到过 {} 个代币合约，当前余额都为 0", "Received {} token contracts, none with a non-zero balance"),
+    HoldingsColBalance => ("余额", "Balance"),
+    HoldingsColContract => ("合约地址", "Contract"),
+    HoldingsUnreadable => (
+        "另有 {} 个合约读不到 balanceOf（不是 ERC20），已跳过",
+        "Skipped {} contracts whose balanceOf could not be read (not ERC20)"
+    ),
+    HoldingsRangeTooLarge => (
+        "区块范围 {}–{} 超过 {} 个区块，请调大 --from-block",
+        "Block range {}–{} exceeds {} blocks; use a later --from-block"
+    ),
+
+    DashboardTitle => (" Arbitrum 实时面板 ", " Arbitrum Dashboard "),
+    DashboardWallet => ("钱包:     ", "Wallet:    "),
+    DashboardBalance => ("余额:     ", "Balance:   "),
+    DashboardGasPrice => ("Gas 价格: ", "Gas price: "),
+    DashboardNoWallet => ("（未配置，用 --address 或 PRIVATE_KEY 指定）", "(not configured; use --address or PRIVATE_KEY)"),
+    DashboardEth => ("{} ETH", "{} ETH"),
+    DashboardGwei => ("{} Gwei", "{} Gwei"),
+    DashboardBlocks => (" 新区块 ", " New blocks "),
+    DashboardColBlock => ("区块", "Block"),
+    DashboardColTime => ("时间戳", "Timestamp"),
+    DashboardColTxs => ("交易数", "Txs"),
+    DashboardColGasUsed => ("Gas 用量", "Gas used"),
+    DashboardHelp => ("每 {} 秒刷新，按 q 退出", "Refreshing every {}s, press q to quit"),
+    DashboardRefreshFailed => ("刷新失败: {}", "Refresh failed: {}"),
+    DashboardLine => (
+        "区块 {}（新增 {} 个） | Gas 价格 {} | 余额 {}",
+        "Block {} ({} new) | gas price {} | balance {}"
+    ),
+
+    InvalidAddress => (
+        "无效的地址: {}（应为 40 位十六进制字符，可带 0x 前缀）",
+        "Invalid address: {} (expected 40 hex characters, optionally 0x-prefixed)"
+    ),
+    VanityInvalidPattern => ("模式 {} 含有非十六进制字符", "Pattern {} contains non-hex characters"),
+    VanityEmptyPattern => ("请至少指定 --prefix 或 --suffix 之一", "Specify at least one of --prefix or --suffix"),
+    VanityPatternTooLong => (
+        "模式共 {} 个字符，超过上限 {}：每多一个字符难度乘以 16，已经无法在合理时间内找到",
+        "The pattern has {} characters, above the limit of {}: each extra character multiplies the work by 16"
+    ),
+    VanityNoPassword => (
+        "没有设置 KEYSTORE_PASSWORD 环境变量，无法加密 keystore（或用 --print-key 直接打印私钥）",
+        "KEYSTORE_PASSWORD is not set, so the keystore cannot be encrypted (or use --print-key to print the key)"
+    ),
+    VanityDifficulty => ("难度: 平均需要尝试 {} 次", "Difficulty: {} attempts on average"),
+    VanitySearching => ("使用 {} 个线程搜索，Ctrl-C 停止...", "Searching on {} threads, Ctrl-C to stop..."),
+    VanityProgress => ("已尝试 {} 次，{} 次/秒", "{} attempts, {} per second"),
+    VanityEta => ("按当前速度，有 50% 概率在 {} 内找到", "At this rate there is a 50% chance of a match within {}"),
+    VanityStopped => ("⚠ 已停止，没有找到匹配的地址", "⚠ Stopped without finding a match"),
+    VanityStats => ("共尝试 {} 次，用时 {} 秒，平均 {} 次/秒", "{} attempts in {}s, {} per second on average"),
+    VanityFound => ("✅ 找到地址: {}", "✅ Found address: {}"),
+    VanityKeystoreSaved => ("私钥已加密保存到: {}", "Key saved encrypted to: {}"),
+    VanityKeyWarning => ("⚠ 私钥如下，请立即妥善保存并清除终端记录：", "⚠ Private key below; store it safely and clear your terminal history:"),
+    ConvertResult => ("{} {} = {} {}", "{} {} = {} {}"),
+    ConvertNeedsDecimals => ("raw / token 单位需要 --decimals", "The raw / token units need --decimals"),
+    ConvertMissingUnits => (
+        "请给出来源和目标单位（wei / gwei / eth / raw / token），或用 --decimals 换算代币数量",
+        "Give the source and target units (wei / gwei / eth / raw / token), or pass --decimals for token amounts"
+    ),
+    WalletNoPassword => (
+        "没有设置 {} 环境变量，无法加密 / 解密 keystore",
+        "{} is not set, so the keystore cannot be encrypted or decrypted"
+    ),
+    WalletKeystoreExists => ("keystore 文件已存在，不会覆盖: {}", "Keystore already exists, not overwriting: {}"),
+    WalletDecryptFailed => (
+        "无法解密 keystore {}（密码错误或文件损坏）: {}",
+        "Could not decrypt keystore {} (wrong password or corrupt file): {}"
+    ),
+    WalletCreated => ("✅ 已生成新钱包", "✅ New wallet created"),
+    WalletAddress => ("地址（EIP-55 校验格式）: {}", "Address (EIP-55 checksummed): {}"),
+    WalletAddressLower => ("地址（小写格式）:        {}", "Address (lowercase):          {}"),
+    WalletKeystoreSaved => ("私钥已加密保存到: {}（权限 0600）", "Key saved encrypted to: {} (mode 0600)"),
+    WalletMnemonicWarning => (
+        "⚠ 助记词如下，只显示这一次。请抄写在纸上离线保存，任何人拿到它都能转走资金：",
+        "⚠ Mnemonic below, shown only once. Write it down offline; anyone who has it controls the funds:"
+    ),
+    WalletEnvHint => ("把下面这行加到 .env 中即可使用该钱包：", "Add this line to .env to use the wallet:"),
+    WalletFaucetHint => (
+        "领取 Arbitrum Sepolia 测试币（或在 Sepolia 领取后通过官方桥转入）：",
+        "Get Arbitrum Sepolia test ETH (or claim on Sepolia and bridge it over):"
+    ),
+    SetupTitle => ("=== arbi 首次使用向导 ===", "=== arbi first-time setup ==="),
+    SetupNeedsTerminal => ("setup 是交互式向导，需要在终端里运行", "setup is an interactive wizard and must be run in a terminal"),
+    SetupAnswerYesNo => ("请输入 y 或 n", "Please answer y or n"),
+    SetupAskKeystore => ("用密码加密，把私钥保存为 keystore 文件？", "Encrypt the key with a password and save it as a keystore file?"),
+    SetupPasswordPrompt => ("keystore 密码（不回显）: ", "Keystore password (hidden): "),
+    SetupPasswordConfirm => ("再输入一次: ", "Repeat it: "),
+    SetupPasswordEmpty => ("⚠ 密码不能为空", "⚠ The password must not be empty"),
+    SetupPasswordMismatch => ("⚠ 两次输入的密码不一致，请重新输入", "⚠ The passwords do not match, try again"),
+    SetupPasswordFailed => ("{} 次都没有输入一致的密码，已取消", "No matching password after {} attempts; cancelled"),
+    SetupAskShowKey => (
+        "不保存 keystore 的话，要在终端里显示一次私钥以便手动保存吗？",
+        "Without a keystore, show the private key once in the terminal so you can store it yourself?"
+    ),
+    SetupKeyDiscarded => (
+        "⚠ 私钥没有保存，也没有显示，这个钱包随程序退出丢弃，不要往这个地址转账",
+        "⚠ The private key was neither saved nor shown; this wallet is discarded on exit, do not send funds to it"
+    ),
+    SetupAskOverwrite => ("{} 已存在，覆盖它吗？", "{} already exists. Overwrite it?"),
+    SetupEnvWritten => ("✅ 已写入 .env 模板: {}（权限 0600，不含私钥）", "✅ Wrote the .env template: {} (mode 0600, no private key)"),
+    SetupEnvKept => ("保留现有的 {}，需要时把下面的内容手动合并进去：", "Keeping the existing {}; merge the lines below by hand if needed:"),
+    SetupEnvHeader => (
+        "由 arbi setup 生成。这个文件可能包含密钥，不要提交到 git",
+        "Generated by arbi setup. This file may hold secrets; never commit it to git"
+    ),
+    SetupEnvNetwork => ("网络: arbitrum-sepolia（测试网）或 arbitrum-one（主网）", "Network: arbitrum-sepolia (testnet) or arbitrum-one (mainnet)"),
+    SetupEnvAddress => ("新钱包地址: {}（仅供参考，不会被读取）", "New wallet address: {} (for reference, not read by any tool)"),
+    SetupEnvPassword => (
+        "keystore 密码：wallet show 等命令从这里读取；留空更安全，用时再在命令行前设置",
+        "Keystore password used by wallet show and friends; safer left empty and set per command"
+    ),
+    SetupEnvPrivateKey => (
+        "level4 转账读取的私钥。setup 不会把私钥明文写进文件，需要时自行填写",
+        "Private key read by the level4 transfers. setup never writes the key here in plaintext; fill it in yourself if needed"
+    ),
+    SetupEnvTransfer => ("level4 转账的默认接收地址和金额（ETH）", "Default recipient and amount (ETH) for the level4 transfer"),
+    SetupNextStep => (
+        "下一步: 在 level2-balance-query 目录运行 `cargo run -- {}` 查看余额",
+        "Next: run `cargo run -- {}` in level2-balance-query to check the balance"
+    ),
+    WalletKeyPrompt => ("未设置 {}，请输入私钥（不回显）: ", "{} is not set; enter the private key (hidden): "),
+    WalletMnemonicPrompt => ("未设置 {}，请输入助记词（不回显）: ", "{} is not set; enter the mnemonic (hidden): "),
+    WalletThrowawayWarning => (
+        "⚠ 仅限测试网！私钥以明文显示在终端里，随时可能被他人看到，绝不能用来存放主网资金",
+        "⚠ TESTNET ONLY! The private key is shown in plain text and may be seen by others; never use it for mainnet funds"
+    ),
+    WalletThrowawayMainnet => (
+        "⚠ 当前网络是 {}：不要往这些地址转入任何真实资产",
+        "⚠ The current network is {}: do not send any real assets to these addresses"
+    ),
+    WalletThrowawayKey => ("私钥: {}", "Private key: {}"),
+    WalletThrowawayMnemonic => ("助记词: {}", "Mnemonic: {}"),
+    WalletInputCancelled => ("已取消输入", "Input cancelled"),
+    WalletAccountsOn => ("{} 上的地址:", "Addresses on {}:"),
+    NormalizeChecksummed => ("EIP-55 校验格式: {}", "EIP-55 checksummed: {}"),
+    NormalizeLowercase => ("小写格式:       {}", "Lowercase:          {}"),
+    ChecksumValid => ("✓ 输入的大小写校验正确", "✓ Input checksum is valid"),
+    ChecksumInvalid => (
+        "❌ 输入的大小写不符合 EIP-55 校验，地址可能抄错了",
+        "❌ Input checksum is invalid; the address may have a typo"
+    ),
+    ChecksumUnchecked => (
+        "⚠ 输入是全小写 / 全大写，不带校验信息，无法发现抄错",
+        "⚠ Input is all lowercase / uppercase and carries no checksum; typos cannot be detected"
+    ),
+
+    InvalidHex => ("无效的十六进制字节串 {}: {}", "Invalid hex bytes {}: {}"),
+    InvalidSignature => (
+        "无效的函数签名: {}（应为 `名称(类型,...)`，如 transfer(address,uint256)）",
+        "Invalid function signature: {} (expected `name(type,...)`, e.g. transfer(address,uint256))"
+    ),
+    Selector => ("选择器 {}  {}", "Selector {}  {}"),
+    PackedBytes => ("encodePacked: {}", "encodePacked: {}"),
+    PackedKeccak => ("keccak256:    {}", "keccak256:    {}"),
+    PackedInvalidItem => (
+        "无效的参数 {}：应为逗号分隔的 类型:值，如 address:0x..,uint256:5",
+        "Invalid argument {}: expected comma-separated type:value pairs, e.g. address:0x..,uint256:5"
+    ),
+    PackedInvalidType => ("无效的 ABI 类型: {}", "Invalid ABI type: {}"),
+    PackedInvalidValue => ("{} 不是有效的 {} 值", "{} is not a valid {} value"),
+    PackedOutOfRange => ("{} 超出 {} 的范围", "{} is out of range for {}"),
+    PackedUnsupported => (
+        "encodePacked 不支持 {}（结构体、嵌套数组和动态类型的数组都不能紧凑编码）",
+        "encodePacked does not support {} (structs, nested arrays and arrays of dynamic types cannot be packed)"
+    ),
+    PackedArrayLength => ("{} 需要 {} 个元素，实际为 {}", "{} needs {} elements, got {}"),
+
+    CalldataTxNotFound => ("交易 {} 不存在", "Transaction {} was not found"),
+    CalldataMissingInput => ("需要 calldata 或 --from-tx", "Pass calldata or --from-tx"),
+    CalldataTooShort => (
+        "calldata 只有 {} 字节，不足 4 字节的函数选择器",
+        "The calldata is only {} bytes, shorter than a 4-byte function selector"
+    ),
+    CalldataInvalidSignature => ("无效的函数签名 {}: {}", "Invalid function signature {}: {}"),
+    CalldataArgsFailed => ("按 {} 解码参数失败: {}", "Cannot decode the arguments as {}: {}"),
+    CalldataSelector => ("函数:   {}  {}", "Function: {}  {}"),
+    CalldataNoMatch => (
+        "（没有匹配的函数，按 32 字节逐字显示）",
+        "(no matching function; showing 32-byte words)"
+    ),
+    CalldataGuessInt => ("整数 {}", "int {}"),
+    CalldataGuessAddress => ("地址? {}", "address? {}"),
+    CalldataTrailing => ("  末尾多出 {} 字节: {}", "  {} trailing bytes: {}"),
+
+    InvalidTxHash => ("无效的交易哈希: {}", "Invalid transaction hash: {}"),
+    StandardTitle => ("=== {} 的接口检测（ERC165）===", "=== Interface detection for {} (ERC165) ==="),
+    StandardErc165 => ("ERC165:               {}", "ERC165:               {}"),
+    StandardInterface => ("{} ({}): {}", "{} ({}): {}"),
+    StandardYes => ("支持", "yes"),
+    StandardNo => ("不支持", "no"),
+    StandardResult => ("结论:                 {}", "Result:               {}"),
+    StandardNotContract => ("不是合约（地址上没有代码）", "not a contract (no code at this address)"),
+    StandardErc20Like => ("未知 / 类 ERC20（没有实现 ERC165）", "unknown / ERC20-like (no ERC165)"),
+    StandardErc165Only => (
+        "实现了 ERC165，但不是 ERC721 / ERC1155",
+        "implements ERC165, but neither ERC721 nor ERC1155"
+    ),
+    InspectNotMined => ("交易 {} 不存在或还没有上链", "Transaction {} was not found or is not mined yet"),
+    InspectAbisFetched => ("已从区块浏览器获取 {} 个合约的 ABI", "Fetched ABIs for {} contracts from the block explorer"),
+    InspectTitle => ("=== 交易 {} ===", "=== Transaction {} ==="),
+    InspectStatus => ("状态:     {}", "Status:   {}"),
+    InspectSuccess => ("成功", "success"),
+    InspectReverted => ("失败（已回滚）", "reverted"),
+    InspectFrom => ("发送方:   {}", "From:     {}"),
+    InspectTo => ("接收方:   {}", "To:       {}"),
+    InspectCreated => ("创建合约: {}", "Created:  {}"),
+    InspectValue => ("金额:     {} ETH", "Value:    {} ETH"),
+    InspectNonce => ("Nonce:    {}", "Nonce:    {}"),
+    InspectBlockTitle => ("区块:", "Block:"),
+    InspectBlock => ("  区块 {}，第 {} 笔（共 {} 笔）", "  Block {}, index {} of {}"),
+    InspectTimestamp => ("  时间戳: {}", "  Timestamp: {}"),
+    InspectBaseFee => ("  基础费: {}", "  Base fee: {}"),
+    InspectL1Block => ("  L1 区块: {}", "  L1 block: {}"),
+    InspectGasTitle => ("Gas:", "Gas:"),
+    InspectGasUsed => ("  使用 {} / 限额 {}", "  Used {} of limit {}"),
+    InspectEffectivePrice => ("  实际价格: {}", "  Effective price: {}"),
+    InspectMaxFees => ("  最高价格: {}，最高小费: {}", "  Max fee: {}, max priority fee: {}"),
+    InspectGasSplit => ("  其中 L2 执行 {}，L1 数据 {}", "  Of which L2 execution {}, L1 data {}"),
+    InspectTotalCost => ("  总费用: {} ETH", "  Total cost: {} ETH"),
+    InspectCallTitle => ("调用:", "Call:"),
+    InspectInitCode => ("部署合约，初始化代码 {} 字节", "Contract deployment, {} bytes of init code"),
+    InspectLogsTitle => ("事件（{} 条，按合约分组）:", "Events ({}, grouped by contract):"),
+    InspectUnknownEvent => ("（没有匹配的 ABI）", "(no matching ABI)"),
+    InspectFlowsTitle => ("价值流动（按地址轧差）:", "Value flows (netted per address):"),
+    InspectNoFlows => ("（无）", "(none)"),
+    InspectRevertedFlows => (
+        "交易失败，金额和事件都已回滚，没有实际转移",
+        "The transaction reverted; its value and events were rolled back and nothing moved"
+    ),
+    AccountTitle => ("=== 账户 {} ===", "=== Account {} ==="),
+    AccountBalance => ("余额:         {} ETH", "Balance:        {} ETH"),
+    AccountNonce => ("Nonce:        {}", "Nonce:          {}"),
+    AccountKind => ("类型:         {}", "Type:           {}"),
+    AccountEoa => ("普通账户（无代码）", "externally owned (no code)"),
+    AccountContract => ("合约（代码 {} 字节）", "contract ({} bytes of code)"),
+    AccountFirstOutgoing => ("第一笔发出:   {}", "First outgoing: {}"),
+    AccountNeverSent => ("从未发出交易", "never sent a transaction"),
+    AccountFirstContract => ("不适用（合约的 nonce 是创建的合约数）", "n/a (a contract's nonce counts contracts it created)"),
+    AccountFirstFound => ("区块 {}，时间戳 {}{}", "block {}, timestamp {}{}"),
+    AccountFirstUnavailable => ("无法查询（需要归档节点）: {}", "unavailable (needs an archive node): {}"),
+    AccountActivity => (
+        "最近活动:     区块 {} - {} 中发出 {} 笔、收到 {} 笔",
+        "Recent:         blocks {} - {}: {} sent, {} received"
+    ),
+    AccountLastSeen => ("最近出现:     区块 {}", "Last seen:      block {}"),
+    ActivityNonceOnly => (
+        "⚠ 没有设置 {}，按 nonce 二分查找区块：只统计账户发出的交易，需要归档节点",
+        "⚠ {} is not set; searching blocks by nonce: only transactions sent by the account are counted, and an archive node is needed"
+    ),
+    ActivityStateUnavailable => (
+        "节点没有区块 {} 的状态（{}），按 nonce 查找需要归档节点，或设置 ARBISCAN_API_KEY 改用浏览器 API",
+        "The node has no state for block {} ({}); the nonce search needs an archive node, or set ARBISCAN_API_KEY to use the explorer API"
+    ),
+    ActivityNone => ("截至区块 {} 没有找到交易", "No transactions found up to block {}"),
+    ActivityFirst => ("第一笔交易: 区块 {}，时间戳 {}，{}", "First tx:  block {}, timestamp {}, {}"),
+    ActivityLast => ("最近一笔:   区块 {}，时间戳 {}，{}", "Latest tx: block {}, timestamp {}, {}"),
+    ActivityHashMissing => ("（区块中没有找到对应交易）", "(no matching transaction in the block)"),
+    ActivityCount => ("交易总数:   {}（发出和收到的外部交易）", "Total txs: {} (external transactions sent and received)"),
+    ActivityCountSent => ("交易总数:   {}（只含账户发出的交易）", "Total txs: {} (sent by the account only)"),
+    ActivitySourceNonce => ("按 nonce 二分查找区块", "block binary search by nonce"),
+    ActivitySource => ("来源:       {}，截至区块 {}", "Source:    {}, as of block {}"),
+
+    BookAdded => ("✅ 已保存 {} → {}", "✅ Saved {} → {}"),
+    BookRemoved => ("✅ 已删除 {}", "✅ Removed {}"),
+    BookNotFound => ("地址簿里没有名字 {}", "No entry named {} in the address book"),
+    BookEmpty => ("地址簿是空的，用 arbi book add <名字> <地址> 添加", "The address book is empty; add entries with arbi book add <name> <address>"),
+    BookInvalidName => ("名字不能为空，也不能含空白字符: \"{}\"", "Names must be non-empty and contain no whitespace: \"{}\""),
+    BookInvalidFile => ("地址簿文件格式不对（应为名字到地址的 JSON 对象）: {}", "The address book file is malformed (expected a JSON object of name to address): {}"),
+
+    TraceHeader => ("调用树（类型 发送方 → 接收方  函数  实际使用 Gas）:", "Call tree (type from → to  function  gas used):"),
+    TraceNoCalldata => ("（无 calldata）", "(no calldata)"),
+    TraceFolded => ("... 省略 {} 个更深的调用（用 --max-depth 调整）", "... {} deeper calls hidden (adjust with --max-depth)"),
+    TraceEmpty => ("节点没有返回交易 {} 的调用记录", "The node returned no call trace for transaction {}"),
+    TraceUnsupported => (
+        "该 RPC 节点不支持交易追踪（debug_traceTransaction: {}；trace_transaction: {}），请换用开启了 debug / trace 接口的节点",
+        "This RPC node does not support tracing (debug_traceTransaction: {}; trace_transaction: {}); use a node with the debug or trace API enabled"
+    ),
+}
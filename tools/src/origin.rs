@@ -0,0 +1,204 @@
+use arbi_common::explorer;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::providers::Middleware;
+use ethers::types::{Address, BlockId, TxHash};
+use serde_json::{Value, json};
+use std::error::Error;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::account;
+use crate::json_cache;
+use crate::messages::{Msg, t, tf};
+
+/// 合约创建信息的来源
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OriginSource {
+    Explorer,
+    BinarySearch,
+    Cache,
+}
+
+/// 合约的部署信息
+pub struct ContractOrigin {
+    /// 部署者；由其他合约内部创建且二分查找找不到对应交易时为 `None`
+    pub deployer: Option<Address>,
+    pub tx_hash: Option<TxHash>,
+    pub block: u64,
+    pub source: OriginSource,
+}
+
+impl ContractOrigin {
+    fn to_json(&self) -> Value {
+        json!({
+            "deployer": self.deployer.map(|a| format!("{:?}", a)),
+            "tx_hash": self.tx_hash.map(|h| format!("{:?}", h)),
+            "block": self.block,
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        let parse_opt = |key: &str| value[key].as_str().map(str::to_string);
+        Some(Self {
+            deployer: parse_opt("deployer").and_then(|s| Address::from_str(&s).ok()),
+            tx_hash: parse_opt("tx_hash").and_then(|s| TxHash::from_str(&s).ok()),
+            block: value["block"].as_u64()?,
+            source: OriginSource::Cache,
+        })
+    }
+}
+
+/// 通过区块浏览器 API 查询合约的部署交易
+async fn origin_from_explorer(
+    provider: &RpcProvider,
+    api_key: &str,
+    address: Address,
+) -> Result<ContractOrigin, Box<dyn Error>> {
+    let address_str = format!("{:?}", address);
+    let result = explorer::get(
+        api_key,
+        &[
+            ("module", "contract"),
+            ("action", "getcontractcreation"),
+            ("contractaddresses", &address_str),
+        ],
+    )
+    .await?;
+
+    let entry = &result[0];
+    let deployer = entry["contractCreator"].as_str().map(Address::from_str).transpose()?;
+    let tx_hash = entry["txHash"]
+        .as_str()
+        .map(TxHash::from_str)
+        .transpose()?
+        .ok_or_else(|| tf(Msg::OriginNoCode, &[&address_str]))?;
+
+    // 浏览器只返回交易哈希，区块号从收据里取
+    let block = provider
+        .get_transaction_receipt(tx_hash)
+        .await?
+        .and_then(|receipt| receipt.block_number)
+        .ok_or_else(|| tf(Msg::OriginNoCode, &[&address_str]))?
+        .as_u64();
+
+    Ok(ContractOrigin {
+        deployer,
+        tx_hash: Some(tx_hash),
+        block,
+        source: OriginSource::Explorer,
+    })
+}
+
+/// 二分查找第一个在该地址上有代码的区块，再在区块里找创建它的交易
+///
+/// 需要节点支持查询历史状态（归档节点）。合约由其他合约内部创建时，区块中没有 `to` 为空且
+/// 收据 `contractAddress` 等于该地址的交易，这时只能给出区块号。
+async fn origin_from_binary_search(
+    provider: &RpcProvider,
+    address: Address,
+) -> Result<ContractOrigin, Box<dyn Error>> {
+    let has_code = |block: u64| async move {
+        let code = provider
+            .get_code(address, Some(BlockId::Number(block.into())))
+            .await?;
+        Ok::<_, Box<dyn Error>>(!code.is_empty())
+    };
+
+    let latest = provider.get_block_number().await?.as_u64();
+    let created_in = account::first_block(0, latest, has_code)
+        .await?
+        .ok_or_else(|| tf(Msg::OriginNoCode, &[&format!("{:?}", address)]))?;
+
+    let block = provider
+        .get_block_with_txs(created_in)
+        .await?
+        .ok_or_else(|| tf(Msg::BlockNotFound, &[&created_in]))?;
+    for tx in block.transactions.iter().filter(|tx| tx.to.is_none()) {
+        let receipt = provider.get_transaction_receipt(tx.hash).await?;
+        if receipt.and_then(|r| r.contract_address) == Some(address) {
+            return Ok(ContractOrigin {
+                deployer: Some(tx.from),
+                tx_hash: Some(tx.hash),
+                block: created_in,
+                source: OriginSource::BinarySearch,
+            });
+        }
+    }
+
+    Ok(ContractOrigin {
+        deployer: None,
+        tx_hash: None,
+        block: created_in,
+        source: OriginSource::BinarySearch,
+    })
+}
+
+/// 查找合约的部署交易和部署者
+///
+/// 先查缓存；配置了 `ARBISCAN_API_KEY` 时用区块浏览器 API，否则（或 API 失败时）退回到较慢的
+/// 二分查找。查到的结果写入缓存文件，下次直接读取。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 合约地址
+/// * `cache_path` - 缓存文件路径
+///
+/// # 返回
+/// * `Result<ContractOrigin, Box<dyn Error>>` - 部署信息
+pub async fn contract_origin(
+    provider: &RpcProvider,
+    address: Address,
+    cache_path: &Path,
+) -> Result<ContractOrigin, Box<dyn Error>> {
+    if let Some(origin) = json_cache::get(cache_path, address)
+        .as_ref()
+        .and_then(ContractOrigin::from_json)
+    {
+        return Ok(origin);
+    }
+
+    let explorer_result = match explorer::api_key() {
+        Some(key) => match origin_from_explorer(provider, &key, address).await {
+            Ok(origin) => Some(origin),
+            Err(e) => {
+                eprintln!("{}", style::warn(tf(Msg::OriginExplorerFallback, &[&e])));
+                None
+            }
+        },
+        None => None,
+    };
+    let origin = match explorer_result {
+        Some(origin) => origin,
+        None => origin_from_binary_search(provider, address).await?,
+    };
+
+    if let Err(e) = json_cache::put(cache_path, address, origin.to_json()) {
+        eprintln!("{}", style::warn(tf(Msg::OriginCacheFailed, &[&e])));
+    }
+    Ok(origin)
+}
+
+/// 打印合约的部署信息
+///
+/// # 参数
+/// * `origin` - `contract_origin` 的结果
+pub fn print_origin(origin: &ContractOrigin) {
+    match origin.deployer {
+        Some(deployer) => println!("{}", tf(Msg::OriginDeployer, &[&style::addr(deployer)])),
+        None => println!("{}", tf(Msg::OriginDeployer, &[&t(Msg::OriginInternal)])),
+    }
+    println!("{}", tf(Msg::OriginBlock, &[&origin.block]));
+    match origin.tx_hash {
+        Some(hash) => {
+            println!("{}", tf(Msg::OriginTxHash, &[&style::addr(format!("{:?}", hash))]))
+        }
+        None => println!("{}", tf(Msg::OriginTxHash, &[&t(Msg::OriginInternal)])),
+    }
+    let source = match origin.source {
+        OriginSource::Explorer => t(Msg::OriginSourceExplorer),
+        OriginSource::BinarySearch => t(Msg::OriginSourceSearch),
+        OriginSource::Cache => t(Msg::OriginSourceCache),
+    };
+    println!("{}", tf(Msg::OriginSource, &[&source]));
+}
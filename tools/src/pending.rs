@@ -0,0 +1,190 @@
+use arbi_common::style;
+use ethers::providers::{Middleware, Provider, StreamExt, Ws};
+use ethers::types::{Address, Transaction, TxHash};
+use ethers::utils::format_units;
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use crate::messages::{Msg, t, tf};
+
+/// 令牌桶限流：每秒补充 `rate` 个令牌，最多攒 `rate` 个
+pub struct RateLimiter {
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(rate: u32) -> Self {
+        Self {
+            rate: rate as f64,
+            tokens: rate as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// 有令牌时取走一个，没有时立即返回 `false`（不等待）
+    ///
+    /// # 参数
+    /// * `now` - 当前时间
+    ///
+    /// # 返回
+    /// * `bool` - 是否取到令牌
+    pub fn try_acquire(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// 订阅期间的计数
+#[derive(Debug, Default)]
+pub struct PendingStats {
+    /// 收到的交易哈希数
+    pub seen: u64,
+    /// 读取了详情的交易数
+    pub fetched: u64,
+    /// 因限流没有读取详情的交易数
+    pub throttled: u64,
+    /// 读取详情时交易已经上链或被丢弃的数量
+    pub gone: u64,
+    /// 匹配过滤地址的交易数
+    pub matched: u64,
+}
+
+/// 交易的发送方或接收方是否为指定地址
+fn involves(tx: &Transaction, address: Address) -> bool {
+    tx.from == address || tx.to == Some(address)
+}
+
+fn print_tx(tx: &Transaction) -> Result<(), Box<dyn Error>> {
+    let to = match tx.to {
+        Some(to) => format!("{:?}", to),
+        None => t(Msg::ContractCreation).to_string(),
+    };
+    println!(
+        "{:?}  {:?} → {}  {} ETH",
+        tx.hash,
+        tx.from,
+        to,
+        format_units(tx.value, "ether")?
+    );
+    Ok(())
+}
+
+/// 处理一个待处理交易哈希：按需读取详情、过滤并打印
+async fn handle(
+    provider: &Provider<Ws>,
+    hash: TxHash,
+    details: bool,
+    filter: Option<Address>,
+    limiter: &mut RateLimiter,
+    stats: &mut PendingStats,
+) -> Result<(), Box<dyn Error>> {
+    stats.seen += 1;
+    // 不读详情也不过滤时只打印哈希
+    if !details && filter.is_none() {
+        println!("{:?}", hash);
+        return Ok(());
+    }
+    if !limiter.try_acquire(Instant::now()) {
+        stats.throttled += 1;
+        return Ok(());
+    }
+
+    stats.fetched += 1;
+    let Some(tx) = provider.get_transaction(hash).await? else {
+        stats.gone += 1;
+        return Ok(());
+    };
+    if let Some(address) = filter {
+        if !involves(&tx, address) {
+            return Ok(());
+        }
+        stats.matched += 1;
+    }
+    if details {
+        print_tx(&tx)?;
+    } else {
+        println!("{:?}", hash);
+    }
+    Ok(())
+}
+
+/// 通过 WebSocket 订阅待处理交易（`eth_subscribe newPendingTransactions`），直到 Ctrl-C
+///
+/// 待处理交易数量可能很大，读取详情（`eth_getTransactionByHash`）受令牌桶限流，
+/// 超出部分只计数不读取。设置了过滤地址时只能在读到详情的交易中匹配。
+///
+/// Arbitrum 没有公共内存池：交易直接发给排序器，按先到先得排序，大多数节点的这个订阅
+/// 不会推送任何交易或直接报错。在以太坊 Sepolia 等 L1 节点上可以看到真实的内存池。
+///
+/// # 参数
+/// * `ws_url` - WebSocket 节点地址
+/// * `details` - 是否读取并打印 from / to / value
+/// * `filter` - 只显示涉及该地址的交易
+/// * `max_fetch_per_sec` - 每秒最多读取详情的次数
+///
+/// # 返回
+/// * `Result<PendingStats, Box<dyn Error>>` - 退出时的计数
+pub async fn watch_pending(
+    ws_url: &str,
+    details: bool,
+    filter: Option<Address>,
+    max_fetch_per_sec: u32,
+) -> Result<PendingStats, Box<dyn Error>> {
+    let provider = Provider::<Ws>::connect(ws_url)
+        .await
+        .map_err(|e| tf(Msg::PendingConnectFailed, &[&ws_url, &e]))?;
+    let mut stream = provider
+        .subscribe_pending_txs()
+        .await
+        .map_err(|e| tf(Msg::PendingUnsupported, &[&ws_url, &e]))?;
+    println!("{}", style::ok(tf(Msg::PendingSubscribed, &[&ws_url])));
+
+    let mut limiter = RateLimiter::new(max_fetch_per_sec.max(1));
+    let mut stats = PendingStats::default();
+    let started = Instant::now();
+    loop {
+        tokio::select! {
+            next = stream.next() => {
+                let Some(hash) = next else {
+                    eprintln!("{}", style::warn(t(Msg::PendingStreamClosed)));
+                    break;
+                };
+                let handled =
+                    handle(&provider, hash, details, filter, &mut limiter, &mut stats).await;
+                if let Err(e) = handled {
+                    let hash = format!("{:?}", hash);
+                    eprintln!("{}", style::warn(tf(Msg::PendingFetchFailed, &[&hash, &e])));
+                }
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    if stats.seen == 0 && started.elapsed() > Duration::from_secs(10) {
+        eprintln!("{}", style::warn(t(Msg::PendingNothingSeen)));
+    }
+    Ok(stats)
+}
+
+/// 打印订阅期间的计数
+pub fn print_stats(stats: &PendingStats, filter: Option<Address>) {
+    println!(
+        "\n{}",
+        tf(
+            Msg::PendingSummary,
+            &[&stats.seen, &stats.fetched, &stats.throttled, &stats.gone]
+        )
+    );
+    if filter.is_some() {
+        println!("{}", tf(Msg::PendingMatched, &[&stats.matched]));
+    }
+}
@@ -0,0 +1,92 @@
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::providers::Middleware;
+use ethers::types::BlockNumber;
+use std::error::Error;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::messages::{Msg, t, tf};
+
+/// 最新区块的新鲜程度
+#[derive(Clone, Copy, Debug)]
+pub struct SequencerStatus {
+    pub block: u64,
+    /// 距离现在多久，本地时钟比区块时间还早时为 0
+    pub age: Duration,
+    /// 超过该时间没有新区块即视为停滞
+    pub stale_after: Duration,
+}
+
+impl SequencerStatus {
+    /// 最新区块是否已经停滞
+    pub fn is_stale(&self) -> bool {
+        self.age > self.stale_after
+    }
+}
+
+/// 计算区块时间距离 `now` 多久
+///
+/// 区块时间戳只精确到秒，Arbitrum 出块间隔约 0.25 秒，刚出的区块算出来常常是零点几秒；
+/// 本地时钟稍慢时还会是负数，这里按 0 处理。
+///
+/// # 参数
+/// * `timestamp` - 区块时间（Unix 秒）
+/// * `now` - 当前时间
+///
+/// # 返回
+/// * `Duration` - 区块年龄
+pub fn block_age(timestamp: u64, now: SystemTime) -> Duration {
+    let block_time = UNIX_EPOCH + Duration::from_secs(timestamp);
+    now.duration_since(block_time).unwrap_or_default()
+}
+
+/// 通过最新区块的时间检查链是否还在出块
+///
+/// Arbitrum 的 L2 区块全部由单一的中心化排序器（sequencer）产生，排序器宕机或与节点失联时
+/// 链会停止出块；这时交易仍可以通过 L1 的 delayed inbox 强制打包，但要等待约 24 小时。
+/// 排序器只在有交易时出块，测试网极度空闲时也可能短暂超过阈值。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `stale_after` - 超过多久没有新区块视为停滞
+///
+/// # 返回
+/// * `Result<SequencerStatus, Box<dyn Error>>` - 最新区块号和年龄
+pub async fn sequencer_status(
+    provider: &RpcProvider,
+    stale_after: Duration,
+) -> Result<SequencerStatus, Box<dyn Error>> {
+    let block = provider
+        .get_block(BlockNumber::Latest)
+        .await?
+        .ok_or_else(|| t(Msg::SequencerNoBlock))?;
+    let number = block.number.ok_or_else(|| t(Msg::SequencerNoBlock))?.as_u64();
+    Ok(SequencerStatus {
+        block: number,
+        age: block_age(block.timestamp.as_u64(), SystemTime::now()),
+        stale_after,
+    })
+}
+
+fn format_age(age: Duration) -> String {
+    if age < Duration::from_secs(1) {
+        "<1s".to_string()
+    } else {
+        format!("{}s", age.as_secs())
+    }
+}
+
+/// 打印状态：正常时一行，停滞时给出警告和原因说明
+pub fn print_status(status: &SequencerStatus) {
+    let age = format_age(status.age);
+    if status.is_stale() {
+        let line = tf(
+            Msg::SequencerStale,
+            &[&status.block, &age, &status.stale_after.as_secs()],
+        );
+        println!("{}", style::warn(line));
+        println!("{}", t(Msg::SequencerStaleHint));
+    } else {
+        println!("{}", style::ok(tf(Msg::SequencerHealthy, &[&age, &status.block])));
+    }
+}
@@ -0,0 +1,162 @@
+//! 首次使用的交互式向导：生成新钱包、加密保存为 keystore、写入 `.env` 模板
+
+use arbi_common::network;
+use arbi_common::style;
+use arbi_common::table::write_atomic;
+use ethers::core::k256::elliptic_curve::zeroize::Zeroizing;
+use ethers::core::rand::thread_rng;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::Address;
+use ethers::utils::{hex, to_checksum};
+use std::error::Error;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use crate::messages::{Msg, t, tf};
+use crate::wallet;
+
+// 两次输入的密码不一致时最多重试几次
+const PASSWORD_ATTEMPTS: usize = 3;
+
+// 问一个是非问题，直接回车取默认值；输入结束（Ctrl-D）时取消向导
+fn ask_yes_no(question: &str, default: bool) -> Result<bool, Box<dyn Error>> {
+    let hint = if default { "[Y/n]" } else { "[y/N]" };
+    loop {
+        eprint!("{} {} ", question, hint);
+        std::io::stderr().flush()?;
+        let mut answer = String::new();
+        if std::io::stdin().lock().read_line(&mut answer)? == 0 {
+            return Err(t(Msg::WalletInputCancelled).into());
+        }
+        match answer.trim().to_lowercase().as_str() {
+            "" => return Ok(default),
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => eprintln!("{}", t(Msg::SetupAnswerYesNo)),
+        }
+    }
+}
+
+// 提示输入两次 keystore 密码（不回显），一致且不为空时返回
+fn prompt_password() -> Result<Zeroizing<String>, Box<dyn Error>> {
+    for _ in 0..PASSWORD_ATTEMPTS {
+        eprint!("{}", t(Msg::SetupPasswordPrompt));
+        std::io::stderr().flush()?;
+        let password = wallet::read_hidden_line()?;
+        if password.is_empty() {
+            eprintln!("{}", style::warn(t(Msg::SetupPasswordEmpty)));
+            continue;
+        }
+        eprint!("{}", t(Msg::SetupPasswordConfirm));
+        std::io::stderr().flush()?;
+        if *wallet::read_hidden_line()? == *password {
+            return Ok(password);
+        }
+        eprintln!("{}", style::warn(t(Msg::SetupPasswordMismatch)));
+    }
+    Err(tf(Msg::SetupPasswordFailed, &[&PASSWORD_ATTEMPTS]).into())
+}
+
+/// 生成 `.env` 模板
+///
+/// 只写入地址和 keystore 路径，`PRIVATE_KEY` 和 `KEYSTORE_PASSWORD` 留空，
+/// 私钥不会以明文出现在文件里。
+///
+/// # 参数
+/// * `address` - 新钱包的地址
+/// * `keystore` - keystore 文件路径，没有保存时为 `None`
+///
+/// # 返回
+/// * `String` - 文件内容
+pub fn env_template(address: Address, keystore: Option<&Path>) -> String {
+    let keystore = match keystore {
+        Some(path) => format!("KEYSTORE_PATH={}", path.display()),
+        None => "# KEYSTORE_PATH=".to_string(),
+    };
+    let lines = [
+        format!("# {}", t(Msg::SetupEnvHeader)),
+        format!("# {}", t(Msg::SetupEnvNetwork)),
+        format!("ARBI_NETWORK={}", network::current().name()),
+        String::new(),
+        format!("# {}", tf(Msg::SetupEnvAddress, &[&to_checksum(&address, None)])),
+        keystore,
+        format!("# {}", t(Msg::SetupEnvPassword)),
+        "KEYSTORE_PASSWORD=".to_string(),
+        String::new(),
+        format!("# {}", t(Msg::SetupEnvPrivateKey)),
+        "PRIVATE_KEY=".to_string(),
+        format!("# {}", t(Msg::SetupEnvTransfer)),
+        "TO_ADDRESS=".to_string(),
+        "AMOUNT=".to_string(),
+    ];
+    lines.join("\n") + "\n"
+}
+
+// 写入 .env：已存在时先确认；在 Unix 上权限设为 0600，之后填进去的私钥只有当前用户可读
+fn write_env(path: &Path, contents: &str) -> Result<bool, Box<dyn Error>> {
+    if path.exists() && !ask_yes_no(&tf(Msg::SetupAskOverwrite, &[&path.display()]), false)? {
+        return Ok(false);
+    }
+    write_atomic(path, contents.as_bytes())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(true)
+}
+
+/// 运行向导
+///
+/// 依次：随机生成私钥并显示地址；询问是否用密码加密保存为 keystore（不保存时可以选择在终端里
+/// 显示一次私钥）；写入 `.env` 模板，文件已存在时确认后才覆盖；最后提示如何领取测试币。
+/// 任何时候都不会把私钥明文写入文件。
+///
+/// # 参数
+/// * `keystore_dir` - keystore 保存目录
+/// * `env_file` - `.env` 文件路径
+///
+/// # 返回
+/// * `Result<(), Box<dyn Error>>` - 执行结果；标准输入不是终端时返回错误
+pub fn run(keystore_dir: &Path, env_file: &Path) -> Result<(), Box<dyn Error>> {
+    if !std::io::stdin().is_terminal() {
+        return Err(t(Msg::SetupNeedsTerminal).into());
+    }
+    println!("{}\n", t(Msg::SetupTitle));
+
+    // 1. 生成钱包
+    let wallet = LocalWallet::new(&mut thread_rng());
+    let address = wallet.address();
+    println!("{}", style::ok(t(Msg::WalletCreated)));
+    println!("{}\n", tf(Msg::WalletAddress, &[&style::addr(to_checksum(&address, None))]));
+
+    // 2. 加密保存
+    let mut keystore: Option<PathBuf> = None;
+    if ask_yes_no(t(Msg::SetupAskKeystore), true)? {
+        let password = prompt_password()?;
+        let path = wallet::save_keystore(keystore_dir, wallet.signer(), &password)?;
+        println!("{}\n", style::ok(tf(Msg::WalletKeystoreSaved, &[&path.display()])));
+        keystore = Some(path);
+    } else if ask_yes_no(t(Msg::SetupAskShowKey), false)? {
+        let key = Zeroizing::new(hex::encode_prefixed(wallet.signer().to_bytes()));
+        println!("{}", style::warn(t(Msg::VanityKeyWarning)));
+        println!("{}\n", key.as_str());
+    } else {
+        println!("{}\n", style::warn(t(Msg::SetupKeyDiscarded)));
+    }
+
+    // 3. .env 模板
+    let template = env_template(address, keystore.as_deref());
+    if write_env(env_file, &template)? {
+        println!("{}", style::ok(tf(Msg::SetupEnvWritten, &[&env_file.display()])));
+    } else {
+        println!("{}", tf(Msg::SetupEnvKept, &[&env_file.display()]));
+        print!("{}", template);
+    }
+
+    // 4. 下一步
+    println!();
+    wallet::print_faucets();
+    println!("\n{}", tf(Msg::SetupNextStep, &[&to_checksum(&address, None)]));
+    Ok(())
+}
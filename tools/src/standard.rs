@@ -0,0 +1,234 @@
+//! 通过 ERC165 的 `supportsInterface(bytes4)` 判断合约声明实现了哪些代币标准
+
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::providers::{Middleware, RpcError};
+use ethers::types::{Address, Bytes, TransactionRequest};
+use ethers::utils::{hex, id};
+use std::error::Error;
+
+use crate::messages::{Msg, t, tf};
+
+/// ERC165 自身的接口 ID
+pub const ERC165_ID: [u8; 4] = [0x01, 0xff, 0xc9, 0xa7];
+/// ERC721 的接口 ID
+pub const ERC721_ID: [u8; 4] = [0x80, 0xac, 0x58, 0xcd];
+/// ERC1155 的接口 ID
+pub const ERC1155_ID: [u8; 4] = [0xd9, 0xb6, 0x7a, 0x26];
+
+// EIP-165 规定任何实现都必须对 0xffffffff 返回 false
+const INVALID_ID: [u8; 4] = [0xff; 4];
+
+// EIP-165 规定 supportsInterface 最多使用 30000 gas，检测时按这个上限调用
+const SUPPORTS_INTERFACE_GAS: u64 = 30_000;
+
+/// 检测结果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Detection {
+    /// 地址上没有代码（EOA 或还没部署）
+    NotContract,
+    /// 没有按 EIP-165 实现 `supportsInterface`，多半是 ERC20 或其他不声明接口的合约
+    NoErc165,
+    /// 实现了 ERC165，以及它声明支持的标准
+    Erc165 { erc721: bool, erc1155: bool },
+}
+
+impl Detection {
+    /// 结论：读取这个合约时应该选用的 ABI
+    pub fn label(self) -> &'static str {
+        match self {
+            Detection::NotContract => t(Msg::StandardNotContract),
+            Detection::NoErc165 => t(Msg::StandardErc20Like),
+            Detection::Erc165 { erc721: true, erc1155: true } => "ERC721 + ERC1155",
+            Detection::Erc165 { erc721: true, .. } => "ERC721",
+            Detection::Erc165 { erc1155: true, .. } => "ERC1155",
+            Detection::Erc165 { .. } => t(Msg::StandardErc165Only),
+        }
+    }
+}
+
+/// 调用 `supportsInterface(interface_id)`
+///
+/// 合约 revert（没有这个函数）或返回值不是 ABI 编码的 bool 时按不支持处理；
+/// 网络错误等请求本身的失败原样返回。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 合约地址
+/// * `interface_id` - 4 字节接口 ID
+///
+/// # 返回
+/// * `Result<bool, Box<dyn Error>>` - 合约是否声明支持该接口
+pub async fn supports_interface(
+    provider: &RpcProvider,
+    address: Address,
+    interface_id: [u8; 4],
+) -> Result<bool, Box<dyn Error>> {
+    let mut data = id("supportsInterface(bytes4)").to_vec();
+    data.extend_from_slice(&interface_id);
+    data.extend_from_slice(&[0; 28]);
+    let tx = TransactionRequest::new()
+        .to(address)
+        .gas(SUPPORTS_INTERFACE_GAS)
+        .data(Bytes::from(data));
+    match provider.call(&tx.into(), None).await {
+        // ABI 编码的 bool：31 个 0 字节后跟 0 或 1
+        Ok(output) => Ok(output.len() == 32
+            && output[..31].iter().all(|byte| *byte == 0)
+            && output[31] == 1),
+        Err(error) if error.as_error_response().is_some() => Ok(false),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// 判断合约声明实现了 ERC721 还是 ERC1155
+///
+/// 先按 EIP-165 的流程确认合约实现了 ERC165（对 `0x01ffc9a7` 返回 true、对 `0xffffffff` 返回 false），
+/// 再查询 ERC721（`0x80ac58cd`）和 ERC1155（`0xd9b67a26`）。没有实现 ERC165 的合约
+/// 无法通过接口判断，归为"未知 / 类 ERC20"。这只是合约自己的声明，不保证真的实现了对应的函数。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `address` - 合约地址
+///
+/// # 返回
+/// * `Result<Detection, Box<dyn Error>>` - 检测结果
+pub async fn detect_standard(
+    provider: &RpcProvider,
+    address: Address,
+) -> Result<Detection, Box<dyn Error>> {
+    if provider.get_code(address, None).await?.is_empty() {
+        return Ok(Detection::NotContract);
+    }
+    if !supports_interface(provider, address, ERC165_ID).await?
+        || supports_interface(provider, address, INVALID_ID).await?
+    {
+        return Ok(Detection::NoErc165);
+    }
+    Ok(Detection::Erc165 {
+        erc721: supports_interface(provider, address, ERC721_ID).await?,
+        erc1155: supports_interface(provider, address, ERC1155_ID).await?,
+    })
+}
+
+/// 打印检测结果
+///
+/// # 参数
+/// * `address` - 合约地址
+/// * `detection` - 检测结果
+pub fn print_detection(address: Address, detection: Detection) {
+    println!("{}", tf(Msg::StandardTitle, &[&style::addr(format!("{:?}", address))]));
+    let yes_no = |supported: bool| match supported {
+        true => style::ok(t(Msg::StandardYes)),
+        false => t(Msg::StandardNo).to_string(),
+    };
+    if let Detection::Erc165 { erc721, erc1155 } = detection {
+        println!("{}", tf(Msg::StandardErc165, &[&yes_no(true)]));
+        let interfaces = [("ERC721", ERC721_ID, erc721), ("ERC1155", ERC1155_ID, erc1155)];
+        for (name, interface_id, supported) in interfaces {
+            let (name, id) = (format!("{:<7}", name), hex::encode_prefixed(interface_id));
+            println!("{}", tf(Msg::StandardInterface, &[&name, &id, &yes_no(supported)]));
+        }
+    } else if detection == Detection::NoErc165 {
+        println!("{}", tf(Msg::StandardErc165, &[&yes_no(false)]));
+    }
+    println!("{}", tf(Msg::StandardResult, &[&detection.label()]));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use serde_json::{Value, json};
+
+    const TRUE: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+    const FALSE: &str = "0x0000000000000000000000000000000000000000000000000000000000000000";
+
+    // 有代码的合约；`reply` 按查询的接口 ID（十六进制，不带 0x）决定 eth_call 的返回
+    fn contract(reply: fn(&str) -> Reply) -> MockRpc {
+        MockRpc::start(move |method, params| match method {
+            "eth_getCode" => Reply::Result(json!("0x6080604052")),
+            "eth_call" => {
+                let call = &params[0];
+                let input = call["input"].as_str().or(call["data"].as_str()).unwrap();
+                assert_eq!(&input[..10], "0x01ffc9a7");
+                assert_eq!(call["gas"], json!("0x7530"));
+                reply(&input[10..18])
+            }
+            _ => Reply::Result(Value::Null),
+        })
+    }
+
+    async fn detect(rpc: &MockRpc) -> Result<Detection, Box<dyn Error>> {
+        detect_standard(&rpc.provider(), Address::repeat_byte(0x42)).await
+    }
+
+    #[tokio::test]
+    async fn detects_declared_interfaces() {
+        let rpc = contract(|id| match id {
+            "01ffc9a7" | "80ac58cd" => Reply::Result(json!(TRUE)),
+            _ => Reply::Result(json!(FALSE)),
+        });
+        let detection = detect(&rpc).await.unwrap();
+        assert_eq!(
+            detection,
+            Detection::Erc165 {
+                erc721: true,
+                erc1155: false,
+            }
+        );
+        assert_eq!(detection.label(), "ERC721");
+        assert_eq!(rpc.count("eth_call"), 4);
+    }
+
+    #[tokio::test]
+    async fn revert_or_empty_return_means_no_erc165() {
+        let reverts = contract(|_| Reply::Error("execution reverted".to_string()));
+        assert_eq!(detect(&reverts).await.unwrap(), Detection::NoErc165);
+        // fallback 函数什么都不返回的合约
+        let empty = contract(|_| Reply::Result(json!("0x")));
+        assert_eq!(detect(&empty).await.unwrap(), Detection::NoErc165);
+        assert_eq!(empty.count("eth_call"), 1);
+    }
+
+    #[tokio::test]
+    async fn contract_claiming_every_interface_is_not_erc165() {
+        let rpc = contract(|_| Reply::Result(json!(TRUE)));
+        assert_eq!(detect(&rpc).await.unwrap(), Detection::NoErc165);
+        assert_eq!(rpc.count("eth_call"), 2);
+    }
+
+    #[tokio::test]
+    async fn only_a_canonical_true_counts() {
+        // 高位不为 0、或者多出字节的返回值都不是 ABI 编码的 true
+        let dirty = contract(|_| Reply::Result(json!(format!("0x{}01", "ff".repeat(31)))));
+        let address = Address::repeat_byte(0x42);
+        assert!(!supports_interface(&dirty.provider(), address, ERC165_ID).await.unwrap());
+        let long = contract(|_| Reply::Result(json!(format!("{}00", TRUE))));
+        assert!(!supports_interface(&long.provider(), address, ERC165_ID).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn address_without_code_is_not_a_contract() {
+        let rpc = MockRpc::start(|_, _| Reply::Result(json!("0x")));
+        let detection = detect(&rpc).await.unwrap();
+        assert_eq!(detection, Detection::NotContract);
+        assert_eq!(detection.label(), t(Msg::StandardNotContract));
+        assert_eq!(rpc.count("eth_call"), 0);
+    }
+
+    #[tokio::test]
+    async fn transport_errors_are_not_treated_as_unsupported() {
+        let rpc = contract(|_| Reply::Disconnect);
+        assert!(detect(&rpc).await.is_err());
+    }
+
+    #[test]
+    fn labels_cover_both_standards() {
+        let erc165 = |erc721, erc1155| Detection::Erc165 { erc721, erc1155 };
+        assert_eq!(erc165(true, true).label(), "ERC721 + ERC1155");
+        assert_eq!(erc165(false, true).label(), "ERC1155");
+        assert_eq!(erc165(false, false).label(), t(Msg::StandardErc165Only));
+        assert_eq!(Detection::NoErc165.label(), t(Msg::StandardErc20Like));
+    }
+}
@@ -0,0 +1,482 @@
+use arbi_common::logs::{LogChunks, transfer_topic};
+use arbi_common::network;
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use arbi_common::table::Table;
+use arbi_common::units;
+use ethers::types::{Address, Filter, H256, Log, TxHash, U256, ValueOrArray};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::str::FromStr;
+
+use crate::history::Direction;
+use crate::inspect::TokenMeta;
+use crate::messages::{Msg, t, tf};
+
+/// 代币列表的环境变量，多个地址用逗号分隔；没有 `--token` 时使用
+pub const TOKENS_ENV: &str = "ARBI_TOKENS";
+
+// ERC20 的 Transfer 有 3 个 topic（from、to 是 indexed，value 在 data 里）；
+// ERC721 的 tokenId 也是 indexed，有 4 个 topic，这里不统计
+const ERC20_TRANSFER_TOPICS: usize = 3;
+
+// CSV 表头
+const CSV_HEADER: [&str; 9] = [
+    "block",
+    "tx_hash",
+    "log_index",
+    "token",
+    "symbol",
+    "direction",
+    "counterparty",
+    "amount_raw",
+    "amount",
+];
+
+/// 一笔 ERC20 转账
+pub struct TokenTransfer {
+    pub block: u64,
+    pub tx_hash: TxHash,
+    pub log_index: u64,
+    pub token: Address,
+    pub direction: Direction,
+    /// 对方地址；发给自己时为账户本身
+    pub counterparty: Address,
+    /// 最小单位的数量
+    pub amount: U256,
+}
+
+impl TokenTransfer {
+    /// 从 Transfer 日志解析；不是 ERC20 Transfer 或缺少交易信息（如 pending 日志）时返回 `None`
+    fn from_log(account: Address, log: &Log) -> Option<Self> {
+        if log.topics.len() != ERC20_TRANSFER_TOPICS
+            || log.topics[0] != transfer_topic()
+            || log.data.len() != 32
+        {
+            return None;
+        }
+        let from = Address::from(log.topics[1]);
+        let to = Address::from(log.topics[2]);
+        let direction = Direction::of(account, from, Some(to));
+        let counterparty = match direction {
+            Direction::In => from,
+            Direction::Out | Direction::SelfTransfer => to,
+        };
+        Some(Self {
+            block: log.block_number?.as_u64(),
+            tx_hash: log.transaction_hash?,
+            log_index: log.log_index?.as_u64(),
+            token: log.address,
+            direction,
+            counterparty,
+            amount: U256::from_big_endian(&log.data),
+        })
+    }
+}
+
+/// 每个代币的收支
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct NetChange {
+    pub received: U256,
+    pub sent: U256,
+}
+
+impl NetChange {
+    /// 净变化：`(是否为负, 绝对值)`
+    pub fn net(&self) -> (bool, U256) {
+        match self.received >= self.sent {
+            true => (false, self.received - self.sent),
+            false => (true, self.sent - self.received),
+        }
+    }
+}
+
+/// 要查询的代币：`--token` 优先，其次 `ARBI_TOKENS`，都没有时使用当前网络的 USDC
+///
+/// # 参数
+/// * `cli` - `--token` 传入的地址
+///
+/// # 返回
+/// * `Result<Vec<Address>, Box<dyn Error>>` - 去重后的代币地址；有无效地址时返回错误
+pub fn resolve_tokens(cli: &[String]) -> Result<Vec<Address>, Box<dyn Error>> {
+    let configured = std::env::var(TOKENS_ENV).unwrap_or_default();
+    let entries: Vec<&str> = match cli.is_empty() {
+        false => cli.iter().map(|entry| entry.trim()).collect(),
+        true => configured.split(',').map(str::trim).filter(|entry| !entry.is_empty()).collect(),
+    };
+    let entries = match entries.is_empty() {
+        true => vec![network::current().usdc_address()],
+        false => entries,
+    };
+    let mut tokens = Vec::new();
+    for entry in entries {
+        let token = Address::from_str(entry).map_err(|_| tf(Msg::InvalidAddress, &[&entry]))?;
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    Ok(tokens)
+}
+
+/// 合并两个方向查到的日志：按 (交易哈希, 日志序号) 去重，解析成转账并按链上顺序排列
+///
+/// 发给自己的转账同时匹配"from 是账户"和"to 是账户"两个查询，会出现两次。
+///
+/// # 参数
+/// * `account` - 账户地址
+/// * `logs` - 所有查询到的日志
+///
+/// # 返回
+/// * `Vec<TokenTransfer>` - 按区块和日志序号排列的转账
+pub fn merge_logs(account: Address, logs: impl IntoIterator<Item = Log>) -> Vec<TokenTransfer> {
+    let mut seen = HashSet::new();
+    let mut transfers: Vec<TokenTransfer> = logs
+        .into_iter()
+        .filter_map(|log| TokenTransfer::from_log(account, &log))
+        .filter(|transfer| seen.insert((transfer.tx_hash, transfer.log_index)))
+        .collect();
+    transfers.sort_by_key(|transfer| (transfer.block, transfer.log_index));
+    transfers
+}
+
+/// 按代币汇总收支；发给自己的转账同时计入收入和支出，净变化为 0
+///
+/// # 参数
+/// * `transfers` - 转账列表
+///
+/// # 返回
+/// * `BTreeMap<Address, NetChange>` - 每个代币的收支
+pub fn net_changes(transfers: &[TokenTransfer]) -> BTreeMap<Address, NetChange> {
+    let mut changes: BTreeMap<Address, NetChange> = BTreeMap::new();
+    for transfer in transfers {
+        let change = changes.entry(transfer.token).or_default();
+        if transfer.direction != Direction::Out {
+            change.received = change.received.saturating_add(transfer.amount);
+        }
+        if transfer.direction != Direction::In {
+            change.sent = change.sent.saturating_add(transfer.amount);
+        }
+    }
+    changes
+}
+
+/// 分段查询一个过滤条件在区块范围内的全部日志，同时返回最后使用的分段大小
+async fn fetch_logs(
+    provider: &RpcProvider,
+    filter: Filter,
+    (from, to): (u64, u64),
+    chunk_size: u64,
+) -> Result<(Vec<Log>, u64), Box<dyn Error>> {
+    let mut chunks = LogChunks::new(provider, filter, from, to, chunk_size);
+    let mut logs = Vec::new();
+    while let Some(chunk) = chunks.next().await? {
+        logs.extend(chunk.logs);
+    }
+    Ok((logs, chunks.chunk_size()))
+}
+
+/// 查询账户在区块范围内的 ERC20 转账
+///
+/// 分别按 indexed 的 from 和 to 过滤 Transfer 事件，两个查询都按 `chunk_size` 分段
+/// （节点拒绝时自动减半，第二个查询沿用减半后的大小），然后合并去重。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `account` - 账户地址
+/// * `tokens` - 代币合约地址
+/// * `range` - 区块范围（含两端）
+/// * `chunk_size` - 每次 `eth_getLogs` 查询的区块数
+///
+/// # 返回
+/// * `Result<Vec<TokenTransfer>, Box<dyn Error>>` - 按链上顺序排列的转账
+pub async fn token_history(
+    provider: &RpcProvider,
+    account: Address,
+    tokens: &[Address],
+    (from, to): (u64, u64),
+    chunk_size: u64,
+) -> Result<Vec<TokenTransfer>, Box<dyn Error>> {
+    if from > to {
+        return Err(tf(Msg::GasUsageBadRange, &[&from, &to]).into());
+    }
+    let topic = H256::from(account);
+    let base = Filter::new()
+        .address(ValueOrArray::Array(tokens.to_vec()))
+        .topic0(transfer_topic());
+    let (sent, chunk_size) =
+        fetch_logs(provider, base.clone().topic1(topic), (from, to), chunk_size).await?;
+    let (received, _) = fetch_logs(provider, base.topic2(topic), (from, to), chunk_size).await?;
+    Ok(merge_logs(account, sent.into_iter().chain(received)))
+}
+
+// 代币符号，读不到时用地址
+fn symbol(token: Address, metas: &HashMap<Address, TokenMeta>) -> String {
+    match metas.get(&token) {
+        Some(meta) => meta.symbol.clone(),
+        None => format!("{:?}", token),
+    }
+}
+
+// 按小数位数格式化数量，读不到小数位数时显示原始整数
+fn amount(token: Address, value: U256, metas: &HashMap<Address, TokenMeta>) -> String {
+    let decimals = metas.get(&token).map(|meta| meta.decimals).unwrap_or_default();
+    units::format_exact(value, decimals).unwrap_or_else(|_| value.to_string())
+}
+
+fn direction_label(direction: Direction) -> &'static str {
+    match direction {
+        Direction::In => t(Msg::TokenHistoryIn),
+        Direction::Out => t(Msg::TokenHistoryOut),
+        Direction::SelfTransfer => t(Msg::TokenHistorySelf),
+    }
+}
+
+/// 打印按时间排列的对账单和每个代币的净变化
+///
+/// # 参数
+/// * `transfers` - 转账列表
+/// * `metas` - 代币的符号和小数位数
+pub fn print_statement(transfers: &[TokenTransfer], metas: &HashMap<Address, TokenMeta>) {
+    if transfers.is_empty() {
+        println!("{}", t(Msg::TokenHistoryEmpty));
+        return;
+    }
+    println!(
+        "{:>10}  {:<4}  {:<42}  {:>24}  {:<8}  {}",
+        t(Msg::GasUsageColBlock),
+        t(Msg::TokenHistoryColDirection),
+        t(Msg::TokenHistoryColCounterparty),
+        t(Msg::TokenHistoryColAmount),
+        t(Msg::TokenHistoryColToken),
+        t(Msg::GasReportColHash)
+    );
+    for transfer in transfers {
+        let line = format!(
+            "{:>10}  {:<4}  {:<42}  {:>24}  {:<8}  {:?}",
+            transfer.block,
+            direction_label(transfer.direction),
+            format!("{:?}", transfer.counterparty),
+            amount(transfer.token, transfer.amount, metas),
+            symbol(transfer.token, metas),
+            transfer.tx_hash
+        );
+        match transfer.direction {
+            Direction::In => println!("{}", style::ok(line)),
+            Direction::Out | Direction::SelfTransfer => println!("{}", line),
+        }
+    }
+
+    println!("\n{}", t(Msg::TokenHistoryNetTitle));
+    for (token, change) in net_changes(transfers) {
+        let (negative, net) = change.net();
+        let sign = match (negative, net.is_zero()) {
+            (_, true) => "",
+            (true, false) => "-",
+            (false, false) => "+",
+        };
+        println!(
+            "{}",
+            tf(
+                Msg::TokenHistoryNet,
+                &[
+                    &symbol(token, metas),
+                    &amount(token, change.received, metas),
+                    &amount(token, change.sent, metas),
+                    &format!("{}{}", sign, amount(token, net, metas)),
+                ]
+            )
+        );
+    }
+}
+
+/// 转账明细的表格，数量同时给出最小单位的整数和按小数位数格式化后的值
+///
+/// # 参数
+/// * `transfers` - 转账列表
+/// * `metas` - 代币的符号和小数位数
+///
+/// # 返回
+/// * `Table` - 用于导出 CSV 的表格
+pub fn to_table(transfers: &[TokenTransfer], metas: &HashMap<Address, TokenMeta>) -> Table {
+    let mut table = Table::new(CSV_HEADER);
+    for transfer in transfers {
+        table.push([
+            transfer.block.to_string(),
+            format!("{:?}", transfer.tx_hash),
+            transfer.log_index.to_string(),
+            format!("{:?}", transfer.token),
+            symbol(transfer.token, metas),
+            transfer.direction.as_str().to_string(),
+            format!("{:?}", transfer.counterparty),
+            transfer.amount.to_string(),
+            amount(transfer.token, transfer.amount, metas),
+        ]);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbi_common::mock_rpc::{MockRpc, Reply};
+    use serde_json::{Value, json};
+
+    const ACCOUNT: u64 = 0xaa;
+    const ALICE: u64 = 0xb0;
+    const CAROL: u64 = 0xc0;
+    const USDC: u64 = 0x10;
+    const DAI: u64 = 0x11;
+
+    fn account(n: u64) -> Address {
+        Address::from_low_u64_be(n)
+    }
+
+    fn topic(n: u64) -> String {
+        format!("{:?}", H256::from(account(n)))
+    }
+
+    // (区块, 交易, 日志序号, 代币, from, to, 数量)
+    type Fixture = (u64, u64, u64, u64, u64, u64, u64);
+
+    const LOGS: [Fixture; 6] = [
+        (120, 1, 0, USDC, ALICE, ACCOUNT, 500),
+        // 第一段的最后一个区块
+        (199, 2, 3, USDC, ACCOUNT, CAROL, 200),
+        // 第二段的第一个区块，发给自己，两个查询都会返回
+        (200, 3, 0, USDC, ACCOUNT, ACCOUNT, 50),
+        (250, 4, 1, DAI, ACCOUNT, ALICE, 70),
+        (250, 4, 0, DAI, CAROL, ACCOUNT, 100),
+        // 与账户无关
+        (320, 5, 0, USDC, ALICE, CAROL, 999),
+    ];
+
+    fn log_json((block, tx, index, token, from, to, amount): Fixture) -> Value {
+        json!({
+            "address": format!("{:?}", account(token)),
+            "topics": [format!("{:?}", transfer_topic()), topic(from), topic(to)],
+            "data": format!("0x{:064x}", amount),
+            "blockNumber": format!("{:#x}", block),
+            "transactionHash": format!("{:?}", TxHash::from_low_u64_be(tx)),
+            "logIndex": format!("{:#x}", index),
+        })
+    }
+
+    fn block_param(filter: &Value, key: &str) -> u64 {
+        let hex = filter[key].as_str().unwrap().trim_start_matches("0x");
+        u64::from_str_radix(hex, 16).unwrap()
+    }
+
+    fn range(filter: &Value) -> (u64, u64) {
+        (block_param(filter, "fromBlock"), block_param(filter, "toBlock"))
+    }
+
+    // 按区块范围、代币和 from / to topic 过滤固定的日志；超过 150 个区块的查询会被拒绝
+    fn node() -> MockRpc {
+        MockRpc::start(|_, params| {
+            let filter = &params[0];
+            let (from, to) = range(filter);
+            if to - from >= 150 {
+                return Reply::Error("eth_getLogs block range is too large".to_string());
+            }
+            // ERC721 的 Transfer 有 4 个 topic，按 to 查询也会返回，但不应计入
+            let mut logs = vec![json!({
+                "address": format!("{:?}", account(USDC)),
+                "topics": [
+                    format!("{:?}", transfer_topic()),
+                    topic(ALICE),
+                    topic(ACCOUNT),
+                    format!("{:?}", H256::from_low_u64_be(7)),
+                ],
+                "data": "0x",
+                "blockNumber": "0x14a",
+                "transactionHash": format!("{:?}", TxHash::from_low_u64_be(6)),
+                "logIndex": "0x0",
+            })];
+            logs.extend(LOGS.into_iter().map(log_json));
+            let tokens = filter["address"].as_array().unwrap();
+            let matches = |log: &Value| {
+                let block = block_param(log, "blockNumber");
+                let topics = log["topics"].as_array().unwrap();
+                (from..=to).contains(&block)
+                    && tokens.contains(&log["address"])
+                    && [1, 2].iter().all(|&i| {
+                        filter["topics"][i].is_null() || filter["topics"][i] == topics[i]
+                    })
+            };
+            Reply::Result(Value::Array(logs.into_iter().filter(matches).collect()))
+        })
+    }
+
+    #[tokio::test]
+    async fn merges_both_directions_across_chunks() {
+        let rpc = node();
+        let tokens = [account(USDC), account(DAI)];
+        let transfers =
+            token_history(&rpc.provider(), account(ACCOUNT), &tokens, (100, 349), 200)
+                .await
+                .unwrap();
+
+        let rows: Vec<_> = transfers
+            .iter()
+            .map(|t| (t.block, t.log_index, t.direction, t.counterparty, t.amount.as_u64()))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                (120, 0, Direction::In, account(ALICE), 500),
+                (199, 3, Direction::Out, account(CAROL), 200),
+                (200, 0, Direction::SelfTransfer, account(ACCOUNT), 50),
+                (250, 0, Direction::In, account(CAROL), 100),
+                (250, 1, Direction::Out, account(ALICE), 70),
+            ]
+        );
+
+        // 第一个查询 200 被拒绝后减半为 100，第二个查询直接用 100
+        let ranges: Vec<_> = rpc
+            .requests()
+            .iter()
+            .map(|(_, params)| range(&params[0]))
+            .collect();
+        assert_eq!(
+            ranges,
+            [(100, 299), (100, 199), (200, 299), (300, 349), (100, 199), (200, 299), (300, 349)]
+        );
+    }
+
+    #[tokio::test]
+    async fn reversed_range_is_rejected_before_querying() {
+        let rpc = node();
+        let error = token_history(&rpc.provider(), account(ACCOUNT), &[], (10, 9), 100)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), tf(Msg::GasUsageBadRange, &[&10, &9]));
+        assert_eq!(rpc.count("eth_getLogs"), 0);
+    }
+
+    #[test]
+    fn self_transfers_count_both_ways_and_net_to_zero() {
+        // 发给自己的那条出现两次，和节点的两个查询各返回一次一样
+        let logs: Vec<Log> = LOGS[..5]
+            .iter()
+            .chain(&LOGS[2..3])
+            .map(|fixture| serde_json::from_value(log_json(*fixture)).unwrap())
+            .collect();
+        let transfers = merge_logs(account(ACCOUNT), logs);
+        assert_eq!(transfers.len(), 5);
+
+        let changes = net_changes(&transfers);
+        let usdc = &changes[&account(USDC)];
+        assert_eq!(usdc.received, U256::from(500 + 50));
+        assert_eq!(usdc.sent, U256::from(200 + 50));
+        let dai = &changes[&account(DAI)];
+        assert_eq!(dai.net(), (false, U256::from(30)));
+
+        let loss = NetChange {
+            received: U256::from(5),
+            sent: U256::from(8),
+        };
+        assert_eq!(loss.net(), (true, U256::from(3)));
+        assert_eq!(NetChange::default().net(), (false, U256::zero()));
+    }
+}
@@ -0,0 +1,221 @@
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::abi::Abi;
+use ethers::providers::{Middleware, ProviderError, RpcError};
+use ethers::types::{Action, CallFrame, NameOrAddress, Res, Trace, TxHash, U256};
+use ethers::utils::format_units;
+use serde_json::json;
+use std::collections::HashMap;
+use std::error::Error;
+
+use crate::messages::{Msg, t, tf};
+
+// JSON-RPC 规范中"方法不存在"的错误码
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// 判断节点返回的错误是否表示不支持该追踪方法
+///
+/// 各家节点的说法不统一：有的返回 -32601，有的只在错误信息里写 "not supported" / "does not exist"。
+fn is_unsupported(err: &ProviderError) -> bool {
+    match err.as_error_response() {
+        Some(resp) => {
+            let message = resp.message.to_ascii_lowercase();
+            resp.code == METHOD_NOT_FOUND
+                || ["not supported", "unsupported", "does not exist", "not found", "not available"]
+                    .iter()
+                    .any(|s| message.contains(s))
+        }
+        None => false,
+    }
+}
+
+/// 把 `trace_transaction` 的扁平结果按 `traceAddress` 还原成调用树
+///
+/// `traceAddress` 是从根调用出发的子调用下标路径，结果按深度优先顺序排列，所以父调用总在子调用之前。
+fn frames_from_parity(traces: Vec<Trace>) -> Option<CallFrame> {
+    let mut root: Option<CallFrame> = None;
+
+    for trace in traces {
+        let (typ, from, to, value, gas, input) = match trace.action {
+            Action::Call(call) => (
+                format!("{:?}", call.call_type).to_ascii_uppercase(),
+                call.from,
+                Some(call.to),
+                call.value,
+                call.gas,
+                call.input,
+            ),
+            Action::Create(create) => (
+                "CREATE".to_string(),
+                create.from,
+                None,
+                create.value,
+                create.gas,
+                create.init,
+            ),
+            // selfdestruct / 出块奖励不是调用，不放进调用树
+            _ => continue,
+        };
+        let (gas_used, output, to) = match trace.result {
+            Some(Res::Call(res)) => (res.gas_used, Some(res.output), to),
+            Some(Res::Create(res)) => (res.gas_used, None, Some(res.address)),
+            _ => (U256::zero(), None, to),
+        };
+        let frame = CallFrame {
+            typ,
+            from,
+            to: to.map(NameOrAddress::Address),
+            value: Some(value),
+            gas,
+            gas_used,
+            input,
+            output,
+            error: trace.error,
+            calls: None,
+            logs: None,
+        };
+
+        match (&mut root, trace.trace_address.as_slice()) {
+            (None, []) => root = Some(frame),
+            (Some(root), path) if !path.is_empty() => insert_frame(root, path, frame),
+            _ => {}
+        }
+    }
+
+    root
+}
+
+/// 按下标路径把子调用挂到调用树上
+fn insert_frame(parent: &mut CallFrame, path: &[usize], frame: CallFrame) {
+    let calls = parent.calls.get_or_insert_with(Vec::new);
+    match path {
+        [_] => calls.push(frame),
+        [first, rest @ ..] => {
+            if let Some(child) = calls.get_mut(*first) {
+                insert_frame(child, rest, frame);
+            }
+        }
+        [] => {}
+    }
+}
+
+/// 获取交易的调用树
+///
+/// 优先使用 geth 风格的 `debug_traceTransaction`（callTracer），节点不支持时再尝试
+/// parity 风格的 `trace_transaction`；两者都不支持时返回说明性的错误。
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `tx_hash` - 交易哈希
+///
+/// # 返回
+/// * `Result<CallFrame, Box<dyn Error>>` - 根调用
+pub async fn call_trace(
+    provider: &RpcProvider,
+    tx_hash: TxHash,
+) -> Result<CallFrame, Box<dyn Error>> {
+    let params = (tx_hash, json!({"tracer": "callTracer"}));
+    let debug_err = match provider
+        .request::<_, CallFrame>("debug_traceTransaction", params)
+        .await
+    {
+        Ok(frame) => return Ok(frame),
+        Err(e) if is_unsupported(&e) => e,
+        Err(e) => return Err(e.into()),
+    };
+
+    match provider.trace_transaction(tx_hash).await {
+        Ok(traces) => frames_from_parity(traces)
+            .ok_or_else(|| tf(Msg::TraceEmpty, &[&format!("{:?}", tx_hash)]).into()),
+        Err(e) if is_unsupported(&e) => Err(tf(Msg::TraceUnsupported, &[&debug_err, &e]).into()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 从 ABI 文件读取函数签名，按 4 字节选择器建立索引
+///
+/// # 参数
+/// * `path` - JSON ABI 文件路径
+///
+/// # 返回
+/// * `Result<HashMap<[u8; 4], String>, Box<dyn Error>>` - 选择器 → `name(types)`
+pub fn load_selectors(path: &str) -> Result<HashMap<[u8; 4], String>, Box<dyn Error>> {
+    let abi: Abi = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    Ok(abi
+        .functions()
+        .map(|f| (f.short_signature(), f.signature()))
+        .collect())
+}
+
+/// 打印调用树
+///
+/// # 参数
+/// * `root` - 根调用
+/// * `selectors` - ABI 中的函数选择器（没有提供 ABI 时为空，只显示选择器）
+/// * `max_depth` - 最大显示深度，更深的调用只显示被折叠的数量
+pub fn print_call_tree(root: &CallFrame, selectors: &HashMap<[u8; 4], String>, max_depth: usize) {
+    println!("{}", t(Msg::TraceHeader));
+    print_frame(root, selectors, 0, max_depth);
+}
+
+/// 打印一个调用及其子调用
+fn print_frame(
+    frame: &CallFrame,
+    selectors: &HashMap<[u8; 4], String>,
+    depth: usize,
+    max_depth: usize,
+) {
+    let indent = "  ".repeat(depth);
+    let to = match &frame.to {
+        Some(NameOrAddress::Address(addr)) => style::addr(format!("{:?}", addr)),
+        Some(NameOrAddress::Name(name)) => name.clone(),
+        None => t(Msg::ContractCreation).to_string(),
+    };
+
+    let function = if frame.input.len() >= 4 {
+        let selector: [u8; 4] = [frame.input[0], frame.input[1], frame.input[2], frame.input[3]];
+        let hex = format!("0x{}", ethers::utils::hex::encode(selector));
+        match selectors.get(&selector) {
+            Some(signature) => format!("{} [{}]", signature, hex),
+            None => hex,
+        }
+    } else {
+        t(Msg::TraceNoCalldata).to_string()
+    };
+
+    let value = match frame.value {
+        Some(value) if !value.is_zero() => format!(
+            "  {} ETH",
+            format_units(value, "ether").unwrap_or_else(|_| value.to_string())
+        ),
+        _ => String::new(),
+    };
+    let line = format!(
+        "{}{} {:?} → {}  {}  gas {}{}",
+        indent, frame.typ, frame.from, to, function, frame.gas_used, value
+    );
+    match &frame.error {
+        Some(error) => println!("{}  {}", line, style::err(format!("❌ {}", error))),
+        None => println!("{}", line),
+    }
+
+    let calls = frame.calls.as_deref().unwrap_or_default();
+    if calls.is_empty() {
+        return;
+    }
+    if depth + 1 > max_depth {
+        println!("{}  {}", indent, tf(Msg::TraceFolded, &[&count_calls(calls)]));
+        return;
+    }
+    for call in calls {
+        print_frame(call, selectors, depth + 1, max_depth);
+    }
+}
+
+/// 统计子调用总数（含所有更深层的调用）
+fn count_calls(calls: &[CallFrame]) -> usize {
+    calls
+        .iter()
+        .map(|c| 1 + count_calls(c.calls.as_deref().unwrap_or_default()))
+        .sum()
+}
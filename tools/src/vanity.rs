@@ -0,0 +1,363 @@
+use arbi_common::style;
+use ethers::core::k256::ecdsa::SigningKey;
+use ethers::core::rand::thread_rng;
+use ethers::types::Address;
+use ethers::utils::{secret_key_to_address, to_checksum};
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::messages::{Msg, t, tf};
+
+// 前缀加后缀最多的十六进制字符数；10 位在普通 CPU 上已经需要按天计算
+pub const MAX_PATTERN_LEN: usize = 10;
+
+// 每个线程攒够这么多次尝试再累加到共享计数，减少原子操作
+const ATTEMPTS_PER_FLUSH: u64 = 1_000;
+
+/// 要匹配的地址模式
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    /// 前缀（不含 0x）
+    prefix: String,
+    suffix: String,
+    /// 是否要求字母大小写与 EIP-55 校验格式一致
+    checksum: bool,
+    // 小写形式，用于第一步快速排除
+    prefix_lower: String,
+    suffix_lower: String,
+}
+
+impl Pattern {
+    /// 校验并创建模式
+    ///
+    /// # 参数
+    /// * `prefix` - 地址前缀，可以带 0x
+    /// * `suffix` - 地址后缀
+    /// * `checksum` - 是否区分大小写（按 EIP-55 校验格式比较）
+    ///
+    /// # 返回
+    /// * `Result<Pattern, Box<dyn Error>>` - 模式；含非十六进制字符、为空或过长时返回错误
+    pub fn new(prefix: &str, suffix: &str, checksum: bool) -> Result<Self, Box<dyn Error>> {
+        let prefix = prefix.strip_prefix("0x").unwrap_or(prefix);
+        for part in [prefix, suffix] {
+            if !part.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(tf(Msg::VanityInvalidPattern, &[&part]).into());
+            }
+        }
+        let len = prefix.len() + suffix.len();
+        if len == 0 {
+            return Err(t(Msg::VanityEmptyPattern).into());
+        }
+        if len > MAX_PATTERN_LEN {
+            return Err(tf(Msg::VanityPatternTooLong, &[&len, &MAX_PATTERN_LEN]).into());
+        }
+
+        Ok(Self {
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+            checksum,
+            prefix_lower: prefix.to_lowercase(),
+            suffix_lower: suffix.to_lowercase(),
+        })
+    }
+
+    /// 地址是否匹配
+    ///
+    /// 先按小写比较，通过后才计算校验格式，绝大多数地址在第一步就被排除。
+    ///
+    /// # 参数
+    /// * `address` - 候选地址
+    ///
+    /// # 返回
+    /// * `bool` - 是否匹配
+    pub fn matches(&self, address: &Address) -> bool {
+        let lower = ethers::utils::hex::encode(address);
+        if !lower.starts_with(&self.prefix_lower) || !lower.ends_with(&self.suffix_lower) {
+            return false;
+        }
+        if !self.checksum {
+            return true;
+        }
+        let checksummed = to_checksum(address, None);
+        let checksummed = &checksummed[2..];
+        checksummed.starts_with(&self.prefix) && checksummed.ends_with(&self.suffix)
+    }
+
+    /// 平均需要尝试的次数
+    ///
+    /// 每个十六进制字符有 16 种取值；区分大小写时，每个字母还要校验格式的大小写刚好对上，
+    /// 概率再减半。
+    ///
+    /// # 返回
+    /// * `f64` - 期望尝试次数
+    pub fn difficulty(&self) -> f64 {
+        let chars = self.prefix.chars().chain(self.suffix.chars());
+        chars.fold(1.0, |difficulty, c| {
+            let case_factor = if self.checksum && c.is_ascii_alphabetic() { 2.0 } else { 1.0 };
+            difficulty * 16.0 * case_factor
+        })
+    }
+}
+
+/// 搜索期间的计数
+#[derive(Clone, Copy, Debug)]
+pub struct VanityStats {
+    pub attempts: u64,
+    pub elapsed: Duration,
+}
+
+impl VanityStats {
+    /// 每秒尝试次数
+    pub fn rate(&self) -> f64 {
+        self.attempts as f64 / self.elapsed.as_secs_f64().max(0.001)
+    }
+}
+
+/// 搜索结果
+pub enum VanityOutcome {
+    Found {
+        key: SigningKey,
+        address: Address,
+        stats: VanityStats,
+    },
+    /// 按 Ctrl-C 停止
+    Stopped(VanityStats),
+}
+
+/// 按当前速度估算有 50% 概率找到所需的时间（秒）
+///
+/// # 参数
+/// * `pattern` - 模式
+/// * `rate` - 每秒尝试次数
+///
+/// # 返回
+/// * `f64` - 秒数
+pub fn median_secs(pattern: &Pattern, rate: f64) -> f64 {
+    // 每次尝试独立，50% 概率找到所需的次数为 ln2 × 期望次数
+    pattern.difficulty() * std::f64::consts::LN_2 / rate.max(1.0)
+}
+
+fn format_secs(secs: f64) -> String {
+    match secs {
+        s if s < 120.0 => format!("{:.0}s", s),
+        s if s < 7200.0 => format!("{:.0}min", s / 60.0),
+        s if s < 172800.0 => format!("{:.1}h", s / 3600.0),
+        s => format!("{:.1}d", s / 86400.0),
+    }
+}
+
+/// 工作线程：不断生成随机私钥，直到自己或其他线程找到匹配，或收到停止信号
+fn worker(
+    pattern: &Pattern,
+    attempts: &AtomicU64,
+    stop: &AtomicBool,
+    found: &Mutex<Option<SigningKey>>,
+) {
+    let mut rng = thread_rng();
+    let mut local = 0;
+    while !stop.load(Ordering::Relaxed) {
+        let key = SigningKey::random(&mut rng);
+        local += 1;
+        if pattern.matches(&secret_key_to_address(&key)) {
+            if let Ok(mut found) = found.lock() {
+                found.get_or_insert(key);
+            }
+            stop.store(true, Ordering::Relaxed);
+        }
+        if local == ATTEMPTS_PER_FLUSH {
+            attempts.fetch_add(local, Ordering::Relaxed);
+            local = 0;
+        }
+    }
+    attempts.fetch_add(local, Ordering::Relaxed);
+}
+
+/// 在 `threads` 个线程上搜索匹配 `pattern` 的地址，每秒打印一次速度，Ctrl-C 停止
+///
+/// # 参数
+/// * `pattern` - 模式
+/// * `threads` - 线程数
+///
+/// # 返回
+/// * `VanityOutcome` - 找到的私钥，或停止时的计数
+pub async fn search(pattern: Pattern, threads: usize) -> VanityOutcome {
+    let pattern = Arc::new(pattern);
+    let attempts = Arc::new(AtomicU64::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
+    let found = Arc::new(Mutex::new(None));
+
+    let handles: Vec<_> = (0..threads.max(1))
+        .map(|_| {
+            let (pattern, attempts, stop, found) =
+                (pattern.clone(), attempts.clone(), stop.clone(), found.clone());
+            std::thread::spawn(move || worker(&pattern, &attempts, &stop, &found))
+        })
+        .collect();
+
+    let started = Instant::now();
+    let mut ticker = tokio::time::interval(Duration::from_secs(1));
+    ticker.tick().await;
+    let mut eta_printed = false;
+    while !stop.load(Ordering::Relaxed) {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let stats = VanityStats {
+                    attempts: attempts.load(Ordering::Relaxed),
+                    elapsed: started.elapsed(),
+                };
+                let rate = format!("{:.0}", stats.rate());
+                eprint!("\r{}", tf(Msg::VanityProgress, &[&stats.attempts, &rate]));
+                let _ = std::io::stderr().flush();
+                // 跑满几秒后速度才稳定，再给出时间估计
+                if !eta_printed && stats.elapsed >= Duration::from_secs(5) {
+                    let eta = format_secs(median_secs(&pattern, stats.rate()));
+                    eprintln!("\n{}", tf(Msg::VanityEta, &[&eta]));
+                    eta_printed = true;
+                }
+            }
+            _ = tokio::signal::ctrl_c() => stop.store(true, Ordering::Relaxed),
+        }
+    }
+    eprintln!();
+
+    for handle in handles {
+        let _ = handle.join();
+    }
+    let stats = VanityStats {
+        attempts: attempts.load(Ordering::Relaxed),
+        elapsed: started.elapsed(),
+    };
+    match found.lock().ok().and_then(|mut found| found.take()) {
+        Some(key) => VanityOutcome::Found {
+            address: secret_key_to_address(&key),
+            key,
+            stats,
+        },
+        None => VanityOutcome::Stopped(stats),
+    }
+}
+
+/// 打印搜索计数
+pub fn print_stats(stats: &VanityStats) {
+    println!(
+        "{}",
+        tf(
+            Msg::VanityStats,
+            &[&stats.attempts, &stats.elapsed.as_secs(), &format!("{:.0}", stats.rate())]
+        )
+    );
+}
+
+/// 打印找到的地址；`print_key` 时直接打印私钥，否则提示 keystore 路径
+pub fn print_found(address: &Address, keystore: Option<&Path>, key: Option<&SigningKey>) {
+    println!("{}", style::ok(tf(Msg::VanityFound, &[&to_checksum(address, None)])));
+    if let Some(path) = keystore {
+        println!("{}", tf(Msg::VanityKeystoreSaved, &[&path.display()]));
+    }
+    if let Some(key) = key {
+        println!("{}", style::warn(t(Msg::VanityKeyWarning)));
+        println!("0x{}", ethers::utils::hex::encode(key.to_bytes()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    // EIP-55 规范里的示例地址
+    const SAMPLE: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    fn matches(prefix: &str, suffix: &str, checksum: bool) -> bool {
+        let pattern = Pattern::new(prefix, suffix, checksum).unwrap();
+        pattern.matches(&Address::from_str(SAMPLE).unwrap())
+    }
+
+    #[test]
+    fn rejects_invalid_empty_and_long_patterns() {
+        let error = Pattern::new("0xbeeg", "", false).unwrap_err();
+        assert_eq!(error.to_string(), tf(Msg::VanityInvalidPattern, &[&"beeg"]));
+        let error = Pattern::new("", "c0ffee!", false).unwrap_err();
+        assert_eq!(error.to_string(), tf(Msg::VanityInvalidPattern, &[&"c0ffee!"]));
+        let error = Pattern::new("0x", "", false).unwrap_err();
+        assert_eq!(error.to_string(), t(Msg::VanityEmptyPattern));
+        let error = Pattern::new("abcdef", "12345", false).unwrap_err();
+        assert_eq!(error.to_string(), tf(Msg::VanityPatternTooLong, &[&11, &MAX_PATTERN_LEN]));
+        assert!(Pattern::new("abcde", "12345", false).is_ok());
+    }
+
+    #[test]
+    fn matches_prefix_and_suffix_ignoring_case() {
+        assert!(matches("0x5aae", "", false));
+        assert!(matches("5AAE", "BEAED", false));
+        assert!(matches("", "aed", false));
+        assert!(!matches("5aaf", "", false));
+        assert!(!matches("5aae", "aee", false));
+        // 前缀只从地址开头匹配
+        assert!(!matches("aaeb", "", false));
+    }
+
+    #[test]
+    fn checksum_mode_compares_the_eip55_case() {
+        assert!(matches("5aAeb", "", true));
+        assert!(matches("", "BeAed", true));
+        assert!(matches("5aAe", "eAed", true));
+        assert!(!matches("5AAeb", "", true));
+        assert!(!matches("", "beaed", true));
+        // 数字没有大小写
+        assert!(matches("5", "", true));
+    }
+
+    #[test]
+    fn difficulty_doubles_for_each_cased_letter() {
+        let difficulty = |prefix, suffix, checksum| {
+            Pattern::new(prefix, suffix, checksum).unwrap().difficulty()
+        };
+        assert_eq!(difficulty("12", "", false), 256.0);
+        assert_eq!(difficulty("ab", "", false), 256.0);
+        assert_eq!(difficulty("12", "", true), 256.0);
+        assert_eq!(difficulty("aB", "", true), 1024.0);
+        assert_eq!(difficulty("a", "1", true), 512.0);
+        assert_eq!(difficulty("dead", "beef", false), 16f64.powi(8));
+    }
+
+    #[test]
+    fn median_time_scales_with_rate() {
+        let pattern = Pattern::new("1234", "", false).unwrap();
+        let ln2 = std::f64::consts::LN_2;
+        assert_eq!(median_secs(&pattern, 65_536.0), ln2);
+        assert_eq!(median_secs(&pattern, 1.0), 65_536.0 * ln2);
+        // 还没测出速度时按每秒一次估算，不会除以 0
+        assert_eq!(median_secs(&pattern, 0.0), 65_536.0 * ln2);
+    }
+
+    #[test]
+    fn formats_seconds_in_the_largest_fitting_unit() {
+        assert_eq!(format_secs(59.4), "59s");
+        assert_eq!(format_secs(600.0), "10min");
+        assert_eq!(format_secs(7_200.0), "2.0h");
+        assert_eq!(format_secs(172_800.0), "2.0d");
+        let stats = VanityStats {
+            attempts: 5_000,
+            elapsed: Duration::from_millis(2_500),
+        };
+        assert_eq!(stats.rate(), 2_000.0);
+    }
+
+    #[tokio::test]
+    async fn search_returns_a_key_for_the_found_address() {
+        let pattern = Pattern::new("0", "", false).unwrap();
+        match search(pattern.clone(), 2).await {
+            VanityOutcome::Found { key, address, stats } => {
+                assert!(pattern.matches(&address));
+                assert_eq!(secret_key_to_address(&key), address);
+                assert!(stats.attempts >= 1);
+            }
+            VanityOutcome::Stopped(_) => panic!("search stopped without a match"),
+        }
+    }
+}
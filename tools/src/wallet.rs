@@ -0,0 +1,420 @@
+use arbi_common::network::{self, Network};
+use arbi_common::rpc::RpcProvider;
+use arbi_common::style;
+use ethers::core::k256::ecdsa::SigningKey;
+use ethers::core::k256::elliptic_curve::zeroize::{Zeroize, Zeroizing};
+use ethers::core::rand::thread_rng;
+use ethers::signers::coins_bip39::{English, Mnemonic};
+use ethers::providers::Middleware;
+use ethers::signers::{LocalWallet, MnemonicBuilder, Signer};
+use ethers::types::{Address, U256};
+use ethers::utils::{format_ether, hex, secret_key_to_address, to_checksum};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::terminal;
+use std::error::Error;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use crate::messages::{Msg, t, tf};
+
+// 读取 keystore 密码的环境变量
+pub const PASSWORD_ENV: &str = "KEYSTORE_PASSWORD";
+
+// 新助记词的单词数
+const MNEMONIC_WORDS: usize = 12;
+
+// Arbitrum Sepolia 测试币水龙头，以及从 Sepolia 跨链的官方桥
+const SEPOLIA_FAUCETS: [&str; 3] = [
+    "https://faucet.quicknode.com/arbitrum/sepolia",
+    "https://www.alchemy.com/faucets/arbitrum-sepolia",
+    "https://bridge.arbitrum.io/?destinationChain=arbitrum-sepolia&sourceChain=sepolia",
+];
+
+/// 从环境变量读取 keystore 密码（会先加载 .env）
+///
+/// # 返回
+/// * `Result<Zeroizing<String>, Box<dyn Error>>` - 密码，离开作用域时清零；未设置时返回错误
+pub fn password_from_env() -> Result<Zeroizing<String>, Box<dyn Error>> {
+    dotenv::dotenv().ok(); // 加载 .env 文件（如果存在）
+    match std::env::var(PASSWORD_ENV) {
+        Ok(password) if !password.is_empty() => Ok(Zeroizing::new(password)),
+        _ => Err(tf(Msg::WalletNoPassword, &[&PASSWORD_ENV]).into()),
+    }
+}
+
+/// 用密码把私钥加密成 V3 keystore 文件（geth / MetaMask 使用的 Web3 Secret Storage 格式）
+///
+/// 文件名为校验格式的地址。在 Unix 上新建的目录权限为 0700；keystore 先写进同目录下
+/// 权限 0700 的临时目录，改成 0600 后再改名过去，写入过程中其他用户始终读不到。
+///
+/// # 参数
+/// * `dir` - 保存目录，不存在时创建
+/// * `key` - 私钥
+/// * `password` - 加密密码
+///
+/// # 返回
+/// * `Result<PathBuf, Box<dyn Error>>` - 文件路径
+pub fn save_keystore(
+    dir: &Path,
+    key: &SigningKey,
+    password: &str,
+) -> Result<PathBuf, Box<dyn Error>> {
+    create_private_dir(dir)?;
+    let name = format!("{}.json", to_checksum(&secret_key_to_address(key), None));
+    let path = dir.join(&name);
+    if path.exists() {
+        return Err(tf(Msg::WalletKeystoreExists, &[&path.display()]).into());
+    }
+
+    let staging = dir.join(format!(".{}.{}.tmp", name, std::process::id()));
+    create_private_dir(&staging)?;
+    let saved = encrypt_into(&staging, &name, key, password)
+        .and_then(|staged| Ok(std::fs::rename(staged, &path)?));
+    let _ = std::fs::remove_dir_all(&staging);
+    saved?;
+    Ok(path)
+}
+
+// 创建只有当前用户能进入的目录（Unix 上为 0700），已存在的目录不改权限
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)
+    }
+    #[cfg(not(unix))]
+    std::fs::create_dir_all(dir)
+}
+
+// 把加密后的 keystore 写入 `dir/name`，Unix 上权限改为 0600，返回文件路径
+fn encrypt_into(
+    dir: &Path,
+    name: &str,
+    key: &SigningKey,
+    password: &str,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let mut pk: [u8; 32] = key.to_bytes().into();
+    let saved = LocalWallet::encrypt_keystore(dir, &mut thread_rng(), pk, password, Some(name));
+    pk.zeroize();
+    saved?;
+
+    let path = dir.join(name);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(path)
+}
+
+/// 新生成的钱包
+pub struct NewWallet {
+    pub address: Address,
+    pub keystore: PathBuf,
+    /// 使用助记词时的 12 个单词，离开作用域时清零
+    pub mnemonic: Option<Zeroizing<String>>,
+}
+
+/// 一次性的测试钱包，私钥和助记词只在终端里显示，不保存
+pub struct ThrowawayWallet {
+    pub address: Address,
+    /// 0x 开头的私钥，离开作用域时清零
+    pub private_key: Zeroizing<String>,
+    /// 使用助记词时的 12 个单词，离开作用域时清零
+    pub mnemonic: Option<Zeroizing<String>>,
+}
+
+// 随机生成私钥；with_mnemonic 时先生成助记词，再派生 m/44'/60'/0'/0/0
+type RandomKey = (SigningKey, Option<Zeroizing<String>>);
+
+fn random_key(with_mnemonic: bool) -> Result<RandomKey, Box<dyn Error>> {
+    let mut rng = thread_rng();
+    if !with_mnemonic {
+        return Ok((SigningKey::random(&mut rng), None));
+    }
+    let mnemonic = Mnemonic::<English>::new_with_count(&mut rng, MNEMONIC_WORDS)?;
+    let phrase = Zeroizing::new(mnemonic.to_phrase());
+    let wallet = MnemonicBuilder::<English>::default()
+        .phrase(phrase.as_str())
+        .index(0u32)?
+        .build()?;
+    Ok((wallet.signer().clone(), Some(phrase)))
+}
+
+/// 生成新私钥（可选从新的 BIP-39 助记词派生 m/44'/60'/0'/0/0），加密写入 keystore
+///
+/// # 参数
+/// * `dir` - keystore 保存目录
+/// * `password` - 加密密码
+/// * `with_mnemonic` - 是否先生成助记词再派生私钥
+///
+/// # 返回
+/// * `Result<NewWallet, Box<dyn Error>>` - 地址、keystore 路径和助记词
+pub fn generate(
+    dir: &Path,
+    password: &str,
+    with_mnemonic: bool,
+) -> Result<NewWallet, Box<dyn Error>> {
+    let (key, mnemonic) = random_key(with_mnemonic)?;
+    let keystore = save_keystore(dir, &key, password)?;
+    Ok(NewWallet {
+        address: secret_key_to_address(&key),
+        keystore,
+        mnemonic,
+    })
+}
+
+/// 生成一次性的测试钱包，不写入任何文件
+///
+/// # 参数
+/// * `with_mnemonic` - 是否先生成助记词再派生私钥
+///
+/// # 返回
+/// * `Result<ThrowawayWallet, Box<dyn Error>>` - 地址、私钥和助记词
+pub fn generate_throwaway(with_mnemonic: bool) -> Result<ThrowawayWallet, Box<dyn Error>> {
+    let (key, mnemonic) = random_key(with_mnemonic)?;
+    Ok(ThrowawayWallet {
+        address: secret_key_to_address(&key),
+        private_key: Zeroizing::new(hex::encode_prefixed(key.to_bytes())),
+        mnemonic,
+    })
+}
+
+/// 解密 keystore，只取出地址；私钥随钱包一起在函数返回前清零
+///
+/// # 参数
+/// * `path` - keystore 文件
+/// * `password` - 密码
+///
+/// # 返回
+/// * `Result<Address, Box<dyn Error>>` - 地址；密码错误时返回错误
+pub fn keystore_address(path: &Path, password: &str) -> Result<Address, Box<dyn Error>> {
+    let wallet = LocalWallet::decrypt_keystore(path, password)
+        .map_err(|e| tf(Msg::WalletDecryptFailed, &[&path.display(), &e]))?;
+    Ok(wallet.address())
+}
+
+/// 打印新钱包：地址、keystore 路径、`.env` 配置行和领取测试币的方法
+pub fn print_new(wallet: &NewWallet) {
+    println!("{}", style::ok(t(Msg::WalletCreated)));
+    println!("{}", tf(Msg::WalletAddress, &[&to_checksum(&wallet.address, None)]));
+    println!("{}", tf(Msg::WalletAddressLower, &[&format!("{:?}", wallet.address)]));
+    println!("{}", tf(Msg::WalletKeystoreSaved, &[&wallet.keystore.display()]));
+
+    if let Some(phrase) = &wallet.mnemonic {
+        println!("\n{}", style::warn(t(Msg::WalletMnemonicWarning)));
+        println!("{}", phrase.as_str());
+    }
+
+    println!("\n{}", t(Msg::WalletEnvHint));
+    println!("KEYSTORE_PATH={}", wallet.keystore.display());
+
+    if network::current() == Network::ArbitrumSepolia {
+        println!();
+        print_faucets();
+    }
+}
+
+/// 打印一次性测试钱包：醒目的仅限测试网警告，然后是每个钱包的地址、私钥和助记词
+pub fn print_throwaway(wallets: &[ThrowawayWallet]) {
+    println!("{}", style::err(t(Msg::WalletThrowawayWarning)));
+    if network::current() != Network::ArbitrumSepolia {
+        println!("{}", style::err(tf(Msg::WalletThrowawayMainnet, &[&network::current().name()])));
+    }
+    for (i, wallet) in wallets.iter().enumerate() {
+        println!();
+        if wallets.len() > 1 {
+            println!("#{}", i + 1);
+        }
+        println!("{}", tf(Msg::WalletAddress, &[&style::addr(to_checksum(&wallet.address, None))]));
+        println!("{}", tf(Msg::WalletThrowawayKey, &[&wallet.private_key.as_str()]));
+        if let Some(phrase) = &wallet.mnemonic {
+            println!("{}", tf(Msg::WalletThrowawayMnemonic, &[&phrase.as_str()]));
+        }
+    }
+    println!("\n{}", style::err(t(Msg::WalletThrowawayWarning)));
+    println!();
+    print_faucets();
+}
+
+/// 在 Arbitrum Sepolia 上打印领取测试币的方法，其他网络不打印
+pub fn print_faucets() {
+    if network::current() != Network::ArbitrumSepolia {
+        return;
+    }
+    println!("{}", t(Msg::WalletFaucetHint));
+    for faucet in SEPOLIA_FAUCETS {
+        println!("  - {}", faucet);
+    }
+}
+
+/// 在终端里读取一行，输入不回显；按 Ctrl-C 取消
+pub fn read_hidden_line() -> Result<Zeroizing<String>, Box<dyn Error>> {
+    terminal::enable_raw_mode()?;
+    let mut line = Zeroizing::new(String::new());
+    let result = loop {
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+            Ok(_) => continue,
+            Err(e) => break Err(e.into()),
+        };
+        match key.code {
+            KeyCode::Enter => break Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                break Err(t(Msg::WalletInputCancelled).into());
+            }
+            KeyCode::Char(c) => line.push(c),
+            KeyCode::Backspace => {
+                line.pop();
+            }
+            _ => {}
+        }
+    };
+    terminal::disable_raw_mode()?;
+    eprintln!();
+    result.map(|_| line)
+}
+
+/// 读取私钥或助记词：优先环境变量（会先加载 .env），其次终端提示输入（不回显），
+/// 标准输入不是终端时读取第一行。不从命令行参数读取，避免留在 shell 历史里
+///
+/// # 参数
+/// * `env` - 环境变量名
+/// * `prompt` - 提示语
+///
+/// # 返回
+/// * `Result<Zeroizing<String>, Box<dyn Error>>` - 输入内容，离开作用域时清零
+pub fn read_secret(env: &str, prompt: Msg) -> Result<Zeroizing<String>, Box<dyn Error>> {
+    dotenv::dotenv().ok(); // 加载 .env 文件（如果存在）
+    if let Ok(value) = std::env::var(env)
+        && !value.trim().is_empty()
+    {
+        return Ok(Zeroizing::new(value));
+    }
+
+    if std::io::stdin().is_terminal() {
+        eprint!("{}", tf(prompt, &[&env]));
+        std::io::stderr().flush()?;
+        return read_hidden_line();
+    }
+    let mut line = Zeroizing::new(String::new());
+    std::io::stdin().lock().read_line(&mut line)?;
+    Ok(line)
+}
+
+/// 一个地址在链上的余额和 nonce
+pub struct AccountInfo {
+    /// 从助记词派生时的序号
+    pub index: Option<u32>,
+    pub address: Address,
+    pub balance: U256,
+    pub nonce: U256,
+}
+
+/// 同时查询地址的余额和 nonce
+///
+/// # 参数
+/// * `provider` - Provider 引用
+/// * `index` - 派生序号
+/// * `address` - 地址
+///
+/// # 返回
+/// * `Result<AccountInfo, Box<dyn Error>>` - 余额和 nonce
+pub async fn account_info(
+    provider: &RpcProvider,
+    index: Option<u32>,
+    address: Address,
+) -> Result<AccountInfo, Box<dyn Error>> {
+    let (balance, nonce) = tokio::try_join!(
+        provider.get_balance(address, None),
+        provider.get_transaction_count(address, None),
+    )?;
+    Ok(AccountInfo {
+        index,
+        address,
+        balance,
+        nonce,
+    })
+}
+
+/// 打印地址、余额和 nonce，每个地址一行
+pub fn print_accounts(accounts: &[AccountInfo]) {
+    println!("{}", tf(Msg::WalletAccountsOn, &[&network::current().name()]));
+    for account in accounts {
+        let path = account
+            .index
+            .map(|index| format!("m/44'/60'/0'/0/{}  ", index))
+            .unwrap_or_default();
+        println!(
+            "{}{}  {} ETH  nonce {}",
+            path,
+            style::addr(to_checksum(&account.address, None)),
+            format_ether(account.balance),
+            account.nonce
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // EIP-155 示例私钥，只用于测试
+    const PRIVATE_KEY: &str = "4646464646464646464646464646464646464646464646464646464646464646";
+
+    fn scratch(name: &str) -> PathBuf {
+        let name = format!("arbi-keystore-{}-{}", name, std::process::id());
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn key() -> SigningKey {
+        SigningKey::from_slice(&hex::decode(PRIVATE_KEY).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn keystore_round_trips_and_only_the_file_is_left() {
+        let root = scratch("round-trip");
+        let dir = root.join("nested").join("keystore");
+        let path = save_keystore(&dir, &key(), "correct horse").unwrap();
+
+        let expected = secret_key_to_address(&key());
+        assert_eq!(path, dir.join(format!("{}.json", to_checksum(&expected, None))));
+        assert_eq!(keystore_address(&path, "correct horse").unwrap(), expected);
+        let decrypted = LocalWallet::decrypt_keystore(&path, "correct horse").unwrap();
+        assert_eq!(decrypted.signer().to_bytes(), key().to_bytes());
+
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().flatten().map(|e| e.path()).collect();
+        assert_eq!(files, [path]);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn wrong_password_and_existing_file_are_errors() {
+        let dir = scratch("errors");
+        let path = save_keystore(&dir, &key(), "correct horse").unwrap();
+        assert!(keystore_address(&path, "wrong").is_err());
+
+        let error = save_keystore(&dir, &key(), "other").unwrap_err();
+        assert_eq!(error.to_string(), tf(Msg::WalletKeystoreExists, &[&path.display()]));
+        // 原文件没有被覆盖
+        assert!(keystore_address(&path, "correct horse").is_ok());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keystore_and_new_directories_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let root = scratch("modes");
+        let dir = root.join("keystore");
+        let path = save_keystore(&dir, &key(), "correct horse").unwrap();
+
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&path), 0o600);
+        assert_eq!(mode(&dir), 0o700);
+        assert_eq!(mode(&root), 0o700);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}